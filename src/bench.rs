@@ -0,0 +1,350 @@
+//! `two-face bench` - parser and highlight throughput over a captured session
+//!
+//! Replays a raw game stream (XML as Lich sends it, one server line per file
+//! line) through the same path live text takes: `XmlParser`, then a text
+//! window per stream with the profile's highlights applied, plus sound
+//! highlight matching. Reports lines per second, heap allocations and the
+//! slowest lines, so releases can be compared on the same capture.
+//!
+//! Allocations are counted by a wrapper around the system allocator that
+//! only counts while a bench is running. It's only installed in builds with
+//! the `bench` feature, so everyday builds keep the plain system allocator.
+
+use crate::config::HighlightPattern;
+use crate::frontend::tui::text_window::{self, StyledText, TextWindow};
+use crate::parser::{ParsedElement, XmlParser};
+use serde::Serialize;
+#[cfg(feature = "bench")]
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+static COUNTING: AtomicBool = AtomicBool::new(false);
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static ALLOCATED_BYTES: AtomicU64 = AtomicU64::new(0);
+
+/// System allocator that counts allocations while `COUNTING` is set
+#[cfg(feature = "bench")]
+pub struct CountingAlloc;
+
+#[cfg(feature = "bench")]
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if COUNTING.load(Ordering::Relaxed) {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            ALLOCATED_BYTES.fetch_add(layout.size() as u64, Ordering::Relaxed);
+        }
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        if COUNTING.load(Ordering::Relaxed) {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            ALLOCATED_BYTES.fetch_add(new_size as u64, Ordering::Relaxed);
+        }
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[cfg(feature = "bench")]
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+/// Allocations and bytes allocated so far
+fn allocation_counts() -> (u64, u64) {
+    (
+        ALLOCATIONS.load(Ordering::Relaxed),
+        ALLOCATED_BYTES.load(Ordering::Relaxed),
+    )
+}
+
+/// Slowest lines kept for the report
+const SLOWEST_KEPT: usize = 5;
+
+/// One of the slowest lines
+#[derive(Debug, Clone, Serialize)]
+pub struct SlowLine {
+    /// 1-based line number in the capture
+    pub line: usize,
+    pub micros: u64,
+    pub text: String,
+}
+
+/// What a bench run measured
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchReport {
+    pub lines: usize,
+    pub bytes: usize,
+    pub iterations: usize,
+    pub highlights: usize,
+    pub elapsed_ms: f64,
+    pub lines_per_sec: f64,
+    pub mb_per_sec: f64,
+    /// Allocation counts are None unless built with the `bench` feature
+    pub allocations: Option<u64>,
+    pub allocations_per_line: Option<f64>,
+    pub allocated_bytes: Option<u64>,
+    pub p50_micros: u64,
+    pub p99_micros: u64,
+    pub max_micros: u64,
+    pub slowest: Vec<SlowLine>,
+}
+
+impl BenchReport {
+    /// Human-readable report
+    pub fn summary(&self) -> String {
+        let mut out = format!(
+            "{} lines ({:.1} KB) x {} iteration(s), {} highlights\n",
+            self.lines,
+            self.bytes as f64 / 1024.0,
+            self.iterations,
+            self.highlights
+        );
+        out.push_str(&format!(
+            "Throughput:  {:.0} lines/sec, {:.2} MB/sec ({:.1} ms total)\n",
+            self.lines_per_sec, self.mb_per_sec, self.elapsed_ms
+        ));
+        match (
+            self.allocations,
+            self.allocations_per_line,
+            self.allocated_bytes,
+        ) {
+            (Some(allocations), Some(per_line), Some(bytes)) => out.push_str(&format!(
+                "Allocations: {} ({:.1}/line, {:.1} KB allocated)\n",
+                allocations,
+                per_line,
+                bytes as f64 / 1024.0
+            )),
+            _ => out.push_str("Allocations: not counted (build with --features bench)\n"),
+        }
+        out.push_str(&format!(
+            "Latency:     p50 {}us, p99 {}us, max {}us\n",
+            self.p50_micros, self.p99_micros, self.max_micros
+        ));
+        if !self.slowest.is_empty() {
+            out.push_str("Slowest lines:\n");
+            for slow in &self.slowest {
+                out.push_str(&format!(
+                    "  {:>7}us  line {}: {}\n",
+                    slow.micros, slow.line, slow.text
+                ));
+            }
+        }
+        out
+    }
+}
+
+/// Parse and highlight one captured line, as the live path does
+struct Pipeline {
+    parser: XmlParser,
+    windows: HashMap<String, TextWindow>,
+    highlights: Vec<HighlightPattern>,
+    triggers: Vec<crate::core::parse_worker::SoundTrigger>,
+}
+
+impl Pipeline {
+    fn new(parser: XmlParser, highlights: &HashMap<String, HighlightPattern>) -> Self {
+        Self {
+            parser,
+            windows: HashMap::new(),
+            highlights: highlights.values().cloned().collect(),
+            triggers: crate::core::parse_worker::SoundTrigger::compile(highlights),
+        }
+    }
+
+    fn run_line(&mut self, line: &str) {
+        let mut touched: Vec<String> = Vec::new();
+        let mut text = String::new();
+        for element in self.parser.parse_line(line) {
+            let ParsedElement::Text {
+                content,
+                stream,
+                fg_color,
+                bg_color,
+                bold,
+                span_type,
+                link_data,
+            } = element
+            else {
+                continue;
+            };
+            text.push_str(&content);
+            if !self.windows.contains_key(&stream) {
+                let mut window = TextWindow::new(stream.clone(), 1000);
+                window.set_highlights(self.highlights.clone());
+                self.windows.insert(stream.clone(), window);
+            }
+            let window = self
+                .windows
+                .get_mut(&stream)
+                .expect("window was just added");
+            window.add_text(StyledText {
+                content,
+                fg: fg_color.and_then(|c| c.parse().ok()),
+                bg: bg_color.and_then(|c| c.parse().ok()),
+                bold,
+                span_type: match span_type {
+                    crate::parser::SpanType::Normal | crate::parser::SpanType::Whisper => {
+                        text_window::SpanType::Normal
+                    }
+                    crate::parser::SpanType::Link => text_window::SpanType::Link,
+                    crate::parser::SpanType::Monsterbold => text_window::SpanType::Monsterbold,
+                    crate::parser::SpanType::Spell => text_window::SpanType::Spell,
+                    crate::parser::SpanType::Speech => text_window::SpanType::Speech,
+                },
+                link_data: link_data.map(|ld| text_window::LinkData {
+                    exist_id: ld.exist_id,
+                    noun: ld.noun,
+                    text: ld.text,
+                    coord: ld.coord,
+                    flags: ld.flags,
+                }),
+            });
+            if !touched.contains(&stream) {
+                touched.push(stream);
+            }
+        }
+        for stream in touched {
+            if let Some(window) = self.windows.get_mut(&stream) {
+                window.finish_line(80);
+            }
+        }
+        let _ = crate::core::parse_worker::sound_hits(&self.triggers, &text);
+    }
+}
+
+/// Run `lines` through the parser and highlights `iterations` times
+pub fn run(
+    lines: &[String],
+    parser: XmlParser,
+    highlights: &HashMap<String, HighlightPattern>,
+    iterations: usize,
+) -> BenchReport {
+    let iterations = iterations.max(1);
+    let mut pipeline = Pipeline::new(parser, highlights);
+    let mut latencies: Vec<u64> = Vec::with_capacity(lines.len() * iterations);
+    // (line index, micros), kept without allocating so only the pipeline is counted
+    let mut slowest: Vec<(usize, u64)> = Vec::with_capacity(SLOWEST_KEPT + 1);
+
+    let (allocations_before, bytes_before) = allocation_counts();
+    COUNTING.store(true, Ordering::Relaxed);
+    let started = Instant::now();
+    for _ in 0..iterations {
+        for (i, line) in lines.iter().enumerate() {
+            let line_started = Instant::now();
+            pipeline.run_line(line);
+            let micros = line_started.elapsed().as_micros() as u64;
+            latencies.push(micros);
+            if slowest.len() < SLOWEST_KEPT
+                || slowest.last().is_some_and(|&(_, slow)| micros > slow)
+            {
+                match slowest.iter_mut().find(|(index, _)| *index == i) {
+                    Some(entry) => entry.1 = entry.1.max(micros),
+                    None => slowest.push((i, micros)),
+                }
+                slowest.sort_unstable_by_key(|&(_, micros)| std::cmp::Reverse(micros));
+                slowest.truncate(SLOWEST_KEPT);
+            }
+        }
+    }
+    let elapsed = started.elapsed();
+    COUNTING.store(false, Ordering::Relaxed);
+    let (allocations_after, bytes_after) = allocation_counts();
+
+    latencies.sort_unstable();
+    let percentile = |p: usize| {
+        latencies
+            .get((latencies.len() * p / 100).min(latencies.len().saturating_sub(1)))
+            .copied()
+            .unwrap_or(0)
+    };
+    let total_lines = lines.len() * iterations;
+    let bytes: usize = lines.iter().map(|l| l.len() + 1).sum();
+    let seconds = elapsed.max(Duration::from_nanos(1)).as_secs_f64();
+    let allocations = allocations_after.saturating_sub(allocations_before);
+    let counted = cfg!(feature = "bench");
+    BenchReport {
+        lines: lines.len(),
+        bytes,
+        iterations,
+        highlights: highlights.len(),
+        elapsed_ms: seconds * 1000.0,
+        lines_per_sec: total_lines as f64 / seconds,
+        mb_per_sec: (bytes * iterations) as f64 / seconds / (1024.0 * 1024.0),
+        allocations: counted.then_some(allocations),
+        allocations_per_line: counted.then(|| allocations as f64 / total_lines.max(1) as f64),
+        allocated_bytes: counted.then(|| bytes_after.saturating_sub(bytes_before)),
+        p50_micros: percentile(50),
+        p99_micros: percentile(99),
+        max_micros: latencies.last().copied().unwrap_or(0),
+        slowest: slowest
+            .into_iter()
+            .map(|(index, micros)| SlowLine {
+                line: index + 1,
+                micros,
+                text: lines[index].chars().take(100).collect(),
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LINES: [&str; 5] = [
+        "<pushStream id=\"thoughts\"/>You hear the faint thoughts of Bob echo in your mind:",
+        "<popStream/>",
+        "<style id=\"roomName\"/>[Town Square]",
+        "<style id=\"\"/>Also here: <a exist=\"1\" noun=\"Ann\">Ann</a>",
+        "A kobold falls to the ground and dies.",
+    ];
+
+    /// Three passes over the sample lines with one highlight
+    fn report() -> BenchReport {
+        let lines: Vec<String> = LINES.iter().map(|s| s.to_string()).collect();
+        let mut highlights = HashMap::new();
+        highlights.insert(
+            "deaths".to_string(),
+            toml::from_str::<HighlightPattern>("pattern = \"dies\\\\.$\"\nfg = \"#ff0000\"")
+                .unwrap(),
+        );
+
+        run(&lines, XmlParser::new(), &highlights, 3)
+    }
+
+    #[test]
+    fn test_counts_lines_iterations_and_highlights() {
+        let report = report();
+        assert_eq!(
+            (report.lines, report.iterations, report.highlights),
+            (5, 3, 1)
+        );
+        assert_eq!(
+            report.allocations.is_some_and(|n| n > 0),
+            cfg!(feature = "bench")
+        );
+    }
+
+    #[test]
+    fn test_timings_are_ordered() {
+        let report = report();
+        assert!(report.lines_per_sec > 0.0);
+        assert!(report.p50_micros <= report.p99_micros && report.p99_micros <= report.max_micros);
+        assert!(!report.slowest.is_empty() && report.slowest.len() <= LINES.len());
+    }
+
+    #[test]
+    fn test_summary_and_json() {
+        let report = report();
+        assert!(report.summary().contains("lines/sec"));
+        assert!(serde_json::to_string(&report)
+            .unwrap()
+            .contains("\"allocations_per_line\""));
+    }
+}
@@ -17,6 +17,14 @@ pub fn copy(text: &str) -> Result<()> {
     Ok(())
 }
 
+/// Copy HTML to the system clipboard, with plain text for apps that can't take HTML
+pub fn copy_html(html: &str, alt_text: &str) -> Result<()> {
+    let mut clipboard = Clipboard::new()?;
+    clipboard.set_html(html, Some(alt_text))?;
+    tracing::debug!("Copied {} bytes of HTML to clipboard", html.len());
+    Ok(())
+}
+
 /// Paste text from system clipboard
 pub fn paste() -> Result<String> {
     let mut clipboard = Clipboard::new()?;
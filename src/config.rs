@@ -12,7 +12,16 @@ use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 
+pub mod bundle;
+pub mod diff;
+pub mod keybind_import;
+pub mod launch_history;
+pub mod layout_diff;
+pub mod layout_import;
 pub mod menu_keybind_validator;
+pub mod settings;
+pub mod sizing;
+pub mod validate;
 
 // Embed default configuration files at compile time
 const DEFAULT_CONFIG: &str = include_str!("../defaults/config.toml");
@@ -20,6 +29,7 @@ const DEFAULT_COLORS: &str = include_str!("../defaults/colors.toml");
 const DEFAULT_HIGHLIGHTS: &str = include_str!("../defaults/highlights.toml");
 const DEFAULT_KEYBINDS: &str = include_str!("../defaults/keybinds.toml");
 const DEFAULT_CMDLIST: &str = include_str!("../defaults/cmdlist1.xml");
+const DEFAULT_CREATURES: &str = include_str!("../defaults/creatures.toml");
 
 // Embed entire directories - automatically includes all files
 static LAYOUTS_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/defaults/layouts");
@@ -53,7 +63,7 @@ impl WidgetCategory {
 
     pub fn from_widget_type(widget_type: &str) -> Self {
         match widget_type {
-            "progress" => Self::ProgressBar,
+            "progress" | "vitals" => Self::ProgressBar,
             "text" => Self::TextWindow,
             "countdown" => Self::Countdown,
             "hand" => Self::Hand,
@@ -67,19 +77,62 @@ impl WidgetCategory {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub connection: ConnectionConfig,
+    #[serde(default)]
+    pub lich: LichLaunchConfig,
+    #[serde(default)]
+    pub on_connect: OnConnectConfig,
     pub ui: UiConfig,
     #[serde(skip)] // Loaded from separate highlights.toml file
     pub highlights: HashMap<String, HighlightPattern>,
     #[serde(skip)] // Loaded from separate keybinds.toml file
     pub keybinds: HashMap<String, KeyBindAction>,
+    #[serde(skip)] // Loaded from separate keybind_modes.toml file
+    pub keybind_modes: HashMap<String, HashMap<String, KeyBindAction>>,
+    #[serde(skip)] // Loaded from separate searches.toml file
+    pub saved_searches: HashMap<String, SavedSearch>,
+    #[serde(skip)] // Loaded from separate snippets.toml file
+    pub snippets: HashMap<String, Snippet>,
+    #[serde(skip)] // Loaded from separate sounds.toml file
+    pub sound_events: HashMap<String, SoundEventConfig>,
+    #[serde(skip)] // Loaded from separate watchlist.toml file
+    pub watchlist: WatchListConfig,
     #[serde(default)]
     pub sound: SoundConfig,
     #[serde(default)]
+    pub creatures: CreatureAlertConfig,
+    #[serde(default)]
     pub tts: TtsConfig,
     #[serde(default)]
+    pub stt: SttConfig,
+    #[serde(default)]
+    pub spectator: SpectatorConfig,
+    #[serde(default)]
+    pub passthrough: PassthroughConfig,
+    #[serde(default)]
+    pub websocket: WebSocketConfig,
+    #[serde(default)]
+    pub relay: RelayConfig,
+    #[serde(default)]
+    pub push: PushConfig,
+    #[serde(default)]
+    pub afk: AfkConfig,
+    #[serde(default)]
+    pub screen_reader: ScreenReaderConfig,
+    #[serde(default)]
+    pub theme_schedule: ThemeScheduleConfig,
+    #[serde(default)]
+    pub gui: GuiConfig,
+    #[serde(default)]
     pub event_patterns: HashMap<String, EventPattern>,
     #[serde(default)]
     pub layout_mappings: Vec<LayoutMapping>,
+    #[serde(default)]
+    // Lines copied or moved into another window by pattern, whatever their stream
+    pub redirects: Vec<RedirectRule>,
+    #[serde(default)] // Custom named actions: name -> sequence of built-in action names
+    pub actions: HashMap<String, Vec<String>>,
+    #[serde(default)] // Injury doll popup treatment overrides: "limb wound 3" -> herb or spell
+    pub injury_remedies: HashMap<String, String>,
     #[serde(skip)] // Don't serialize/deserialize this - it's set at runtime
     pub character: Option<String>, // Character name for character-specific saving
     #[serde(skip)] // Loaded from separate colors.toml file (includes color_palette)
@@ -90,6 +143,21 @@ pub struct Config {
     pub active_theme: String, // Currently active theme name
 }
 
+/// Send lines matching `pattern` to another text window as well as (or instead of) their own
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RedirectRule {
+    pub pattern: String, // Regex on the plain line text; "{character}" stands for the character's name
+    pub window: String,  // Text window the lines go to
+    #[serde(default = "default_redirect_mode")]
+    pub mode: String, // "copy" leaves the line where it was, "move" takes it out of its own windows
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub streams: Vec<String>, // Only lines from these streams (empty = any)
+}
+
+fn default_redirect_mode() -> String {
+    "copy".to_string()
+}
+
 /// Terminal size range to layout mapping
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LayoutMapping {
@@ -199,6 +267,8 @@ impl SpellColorRange {
 pub struct UiColors {
     #[serde(default = "default_command_echo_color")]
     pub command_echo_color: String,
+    #[serde(default = "default_scripted_echo_color")]
+    pub scripted_echo_color: String, // Echo of commands sent by macros, login commands, plugins and remote clients
     #[serde(default = "default_border_color_default")]
     pub border_color: String, // Default border color for all widgets
     #[serde(default = "default_focused_border_color")]
@@ -234,6 +304,7 @@ impl Default for UiColors {
     fn default() -> Self {
         Self {
             command_echo_color: default_command_echo_color(),
+            scripted_echo_color: default_scripted_echo_color(),
             border_color: default_border_color_default(),
             focused_border_color: default_focused_border_color(),
             text_color: default_text_color_default(),
@@ -361,12 +432,187 @@ impl Config {
 
     /// Save keybinds to keybinds.toml for a character
     fn save_keybinds(&self, character: Option<&str>) -> Result<()> {
+        Self::write_keybinds(&self.keybinds, character)
+    }
+
+    /// Write a keybind table to keybinds.toml without touching the rest of the config
+    pub fn write_keybinds(
+        keybinds: &HashMap<String, KeyBindAction>,
+        character: Option<&str>,
+    ) -> Result<()> {
         let keybinds_path = Self::keybinds_path(character)?;
-        let contents =
-            toml::to_string_pretty(&self.keybinds).context("Failed to serialize keybinds")?;
+        if let Some(parent) = keybinds_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let contents = toml::to_string_pretty(keybinds).context("Failed to serialize keybinds")?;
         fs::write(&keybinds_path, contents).context("Failed to write keybinds.toml")?;
         Ok(())
     }
+
+    /// Load mode-scoped keybinds from keybind_modes.toml (one table per mode)
+    ///
+    /// Only read, never written: these are hand-edited overlays on keybinds.toml.
+    pub fn load_keybind_modes(
+        character: Option<&str>,
+    ) -> Result<HashMap<String, HashMap<String, KeyBindAction>>> {
+        let modes_path = Self::keybind_modes_path(character)?;
+
+        if modes_path.exists() {
+            let contents =
+                fs::read_to_string(&modes_path).context("Failed to read keybind_modes.toml")?;
+            let modes: HashMap<String, HashMap<String, KeyBindAction>> =
+                toml::from_str(&contents).context("Failed to parse keybind_modes.toml")?;
+            Ok(modes)
+        } else {
+            Ok(HashMap::new())
+        }
+    }
+
+    /// Load saved searches from searches.toml for a character (empty if none saved yet)
+    pub fn load_saved_searches(character: Option<&str>) -> Result<HashMap<String, SavedSearch>> {
+        let searches_path = Self::searches_path(character)?;
+
+        if searches_path.exists() {
+            let contents =
+                fs::read_to_string(&searches_path).context("Failed to read searches.toml")?;
+            let searches: HashMap<String, SavedSearch> =
+                toml::from_str(&contents).context("Failed to parse searches.toml")?;
+            Ok(searches)
+        } else {
+            Ok(HashMap::new())
+        }
+    }
+
+    /// Load command snippets from snippets.toml for a character (empty if none saved yet)
+    pub fn load_snippets(character: Option<&str>) -> Result<HashMap<String, Snippet>> {
+        let snippets_path = Self::snippets_path(character)?;
+
+        if snippets_path.exists() {
+            let contents =
+                fs::read_to_string(&snippets_path).context("Failed to read snippets.toml")?;
+            let snippets: HashMap<String, Snippet> =
+                toml::from_str(&contents).context("Failed to parse snippets.toml")?;
+            Ok(snippets)
+        } else {
+            Ok(HashMap::new())
+        }
+    }
+
+    /// Load event sounds from sounds.toml for a character (empty if there's no file)
+    pub fn load_sound_events(character: Option<&str>) -> Result<HashMap<String, SoundEventConfig>> {
+        let sounds_path = Self::sound_events_path(character)?;
+
+        if sounds_path.exists() {
+            let contents =
+                fs::read_to_string(&sounds_path).context("Failed to read sounds.toml")?;
+            let sounds: HashMap<String, SoundEventConfig> =
+                toml::from_str(&contents).context("Failed to parse sounds.toml")?;
+            for (name, sound) in &sounds {
+                if crate::core::sound_events::GameEvent::from_name(name).is_none() {
+                    tracing::warn!("sounds.toml: unknown event '{}'", name);
+                }
+                if let Some(category) = &sound.category {
+                    if crate::sound::SoundCategory::from_name(category).is_none() {
+                        tracing::warn!("sounds.toml: unknown category '{}' for {}", category, name);
+                    }
+                }
+            }
+            Ok(sounds)
+        } else {
+            Ok(HashMap::new())
+        }
+    }
+
+    /// Re-read just the `[[redirects]]` rules from a character's config.toml
+    pub fn load_redirects(character: Option<&str>) -> Result<Vec<RedirectRule>> {
+        #[derive(Deserialize)]
+        struct RedirectsOnly {
+            #[serde(default)]
+            redirects: Vec<RedirectRule>,
+        }
+
+        let config_path = Self::config_path(character)?;
+        let contents = fs::read_to_string(&config_path).context("Failed to read config.toml")?;
+        let parsed: RedirectsOnly =
+            toml::from_str(&contents).context("Failed to parse config.toml")?;
+        Ok(parsed.redirects)
+    }
+
+    /// Save saved searches to searches.toml for a character
+    pub fn save_saved_searches(&self, character: Option<&str>) -> Result<()> {
+        let searches_path = Self::searches_path(character)?;
+        if let Some(parent) = searches_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let contents = toml::to_string_pretty(&self.saved_searches)
+            .context("Failed to serialize saved searches")?;
+        fs::write(&searches_path, contents).context("Failed to write searches.toml")?;
+        Ok(())
+    }
+
+    /// Save command snippets to snippets.toml for a character
+    pub fn save_snippets(&self, character: Option<&str>) -> Result<()> {
+        let snippets_path = Self::snippets_path(character)?;
+        if let Some(parent) = snippets_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let contents =
+            toml::to_string_pretty(&self.snippets).context("Failed to serialize snippets")?;
+        fs::write(&snippets_path, contents).context("Failed to write snippets.toml")?;
+        Ok(())
+    }
+
+    /// Load the bundled creature database plus ~/.two-face/creatures.toml on top
+    pub fn load_creature_db() -> Result<crate::core::creatures::CreatureDb> {
+        use crate::core::creatures::CreatureDb;
+
+        let mut db = CreatureDb::parse(DEFAULT_CREATURES)
+            .context("Failed to parse bundled creatures.toml")?;
+        let creatures_path = Self::config_dir()?.join("creatures.toml");
+        if creatures_path.exists() {
+            let contents =
+                fs::read_to_string(&creatures_path).context("Failed to read creatures.toml")?;
+            db.merge(CreatureDb::parse(&contents).context("Failed to parse creatures.toml")?);
+        }
+        Ok(db)
+    }
+
+    /// Load the friends/enemies watch list from watchlist.toml (empty if none saved yet)
+    pub fn load_watchlist(character: Option<&str>) -> Result<WatchListConfig> {
+        let watchlist_path = Self::watchlist_path(character)?;
+
+        if watchlist_path.exists() {
+            let contents =
+                fs::read_to_string(&watchlist_path).context("Failed to read watchlist.toml")?;
+            toml::from_str(&contents).context("Failed to parse watchlist.toml")
+        } else {
+            Ok(WatchListConfig::default())
+        }
+    }
+
+    /// Save the friends/enemies watch list to watchlist.toml for a character
+    pub fn save_watchlist(&self, character: Option<&str>) -> Result<()> {
+        let watchlist_path = Self::watchlist_path(character)?;
+        if let Some(parent) = watchlist_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let contents =
+            toml::to_string_pretty(&self.watchlist).context("Failed to serialize watch list")?;
+        fs::write(&watchlist_path, contents).context("Failed to write watchlist.toml")?;
+        Ok(())
+    }
+
+    /// Save sound event mappings to sounds.toml
+    pub fn save_sound_events(&self, character: Option<&str>) -> Result<()> {
+        let sounds_path = Self::sound_events_path(character)?;
+        if let Some(parent) = sounds_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let contents = toml::to_string_pretty(&self.sound_events)
+            .context("Failed to serialize sound events")?;
+        fs::write(&sounds_path, contents).context("Failed to write sounds.toml")?;
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -580,6 +826,15 @@ pub struct WindowBase {
     /// Whether this window is currently visible (defaults to true for backwards compatibility)
     #[serde(default = "default_true")]
     pub visible: bool,
+    /// Compact mode hides windows ranked below `ui.compact_min_priority` (unset = widget type default)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<u8>,
+    /// Width as a share of the screen ("30%") or flex weight ("1fr"), resolved into `cols`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<sizing::SizeSpec>,
+    /// Height as a share of the screen or flex weight, resolved into `rows`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub height: Option<sizing::SizeSpec>,
 }
 
 /// Text widget specific data
@@ -589,6 +844,22 @@ pub struct TextWidgetData {
     pub streams: Vec<String>,
     #[serde(default = "default_buffer_size")]
     pub buffer_size: usize,
+    /// Only show lines matching one of these regexes (empty = all lines)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<String>,
+    /// Hide lines matching any of these regexes
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+    /// Timestamp settings for this window (unset = `[ui.timestamps]`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamps: Option<TimestampOverride>,
+    /// Fold repeated identical lines into one with an "(xN)" count (unset = `ui.collapse_repeats`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collapse_repeats: Option<bool>,
+    /// Idle separator threshold for this window in minutes, 0 = off
+    /// (unset = `ui.gap_separator_minutes` for main, off elsewhere)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gap_separator_minutes: Option<u32>,
 }
 
 /// Room widget specific data
@@ -633,6 +904,9 @@ pub struct InventoryWidgetData {
     pub streams: Vec<String>,
     #[serde(default)]
     pub buffer_size: usize,
+    /// Container nodes shown collapsed, by container path ("backpack/small pouch")
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub collapsed: Vec<String>,
 }
 
 /// TabbedText widget specific data
@@ -642,6 +916,12 @@ pub struct TabbedTextWidgetData {
     pub tabs: Vec<TabbedTextTab>,
     #[serde(default = "default_buffer_size")]
     pub buffer_size: usize,
+    /// Timestamp settings for every tab (unset = `[ui.timestamps]`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamps: Option<TimestampOverride>,
+    /// Idle separator threshold for every tab in minutes (unset = off)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gap_separator_minutes: Option<u32>,
 }
 
 /// Tab configuration for TabbedText widget
@@ -663,6 +943,107 @@ pub struct ProgressWidgetData {
     pub color: Option<String>,
 }
 
+/// Vitals cluster widget specific data
+///
+/// Renders several game progress bars inside one border with the label and
+/// value columns aligned across rows.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VitalsWidgetData {
+    #[serde(default = "default_vitals_children")]
+    pub children: Vec<VitalsChild>,
+}
+
+/// One bar inside a vitals cluster
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VitalsChild {
+    /// Game progress bar id ("health", "mana", "stamina", "spirit", "encumlevel", ...)
+    pub id: String,
+    /// Row label (defaults to the id)
+    #[serde(default)]
+    pub label: Option<String>,
+    /// Bar fill color (defaults to the standalone progress bar color for this id)
+    #[serde(default)]
+    pub color: Option<String>,
+}
+
+fn default_vitals_children() -> Vec<VitalsChild> {
+    [
+        ("health", "Health"),
+        ("mana", "Mana"),
+        ("stamina", "Stamina"),
+        ("spirit", "Spirit"),
+    ]
+    .into_iter()
+    .map(|(id, label)| VitalsChild {
+        id: id.to_string(),
+        label: Some(label.to_string()),
+        color: None,
+    })
+    .collect()
+}
+
+/// Container widget specific data
+///
+/// A container owns the geometry of its children: they are laid out inside it
+/// in rows or columns by weight, so the group moves and resizes as one unit.
+/// Children stay ordinary top-level windows and are referenced by name, which
+/// lets containers nest.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContainerWidgetData {
+    /// "rows" stacks children top to bottom, "columns" places them side by side
+    #[serde(default = "default_container_direction")]
+    pub direction: String,
+    #[serde(default)]
+    pub children: Vec<ContainerChild>,
+}
+
+/// A window placed inside a container
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContainerChild {
+    pub name: String,
+    /// Relative share of the container's space
+    #[serde(default = "default_container_weight")]
+    pub weight: u16,
+}
+
+fn default_container_direction() -> String {
+    "rows".to_string()
+}
+
+fn default_container_weight() -> u16 {
+    1
+}
+
+impl ContainerWidgetData {
+    /// True when children are placed side by side
+    pub fn is_columns(&self) -> bool {
+        matches!(self.direction.as_str(), "columns" | "cols" | "horizontal")
+    }
+
+    /// Split `total` cells by weight, returning (offset, size) per weight
+    ///
+    /// Rounding leftovers go to the last child so the container is always
+    /// filled exactly. A weight of 0 is treated as 1.
+    pub fn split(weights: &[u16], total: u16) -> Vec<(u16, u16)> {
+        let sum: u32 = weights.iter().map(|w| (*w).max(1) as u32).sum();
+        let mut offset = 0u16;
+        weights
+            .iter()
+            .enumerate()
+            .map(|(i, weight)| {
+                let size = if i + 1 == weights.len() {
+                    total.saturating_sub(offset)
+                } else {
+                    ((total as u32 * (*weight).max(1) as u32) / sum) as u16
+                };
+                let cell = (offset, size);
+                offset = offset.saturating_add(size);
+                cell
+            })
+            .collect()
+    }
+}
+
 /// Countdown timer widget specific data
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CountdownWidgetData {
@@ -739,6 +1120,78 @@ pub struct PlayersWidgetData {
     // No extra fields currently
 }
 
+/// Loot tracker widget specific data
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LootTrackerWidgetData {
+    /// Gem names listed under the totals (0 = none)
+    #[serde(default = "default_loot_gem_rows")]
+    pub gem_rows: usize,
+}
+
+fn default_loot_gem_rows() -> usize {
+    5
+}
+
+/// Session stats widget specific data
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionStatsWidgetData {
+    // No extra fields currently
+}
+
+/// Plugin widget specific data
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PluginWidgetData {
+    /// Widget name the plugin provides (defaults to the window name)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+}
+
+/// Container contents tree widget specific data
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContainerTreeWidgetData {
+    // No extra fields currently
+}
+
+/// Bounty tracker widget specific data
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BountyTrackerWidgetData {
+    // No extra fields currently
+}
+
+/// Watch list widget specific data
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WatchListWidgetData {
+    /// List the friends and enemies who aren't online too
+    #[serde(default)]
+    pub show_offline: bool,
+}
+
+/// Art window specific data
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArtWidgetData {
+    /// Image file (PNG or JPEG). Relative paths are under ~/.two-face/art/;
+    /// "{room}" becomes the current room id, so one window can follow a folder
+    /// of area maps named by room
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Shown instead when the image is missing or can't be read
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alt: Option<String>,
+}
+
+/// Status line widget specific data
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatusLineWidgetData {
+    /// Text with `{field}` values (`{rt}`, `{mana}`, `{stance}`, ...) and
+    /// `{#rrggbb}` ... `{/}` color runs
+    #[serde(default = "default_status_line_template")]
+    pub template: String,
+}
+
+fn default_status_line_template() -> String {
+    "RT {rt}  Mana {mana}/{mana_max}  {stance}  {encumbrance}".to_string()
+}
+
 /// Spacer widget specific data
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SpacerWidgetData {
@@ -827,6 +1280,22 @@ pub enum WindowDef {
         data: ProgressWidgetData,
     },
 
+    #[serde(rename = "vitals")]
+    Vitals {
+        #[serde(flatten)]
+        base: WindowBase,
+        #[serde(flatten)]
+        data: VitalsWidgetData,
+    },
+
+    #[serde(rename = "container")]
+    Container {
+        #[serde(flatten)]
+        base: WindowBase,
+        #[serde(flatten)]
+        data: ContainerWidgetData,
+    },
+
     #[serde(rename = "countdown")]
     Countdown {
         #[serde(flatten)]
@@ -899,6 +1368,70 @@ pub enum WindowDef {
         data: PlayersWidgetData,
     },
 
+    #[serde(rename = "loot_tracker")]
+    LootTracker {
+        #[serde(flatten)]
+        base: WindowBase,
+        #[serde(flatten)]
+        data: LootTrackerWidgetData,
+    },
+
+    #[serde(rename = "session_stats")]
+    SessionStats {
+        #[serde(flatten)]
+        base: WindowBase,
+        #[serde(flatten)]
+        data: SessionStatsWidgetData,
+    },
+
+    #[serde(rename = "plugin")]
+    Plugin {
+        #[serde(flatten)]
+        base: WindowBase,
+        #[serde(flatten)]
+        data: PluginWidgetData,
+    },
+
+    #[serde(rename = "containers")]
+    ContainerTree {
+        #[serde(flatten)]
+        base: WindowBase,
+        #[serde(flatten)]
+        data: ContainerTreeWidgetData,
+    },
+
+    #[serde(rename = "bounty_tracker")]
+    BountyTracker {
+        #[serde(flatten)]
+        base: WindowBase,
+        #[serde(flatten)]
+        data: BountyTrackerWidgetData,
+    },
+
+    #[serde(rename = "watch_list")]
+    WatchList {
+        #[serde(flatten)]
+        base: WindowBase,
+        #[serde(flatten)]
+        data: WatchListWidgetData,
+    },
+
+    #[serde(rename = "art")]
+    Art {
+        #[serde(flatten)]
+        base: WindowBase,
+        #[serde(flatten)]
+        data: ArtWidgetData,
+    },
+
+    #[serde(rename = "status_line")]
+    StatusLine {
+        #[serde(flatten)]
+        base: WindowBase,
+        #[serde(flatten)]
+        data: StatusLineWidgetData,
+    },
+
     #[serde(rename = "spacer")]
     Spacer {
         #[serde(flatten)]
@@ -934,6 +1467,8 @@ impl WindowDef {
             WindowDef::Inventory { base, .. } => &base.name,
             WindowDef::CommandInput { base, .. } => &base.name,
             WindowDef::Progress { base, .. } => &base.name,
+            WindowDef::Vitals { base, .. } => &base.name,
+            WindowDef::Container { base, .. } => &base.name,
             WindowDef::Countdown { base, .. } => &base.name,
             WindowDef::Compass { base, .. } => &base.name,
             WindowDef::Indicator { base, .. } => &base.name,
@@ -946,6 +1481,14 @@ impl WindowDef {
             WindowDef::Spacer { base, .. } => &base.name,
             WindowDef::Spells { base, .. } => &base.name,
             WindowDef::QuickBar { base, .. } => &base.name,
+            WindowDef::LootTracker { base, .. } => &base.name,
+            WindowDef::SessionStats { base, .. } => &base.name,
+            WindowDef::BountyTracker { base, .. } => &base.name,
+            WindowDef::ContainerTree { base, .. } => &base.name,
+            WindowDef::Plugin { base, .. } => &base.name,
+            WindowDef::WatchList { base, .. } => &base.name,
+            WindowDef::Art { base, .. } => &base.name,
+            WindowDef::StatusLine { base, .. } => &base.name,
         }
     }
 
@@ -958,6 +1501,8 @@ impl WindowDef {
             WindowDef::Inventory { .. } => "inventory",
             WindowDef::CommandInput { .. } => "command_input",
             WindowDef::Progress { .. } => "progress",
+            WindowDef::Vitals { .. } => "vitals",
+            WindowDef::Container { .. } => "container",
             WindowDef::Countdown { .. } => "countdown",
             WindowDef::Compass { .. } => "compass",
             WindowDef::Indicator { .. } => "indicator",
@@ -970,6 +1515,14 @@ impl WindowDef {
             WindowDef::Spacer { .. } => "spacer",
             WindowDef::Spells { .. } => "spells",
             WindowDef::QuickBar { .. } => "quickbar",
+            WindowDef::LootTracker { .. } => "loot_tracker",
+            WindowDef::SessionStats { .. } => "session_stats",
+            WindowDef::BountyTracker { .. } => "bounty_tracker",
+            WindowDef::ContainerTree { .. } => "containers",
+            WindowDef::Plugin { .. } => "plugin",
+            WindowDef::WatchList { .. } => "watch_list",
+            WindowDef::Art { .. } => "art",
+            WindowDef::StatusLine { .. } => "status_line",
         }
     }
 
@@ -982,6 +1535,8 @@ impl WindowDef {
             WindowDef::Inventory { base, .. } => base,
             WindowDef::CommandInput { base, .. } => base,
             WindowDef::Progress { base, .. } => base,
+            WindowDef::Vitals { base, .. } => base,
+            WindowDef::Container { base, .. } => base,
             WindowDef::Countdown { base, .. } => base,
             WindowDef::Compass { base, .. } => base,
             WindowDef::Indicator { base, .. } => base,
@@ -994,6 +1549,14 @@ impl WindowDef {
             WindowDef::Spacer { base, .. } => base,
             WindowDef::Spells { base, .. } => base,
             WindowDef::QuickBar { base, .. } => base,
+            WindowDef::LootTracker { base, .. } => base,
+            WindowDef::SessionStats { base, .. } => base,
+            WindowDef::BountyTracker { base, .. } => base,
+            WindowDef::ContainerTree { base, .. } => base,
+            WindowDef::Plugin { base, .. } => base,
+            WindowDef::WatchList { base, .. } => base,
+            WindowDef::Art { base, .. } => base,
+            WindowDef::StatusLine { base, .. } => base,
         }
     }
 
@@ -1006,6 +1569,8 @@ impl WindowDef {
             WindowDef::Inventory { base, .. } => base,
             WindowDef::CommandInput { base, .. } => base,
             WindowDef::Progress { base, .. } => base,
+            WindowDef::Vitals { base, .. } => base,
+            WindowDef::Container { base, .. } => base,
             WindowDef::Countdown { base, .. } => base,
             WindowDef::Compass { base, .. } => base,
             WindowDef::Indicator { base, .. } => base,
@@ -1018,6 +1583,14 @@ impl WindowDef {
             WindowDef::Spacer { base, .. } => base,
             WindowDef::Spells { base, .. } => base,
             WindowDef::QuickBar { base, .. } => base,
+            WindowDef::LootTracker { base, .. } => base,
+            WindowDef::SessionStats { base, .. } => base,
+            WindowDef::BountyTracker { base, .. } => base,
+            WindowDef::ContainerTree { base, .. } => base,
+            WindowDef::Plugin { base, .. } => base,
+            WindowDef::WatchList { base, .. } => base,
+            WindowDef::Art { base, .. } => base,
+            WindowDef::StatusLine { base, .. } => base,
         }
     }
 
@@ -1119,56 +1692,263 @@ pub struct ConnectionConfig {
     pub character: Option<String>,
 }
 
+/// Starting Lich when nothing is listening on connection.port
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct UiConfig {
-    #[serde(default = "default_buffer_size")]
-    pub buffer_size: usize,
+pub struct LichLaunchConfig {
+    #[serde(default = "default_lich_auto_launch")]
+    pub auto_launch: String, // "ask", "always" or "never"
     #[serde(default)]
-    pub show_timestamps: bool,
-    #[serde(default)]
-    pub layout: LayoutConfig,
-    #[serde(default = "default_border_style")]
-    pub border_style: String, // Default border style: "single", "double", "rounded", "thick", "none"
-    #[serde(default = "default_countdown_icon")]
-    pub countdown_icon: String, // Unicode character for countdown blocks (e.g., "\u{f0c8}")
-    #[serde(default = "default_poll_timeout_ms")]
-    pub poll_timeout_ms: u64, // Event poll timeout in milliseconds (lower = higher FPS, higher CPU)
-    // Startup music settings
-    #[serde(default = "default_startup_music")]
-    pub startup_music: bool, // Play startup music on connection
-    #[serde(default = "default_startup_music_file")]
-    pub startup_music_file: String, // Sound file to play on startup (without extension)
-    // Text selection settings
-    #[serde(default = "default_selection_enabled")]
-    pub selection_enabled: bool,
-    #[serde(default = "default_selection_respect_window_boundaries")]
-    pub selection_respect_window_boundaries: bool,
-    // Drag and drop settings
-    #[serde(default = "default_drag_modifier_key")]
-    pub drag_modifier_key: String, // Modifier key required for drag and drop (e.g., "ctrl", "alt", "shift")
-    // Command history settings
-    #[serde(default = "default_min_command_length")]
-    pub min_command_length: usize, // Minimum command length to save to history (commands shorter than this are not saved)
-    // Performance stats settings
-    #[serde(default = "default_perf_stats_x")]
-    pub perf_stats_x: u16,
-    #[serde(default = "default_perf_stats_y")]
-    pub perf_stats_y: u16,
-    #[serde(default = "default_perf_stats_width")]
-    pub perf_stats_width: u16,
-    #[serde(default = "default_perf_stats_height")]
-    pub perf_stats_height: u16,
+    pub command: String, // Empty = find lich.rbw; {character} and {port} are filled in
+    #[serde(default = "default_lich_wait_secs")]
+    pub wait_secs: u64, // How long to wait for Lich's port to open
 }
 
-// CommandInputConfig removed - command_input is now a regular window in the windows array
+fn default_lich_auto_launch() -> String {
+    "ask".to_string()
+}
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct LayoutConfig {
-    // Layout is now entirely defined by window positions and sizes
-    // No global grid needed
+fn default_lich_wait_secs() -> u64 {
+    60
 }
 
-/// Represents a saved layout (windows only - command_input is just another window)
+impl Default for LichLaunchConfig {
+    fn default() -> Self {
+        Self {
+            auto_launch: default_lich_auto_launch(),
+            command: String::new(),
+            wait_secs: default_lich_wait_secs(),
+        }
+    }
+}
+
+/// Commands sent once the game is ready after connecting (the first prompt)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OnConnectConfig {
+    #[serde(default)]
+    pub commands: Vec<String>, // Sent in order, like typed input (dot commands work)
+    #[serde(default = "default_on_connect_delay_ms")]
+    pub delay_ms: u64, // Pause between commands
+}
+
+fn default_on_connect_delay_ms() -> u64 {
+    1000
+}
+
+impl Default for OnConnectConfig {
+    fn default() -> Self {
+        Self {
+            commands: Vec::new(),
+            delay_ms: default_on_connect_delay_ms(),
+        }
+    }
+}
+
+/// Timestamps on text window lines (`[ui.timestamps]`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TimestampConfig {
+    pub enabled: bool,
+    pub format: String,        // strftime format ("%H:%M:%S"); default "%l:%M %p"
+    pub relative: bool,        // Show age ("3m") instead of the clock time
+    pub position: String,      // "end" (after the text) or "start"
+    pub color: Option<String>, // Dim gray when unset
+}
+
+impl Default for TimestampConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            format: "%l:%M %p".to_string(),
+            relative: false,
+            position: "end".to_string(),
+            color: None,
+        }
+    }
+}
+
+impl TimestampConfig {
+    /// Whether chrono can render a strftime format
+    pub fn format_is_valid(format: &str) -> bool {
+        use chrono::format::{Item, StrftimeItems};
+        !StrftimeItems::new(format).any(|item| matches!(item, Item::Error))
+    }
+
+    /// These settings with a window's overrides applied
+    pub fn with_override(&self, over: Option<&TimestampOverride>) -> TimestampConfig {
+        let Some(over) = over else {
+            return self.clone();
+        };
+        TimestampConfig {
+            enabled: over.enabled.unwrap_or(self.enabled),
+            format: over.format.clone().unwrap_or_else(|| self.format.clone()),
+            relative: over.relative.unwrap_or(self.relative),
+            position: over
+                .position
+                .clone()
+                .unwrap_or_else(|| self.position.clone()),
+            color: over.color.clone().or_else(|| self.color.clone()),
+        }
+    }
+}
+
+/// Per-window timestamp settings; unset fields follow `[ui.timestamps]`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TimestampOverride {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relative: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+}
+
+/// `[ui.timestamps]` table, or the bare boolean older configs used
+fn deserialize_timestamps<'de, D>(deserializer: D) -> Result<TimestampConfig, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Timestamps {
+        Enabled(bool),
+        Full(TimestampConfig),
+    }
+    Ok(match Timestamps::deserialize(deserializer)? {
+        Timestamps::Enabled(enabled) => TimestampConfig {
+            enabled,
+            ..TimestampConfig::default()
+        },
+        Timestamps::Full(config) => config,
+    })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UiConfig {
+    #[serde(default = "default_buffer_size")]
+    pub buffer_size: usize,
+    /// Line timestamps; `show_timestamps = true` from older configs still works
+    #[serde(
+        default,
+        alias = "show_timestamps",
+        deserialize_with = "deserialize_timestamps"
+    )]
+    pub timestamps: TimestampConfig,
+    #[serde(default)]
+    pub layout: LayoutConfig,
+    #[serde(default = "default_border_style")]
+    pub border_style: String, // Default border style: "single", "double", "rounded", "thick", "none"
+    #[serde(default = "default_countdown_icon")]
+    pub countdown_icon: String, // Unicode character for countdown blocks (e.g., "\u{f0c8}")
+    #[serde(default = "default_poll_timeout_ms")]
+    pub poll_timeout_ms: u64, // Event poll timeout in milliseconds (lower = higher FPS, higher CPU)
+    #[serde(default = "default_idle_poll_max_ms")]
+    pub idle_poll_max_ms: u64, // Longest poll timeout once idle (<= poll_timeout_ms = never back off)
+    #[serde(default = "default_color_mode")]
+    pub color_mode: String, // "auto", "truecolor", "256", or "16" - RGB colors are downsampled below truecolor
+    #[serde(default = "default_graphics")]
+    pub graphics: String, // Art window images: "auto", "kitty", "sixel", or "off" (half-block text)
+    #[serde(default)]
+    pub min_contrast: f64, // Lighten/darken text to at least this contrast ratio vs its background (0 = off, 4.5 = WCAG AA)
+    #[serde(default)]
+    pub startup_layout: String, // Saved layout loaded at startup ("" = last session's layout)
+    #[serde(default = "default_true")]
+    pub ask_startup_layout: bool, // Offer the saved layouts at startup when there's no default
+    #[serde(default = "default_true")]
+    pub confirm_config_writes: bool, // Review highlight/keybind/color edits before they're written (.configdiff)
+    #[serde(default = "default_true")]
+    pub confirm_multiline_paste: bool, // Ask before sending a paste with several lines
+    #[serde(default)]
+    pub compose_line_delay_ms: u64, // Wait between lines sent from the composer (0 = back to back)
+    #[serde(default = "default_command_echo")]
+    pub command_echo: String, // Echo sent commands in main: "all", "typed" (not macros, plugins or remote) or "none"
+    #[serde(default = "default_true")]
+    pub command_echo_prompt: bool, // Start each echo with the game prompt
+    #[serde(default)]
+    pub command_echo_prefix: String, // Text before each echoed command, after the prompt (e.g. "> ")
+    #[serde(default)]
+    pub snap_grid: u16, // Round mouse window moves/resizes to this many cells (0 = off)
+    #[serde(default = "default_true")]
+    pub snap_to_windows: bool, // Snap dragged window edges to nearby window edges, with guide lines
+    // Compact mode settings (narrow terminals)
+    #[serde(default = "default_compact_width")]
+    pub compact_width: u16, // Switch to compact mode below this many columns (0 = only via .compact)
+    #[serde(default = "default_compact_borders")]
+    pub compact_borders: String, // Borders in compact mode: "single" or "none"
+    #[serde(default = "default_compact_min_priority")]
+    pub compact_min_priority: u8, // Hide windows whose priority is below this in compact mode
+    // Startup music settings
+    #[serde(default = "default_startup_music")]
+    pub startup_music: bool, // Play startup music on connection
+    #[serde(default = "default_startup_music_file")]
+    pub startup_music_file: String, // Sound file to play on startup (without extension)
+    // Text selection settings
+    #[serde(default = "default_selection_enabled")]
+    pub selection_enabled: bool,
+    #[serde(default = "default_selection_respect_window_boundaries")]
+    pub selection_respect_window_boundaries: bool,
+    // Drag and drop settings
+    #[serde(default = "default_drag_modifier_key")]
+    pub drag_modifier_key: String, // Modifier key required for drag and drop (e.g., "ctrl", "alt", "shift")
+    // Command history settings
+    #[serde(default = "default_min_command_length")]
+    pub min_command_length: usize, // Minimum command length to save to history (commands shorter than this are not saved)
+    #[serde(default = "default_history_max_entries")]
+    pub history_max_entries: usize, // Commands kept in history (in memory and in history.txt)
+    #[serde(default)]
+    pub history_denylist: Vec<String>, // Regexes; matching commands are never written to history.txt
+
+    #[serde(default = "default_chord_timeout_ms")]
+    pub chord_timeout_ms: u64, // How long to wait for the next key of a multi-key keybind (e.g. "ctrl+x h")
+    #[serde(default)]
+    pub text_fade_minutes: u32, // Dim text window lines older than this many minutes (0 = off)
+    #[serde(default)]
+    pub gap_separator_minutes: u32, // Mark silences this long in main with a separator line (0 = off; other windows opt in)
+    #[serde(default = "default_true")]
+    pub input_timer: bool, // Color the command input border during roundtime/casttime and ghost the text in roundtime
+    #[serde(default)]
+    pub collapse_repeats: bool, // Fold a text window line identical to the one before it into an "(xN)" count
+    #[serde(default)]
+    pub more_prompt: bool, // Hold the scrolled-back main window at "-- more --" after each screen of new text
+    #[serde(default)]
+    pub scrollback_disk_lines: usize, // Lines past buffer_size each text window keeps on disk (0 = off)
+    #[serde(default = "default_true")]
+    pub keyboard_enhancement: bool, // Use the kitty keyboard protocol (when supported) so numpad keys are distinct
+    // Performance stats settings
+    #[serde(default = "default_perf_stats_x")]
+    pub perf_stats_x: u16,
+    #[serde(default = "default_perf_stats_y")]
+    pub perf_stats_y: u16,
+    #[serde(default = "default_perf_stats_width")]
+    pub perf_stats_width: u16,
+    #[serde(default = "default_perf_stats_height")]
+    pub perf_stats_height: u16,
+}
+
+impl UiConfig {
+    /// Idle separator threshold for a text window: its own setting, else the
+    /// global one for main only (quiet streams would get one before nearly every line)
+    pub fn gap_minutes_for(&self, window: &str, own: Option<u32>) -> u32 {
+        own.unwrap_or(if window == "main" {
+            self.gap_separator_minutes
+        } else {
+            0
+        })
+    }
+}
+
+// CommandInputConfig removed - command_input is now a regular window in the windows array
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct LayoutConfig {
+    // Layout is now entirely defined by window positions and sizes
+    // No global grid needed
+}
+
+/// Represents a saved layout (windows only - command_input is just another window)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Layout {
     pub windows: Vec<WindowDef>,
@@ -1262,14 +2042,62 @@ pub struct HighlightPattern {
     pub sound: Option<String>, // Sound file to play when pattern matches
     #[serde(skip_serializing_if = "Option::is_none")]
     pub sound_volume: Option<f32>, // Volume override for this sound (0.0 to 1.0)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sound_category: Option<String>, // Volume category: alerts (default), ambience or ui
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sound_cooldown_ms: Option<u64>, // Minimum time between this highlight's sounds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_fires: Option<u32>, // Play the sound at most this many times per session (1 = once)
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub only_when_unfocused: bool, // Only play while the terminal is in the background
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub bell: bool, // Ring the terminal bell on a match
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub flash: bool, // Flash the border of the window(s) in `windows` (main if none) on a match
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quiet_hours: Option<String>, // "22:00-07:00": no sound during this local time range
     #[serde(skip_serializing_if = "Option::is_none")]
     pub category: Option<String>, // Category for grouping highlights (e.g., "Combat", "Healing", "Death")
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub windows: Vec<String>, // Window names or stream ids this pattern is limited to (empty = everywhere)
 
     // Performance optimization: cache compiled regex (not serialized)
     #[serde(skip)]
     pub compiled_regex: Option<regex::Regex>,
 }
 
+/// A named search pattern saved for reuse (`.savesearch`, `.search <window> @name`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedSearch {
+    pub pattern: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window: Option<String>, // Window to search when none is given (None = focused window)
+}
+
+/// A named command snippet (`.snippet <name>`): macro text with `${name}` placeholders
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snippet {
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>, // Shown in the snippet browser instead of the text
+}
+
+impl HighlightPattern {
+    /// Whether this pattern should be applied in the given window.
+    ///
+    /// A pattern with no `windows` scope applies everywhere. Otherwise it applies
+    /// when the scope names the window itself or any stream routed into it.
+    pub fn applies_to_window(&self, window_name: &str, streams: &[String]) -> bool {
+        if self.windows.is_empty() {
+            return true;
+        }
+        self.windows.iter().any(|scope| {
+            scope.eq_ignore_ascii_case(window_name)
+                || streams.iter().any(|s| scope.eq_ignore_ascii_case(s))
+        })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EventPattern {
     pub pattern: String,     // Regex pattern to match
@@ -1314,6 +2142,25 @@ pub struct SoundConfig {
     pub volume: f32, // Master volume (0.0 to 1.0)
     #[serde(default = "default_sound_cooldown")]
     pub cooldown_ms: u64, // Cooldown between same sound plays (milliseconds)
+    #[serde(default = "default_category_volume")]
+    pub alerts_volume: f32, // Level for alert sounds (deaths, whispers, highlight sounds)
+    #[serde(default = "default_category_volume")]
+    pub ambience_volume: f32, // Level for ambient sounds
+    #[serde(default = "default_category_volume")]
+    pub ui_volume: f32, // Level for client feedback (roundtime end, expiring spells)
+    #[serde(default)]
+    pub pack: String, // Sound pack under ~/.two-face/sounds/<pack>/ ("" = none)
+}
+
+impl SoundConfig {
+    /// Configured level of one volume category
+    pub fn category_volume(&self, category: crate::sound::SoundCategory) -> f32 {
+        match category {
+            crate::sound::SoundCategory::Alerts => self.alerts_volume,
+            crate::sound::SoundCategory::Ambience => self.ambience_volume,
+            crate::sound::SoundCategory::Ui => self.ui_volume,
+        }
+    }
 }
 
 fn default_sound_enabled() -> bool {
@@ -1328,12 +2175,130 @@ fn default_sound_cooldown() -> u64 {
     500 // 500ms default cooldown
 }
 
+fn default_category_volume() -> f32 {
+    1.0
+}
+
 impl Default for SoundConfig {
     fn default() -> Self {
         Self {
             enabled: default_sound_enabled(),
             volume: default_sound_volume(),
             cooldown_ms: default_sound_cooldown(),
+            alerts_volume: default_category_volume(),
+            ambience_volume: default_category_volume(),
+            ui_volume: default_category_volume(),
+            pack: String::new(),
+        }
+    }
+}
+
+/// One entry in sounds.toml: the sound played for a game event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SoundEventConfig {
+    #[serde(default)]
+    pub file: String, // File in ~/.two-face/sounds/ (extension optional, empty = no sound)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub volume: Option<f32>, // Overrides the master volume (0.0 to 1.0)
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_spell_warning_seconds")]
+    pub seconds: u32, // spell_expiring: warn when this many seconds are left
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub names: Vec<String>, // friend_login: only these characters (empty = anyone)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>, // Volume category (default depends on the event)
+    #[serde(default)]
+    pub bell: bool, // Also ring the terminal bell
+    #[serde(default)]
+    pub flash: bool, // Also flash the border of the event's window
+}
+
+fn default_spell_warning_seconds() -> u32 {
+    30
+}
+
+impl Default for SoundEventConfig {
+    fn default() -> Self {
+        Self {
+            file: String::new(),
+            volume: None,
+            enabled: true,
+            seconds: default_spell_warning_seconds(),
+            names: Vec::new(),
+            category: None,
+            bell: false,
+            flash: false,
+        }
+    }
+}
+
+/// Alerts for dangerous creatures from the creature database (`[creatures]`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatureAlertConfig {
+    #[serde(default = "default_creature_warn_at")]
+    pub warn_at: crate::core::creatures::Danger, // Lowest danger that raises an alert
+    #[serde(default = "default_enabled")]
+    pub announce: bool, // Warning line in the main window when one arrives
+    #[serde(default)]
+    pub speak: bool, // Speak the warning through TTS
+    #[serde(default = "default_enabled")]
+    pub color_targets: bool, // Color dangerous entries in targets windows
+    #[serde(default = "default_creature_color")]
+    pub color: String,
+}
+
+fn default_creature_warn_at() -> crate::core::creatures::Danger {
+    crate::core::creatures::Danger::High
+}
+
+fn default_creature_color() -> String {
+    "#ff8c00".to_string()
+}
+
+impl Default for CreatureAlertConfig {
+    fn default() -> Self {
+        Self {
+            warn_at: default_creature_warn_at(),
+            announce: true,
+            speak: false,
+            color_targets: true,
+            color: default_creature_color(),
+        }
+    }
+}
+
+/// Friends and enemies watched for on the logons and death streams (`watchlist.toml`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchListConfig {
+    #[serde(default)]
+    pub friends: Vec<String>,
+    #[serde(default)]
+    pub enemies: Vec<String>,
+    #[serde(default = "default_enabled")]
+    pub announce: bool, // Echo arrivals, departures and deaths into the main window
+    #[serde(default = "default_watch_friend_color")]
+    pub friend_color: String,
+    #[serde(default = "default_watch_enemy_color")]
+    pub enemy_color: String,
+}
+
+fn default_watch_friend_color() -> String {
+    "#55ff55".to_string()
+}
+
+fn default_watch_enemy_color() -> String {
+    "#ff5555".to_string()
+}
+
+impl Default for WatchListConfig {
+    fn default() -> Self {
+        Self {
+            friends: Vec::new(),
+            enemies: Vec::new(),
+            announce: true,
+            friend_color: default_watch_friend_color(),
+            enemy_color: default_watch_enemy_color(),
         }
     }
 }
@@ -1358,6 +2323,59 @@ pub struct TtsConfig {
     pub speak_whispers: bool, // Automatically speak whispers
     #[serde(default = "default_tts_speak_main")]
     pub speak_main: bool, // Automatically speak main window
+    #[serde(default)]
+    pub speak_deaths: bool, // Automatically speak death messages
+    #[serde(default = "default_tts_pitch")]
+    pub pitch: f32, // Pitch (0.5 to 2.0, 1.0 = the voice's normal pitch)
+    #[serde(default)]
+    pub streams: HashMap<String, TtsVoiceConfig>, // Per-window voice overrides ("speech", "thoughts", "death", "main")
+    #[serde(default)]
+    pub include: Vec<String>, // Regexes; when set, only matching lines are spoken
+    #[serde(default)]
+    pub exclude: Vec<String>, // Regexes; matching lines are never spoken
+    #[serde(default)]
+    pub only_when_unfocused: bool, // Stay quiet while the terminal has focus
+    #[serde(default)]
+    pub priority_rules: Vec<TtsPriorityRule>, // First match sets the priority
+    #[serde(default = "default_tts_interrupt_priority")]
+    pub interrupt_priority: String, // "normal", "high", "critical", or "never"
+}
+
+/// Give lines matching `pattern` a different speech priority
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TtsPriorityRule {
+    pub pattern: String,
+    pub priority: String, // "normal", "high", or "critical"
+}
+
+/// Voice settings for one window's speech; unset fields use the [tts] defaults
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TtsVoiceConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub voice: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pitch: Option<f32>,
+}
+
+impl TtsConfig {
+    /// Voice, rate and pitch to use for a window, after overrides
+    pub fn voice_for(&self, window: &str) -> (Option<String>, f32, f32) {
+        let stream = self.streams.get(window);
+        let voice = stream
+            .and_then(|s| s.voice.clone())
+            .or_else(|| self.voice.clone())
+            .filter(|v| !v.trim().is_empty());
+        let rate = stream.and_then(|s| s.rate).unwrap_or(self.rate);
+        let pitch = stream.and_then(|s| s.pitch).unwrap_or(self.pitch);
+        (voice, rate, pitch)
+    }
+
+    /// Override entry for a window, created empty if missing
+    pub fn stream_mut(&mut self, window: &str) -> &mut TtsVoiceConfig {
+        self.streams.entry(window.to_string()).or_default()
+    }
 }
 
 fn default_tts_enabled() -> bool {
@@ -1388,6 +2406,14 @@ fn default_tts_speak_main() -> bool {
     false // Main window can be overwhelming, off by default
 }
 
+fn default_tts_pitch() -> f32 {
+    1.0 // Voice's normal pitch
+}
+
+fn default_tts_interrupt_priority() -> String {
+    "high".to_string() // Whispers and thoughts cut off regular text
+}
+
 impl Default for TtsConfig {
     fn default() -> Self {
         Self {
@@ -1398,6 +2424,519 @@ impl Default for TtsConfig {
             speak_thoughts: default_tts_speak_thoughts(),
             speak_whispers: default_tts_speak_whispers(),
             speak_main: default_tts_speak_main(),
+            speak_deaths: false,
+            pitch: default_tts_pitch(),
+            streams: HashMap::new(),
+            include: Vec::new(),
+            exclude: Vec::new(),
+            only_when_unfocused: false,
+            priority_rules: Vec::new(),
+            interrupt_priority: default_tts_interrupt_priority(),
+        }
+    }
+}
+
+/// Speech-to-Text Configuration
+///
+/// Push-to-talk dictation into the command input using a local whisper.cpp
+/// model. Requires building with the `stt` feature; disabled by default.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SttConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub model_path: Option<String>, // ggml model file (relative paths resolve under ~/.two-face/models/)
+    #[serde(default = "default_stt_language")]
+    pub language: String, // Spoken language code passed to whisper ("auto" to detect)
+    #[serde(default)]
+    pub auto_send: bool, // Send transcripts immediately instead of waiting for Enter
+}
+
+fn default_stt_language() -> String {
+    "en".to_string()
+}
+
+impl Default for SttConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false, // Disabled by default (opt-in)
+            model_path: None,
+            language: default_stt_language(),
+            auto_send: false, // Dictated text waits in the command input for confirmation
+        }
+    }
+}
+
+/// Read-only spectator view configuration
+///
+/// Serves selected text windows as a live HTML page for a tablet or second
+/// screen. Requires building with the `spectator` feature; disabled by default
+/// and never starts without a token.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpectatorConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_spectator_bind")]
+    pub bind: String, // Listen address ("0.0.0.0" to allow other devices on the LAN)
+    #[serde(default = "default_spectator_port")]
+    pub port: u16,
+    #[serde(default)]
+    pub token: String, // Required on every request (?token=... or Authorization: Bearer)
+    #[serde(default = "default_spectator_windows")]
+    pub windows: Vec<String>, // Text windows to serve, in page order
+    #[serde(default = "default_spectator_lines")]
+    pub lines: usize, // Most recent lines served per window
+    #[serde(default = "default_spectator_refresh_secs")]
+    pub refresh_secs: u64, // Browser reload interval
+}
+
+/// Raw game stream passthrough configuration
+///
+/// Mirrors every line from the game server, read-only, to companion tools
+/// that connect to a local port. Disabled by default; only addresses in
+/// `allow` may connect.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PassthroughConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_passthrough_bind")]
+    pub bind: String, // Listen address ("0.0.0.0" to accept tools on other machines)
+    #[serde(default = "default_passthrough_port")]
+    pub port: u16,
+    #[serde(default = "default_passthrough_allow")]
+    pub allow: Vec<String>, // Client IP addresses allowed to connect
+}
+
+fn default_passthrough_bind() -> String {
+    "127.0.0.1".to_string()
+}
+
+fn default_passthrough_port() -> u16 {
+    8766
+}
+
+fn default_passthrough_allow() -> Vec<String> {
+    vec!["127.0.0.1".to_string(), "::1".to_string()]
+}
+
+impl Default for PassthroughConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false, // Disabled by default (opt-in)
+            bind: default_passthrough_bind(),
+            port: default_passthrough_port(),
+            allow: default_passthrough_allow(),
+        }
+    }
+}
+
+/// Local WebSocket API configuration
+///
+/// Serves parsed events (room, vitals, lines) to companion apps and takes
+/// commands back. Disabled by default; a token is required.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebSocketConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_passthrough_bind")]
+    pub bind: String, // Listen address ("0.0.0.0" to allow other devices on your LAN)
+    #[serde(default = "default_websocket_port")]
+    pub port: u16,
+    #[serde(default)]
+    pub token: String, // Required on the upgrade request (?token=... or Authorization: Bearer)
+    #[serde(default = "default_true")]
+    pub accept_commands: bool, // false = events only
+}
+
+fn default_websocket_port() -> u16 {
+    8767
+}
+
+impl Default for WebSocketConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false, // Disabled by default (opt-in)
+            bind: default_passthrough_bind(),
+            port: default_websocket_port(),
+            token: String::new(),
+            accept_commands: true,
+        }
+    }
+}
+
+/// Webhook relay: forwards selected streams and pattern matches to a
+/// Discord webhook or another HTTP endpoint. Disabled by default.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelayConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub url: String, // Discord webhook URL or any endpoint taking a JSON POST
+    #[serde(default = "default_relay_format")]
+    pub format: String, // "discord" or "json"
+    #[serde(default)]
+    pub streams: Vec<String>, // Streams relayed in full ("death", "thoughts")
+    #[serde(default)]
+    pub patterns: Vec<String>, // Regexes; matching lines from any stream are relayed
+    #[serde(default)]
+    pub highlights: Vec<String>, // Names of highlights whose matches are relayed
+    #[serde(default)]
+    pub redact: Vec<RedactRule>, // Applied to every relayed line
+    #[serde(default = "default_relay_max_per_minute")]
+    pub max_per_minute: u32, // Posts per minute; lines over the limit are dropped (0 = no limit)
+    #[serde(default = "default_relay_batch_ms")]
+    pub batch_ms: u64, // Lines arriving within this window share one post
+}
+
+/// Text hidden from relayed lines
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedactRule {
+    pub pattern: String,
+    #[serde(default = "default_redaction")]
+    pub replacement: String,
+}
+
+fn default_relay_format() -> String {
+    "discord".to_string()
+}
+
+fn default_relay_max_per_minute() -> u32 {
+    20
+}
+
+fn default_relay_batch_ms() -> u64 {
+    2000
+}
+
+fn default_redaction() -> String {
+    "[redacted]".to_string()
+}
+
+impl Default for RelayConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false, // Disabled by default (opt-in)
+            url: String::new(),
+            format: default_relay_format(),
+            streams: Vec::new(),
+            patterns: Vec::new(),
+            highlights: Vec::new(),
+            redact: Vec::new(),
+            max_per_minute: default_relay_max_per_minute(),
+            batch_ms: default_relay_batch_ms(),
+        }
+    }
+}
+
+/// Push notifications to a phone (ntfy or Pushover) for deaths, whispers
+/// while away, and lost connections. Disabled by default.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PushConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_push_service")]
+    pub service: String, // "ntfy" or "pushover"
+    #[serde(default = "default_ntfy_server")]
+    pub server: String, // ntfy server
+    #[serde(default)]
+    pub topic: String, // ntfy topic
+    #[serde(default)]
+    pub token: String, // Pushover application token, or an ntfy access token
+    #[serde(default)]
+    pub user: String, // Pushover user key
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quiet_hours: Option<String>, // "23:00-07:00": nothing is sent during this local time range
+    #[serde(default = "default_push_cooldown_secs")]
+    pub cooldown_secs: u64, // Minimum time between two notifications for the same event
+    #[serde(default = "default_push_afk_minutes")]
+    pub afk_minutes: u64, // Whispers notify after this long without a command sent
+    #[serde(default)]
+    pub events: HashMap<String, PushEventConfig>, // "death", "whisper", "disconnect"; unlisted events are on
+}
+
+/// Per-event push notification settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PushEventConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<String>, // min, low, default, high or urgent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quiet_hours: Option<String>, // Replaces [push] quiet_hours for this event ("" = never quiet)
+}
+
+fn default_push_service() -> String {
+    "ntfy".to_string()
+}
+
+fn default_ntfy_server() -> String {
+    "https://ntfy.sh".to_string()
+}
+
+fn default_push_cooldown_secs() -> u64 {
+    60
+}
+
+fn default_push_afk_minutes() -> u64 {
+    5
+}
+
+impl Default for PushConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false, // Disabled by default (opt-in)
+            service: default_push_service(),
+            server: default_ntfy_server(),
+            topic: String::new(),
+            token: String::new(),
+            user: String::new(),
+            quiet_hours: None,
+            cooldown_secs: default_push_cooldown_secs(),
+            afk_minutes: default_push_afk_minutes(),
+            events: HashMap::new(),
+        }
+    }
+}
+
+/// Away-from-keyboard tracking: after `minutes` without keyboard or mouse
+/// input the session is marked AFK, whispers can get an auto-reply, and
+/// what was missed is logged to the `afk` window.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AfkConfig {
+    #[serde(default = "default_afk_minutes")]
+    pub minutes: u64, // 0 = only when marked with .afk
+    #[serde(default)]
+    pub auto_reply: String, // Whispered once to each person who whispers while AFK ("" = off)
+    #[serde(default = "default_afk_log_streams")]
+    pub log_streams: Vec<String>, // Streams logged while AFK, besides whispers
+}
+
+fn default_afk_minutes() -> u64 {
+    15
+}
+
+fn default_afk_log_streams() -> Vec<String> {
+    vec!["speech".to_string()]
+}
+
+impl Default for AfkConfig {
+    fn default() -> Self {
+        Self {
+            minutes: default_afk_minutes(),
+            auto_reply: String::new(),
+            log_streams: default_afk_log_streams(),
+        }
+    }
+}
+
+/// Screen reader mode: no box art, a review buffer, and new lines spoken
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScreenReaderConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_review_lines")]
+    pub review_lines: usize, // Lines of recent output kept for review_previous/review_next
+    #[serde(default = "default_speech_dedupe_seconds")]
+    pub dedupe_seconds: u64, // Don't repeat a line spoken this recently (0 = speak every repeat)
+}
+
+fn default_review_lines() -> usize {
+    500
+}
+
+fn default_speech_dedupe_seconds() -> u64 {
+    10
+}
+
+impl Default for ScreenReaderConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            review_lines: default_review_lines(),
+            dedupe_seconds: default_speech_dedupe_seconds(),
+        }
+    }
+}
+
+fn default_spectator_bind() -> String {
+    "127.0.0.1".to_string()
+}
+
+fn default_spectator_port() -> u16 {
+    8765
+}
+
+fn default_spectator_windows() -> Vec<String> {
+    vec!["main".to_string(), "thoughts".to_string()]
+}
+
+fn default_spectator_lines() -> usize {
+    200
+}
+
+fn default_spectator_refresh_secs() -> u64 {
+    2
+}
+
+impl Default for SpectatorConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false, // Disabled by default (opt-in)
+            bind: default_spectator_bind(),
+            port: default_spectator_port(),
+            token: String::new(),
+            windows: default_spectator_windows(),
+            lines: default_spectator_lines(),
+            refresh_secs: default_spectator_refresh_secs(),
+        }
+    }
+}
+
+/// Settings only the GUI frontend reads; the TUI ignores this section
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GuiConfig {
+    #[serde(default)]
+    pub fonts: GuiFontConfig,
+}
+
+/// Fonts for the GUI frontend
+///
+/// Families are font names or paths to .ttf/.otf files; empty means the
+/// built-in default. `fallback` is tried in order for glyphs the main font
+/// lacks (a Nerd Font here covers icons).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GuiFontConfig {
+    #[serde(default)]
+    pub ui_family: String, // Menus, dialogs, window titles
+    #[serde(default = "default_gui_font_size")]
+    pub ui_size: f32,
+    #[serde(default)]
+    pub text_family: String, // Game text (should be monospace)
+    #[serde(default = "default_gui_font_size")]
+    pub text_size: f32,
+    #[serde(default = "default_gui_font_fallback")]
+    pub fallback: Vec<String>,
+    #[serde(default)]
+    pub window_sizes: HashMap<String, f32>, // Game text size per window name
+}
+
+/// Sizes outside this range are clamped rather than rejected
+pub const GUI_FONT_SIZE_RANGE: (f32, f32) = (6.0, 72.0);
+
+fn default_gui_font_size() -> f32 {
+    14.0
+}
+
+fn default_gui_font_fallback() -> Vec<String> {
+    vec![
+        "Symbols Nerd Font Mono".to_string(),
+        "Noto Color Emoji".to_string(),
+    ]
+}
+
+impl Default for GuiFontConfig {
+    fn default() -> Self {
+        Self {
+            ui_family: String::new(),
+            ui_size: default_gui_font_size(),
+            text_family: String::new(),
+            text_size: default_gui_font_size(),
+            fallback: default_gui_font_fallback(),
+            window_sizes: HashMap::new(),
+        }
+    }
+}
+
+impl GuiFontConfig {
+    /// Game text size for a window, honoring its override
+    pub fn text_size_for(&self, window: &str) -> f32 {
+        let size = self
+            .window_sizes
+            .get(window)
+            .copied()
+            .unwrap_or(self.text_size);
+        clamp_font_size(size)
+    }
+
+    pub fn ui_size(&self) -> f32 {
+        clamp_font_size(self.ui_size)
+    }
+
+    /// Families to try for game text, main font first (empty = default only)
+    pub fn text_chain(&self) -> Vec<&str> {
+        Self::chain(&self.text_family, &self.fallback)
+    }
+
+    /// Families to try for UI text, main font first
+    pub fn ui_chain(&self) -> Vec<&str> {
+        Self::chain(&self.ui_family, &self.fallback)
+    }
+
+    fn chain<'a>(primary: &'a str, fallback: &'a [String]) -> Vec<&'a str> {
+        let mut chain: Vec<&str> = Vec::new();
+        for family in std::iter::once(primary).chain(fallback.iter().map(String::as_str)) {
+            let family = family.trim();
+            if !family.is_empty() && !chain.contains(&family) {
+                chain.push(family);
+            }
+        }
+        chain
+    }
+}
+
+fn clamp_font_size(size: f32) -> f32 {
+    if size.is_finite() {
+        size.clamp(GUI_FONT_SIZE_RANGE.0, GUI_FONT_SIZE_RANGE.1)
+    } else {
+        default_gui_font_size()
+    }
+}
+
+/// Automatic day/night theme switching
+///
+/// In "time" mode the day theme applies from `day_start` until `night_start`
+/// (local "HH:MM"); in "os" mode it follows the OS light/dark preference.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThemeScheduleConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_theme_schedule_mode")]
+    pub mode: String, // "time" or "os"
+    #[serde(default = "default_day_theme")]
+    pub day_theme: String,
+    #[serde(default = "default_theme_name")]
+    pub night_theme: String,
+    #[serde(default = "default_day_start")]
+    pub day_start: String,
+    #[serde(default = "default_night_start")]
+    pub night_start: String,
+}
+
+fn default_theme_schedule_mode() -> String {
+    "time".to_string()
+}
+
+fn default_day_theme() -> String {
+    "light".to_string()
+}
+
+fn default_day_start() -> String {
+    "07:00".to_string()
+}
+
+fn default_night_start() -> String {
+    "19:00".to_string()
+}
+
+impl Default for ThemeScheduleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false, // Opt-in; the active theme is left alone otherwise
+            mode: default_theme_schedule_mode(),
+            day_theme: default_day_theme(),
+            night_theme: default_theme_name(),
+            day_start: default_day_start(),
+            night_start: default_night_start(),
         }
     }
 }
@@ -1416,7 +2955,146 @@ pub enum KeyBindAction {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MacroAction {
-    pub macro_text: String, // e.g., "sw\r" for southwest movement
+    pub macro_text: String, // e.g., "sw\r" for southwest movement; see core::macros for \p, \?, \x, @
+}
+
+impl KeyBindAction {
+    /// Short description for status lines ("scroll_current_window_up_page", "macro 'sw'")
+    pub fn describe(&self) -> String {
+        match self {
+            KeyBindAction::Action(name) => name.clone(),
+            KeyBindAction::Macro(m) => {
+                format!("macro '{}'", m.macro_text.trim_end_matches(['\r', '\n']))
+            }
+        }
+    }
+}
+
+/// A clash found when saving a keybind (see `Config::keybind_conflicts`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeybindConflict {
+    /// Existing keybind involved (removed if the user chooses to overwrite)
+    pub existing: Option<String>,
+    /// What goes wrong if the keybind is saved as-is
+    pub description: String,
+}
+
+/// Keys handled before user keybinds are consulted - binding them never fires
+const RESERVED_GLOBAL_KEYS: &[(&str, &str)] = &[
+    ("ctrl+c", "quits"),
+    ("ctrl+f", "opens search"),
+    ("ctrl+page_up", "jumps to the previous search match"),
+    ("ctrl+page_down", "jumps to the next search match"),
+    ("esc", "closes menus and popups"),
+    ("enter", "submits the command input"),
+];
+
+/// Command input editing keys that a keybind would take over
+const COMMAND_INPUT_KEYS: &[(&str, &str)] = &[
+    ("ctrl+a", "moves to the start of the line"),
+    ("ctrl+e", "moves to the end of the line"),
+    ("ctrl+u", "clears the command input"),
+    ("ctrl+w", "deletes the previous word"),
+];
+
+/// Normalize a key step for comparison (ctrl+C and ctrl+c are the same press)
+fn normalize_key_step((code, modifiers): (KeyCode, KeyModifiers)) -> (KeyCode, KeyModifiers) {
+    match code {
+        KeyCode::Char(c) if modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) => {
+            (KeyCode::Char(c.to_ascii_lowercase()), modifiers)
+        }
+        _ => (code, modifiers),
+    }
+}
+
+fn normalized_sequence(key_str: &str) -> Option<Vec<(KeyCode, KeyModifiers)>> {
+    parse_key_sequence(key_str).map(|seq| seq.into_iter().map(normalize_key_step).collect())
+}
+
+impl Config {
+    /// Find everything a new keybind would collide with
+    ///
+    /// Checks existing binds (exact matches and chord prefixes that shadow each
+    /// other), keys the app handles globally, and command input editing/typing
+    /// keys. `replacing` is the bind being edited, which is never a conflict.
+    pub fn keybind_conflicts(
+        &self,
+        key_combo: &str,
+        replacing: Option<&str>,
+    ) -> Vec<KeybindConflict> {
+        let Some(sequence) = normalized_sequence(key_combo) else {
+            return Vec::new();
+        };
+        let mut conflicts = Vec::new();
+
+        let mut existing: Vec<_> = self
+            .keybinds
+            .iter()
+            .filter(|(combo, _)| Some(combo.as_str()) != replacing)
+            .collect();
+        existing.sort_by(|a, b| a.0.cmp(b.0));
+
+        for (combo, action) in existing {
+            let Some(other) = normalized_sequence(combo) else {
+                continue;
+            };
+            let description = if other == sequence {
+                format!("'{}' is already bound to {}", combo, action.describe())
+            } else if sequence.starts_with(&other) {
+                format!(
+                    "'{}' ({}) fires before this chord can finish",
+                    combo,
+                    action.describe()
+                )
+            } else if other.starts_with(&sequence) {
+                format!(
+                    "chord '{}' ({}) takes precedence over this key",
+                    combo,
+                    action.describe()
+                )
+            } else {
+                continue;
+            };
+            conflicts.push(KeybindConflict {
+                existing: Some(combo.clone()),
+                description,
+            });
+        }
+
+        for (reserved, what) in RESERVED_GLOBAL_KEYS {
+            if let Some(step) = normalized_sequence(reserved).and_then(|s| s.into_iter().next()) {
+                if sequence.contains(&step) {
+                    conflicts.push(KeybindConflict {
+                        existing: None,
+                        description: format!(
+                            "'{}' {} and is never passed to keybinds",
+                            reserved, what
+                        ),
+                    });
+                }
+            }
+        }
+
+        let first = sequence[0];
+        for (editing_key, what) in COMMAND_INPUT_KEYS {
+            if normalized_sequence(editing_key).is_some_and(|s| s[0] == first) {
+                conflicts.push(KeybindConflict {
+                    existing: None,
+                    description: format!("'{}' {} in the command input", editing_key, what),
+                });
+            }
+        }
+        if let (KeyCode::Char(c), modifiers) = first {
+            if !modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) {
+                conflicts.push(KeybindConflict {
+                    existing: None,
+                    description: format!("'{}' would no longer type in the command input", c),
+                });
+            }
+        }
+
+        conflicts
+    }
 }
 
 /// Actions that can be bound to keys
@@ -1438,9 +3116,11 @@ pub enum KeyAction {
     NextCommand,
     SendLastCommand,
     SendSecondLastCommand,
+    HistorySearch,
 
     // Window actions
     SwitchCurrentWindow,
+    SwitchCurrentWindowBack,
     ScrollCurrentWindowUpOne,
     ScrollCurrentWindowDownOne,
     ScrollCurrentWindowUpPage,
@@ -1455,17 +3135,36 @@ pub enum KeyAction {
     // Debug/Performance actions
     TogglePerformanceStats,
 
+    // Sound
+    ToggleSound, // Master mute for sound effects (.mute)
+
     // TTS (Text-to-Speech) actions - Accessibility
     TtsNext,           // Next message (sequential, includes read)
     TtsPrevious,       // Previous message (sequential, includes read)
     TtsNextUnread,     // Skip to next unread message
     TtsStop,           // Stop current speech (keeps position)
+    TtsPause,          // Pause/resume; new lines queue up while paused
+    TtsSkip,           // Cut off the current line and play the next queued one
+    TtsFlush,          // Stop and discard everything still queued
+    TtsRepeatLast,     // Say the last line again
     TtsMuteToggle,     // Toggle TTS mute on/off
     TtsIncreaseRate,   // Increase speech rate by 0.1
     TtsDecreaseRate,   // Decrease speech rate by 0.1
     TtsIncreaseVolume, // Increase volume by 0.1
     TtsDecreaseVolume, // Decrease volume by 0.1
 
+    // Screen reader review buffer - Accessibility
+    ReviewPrevious, // Read the previous line of recent output
+    ReviewNext,     // Read the next line of recent output
+    ReviewLatest,   // Stop reviewing and follow live output
+
+    // Web links from game text
+    CycleRecentUrls, // Step to the next older link, shown on the main window
+    OpenRecentUrl,   // Open the link stepped to (or the newest) in the browser
+
+    // STT (Speech-to-Text) actions - Accessibility
+    SttPushToTalk, // Start dictation, or stop and transcribe if already listening
+
     // Macro - send literal text
     SendMacro(String),
 }
@@ -1768,7 +3467,9 @@ impl KeyAction {
             "next_command" => Some(Self::NextCommand),
             "send_last_command" => Some(Self::SendLastCommand),
             "send_second_last_command" => Some(Self::SendSecondLastCommand),
+            "history_search" => Some(Self::HistorySearch),
             "switch_current_window" => Some(Self::SwitchCurrentWindow),
+            "switch_current_window_back" => Some(Self::SwitchCurrentWindowBack),
             "scroll_current_window_up_one" => Some(Self::ScrollCurrentWindowUpOne),
             "scroll_current_window_down_one" => Some(Self::ScrollCurrentWindowDownOne),
             "scroll_current_window_up_page" => Some(Self::ScrollCurrentWindowUpPage),
@@ -1778,16 +3479,27 @@ impl KeyAction {
             "prev_search_match" => Some(Self::PrevSearchMatch),
             "clear_search" => Some(Self::ClearSearch),
             "toggle_performance_stats" => Some(Self::TogglePerformanceStats),
+            "toggle_sound" => Some(Self::ToggleSound),
             "tts_next" => Some(Self::TtsNext),
             "tts_previous" => Some(Self::TtsPrevious),
             "tts_next_unread" => Some(Self::TtsNextUnread),
             "tts_stop" => Some(Self::TtsStop),
-            "tts_pause_resume" => Some(Self::TtsStop), // Legacy support
+            "tts_pause" => Some(Self::TtsPause),
+            "tts_pause_resume" => Some(Self::TtsPause), // Legacy name
+            "tts_skip" => Some(Self::TtsSkip),
+            "tts_flush" => Some(Self::TtsFlush),
+            "tts_repeat_last" => Some(Self::TtsRepeatLast),
             "tts_mute_toggle" => Some(Self::TtsMuteToggle),
             "tts_increase_rate" => Some(Self::TtsIncreaseRate),
             "tts_decrease_rate" => Some(Self::TtsDecreaseRate),
             "tts_increase_volume" => Some(Self::TtsIncreaseVolume),
             "tts_decrease_volume" => Some(Self::TtsDecreaseVolume),
+            "review_previous" => Some(Self::ReviewPrevious),
+            "review_next" => Some(Self::ReviewNext),
+            "review_latest" => Some(Self::ReviewLatest),
+            "cycle_recent_urls" => Some(Self::CycleRecentUrls),
+            "open_recent_url" => Some(Self::OpenRecentUrl),
+            "stt_push_to_talk" => Some(Self::SttPushToTalk),
             _ => None,
         }
     }
@@ -1849,7 +3561,9 @@ pub fn parse_key_string(key_str: &str) -> Option<(KeyCode, KeyModifiers)> {
         "backspace" => KeyCode::Backspace,
         "delete" => KeyCode::Delete,
         "insert" => KeyCode::Insert,
+        "tab" if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::BackTab, // How terminals report Shift+Tab
         "tab" => KeyCode::Tab,
+        "backtab" => KeyCode::BackTab,
         "esc" | "escape" => KeyCode::Esc,
         "space" => KeyCode::Char(' '),
         "left" => KeyCode::Left,
@@ -1901,7 +3615,125 @@ pub fn parse_key_string(key_str: &str) -> Option<(KeyCode, KeyModifiers)> {
         _ => return None,
     };
 
-    Some((key_code, modifiers))
+    Some((key_code, modifiers))
+}
+
+/// Parse a keybind string into a sequence of key presses.
+///
+/// Single keys ("ctrl+f") produce one step. Multi-key chords separate steps with
+/// whitespace, optionally joined by "then": "ctrl+x h" or "ctrl+x then h".
+pub fn parse_key_sequence(key_str: &str) -> Option<Vec<(KeyCode, KeyModifiers)>> {
+    let steps: Vec<(KeyCode, KeyModifiers)> = key_str
+        .split_whitespace()
+        .filter(|step| !step.eq_ignore_ascii_case("then"))
+        .map(parse_key_string)
+        .collect::<Option<Vec<_>>>()?;
+
+    if steps.is_empty() {
+        None
+    } else {
+        Some(steps)
+    }
+}
+
+/// Character a numpad key types when it isn't bound to anything
+pub fn keypad_char(code: KeyCode) -> Option<char> {
+    match code {
+        KeyCode::Keypad0 => Some('0'),
+        KeyCode::Keypad1 => Some('1'),
+        KeyCode::Keypad2 => Some('2'),
+        KeyCode::Keypad3 => Some('3'),
+        KeyCode::Keypad4 => Some('4'),
+        KeyCode::Keypad5 => Some('5'),
+        KeyCode::Keypad6 => Some('6'),
+        KeyCode::Keypad7 => Some('7'),
+        KeyCode::Keypad8 => Some('8'),
+        KeyCode::Keypad9 => Some('9'),
+        KeyCode::KeypadPeriod => Some('.'),
+        KeyCode::KeypadPlus => Some('+'),
+        KeyCode::KeypadMinus => Some('-'),
+        KeyCode::KeypadMultiply => Some('*'),
+        KeyCode::KeypadDivide => Some('/'),
+        _ => None,
+    }
+}
+
+/// Format a key press back into keybind string form (inverse of `parse_key_string`)
+pub fn format_key_for_keybind(code: KeyCode, modifiers: KeyModifiers) -> String {
+    let base_key = match code {
+        KeyCode::Char(' ') => "space",
+        KeyCode::Char(c) => {
+            // Return character as-is (don't map to numpad format)
+            if modifiers.is_empty() {
+                return c.to_string();
+            } else {
+                return format!("{}+{}", format_modifiers(modifiers), c);
+            }
+        }
+        KeyCode::Enter => "enter",
+        KeyCode::Esc => "esc",
+        KeyCode::Tab => "tab",
+        KeyCode::BackTab if modifiers.contains(KeyModifiers::SHIFT) => "tab",
+        KeyCode::BackTab => "backtab",
+        KeyCode::Backspace => "backspace",
+        KeyCode::Delete => "delete",
+        KeyCode::Insert => "insert",
+        KeyCode::Up => "up",
+        KeyCode::Down => "down",
+        KeyCode::Left => "left",
+        KeyCode::Right => "right",
+        KeyCode::Home => "home",
+        KeyCode::End => "end",
+        KeyCode::PageUp => "pageup",
+        KeyCode::PageDown => "pagedown",
+        KeyCode::Keypad0 => "num_0",
+        KeyCode::Keypad1 => "num_1",
+        KeyCode::Keypad2 => "num_2",
+        KeyCode::Keypad3 => "num_3",
+        KeyCode::Keypad4 => "num_4",
+        KeyCode::Keypad5 => "num_5",
+        KeyCode::Keypad6 => "num_6",
+        KeyCode::Keypad7 => "num_7",
+        KeyCode::Keypad8 => "num_8",
+        KeyCode::Keypad9 => "num_9",
+        KeyCode::KeypadPeriod => "num_.",
+        KeyCode::KeypadPlus => "num_+",
+        KeyCode::KeypadMinus => "num_-",
+        KeyCode::KeypadMultiply => "num_*",
+        KeyCode::KeypadDivide => "num_/",
+        KeyCode::F(n) => {
+            return if modifiers.is_empty() {
+                format!("f{}", n)
+            } else {
+                format!("{}+f{}", format_modifiers(modifiers), n)
+            }
+        }
+        _ => return String::new(), // Unhandled key
+    };
+
+    // Add modifiers if present
+    if modifiers.is_empty() {
+        base_key.to_string()
+    } else {
+        format!("{}+{}", format_modifiers(modifiers), base_key)
+    }
+}
+
+/// Format modifiers as a string (helper for format_key_for_keybind)
+fn format_modifiers(modifiers: KeyModifiers) -> String {
+    let mut parts = Vec::new();
+
+    if modifiers.contains(KeyModifiers::CONTROL) {
+        parts.push("ctrl");
+    }
+    if modifiers.contains(KeyModifiers::ALT) {
+        parts.push("alt");
+    }
+    if modifiers.contains(KeyModifiers::SHIFT) {
+        parts.push("shift");
+    }
+
+    parts.join("+")
 }
 
 fn default_host() -> String {
@@ -1920,6 +3752,14 @@ fn default_command_echo_color() -> String {
     "#ffffff".to_string()
 }
 
+fn default_scripted_echo_color() -> String {
+    "#87ceeb".to_string()
+}
+
+fn default_command_echo() -> String {
+    "all".to_string()
+}
+
 fn default_border_color_default() -> String {
     "#00ffff".to_string() // cyan
 }
@@ -1995,6 +3835,10 @@ pub fn default_keybinds() -> HashMap<String, KeyBindAction> {
         "tab".to_string(),
         KeyBindAction::Action("switch_current_window".to_string()),
     );
+    map.insert(
+        "shift+tab".to_string(),
+        KeyBindAction::Action("switch_current_window_back".to_string()),
+    );
     map.insert(
         "alt+page_up".to_string(),
         KeyBindAction::Action("scroll_current_window_up_one".to_string()),
@@ -2148,6 +3992,18 @@ fn default_poll_timeout_ms() -> u64 {
     16 // 16ms = ~60 FPS, 8ms = ~120 FPS, 4ms = ~240 FPS
 }
 
+fn default_idle_poll_max_ms() -> u64 {
+    200
+}
+
+fn default_color_mode() -> String {
+    "auto".to_string()
+}
+
+fn default_graphics() -> String {
+    "auto".to_string()
+}
+
 fn default_selection_enabled() -> bool {
     true
 }
@@ -2172,6 +4028,26 @@ fn default_min_command_length() -> usize {
     3
 }
 
+fn default_history_max_entries() -> usize {
+    1000
+}
+
+fn default_chord_timeout_ms() -> u64 {
+    1000 // 1 second to finish a key sequence before it's abandoned
+}
+
+fn default_compact_width() -> u16 {
+    100 // An 80-column phone SSH session is compact; a typical desktop terminal isn't
+}
+
+fn default_compact_borders() -> String {
+    "single".to_string()
+}
+
+fn default_compact_min_priority() -> u8 {
+    50
+}
+
 fn default_perf_stats_x() -> u16 {
     0 // Calculated dynamically: terminal_width - 35
 }
@@ -2185,7 +4061,7 @@ fn default_perf_stats_width() -> u16 {
 }
 
 fn default_perf_stats_height() -> u16 {
-    23
+    33
 }
 
 // default_command_input* functions removed - command_input is now in windows array
@@ -2264,6 +4140,10 @@ impl Layout {
                         layout.scale_to_terminal_size(curr_width, curr_height);
                     }
                 }
+                if layout.resolve_sizes(curr_width, curr_height) {
+                    layout.terminal_width = Some(curr_width);
+                    layout.terminal_height = Some(curr_height);
+                }
             }
 
             return Ok((layout, Some(base_name)));
@@ -2562,11 +4442,153 @@ impl Layout {
         Ok(())
     }
 
+    /// Save to an explicit file (`two-face layout merge` writes back to its target)
+    pub fn save_to_file(&mut self, path: &std::path::Path) -> Result<()> {
+        self.normalize_windows_for_save();
+        let toml_string = toml::to_string_pretty(&self).context("Failed to serialize layout")?;
+        fs::write(path, toml_string).context(format!("Failed to write layout file: {:?}", path))?;
+        tracing::info!("Saved layout to {:?}", path);
+        Ok(())
+    }
+
     /// Get a window from the layout by name
     pub fn get_window(&self, name: &str) -> Option<&WindowDef> {
         self.windows.iter().find(|w| w.name() == name)
     }
 
+    /// Resolve percentage and flex sizes (`width`/`height`) for the screen
+    /// size, moving the windows after them along to make room
+    ///
+    /// Only visible windows outside containers take part; containers size
+    /// their own children. Returns false when no window uses these sizes.
+    pub fn resolve_sizes(&mut self, terminal_width: u16, terminal_height: u16) -> bool {
+        let members: Vec<usize> = (0..self.windows.len())
+            .filter(|&i| {
+                let base = self.windows[i].base();
+                base.visible && self.container_of(&base.name).is_none()
+            })
+            .collect();
+        if !members.iter().any(|&i| {
+            self.windows[i].base().width.is_some() || self.windows[i].base().height.is_some()
+        }) {
+            return false;
+        }
+
+        let spans = |horizontal: bool| -> Vec<sizing::Span> {
+            members
+                .iter()
+                .map(|&i| {
+                    let base = self.windows[i].base();
+                    if horizontal {
+                        sizing::Span {
+                            start: base.col,
+                            len: base.cols,
+                            spec: base.width,
+                            min: base.min_cols.unwrap_or(0),
+                            max: base.max_cols,
+                            cross: (base.row, base.row + base.rows),
+                        }
+                    } else {
+                        sizing::Span {
+                            start: base.row,
+                            len: base.rows,
+                            spec: base.height,
+                            min: base.min_rows.unwrap_or(0),
+                            max: base.max_rows,
+                            cross: (base.col, base.col + base.cols),
+                        }
+                    }
+                })
+                .collect()
+        };
+        let columns = sizing::resolve_axis(
+            &spans(true),
+            self.terminal_width.unwrap_or(terminal_width),
+            terminal_width,
+        );
+        let rows = sizing::resolve_axis(
+            &spans(false),
+            self.terminal_height.unwrap_or(terminal_height),
+            terminal_height,
+        );
+
+        for (k, &i) in members.iter().enumerate() {
+            let base = self.windows[i].base_mut();
+            (base.col, base.cols) = columns[k];
+            (base.row, base.rows) = rows[k];
+            tracing::debug!(
+                "Resolved size of '{}': pos=({},{}) size={}x{}",
+                base.name,
+                base.col,
+                base.row,
+                base.cols,
+                base.rows
+            );
+        }
+        true
+    }
+
+    /// Name of the container that holds `name`, if any
+    pub fn container_of(&self, name: &str) -> Option<&str> {
+        self.windows.iter().find_map(|w| match w {
+            WindowDef::Container { base, data } if data.children.iter().any(|c| c.name == name) => {
+                Some(base.name.as_str())
+            }
+            _ => None,
+        })
+    }
+
+    /// Move a window into `container` (or out of any container with None)
+    ///
+    /// The window keeps its weight when moving between containers. Placing a
+    /// container inside itself or one of its own descendants is rejected.
+    pub fn set_container(&mut self, name: &str, container: Option<&str>) -> Result<()> {
+        if container == self.container_of(name) {
+            return Ok(());
+        }
+
+        if let Some(target) = container {
+            if !matches!(self.get_window(target), Some(WindowDef::Container { .. })) {
+                return Err(anyhow::anyhow!("'{}' is not a container", target));
+            }
+            // Walk up from the target; meeting `name` means a cycle
+            let mut ancestor = Some(target);
+            let mut depth = 0;
+            while let Some(current) = ancestor {
+                if current == name || depth > self.windows.len() {
+                    return Err(anyhow::anyhow!(
+                        "Cannot place '{}' inside its own container '{}'",
+                        name,
+                        target
+                    ));
+                }
+                ancestor = self.container_of(current);
+                depth += 1;
+            }
+        }
+
+        let mut weight = default_container_weight();
+        for window in &mut self.windows {
+            if let WindowDef::Container { data, .. } = window {
+                if let Some(index) = data.children.iter().position(|c| c.name == name) {
+                    weight = data.children.remove(index).weight;
+                }
+            }
+        }
+
+        if let Some(target) = container {
+            if let Some(WindowDef::Container { data, .. }) =
+                self.windows.iter_mut().find(|w| w.name() == target)
+            {
+                data.children.push(ContainerChild {
+                    name: name.to_string(),
+                    weight,
+                });
+            }
+        }
+        Ok(())
+    }
+
     /// Add a window to the layout (from template or make visible if exists)
     /// Generate a unique spacer widget name based on existing spacers in layout
     /// Uses max number + 1 algorithm, checking ALL widgets including hidden ones
@@ -2744,6 +4766,9 @@ impl Config {
             min_cols: None,
             max_cols: None,
             visible: true,
+            priority: None,
+            width: None,
+            height: None,
         };
 
         match name {
@@ -2759,6 +4784,11 @@ impl Config {
                 data: TextWidgetData {
                     streams: vec!["main".to_string()],
                     buffer_size: 10000,
+                    include: Vec::new(),
+                    exclude: Vec::new(),
+                    timestamps: None,
+                    collapse_repeats: None,
+                    gap_separator_minutes: None,
                 },
             }),
 
@@ -2793,6 +4823,7 @@ impl Config {
                 data: InventoryWidgetData {
                     streams: vec!["inv".to_string()],
                     buffer_size: 0, // No scrollback for inventory (content replaced each update)
+                    collapsed: Vec::new(),
                 },
             }),
 
@@ -3185,6 +5216,11 @@ impl Config {
                 data: TextWidgetData {
                     streams: vec!["thoughts".to_string()],
                     buffer_size: 1000,
+                    include: Vec::new(),
+                    exclude: Vec::new(),
+                    timestamps: None,
+                    collapse_repeats: None,
+                    gap_separator_minutes: None,
                 },
             }),
 
@@ -3201,6 +5237,11 @@ impl Config {
                 data: TextWidgetData {
                     streams: vec!["speech".to_string()],
                     buffer_size: 1000,
+                    include: Vec::new(),
+                    exclude: Vec::new(),
+                    timestamps: None,
+                    collapse_repeats: None,
+                    gap_separator_minutes: None,
                 },
             }),
 
@@ -3217,6 +5258,11 @@ impl Config {
                 data: TextWidgetData {
                     streams: vec!["announcements".to_string()],
                     buffer_size: 500,
+                    include: Vec::new(),
+                    exclude: Vec::new(),
+                    timestamps: None,
+                    collapse_repeats: None,
+                    gap_separator_minutes: None,
                 },
             }),
 
@@ -3233,6 +5279,32 @@ impl Config {
                 data: TextWidgetData {
                     streams: vec!["loot".to_string()],
                     buffer_size: 500,
+                    include: Vec::new(),
+                    exclude: Vec::new(),
+                    timestamps: None,
+                    collapse_repeats: None,
+                    gap_separator_minutes: None,
+                },
+            }),
+
+            "afk" => Some(WindowDef::Text {
+                base: WindowBase {
+                    name: "afk".to_string(),
+                    title: Some("While AFK".to_string()),
+                    rows: 10,
+                    cols: 50,
+                    show_border: true,
+                    text_color: Some("#DDA0DD".to_string()), // Plum
+                    ..base_defaults.clone()
+                },
+                data: TextWidgetData {
+                    streams: vec!["afk".to_string()],
+                    buffer_size: 500,
+                    include: Vec::new(),
+                    exclude: Vec::new(),
+                    timestamps: None,
+                    collapse_repeats: None,
+                    gap_separator_minutes: None,
                 },
             }),
 
@@ -3249,6 +5321,11 @@ impl Config {
                 data: TextWidgetData {
                     streams: vec!["death".to_string()],
                     buffer_size: 500,
+                    include: Vec::new(),
+                    exclude: Vec::new(),
+                    timestamps: None,
+                    collapse_repeats: None,
+                    gap_separator_minutes: None,
                 },
             }),
 
@@ -3265,6 +5342,11 @@ impl Config {
                 data: TextWidgetData {
                     streams: vec!["logons".to_string()],
                     buffer_size: 500,
+                    include: Vec::new(),
+                    exclude: Vec::new(),
+                    timestamps: None,
+                    collapse_repeats: None,
+                    gap_separator_minutes: None,
                 },
             }),
 
@@ -3281,6 +5363,11 @@ impl Config {
                 data: TextWidgetData {
                     streams: vec!["familiar".to_string()],
                     buffer_size: 1000,
+                    include: Vec::new(),
+                    exclude: Vec::new(),
+                    timestamps: None,
+                    collapse_repeats: None,
+                    gap_separator_minutes: None,
                 },
             }),
 
@@ -3297,6 +5384,11 @@ impl Config {
                 data: TextWidgetData {
                     streams: vec!["ambients".to_string()],
                     buffer_size: 500,
+                    include: Vec::new(),
+                    exclude: Vec::new(),
+                    timestamps: None,
+                    collapse_repeats: None,
+                    gap_separator_minutes: None,
                 },
             }),
 
@@ -3313,6 +5405,11 @@ impl Config {
                 data: TextWidgetData {
                     streams: vec!["bounty".to_string()],
                     buffer_size: 0, // VellumFE uses 0 - content is cleared and replaced
+                    include: Vec::new(),
+                    exclude: Vec::new(),
+                    timestamps: None,
+                    collapse_repeats: None,
+                    gap_separator_minutes: None,
                 },
             }),
 
@@ -3329,6 +5426,33 @@ impl Config {
                 data: TextWidgetData {
                     streams: vec!["society".to_string()],
                     buffer_size: 500,
+                    include: Vec::new(),
+                    exclude: Vec::new(),
+                    timestamps: None,
+                    collapse_repeats: None,
+                    gap_separator_minutes: None,
+                },
+            }),
+
+            // Filled by the client from spell hand changes, not a game stream
+            "spellhistory" => Some(WindowDef::Text {
+                base: WindowBase {
+                    name: "spellhistory".to_string(),
+                    title: Some("Spell History".to_string()),
+                    rows: 6,
+                    cols: 40,
+                    show_border: true,
+                    text_color: Some("#9370DB".to_string()), // Medium purple
+                    ..base_defaults.clone()
+                },
+                data: TextWidgetData {
+                    streams: vec!["spellhistory".to_string()],
+                    buffer_size: 200,
+                    include: Vec::new(),
+                    exclude: Vec::new(),
+                    timestamps: None,
+                    collapse_repeats: None,
+                    gap_separator_minutes: None,
                 },
             }),
 
@@ -3375,6 +5499,148 @@ impl Config {
                 },
             }),
 
+            "container" => Some(WindowDef::Container {
+                base: WindowBase {
+                    name: "container".to_string(),
+                    title: Some("Container".to_string()),
+                    rows: 12,
+                    cols: 40,
+                    show_border: true,
+                    ..base_defaults.clone()
+                },
+                data: ContainerWidgetData {
+                    direction: default_container_direction(),
+                    children: Vec::new(),
+                },
+            }),
+
+            "vitals" => Some(WindowDef::Vitals {
+                base: WindowBase {
+                    name: "vitals".to_string(),
+                    title: Some("Vitals".to_string()),
+                    rows: 6, // Four bars plus border
+                    cols: 30,
+                    show_border: true,
+                    min_rows: Some(3),
+                    ..base_defaults.clone()
+                },
+                data: VitalsWidgetData {
+                    children: default_vitals_children(),
+                },
+            }),
+
+            // Counts from main-window loot lines, not a game stream
+            "loot_tracker" => Some(WindowDef::LootTracker {
+                base: WindowBase {
+                    name: "loot_tracker".to_string(),
+                    title: Some("Loot".to_string()),
+                    rows: 16,
+                    cols: 30,
+                    show_border: true,
+                    ..base_defaults.clone()
+                },
+                data: LootTrackerWidgetData {
+                    gem_rows: default_loot_gem_rows(),
+                },
+            }),
+
+            // Experience, kills, deaths and silvers for this session
+            "session_stats" => Some(WindowDef::SessionStats {
+                base: WindowBase {
+                    name: "session_stats".to_string(),
+                    title: Some("Session".to_string()),
+                    rows: 8,
+                    cols: 30,
+                    show_border: true,
+                    ..base_defaults.clone()
+                },
+                data: SessionStatsWidgetData {},
+            }),
+
+            // Parsed bounty stream; the "bounty" text window shows it raw
+            "bounty_tracker" => Some(WindowDef::BountyTracker {
+                base: WindowBase {
+                    name: "bounty_tracker".to_string(),
+                    title: Some("Bounty".to_string()),
+                    rows: 7,
+                    cols: 34,
+                    show_border: true,
+                    ..base_defaults.clone()
+                },
+                data: BountyTrackerWidgetData {},
+            }),
+
+            // What's in each bag, from LOOK IN and put/get lines
+            "containers" => Some(WindowDef::ContainerTree {
+                base: WindowBase {
+                    name: "containers".to_string(),
+                    title: Some("Containers".to_string()),
+                    rows: 14,
+                    cols: 34,
+                    show_border: true,
+                    ..base_defaults.clone()
+                },
+                data: ContainerTreeWidgetData {},
+            }),
+
+            // Lines from a plugin's widget provider (set provider to its widget name)
+            "plugin" => Some(WindowDef::Plugin {
+                base: WindowBase {
+                    name: "plugin".to_string(),
+                    title: Some("Plugin".to_string()),
+                    rows: 10,
+                    cols: 34,
+                    show_border: true,
+                    ..base_defaults.clone()
+                },
+                data: PluginWidgetData { provider: None },
+            }),
+
+            // Friends and enemies from watchlist.toml seen on the logons stream
+            "watch_list" => Some(WindowDef::WatchList {
+                base: WindowBase {
+                    name: "watch_list".to_string(),
+                    title: Some("Watch List".to_string()),
+                    rows: 10,
+                    cols: 26,
+                    show_border: true,
+                    ..base_defaults.clone()
+                },
+                data: WatchListWidgetData {
+                    show_offline: false,
+                },
+            }),
+
+            // An image (portrait, area map) drawn with kitty/sixel graphics
+            "art" => Some(WindowDef::Art {
+                base: WindowBase {
+                    name: "art".to_string(),
+                    title: Some("Art".to_string()),
+                    rows: 12,
+                    cols: 30,
+                    show_border: true,
+                    ..base_defaults.clone()
+                },
+                data: ArtWidgetData {
+                    path: None,
+                    alt: None,
+                },
+            }),
+
+            // One row of game state fields laid out by a template
+            "status_line" => Some(WindowDef::StatusLine {
+                base: WindowBase {
+                    name: "status_line".to_string(),
+                    rows: 1,
+                    cols: 60,
+                    show_border: false,
+                    ..base_defaults.clone()
+                },
+                data: StatusLineWidgetData {
+                    template: default_status_line_template(),
+                },
+            }),
+
             "spacer" => Some(WindowDef::Spacer {
                 base: WindowBase {
                     name: String::new(), // Will be set by caller with auto-generated name
@@ -3405,18 +5671,21 @@ impl Config {
             "pbarStance",
             "mindState",
             "lblBPs",
+            "vitals",
             // Text windows
             "main",
             "thoughts",
             "speech",
             "announcements",
             "loot",
+            "afk",
             "death",
             "logons",
             "familiar",
             "ambients",
             "bounty",
             "society",
+            "spellhistory",
             // Special widgets
             "quickbar",
             // Countdowns
@@ -3439,6 +5708,15 @@ impl Config {
             "compass",
             "injuries",
             "spacer",
+            "container",
+            "loot_tracker",
+            "session_stats",
+            "bounty_tracker",
+            "containers",
+            "plugin",
+            "watch_list",
+            "art",
+            "status_line",
             // command_input is NOT in this list - it's always present and can't be added/removed
         ]
     }
@@ -3531,6 +5809,11 @@ impl Config {
         config.colors = ColorConfig::load(character)?;
         config.highlights = Self::load_highlights(character)?;
         config.keybinds = Self::load_keybinds(character)?;
+        config.keybind_modes = Self::load_keybind_modes(character)?;
+        config.saved_searches = Self::load_saved_searches(character)?;
+        config.snippets = Self::load_snippets(character)?;
+        config.sound_events = Self::load_sound_events(character)?;
+        config.watchlist = Self::load_watchlist(character)?;
 
         // Validate and auto-fix menu keybinds
         let validation = menu_keybind_validator::validate_menu_keybinds(&config.menu_keybinds);
@@ -3700,6 +5983,11 @@ impl Config {
         config.colors = ColorConfig::load(character)?;
         config.highlights = Self::load_highlights(character)?;
         config.keybinds = Self::load_keybinds(character)?;
+        config.keybind_modes = Self::load_keybind_modes(character)?;
+        config.saved_searches = Self::load_saved_searches(character)?;
+        config.snippets = Self::load_snippets(character)?;
+        config.sound_events = Self::load_sound_events(character)?;
+        config.watchlist = Self::load_watchlist(character)?;
 
         // Validate and auto-fix menu keybinds
         let validation = menu_keybind_validator::validate_menu_keybinds(&config.menu_keybinds);
@@ -3744,10 +6032,15 @@ impl Config {
         let contents = toml::to_string_pretty(self).context("Failed to serialize config")?;
         fs::write(&config_path, contents).context("Failed to write config file")?;
 
-        // Save to separate files
-        self.colors.save(char_name)?;
-        self.save_highlights(char_name)?;
-        self.save_keybinds(char_name)?;
+        // Save to separate files (hand-editable ones wait for review when confirm_config_writes is on)
+        if !self.ui.confirm_config_writes {
+            self.colors.save(char_name)?;
+            self.save_highlights(char_name)?;
+            self.save_keybinds(char_name)?;
+        }
+        self.save_saved_searches(char_name)?;
+        self.save_snippets(char_name)?;
+        self.save_watchlist(char_name)?;
 
         Ok(())
     }
@@ -3813,6 +6106,18 @@ impl Config {
         Ok(Self::config_dir()?.join("sounds"))
     }
 
+    /// Get the shared speech-to-text models directory
+    /// Returns: ~/.two-face/models/
+    pub fn models_dir() -> Result<PathBuf> {
+        Ok(Self::config_dir()?.join("models"))
+    }
+
+    /// Get the plugins directory (shared across characters)
+    /// Returns: ~/.two-face/plugins/
+    pub fn plugins_dir() -> Result<PathBuf> {
+        Ok(Self::config_dir()?.join("plugins"))
+    }
+
     /// Get path to debug log for a character
     /// Returns: ~/.two-face/{character}/debug.log
     pub fn get_log_path(character: Option<&str>) -> Result<PathBuf> {
@@ -3825,6 +6130,39 @@ impl Config {
         Ok(Self::profile_dir(character)?.join("history.txt"))
     }
 
+    /// Get the directory for spilled text window scrollback
+    /// Returns: ~/.two-face/{character}/scrollback/
+    pub fn scrollback_dir(character: Option<&str>) -> Result<PathBuf> {
+        Ok(Self::profile_dir(character)?.join("scrollback"))
+    }
+
+    /// Get the directory for .dump window exports
+    /// Returns: ~/.two-face/{character}/dumps/
+    pub fn dumps_dir(character: Option<&str>) -> Result<PathBuf> {
+        Ok(Self::profile_dir(character)?.join("dumps"))
+    }
+
+    /// Get path to the instance lock for a character
+    /// Returns: ~/.two-face/{character}/instance.lock, or per port for the default profile
+    pub fn instance_lock_path(character: Option<&str>, port: u16) -> Result<PathBuf> {
+        let file = match character {
+            Some(_) => "instance.lock".to_string(),
+            None => format!("instance-{}.lock", port),
+        };
+        Ok(Self::profile_dir(character)?.join(file))
+    }
+
+    /// Get path to the control socket `two-face send` connects to, inside an
+    /// owner-only directory of its own
+    /// Returns: ~/.two-face/{character}/control/control.sock, or per port for the default profile
+    pub fn control_socket_path(character: Option<&str>, port: u16) -> Result<PathBuf> {
+        let file = match character {
+            Some(_) => "control.sock".to_string(),
+            None => format!("control-{}.sock", port),
+        };
+        Ok(Self::profile_dir(character)?.join("control").join(file))
+    }
+
     /// Get path to widget_state.toml for a character
     /// Returns: ~/.two-face/{character}/widget_state.toml
     pub fn widget_state_path(character: Option<&str>) -> Result<PathBuf> {
@@ -3849,6 +6187,42 @@ impl Config {
         Ok(Self::profile_dir(character)?.join("keybinds.toml"))
     }
 
+    /// Get path to keybind_modes.toml for a character
+    /// Returns: ~/.two-face/{character}/keybind_modes.toml
+    pub fn keybind_modes_path(character: Option<&str>) -> Result<PathBuf> {
+        Ok(Self::profile_dir(character)?.join("keybind_modes.toml"))
+    }
+
+    /// Get path to sounds.toml (event sounds) for a character
+    /// Returns: ~/.two-face/{character}/sounds.toml
+    pub fn sound_events_path(character: Option<&str>) -> Result<PathBuf> {
+        Ok(Self::profile_dir(character)?.join("sounds.toml"))
+    }
+
+    /// Get path to searches.toml for a character
+    /// Returns: ~/.two-face/{character}/searches.toml
+    pub fn searches_path(character: Option<&str>) -> Result<PathBuf> {
+        Ok(Self::profile_dir(character)?.join("searches.toml"))
+    }
+
+    /// Get path to snippets.toml for a character
+    /// Returns: ~/.two-face/{character}/snippets.toml
+    pub fn snippets_path(character: Option<&str>) -> Result<PathBuf> {
+        Ok(Self::profile_dir(character)?.join("snippets.toml"))
+    }
+
+    /// Get path to sessions.toml (saved session statistics) for a character
+    /// Returns: ~/.two-face/{character}/sessions.toml
+    pub fn sessions_path(character: Option<&str>) -> Result<PathBuf> {
+        Ok(Self::profile_dir(character)?.join("sessions.toml"))
+    }
+
+    /// Get path to watchlist.toml for a character
+    /// Returns: ~/.two-face/{character}/watchlist.toml
+    pub fn watchlist_path(character: Option<&str>) -> Result<PathBuf> {
+        Ok(Self::profile_dir(character)?.join("watchlist.toml"))
+    }
+
     /// List all saved layouts
     pub fn list_layouts() -> Result<Vec<String>> {
         let layouts_dir = Self::config_dir()?.join("layouts");
@@ -4004,19 +6378,48 @@ impl Default for Config {
                 port: default_port(),
                 character: None,
             },
+            lich: LichLaunchConfig::default(),
+            on_connect: OnConnectConfig::default(),
             ui: UiConfig {
                 buffer_size: default_buffer_size(),
-                show_timestamps: false,
+                timestamps: TimestampConfig::default(),
                 layout: LayoutConfig::default(),
                 border_style: default_border_style(),
                 countdown_icon: default_countdown_icon(),
                 poll_timeout_ms: default_poll_timeout_ms(),
+                idle_poll_max_ms: default_idle_poll_max_ms(),
+                color_mode: default_color_mode(),
+                graphics: default_graphics(),
+                min_contrast: 0.0,
+                startup_layout: String::new(),
+                ask_startup_layout: true,
+                confirm_config_writes: true,
+                confirm_multiline_paste: true,
+                compose_line_delay_ms: 0,
+                command_echo: default_command_echo(),
+                command_echo_prompt: true,
+                command_echo_prefix: String::new(),
+                snap_grid: 0,
+                snap_to_windows: true,
+                compact_width: default_compact_width(),
+                compact_borders: default_compact_borders(),
+                compact_min_priority: default_compact_min_priority(),
                 startup_music: default_startup_music(),
                 startup_music_file: default_startup_music_file(),
                 selection_enabled: default_selection_enabled(),
                 selection_respect_window_boundaries: default_selection_respect_window_boundaries(),
                 drag_modifier_key: default_drag_modifier_key(),
                 min_command_length: default_min_command_length(),
+                history_max_entries: default_history_max_entries(),
+                history_denylist: Vec::new(),
+                chord_timeout_ms: default_chord_timeout_ms(),
+                text_fade_minutes: 0,
+                gap_separator_minutes: 0,
+                input_timer: true,
+                collapse_repeats: false,
+                more_prompt: false,
+                scrollback_disk_lines: 0,
+                keyboard_enhancement: true,
                 perf_stats_x: default_perf_stats_x(),
                 perf_stats_y: default_perf_stats_y(),
                 perf_stats_width: default_perf_stats_width(),
@@ -4024,11 +6427,30 @@ impl Default for Config {
             },
             highlights: HashMap::new(),     // Loaded from highlights.toml
             keybinds: HashMap::new(),       // Loaded from keybinds.toml
+            keybind_modes: HashMap::new(),  // Loaded from keybind_modes.toml
+            saved_searches: HashMap::new(), // Loaded from searches.toml
+            snippets: HashMap::new(),       // Loaded from snippets.toml
+            sound_events: HashMap::new(),   // Loaded from sounds.toml
+            watchlist: WatchListConfig::default(), // Loaded from watchlist.toml
             colors: ColorConfig::default(), // Loaded from colors.toml
             sound: SoundConfig::default(),
+            creatures: CreatureAlertConfig::default(),
             tts: TtsConfig::default(),
+            stt: SttConfig::default(),
+            spectator: SpectatorConfig::default(),
+            passthrough: PassthroughConfig::default(),
+            websocket: WebSocketConfig::default(),
+            relay: RelayConfig::default(),
+            push: PushConfig::default(),
+            afk: AfkConfig::default(),
+            screen_reader: ScreenReaderConfig::default(),
+            theme_schedule: ThemeScheduleConfig::default(),
+            gui: GuiConfig::default(),
             event_patterns: HashMap::new(), // Empty by default - user adds via config
             layout_mappings: Vec::new(),    // Empty by default - user adds via config
+            redirects: Vec::new(),
+            actions: HashMap::new(), // Custom actions defined in [actions]
+            injury_remedies: HashMap::new(), // Built-in herb chart unless overridden
             character: None,                // Set at runtime via load_with_options
             menu_keybinds: MenuKeybinds::default(),
             active_theme: default_theme_name(),
@@ -4170,6 +6592,9 @@ mod tests {
                 min_cols: None,
                 max_cols: None,
                 visible: true,
+                priority: None,
+                width: None,
+                height: None,
             },
             data: SpacerWidgetData {},
         };
@@ -4239,6 +6664,9 @@ visible = true
                 min_cols: None,
                 max_cols: None,
                 visible: true,
+                priority: None,
+                width: None,
+                height: None,
             },
             data: SpacerWidgetData {},
         };
@@ -4303,6 +6731,9 @@ visible = true
                 min_cols: None,
                 max_cols: None,
                 visible: true,
+                priority: None,
+                width: None,
+                height: None,
             },
             data: SpacerWidgetData {},
         };
@@ -4329,6 +6760,9 @@ visible = true
                 min_cols: None,
                 max_cols: None,
                 visible: true,
+                priority: None,
+                width: None,
+                height: None,
             },
             data: SpacerWidgetData {},
         };
@@ -4378,6 +6812,9 @@ visible = true
                 min_cols: None,
                 max_cols: None,
                 visible: true,
+                priority: None,
+                width: None,
+                height: None,
             },
             data: SpacerWidgetData {},
         };
@@ -4404,6 +6841,9 @@ visible = true
                 min_cols: None,
                 max_cols: None,
                 visible: false,  // Hidden!
+                priority: None,
+                width: None,
+                height: None,
             },
             data: SpacerWidgetData {},
         };
@@ -4456,10 +6896,18 @@ visible = true
                 min_cols: None,
                 max_cols: None,
                 visible: true,
+                priority: None,
+                width: None,
+                height: None,
             },
             data: TextWidgetData {
                 streams: vec!["main".to_string()],
                 buffer_size: 1000,
+                include: Vec::new(),
+                exclude: Vec::new(),
+                timestamps: None,
+                collapse_repeats: None,
+                gap_separator_minutes: None,
             },
         };
 
@@ -4485,6 +6933,9 @@ visible = true
                 min_cols: None,
                 max_cols: None,
                 visible: true,
+                priority: None,
+                width: None,
+                height: None,
             },
             data: SpacerWidgetData {},
         };
@@ -4511,10 +6962,18 @@ visible = true
                 min_cols: None,
                 max_cols: None,
                 visible: true,
+                priority: None,
+                width: None,
+                height: None,
             },
             data: TextWidgetData {
                 streams: vec!["main".to_string()],
                 buffer_size: 1000,
+                include: Vec::new(),
+                exclude: Vec::new(),
+                timestamps: None,
+                collapse_repeats: None,
+                gap_separator_minutes: None,
             },
         };
 
@@ -4563,10 +7022,18 @@ visible = true
                 min_cols: None,
                 max_cols: None,
                 visible: true,
+                priority: None,
+                width: None,
+                height: None,
             },
             data: TextWidgetData {
                 streams: vec!["main".to_string()],
                 buffer_size: 1000,
+                include: Vec::new(),
+                exclude: Vec::new(),
+                timestamps: None,
+                collapse_repeats: None,
+                gap_separator_minutes: None,
             },
         };
 
@@ -4592,6 +7059,9 @@ visible = true
                 min_cols: None,
                 max_cols: None,
                 visible: true,
+                priority: None,
+                width: None,
+                height: None,
             },
             data: SpacerWidgetData {},
         };
@@ -4618,10 +7088,18 @@ visible = true
                 min_cols: None,
                 max_cols: None,
                 visible: true,
+                priority: None,
+                width: None,
+                height: None,
             },
             data: TextWidgetData {
                 streams: vec!["main".to_string()],
                 buffer_size: 1000,
+                include: Vec::new(),
+                exclude: Vec::new(),
+                timestamps: None,
+                collapse_repeats: None,
+                gap_separator_minutes: None,
             },
         };
 
@@ -4683,10 +7161,18 @@ visible = true
                 min_cols: None,
                 max_cols: None,
                 visible: true,
+                priority: None,
+                width: None,
+                height: None,
             },
             data: TextWidgetData {
                 streams: vec!["main".to_string()],
                 buffer_size: 5000,
+                include: Vec::new(),
+                exclude: Vec::new(),
+                timestamps: None,
+                collapse_repeats: None,
+                gap_separator_minutes: None,
             },
         };
 
@@ -4712,6 +7198,9 @@ visible = true
                 min_cols: None,
                 max_cols: None,
                 visible: true,
+                priority: None,
+                width: None,
+                height: None,
             },
             data: SpacerWidgetData {},
         };
@@ -4738,10 +7227,18 @@ visible = true
                 min_cols: None,
                 max_cols: None,
                 visible: true,
+                priority: None,
+                width: None,
+                height: None,
             },
             data: TextWidgetData {
                 streams: vec!["status".to_string()],
                 buffer_size: 100,
+                include: Vec::new(),
+                exclude: Vec::new(),
+                timestamps: None,
+                collapse_repeats: None,
+                gap_separator_minutes: None,
             },
         };
 
@@ -4771,4 +7268,259 @@ visible = true
         assert!(a_end <= spacer_start, "A should not overlap spacer");
         assert!(spacer_end <= b_start, "Spacer should not overlap B");
     }
+
+    #[test]
+    fn test_highlight_window_scope() {
+        let toml_str = r##"
+pattern = "whispers"
+fg = "#ff00ff"
+windows = ["speech", "thoughts"]
+"##;
+        let pattern: HighlightPattern = toml::from_str(toml_str).unwrap();
+        assert_eq!(pattern.windows, vec!["speech", "thoughts"]);
+
+        // Matches by window name
+        assert!(pattern.applies_to_window("speech", &[]));
+        // Matches by stream routed into a differently named window
+        assert!(pattern.applies_to_window("chatter", &["thoughts".to_string()]));
+        // Out of scope
+        assert!(!pattern.applies_to_window("main", &["main".to_string()]));
+
+        // Unscoped patterns apply everywhere and don't serialize the field
+        let unscoped: HighlightPattern = toml::from_str("pattern = \"foo\"").unwrap();
+        assert!(unscoped.applies_to_window("main", &[]));
+        assert!(!toml::to_string(&unscoped).unwrap().contains("windows"));
+    }
+
+    #[test]
+    fn test_parse_key_sequence() {
+        // Single key is a one-step sequence
+        assert_eq!(
+            parse_key_sequence("ctrl+f"),
+            Some(vec![(KeyCode::Char('f'), KeyModifiers::CONTROL)])
+        );
+
+        // Whitespace and "then" both separate chord steps
+        let expected = vec![
+            (KeyCode::Char('x'), KeyModifiers::CONTROL),
+            (KeyCode::Char('h'), KeyModifiers::empty()),
+        ];
+        assert_eq!(parse_key_sequence("ctrl+x h"), Some(expected.clone()));
+        assert_eq!(parse_key_sequence("ctrl+x then h"), Some(expected));
+
+        // Any bad step invalidates the whole sequence
+        assert_eq!(parse_key_sequence("ctrl+x bogus"), None);
+        assert_eq!(parse_key_sequence("then"), None);
+
+        // Formatting round-trips through the parser
+        for key in ["ctrl+x", "f5", "alt+f4", "space", "num_7", "pageup"] {
+            let (code, mods) = parse_key_string(key).unwrap();
+            assert_eq!(format_key_for_keybind(code, mods), key);
+        }
+    }
+
+    #[test]
+    fn test_vitals_window_children() {
+        let layout: Layout = toml::from_str(
+            r##"
+            [[windows]]
+            widget_type = "vitals"
+            name = "vitals"
+            row = 0
+            col = 0
+            rows = 4
+            cols = 30
+            children = [
+              { id = "health", label = "HP" },
+              { id = "mindState", color = "#9370db" },
+            ]
+
+            [[windows]]
+            widget_type = "vitals"
+            name = "defaults"
+            row = 0
+            col = 0
+            rows = 6
+            cols = 30
+            "##,
+        )
+        .unwrap();
+
+        let WindowDef::Vitals { data, .. } = &layout.windows[0] else {
+            panic!("Expected WindowDef::Vitals variant");
+        };
+        assert_eq!(data.children.len(), 2);
+        assert_eq!(data.children[0].label.as_deref(), Some("HP"));
+        assert_eq!(data.children[1].id, "mindState");
+        assert_eq!(data.children[1].color.as_deref(), Some("#9370db"));
+
+        let WindowDef::Vitals { data, .. } = &layout.windows[1] else {
+            panic!("Expected WindowDef::Vitals variant");
+        };
+        assert_eq!(data.children.len(), 4);
+    }
+
+    #[test]
+    fn test_container_split_by_weight() {
+        assert_eq!(
+            ContainerWidgetData::split(&[1, 1, 2], 20),
+            vec![(0, 5), (5, 5), (10, 10)]
+        );
+        // Rounding leftovers go to the last child
+        assert_eq!(
+            ContainerWidgetData::split(&[1, 1, 1], 10),
+            vec![(0, 3), (3, 3), (6, 4)]
+        );
+        assert!(ContainerWidgetData::split(&[], 10).is_empty());
+    }
+
+    #[test]
+    fn test_set_container_rejects_cycles() {
+        let mut layout: Layout = toml::from_str(
+            r#"
+            [[windows]]
+            widget_type = "container"
+            name = "outer"
+            direction = "columns"
+            children = [{ name = "inner", weight = 2 }]
+
+            [[windows]]
+            widget_type = "container"
+            name = "inner"
+
+            [[windows]]
+            widget_type = "text"
+            name = "thoughts"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(layout.container_of("inner"), Some("outer"));
+        layout.set_container("thoughts", Some("inner")).unwrap();
+        assert_eq!(layout.container_of("thoughts"), Some("inner"));
+
+        // outer -> inner -> outer would loop forever
+        assert!(layout.set_container("outer", Some("inner")).is_err());
+        assert!(layout.set_container("inner", Some("inner")).is_err());
+        assert!(layout.set_container("inner", Some("thoughts")).is_err());
+
+        // Moving keeps the weight; None takes the window out
+        layout.set_container("inner", None).unwrap();
+        assert_eq!(layout.container_of("inner"), None);
+        layout.set_container("inner", Some("outer")).unwrap();
+        let Some(WindowDef::Container { data, .. }) = layout.get_window("outer") else {
+            panic!("Expected WindowDef::Container variant");
+        };
+        assert_eq!(data.children[0].weight, 1);
+    }
+
+    #[test]
+    fn test_keybind_conflicts() {
+        let mut config = Config::default();
+        config.keybinds.insert(
+            "f5".to_string(),
+            KeyBindAction::Action("start_search".to_string()),
+        );
+        config.keybinds.insert(
+            "ctrl+x h".to_string(),
+            KeyBindAction::Action("highlights".to_string()),
+        );
+
+        // Exact duplicate (and editing the same bind is fine)
+        let conflicts = config.keybind_conflicts("f5", None);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].existing.as_deref(), Some("f5"));
+        assert!(config.keybind_conflicts("f5", Some("f5")).is_empty());
+
+        // Chord prefix shadowing, either direction
+        assert_eq!(
+            config.keybind_conflicts("ctrl+x", None)[0]
+                .existing
+                .as_deref(),
+            Some("ctrl+x h")
+        );
+        assert_eq!(
+            config.keybind_conflicts("f5 a", None)[0]
+                .existing
+                .as_deref(),
+            Some("f5")
+        );
+
+        // Global and command-input keys have no existing bind to remove
+        let conflicts = config.keybind_conflicts("ctrl+C", None);
+        assert_eq!(conflicts.len(), 1);
+        assert!(conflicts[0].existing.is_none());
+        assert_eq!(config.keybind_conflicts("ctrl+w", None).len(), 1);
+        assert_eq!(config.keybind_conflicts("q", None).len(), 1);
+
+        assert!(config.keybind_conflicts("alt+f9", None).is_empty());
+    }
+
+    #[test]
+    fn test_gui_font_config() {
+        let fonts: GuiFontConfig = toml::from_str(
+            r#"
+            text_family = "JetBrains Mono"
+            text_size = 13.5
+            fallback = ["Symbols Nerd Font Mono", "JetBrains Mono", " "]
+            window_sizes = { main = 16.0, thoughts = 200.0 }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(fonts.text_size_for("main"), 16.0);
+        assert_eq!(fonts.text_size_for("thoughts"), GUI_FONT_SIZE_RANGE.1);
+        assert_eq!(fonts.text_size_for("room"), 13.5);
+        assert_eq!(
+            fonts.text_chain(),
+            vec!["JetBrains Mono", "Symbols Nerd Font Mono"]
+        );
+        assert_eq!(
+            fonts.ui_chain(),
+            vec!["Symbols Nerd Font Mono", "JetBrains Mono"]
+        );
+        assert_eq!(fonts.ui_size(), 14.0);
+
+        // Old configs without a [gui] section still load
+        let config: Config = toml::from_str(&toml::to_string(&Config::default()).unwrap()).unwrap();
+        assert!(config.gui.fonts.window_sizes.is_empty());
+    }
+
+    #[test]
+    fn test_timestamp_config() {
+        // The old boolean still turns timestamps on
+        let ui: UiConfig = toml::from_str("show_timestamps = true").unwrap();
+        assert!(ui.timestamps.enabled);
+        assert_eq!(ui.timestamps.format, TimestampConfig::default().format);
+
+        let ui: UiConfig =
+            toml::from_str("[timestamps]\nenabled = true\nformat = \"%H:%M\"\nrelative = true")
+                .unwrap();
+        assert_eq!(ui.timestamps.format, "%H:%M");
+        assert!(ui.timestamps.relative);
+
+        let over = TimestampOverride {
+            enabled: Some(false),
+            position: Some("start".to_string()),
+            ..Default::default()
+        };
+        let merged = ui.timestamps.with_override(Some(&over));
+        assert!(!merged.enabled && merged.relative);
+        assert_eq!(
+            (merged.format.as_str(), merged.position.as_str()),
+            ("%H:%M", "start")
+        );
+
+        assert!(TimestampConfig::format_is_valid("%Y-%m-%d %H:%M"));
+        assert!(!TimestampConfig::format_is_valid("%Q"));
+    }
+
+    #[test]
+    fn test_gap_separator_minutes_default_to_main_only() {
+        let mut ui = Config::default().ui;
+        ui.gap_separator_minutes = 10;
+        assert_eq!(ui.gap_minutes_for("main", None), 10);
+        assert_eq!(ui.gap_minutes_for("thoughts", None), 0);
+        assert_eq!(ui.gap_minutes_for("thoughts", Some(30)), 30);
+        assert_eq!(ui.gap_minutes_for("main", Some(0)), 0);
+    }
 }
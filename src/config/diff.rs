@@ -0,0 +1,342 @@
+//! Review UI edits to hand-maintained config files before they hit disk
+//!
+//! Highlights, keybinds and colors are often edited by hand, and writing them
+//! back re-serializes the whole file (comments and ordering are lost). With
+//! `ui.confirm_config_writes` on, those files are only written from the review
+//! view. It lists each entry that differs from the file on disk and lets the
+//! user discard individual changes before saving.
+//!
+//! Both sides are compared after a round trip through the typed structs, so
+//! defaults the file leaves out don't show up as changes.
+
+use super::{ColorConfig, Config};
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// A config file the review covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFile {
+    Highlights,
+    Keybinds,
+    Colors,
+}
+
+impl ConfigFile {
+    pub const ALL: [ConfigFile; 3] = [Self::Highlights, Self::Keybinds, Self::Colors];
+
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            Self::Highlights => "highlights.toml",
+            Self::Keybinds => "keybinds.toml",
+            Self::Colors => "colors.toml",
+        }
+    }
+
+    /// Entries nested this deep are compared one by one (colors.toml compares
+    /// `presets.speech`, `ui.border_color`, ...; the others compare whole entries)
+    fn depth(&self) -> usize {
+        match self {
+            Self::Colors => 1,
+            _ => 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Added,
+    Modified,
+    Removed,
+}
+
+impl ChangeKind {
+    pub fn symbol(&self) -> char {
+        match self {
+            Self::Added => '+',
+            Self::Modified => '~',
+            Self::Removed => '-',
+        }
+    }
+}
+
+/// One entry that differs between memory and disk
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigChange {
+    pub file: ConfigFile,
+    /// Path of the entry (["speech"], ["ui", "border_color"])
+    pub path: Vec<String>,
+    pub kind: ChangeKind,
+    before: Option<toml::Value>,
+    after: Option<toml::Value>,
+}
+
+impl ConfigChange {
+    /// Dotted name for display ("ui.border_color")
+    pub fn key(&self) -> String {
+        self.path.join(".")
+    }
+
+    /// Unified-style diff of the entry: ('-', line), ('+', line), (' ', line)
+    pub fn diff_lines(&self) -> Vec<(char, String)> {
+        let render = |value: &Option<toml::Value>| -> Vec<String> {
+            let Some(value) = value else {
+                return Vec::new();
+            };
+            let mut table = toml::Table::new();
+            insert_path(&mut table, &self.path, value.clone());
+            toml::to_string_pretty(&table)
+                .unwrap_or_default()
+                .lines()
+                .map(str::to_string)
+                .collect()
+        };
+        line_diff(&render(&self.before), &render(&self.after))
+    }
+}
+
+/// Every entry in the three files that would change on save
+pub fn pending_changes(config: &Config) -> Result<Vec<ConfigChange>> {
+    let mut changes = Vec::new();
+    for file in ConfigFile::ALL {
+        let (disk, memory) = tables(config, file)?;
+        collect_changes(file, &[], &disk, &memory, file.depth(), &mut changes);
+    }
+    Ok(changes)
+}
+
+/// Undo one change in memory (the file on disk is untouched)
+pub fn discard(config: &mut Config, change: &ConfigChange) -> Result<()> {
+    let (_, mut memory) = tables(config, change.file)?;
+    match &change.before {
+        Some(value) => insert_path(&mut memory, &change.path, value.clone()),
+        None => remove_path(&mut memory, &change.path),
+    }
+
+    match change.file {
+        ConfigFile::Highlights => {
+            config.highlights = from_table(memory)?;
+            Config::compile_highlight_patterns(&mut config.highlights);
+        }
+        ConfigFile::Keybinds => config.keybinds = from_table(memory)?,
+        ConfigFile::Colors => config.colors = from_table(memory)?,
+    }
+    Ok(())
+}
+
+/// Write the reviewed files (only the ones that actually differ)
+pub fn save_reviewed(config: &Config) -> Result<Vec<ConfigFile>> {
+    let character = config.character.as_deref();
+    let changed: Vec<ConfigFile> = ConfigFile::ALL
+        .into_iter()
+        .filter(|&file| {
+            tables(config, file)
+                .map(|(disk, memory)| disk != memory)
+                .unwrap_or(true)
+        })
+        .collect();
+
+    for file in &changed {
+        match file {
+            ConfigFile::Highlights => config.save_highlights(character)?,
+            ConfigFile::Keybinds => config.save_keybinds(character)?,
+            ConfigFile::Colors => config.colors.save(character)?,
+        }
+    }
+    Ok(changed)
+}
+
+/// (disk, memory) versions of one file as normalized TOML tables
+fn tables(config: &Config, file: ConfigFile) -> Result<(toml::Table, toml::Table)> {
+    let character = config.character.as_deref();
+    Ok(match file {
+        ConfigFile::Highlights => (
+            to_table(&Config::load_highlights(character)?)?,
+            to_table(&config.highlights)?,
+        ),
+        ConfigFile::Keybinds => (
+            to_table(&Config::load_keybinds(character)?)?,
+            to_table(&config.keybinds)?,
+        ),
+        ConfigFile::Colors => (
+            to_table(&ColorConfig::load(character)?)?,
+            to_table(&config.colors)?,
+        ),
+    })
+}
+
+fn to_table<T: Serialize>(value: &T) -> Result<toml::Table> {
+    toml::Table::try_from(value).context("Failed to serialize config for comparison")
+}
+
+fn from_table<T: DeserializeOwned>(table: toml::Table) -> Result<T> {
+    toml::Value::Table(table)
+        .try_into()
+        .context("Failed to rebuild config after discarding a change")
+}
+
+fn collect_changes(
+    file: ConfigFile,
+    prefix: &[String],
+    disk: &toml::Table,
+    memory: &toml::Table,
+    depth: usize,
+    out: &mut Vec<ConfigChange>,
+) {
+    let mut keys: Vec<&String> = disk.keys().chain(memory.keys()).collect();
+    keys.sort();
+    keys.dedup();
+
+    for key in keys {
+        let mut path = prefix.to_vec();
+        path.push(key.clone());
+        let (before, after) = (disk.get(key), memory.get(key));
+        if before == after {
+            continue;
+        }
+        if depth > 0 {
+            if let (Some(toml::Value::Table(b)), Some(toml::Value::Table(a))) = (before, after) {
+                collect_changes(file, &path, b, a, depth - 1, out);
+                continue;
+            }
+        }
+        let kind = match (before, after) {
+            (None, _) => ChangeKind::Added,
+            (_, None) => ChangeKind::Removed,
+            _ => ChangeKind::Modified,
+        };
+        out.push(ConfigChange {
+            file,
+            path,
+            kind,
+            before: before.cloned(),
+            after: after.cloned(),
+        });
+    }
+}
+
+fn insert_path(table: &mut toml::Table, path: &[String], value: toml::Value) {
+    match path {
+        [] => {}
+        [key] => {
+            table.insert(key.clone(), value);
+        }
+        [head, rest @ ..] => {
+            let child = table
+                .entry(head.clone())
+                .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+            if let toml::Value::Table(child) = child {
+                insert_path(child, rest, value);
+            }
+        }
+    }
+}
+
+fn remove_path(table: &mut toml::Table, path: &[String]) {
+    match path {
+        [] => {}
+        [key] => {
+            table.remove(key);
+        }
+        [head, rest @ ..] => {
+            if let Some(toml::Value::Table(child)) = table.get_mut(head) {
+                remove_path(child, rest);
+            }
+        }
+    }
+}
+
+/// Line diff via longest common subsequence (entries are only a few lines)
+fn line_diff(before: &[String], after: &[String]) -> Vec<(char, String)> {
+    let (n, m) = (before.len(), after.len());
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if before[i] == after[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut out = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && before[i] == after[j] {
+            out.push((' ', before[i].clone()));
+            i += 1;
+            j += 1;
+        } else if j < m && (i == n || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            out.push(('+', after[j].clone()));
+            j += 1;
+        } else {
+            out.push(('-', before[i].clone()));
+            i += 1;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(source: &str) -> toml::Table {
+        source.parse().unwrap()
+    }
+
+    const DISK: &str = "[presets.speech]\nfg = \"#00ff00\"\n[presets.whisper]\nfg = \"#ffffff\"\n\
+        [ui]\nborder_color = \"#333333\"\n";
+    const MEMORY: &str =
+        "[presets.speech]\nfg = \"#00ff00\"\n[presets.thought]\nfg = \"#ff00ff\"\n\
+        [ui]\nborder_color = \"#444444\"\n";
+
+    fn changes() -> Vec<ConfigChange> {
+        let mut changes = Vec::new();
+        collect_changes(
+            ConfigFile::Colors,
+            &[],
+            &table(DISK),
+            &table(MEMORY),
+            1,
+            &mut changes,
+        );
+        changes
+    }
+
+    #[test]
+    fn test_config_changes() {
+        let changes = changes();
+        let summary: Vec<(ChangeKind, String)> =
+            changes.iter().map(|c| (c.kind, c.key())).collect();
+        assert_eq!(
+            summary,
+            vec![
+                (ChangeKind::Added, "presets.thought".to_string()),
+                (ChangeKind::Removed, "presets.whisper".to_string()),
+                (ChangeKind::Modified, "ui.border_color".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_change_diff_lines() {
+        let diff = changes()[2].diff_lines();
+        assert!(diff.contains(&('-', "border_color = \"#333333\"".to_string())));
+        assert!(diff.contains(&('+', "border_color = \"#444444\"".to_string())));
+        assert!(diff.contains(&(' ', "[ui]".to_string())));
+    }
+
+    #[test]
+    fn test_discarding_changes_restores_disk_values() {
+        let mut reverted = table(MEMORY);
+        for change in &changes() {
+            match &change.before {
+                Some(value) => insert_path(&mut reverted, &change.path, value.clone()),
+                None => remove_path(&mut reverted, &change.path),
+            }
+        }
+        assert_eq!(reverted, table(DISK));
+    }
+}
@@ -0,0 +1,405 @@
+//! Keybind importer for Wizard FE and StormFront macro files
+//!
+//! Wizard `.key` files are line based (`KEY=MACRO`); StormFront exports its
+//! macros as XML (`<k key="..." action="..."/>`). Both are converted into
+//! `keybinds.toml` entries: key names become two-face key strings ("ctrl+f1",
+//! "num_8") and macro text becomes `MacroAction` text, where a real carriage
+//! return sends the line. The classic `\p` (pause), `\?` (wait for input),
+//! `\x` (clear input) and `@` (cursor) tokens are kept verbatim.
+
+use crate::config::{parse_key_string, KeyBindAction, MacroAction};
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Source front-end of a macro file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    Wizard,
+    StormFront,
+}
+
+impl ImportFormat {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "wizard" | "wiz" | "key" => Some(Self::Wizard),
+            "stormfront" | "sf" | "xml" => Some(Self::StormFront),
+            _ => None,
+        }
+    }
+
+    /// Guess the format from file contents (StormFront files are XML)
+    pub fn detect(contents: &str) -> Self {
+        let trimmed = contents.trim_start();
+        if trimmed.starts_with('<') || contents.contains("<k ") || contents.contains("<macro ") {
+            Self::StormFront
+        } else {
+            Self::Wizard
+        }
+    }
+}
+
+/// Result of converting one macro file
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    /// Converted entries, keyed by two-face key string
+    pub keybinds: HashMap<String, KeyBindAction>,
+    /// Entries that could not be converted: (source line, reason)
+    pub skipped: Vec<(String, String)>,
+}
+
+impl ImportReport {
+    /// Merge the converted entries into an existing keybind table
+    ///
+    /// Existing bindings win unless `overwrite` is set. Returns the number of
+    /// entries applied and the keys that were left alone.
+    pub fn merge_into(
+        self,
+        keybinds: &mut HashMap<String, KeyBindAction>,
+        overwrite: bool,
+    ) -> (usize, Vec<String>) {
+        let mut applied = 0;
+        let mut kept = Vec::new();
+        for (key, action) in self.keybinds {
+            if !overwrite && keybinds.contains_key(&key) {
+                kept.push(key);
+                continue;
+            }
+            keybinds.insert(key, action);
+            applied += 1;
+        }
+        kept.sort();
+        (applied, kept)
+    }
+}
+
+/// Read and convert a macro file, detecting the format when not given
+///
+/// `.xml` files are treated as StormFront and `.key` files as Wizard; anything
+/// else is detected from its contents.
+pub fn import_file(path: &Path, format: Option<ImportFormat>) -> Result<ImportReport> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read macro file {}", path.display()))?;
+    let format = format.unwrap_or_else(|| {
+        match path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase())
+        {
+            Some(ext) if ext == "xml" => ImportFormat::StormFront,
+            Some(ext) if ext == "key" => ImportFormat::Wizard,
+            _ => ImportFormat::detect(&contents),
+        }
+    });
+    Ok(import_macros(&contents, format))
+}
+
+/// Convert the contents of a Wizard or StormFront macro file
+pub fn import_macros(contents: &str, format: ImportFormat) -> ImportReport {
+    let entries = match format {
+        ImportFormat::Wizard => parse_wizard(contents),
+        ImportFormat::StormFront => parse_stormfront(contents),
+    };
+
+    let mut report = ImportReport::default();
+    for (source, key, text) in entries {
+        let Some(key_string) = convert_key_name(&key) else {
+            report
+                .skipped
+                .push((source, format!("unsupported key '{}'", key)));
+            continue;
+        };
+        let macro_text = convert_macro_text(&text);
+        if macro_text.trim_end_matches(['\r', '\n']).trim().is_empty() {
+            report.skipped.push((source, "empty macro".to_string()));
+            continue;
+        }
+        report
+            .keybinds
+            .insert(key_string, KeyBindAction::Macro(MacroAction { macro_text }));
+    }
+    report
+}
+
+/// Wizard `.key` lines: `KEY=MACRO` (a tab also separates), `#`/`;` comments
+fn parse_wizard(contents: &str) -> Vec<(String, String, String)> {
+    let mut entries = Vec::new();
+    for line in contents.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty()
+            || trimmed.starts_with('#')
+            || trimmed.starts_with(';')
+            || trimmed.starts_with("//")
+            || trimmed.starts_with('[')
+        {
+            continue;
+        }
+        let split = trimmed.split_once('=').or_else(|| trimmed.split_once('\t'));
+        if let Some((key, text)) = split {
+            let text = text.trim();
+            let text = text
+                .strip_prefix('"')
+                .and_then(|t| t.strip_suffix('"'))
+                .unwrap_or(text);
+            entries.push((
+                trimmed.to_string(),
+                key.trim().to_string(),
+                text.to_string(),
+            ));
+        }
+    }
+    entries
+}
+
+/// StormFront XML: any element carrying `key` plus `action`/`macro`/`value`
+fn parse_stormfront(contents: &str) -> Vec<(String, String, String)> {
+    let element_re = regex::Regex::new(r"<\s*[A-Za-z]+\b([^>]*)>").unwrap();
+    let attr_re = regex::Regex::new(r#"([A-Za-z_]+)\s*=\s*(?:"([^"]*)"|'([^']*)')"#).unwrap();
+
+    let mut entries = Vec::new();
+    for element in element_re.captures_iter(contents) {
+        let mut key = None;
+        let mut text = None;
+        for attr in attr_re.captures_iter(&element[1]) {
+            let value = attr
+                .get(2)
+                .or_else(|| attr.get(3))
+                .map_or("", |m| m.as_str());
+            match attr[1].to_lowercase().as_str() {
+                "key" => key = Some(decode_xml_entities(value)),
+                "action" | "macro" | "value" => text = Some(decode_xml_entities(value)),
+                _ => {}
+            }
+        }
+        if let (Some(key), Some(text)) = (key, text) {
+            entries.push((element[0].to_string(), key, text));
+        }
+    }
+    entries
+}
+
+pub(crate) fn decode_xml_entities(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Convert a Wizard/StormFront key name ("Ctrl-F1", "NumPad8", "Alt+PgUp")
+/// into a two-face key string, or None if two-face cannot bind it
+pub fn convert_key_name(name: &str) -> Option<String> {
+    let name = name.trim();
+    let mut modifiers = Vec::new();
+    let mut rest = name;
+
+    // Peel off leading modifiers; "-" and "+" both act as separators
+    loop {
+        let lower = rest.to_lowercase();
+        let found = [
+            ("control", "ctrl"),
+            ("ctrl", "ctrl"),
+            ("ctl", "ctrl"),
+            ("alt", "alt"),
+            ("meta", "alt"),
+            ("shift", "shift"),
+        ]
+        .iter()
+        .find_map(|(prefix, modifier)| {
+            let after = lower.strip_prefix(prefix)?;
+            let sep = after
+                .chars()
+                .next()
+                .filter(|c| matches!(c, '-' | '+' | ' '))?;
+            // A bare separator ("Ctrl+-") is the key itself, not a separator
+            (after.len() > 1).then_some((prefix.len() + sep.len_utf8(), *modifier))
+        });
+        match found {
+            Some((consumed, modifier)) => {
+                if !modifiers.contains(&modifier) {
+                    modifiers.push(modifier);
+                }
+                rest = &rest[consumed..];
+            }
+            None => break,
+        }
+    }
+
+    let key = convert_base_key(rest.trim())?;
+    // Same modifier order as the shipped keybinds.toml ("ctrl+alt+left")
+    let mut out = String::new();
+    for modifier in ["ctrl", "alt", "shift"] {
+        if modifiers.contains(&modifier) {
+            out.push_str(modifier);
+            out.push('+');
+        }
+    }
+    out.push_str(&key);
+
+    parse_key_string(&out).map(|_| out)
+}
+
+fn convert_base_key(key: &str) -> Option<String> {
+    let lower = key.to_lowercase().replace(' ', "");
+
+    for prefix in ["numpad", "keypad", "num", "kp"] {
+        if let Some(pad) = lower.strip_prefix(prefix) {
+            let pad = pad.trim_start_matches(['_', '-']);
+            let mapped = match pad {
+                "add" | "plus" | "+" => "+",
+                "subtract" | "minus" | "-" => "-",
+                "multiply" | "*" => "*",
+                "divide" | "/" => "/",
+                "decimal" | "period" | "." | "del" => ".",
+                "enter" => return Some("enter".to_string()),
+                d if d.len() == 1 && d.chars().all(|c| c.is_ascii_digit()) => d,
+                _ => continue,
+            };
+            return Some(format!("num_{}", mapped));
+        }
+    }
+
+    let named = match lower.as_str() {
+        "return" | "enter" => "enter",
+        "esc" | "escape" => "esc",
+        "pgup" | "pageup" | "prior" => "page_up",
+        "pgdn" | "pagedown" | "next" => "page_down",
+        "ins" | "insert" => "insert",
+        "del" | "delete" => "delete",
+        "bksp" | "backspace" | "back" => "backspace",
+        "home" => "home",
+        "end" => "end",
+        "tab" => "tab",
+        "space" | "spacebar" => "space",
+        "up" | "uparrow" => "up",
+        "down" | "downarrow" => "down",
+        "left" | "leftarrow" => "left",
+        "right" | "rightarrow" => "right",
+        // Bare operators on the keypad (Wizard writes "Add", "Subtract")
+        "add" => "num_+",
+        "subtract" => "num_-",
+        "multiply" => "num_*",
+        "divide" => "num_/",
+        "decimal" => "num_.",
+        _ => "",
+    };
+    if !named.is_empty() {
+        return Some(named.to_string());
+    }
+
+    if let Some(n) = lower.strip_prefix('f') {
+        if n.parse::<u8>().is_ok_and(|n| (1..=12).contains(&n)) {
+            return Some(lower);
+        }
+    }
+
+    let mut chars = lower.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c.to_string()),
+        _ => None,
+    }
+}
+
+/// Convert macro text into `MacroAction` text
+///
+/// `\r` (and `\n`) become a real carriage return, which sends the line.
+/// `\p`, `\?`, `\x` and `@` are normalised to lowercase tokens and kept, and
+/// `\\` and `\@` stay escaped so a literal backslash or `@` survives.
+pub fn convert_macro_text(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('r') | Some('R') | Some('n') | Some('N') => out.push('\r'),
+            Some('p') | Some('P') => out.push_str("\\p"),
+            Some('?') => out.push_str("\\?"),
+            Some('x') | Some('X') => out.push_str("\\x"),
+            Some('\\') => out.push_str("\\\\"),
+            Some('@') => out.push_str("\\@"),
+            Some(other) => {
+                out.push('\\');
+                out.push(other);
+            }
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn macro_text(report: &ImportReport, key: &str) -> String {
+        match report.keybinds.get(key) {
+            Some(KeyBindAction::Macro(m)) => m.macro_text.clone(),
+            other => panic!("expected macro for {}, got {:?}", key, other),
+        }
+    }
+
+    #[test]
+    fn test_convert_key_names() {
+        assert_eq!(convert_key_name("Ctrl-F1").as_deref(), Some("ctrl+f1"));
+        assert_eq!(convert_key_name("NumPad8").as_deref(), Some("num_8"));
+        assert_eq!(convert_key_name("Keypad Add").as_deref(), Some("num_+"));
+        assert_eq!(
+            convert_key_name("Alt+Shift+PgUp").as_deref(),
+            Some("alt+shift+page_up")
+        );
+        assert_eq!(convert_key_name("Ctrl-A").as_deref(), Some("ctrl+a"));
+        assert_eq!(convert_key_name("Mouse4"), None);
+    }
+
+    #[test]
+    fn test_convert_macro_text() {
+        assert_eq!(convert_macro_text(r"sw\r"), "sw\r");
+        assert_eq!(
+            convert_macro_text(r"stance off\rattack\P\r"),
+            "stance off\rattack\\p\r"
+        );
+        assert_eq!(convert_macro_text(r"prep 101\?\r"), "prep 101\\?\r");
+        assert_eq!(
+            convert_macro_text(r"\xget @ from pack"),
+            "\\xget @ from pack"
+        );
+    }
+
+    const STORMFRONT: &str = r#"<macros><k key="Alt-S" action="search\r" /><k action="look &amp; listen\r" key="F2"/></macros>"#;
+
+    #[test]
+    fn test_import_wizard_macros() {
+        let wizard =
+            "# Wizard macros\nNumPad8=north\\r\nCtrl-F1=\"stance offensive\\r\"\nWeird=oops\\r\n";
+        assert_eq!(ImportFormat::detect(wizard), ImportFormat::Wizard);
+        let report = import_macros(wizard, ImportFormat::Wizard);
+        assert_eq!(macro_text(&report, "num_8"), "north\r");
+        assert_eq!(macro_text(&report, "ctrl+f1"), "stance offensive\r");
+        assert_eq!(report.skipped.len(), 1);
+    }
+
+    #[test]
+    fn test_import_stormfront_macros() {
+        assert_eq!(ImportFormat::detect(STORMFRONT), ImportFormat::StormFront);
+        let report = import_macros(STORMFRONT, ImportFormat::StormFront);
+        assert_eq!(macro_text(&report, "alt+s"), "search\r");
+        assert_eq!(macro_text(&report, "f2"), "look & listen\r");
+    }
+
+    #[test]
+    fn test_merge_keeps_existing_keybinds_without_overwrite() {
+        let report = import_macros(STORMFRONT, ImportFormat::StormFront);
+        let mut existing = HashMap::new();
+        existing.insert(
+            "f2".to_string(),
+            KeyBindAction::Action("start_search".to_string()),
+        );
+        let (applied, kept) = report.merge_into(&mut existing, false);
+        assert_eq!((applied, kept), (1, vec!["f2".to_string()]));
+        assert!(matches!(existing.get("f2"), Some(KeyBindAction::Action(_))));
+    }
+}
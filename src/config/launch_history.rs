@@ -0,0 +1,198 @@
+//! Which profiles have been played, when, and how
+//!
+//! `launches.toml` in the two-face directory keeps one entry per profile:
+//! when it was last launched, on which port, with which frontend, and the
+//! account and world for direct connections. The startup profile picker
+//! lists profiles from it and relaunches a pick with the same settings.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+const FILE: &str = "launches.toml";
+
+/// How a profile was last launched
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LaunchRecord {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_played: Option<String>, // RFC 3339
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frontend: Option<String>, // "tui" or "gui"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub direct_account: Option<String>, // Set for direct (no Lich) connections
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub direct_game: Option<String>, // "prime", "platinum" or "shattered"
+}
+
+impl LaunchRecord {
+    /// "Lich :8001" or "Prime (direct)"
+    pub fn connection_label(&self) -> String {
+        match (&self.direct_account, &self.direct_game) {
+            (Some(_), Some(game)) => {
+                let mut game = game.clone();
+                if let Some(first) = game.get_mut(..1) {
+                    first.make_ascii_uppercase();
+                }
+                format!("{} (direct)", game)
+            }
+            (Some(_), None) => "direct".to_string(),
+            _ => match self.port {
+                Some(port) => format!("Lich :{}", port),
+                None => "Lich".to_string(),
+            },
+        }
+    }
+
+    /// "today", "yesterday", "5 days ago" or the date; "never" if unknown
+    pub fn last_played_label(&self, now: chrono::DateTime<chrono::Local>) -> String {
+        let Some(played) = self
+            .last_played
+            .as_deref()
+            .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+        else {
+            return "never".to_string();
+        };
+        let played = played.with_timezone(&chrono::Local);
+        match (now.date_naive() - played.date_naive()).num_days() {
+            i64::MIN..=0 => "today".to_string(),
+            1 => "yesterday".to_string(),
+            days @ 2..=13 => format!("{} days ago", days),
+            _ => played.format("%Y-%m-%d").to_string(),
+        }
+    }
+}
+
+/// A profile directory and how it was last launched
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProfileSummary {
+    pub name: String,
+    pub record: LaunchRecord,
+}
+
+/// Launch records by profile name; empty if there are none yet
+pub fn load(base: &Path) -> HashMap<String, LaunchRecord> {
+    let path = base.join(FILE);
+    let Ok(contents) = std::fs::read_to_string(&path) else {
+        return HashMap::new();
+    };
+    toml::from_str(&contents).unwrap_or_else(|e| {
+        tracing::warn!("Ignoring unreadable {}: {}", path.display(), e);
+        HashMap::new()
+    })
+}
+
+/// Remember that `profile` was just launched this way
+pub fn record(base: &Path, profile: &str, mut launch: LaunchRecord) -> Result<()> {
+    launch.last_played = Some(chrono::Utc::now().to_rfc3339());
+    let mut records = load(base);
+    records.insert(profile.to_string(), launch);
+    let path = base.join(FILE);
+    let contents =
+        toml::to_string_pretty(&records).context("Failed to serialize launch history")?;
+    std::fs::write(&path, contents).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Every profile (directory with a config.toml), most recently played first
+pub fn list_profiles(base: &Path, records: &HashMap<String, LaunchRecord>) -> Vec<ProfileSummary> {
+    let mut profiles: Vec<ProfileSummary> = std::fs::read_dir(base)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| e.path().join("config.toml").is_file())
+                .filter_map(|e| e.file_name().to_str().map(str::to_string))
+                .map(|name| ProfileSummary {
+                    record: records.get(&name).cloned().unwrap_or_default(),
+                    name,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    // RFC 3339 UTC timestamps sort chronologically as strings
+    profiles.sort_by(|a, b| {
+        b.record
+            .last_played
+            .cmp(&a.record.last_played)
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+    profiles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    /// Profiles Ann, bob and default, plus a layouts dir without a config
+    fn profiles_dir(name: &str) -> std::path::PathBuf {
+        let base =
+            std::env::temp_dir().join(format!("two-face-launches-{}-{}", name, std::process::id()));
+        for profile in ["Ann", "bob", "default", "layouts"] {
+            std::fs::create_dir_all(base.join(profile)).unwrap();
+        }
+        for profile in ["Ann", "bob", "default"] {
+            std::fs::write(base.join(profile).join("config.toml"), "").unwrap();
+        }
+        base
+    }
+
+    fn names(base: &Path) -> Vec<String> {
+        list_profiles(base, &load(base))
+            .into_iter()
+            .map(|p| p.name)
+            .collect()
+    }
+
+    #[test]
+    fn test_list_profiles_needs_config() {
+        let base = profiles_dir("list");
+        assert_eq!(names(&base), vec!["Ann", "bob", "default"]);
+        std::fs::remove_dir_all(&base).ok();
+    }
+
+    #[test]
+    fn test_recorded_launch_sorts_first() {
+        let base = profiles_dir("record");
+        record(
+            &base,
+            "bob",
+            LaunchRecord {
+                port: Some(8001),
+                frontend: Some("tui".to_string()),
+                ..LaunchRecord::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(names(&base), vec!["bob", "Ann", "default"]);
+        let bob = &load(&base)["bob"];
+        assert_eq!(bob.port, Some(8001));
+        assert_eq!(bob.connection_label(), "Lich :8001");
+        assert_eq!(bob.last_played_label(chrono::Local::now()), "today");
+        std::fs::remove_dir_all(&base).ok();
+    }
+
+    #[test]
+    fn test_direct_launch_labels() {
+        let direct = LaunchRecord {
+            direct_account: Some("acct".to_string()),
+            direct_game: Some("platinum".to_string()),
+            last_played: Some("2026-01-01T12:00:00Z".to_string()),
+            ..LaunchRecord::default()
+        };
+        assert_eq!(direct.connection_label(), "Platinum (direct)");
+        let later = chrono::Local
+            .with_ymd_and_hms(2026, 3, 1, 12, 0, 0)
+            .unwrap();
+        assert_eq!(direct.last_played_label(later), "2026-01-01");
+    }
+
+    #[test]
+    fn test_never_played() {
+        let later = chrono::Local
+            .with_ymd_and_hms(2026, 3, 1, 12, 0, 0)
+            .unwrap();
+        assert_eq!(LaunchRecord::default().last_played_label(later), "never");
+    }
+}
@@ -0,0 +1,254 @@
+//! Compare and merge layouts
+//!
+//! Per-character variants of a base layout drift apart. `two-face layout diff`
+//! lists the windows added, removed or changed between two layout files, and
+//! `layout merge` copies the windows one has and the other lacks, leaving the
+//! windows already in the target alone.
+
+use super::diff::ChangeKind;
+use super::{Layout, WindowDef};
+
+/// How one window differs between two layouts
+#[derive(Debug, Clone, PartialEq)]
+pub struct WindowChange {
+    pub name: String,
+    pub kind: ChangeKind,
+    /// What changed, for modified windows ("size 80x30 -> 90x30", "title: ...")
+    pub details: Vec<String>,
+}
+
+/// Windows removed from `a`, changed, and added in `b`, in layout order
+pub fn diff_layouts(a: &Layout, b: &Layout) -> Vec<WindowChange> {
+    let mut changes = Vec::new();
+    for old in &a.windows {
+        let change = match b.get_window(old.name()) {
+            None => WindowChange {
+                name: old.name().to_string(),
+                kind: ChangeKind::Removed,
+                details: Vec::new(),
+            },
+            Some(new) => {
+                let details = window_details(old, new);
+                if details.is_empty() {
+                    continue;
+                }
+                WindowChange {
+                    name: old.name().to_string(),
+                    kind: ChangeKind::Modified,
+                    details,
+                }
+            }
+        };
+        changes.push(change);
+    }
+    for new in &b.windows {
+        if a.get_window(new.name()).is_none() {
+            changes.push(WindowChange {
+                name: new.name().to_string(),
+                kind: ChangeKind::Added,
+                details: vec![format!(
+                    "{} at {},{} size {}x{}",
+                    new.widget_type(),
+                    new.base().row,
+                    new.base().col,
+                    new.base().cols,
+                    new.base().rows
+                )],
+            });
+        }
+    }
+    changes
+}
+
+/// Differences between two windows with the same name
+fn window_details(old: &WindowDef, new: &WindowDef) -> Vec<String> {
+    if old.widget_type() != new.widget_type() {
+        return vec![format!(
+            "widget {} -> {}",
+            old.widget_type(),
+            new.widget_type()
+        )];
+    }
+
+    let (a, b) = (old.base(), new.base());
+    let mut details = Vec::new();
+    if (a.row, a.col) != (b.row, b.col) {
+        details.push(format!(
+            "position {},{} -> {},{}",
+            a.row, a.col, b.row, b.col
+        ));
+    }
+    if (a.cols, a.rows) != (b.cols, b.rows) {
+        details.push(format!(
+            "size {}x{} -> {}x{}",
+            a.cols, a.rows, b.cols, b.rows
+        ));
+    }
+
+    // Every other setting, compared as it would be written to the file
+    let table = |window: &WindowDef| match toml::Value::try_from(window) {
+        Ok(toml::Value::Table(table)) => table,
+        _ => toml::Table::new(),
+    };
+    let (old_table, new_table) = (table(old), table(new));
+    let mut keys: Vec<&String> = old_table.keys().chain(new_table.keys()).collect();
+    keys.sort();
+    keys.dedup();
+    for key in keys {
+        if matches!(
+            key.as_str(),
+            "name" | "widget_type" | "row" | "col" | "rows" | "cols"
+        ) {
+            continue;
+        }
+        let (before, after) = (old_table.get(key), new_table.get(key));
+        if before != after {
+            let show = |value: Option<&toml::Value>| {
+                value.map_or("(unset)".to_string(), |v| v.to_string())
+            };
+            details.push(format!("{}: {} -> {}", key, show(before), show(after)));
+        }
+    }
+    details
+}
+
+/// Copy the windows `from` has and `into` lacks (just those named in `only`,
+/// when given), returning their names
+///
+/// Positions are scaled when the two layouts were made for different terminal
+/// sizes, so an added window lands in the same part of the screen.
+pub fn merge_additions(from: &Layout, into: &mut Layout, only: &[String]) -> Vec<String> {
+    let scale =
+        |value: u16, from_size: Option<u16>, into_size: Option<u16>| match (from_size, into_size) {
+            (Some(f), Some(i)) if f > 0 && f != i => (value as u32 * i as u32 / f as u32) as u16,
+            _ => value,
+        };
+
+    let mut added = Vec::new();
+    for window in &from.windows {
+        let name = window.name();
+        if into.get_window(name).is_some() || (!only.is_empty() && !only.iter().any(|n| n == name))
+        {
+            continue;
+        }
+        let mut window = window.clone();
+        let base = window.base_mut();
+        base.col = scale(base.col, from.terminal_width, into.terminal_width);
+        base.cols = scale(base.cols, from.terminal_width, into.terminal_width).max(1);
+        base.row = scale(base.row, from.terminal_height, into.terminal_height);
+        base.rows = scale(base.rows, from.terminal_height, into.terminal_height).max(1);
+        into.windows.push(window);
+        added.push(name.to_string());
+    }
+    added
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    fn layout(windows: &[(&str, u16, u16, u16, u16)], size: (u16, u16)) -> Layout {
+        Layout {
+            windows: windows
+                .iter()
+                .map(|&(name, row, col, rows, cols)| {
+                    let mut window = Config::get_window_template(name).unwrap();
+                    let base = window.base_mut();
+                    (base.row, base.col, base.rows, base.cols) = (row, col, rows, cols);
+                    window
+                })
+                .collect(),
+            terminal_width: Some(size.0),
+            terminal_height: Some(size.1),
+            base_layout: None,
+            theme: None,
+        }
+    }
+
+    fn base() -> Layout {
+        layout(
+            &[
+                ("main", 0, 0, 30, 80),
+                ("thoughts", 0, 80, 10, 40),
+                ("loot", 10, 80, 10, 40),
+            ],
+            (120, 40),
+        )
+    }
+
+    /// Wider main, narrower renamed thoughts, loot dropped and compass added
+    fn variant() -> Layout {
+        let mut variant = layout(
+            &[
+                ("main", 0, 0, 30, 90),
+                ("thoughts", 0, 90, 10, 30),
+                ("compass", 30, 0, 5, 13),
+            ],
+            (120, 40),
+        );
+        if let Some(title) = variant.windows.iter_mut().find(|w| w.name() == "thoughts") {
+            title.base_mut().title = Some("Minds".to_string());
+        }
+        variant
+    }
+
+    #[test]
+    fn test_diff_change_kinds() {
+        let changes = diff_layouts(&base(), &variant());
+        let summary: Vec<(&str, ChangeKind)> =
+            changes.iter().map(|c| (c.name.as_str(), c.kind)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("main", ChangeKind::Modified),
+                ("thoughts", ChangeKind::Modified),
+                ("loot", ChangeKind::Removed),
+                ("compass", ChangeKind::Added),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_details() {
+        let changes = diff_layouts(&base(), &variant());
+        assert_eq!(changes[0].details, vec!["size 80x30 -> 90x30"]);
+        assert_eq!(
+            changes[1].details,
+            vec![
+                "position 0,80 -> 0,90",
+                "size 40x10 -> 30x10",
+                "title: \"Thoughts\" -> \"Minds\""
+            ]
+        );
+    }
+
+    #[test]
+    fn test_identical_layouts_have_no_changes() {
+        assert!(diff_layouts(&base(), &base()).is_empty());
+    }
+
+    #[test]
+    fn test_merge_scales_to_target_terminal() {
+        let mut wide = layout(&[("main", 0, 0, 30, 160)], (240, 40));
+        assert_eq!(
+            merge_additions(&base(), &mut wide, &["loot".to_string()]),
+            vec!["loot"]
+        );
+        let loot = wide.get_window("loot").unwrap().base();
+        assert_eq!(
+            (loot.row, loot.col, loot.rows, loot.cols),
+            (10, 160, 10, 80)
+        );
+    }
+
+    #[test]
+    fn test_merge_copies_only_missing_windows() {
+        let mut target = layout(
+            &[("main", 0, 0, 30, 80), ("loot", 10, 80, 10, 40)],
+            (120, 40),
+        );
+        assert_eq!(merge_additions(&base(), &mut target, &[]), vec!["thoughts"]);
+        assert_eq!(target.windows.len(), 3);
+    }
+}
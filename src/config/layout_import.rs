@@ -0,0 +1,613 @@
+//! Layout importer for StormFront and VellumFE layout files
+//!
+//! StormFront saves its window arrangement as XML (`.sal`): one element per
+//! stream window or dialog, positioned in pixels. VellumFE layouts are TOML
+//! `[[windows]]` tables already measured in terminal cells. Both become a
+//! two-face `Layout`. Each window is matched to the closest `WindowDef`, by
+//! name first (so `health` stays a progress bar fed by the health vital) and
+//! then by widget type; stream windows without a template become text windows
+//! on that stream. StormFront pixels are scaled to the terminal, and a command
+//! line is added along the bottom when the source has none.
+
+use super::keybind_import::decode_xml_entities;
+use crate::config::{Config, Layout, TabbedTextTab, WindowDef};
+use anyhow::{bail, Context, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Rows given to the command line added under an imported layout
+const INPUT_ROWS: u16 = 3;
+
+/// Source front-end of a layout file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutFormat {
+    StormFront,
+    VellumFE,
+}
+
+impl LayoutFormat {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "stormfront" | "sf" | "sal" | "xml" => Some(Self::StormFront),
+            "vellumfe" | "vellum" | "vfe" | "toml" => Some(Self::VellumFE),
+            _ => None,
+        }
+    }
+
+    /// Guess the format from file contents (StormFront layouts are XML)
+    pub fn detect(contents: &str) -> Self {
+        if contents.trim_start().starts_with('<') {
+            Self::StormFront
+        } else {
+            Self::VellumFE
+        }
+    }
+}
+
+/// Result of converting one layout file
+#[derive(Debug)]
+pub struct LayoutImportReport {
+    pub layout: Layout,
+    /// Windows that could not be converted: (source window, reason)
+    pub skipped: Vec<(String, String)>,
+}
+
+/// A window as read from the source file
+#[derive(Debug, Default)]
+struct SourceWindow {
+    /// How the window appeared in the file, for the report
+    source: String,
+    name: String,
+    /// Source widget type; "stream" is a StormFront stream window and ""
+    /// means the name alone decides
+    kind: String,
+    title: Option<String>,
+    streams: Vec<String>,
+    tabs: Vec<TabbedTextTab>,
+    /// Position and size: pixels for StormFront, cells for VellumFE
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+    visible: bool,
+    show_border: Option<bool>,
+    buffer_size: Option<usize>,
+}
+
+/// Windows read from a file, and the screen size it was made for if it says
+type SourceLayout = (Vec<SourceWindow>, Option<(f64, f64)>);
+
+/// Read and convert a layout file, detecting the format when not given
+///
+/// `.sal`/`.xml` files are treated as StormFront and `.toml` files as
+/// VellumFE; anything else is detected from its contents. StormFront layouts
+/// are scaled to fill `screen` (columns, rows).
+pub fn import_file(
+    path: &Path,
+    format: Option<LayoutFormat>,
+    screen: (u16, u16),
+) -> Result<LayoutImportReport> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read layout file {}", path.display()))?;
+    let format = format.unwrap_or_else(|| {
+        match path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase())
+        {
+            Some(ext) if ext == "sal" || ext == "xml" => LayoutFormat::StormFront,
+            Some(ext) if ext == "toml" => LayoutFormat::VellumFE,
+            _ => LayoutFormat::detect(&contents),
+        }
+    });
+    import_layout(&contents, format, screen)
+}
+
+/// Convert the contents of a StormFront or VellumFE layout file
+pub fn import_layout(
+    contents: &str,
+    format: LayoutFormat,
+    screen: (u16, u16),
+) -> Result<LayoutImportReport> {
+    let (windows, source_size) = match format {
+        LayoutFormat::StormFront => (parse_stormfront(contents), None),
+        LayoutFormat::VellumFE => parse_vellumfe(contents)?,
+    };
+    if windows.is_empty() {
+        bail!("No windows found in the layout file");
+    }
+
+    let mut skipped = Vec::new();
+    let mut converted: Vec<(SourceWindow, WindowDef)> = Vec::new();
+    for window in windows {
+        match convert(&window) {
+            Ok(def) if converted.iter().any(|(_, d)| d.name() == def.name()) => {
+                skipped.push((window.source, format!("duplicate window '{}'", def.name())));
+            }
+            Ok(def) => converted.push((window, def)),
+            Err(reason) => skipped.push((window.source, reason)),
+        }
+    }
+    if converted.is_empty() {
+        bail!("None of the {} window(s) could be converted", skipped.len());
+    }
+
+    // StormFront pixels are stretched over the screen; VellumFE cells stay put
+    let has_input = converted
+        .iter()
+        .any(|(_, def)| matches!(def, WindowDef::CommandInput { .. }));
+    let extent = source_size.unwrap_or_else(|| {
+        converted
+            .iter()
+            .fold((1.0f64, 1.0f64), |(w, h), (window, _)| {
+                (
+                    w.max(window.x + window.width),
+                    h.max(window.y + window.height),
+                )
+            })
+    });
+    let (width, height) = match format {
+        LayoutFormat::StormFront => (
+            screen.0,
+            screen
+                .1
+                .saturating_sub(if has_input { 0 } else { INPUT_ROWS }),
+        ),
+        LayoutFormat::VellumFE => (extent.0.round() as u16, extent.1.round() as u16),
+    };
+    let scale = |value: f64, extent: f64, target: u16| {
+        (value * target as f64 / extent.max(1.0))
+            .round()
+            .min(target as f64) as u16
+    };
+
+    let mut windows = Vec::new();
+    for (window, mut def) in converted {
+        let base = def.base_mut();
+        base.col = scale(window.x, extent.0, width);
+        base.row = scale(window.y, extent.1, height);
+        base.cols = scale(window.x + window.width, extent.0, width)
+            .saturating_sub(base.col)
+            .max(1);
+        base.rows = scale(window.y + window.height, extent.1, height)
+            .saturating_sub(base.row)
+            .max(1);
+        windows.push(def);
+    }
+
+    let mut terminal_height = height;
+    if !has_input {
+        if let Some(mut input) = Config::get_window_template("command_input") {
+            let base = input.base_mut();
+            base.row = height;
+            base.col = 0;
+            base.rows = INPUT_ROWS;
+            base.cols = width.max(1);
+            windows.push(input);
+            terminal_height = height + INPUT_ROWS;
+        }
+    }
+
+    Ok(LayoutImportReport {
+        layout: Layout {
+            windows,
+            terminal_width: Some(width),
+            terminal_height: Some(terminal_height),
+            base_layout: None,
+            theme: None,
+        },
+        skipped,
+    })
+}
+
+/// StormFront XML: any element with an `id` (or `name`) and a pixel
+/// rectangle; `<stream>`/`<window>` elements are text streams
+fn parse_stormfront(contents: &str) -> Vec<SourceWindow> {
+    let element_re = regex::Regex::new(r"<\s*([A-Za-z]+)\b([^>]*)>").unwrap();
+    let attr_re = regex::Regex::new(r#"([A-Za-z_]+)\s*=\s*(?:"([^"]*)"|'([^']*)')"#).unwrap();
+
+    let mut windows = Vec::new();
+    for element in element_re.captures_iter(contents) {
+        let attrs: HashMap<String, String> = attr_re
+            .captures_iter(&element[2])
+            .map(|attr| {
+                let value = attr
+                    .get(2)
+                    .or_else(|| attr.get(3))
+                    .map_or("", |m| m.as_str());
+                (attr[1].to_lowercase(), decode_xml_entities(value))
+            })
+            .collect();
+        let Some(name) = attrs.get("id").or_else(|| attrs.get("name")) else {
+            continue;
+        };
+        let number = |keys: &[&str]| {
+            keys.iter()
+                .find_map(|key| attrs.get(*key))
+                .and_then(|value| value.trim().parse::<f64>().ok())
+                .map(|value| value.max(0.0))
+        };
+        let (Some(x), Some(y), Some(width), Some(height)) = (
+            number(&["left", "x"]),
+            number(&["top", "y"]),
+            number(&["width", "w"]),
+            number(&["height", "h"]),
+        ) else {
+            continue;
+        };
+        let flag = |key: &str| {
+            attrs
+                .get(key)
+                .map(|value| matches!(value.to_lowercase().as_str(), "true" | "1" | "yes"))
+        };
+        let tag = element[1].to_lowercase();
+        windows.push(SourceWindow {
+            source: element[0].to_string(),
+            name: name.clone(),
+            kind: if tag == "stream" || tag == "window" {
+                "stream"
+            } else {
+                ""
+            }
+            .to_string(),
+            title: attrs.get("title").filter(|t| !t.is_empty()).cloned(),
+            x,
+            y,
+            width,
+            height,
+            visible: flag("hidden") != Some(true)
+                && flag("closed") != Some(true)
+                && flag("visible") != Some(false),
+            ..Default::default()
+        });
+    }
+    windows
+}
+
+/// VellumFE TOML: `[[windows]]` tables plus the size the layout was made for
+fn parse_vellumfe(contents: &str) -> Result<SourceLayout> {
+    let value: toml::Value = toml::from_str(contents).context("Not a VellumFE layout")?;
+    let number = |table: &toml::Value, key: &str| {
+        table
+            .get(key)
+            .and_then(|v| v.as_integer())
+            .map(|n| n.max(0) as f64)
+    };
+    let text = |table: &toml::Value, key: &str| {
+        table.get(key).and_then(|v| v.as_str()).map(str::to_string)
+    };
+    // `streams = [...]` or a single `stream = "..."`
+    let streams = |table: &toml::Value| -> Vec<String> {
+        match table.get("streams").and_then(|v| v.as_array()) {
+            Some(list) => list
+                .iter()
+                .filter_map(|s| s.as_str())
+                .map(str::to_string)
+                .collect(),
+            None => text(table, "stream").into_iter().collect(),
+        }
+    };
+
+    let size = number(&value, "terminal_width").zip(number(&value, "terminal_height"));
+    let Some(tables) = value.get("windows").and_then(|w| w.as_array()) else {
+        bail!("Not a VellumFE layout: no [[windows]] tables");
+    };
+
+    let windows = tables
+        .iter()
+        .map(|table| {
+            let name = text(table, "name").unwrap_or_default();
+            let kind = text(table, "widget_type").unwrap_or_else(|| "text".to_string());
+            let tabs = table
+                .get("tabs")
+                .and_then(|t| t.as_array())
+                .map(|tabs| {
+                    tabs.iter()
+                        .filter_map(|tab| {
+                            Some(TabbedTextTab {
+                                name: text(tab, "name")?,
+                                streams: streams(tab),
+                                show_timestamps: None,
+                            })
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            SourceWindow {
+                source: format!("{} ({})", name, kind),
+                name,
+                kind,
+                title: text(table, "title"),
+                streams: streams(table),
+                tabs,
+                x: number(table, "col").unwrap_or(0.0),
+                y: number(table, "row").unwrap_or(0.0),
+                width: number(table, "cols").unwrap_or(40.0),
+                height: number(table, "rows").unwrap_or(10.0),
+                visible: table
+                    .get("visible")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(true),
+                show_border: table.get("show_border").and_then(|v| v.as_bool()),
+                buffer_size: number(table, "buffer_size").map(|n| n as usize),
+            }
+        })
+        .collect();
+    Ok((windows, size))
+}
+
+/// Two-face window names for StormFront ids that differ
+fn two_face_name(name: &str) -> String {
+    match name.to_lowercase().as_str() {
+        "smain" | "main" => "main",
+        "inv" | "inventory" => "inventory",
+        "talk" | "conversation" => "speech",
+        "deaths" => "death",
+        "atmospherics" => "ambients",
+        "percwindow" | "activespells" => "active_spells",
+        "minivitals" => "vitals",
+        "left" | "lefthand" => "left_hand",
+        "right" | "righthand" => "right_hand",
+        "spell" | "spellhand" => "spell_hand",
+        "injury_doll" => "injuries",
+        _ => return name.to_string(),
+    }
+    .to_string()
+}
+
+/// Two-face widget type for a source widget type (None = nothing like it)
+fn widget_type(kind: &str) -> Option<&'static str> {
+    Some(
+        match kind.to_lowercase().replace(['-', ' '], "_").as_str() {
+            "" => "",
+            "stream" => "stream",
+            "text" | "window" => "text",
+            "tabbed" | "tabbedtext" | "tabbed_text" => "tabbedtext",
+            "room" => "room",
+            "inventory" => "inventory",
+            "command_input" | "commandinput" | "input" => "command_input",
+            "progress" | "progressbar" => "progress",
+            "countdown" => "countdown",
+            "compass" => "compass",
+            "injury_doll" | "injuries" => "injury_doll",
+            "indicator" => "indicator",
+            "dashboard" => "dashboard",
+            "hand" | "lefthand" | "righthand" | "spellhand" => "hand",
+            "active_effects" | "activeeffects" | "effects" => "active_effects",
+            "targets" | "entity" => "targets",
+            "players" => "players",
+            "spacer" => "spacer",
+            "spells" => "spells",
+            "vitals" => "vitals",
+            "quickbar" => "quickbar",
+            _ => return None,
+        },
+    )
+}
+
+/// A fresh window of a widget type, from its stock template where there is one
+fn window_of_type(kind: &str, name: &str) -> Option<WindowDef> {
+    let template = match kind {
+        "stream" | "text" => "main",
+        "progress" => "health",
+        "countdown" => "roundtime",
+        "hand" => "left_hand",
+        "active_effects" => "active_spells",
+        "injury_doll" => "injuries",
+        other => other,
+    };
+    Config::get_window_template(template).or_else(|| {
+        let mut table = toml::map::Map::new();
+        table.insert(
+            "widget_type".to_string(),
+            toml::Value::String(kind.to_string()),
+        );
+        table.insert("name".to_string(), toml::Value::String(name.to_string()));
+        toml::Value::Table(table).try_into().ok()
+    })
+}
+
+/// Match one source window to a two-face window (geometry is set later)
+fn convert(window: &SourceWindow) -> Result<WindowDef, String> {
+    if window.name.is_empty() {
+        return Err("window has no name".to_string());
+    }
+    let name = two_face_name(&window.name);
+    let Some(kind) = widget_type(&window.kind) else {
+        return Err(format!("no two-face widget like '{}'", window.kind));
+    };
+
+    let by_name = Config::get_window_template(&name)
+        .filter(|template| matches!(kind, "" | "stream") || template.widget_type() == kind);
+    let from_template = by_name.is_some();
+    let mut def = match by_name {
+        Some(template) => template,
+        None if kind.is_empty() => return Err("no matching two-face window".to_string()),
+        None => window_of_type(kind, &name)
+            .ok_or_else(|| format!("no two-face widget like '{}'", window.kind))?,
+    };
+
+    // The command line is found by name, whatever the source called it
+    let name = match def {
+        WindowDef::CommandInput { .. } => "command_input".to_string(),
+        _ => name,
+    };
+    let base = def.base_mut();
+    base.name = name.clone();
+    base.visible = window.visible;
+    if let Some(show_border) = window.show_border {
+        base.show_border = show_border;
+    }
+    if let Some(ref title) = window.title {
+        base.title = Some(title.clone());
+    } else if !from_template {
+        base.title = Some(name.clone());
+    }
+    if !from_template {
+        base.locked = false;
+    }
+
+    match def {
+        WindowDef::Text { ref mut data, .. } => {
+            if !window.streams.is_empty() {
+                data.streams = window.streams.clone();
+            } else if !from_template {
+                data.streams = vec![name.clone()];
+            }
+            if let Some(buffer_size) = window.buffer_size {
+                data.buffer_size = buffer_size;
+            }
+        }
+        WindowDef::TabbedText { ref mut data, .. } => {
+            data.tabs = if window.tabs.is_empty() {
+                // One tab per stream
+                window
+                    .streams
+                    .iter()
+                    .map(|stream| TabbedTextTab {
+                        name: stream.clone(),
+                        streams: vec![stream.clone()],
+                        show_timestamps: None,
+                    })
+                    .collect()
+            } else {
+                window.tabs.clone()
+            };
+            if let Some(buffer_size) = window.buffer_size {
+                data.buffer_size = buffer_size;
+            }
+        }
+        WindowDef::Progress { ref mut data, .. } if !from_template => {
+            data.label = window.title.clone();
+        }
+        _ => {}
+    }
+    Ok(def)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window<'a>(layout: &'a Layout, name: &str) -> &'a WindowDef {
+        layout.windows.iter().find(|w| w.name() == name).unwrap()
+    }
+
+    #[test]
+    fn test_import_stormfront_layout() {
+        let sal = r#"<layout>
+            <stream id="smain" title="Story" left="0" top="0" width="600" height="400"/>
+            <stream id="thoughts" left="600" top="0" width="200" height="200"/>
+            <stream id="expr" title="Experience" left="600" top="200" width="200" height="200" hidden="true"/>
+            <dialog id="minivitals" left="0" top="400" width="800" height="40"/>
+            <dialog id="stance" left="0" top="440" width="100" height="20"/>
+            <font face="Arial" size="10"/>
+        </layout>"#;
+        assert_eq!(LayoutFormat::detect(sal), LayoutFormat::StormFront);
+
+        let report = import_layout(sal, LayoutFormat::StormFront, (160, 49)).unwrap();
+        let layout = &report.layout;
+        assert_eq!(
+            (layout.terminal_width, layout.terminal_height),
+            (Some(160), Some(49))
+        );
+
+        let main = window(layout, "main").base();
+        assert_eq!((main.col, main.row, main.cols, main.rows), (0, 0, 120, 42));
+        let thoughts = window(layout, "thoughts").base();
+        assert_eq!((thoughts.col, thoughts.cols), (120, 40));
+
+        // Unknown stream becomes a text window on that stream, hidden as it was
+        let WindowDef::Text { base, data } = window(layout, "expr") else {
+            panic!("expr should be a text window");
+        };
+        assert_eq!(data.streams, vec!["expr"]);
+        assert_eq!(base.title.as_deref(), Some("Experience"));
+        assert!(!base.visible);
+
+        assert_eq!(window(layout, "vitals").widget_type(), "vitals");
+        let input = window(layout, "command_input").base();
+        assert_eq!((input.row, input.rows, input.cols), (46, 3, 160));
+
+        // The stance dialog has no two-face counterpart
+        assert_eq!(report.skipped.len(), 1);
+        assert!(report.skipped[0].0.contains("stance"));
+    }
+
+    #[test]
+    fn test_import_vellumfe_layout() {
+        let layout = r#"
+terminal_width = 100
+terminal_height = 40
+
+[[windows]]
+name = "main"
+widget_type = "text"
+streams = ["main"]
+row = 0
+col = 0
+rows = 37
+cols = 80
+
+[[windows]]
+name = "chatter"
+widget_type = "tabbed"
+row = 0
+col = 80
+rows = 20
+cols = 20
+tabs = [{ name = "Speech", stream = "speech" }, { name = "Thoughts", streams = ["thoughts"] }]
+
+[[windows]]
+name = "mindstate"
+widget_type = "progress"
+title = "Mind"
+row = 20
+col = 80
+rows = 3
+cols = 20
+
+[[windows]]
+name = "map"
+widget_type = "map"
+
+[[windows]]
+name = "input"
+widget_type = "command_input"
+row = 37
+col = 0
+rows = 3
+cols = 100
+"#;
+        let report = import_layout(layout, LayoutFormat::VellumFE, (200, 60)).unwrap();
+        let layout = &report.layout;
+        assert_eq!(
+            (layout.terminal_width, layout.terminal_height),
+            (Some(100), Some(40))
+        );
+        assert_eq!(layout.windows.len(), 4);
+
+        let WindowDef::TabbedText { base, data } = window(layout, "chatter") else {
+            panic!("chatter should be tabbed");
+        };
+        assert_eq!((base.col, base.row, base.cols, base.rows), (80, 0, 20, 20));
+        assert_eq!(data.tabs.len(), 2);
+        assert_eq!(data.tabs[0].streams, vec!["speech"]);
+
+        let WindowDef::Progress { base, data } = window(layout, "mindstate") else {
+            panic!("mindstate should be a progress bar");
+        };
+        assert_eq!(base.title.as_deref(), Some("Mind"));
+        assert_eq!(data.label.as_deref(), Some("Mind"));
+
+        assert_eq!(window(layout, "command_input").base().row, 37);
+        assert_eq!(
+            report.skipped,
+            vec![(
+                "map (map)".to_string(),
+                "no two-face widget like 'map'".to_string()
+            )]
+        );
+    }
+}
@@ -0,0 +1,1352 @@
+//! Typed registry of settings editable from the settings editor.
+//!
+//! Each entry knows how to read and write its `Config` field, which values
+//! are valid, and whether a change takes effect immediately or only after a
+//! restart. Live side effects (resizing buffers, retuning sound) are applied
+//! by `AppCore::apply_setting`; this module only touches `Config`.
+//!
+//! Fields without a registry entry are still editable: `reflected` walks the
+//! serialized config (and sounds.toml) and lists every plain leaf under its
+//! dotted key, and `set_setting` writes those back through serde.
+
+use crate::config::Config;
+use anyhow::{bail, Context, Result};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SettingValue {
+    String(String),
+    Number(i64),
+    Float(f64),
+    Boolean(bool),
+    Color(String),
+    Enum(String, Vec<String>), // (current_value, all_options)
+}
+
+impl SettingValue {
+    pub fn to_display_string(&self) -> String {
+        match self {
+            SettingValue::String(s) => s.clone(),
+            SettingValue::Number(n) => n.to_string(),
+            SettingValue::Float(f) => format!("{:.2}", f),
+            SettingValue::Boolean(b) => if *b { "true" } else { "false" }.to_string(),
+            SettingValue::Color(c) => c.clone(),
+            SettingValue::Enum(val, _) => val.clone(),
+        }
+    }
+
+    pub fn parse_from_string(&self, s: &str) -> Option<SettingValue> {
+        match self {
+            SettingValue::String(_) => Some(SettingValue::String(s.to_string())),
+            SettingValue::Number(_) => s.trim().parse::<i64>().ok().map(SettingValue::Number),
+            SettingValue::Float(_) => s.trim().parse::<f64>().ok().map(SettingValue::Float),
+            SettingValue::Boolean(_) => match s.to_lowercase().as_str() {
+                "true" | "t" | "1" | "yes" | "y" => Some(SettingValue::Boolean(true)),
+                "false" | "f" | "0" | "no" | "n" => Some(SettingValue::Boolean(false)),
+                _ => None,
+            },
+            SettingValue::Color(_) => Some(SettingValue::Color(s.trim().to_string())),
+            SettingValue::Enum(_, options) => {
+                if options.contains(&s.to_string()) {
+                    Some(SettingValue::Enum(s.to_string(), options.clone()))
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    fn as_text(&self) -> &str {
+        match self {
+            SettingValue::String(s) | SettingValue::Color(s) | SettingValue::Enum(s, _) => s,
+            _ => "",
+        }
+    }
+
+    fn as_i64(&self) -> i64 {
+        match self {
+            SettingValue::Number(n) => *n,
+            SettingValue::Float(f) => *f as i64,
+            _ => 0,
+        }
+    }
+
+    fn as_f64(&self) -> f64 {
+        match self {
+            SettingValue::Float(f) => *f,
+            SettingValue::Number(n) => *n as f64,
+            _ => 0.0,
+        }
+    }
+
+    fn as_bool(&self) -> bool {
+        matches!(self, SettingValue::Boolean(true))
+    }
+}
+
+/// Value type and constraints of a setting
+#[derive(Debug, Clone, Copy)]
+pub enum SettingKind {
+    Text { allow_empty: bool },
+    Integer { min: i64, max: i64 },
+    Float { min: f64, max: f64 },
+    Bool,
+    Choice(&'static [&'static str]),
+}
+
+/// When a change takes effect
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApplyMode {
+    Live,
+    Restart,
+}
+
+pub struct SettingDef {
+    pub key: &'static str,
+    pub category: &'static str,
+    pub display_name: &'static str,
+    pub description: &'static str,
+    pub kind: SettingKind,
+    pub apply: ApplyMode,
+    get: fn(&Config) -> SettingValue,
+    set: fn(&mut Config, &SettingValue),
+}
+
+impl SettingDef {
+    /// Current value, typed for the editor
+    pub fn value(&self, config: &Config) -> SettingValue {
+        match (self.kind, (self.get)(config)) {
+            (SettingKind::Choice(options), value) => SettingValue::Enum(
+                value.as_text().to_string(),
+                options.iter().map(|o| o.to_string()).collect(),
+            ),
+            (_, value) => value,
+        }
+    }
+
+    /// Check a proposed value against this setting's constraints
+    pub fn validate(&self, value: &SettingValue) -> Result<(), String> {
+        match (self.kind, value) {
+            (SettingKind::Text { allow_empty }, SettingValue::String(s)) => {
+                if !allow_empty && s.trim().is_empty() {
+                    return Err(format!("{} cannot be empty", self.display_name));
+                }
+            }
+            (SettingKind::Integer { min, max }, SettingValue::Number(n)) => {
+                if !(min..=max).contains(n) {
+                    return Err(format!(
+                        "{} must be between {} and {}",
+                        self.display_name, min, max
+                    ));
+                }
+            }
+            (SettingKind::Float { min, max }, SettingValue::Float(f)) => {
+                if !(min..=max).contains(f) {
+                    return Err(format!(
+                        "{} must be between {} and {}",
+                        self.display_name, min, max
+                    ));
+                }
+            }
+            (SettingKind::Bool, SettingValue::Boolean(_)) => {}
+            (SettingKind::Choice(options), SettingValue::Enum(v, _) | SettingValue::String(v)) => {
+                if !options.contains(&v.as_str()) {
+                    return Err(format!(
+                        "{} must be one of: {}",
+                        self.display_name,
+                        options.join(", ")
+                    ));
+                }
+            }
+            _ => return Err(format!("Wrong value type for {}", self.display_name)),
+        }
+        Ok(())
+    }
+}
+
+const BORDER_STYLES: &[&str] = &["single", "double", "rounded", "thick", "none"];
+const DRAG_MODIFIERS: &[&str] = &["ctrl", "alt", "shift"];
+const COLOR_MODES: &[&str] = &["auto", "truecolor", "256", "16"];
+const GRAPHICS_MODES: &[&str] = &["auto", "kitty", "sixel", "off"];
+const COMPACT_BORDERS: &[&str] = &["single", "none"];
+const COMMAND_ECHOES: &[&str] = &["all", "typed", "none"];
+const THEME_SCHEDULE_MODES: &[&str] = &["time", "os"];
+const TIMESTAMP_POSITIONS: &[&str] = &["end", "start"];
+const TTS_INTERRUPT_LEVELS: &[&str] = &["normal", "high", "critical", "never"];
+
+static SETTINGS: &[SettingDef] = &[
+    // Connection
+    SettingDef {
+        key: "connection.host",
+        category: "Connection",
+        display_name: "Host",
+        description: "Game server hostname or IP address",
+        kind: SettingKind::Text { allow_empty: false },
+        apply: ApplyMode::Restart,
+        get: |c| SettingValue::String(c.connection.host.clone()),
+        set: |c, v| c.connection.host = v.as_text().trim().to_string(),
+    },
+    SettingDef {
+        key: "connection.port",
+        category: "Connection",
+        display_name: "Port",
+        description: "Game server port number",
+        kind: SettingKind::Integer { min: 1, max: 65535 },
+        apply: ApplyMode::Restart,
+        get: |c| SettingValue::Number(c.connection.port as i64),
+        set: |c, v| c.connection.port = v.as_i64() as u16,
+    },
+    SettingDef {
+        key: "connection.character",
+        category: "Connection",
+        display_name: "Character",
+        description: "Default character name (empty = none)",
+        kind: SettingKind::Text { allow_empty: true },
+        apply: ApplyMode::Restart,
+        get: |c| SettingValue::String(c.connection.character.clone().unwrap_or_default()),
+        set: |c, v| {
+            let name = v.as_text().trim();
+            c.connection.character = (!name.is_empty()).then(|| name.to_string());
+        },
+    },
+    // UI
+    SettingDef {
+        key: "ui.buffer_size",
+        category: "UI",
+        display_name: "Buffer Size",
+        description: "Number of lines to keep in text window buffers",
+        kind: SettingKind::Integer {
+            min: 100,
+            max: 100_000,
+        },
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::Number(c.ui.buffer_size as i64),
+        set: |c, v| c.ui.buffer_size = v.as_i64() as usize,
+    },
+    SettingDef {
+        key: "ui.timestamps.enabled",
+        category: "UI",
+        display_name: "Show Timestamps",
+        description: "Stamp new lines in text windows with the time they arrived",
+        kind: SettingKind::Bool,
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::Boolean(c.ui.timestamps.enabled),
+        set: |c, v| c.ui.timestamps.enabled = v.as_bool(),
+    },
+    SettingDef {
+        key: "ui.timestamps.format",
+        category: "UI",
+        display_name: "Timestamp Format",
+        description: "strftime format, e.g. %H:%M:%S or %l:%M %p",
+        kind: SettingKind::Text { allow_empty: false },
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::String(c.ui.timestamps.format.clone()),
+        set: |c, v| c.ui.timestamps.format = v.as_text().to_string(),
+    },
+    SettingDef {
+        key: "ui.timestamps.relative",
+        category: "UI",
+        display_name: "Relative Timestamps",
+        description: "Show how long ago a line arrived (3m) instead of the time",
+        kind: SettingKind::Bool,
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::Boolean(c.ui.timestamps.relative),
+        set: |c, v| c.ui.timestamps.relative = v.as_bool(),
+    },
+    SettingDef {
+        key: "ui.timestamps.position",
+        category: "UI",
+        display_name: "Timestamp Position",
+        description: "Put timestamps before or after the line's text",
+        kind: SettingKind::Choice(TIMESTAMP_POSITIONS),
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::String(c.ui.timestamps.position.clone()),
+        set: |c, v| c.ui.timestamps.position = v.as_text().to_string(),
+    },
+    SettingDef {
+        key: "ui.border_style",
+        category: "UI",
+        display_name: "Border Style",
+        description: "Border style for newly created windows",
+        kind: SettingKind::Choice(BORDER_STYLES),
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::String(c.ui.border_style.clone()),
+        set: |c, v| c.ui.border_style = v.as_text().to_string(),
+    },
+    SettingDef {
+        key: "ui.countdown_icon",
+        category: "UI",
+        display_name: "Countdown Icon",
+        description: "Unicode character for countdown blocks",
+        kind: SettingKind::Text { allow_empty: false },
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::String(c.ui.countdown_icon.clone()),
+        set: |c, v| c.ui.countdown_icon = v.as_text().to_string(),
+    },
+    SettingDef {
+        key: "ui.poll_timeout_ms",
+        category: "UI",
+        display_name: "Poll Timeout (ms)",
+        description: "Event poll timeout - lower = higher FPS, higher CPU",
+        kind: SettingKind::Integer { min: 1, max: 1000 },
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::Number(c.ui.poll_timeout_ms as i64),
+        set: |c, v| c.ui.poll_timeout_ms = v.as_i64() as u64,
+    },
+    SettingDef {
+        key: "ui.idle_poll_max_ms",
+        category: "UI",
+        display_name: "Idle Poll Timeout (ms)",
+        description: "Poll timeout the input loop backs off to while idle (at or below Poll Timeout = off)",
+        kind: SettingKind::Integer { min: 1, max: 2000 },
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::Number(c.ui.idle_poll_max_ms as i64),
+        set: |c, v| c.ui.idle_poll_max_ms = v.as_i64() as u64,
+    },
+    SettingDef {
+        key: "ui.color_mode",
+        category: "UI",
+        display_name: "Color Mode",
+        description: "Colors the terminal can show; auto detects from COLORTERM/TERM",
+        kind: SettingKind::Choice(COLOR_MODES),
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::String(c.ui.color_mode.clone()),
+        set: |c, v| c.ui.color_mode = v.as_text().to_string(),
+    },
+    SettingDef {
+        key: "ui.graphics",
+        category: "UI",
+        display_name: "Graphics",
+        description: "How art windows draw images; auto detects kitty/sixel terminals, off uses half-block text",
+        kind: SettingKind::Choice(GRAPHICS_MODES),
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::String(c.ui.graphics.clone()),
+        set: |c, v| c.ui.graphics = v.as_text().to_string(),
+    },
+    SettingDef {
+        key: "ui.chord_timeout_ms",
+        category: "UI",
+        display_name: "Chord Timeout (ms)",
+        description: "How long to wait for the next key of a multi-key keybind",
+        kind: SettingKind::Integer {
+            min: 100,
+            max: 10_000,
+        },
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::Number(c.ui.chord_timeout_ms as i64),
+        set: |c, v| c.ui.chord_timeout_ms = v.as_i64() as u64,
+    },
+    SettingDef {
+        key: "ui.text_fade_minutes",
+        category: "UI",
+        display_name: "Text Fade (min)",
+        description: "Dim text window lines older than this (0 = off)",
+        kind: SettingKind::Integer { min: 0, max: 1440 },
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::Number(c.ui.text_fade_minutes as i64),
+        set: |c, v| c.ui.text_fade_minutes = v.as_i64() as u32,
+    },
+    SettingDef {
+        key: "ui.gap_separator_minutes",
+        category: "UI",
+        display_name: "Idle Separator (min)",
+        description: "Mark main window silences this long with a separator line (0 = off)",
+        kind: SettingKind::Integer { min: 0, max: 1440 },
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::Number(c.ui.gap_separator_minutes as i64),
+        set: |c, v| c.ui.gap_separator_minutes = v.as_i64() as u32,
+    },
+    SettingDef {
+        key: "ui.input_timer",
+        category: "UI",
+        display_name: "Timer on Command Input",
+        description: "Color the command input border during roundtime and casttime, ghosting the text in roundtime",
+        kind: SettingKind::Bool,
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::Boolean(c.ui.input_timer),
+        set: |c, v| c.ui.input_timer = v.as_bool(),
+    },
+    SettingDef {
+        key: "ui.collapse_repeats",
+        category: "UI",
+        display_name: "Collapse Repeats",
+        description: "Fold a line identical to the one before it into an \"(xN)\" count (windows can override)",
+        kind: SettingKind::Bool,
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::Boolean(c.ui.collapse_repeats),
+        set: |c, v| c.ui.collapse_repeats = v.as_bool(),
+    },
+    SettingDef {
+        key: "ui.more_prompt",
+        category: "UI",
+        display_name: "More Prompt",
+        description: "Hold the scrolled-back main window at \"-- more --\" after each screen of new text",
+        kind: SettingKind::Bool,
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::Boolean(c.ui.more_prompt),
+        set: |c, v| c.ui.more_prompt = v.as_bool(),
+    },
+    SettingDef {
+        key: "ui.scrollback_disk_lines",
+        category: "UI",
+        display_name: "Disk Scrollback Lines",
+        description: "Older lines each text window keeps on disk past the buffer (0 = off)",
+        kind: SettingKind::Integer {
+            min: 0,
+            max: 1_000_000,
+        },
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::Number(c.ui.scrollback_disk_lines as i64),
+        set: |c, v| c.ui.scrollback_disk_lines = v.as_i64() as usize,
+    },
+    SettingDef {
+        key: "ui.startup_music",
+        category: "UI",
+        display_name: "Startup Music",
+        description: "Play music when connecting",
+        kind: SettingKind::Bool,
+        apply: ApplyMode::Restart,
+        get: |c| SettingValue::Boolean(c.ui.startup_music),
+        set: |c, v| c.ui.startup_music = v.as_bool(),
+    },
+    SettingDef {
+        key: "ui.startup_music_file",
+        category: "UI",
+        display_name: "Startup Music File",
+        description: "Sound file to play on startup (without extension)",
+        kind: SettingKind::Text { allow_empty: true },
+        apply: ApplyMode::Restart,
+        get: |c| SettingValue::String(c.ui.startup_music_file.clone()),
+        set: |c, v| c.ui.startup_music_file = v.as_text().to_string(),
+    },
+    SettingDef {
+        key: "ui.selection_enabled",
+        category: "UI",
+        display_name: "Selection Enabled",
+        description: "Enable text selection with mouse",
+        kind: SettingKind::Bool,
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::Boolean(c.ui.selection_enabled),
+        set: |c, v| c.ui.selection_enabled = v.as_bool(),
+    },
+    SettingDef {
+        key: "ui.selection_respect_window_boundaries",
+        category: "UI",
+        display_name: "Selection Respects Windows",
+        description: "Prevent selection from crossing window boundaries",
+        kind: SettingKind::Bool,
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::Boolean(c.ui.selection_respect_window_boundaries),
+        set: |c, v| c.ui.selection_respect_window_boundaries = v.as_bool(),
+    },
+    SettingDef {
+        key: "ui.drag_modifier_key",
+        category: "UI",
+        display_name: "Drag Modifier Key",
+        description: "Modifier key required for drag and drop",
+        kind: SettingKind::Choice(DRAG_MODIFIERS),
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::String(c.ui.drag_modifier_key.clone()),
+        set: |c, v| c.ui.drag_modifier_key = v.as_text().to_string(),
+    },
+    SettingDef {
+        key: "ui.min_command_length",
+        category: "UI",
+        display_name: "Min Command Length",
+        description: "Minimum command length to save to history",
+        kind: SettingKind::Integer { min: 0, max: 100 },
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::Number(c.ui.min_command_length as i64),
+        set: |c, v| c.ui.min_command_length = v.as_i64() as usize,
+    },
+    SettingDef {
+        key: "ui.history_max_entries",
+        category: "UI",
+        display_name: "History Size",
+        description: "Commands kept in history (in memory and in history.txt)",
+        kind: SettingKind::Integer { min: 1, max: 100000 },
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::Number(c.ui.history_max_entries as i64),
+        set: |c, v| c.ui.history_max_entries = v.as_i64() as usize,
+    },
+    SettingDef {
+        key: "ui.confirm_config_writes",
+        category: "UI",
+        display_name: "Review Config Writes",
+        description: "Show a diff of highlight/keybind/color edits before writing them",
+        kind: SettingKind::Bool,
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::Boolean(c.ui.confirm_config_writes),
+        set: |c, v| c.ui.confirm_config_writes = v.as_bool(),
+    },
+    SettingDef {
+        key: "ui.confirm_multiline_paste",
+        category: "UI",
+        display_name: "Confirm Multi-line Paste",
+        description: "Ask before sending a paste with several lines (off = send them all)",
+        kind: SettingKind::Bool,
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::Boolean(c.ui.confirm_multiline_paste),
+        set: |c, v| c.ui.confirm_multiline_paste = v.as_bool(),
+    },
+    SettingDef {
+        key: "ui.compose_line_delay_ms",
+        category: "UI",
+        display_name: "Composer Line Delay (ms)",
+        description: "Wait between lines sent from the composer (0 = back to back)",
+        kind: SettingKind::Integer { min: 0, max: 60000 },
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::Number(c.ui.compose_line_delay_ms as i64),
+        set: |c, v| c.ui.compose_line_delay_ms = v.as_i64() as u64,
+    },
+    SettingDef {
+        key: "ui.command_echo",
+        category: "UI",
+        display_name: "Command Echo",
+        description: "Echo sent commands in main: all, typed only (not macros, plugins or remote), or none",
+        kind: SettingKind::Choice(COMMAND_ECHOES),
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::String(c.ui.command_echo.clone()),
+        set: |c, v| c.ui.command_echo = v.as_text().to_string(),
+    },
+    SettingDef {
+        key: "ui.command_echo_prompt",
+        category: "UI",
+        display_name: "Prompt in Echo",
+        description: "Start each echoed command with the game prompt",
+        kind: SettingKind::Bool,
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::Boolean(c.ui.command_echo_prompt),
+        set: |c, v| c.ui.command_echo_prompt = v.as_bool(),
+    },
+    SettingDef {
+        key: "ui.command_echo_prefix",
+        category: "UI",
+        display_name: "Echo Prefix",
+        description: "Text before each echoed command, after the prompt (e.g. \"> \")",
+        kind: SettingKind::Text { allow_empty: true },
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::String(c.ui.command_echo_prefix.clone()),
+        set: |c, v| c.ui.command_echo_prefix = v.as_text().to_string(),
+    },
+    SettingDef {
+        key: "ui.snap_grid",
+        category: "UI",
+        display_name: "Window Snap Grid",
+        description: "Round mouse window moves and resizes to this many cells (0 = off)",
+        kind: SettingKind::Integer { min: 0, max: 50 },
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::Number(c.ui.snap_grid as i64),
+        set: |c, v| c.ui.snap_grid = v.as_i64() as u16,
+    },
+    SettingDef {
+        key: "ui.snap_to_windows",
+        category: "UI",
+        display_name: "Snap to Window Edges",
+        description: "Snap dragged window edges to nearby window edges and show guide lines",
+        kind: SettingKind::Bool,
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::Boolean(c.ui.snap_to_windows),
+        set: |c, v| c.ui.snap_to_windows = v.as_bool(),
+    },
+    SettingDef {
+        key: "ui.compact_width",
+        category: "UI",
+        display_name: "Compact Below Width",
+        description: "Switch to compact mode below this many columns (0 = only with .compact on)",
+        kind: SettingKind::Integer { min: 0, max: 500 },
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::Number(c.ui.compact_width as i64),
+        set: |c, v| c.ui.compact_width = v.as_i64() as u16,
+    },
+    SettingDef {
+        key: "ui.compact_borders",
+        category: "UI",
+        display_name: "Compact Borders",
+        description: "Window borders in compact mode",
+        kind: SettingKind::Choice(COMPACT_BORDERS),
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::String(c.ui.compact_borders.clone()),
+        set: |c, v| c.ui.compact_borders = v.as_text().to_string(),
+    },
+    SettingDef {
+        key: "ui.compact_min_priority",
+        category: "UI",
+        display_name: "Compact Min Priority",
+        description: "Hide windows whose priority is below this in compact mode",
+        kind: SettingKind::Integer { min: 0, max: 100 },
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::Number(c.ui.compact_min_priority as i64),
+        set: |c, v| c.ui.compact_min_priority = v.as_i64() as u8,
+    },
+    // Sound
+    SettingDef {
+        key: "sound.enabled",
+        category: "Sound",
+        display_name: "Sound Enabled",
+        description: "Enable sound effects",
+        kind: SettingKind::Bool,
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::Boolean(c.sound.enabled),
+        set: |c, v| c.sound.enabled = v.as_bool(),
+    },
+    SettingDef {
+        key: "sound.volume",
+        category: "Sound",
+        display_name: "Master Volume",
+        description: "Master volume (0.0 to 1.0)",
+        kind: SettingKind::Float { min: 0.0, max: 1.0 },
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::Float(c.sound.volume as f64),
+        set: |c, v| c.sound.volume = v.as_f64() as f32,
+    },
+    SettingDef {
+        key: "sound.cooldown_ms",
+        category: "Sound",
+        display_name: "Sound Cooldown (ms)",
+        description: "Cooldown between same sound plays",
+        kind: SettingKind::Integer {
+            min: 0,
+            max: 60_000,
+        },
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::Number(c.sound.cooldown_ms as i64),
+        set: |c, v| c.sound.cooldown_ms = v.as_i64() as u64,
+    },
+    SettingDef {
+        key: "sound.alerts_volume",
+        category: "Sound",
+        display_name: "Alerts Volume",
+        description: "Level for alert sounds: deaths, whispers, highlight sounds (0.0 to 1.0)",
+        kind: SettingKind::Float { min: 0.0, max: 1.0 },
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::Float(c.sound.alerts_volume as f64),
+        set: |c, v| c.sound.alerts_volume = v.as_f64() as f32,
+    },
+    SettingDef {
+        key: "sound.ambience_volume",
+        category: "Sound",
+        display_name: "Ambience Volume",
+        description: "Level for sounds in the ambience category (0.0 to 1.0)",
+        kind: SettingKind::Float { min: 0.0, max: 1.0 },
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::Float(c.sound.ambience_volume as f64),
+        set: |c, v| c.sound.ambience_volume = v.as_f64() as f32,
+    },
+    SettingDef {
+        key: "sound.ui_volume",
+        category: "Sound",
+        display_name: "UI Volume",
+        description: "Level for client feedback: roundtime end, expiring spells (0.0 to 1.0)",
+        kind: SettingKind::Float { min: 0.0, max: 1.0 },
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::Float(c.sound.ui_volume as f64),
+        set: |c, v| c.sound.ui_volume = v.as_f64() as f32,
+    },
+    SettingDef {
+        key: "sound.pack",
+        category: "Sound",
+        display_name: "Sound Pack",
+        description: "Folder under ~/.two-face/sounds/ whose files replace the defaults (empty = none)",
+        kind: SettingKind::Text { allow_empty: true },
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::String(c.sound.pack.clone()),
+        set: |c, v| c.sound.pack = v.as_text().trim().to_string(),
+    },
+    // Away from keyboard
+    SettingDef {
+        key: "afk.minutes",
+        category: "AFK",
+        display_name: "AFK After (min)",
+        description: "Minutes without keyboard or mouse input before you're marked AFK (0 = only .afk)",
+        kind: SettingKind::Integer { min: 0, max: 1440 },
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::Number(c.afk.minutes as i64),
+        set: |c, v| c.afk.minutes = v.as_i64() as u64,
+    },
+    SettingDef {
+        key: "afk.auto_reply",
+        category: "AFK",
+        display_name: "Auto-Reply",
+        description: "Whispered once to each person who whispers while you're AFK (empty = off)",
+        kind: SettingKind::Text { allow_empty: true },
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::String(c.afk.auto_reply.clone()),
+        set: |c, v| c.afk.auto_reply = v.as_text().trim().to_string(),
+    },
+    // Text-to-speech
+    SettingDef {
+        key: "tts.enabled",
+        category: "TTS",
+        display_name: "TTS Enabled",
+        description: "Speak game text aloud",
+        kind: SettingKind::Bool,
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::Boolean(c.tts.enabled),
+        set: |c, v| c.tts.enabled = v.as_bool(),
+    },
+    SettingDef {
+        key: "screen_reader.enabled",
+        category: "TTS",
+        display_name: "Screen Reader Mode",
+        description: "No borders or bar art, speak new main-window lines, review keys",
+        kind: SettingKind::Bool,
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::Boolean(c.screen_reader.enabled),
+        set: |c, v| c.screen_reader.enabled = v.as_bool(),
+    },
+    SettingDef {
+        key: "tts.voice",
+        category: "TTS",
+        display_name: "Default Voice",
+        description: "Voice name for all windows (empty = system default, .ttsvoices lists them)",
+        kind: SettingKind::Text { allow_empty: true },
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::String(c.tts.voice.clone().unwrap_or_default()),
+        set: |c, v| {
+            let name = v.as_text().trim();
+            c.tts.voice = (!name.is_empty()).then(|| name.to_string());
+        },
+    },
+    SettingDef {
+        key: "tts.rate",
+        category: "TTS",
+        display_name: "Default Rate",
+        description: "Speech rate (0.5 to 2.0, 1.0 = normal)",
+        kind: SettingKind::Float { min: 0.5, max: 2.0 },
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::Float(c.tts.rate as f64),
+        set: |c, v| c.tts.rate = v.as_f64() as f32,
+    },
+    SettingDef {
+        key: "tts.pitch",
+        category: "TTS",
+        display_name: "Default Pitch",
+        description: "Voice pitch (0.5 to 2.0, 1.0 = normal)",
+        kind: SettingKind::Float { min: 0.5, max: 2.0 },
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::Float(c.tts.pitch as f64),
+        set: |c, v| c.tts.pitch = v.as_f64() as f32,
+    },
+    SettingDef {
+        key: "tts.volume",
+        category: "TTS",
+        display_name: "Volume",
+        description: "Speech volume (0.0 to 1.0)",
+        kind: SettingKind::Float { min: 0.0, max: 1.0 },
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::Float(c.tts.volume as f64),
+        set: |c, v| c.tts.volume = v.as_f64() as f32,
+    },
+    SettingDef {
+        key: "tts.speak_deaths",
+        category: "TTS",
+        display_name: "Speak Deaths",
+        description: "Automatically speak the death window",
+        kind: SettingKind::Bool,
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::Boolean(c.tts.speak_deaths),
+        set: |c, v| c.tts.speak_deaths = v.as_bool(),
+    },
+    SettingDef {
+        key: "tts.only_when_unfocused",
+        category: "TTS",
+        display_name: "Only When Unfocused",
+        description: "Stay quiet while the terminal window has focus",
+        kind: SettingKind::Bool,
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::Boolean(c.tts.only_when_unfocused),
+        set: |c, v| c.tts.only_when_unfocused = v.as_bool(),
+    },
+    SettingDef {
+        key: "tts.interrupt_priority",
+        category: "TTS",
+        display_name: "Interrupt Priority",
+        description: "Lines at this priority or above cut off less urgent speech",
+        kind: SettingKind::Choice(TTS_INTERRUPT_LEVELS),
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::String(c.tts.interrupt_priority.clone()),
+        set: |c, v| c.tts.interrupt_priority = v.as_text().to_string(),
+    },
+    SettingDef {
+        key: "tts.streams.speech.voice",
+        category: "TTS",
+        display_name: "Speech Voice",
+        description: "Voice for the speech window (empty = default voice)",
+        kind: SettingKind::Text { allow_empty: true },
+        apply: ApplyMode::Live,
+        get: |c| stream_voice(c, "speech"),
+        set: |c, v| set_stream_voice(c, "speech", v),
+    },
+    SettingDef {
+        key: "tts.streams.speech.rate",
+        category: "TTS",
+        display_name: "Speech Rate",
+        description: "Speech rate for the speech window (0 = default rate)",
+        kind: SettingKind::Float { min: 0.0, max: 2.0 },
+        apply: ApplyMode::Live,
+        get: |c| stream_rate(c, "speech"),
+        set: |c, v| set_stream_rate(c, "speech", v),
+    },
+    SettingDef {
+        key: "tts.streams.speech.pitch",
+        category: "TTS",
+        display_name: "Speech Pitch",
+        description: "Voice pitch for the speech window (0 = default pitch)",
+        kind: SettingKind::Float { min: 0.0, max: 2.0 },
+        apply: ApplyMode::Live,
+        get: |c| stream_pitch(c, "speech"),
+        set: |c, v| set_stream_pitch(c, "speech", v),
+    },
+    SettingDef {
+        key: "tts.streams.thoughts.voice",
+        category: "TTS",
+        display_name: "Thoughts Voice",
+        description: "Voice for the thoughts window (empty = default voice)",
+        kind: SettingKind::Text { allow_empty: true },
+        apply: ApplyMode::Live,
+        get: |c| stream_voice(c, "thoughts"),
+        set: |c, v| set_stream_voice(c, "thoughts", v),
+    },
+    SettingDef {
+        key: "tts.streams.thoughts.rate",
+        category: "TTS",
+        display_name: "Thoughts Rate",
+        description: "Speech rate for the thoughts window (0 = default rate)",
+        kind: SettingKind::Float { min: 0.0, max: 2.0 },
+        apply: ApplyMode::Live,
+        get: |c| stream_rate(c, "thoughts"),
+        set: |c, v| set_stream_rate(c, "thoughts", v),
+    },
+    SettingDef {
+        key: "tts.streams.thoughts.pitch",
+        category: "TTS",
+        display_name: "Thoughts Pitch",
+        description: "Voice pitch for the thoughts window (0 = default pitch)",
+        kind: SettingKind::Float { min: 0.0, max: 2.0 },
+        apply: ApplyMode::Live,
+        get: |c| stream_pitch(c, "thoughts"),
+        set: |c, v| set_stream_pitch(c, "thoughts", v),
+    },
+    SettingDef {
+        key: "tts.streams.death.voice",
+        category: "TTS",
+        display_name: "Death Voice",
+        description: "Voice for the death window (empty = default voice)",
+        kind: SettingKind::Text { allow_empty: true },
+        apply: ApplyMode::Live,
+        get: |c| stream_voice(c, "death"),
+        set: |c, v| set_stream_voice(c, "death", v),
+    },
+    SettingDef {
+        key: "tts.streams.death.rate",
+        category: "TTS",
+        display_name: "Death Rate",
+        description: "Speech rate for the death window (0 = default rate)",
+        kind: SettingKind::Float { min: 0.0, max: 2.0 },
+        apply: ApplyMode::Live,
+        get: |c| stream_rate(c, "death"),
+        set: |c, v| set_stream_rate(c, "death", v),
+    },
+    SettingDef {
+        key: "tts.streams.death.pitch",
+        category: "TTS",
+        display_name: "Death Pitch",
+        description: "Voice pitch for the death window (0 = default pitch)",
+        kind: SettingKind::Float { min: 0.0, max: 2.0 },
+        apply: ApplyMode::Live,
+        get: |c| stream_pitch(c, "death"),
+        set: |c, v| set_stream_pitch(c, "death", v),
+    },
+    SettingDef {
+        key: "tts.streams.main.voice",
+        category: "TTS",
+        display_name: "Main Voice",
+        description: "Voice for the main window (empty = default voice)",
+        kind: SettingKind::Text { allow_empty: true },
+        apply: ApplyMode::Live,
+        get: |c| stream_voice(c, "main"),
+        set: |c, v| set_stream_voice(c, "main", v),
+    },
+    SettingDef {
+        key: "tts.streams.main.rate",
+        category: "TTS",
+        display_name: "Main Rate",
+        description: "Speech rate for the main window (0 = default rate)",
+        kind: SettingKind::Float { min: 0.0, max: 2.0 },
+        apply: ApplyMode::Live,
+        get: |c| stream_rate(c, "main"),
+        set: |c, v| set_stream_rate(c, "main", v),
+    },
+    SettingDef {
+        key: "tts.streams.main.pitch",
+        category: "TTS",
+        display_name: "Main Pitch",
+        description: "Voice pitch for the main window (0 = default pitch)",
+        kind: SettingKind::Float { min: 0.0, max: 2.0 },
+        apply: ApplyMode::Live,
+        get: |c| stream_pitch(c, "main"),
+        set: |c, v| set_stream_pitch(c, "main", v),
+    },
+    // Theme
+    SettingDef {
+        key: "active_theme",
+        category: "Theme",
+        display_name: "Active Theme",
+        description: "Currently active color theme",
+        kind: SettingKind::Text { allow_empty: false },
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::String(c.active_theme.clone()),
+        set: |c, v| c.active_theme = v.as_text().trim().to_string(),
+    },
+    SettingDef {
+        key: "ui.min_contrast",
+        category: "Theme",
+        display_name: "Minimum Contrast",
+        description: "Adjust text colors to this contrast ratio vs the background (0 = off, 4.5 = WCAG AA)",
+        kind: SettingKind::Float { min: 0.0, max: 21.0 },
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::Float(c.ui.min_contrast),
+        set: |c, v| c.ui.min_contrast = v.as_f64(),
+    },
+    SettingDef {
+        key: "theme_schedule.enabled",
+        category: "Theme",
+        display_name: "Auto Day/Night Theme",
+        description: "Switch between the day and night themes automatically",
+        kind: SettingKind::Bool,
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::Boolean(c.theme_schedule.enabled),
+        set: |c, v| c.theme_schedule.enabled = v.as_bool(),
+    },
+    SettingDef {
+        key: "theme_schedule.mode",
+        category: "Theme",
+        display_name: "Day/Night Source",
+        description: "time = use the schedule below, os = follow the OS dark mode",
+        kind: SettingKind::Choice(THEME_SCHEDULE_MODES),
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::String(c.theme_schedule.mode.clone()),
+        set: |c, v| c.theme_schedule.mode = v.as_text().to_string(),
+    },
+    SettingDef {
+        key: "theme_schedule.day_theme",
+        category: "Theme",
+        display_name: "Day Theme",
+        description: "Theme used during the day (or OS light mode)",
+        kind: SettingKind::Text { allow_empty: false },
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::String(c.theme_schedule.day_theme.clone()),
+        set: |c, v| c.theme_schedule.day_theme = v.as_text().trim().to_string(),
+    },
+    SettingDef {
+        key: "theme_schedule.night_theme",
+        category: "Theme",
+        display_name: "Night Theme",
+        description: "Theme used at night (or OS dark mode)",
+        kind: SettingKind::Text { allow_empty: false },
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::String(c.theme_schedule.night_theme.clone()),
+        set: |c, v| c.theme_schedule.night_theme = v.as_text().trim().to_string(),
+    },
+    SettingDef {
+        key: "theme_schedule.day_start",
+        category: "Theme",
+        display_name: "Day Starts At",
+        description: "Local time the day theme starts (HH:MM)",
+        kind: SettingKind::Text { allow_empty: false },
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::String(c.theme_schedule.day_start.clone()),
+        set: |c, v| c.theme_schedule.day_start = v.as_text().trim().to_string(),
+    },
+    SettingDef {
+        key: "theme_schedule.night_start",
+        category: "Theme",
+        display_name: "Night Starts At",
+        description: "Local time the night theme starts (HH:MM)",
+        kind: SettingKind::Text { allow_empty: false },
+        apply: ApplyMode::Live,
+        get: |c| SettingValue::String(c.theme_schedule.night_start.clone()),
+        set: |c, v| c.theme_schedule.night_start = v.as_text().trim().to_string(),
+    },
+];
+
+// Per-window TTS overrides: empty voice / zero rate or pitch means "use the default"
+
+fn stream_voice(c: &Config, stream: &str) -> SettingValue {
+    let voice = c.tts.streams.get(stream).and_then(|s| s.voice.clone());
+    SettingValue::String(voice.unwrap_or_default())
+}
+
+fn set_stream_voice(c: &mut Config, stream: &str, v: &SettingValue) {
+    let name = v.as_text().trim();
+    c.tts.stream_mut(stream).voice = (!name.is_empty()).then(|| name.to_string());
+}
+
+fn stream_rate(c: &Config, stream: &str) -> SettingValue {
+    SettingValue::Float(
+        c.tts
+            .streams
+            .get(stream)
+            .and_then(|s| s.rate)
+            .unwrap_or(0.0) as f64,
+    )
+}
+
+fn set_stream_rate(c: &mut Config, stream: &str, v: &SettingValue) {
+    let rate = v.as_f64() as f32;
+    c.tts.stream_mut(stream).rate = (rate > 0.0).then_some(rate.max(0.5));
+}
+
+fn stream_pitch(c: &Config, stream: &str) -> SettingValue {
+    SettingValue::Float(
+        c.tts
+            .streams
+            .get(stream)
+            .and_then(|s| s.pitch)
+            .unwrap_or(0.0) as f64,
+    )
+}
+
+fn set_stream_pitch(c: &mut Config, stream: &str, v: &SettingValue) {
+    let pitch = v.as_f64() as f32;
+    c.tts.stream_mut(stream).pitch = (pitch > 0.0).then_some(pitch.max(0.5));
+}
+
+/// Every registered setting, in editor order
+pub fn all() -> &'static [SettingDef] {
+    SETTINGS
+}
+
+/// Look up a setting by its config key ("ui.buffer_size")
+pub fn find(key: &str) -> Option<&'static SettingDef> {
+    SETTINGS.iter().find(|def| def.key == key)
+}
+
+/// Prefix for keys that live in sounds.toml rather than config.toml
+pub const SOUNDS_PREFIX: &str = "sounds.";
+
+/// A config field found by walking the serialized config
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReflectedSetting {
+    pub key: String, // Dotted path, "sounds." for sounds.toml entries
+    pub category: String,
+    pub display_name: String,
+    pub value: SettingValue,
+}
+
+/// Every editable config leaf that has no registry entry
+///
+/// Tables are walked in key order; booleans, numbers, strings and lists of
+/// strings (edited comma-separated) are listed. Arrays of tables and keys
+/// containing dots have no dotted path and are left to the config files.
+pub fn reflected(config: &Config) -> Vec<ReflectedSetting> {
+    let mut out = Vec::new();
+    let mut empty_lists = Vec::new();
+    match toml::Value::try_from(config) {
+        Ok(tree) => walk(&tree, "", &mut out, &mut empty_lists),
+        Err(e) => tracing::warn!("Failed to serialize config for the settings editor: {}", e),
+    }
+    if let Ok(tree) = toml::Value::try_from(&config.sound_events) {
+        walk(
+            &tree,
+            SOUNDS_PREFIX.trim_end_matches('.'),
+            &mut out,
+            &mut empty_lists,
+        );
+    }
+    out.retain(|setting| find(&setting.key).is_none());
+    // An empty array may be a list of tables; keep it only if strings fit
+    out.retain(|setting| {
+        !empty_lists.contains(&setting.key)
+            || config
+                .clone()
+                .set_setting(&setting.key, &SettingValue::String("x".to_string()))
+                .is_ok()
+    });
+    out
+}
+
+fn walk(
+    node: &toml::Value,
+    path: &str,
+    out: &mut Vec<ReflectedSetting>,
+    empty_lists: &mut Vec<String>,
+) {
+    if let toml::Value::Table(table) = node {
+        for (name, child) in table {
+            if name.is_empty() || name.contains('.') {
+                continue;
+            }
+            let child_path = if path.is_empty() {
+                name.clone()
+            } else {
+                format!("{}.{}", path, name)
+            };
+            walk(child, &child_path, out, empty_lists);
+        }
+        return;
+    }
+    let value = match node {
+        toml::Value::Boolean(b) => SettingValue::Boolean(*b),
+        toml::Value::Integer(n) => SettingValue::Number(*n),
+        toml::Value::Float(f) => SettingValue::Float(*f),
+        toml::Value::String(s) => SettingValue::String(s.clone()),
+        toml::Value::Array(items) if items.iter().all(toml::Value::is_str) => {
+            if items.is_empty() {
+                empty_lists.push(path.to_string());
+            }
+            let items: Vec<&str> = items.iter().filter_map(toml::Value::as_str).collect();
+            SettingValue::String(items.join(", "))
+        }
+        _ => return,
+    };
+    let (section, rest) = path.split_once('.').unwrap_or(("", path));
+    out.push(ReflectedSetting {
+        key: path.to_string(),
+        category: section_category(section),
+        display_name: rest
+            .split('.')
+            .map(humanize)
+            .collect::<Vec<_>>()
+            .join(" / "),
+        value,
+    });
+}
+
+/// Registry category for a top-level table, else its name made readable
+fn section_category(section: &str) -> String {
+    if section.is_empty() {
+        return "General".to_string();
+    }
+    if section == SOUNDS_PREFIX.trim_end_matches('.') {
+        return "Sound Events".to_string();
+    }
+    SETTINGS
+        .iter()
+        .find(|def| def.key.split('.').next() == Some(section))
+        .map(|def| def.category.to_string())
+        .unwrap_or_else(|| humanize(section))
+}
+
+/// "poll_timeout_ms" -> "Poll Timeout Ms", with the usual acronyms capitalized
+fn humanize(segment: &str) -> String {
+    segment
+        .split('_')
+        .filter(|word| !word.is_empty())
+        .map(|word| match word {
+            "ui" | "tts" | "stt" | "gui" | "url" | "id" | "ms" | "rt" | "ct" => word.to_uppercase(),
+            _ => {
+                let mut chars = word.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_uppercase().chain(chars).collect())
+                    .unwrap_or_default()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Replace one serialized leaf, keeping its TOML type
+fn replace_leaf(tree: &mut toml::Value, path: &str, value: &SettingValue) -> Result<()> {
+    let mut node = tree;
+    for segment in path.split('.') {
+        node = node
+            .get_mut(segment)
+            .with_context(|| format!("Unknown setting '{}'", path))?;
+    }
+    *node = match (&*node, value) {
+        (toml::Value::Boolean(_), SettingValue::Boolean(b)) => toml::Value::Boolean(*b),
+        (toml::Value::Integer(_), SettingValue::Number(n)) => toml::Value::Integer(*n),
+        (toml::Value::Float(_), SettingValue::Float(f)) => toml::Value::Float(*f),
+        (toml::Value::Float(_), SettingValue::Number(n)) => toml::Value::Float(*n as f64),
+        (toml::Value::String(_), SettingValue::String(s)) => toml::Value::String(s.clone()),
+        (toml::Value::Array(_), SettingValue::String(s)) => toml::Value::Array(
+            s.split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(|item| toml::Value::String(item.to_string()))
+                .collect(),
+        ),
+        (toml::Value::Table(_) | toml::Value::Array(_) | toml::Value::Datetime(_), _) => {
+            bail!("{} can only be edited in the config file", path)
+        }
+        _ => bail!("Wrong value type for {}", path),
+    };
+    Ok(())
+}
+
+impl Config {
+    /// Validate and store a setting; side effects are the caller's business
+    ///
+    /// Keys outside the registry go through serde, so the config's own types
+    /// do the validation. Those apply after a restart, except sounds.toml
+    /// entries, which are read on every event.
+    pub fn set_setting(&mut self, key: &str, value: &SettingValue) -> Result<ApplyMode> {
+        let Some(def) = find(key) else {
+            return self.set_reflected(key, value);
+        };
+        if let Err(message) = def.validate(value) {
+            bail!(message);
+        }
+        (def.set)(self, value);
+        Ok(def.apply)
+    }
+
+    fn set_reflected(&mut self, key: &str, value: &SettingValue) -> Result<ApplyMode> {
+        if let Some(path) = key.strip_prefix(SOUNDS_PREFIX) {
+            let mut tree = toml::Value::try_from(&self.sound_events)?;
+            replace_leaf(&mut tree, path, value)?;
+            self.sound_events = tree
+                .try_into()
+                .map_err(|e| anyhow::anyhow!("Invalid value for {}: {}", key, e))?;
+            return Ok(ApplyMode::Live);
+        }
+
+        let mut tree = toml::Value::try_from(&*self)?;
+        replace_leaf(&mut tree, key, value)?;
+        let updated: Config = tree
+            .try_into()
+            .map_err(|e| anyhow::anyhow!("Invalid value for {}: {}", key, e))?;
+        // Fields kept in their own files don't round-trip through config.toml
+        let previous = std::mem::replace(self, updated);
+        self.highlights = previous.highlights;
+        self.keybinds = previous.keybinds;
+        self.keybind_modes = previous.keybind_modes;
+        self.saved_searches = previous.saved_searches;
+        self.snippets = previous.snippets;
+        self.sound_events = previous.sound_events;
+        self.watchlist = previous.watchlist;
+        self.character = previous.character;
+        self.colors = previous.colors;
+        Ok(ApplyMode::Restart)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_setting_validates_ranges_and_choices() {
+        let mut config = Config::default();
+
+        assert!(config
+            .set_setting("ui.poll_timeout_ms", &SettingValue::Number(0))
+            .is_err());
+        let apply = config
+            .set_setting("ui.poll_timeout_ms", &SettingValue::Number(30))
+            .unwrap();
+        assert_eq!(apply, ApplyMode::Live);
+        assert_eq!(config.ui.poll_timeout_ms, 30);
+
+        let border = find("ui.border_style").unwrap().value(&config);
+        assert!(matches!(border, SettingValue::Enum(_, ref options) if options.len() == 5));
+        assert!(config
+            .set_setting(
+                "ui.border_style",
+                &SettingValue::Enum("wavy".into(), vec![])
+            )
+            .is_err());
+
+        let apply = config
+            .set_setting("connection.port", &SettingValue::Number(4901))
+            .unwrap();
+        assert_eq!(apply, ApplyMode::Restart);
+        assert_eq!(config.connection.port, 4901);
+
+        assert!(config
+            .set_setting("no.such.key", &SettingValue::Boolean(true))
+            .is_err());
+    }
+
+    #[test]
+    fn test_tts_stream_overrides() {
+        let mut config = Config::default();
+        config
+            .set_setting("tts.streams.speech.rate", &SettingValue::Float(1.5))
+            .unwrap();
+        config
+            .set_setting(
+                "tts.streams.speech.voice",
+                &SettingValue::String("Samantha".into()),
+            )
+            .unwrap();
+        assert_eq!(
+            config.tts.voice_for("speech"),
+            (Some("Samantha".to_string()), 1.5, 1.0)
+        );
+        assert_eq!(config.tts.voice_for("thoughts"), (None, 1.0, 1.0));
+
+        // Zero / empty goes back to the defaults
+        config
+            .set_setting("tts.streams.speech.rate", &SettingValue::Float(0.0))
+            .unwrap();
+        config
+            .set_setting(
+                "tts.streams.speech.voice",
+                &SettingValue::String(" ".into()),
+            )
+            .unwrap();
+        config.tts.rate = 1.2;
+        assert_eq!(config.tts.voice_for("speech"), (None, 1.2, 1.0));
+        assert!(config
+            .set_setting("tts.pitch", &SettingValue::Float(3.0))
+            .is_err());
+    }
+
+    #[test]
+    fn test_reflected_settings_round_trip() {
+        let mut config = Config {
+            character: Some("Ann".to_string()),
+            ..Config::default()
+        };
+        config.sound_events.insert(
+            "whisper".to_string(),
+            crate::config::SoundEventConfig::default(),
+        );
+
+        let settings = reflected(&config);
+        assert!(settings.iter().all(|s| find(&s.key).is_none()));
+        let whisper = settings
+            .iter()
+            .find(|s| s.key == "sounds.whisper.enabled")
+            .unwrap();
+        assert_eq!(whisper.category, "Sound Events");
+        assert_eq!(whisper.display_name, "Whisper / Enabled");
+        // Empty lists of tables aren't offered as text
+        assert!(settings.iter().any(|s| s.key == "relay.patterns"));
+        assert!(!settings.iter().any(|s| s.key == "layout_mappings"));
+
+        // Pick a plain config.toml leaf of each kind the walk produced
+        let number = settings
+            .iter()
+            .find(|s| {
+                matches!(s.value, SettingValue::Number(_)) && !s.key.starts_with(SOUNDS_PREFIX)
+            })
+            .unwrap();
+        let apply = config
+            .set_setting(&number.key, &SettingValue::Number(7))
+            .unwrap();
+        assert_eq!(apply, ApplyMode::Restart);
+        let updated = reflected(&config);
+        let changed = updated.iter().find(|s| s.key == number.key).unwrap();
+        assert_eq!(changed.value, SettingValue::Number(7));
+        // Fields kept outside config.toml survive the round trip
+        assert_eq!(config.character.as_deref(), Some("Ann"));
+        assert!(config.sound_events.contains_key("whisper"));
+
+        let apply = config
+            .set_setting("sounds.whisper.enabled", &SettingValue::Boolean(false))
+            .unwrap();
+        assert_eq!(apply, ApplyMode::Live);
+        assert!(!config.sound_events["whisper"].enabled);
+
+        assert!(config
+            .set_setting(&number.key, &SettingValue::Boolean(true))
+            .is_err());
+        assert!(config
+            .set_setting("sounds.whisper.seconds", &SettingValue::Number(-1))
+            .is_err());
+    }
+}
@@ -0,0 +1,264 @@
+//! Percentage and flex window sizes.
+//!
+//! A layout's `cols`/`rows` are fixed cell counts, scaled in proportion when
+//! the terminal changes size. A window can instead set `width`/`height` to a
+//! share of the screen (`"30%"`) or a flex weight (`"1fr"`, `"2fr"`): flex
+//! windows take whatever space their neighbours leave, split by weight, so a
+//! main window between two fixed sidebars always fills the middle.
+//!
+//! Sizes are resolved whenever the layout is fitted to the terminal. Windows
+//! further along the row (or column) keep their gaps and move to make room,
+//! and `cols`/`rows` are rewritten with the result.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// A window width or height that depends on the screen size
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum SizeSpec {
+    /// Share of the screen, 0-100
+    Percent(f32),
+    /// Share of the space left over, by weight
+    Flex(u16),
+}
+
+impl FromStr for SizeSpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some(percent) = s.strip_suffix('%') {
+            return match percent.trim().parse::<f32>() {
+                Ok(p) if (0.0..=100.0).contains(&p) => Ok(Self::Percent(p)),
+                _ => Err(format!("'{}' is not a percentage between 0% and 100%", s)),
+            };
+        }
+        if s.eq_ignore_ascii_case("fill") {
+            return Ok(Self::Flex(1));
+        }
+        if let Some(weight) = s.strip_suffix("fr") {
+            return match weight.trim().parse::<u16>() {
+                Ok(w) if w > 0 => Ok(Self::Flex(w)),
+                _ => Err(format!("'{}' is not a flex weight like 1fr", s)),
+            };
+        }
+        Err(format!(
+            "'{}' is not a size: use a percentage (\"30%\") or flex weight (\"1fr\")",
+            s
+        ))
+    }
+}
+
+impl fmt::Display for SizeSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Percent(p) => write!(f, "{}%", p),
+            Self::Flex(w) => write!(f, "{}fr", w),
+        }
+    }
+}
+
+impl TryFrom<String> for SizeSpec {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<SizeSpec> for String {
+    fn from(spec: SizeSpec) -> Self {
+        spec.to_string()
+    }
+}
+
+/// One window along the axis being resolved
+#[derive(Debug, Clone)]
+pub struct Span {
+    pub start: u16,
+    pub len: u16,
+    pub spec: Option<SizeSpec>,
+    pub min: u16,
+    pub max: Option<u16>,
+    /// Where the window sits on the other axis, end exclusive
+    pub cross: (u16, u16),
+}
+
+impl Span {
+    fn end(&self) -> u16 {
+        self.start + self.len
+    }
+
+    /// Side by side: overlapping on the other axis
+    fn beside(&self, other: &Span) -> bool {
+        self.cross.0 < other.cross.1 && other.cross.0 < self.cross.1
+    }
+
+    fn clamp(&self, size: u16) -> u16 {
+        let size = size.max(self.min).max(1);
+        self.max.map_or(size, |max| size.min(max.max(1)))
+    }
+}
+
+/// New (start, len) for each span, laid out for a screen `base_extent` cells
+/// long, on a screen `extent` cells long
+pub fn resolve_axis(spans: &[Span], base_extent: u16, extent: u16) -> Vec<(u16, u16)> {
+    let mut sizes: Vec<u16> = spans
+        .iter()
+        .map(|span| match span.spec {
+            Some(SizeSpec::Percent(p)) => span.clamp((p * extent as f32 / 100.0).round() as u16),
+            Some(SizeSpec::Flex(_)) => span.clamp(0),
+            None => span.len,
+        })
+        .collect();
+
+    // Earliest first, so every window's neighbours before it are placed first
+    let mut order: Vec<usize> = (0..spans.len()).collect();
+    order.sort_by_key(|&i| spans[i].start);
+
+    // Space each flex window can't have: everything up to it, and everything
+    // after it out to the screen edge (keeping the gap the layout had there)
+    let starts = place(spans, &sizes, &order);
+    let mut tails = vec![0u16; spans.len()];
+    for &i in order.iter().rev() {
+        tails[i] = after(spans, i)
+            .into_iter()
+            .map(|j| (spans[j].start - spans[i].end()) + sizes[j] + tails[j])
+            .max()
+            .unwrap_or_else(|| base_extent.saturating_sub(spans[i].end()));
+    }
+
+    let flex_sizes: Vec<(usize, u16)> = (0..spans.len())
+        .filter_map(|i| match spans[i].spec {
+            Some(SizeSpec::Flex(weight)) => {
+                let slack = extent.saturating_sub(starts[i] + sizes[i] + tails[i]) as u32;
+                // Flex windows in the same row (or column) split the slack by
+                // weight; cumulative shares keep rounding from losing cells
+                let (mut before, mut total) = (0u32, 0u32);
+                for j in 0..spans.len() {
+                    let in_line = j == i
+                        || (spans[j].beside(&spans[i]) && !overlaps_along(&spans[i], &spans[j]));
+                    if let (true, Some(SizeSpec::Flex(w))) = (in_line, spans[j].spec) {
+                        total += w as u32;
+                        if spans[j].start < spans[i].start {
+                            before += w as u32;
+                        }
+                    }
+                }
+                let share = slack * (before + weight as u32) / total - slack * before / total;
+                Some((i, spans[i].clamp(sizes[i] + share as u16)))
+            }
+            _ => None,
+        })
+        .collect();
+    for (i, size) in flex_sizes {
+        sizes[i] = size;
+    }
+
+    let starts = place(spans, &sizes, &order);
+    starts.into_iter().zip(sizes).collect()
+}
+
+fn overlaps_along(a: &Span, b: &Span) -> bool {
+    a.start < b.end() && b.start < a.end()
+}
+
+/// The closest windows beside span `i` that end before it starts
+fn before(spans: &[Span], i: usize) -> Vec<usize> {
+    let candidates = (0..spans.len())
+        .filter(|&j| spans[j].beside(&spans[i]) && spans[j].end() <= spans[i].start);
+    let nearest = candidates.clone().map(|j| spans[j].end()).max();
+    candidates
+        .filter(|&j| Some(spans[j].end()) == nearest)
+        .collect()
+}
+
+/// The closest windows beside span `i` that start after it ends
+fn after(spans: &[Span], i: usize) -> Vec<usize> {
+    let candidates = (0..spans.len())
+        .filter(|&j| spans[j].beside(&spans[i]) && spans[j].start >= spans[i].end());
+    let nearest = candidates.clone().map(|j| spans[j].start).min();
+    candidates
+        .filter(|&j| Some(spans[j].start) == nearest)
+        .collect()
+}
+
+/// Start of each span given its neighbours' sizes: right after the neighbours
+/// before it, keeping the layout's gap
+fn place(spans: &[Span], sizes: &[u16], order: &[usize]) -> Vec<u16> {
+    let mut starts: Vec<u16> = spans.iter().map(|span| span.start).collect();
+    for &i in order {
+        if let Some(start) = before(spans, i)
+            .into_iter()
+            .map(|j| starts[j] + sizes[j] + (spans[i].start - spans[j].end()))
+            .max()
+        {
+            starts[i] = start;
+        }
+    }
+    starts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(start: u16, len: u16, spec: Option<&str>, cross: (u16, u16)) -> Span {
+        Span {
+            start,
+            len,
+            spec: spec.map(|s| s.parse().unwrap()),
+            min: 0,
+            max: None,
+            cross,
+        }
+    }
+
+    #[test]
+    fn test_parse_size_spec() {
+        assert_eq!("30%".parse::<SizeSpec>(), Ok(SizeSpec::Percent(30.0)));
+        assert_eq!("2fr".parse::<SizeSpec>(), Ok(SizeSpec::Flex(2)));
+        assert_eq!("fill".parse::<SizeSpec>(), Ok(SizeSpec::Flex(1)));
+        assert!("0fr".parse::<SizeSpec>().is_err());
+        assert!("120%".parse::<SizeSpec>().is_err());
+        assert!("wide".parse::<SizeSpec>().is_err());
+    }
+
+    #[test]
+    fn test_fill_window_takes_the_growth() {
+        // Sidebar | main (fill) | two stacked right panels, command line under it all
+        let spans = [
+            span(0, 20, None, (0, 30)),
+            span(20, 60, Some("1fr"), (0, 30)),
+            span(80, 20, None, (0, 15)),
+            span(80, 20, None, (15, 30)),
+            span(0, 100, Some("100%"), (30, 33)),
+        ];
+        assert_eq!(
+            resolve_axis(&spans, 100, 140),
+            vec![(0, 20), (20, 100), (120, 20), (120, 20), (0, 140)]
+        );
+    }
+
+    #[test]
+    fn test_fixed_windows_stay_put() {
+        let fixed = [span(2, 20, None, (0, 10)), span(25, 30, None, (0, 10))];
+        assert_eq!(resolve_axis(&fixed, 60, 200), vec![(2, 20), (25, 30)]);
+    }
+
+    #[test]
+    fn test_weights_split_the_slack() {
+        // A percentage window sits between the flexible ones
+        let spans = [
+            span(0, 10, Some("1fr"), (0, 10)),
+            span(10, 10, Some("20%"), (0, 10)),
+            span(20, 10, Some("3fr"), (0, 10)),
+        ];
+        assert_eq!(
+            resolve_axis(&spans, 30, 100),
+            vec![(0, 20), (20, 20), (40, 60)]
+        );
+    }
+}
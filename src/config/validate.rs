@@ -0,0 +1,585 @@
+//! `two-face config validate` and `two-face config schema`
+//!
+//! Validation reads a profile's config.toml, highlights.toml, keybinds.toml
+//! and colors.toml, plus the shared custom themes, and reports:
+//! - keys serde would silently ignore (typos, options from old versions),
+//!   found by round-tripping each file through its type and looking for
+//!   keys that didn't survive
+//! - regexes that don't compile (highlights, event patterns, relay rules)
+//! - colors that are neither `#RRGGBB` nor a palette name
+//! - keybinds whose key or action isn't recognised
+//!
+//! The schema is derived from the default config rather than maintained by
+//! hand, with descriptions from the settings registry. Objects stay open to
+//! extra keys, since optional settings that default to unset don't appear
+//! in the defaults.
+
+use crate::config::{ColorConfig, Config, HighlightPattern, KeyBindAction};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Map, Value as Json};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use toml::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// One problem found in a file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Issue {
+    pub severity: Severity,
+    pub file: PathBuf,
+    /// Dotted key path ("highlights.deaths.fg"), empty for the whole file
+    pub key: String,
+    pub message: String,
+}
+
+impl Issue {
+    /// "✗ highlights.toml: deaths.pattern: regex parse error ..."
+    pub fn describe(&self) -> String {
+        let icon = match self.severity {
+            Severity::Error => "✗",
+            Severity::Warning => "⚠",
+        };
+        let file = self
+            .file
+            .file_name()
+            .map(|f| f.to_string_lossy())
+            .unwrap_or_default();
+        if self.key.is_empty() {
+            format!("{} {}: {}", icon, file, self.message)
+        } else {
+            format!("{} {}: {}: {}", icon, file, self.key, self.message)
+        }
+    }
+}
+
+/// Collects issues for one file
+struct FileCheck<'a> {
+    file: &'a Path,
+    issues: &'a mut Vec<Issue>,
+}
+
+impl FileCheck<'_> {
+    fn push(&mut self, severity: Severity, key: &str, message: impl Into<String>) {
+        self.issues.push(Issue {
+            severity,
+            file: self.file.to_path_buf(),
+            key: key.to_string(),
+            message: message.into(),
+        });
+    }
+
+    fn regex(&mut self, key: &str, pattern: &str) {
+        if let Err(e) = regex::Regex::new(pattern) {
+            let first = e
+                .to_string()
+                .lines()
+                .last()
+                .unwrap_or_default()
+                .trim()
+                .to_string();
+            self.push(
+                Severity::Error,
+                key,
+                format!("invalid regex {:?}: {}", pattern, first),
+            );
+        }
+    }
+
+    fn color(&mut self, key: &str, color: &str, palette: &HashSet<String>) {
+        if !valid_color(color, palette) {
+            self.push(
+                Severity::Error,
+                key,
+                format!("invalid color {:?} (use #RRGGBB or a palette name)", color),
+            );
+        }
+    }
+}
+
+/// `#RRGGBB`, a palette name, or empty/"none"/"-" for no color
+fn valid_color(color: &str, palette: &HashSet<String>) -> bool {
+    let color = color.trim();
+    if color.is_empty() || color.eq_ignore_ascii_case("none") || color == "-" {
+        return true;
+    }
+    if let Some(hex) = color.strip_prefix('#') {
+        return hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit());
+    }
+    palette.contains(&color.to_lowercase())
+}
+
+/// Keys in `value` that don't survive a round trip through `T`
+fn unknown_keys<T: DeserializeOwned + Serialize>(value: &Value) -> Result<Vec<String>, String> {
+    let parsed: T = value
+        .clone()
+        .try_into()
+        .map_err(|e: toml::de::Error| e.to_string())?;
+    let back = Value::try_from(&parsed).map_err(|e| e.to_string())?;
+    let mut unknown = Vec::new();
+    collect_unknown(value, &back, "", &mut unknown);
+    Ok(unknown)
+}
+
+fn collect_unknown(user: &Value, back: &Value, path: &str, out: &mut Vec<String>) {
+    let join = |key: &str| {
+        if path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", path, key)
+        }
+    };
+    match (user, back) {
+        (Value::Table(user), Value::Table(back)) => {
+            for (key, value) in user {
+                match back.get(key) {
+                    Some(back_value) => collect_unknown(value, back_value, &join(key), out),
+                    // Defaults that serialize to nothing (`bold = false`, `windows = []`)
+                    None if is_skippable_default(value) => {}
+                    None => out.push(join(key)),
+                }
+            }
+        }
+        (Value::Array(user), Value::Array(back)) if user.len() == back.len() => {
+            for (i, (user, back)) in user.iter().zip(back).enumerate() {
+                collect_unknown(user, back, &format!("{}[{}]", path, i), out);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn is_skippable_default(value: &Value) -> bool {
+    match value {
+        Value::Boolean(b) => !b,
+        Value::Array(a) => a.is_empty(),
+        Value::Table(t) => t.is_empty(),
+        _ => false,
+    }
+}
+
+/// Read and parse a TOML file; None (with an issue) if it can't be
+fn read_toml(check: &mut FileCheck) -> Option<Value> {
+    let contents = match std::fs::read_to_string(check.file) {
+        Ok(contents) => contents,
+        Err(e) => {
+            check.push(Severity::Error, "", format!("can't read: {}", e));
+            return None;
+        }
+    };
+    match contents.parse::<Value>() {
+        Ok(value) => Some(value),
+        Err(e) => {
+            check.push(
+                Severity::Error,
+                "",
+                format!("not valid TOML: {}", e.message()),
+            );
+            None
+        }
+    }
+}
+
+/// Old keys that are still read, with their new names
+const RENAMED_KEYS: &[(&str, &str)] = &[("ui.show_timestamps", "ui.timestamps.enabled")];
+
+/// Parse as `T`, reporting type errors and unknown keys
+fn check_shape<T: DeserializeOwned + Serialize>(check: &mut FileCheck, value: &Value) -> Option<T> {
+    match unknown_keys::<T>(value) {
+        Ok(unknown) => {
+            for key in unknown {
+                match RENAMED_KEYS.iter().find(|(old, _)| *old == key) {
+                    Some((_, new)) => check.push(
+                        Severity::Warning,
+                        &key,
+                        format!("renamed to {} (still read)", new),
+                    ),
+                    None => check.push(Severity::Warning, &key, "unknown key (ignored)"),
+                }
+            }
+        }
+        Err(e) => {
+            check.push(Severity::Error, "", e.trim().to_string());
+            return None;
+        }
+    }
+    value.clone().try_into().ok()
+}
+
+/// Every string under a color-like key: fg, bg, color, *_color, *_background
+fn color_fields(value: &Value, path: &str, out: &mut Vec<(String, String)>) {
+    match value {
+        Value::Table(table) => {
+            for (key, value) in table {
+                let key_path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                match value {
+                    Value::String(s)
+                        if matches!(key.as_str(), "fg" | "bg" | "color")
+                            || key.ends_with("_color")
+                            || key.ends_with("_background") =>
+                    {
+                        out.push((key_path, s.clone()))
+                    }
+                    _ => color_fields(value, &key_path, out),
+                }
+            }
+        }
+        Value::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                color_fields(item, &format!("{}[{}]", path, i), out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Validate a profile's files and the custom themes in `themes_dir`
+pub fn validate_files(
+    config_path: &Path,
+    highlights_path: &Path,
+    keybinds_path: &Path,
+    colors_path: &Path,
+    themes_dir: &Path,
+) -> Vec<Issue> {
+    let mut issues = Vec::new();
+
+    // colors.toml first: its palette names are valid colors everywhere else
+    let mut palette: HashSet<String> = ColorConfig::default()
+        .color_palette
+        .iter()
+        .map(|c| c.name.to_lowercase())
+        .collect();
+    if colors_path.exists() {
+        let mut check = FileCheck {
+            file: colors_path,
+            issues: &mut issues,
+        };
+        if let Some(value) = read_toml(&mut check) {
+            if let Some(colors) = check_shape::<ColorConfig>(&mut check, &value) {
+                palette.extend(colors.color_palette.iter().map(|c| c.name.to_lowercase()));
+            }
+            let mut colors = Vec::new();
+            color_fields(&value, "", &mut colors);
+            for (key, color) in colors {
+                check.color(&key, &color, &palette);
+            }
+        }
+    }
+
+    let mut actions = HashMap::new();
+    if config_path.exists() {
+        let mut check = FileCheck {
+            file: config_path,
+            issues: &mut issues,
+        };
+        if let Some(value) = read_toml(&mut check) {
+            if let Some(config) = check_shape::<Config>(&mut check, &value) {
+                for (name, pattern) in &config.event_patterns {
+                    check.regex(
+                        &format!("event_patterns.{}.pattern", name),
+                        &pattern.pattern,
+                    );
+                }
+                for (i, pattern) in config.relay.patterns.iter().enumerate() {
+                    check.regex(&format!("relay.patterns[{}]", i), pattern);
+                }
+                for (i, rule) in config.relay.redact.iter().enumerate() {
+                    check.regex(&format!("relay.redact[{}].pattern", i), &rule.pattern);
+                }
+                if !crate::config::TimestampConfig::format_is_valid(&config.ui.timestamps.format) {
+                    check.push(
+                        Severity::Error,
+                        "ui.timestamps.format",
+                        "not a valid strftime format",
+                    );
+                }
+                actions = config.actions;
+            }
+        }
+    } else {
+        issues.push(Issue {
+            severity: Severity::Warning,
+            file: config_path.to_path_buf(),
+            key: String::new(),
+            message: "not found (defaults are written on first run)".to_string(),
+        });
+    }
+
+    if highlights_path.exists() {
+        let mut check = FileCheck {
+            file: highlights_path,
+            issues: &mut issues,
+        };
+        if let Some(value) = read_toml(&mut check) {
+            if let Some(highlights) =
+                check_shape::<HashMap<String, HighlightPattern>>(&mut check, &value)
+            {
+                let mut names: Vec<_> = highlights.keys().collect();
+                names.sort();
+                for name in names {
+                    let highlight = &highlights[name];
+                    if !highlight.fast_parse {
+                        check.regex(&format!("{}.pattern", name), &highlight.pattern);
+                    }
+                    for (field, color) in [("fg", &highlight.fg), ("bg", &highlight.bg)] {
+                        if let Some(color) = color {
+                            check.color(&format!("{}.{}", name, field), color, &palette);
+                        }
+                    }
+                    if let Some(hours) = &highlight.quiet_hours {
+                        if let Err(e) = crate::core::trigger_gate::parse_quiet_hours(hours) {
+                            check.push(
+                                Severity::Error,
+                                &format!("{}.quiet_hours", name),
+                                e.to_string(),
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    if keybinds_path.exists() {
+        let mut check = FileCheck {
+            file: keybinds_path,
+            issues: &mut issues,
+        };
+        if let Some(value) = read_toml(&mut check) {
+            if let Some(keybinds) =
+                check_shape::<HashMap<String, KeyBindAction>>(&mut check, &value)
+            {
+                let mut keys: Vec<_> = keybinds.keys().collect();
+                keys.sort();
+                for key in keys {
+                    if crate::config::parse_key_sequence(key).is_none() {
+                        check.push(Severity::Error, key, "unrecognised key");
+                    }
+                    if let KeyBindAction::Action(action) = &keybinds[key] {
+                        if crate::config::KeyAction::from_str(action).is_none()
+                            && crate::core::actions::AppAction::resolve(action, &actions).is_none()
+                        {
+                            check.push(
+                                Severity::Error,
+                                key,
+                                format!("unknown action {:?}", action),
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    if let Ok(entries) = std::fs::read_dir(themes_dir) {
+        let mut paths: Vec<PathBuf> = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|e| e == "toml"))
+            .collect();
+        paths.sort();
+        for path in paths {
+            let mut check = FileCheck {
+                file: &path,
+                issues: &mut issues,
+            };
+            let Some(value) = read_toml(&mut check) else {
+                continue;
+            };
+            type ThemeData = crate::frontend::tui::theme_editor::ThemeData;
+            if check_shape::<ThemeData>(&mut check, &value).is_none() {
+                continue;
+            }
+            if let Value::Table(table) = &value {
+                for (key, color) in table {
+                    if let (false, Value::String(color)) =
+                        (matches!(key.as_str(), "name" | "description"), color)
+                    {
+                        check.color(key, color, &HashSet::new());
+                    }
+                }
+            }
+        }
+    }
+
+    issues
+}
+
+/// JSON schema for config.toml, derived from the defaults
+pub fn config_schema() -> Json {
+    let defaults = serde_json::to_value(Config::default()).unwrap_or(Json::Null);
+    let descriptions: HashMap<&str, &str> = crate::config::settings::all()
+        .iter()
+        .map(|s| (s.key, s.description))
+        .collect();
+    let mut schema = schema_for(&defaults, "", &descriptions);
+    if let Json::Object(object) = &mut schema {
+        object.insert(
+            "$schema".to_string(),
+            json!("http://json-schema.org/draft-07/schema#"),
+        );
+        object.insert("title".to_string(), json!("two-face config.toml"));
+    }
+    schema
+}
+
+fn schema_for(value: &Json, path: &str, descriptions: &HashMap<&str, &str>) -> Json {
+    let mut schema = match value {
+        Json::Bool(_) => json!({ "type": "boolean" }),
+        Json::Number(n) if n.is_f64() => json!({ "type": "number" }),
+        Json::Number(_) => json!({ "type": "integer" }),
+        Json::String(_) => json!({ "type": "string" }),
+        Json::Array(items) => match items.first() {
+            Some(first) => {
+                json!({ "type": "array", "items": schema_for(first, path, descriptions) })
+            }
+            None => json!({ "type": "array" }),
+        },
+        // An empty table in the defaults is a map of user-named entries
+        Json::Object(object) if object.is_empty() => json!({ "type": "object" }),
+        Json::Object(object) => {
+            let properties: Map<String, Json> = object
+                .iter()
+                .map(|(key, v)| {
+                    let key_path = if path.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{}.{}", path, key)
+                    };
+                    (key.clone(), schema_for(v, &key_path, descriptions))
+                })
+                .collect();
+            json!({ "type": "object", "properties": properties })
+        }
+        Json::Null => json!({}),
+    };
+    if let (Json::Object(object), Some(description)) = (&mut schema, descriptions.get(path)) {
+        object.insert("description".to_string(), json!(description));
+    }
+    schema
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Validate default config.toml (plus `ui_extra` keys) alongside the
+    /// given highlights and keybinds, returning each issue described
+    fn validate(
+        name: &str,
+        ui_extra: &[&str],
+        highlights: &str,
+        keybinds: &str,
+    ) -> Vec<(Severity, String)> {
+        let dir =
+            std::env::temp_dir().join(format!("two-face-validate-{}-{}", name, std::process::id()));
+        let themes = dir.join("themes");
+        std::fs::create_dir_all(&themes).unwrap();
+        let config = dir.join("config.toml");
+        let highlights_path = dir.join("highlights.toml");
+        let keybinds_path = dir.join("keybinds.toml");
+
+        let mut defaults = toml::Value::try_from(Config::default()).unwrap();
+        for key in ui_extra {
+            defaults["ui"]
+                .as_table_mut()
+                .unwrap()
+                .insert(key.to_string(), Value::Integer(5));
+        }
+        std::fs::write(&config, toml::to_string(&defaults).unwrap()).unwrap();
+        std::fs::write(&highlights_path, highlights).unwrap();
+        std::fs::write(&keybinds_path, keybinds).unwrap();
+
+        let issues = validate_files(
+            &config,
+            &highlights_path,
+            &keybinds_path,
+            &dir.join("colors.toml"),
+            &themes,
+        );
+        std::fs::remove_dir_all(&dir).ok();
+        issues.iter().map(|i| (i.severity, i.describe())).collect()
+    }
+
+    fn has(issues: &[(Severity, String)], text: &str) -> bool {
+        issues.iter().any(|(_, d)| d.contains(text))
+    }
+
+    #[test]
+    fn test_defaults_are_clean() {
+        assert_eq!(validate("clean", &[], "", ""), vec![]);
+    }
+
+    #[test]
+    fn test_unknown_config_key_is_a_warning() {
+        let issues = validate("unknown-key", &["buffer_sise"], "", "");
+        assert!(
+            has(&issues, "config.toml: ui.buffer_sise: unknown key"),
+            "{:#?}",
+            issues
+        );
+        assert_eq!(
+            issues
+                .iter()
+                .filter(|(s, _)| *s == Severity::Warning)
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_highlight_regex_and_colors() {
+        let issues = validate(
+            "highlights",
+            &[],
+            "[deaths]\npattern = \"dies(\"\nfg = \"#ff00zz\"\nbold = false\n\n[names]\npattern = \"Bob\"\nfg = \"red\"\nbg = \"nope\"\n",
+            "",
+        );
+        assert!(
+            has(&issues, "highlights.toml: deaths.pattern: invalid regex"),
+            "{:#?}",
+            issues
+        );
+        assert!(has(&issues, "deaths.fg: invalid color \"#ff00zz\""));
+        assert!(!has(&issues, "deaths.bold"));
+        assert!(!has(&issues, "names.fg"));
+        assert!(has(&issues, "names.bg: invalid color"));
+    }
+
+    #[test]
+    fn test_keybind_keys_and_actions() {
+        let issues = validate(
+            "keybinds",
+            &[],
+            "",
+            "\"ctrl+q\" = \"cursor_home\"\n\"hyper+q\" = \"quit\"\n\"f5\" = \"fly_away\"\n",
+        );
+        assert!(
+            has(&issues, "keybinds.toml: hyper+q: unrecognised key"),
+            "{:#?}",
+            issues
+        );
+        assert!(has(&issues, "f5: unknown action \"fly_away\""));
+        assert!(!has(&issues, "ctrl+q"));
+    }
+
+    #[test]
+    fn test_config_schema() {
+        let schema = config_schema();
+        assert_eq!(
+            schema["properties"]["ui"]["properties"]["buffer_size"]["type"],
+            "integer"
+        );
+        assert!(schema["properties"]["ui"]["properties"]["buffer_size"]["description"].is_string());
+        assert_eq!(schema["properties"]["actions"]["type"], "object");
+    }
+}
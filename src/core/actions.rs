@@ -0,0 +1,371 @@
+//! Central registry of named application actions.
+//!
+//! Menus, keybinds, dot commands, and user-defined custom actions all resolve
+//! to an `AppAction` so there is exactly one dispatch path (in main.rs, which
+//! has frontend access). Actions are named by the same strings menus already
+//! use ("settings", "loadlayout:<name>"), optionally prefixed with "action:",
+//! plus every keybind action name ("scroll_current_window_up_page").
+
+use crate::config::KeyAction;
+use crate::core::paste::PasteChoice;
+use crate::selection::CopyFormat;
+use anyhow::{bail, Result};
+use std::collections::HashMap;
+
+/// Custom actions may reference other custom actions, but not endlessly
+const MAX_CUSTOM_ACTION_DEPTH: usize = 8;
+
+/// Every action that can be triggered by name
+#[derive(Debug, Clone, PartialEq)]
+pub enum AppAction {
+    /// Keybind-level action handled entirely by AppCore (scrolling, search, TTS...)
+    Key(KeyAction),
+
+    // Layouts and windows
+    LoadLayout(String),
+    /// Make a saved layout the startup default and load it
+    SetDefaultLayout(String),
+    /// Stop offering the startup layout dialog
+    StopLayoutPrompt,
+    CreateWindow(String),
+    /// Open the window editor (None = show window picker first)
+    EditWindow(Option<String>),
+    ShowWindow(String),
+    /// Show widget picker (None) or the windows in one category
+    AddWindow(Option<String>),
+    /// Hide a window (None = show window picker first)
+    HideWindow(Option<String>),
+    ListWindows,
+
+    // Configuration browsers and forms
+    Highlights,
+    AddHighlight,
+    Keybinds,
+    AddKeybind,
+    Colors,
+    AddColor,
+    UiColors,
+    SpellColors,
+    AddSpellColor,
+    /// Review unsaved highlight/keybind/color edits before writing them
+    ConfigDiff,
+    Settings,
+    /// Settings editor showing only the TTS section (voices per window)
+    TtsSettings,
+    Themes,
+    SetTheme(String),
+    EditTheme,
+    /// Re-read the terminal's palette and switch to the "terminal" theme
+    TerminalTheme,
+
+    /// Turn terminal mouse capture on/off (None = toggle)
+    MouseCapture(Option<bool>),
+
+    /// Copy the last selection again, as plain text, ANSI or HTML
+    CopySelection(crate::selection::CopyFormat),
+    /// Keyboard cursor in the focused text window for selecting and copying
+    CopyMode,
+    /// Multi-line editor for composing several commands to send in order
+    Compose,
+    /// Snippet browser
+    Snippets,
+    /// Run a snippet, asking for its placeholders
+    RunSnippet(String),
+    /// Write or rewrite a snippet's text in the composer
+    EditSnippet(String),
+    /// Answer the multi-line paste dialog
+    Paste(crate::core::paste::PasteChoice),
+
+    // Search and tabs
+    Search {
+        window: String,
+        pattern: String,
+    },
+    /// Show the next page of held output ("-- more --") in the main window
+    More,
+    /// Write a text window's buffer to a file (None = timestamped name);
+    /// `force` allows overwriting an existing file
+    Dump {
+        window: String,
+        file: Option<String>,
+        force: bool,
+    },
+    NextTab,
+    PrevTab,
+    GoNew,
+
+    /// Switch keybind mode (None = back to base keybinds)
+    SetMode(Option<String>),
+
+    /// User-defined sequence of actions from `[actions]` in config.toml
+    Custom(String),
+}
+
+/// Argument-free built-in action names, for listing and completion
+pub const BUILTIN_ACTION_NAMES: &[&str] = &[
+    "addwindow",
+    "hidewindow",
+    "editwindow",
+    "listwindows",
+    "nolayoutprompt",
+    "highlights",
+    "addhighlight",
+    "keybinds",
+    "addkeybind",
+    "colors",
+    "addcolor",
+    "uicolors",
+    "spellcolors",
+    "addspellcolor",
+    "configdiff",
+    "settings",
+    "ttssettings",
+    "themes",
+    "edittheme",
+    "terminaltheme",
+    "togglemouse",
+    "copytext",
+    "copyansi",
+    "copyhtml",
+    "copymode",
+    "compose",
+    "snippets",
+    "nexttab",
+    "prevtab",
+    "gonew",
+    "more",
+];
+
+impl AppAction {
+    /// Parse a built-in action name (with or without the "action:" prefix)
+    pub fn parse(name: &str) -> Option<Self> {
+        let name = name.strip_prefix("action:").unwrap_or(name).trim();
+
+        if let Some(key_action) = KeyAction::from_str(name) {
+            return Some(Self::Key(key_action));
+        }
+
+        // Parameterized actions: "<verb>:<argument>"
+        if let Some((verb, arg)) = name.split_once(':') {
+            let arg = arg.to_string();
+            return match verb {
+                "loadlayout" => Some(Self::LoadLayout(arg)),
+                "defaultlayout" => Some(Self::SetDefaultLayout(arg)),
+                "createwindow" => Some(Self::CreateWindow(arg)),
+                "editwindow" => Some(Self::EditWindow(Some(arg))),
+                "showwindow" => Some(Self::ShowWindow(arg)),
+                "addwindow" => Some(Self::AddWindow(Some(arg))),
+                "hidewindow" => Some(Self::HideWindow(Some(arg))),
+                "settheme" => Some(Self::SetTheme(arg)),
+                "snippet" => Some(Self::RunSnippet(arg)),
+                "editsnippet" => Some(Self::EditSnippet(arg)),
+                "mouse" => match arg.as_str() {
+                    "on" => Some(Self::MouseCapture(Some(true))),
+                    "off" => Some(Self::MouseCapture(Some(false))),
+                    _ => None,
+                },
+                "mode" => Some(Self::SetMode(match arg.as_str() {
+                    "off" | "default" | "none" => None,
+                    _ => Some(arg),
+                })),
+                "search" => {
+                    let (window, pattern) = arg.split_once(':')?;
+                    Some(Self::Search {
+                        window: window.to_string(),
+                        pattern: pattern.to_string(),
+                    })
+                }
+                "dump" | "dumpforce" => {
+                    let (window, file) = match arg.split_once(':') {
+                        Some((window, file)) => (window, Some(file.to_string())),
+                        None => (arg.as_str(), None),
+                    };
+                    Some(Self::Dump {
+                        window: window.to_string(),
+                        file: file.filter(|f| !f.is_empty()),
+                        force: verb == "dumpforce",
+                    })
+                }
+                _ => None,
+            };
+        }
+
+        match name {
+            "addwindow" => Some(Self::AddWindow(None)),
+            "hidewindow" => Some(Self::HideWindow(None)),
+            "editwindow" => Some(Self::EditWindow(None)),
+            "listwindows" => Some(Self::ListWindows),
+            "nolayoutprompt" => Some(Self::StopLayoutPrompt),
+            "highlights" => Some(Self::Highlights),
+            "addhighlight" => Some(Self::AddHighlight),
+            "keybinds" => Some(Self::Keybinds),
+            "addkeybind" => Some(Self::AddKeybind),
+            "colors" => Some(Self::Colors),
+            "addcolor" => Some(Self::AddColor),
+            "uicolors" => Some(Self::UiColors),
+            "spellcolors" => Some(Self::SpellColors),
+            "addspellcolor" => Some(Self::AddSpellColor),
+            "configdiff" => Some(Self::ConfigDiff),
+            "settings" => Some(Self::Settings),
+            "ttssettings" => Some(Self::TtsSettings),
+            "themes" => Some(Self::Themes),
+            "edittheme" => Some(Self::EditTheme),
+            "terminaltheme" => Some(Self::TerminalTheme),
+            "togglemouse" => Some(Self::MouseCapture(None)),
+            "copytext" => Some(Self::CopySelection(CopyFormat::Plain)),
+            "copyansi" => Some(Self::CopySelection(CopyFormat::Ansi)),
+            "copyhtml" => Some(Self::CopySelection(CopyFormat::Html)),
+            "copymode" => Some(Self::CopyMode),
+            "compose" => Some(Self::Compose),
+            "snippets" => Some(Self::Snippets),
+            "pasteall" => Some(Self::Paste(PasteChoice::All)),
+            "pastefirst" => Some(Self::Paste(PasteChoice::First)),
+            "pasteedit" => Some(Self::Paste(PasteChoice::Edit)),
+            "nexttab" => Some(Self::NextTab),
+            "prevtab" => Some(Self::PrevTab),
+            "gonew" => Some(Self::GoNew),
+            "more" => Some(Self::More),
+            _ => None,
+        }
+    }
+
+    /// Resolve a name against built-ins first, then the user's custom actions
+    pub fn resolve(name: &str, custom: &HashMap<String, Vec<String>>) -> Option<Self> {
+        Self::parse(name).or_else(|| {
+            let name = name.strip_prefix("action:").unwrap_or(name).trim();
+            custom
+                .contains_key(name)
+                .then(|| Self::Custom(name.to_string()))
+        })
+    }
+}
+
+/// Flatten a custom action into the built-in actions it runs, in order
+///
+/// Nested custom actions are expanded in place. Unknown steps and reference
+/// cycles are reported as errors so nothing runs half-way.
+pub fn expand_custom_action(
+    name: &str,
+    custom: &HashMap<String, Vec<String>>,
+) -> Result<Vec<AppAction>> {
+    let mut steps = Vec::new();
+    expand_into(name, custom, 0, &mut steps)?;
+    Ok(steps)
+}
+
+fn expand_into(
+    name: &str,
+    custom: &HashMap<String, Vec<String>>,
+    depth: usize,
+    out: &mut Vec<AppAction>,
+) -> Result<()> {
+    if depth >= MAX_CUSTOM_ACTION_DEPTH {
+        bail!("Custom action '{}' nests too deeply (cycle?)", name);
+    }
+    let Some(steps) = custom.get(name) else {
+        bail!("Unknown custom action '{}'", name);
+    };
+
+    for step in steps {
+        match AppAction::resolve(step, custom) {
+            Some(AppAction::Custom(inner)) => expand_into(&inner, custom, depth + 1, out)?,
+            Some(action) => out.push(action),
+            None => bail!("Custom action '{}': unknown step '{}'", name, step),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_builtin_actions() {
+        assert_eq!(
+            AppAction::parse("action:settings"),
+            Some(AppAction::Settings)
+        );
+        assert_eq!(AppAction::parse("nexttab"), Some(AppAction::NextTab));
+        assert_eq!(
+            AppAction::parse("action:loadlayout:hunting"),
+            Some(AppAction::LoadLayout("hunting".to_string()))
+        );
+        assert_eq!(
+            AppAction::parse("action:search:main:foo:bar"),
+            Some(AppAction::Search {
+                window: "main".to_string(),
+                pattern: "foo:bar".to_string()
+            })
+        );
+        assert_eq!(
+            AppAction::parse("start_search"),
+            Some(AppAction::Key(KeyAction::StartSearch))
+        );
+        assert_eq!(
+            AppAction::parse("action:mouse:off"),
+            Some(AppAction::MouseCapture(Some(false)))
+        );
+        assert_eq!(
+            AppAction::parse("togglemouse"),
+            Some(AppAction::MouseCapture(None))
+        );
+        assert_eq!(
+            AppAction::parse("action:copyhtml"),
+            Some(AppAction::CopySelection(CopyFormat::Html))
+        );
+        assert_eq!(AppAction::parse("copymode"), Some(AppAction::CopyMode));
+        assert_eq!(AppAction::parse("action:compose"), Some(AppAction::Compose));
+        assert_eq!(
+            AppAction::parse("snippet:bounty-check"),
+            Some(AppAction::RunSnippet("bounty-check".to_string()))
+        );
+        assert_eq!(
+            AppAction::parse("action:dump:main:C:\\logs\\hunt.txt"),
+            Some(AppAction::Dump {
+                window: "main".to_string(),
+                file: Some("C:\\logs\\hunt.txt".to_string()),
+                force: false,
+            })
+        );
+        assert_eq!(
+            AppAction::parse("action:dumpforce:main:hunt"),
+            Some(AppAction::Dump {
+                window: "main".to_string(),
+                file: Some("hunt".to_string()),
+                force: true,
+            })
+        );
+        assert_eq!(AppAction::parse("mouse:maybe"), None);
+        assert_eq!(AppAction::parse("not_an_action"), None);
+    }
+
+    #[test]
+    fn test_expand_custom_action() {
+        let mut custom = HashMap::new();
+        custom.insert(
+            "review".to_string(),
+            vec![
+                "nexttab".to_string(),
+                "scroll_current_window_up_page".to_string(),
+            ],
+        );
+        custom.insert(
+            "review_twice".to_string(),
+            vec!["review".to_string(), "review".to_string()],
+        );
+        custom.insert("loop".to_string(), vec!["loop".to_string()]);
+        custom.insert("broken".to_string(), vec!["no_such_step".to_string()]);
+
+        assert_eq!(
+            AppAction::resolve("action:review", &custom),
+            Some(AppAction::Custom("review".to_string()))
+        );
+        assert_eq!(
+            expand_custom_action("review_twice", &custom).unwrap().len(),
+            4
+        );
+        assert!(expand_custom_action("loop", &custom).is_err());
+        assert!(expand_custom_action("broken", &custom).is_err());
+    }
+}
@@ -0,0 +1,164 @@
+//! Away-from-keyboard tracking
+//!
+//! Keyboard and mouse input resets the idle clock. After `afk.minutes`
+//! without any, the session is marked AFK: the command input title says so,
+//! whispers can get a one-time `afk.auto_reply` per sender, and what was
+//! missed is logged to the `afk` window (or replayed in main on return).
+
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+/// Missed lines kept for the return summary when there's no afk window
+const MAX_MISSED_LINES: usize = 100;
+
+/// Idle clock plus what happened while away
+#[derive(Debug)]
+pub struct AfkTracker {
+    last_input: Instant,
+    away_since: Option<Instant>,
+    /// Whisperers already auto-replied to during this absence
+    replied: HashSet<String>,
+    /// Lines logged while away, for the summary on return
+    missed: Vec<String>,
+    missed_total: usize,
+}
+
+/// How an absence ended: how long it lasted and the lines that were missed
+#[derive(Debug, PartialEq)]
+pub struct AfkReturn {
+    pub away: Duration,
+    pub missed: Vec<String>,
+    pub missed_total: usize,
+}
+
+impl AfkTracker {
+    pub fn new(now: Instant) -> Self {
+        Self {
+            last_input: now,
+            away_since: None,
+            replied: HashSet::new(),
+            missed: Vec::new(),
+            missed_total: 0,
+        }
+    }
+
+    /// Time since the last keyboard or mouse input
+    pub fn idle(&self, now: Instant) -> Duration {
+        now.saturating_duration_since(self.last_input)
+    }
+
+    pub fn is_away(&self) -> bool {
+        self.away_since.is_some()
+    }
+
+    /// Note user input; returns the absence it ended, if any
+    pub fn record_input(&mut self, now: Instant) -> Option<AfkReturn> {
+        self.last_input = now;
+        let since = self.away_since.take()?;
+        self.replied.clear();
+        Some(AfkReturn {
+            away: now.saturating_duration_since(since),
+            missed: std::mem::take(&mut self.missed),
+            missed_total: std::mem::take(&mut self.missed_total),
+        })
+    }
+
+    /// Mark the session away now (`.afk`)
+    pub fn set_away(&mut self, now: Instant) {
+        if self.away_since.is_none() {
+            self.away_since = Some(now);
+        }
+    }
+
+    /// Go AFK once idle for `after` (None = automatic AFK off); true when it just happened
+    pub fn poll(&mut self, now: Instant, after: Option<Duration>) -> bool {
+        match after {
+            Some(after) if !self.is_away() && self.idle(now) >= after => {
+                // Away since the last input, not since this poll
+                self.away_since = Some(self.last_input);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether a whisper from `name` still gets an auto-reply this absence
+    pub fn take_reply(&mut self, name: &str) -> bool {
+        self.is_away() && self.replied.insert(name.to_ascii_lowercase())
+    }
+
+    /// Remember a line that arrived while away
+    pub fn record_missed(&mut self, line: String) {
+        self.missed_total += 1;
+        if self.missed.len() < MAX_MISSED_LINES {
+            self.missed.push(line);
+        }
+    }
+}
+
+/// The sender of a whisper meant for us ("Ann whispers, ..." / "Ann whispers to you, ...")
+///
+/// Anchored at the start of the line, so quoted or relayed text doesn't count.
+pub fn whisperer(text: &str) -> Option<&str> {
+    let (name, rest) = text.trim().split_once(' ')?;
+    let rest = rest.strip_prefix("whispers")?;
+    let rest = rest.strip_prefix(" to you").unwrap_or(rest);
+    let named = !name.is_empty() && name != "You" && name.chars().all(|c| c.is_alphabetic());
+    (named && rest.starts_with(',')).then_some(name)
+}
+
+/// "12 min" / "2h 5m"
+pub fn format_away(away: Duration) -> String {
+    let minutes = away.as_secs() / 60;
+    match (minutes / 60, minutes % 60) {
+        (0, m) => format!("{} min", m),
+        (h, 0) => format!("{}h", h),
+        (h, m) => format!("{}h {}m", h, m),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_afk_tracker() {
+        let start = Instant::now();
+        let mut afk = AfkTracker::new(start);
+        let after = Some(Duration::from_secs(600));
+        assert!(!afk.poll(start + Duration::from_secs(599), after));
+        assert!(!afk.poll(start + Duration::from_secs(3600), None));
+        assert!(afk.poll(start + Duration::from_secs(600), after));
+        assert!(afk.is_away());
+        assert!(!afk.poll(start + Duration::from_secs(700), after));
+
+        assert!(afk.take_reply("Ann"));
+        assert!(!afk.take_reply("ann"));
+        afk.record_missed("Ann whispers, \"hi\"".to_string());
+
+        let back = afk.record_input(start + Duration::from_secs(1500)).unwrap();
+        assert_eq!(format_away(back.away), "25 min");
+        assert_eq!(back.missed, vec!["Ann whispers, \"hi\"".to_string()]);
+        assert_eq!(back.missed_total, 1);
+        assert!(!afk.is_away());
+        assert!(!afk.take_reply("Ann"));
+        assert!(afk
+            .record_input(start + Duration::from_secs(1501))
+            .is_none());
+
+        afk.set_away(start + Duration::from_secs(1502));
+        assert!(afk.take_reply("Ann"));
+    }
+
+    #[test]
+    fn test_whisperer() {
+        assert_eq!(whisperer("Ann whispers, \"brb\""), Some("Ann"));
+        assert_eq!(whisperer("Ann whispers to you, \"brb\""), Some("Ann"));
+        assert_eq!(whisperer("You whisper to Ann, \"brb\""), None);
+        assert_eq!(whisperer("Ann says, \"whispers, they said\""), None);
+        assert_eq!(whisperer("Bob says, \"Ann whispers, hi\""), None);
+        assert_eq!(whisperer("Ann whispers to Bob, \"brb\""), None);
+        assert_eq!(format_away(Duration::from_secs(125 * 60)), "2h 5m");
+        assert_eq!(format_away(Duration::from_secs(120 * 60)), "2h");
+    }
+}
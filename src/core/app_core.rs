@@ -10,9 +10,12 @@ use crate::core::{GameState, MessageProcessor};
 use crate::data::*;
 use crate::parser::{ParsedElement, XmlParser};
 use crate::performance::PerformanceStats;
-use anyhow::Result;
+use anyhow::{Context, Result};
 use std::collections::HashMap;
 
+/// Most lines `.widget <name> add` keeps; the oldest go first
+const SCRIPT_WIDGET_MAX_LINES: usize = 500;
+
 /// Pending menu request for correlation
 #[derive(Clone, Debug)]
 pub struct PendingMenuRequest {
@@ -20,6 +23,28 @@ pub struct PendingMenuRequest {
     pub noun: String,
 }
 
+/// Result of applying a setting from the settings editor
+#[derive(Debug, Clone, PartialEq)]
+pub struct SettingApplied {
+    /// The new value is saved but only takes effect after a restart
+    pub restart_required: bool,
+    /// Action string to dispatch to finish applying (e.g. a theme switch)
+    pub follow_up: Option<String>,
+}
+
+/// Result of feeding a key press through the chord (multi-key keybind) matcher
+#[derive(Clone, Debug)]
+pub enum ChordResult {
+    /// Key isn't part of any chord - handle it normally
+    NotChord,
+    /// Key extended a chord prefix - wait for the next key
+    Pending,
+    /// Key completed a chord - run its action
+    Complete(crate::config::KeyBindAction),
+    /// Key broke an in-progress chord - swallow it
+    Cancelled,
+}
+
 /// Core application state - frontend-agnostic
 pub struct AppCore {
     // === Configuration ===
@@ -36,6 +61,27 @@ pub struct AppCore {
     /// Game session state (connection, character, room, vitals, etc.)
     pub game_state: GameState,
 
+    /// Silvers, boxes and gems picked up, per hunt and for the session
+    pub loot: crate::data::LootTracker,
+
+    /// Experience, kills and deaths this session
+    pub session: crate::core::session_stats::SessionStats,
+
+    /// Adventurer's Guild task parsed from the bounty stream
+    pub bounty: crate::data::BountyTracker,
+
+    /// What's in each container, from LOOK IN and put/get lines
+    pub containers: crate::data::ContainerTracker,
+
+    /// Creature danger database (bundled plus ~/.two-face/creatures.toml)
+    pub creatures: crate::core::creatures::CreatureDb,
+
+    /// Dangerous creatures already announced in this room, and the room
+    creatures_warned: (Option<String>, std::collections::HashSet<String>),
+
+    /// Friends and enemies from watchlist.toml seen online this session
+    pub watch: crate::data::WatchTracker,
+
     /// UI state (windows, focus, input, popups, etc.)
     pub ui_state: UiState,
 
@@ -88,6 +134,84 @@ pub struct AppCore {
     /// Text-to-Speech manager for accessibility
     pub tts_manager: crate::tts::TtsManager,
 
+    /// Speech-to-Text (push-to-talk dictation) manager for accessibility
+    pub stt_manager: crate::stt::SttManager,
+
+    /// Read-only HTTP spectator view (no-op unless enabled and compiled in)
+    pub spectator: crate::spectator::Spectator,
+
+    /// Plugins from ~/.two-face/plugins/ (empty unless compiled in)
+    pub plugins: crate::plugins::PluginHost,
+
+    /// Plugin actions waiting for the main loop (sends need the network channel)
+    plugin_actions: Vec<crate::plugins::PluginAction>,
+
+    /// Read-only raw stream mirror for companion tools (no-op unless enabled)
+    pub passthrough: crate::passthrough::Passthrough,
+
+    /// Local WebSocket API for companion apps (no-op unless enabled)
+    pub websocket: crate::websocket::WebSocketApi,
+
+    /// Webhook relay for selected lines (no-op unless enabled)
+    pub relay: crate::relay::Relay,
+
+    /// Phone notifications for deaths, whispers while away and disconnects
+    pub push: crate::push::PushNotifier,
+
+    /// Idle clock and what was missed while AFK
+    afk: crate::core::afk::AfkTracker,
+
+    /// Auto-replies to whispers received while AFK, sent by the main loop
+    afk_replies: Vec<String>,
+
+    /// QUIT/EXIT was sent, so the disconnect that follows isn't news
+    quitting_game: bool,
+
+    /// Connected, and `[on_connect]` commands go out at the first prompt
+    on_connect_pending: bool,
+
+    /// Day/night theme switching (idle unless `[theme_schedule]` is enabled)
+    pub theme_scheduler: crate::core::theme_schedule::ThemeScheduler,
+
+    /// Roundtime/spell timers for sounds.toml events
+    pub sound_event_tracker: crate::core::sound_events::SoundEventTracker,
+
+    /// Master mute for sound effects (`.mute`, toggle_sound; not saved)
+    pub sound_muted: bool,
+
+    /// Sounds are held back until this time (`.dnd <minutes>`)
+    pub do_not_disturb_until: Option<std::time::Instant>,
+
+    /// Cooldowns and fire counts for highlight sounds
+    pub trigger_gate: crate::core::trigger_gate::TriggerGate,
+
+    /// Does the terminal have focus? (for highlights with `only_when_unfocused`)
+    pub terminal_focused: bool,
+
+    /// Highlights with sounds, compiled once for matching server lines
+    sound_triggers: Vec<crate::core::parse_worker::SoundTrigger>,
+
+    /// Background parser for server text (None = parse on this thread)
+    parse_worker: Option<crate::core::parse_worker::ParseWorker>,
+
+    /// `.compact on|off` (None = automatic below `ui.compact_width`)
+    pub compact_override: Option<bool>,
+
+    /// Compact profile in effect (narrow terminal or `.compact on`)
+    compact_on: bool,
+
+    /// Borders/titles compact or screen reader mode replaced (Some while either is on)
+    compact_chrome: Option<HashMap<String, crate::core::compact::WindowChrome>>,
+
+    /// Screen reader mode: plain chrome, spoken output, review buffer
+    screen_reader_on: bool,
+
+    /// Recent output for review_previous/review_next
+    review: crate::core::screen_reader::ReviewBuffer,
+
+    /// Web links seen in game text, for cycle_recent_urls/open_recent_url
+    recent_urls: crate::core::urls::RecentUrls,
+
     // === Navigation State ===
     /// Navigation room ID from <nav rm='...'/>
     pub nav_room_id: Option<String>,
@@ -115,6 +239,9 @@ pub struct AppCore {
     /// Dirty flag - true if state changed and needs re-render
     pub needs_render: bool,
 
+    /// Server data or a timer changed window contents; only the windows that changed are redrawn
+    pub content_changed: bool,
+
     /// Track if current chunk has main stream text
     pub chunk_has_main_text: bool,
 
@@ -134,6 +261,31 @@ pub struct AppCore {
     /// Runtime keybind map for fast O(1) lookups (KeyEvent -> KeyBindAction)
     /// Built from config.keybinds at startup and on config reload
     pub keybind_map: HashMap<crossterm::event::KeyEvent, crate::config::KeyBindAction>,
+
+    /// Multi-key keybind sequences (e.g. "ctrl+x h"), keyed by the full sequence
+    pub chord_map: HashMap<Vec<crossterm::event::KeyEvent>, crate::config::KeyBindAction>,
+
+    /// Keys pressed so far in an in-progress chord
+    pub pending_chord: Vec<crossterm::event::KeyEvent>,
+
+    /// When the last key of the pending chord arrived (for timeout)
+    pending_chord_at: Option<std::time::Instant>,
+
+    /// Per-mode keybind overlays (keybind_modes.toml), checked before keybind_map
+    pub mode_keybind_maps:
+        HashMap<String, HashMap<crossterm::event::KeyEvent, crate::config::KeyBindAction>>,
+
+    /// User-selected keybind mode (`.mode <name>`), None = base keybinds only
+    pub active_keybind_mode: Option<String>,
+
+    /// Keybind macro in progress (paused on `\p` or waiting on `\?`)
+    pub active_macro: Option<crate::core::macros::MacroRun>,
+
+    /// Snippet asking for its placeholder values in the command input
+    pub pending_snippet: Option<crate::core::snippets::SnippetRun>,
+
+    /// Alignment guides shown while a window is dragged with the mouse
+    pub snap_guides: Vec<crate::core::snap::SnapGuide>,
 }
 
 impl AppCore {
@@ -194,7 +346,18 @@ impl AppCore {
         let parser = XmlParser::with_presets(preset_list, config.event_patterns.clone());
 
         // Initialize sound player (if sound feature is enabled)
-        let sound_player = crate::sound::SoundPlayer::new(true, 0.8, 500).ok();
+        let mut sound_player = crate::sound::SoundPlayer::new(
+            config.sound.enabled,
+            config.sound.volume,
+            config.sound.cooldown_ms,
+        )
+        .ok();
+        if let Some(ref mut player) = sound_player {
+            for category in crate::sound::SoundCategory::ALL {
+                player.set_category_volume(category, config.sound.category_volume(category));
+            }
+            player.set_pack(Some(&config.sound.pack));
+        }
         if sound_player.is_some() {
             tracing::debug!("Sound player initialized");
             // Ensure sounds directory exists
@@ -204,17 +367,46 @@ impl AppCore {
         }
 
         // Initialize TTS manager (respects config.tts.enabled)
-        let tts_manager = crate::tts::TtsManager::new(
-            config.tts.enabled,
-            config.tts.rate,
-            config.tts.volume
-        );
+        let tts_manager = crate::tts::TtsManager::new(&config.tts);
+        let review =
+            crate::core::screen_reader::ReviewBuffer::new(config.screen_reader.review_lines);
         if config.tts.enabled {
             tracing::info!("TTS enabled - accessibility features active");
         }
 
+        // Initialize STT manager (worker thread starts on first push-to-talk)
+        let stt_manager = crate::stt::SttManager::new(&config.stt);
+
+        // Plugins answer their init now; anything they ask for waits for the main loop
+        let (plugins, plugin_actions) = match Config::plugins_dir() {
+            Ok(dir) => crate::plugins::PluginHost::load(
+                &dir,
+                &dir.join("settings.toml"),
+                config.character.as_deref(),
+            ),
+            Err(_) => Default::default(),
+        };
+
+        // Spectator server starts immediately when enabled
+        let spectator = crate::spectator::Spectator::new(&config.spectator);
+        let passthrough = crate::passthrough::Passthrough::new(&config.passthrough);
+        let websocket = crate::websocket::WebSocketApi::new(&config.websocket);
+        let relay = crate::relay::Relay::new(
+            &config.relay,
+            &config.highlights,
+            config.character.as_deref(),
+        );
+        let push = crate::push::PushNotifier::new(&config.push, config.character.as_deref());
+        let sound_triggers = crate::core::parse_worker::SoundTrigger::compile(&config.highlights);
+        let creatures = Config::load_creature_db().unwrap_or_else(|e| {
+            tracing::warn!("Failed to load creature database: {:#}", e);
+            Default::default()
+        });
+
         // Build the runtime keybind map from config
         let keybind_map = Self::build_keybind_map(&config);
+        let chord_map = Self::build_chord_map(&config);
+        let mode_keybind_maps = Self::build_mode_keybind_maps(&config);
 
         let layout_theme = layout.theme.clone();
         let mut app = Self {
@@ -222,6 +414,13 @@ impl AppCore {
             layout: layout.clone(),
             baseline_layout: Some(layout),
             game_state: GameState::new(),
+            loot: crate::data::LootTracker::new(),
+            session: crate::core::session_stats::SessionStats::new(),
+            bounty: crate::data::BountyTracker::new(),
+            containers: crate::data::ContainerTracker::new(),
+            creatures,
+            creatures_warned: Default::default(),
+            watch: crate::data::WatchTracker::new(),
             ui_state: UiState::new(),
             parser,
             message_processor,
@@ -238,6 +437,32 @@ impl AppCore {
             show_perf_stats: false,
             sound_player,
             tts_manager,
+            stt_manager,
+            spectator,
+            plugins,
+            plugin_actions,
+            passthrough,
+            websocket,
+            relay,
+            push,
+            afk: crate::core::afk::AfkTracker::new(std::time::Instant::now()),
+            afk_replies: Vec::new(),
+            quitting_game: false,
+            on_connect_pending: false,
+            theme_scheduler: Default::default(),
+            sound_event_tracker: Default::default(),
+            sound_muted: false,
+            do_not_disturb_until: None,
+            trigger_gate: Default::default(),
+            terminal_focused: true,
+            sound_triggers,
+            parse_worker: None,
+            compact_override: None,
+            compact_on: false,
+            compact_chrome: None,
+            screen_reader_on: false,
+            review,
+            recent_urls: Default::default(),
             nav_room_id: None,
             lich_room_id: None,
             room_subtitle: None,
@@ -246,12 +471,21 @@ impl AppCore {
             room_window_dirty: false,
             running: true,
             needs_render: true,
+            content_changed: false,
             chunk_has_main_text: false,
             chunk_has_silent_updates: false,
             layout_modified_since_save: false,
             save_reminder_shown: false,
             base_layout_name: None,
             keybind_map,
+            chord_map,
+            pending_chord: Vec::new(),
+            pending_chord_at: None,
+            mode_keybind_maps,
+            active_keybind_mode: None,
+            active_macro: None,
+            pending_snippet: None,
+            snap_guides: Vec::new(),
         };
 
         if let Some((theme_id, _)) = app.apply_layout_theme(layout_theme.as_deref()) {
@@ -260,16 +494,43 @@ impl AppCore {
             // The frontend will refresh during initialization from config.
         }
 
+        if app.config.screen_reader.enabled {
+            app.set_screen_reader(true);
+        }
+        app.compile_redirects();
+
         Ok(app)
     }
 
     /// Build runtime keybind map from config for fast O(1) lookups
     /// Converts string-based keybinds (e.g., "num_0", "Ctrl+s") to KeyEvent structs
     fn build_keybind_map(config: &Config) -> HashMap<crossterm::event::KeyEvent, crate::config::KeyBindAction> {
+        Self::build_key_map(&config.keybinds)
+    }
+
+    /// Build one runtime map per keybind mode (keybind_modes.toml)
+    fn build_mode_keybind_maps(
+        config: &Config,
+    ) -> HashMap<String, HashMap<crossterm::event::KeyEvent, crate::config::KeyBindAction>> {
+        config
+            .keybind_modes
+            .iter()
+            .map(|(mode, binds)| (mode.clone(), Self::build_key_map(binds)))
+            .collect()
+    }
+
+    fn build_key_map(
+        keybinds: &HashMap<String, crate::config::KeyBindAction>,
+    ) -> HashMap<crossterm::event::KeyEvent, crate::config::KeyBindAction> {
         use crossterm::event::{KeyEvent, KeyEventKind, KeyEventState};
         let mut map = HashMap::new();
 
-        for (key_string, action) in &config.keybinds {
+        for (key_string, action) in keybinds {
+            // Multi-key sequences live in the chord map
+            if key_string.split_whitespace().count() > 1 {
+                continue;
+            }
+
             // Parse the key string into a (KeyCode, KeyModifiers) tuple
             if let Some((code, modifiers)) = crate::config::parse_key_string(key_string) {
                 // Create a KeyEvent from the parsed code and modifiers
@@ -289,9 +550,180 @@ impl AppCore {
         map
     }
 
+    /// Build the chord map from multi-key keybind strings like "ctrl+x h"
+    fn build_chord_map(
+        config: &Config,
+    ) -> HashMap<Vec<crossterm::event::KeyEvent>, crate::config::KeyBindAction> {
+        use crossterm::event::KeyEvent;
+        let mut map = HashMap::new();
+
+        for (key_string, action) in &config.keybinds {
+            if key_string.split_whitespace().count() <= 1 {
+                continue;
+            }
+
+            match crate::config::parse_key_sequence(key_string) {
+                Some(steps) if steps.len() > 1 => {
+                    let sequence: Vec<KeyEvent> = steps
+                        .into_iter()
+                        .map(|(code, modifiers)| KeyEvent::new(code, modifiers))
+                        .collect();
+                    map.insert(sequence, action.clone());
+                }
+                _ => tracing::warn!("Failed to parse keybind sequence: '{}'", key_string),
+            }
+        }
+
+        tracing::debug!("Built chord map with {} entries", map.len());
+        map
+    }
+
     /// Rebuild the keybind map (call after config changes)
     pub fn rebuild_keybind_map(&mut self) {
         self.keybind_map = Self::build_keybind_map(&self.config);
+        self.chord_map = Self::build_chord_map(&self.config);
+        self.mode_keybind_maps = Self::build_mode_keybind_maps(&self.config);
+        self.clear_pending_chord();
+    }
+
+    /// Look up the keybind for a key, honoring keybind modes
+    ///
+    /// A table named after the current input mode ("search") applies while that
+    /// mode is active; otherwise the user's `.mode` overlay is checked before the
+    /// base keybinds. Binding a key to "type" in a mode unbinds it there, so it
+    /// falls through to normal typing.
+    pub fn lookup_keybind(
+        &self,
+        key: &crossterm::event::KeyEvent,
+    ) -> Option<crate::config::KeyBindAction> {
+        use crate::config::KeyBindAction;
+        use crate::data::ui_state::InputMode;
+
+        let overlay = match self.ui_state.input_mode {
+            InputMode::Search => Some("search"),
+            InputMode::Normal => self.active_keybind_mode.as_deref(),
+            _ => None,
+        };
+
+        if let Some(action) = overlay
+            .and_then(|mode| self.mode_keybind_maps.get(mode))
+            .and_then(|map| map.get(key))
+        {
+            return match action {
+                KeyBindAction::Action(name) if name == "type" => None,
+                _ => Some(action.clone()),
+            };
+        }
+
+        if self.ui_state.input_mode == InputMode::Normal {
+            self.keybind_map.get(key).cloned()
+        } else {
+            None
+        }
+    }
+
+    /// Switch the active keybind mode (None returns to base keybinds)
+    pub fn set_keybind_mode(&mut self, mode: Option<&str>) {
+        match mode {
+            None | Some("off") | Some("default") | Some("none") => {
+                self.active_keybind_mode = None;
+                self.add_system_message("Keybind mode: default");
+            }
+            Some(name) if self.mode_keybind_maps.contains_key(name) => {
+                self.active_keybind_mode = Some(name.to_string());
+                self.add_system_message(&format!("Keybind mode: {}", name));
+            }
+            Some(name) => {
+                self.add_system_message(&format!(
+                    "Unknown keybind mode '{}' - define it in keybind_modes.toml",
+                    name
+                ));
+            }
+        }
+        self.needs_render = true;
+    }
+
+    /// Feed a key press through the chord matcher
+    ///
+    /// A key that starts or continues a known sequence is held as pending until
+    /// the sequence completes, breaks, or times out (`ui.chord_timeout_ms`).
+    /// Chord prefixes take precedence over single-key binds on the same key.
+    pub fn process_chord_key(&mut self, key: crossterm::event::KeyEvent) -> ChordResult {
+        if self.chord_map.is_empty() {
+            return ChordResult::NotChord;
+        }
+
+        let mut sequence = self.pending_chord.clone();
+        sequence.push(key);
+
+        if let Some(action) = self.chord_map.get(&sequence).cloned() {
+            self.clear_pending_chord();
+            return ChordResult::Complete(action);
+        }
+
+        let is_prefix = self
+            .chord_map
+            .keys()
+            .any(|chord| chord.len() > sequence.len() && chord.starts_with(&sequence));
+        if is_prefix {
+            self.pending_chord = sequence;
+            self.pending_chord_at = Some(std::time::Instant::now());
+            self.needs_render = true;
+            return ChordResult::Pending;
+        }
+
+        if self.pending_chord.is_empty() {
+            ChordResult::NotChord
+        } else {
+            self.clear_pending_chord();
+            ChordResult::Cancelled
+        }
+    }
+
+    /// Abandon a pending chord whose next key didn't arrive in time
+    pub fn check_chord_timeout(&mut self) {
+        if let Some(started) = self.pending_chord_at {
+            let timeout = std::time::Duration::from_millis(self.config.ui.chord_timeout_ms);
+            if started.elapsed() >= timeout {
+                tracing::debug!("Chord timed out after {} keys", self.pending_chord.len());
+                self.clear_pending_chord();
+            }
+        }
+    }
+
+    fn clear_pending_chord(&mut self) {
+        if !self.pending_chord.is_empty() {
+            self.needs_render = true;
+        }
+        self.pending_chord.clear();
+        self.pending_chord_at = None;
+    }
+
+    /// Short status shown in the command input title (pending chord, dictation)
+    pub fn input_status_label(&self) -> Option<String> {
+        if let Some(ref run) = self.pending_snippet {
+            if let Some(name) = run.prompt() {
+                return Some(format!("{}: {}?", run.name, name));
+            }
+        }
+        if !self.pending_chord.is_empty() {
+            let keys: Vec<String> = self
+                .pending_chord
+                .iter()
+                .map(|k| crate::config::format_key_for_keybind(k.code, k.modifiers))
+                .collect();
+            return Some(format!("{} ...", keys.join(" ")));
+        }
+        if let Some(label) = self.stt_manager.status_label() {
+            return Some(label.to_string());
+        }
+        if self.afk.is_away() {
+            let idle = self.afk.idle(std::time::Instant::now());
+            return Some(format!("AFK {}", crate::core::afk::format_away(idle)));
+        }
+        self.active_keybind_mode
+            .as_ref()
+            .map(|mode| format!("Mode: {}", mode))
     }
 
     // ===========================================================================================
@@ -364,23 +796,18 @@ impl AppCore {
                 Ok(vec![]) // Actions don't send commands to server
             }
             KeyBindAction::Macro(macro_action) => {
-                // Strip any trailing \r or \n from macro text (legacy from wrayth-style macros)
-                // These control characters corrupt the StyledLine and cause rendering artifacts
-                let clean_text = macro_action.macro_text.trim_end_matches(&['\r', '\n'][..]).to_string();
-
-                tracing::info!("[MACRO] Executing macro: '{}' (raw: '{}')",
-                    clean_text, macro_action.macro_text);
-
-                // Send the macro text as a command (posts prompt+echo, returns command for server)
-                let command = self.send_command(clean_text)?;
-                tracing::info!("[MACRO] send_command returned: '{}'", command);
-                Ok(vec![command]) // Return command for network layer to send
+                // Macros run through the interpreter; a new macro replaces one that is
+                // still paused or waiting. Its output is collected by poll_macro().
+                tracing::info!("[MACRO] Starting macro: {:?}", macro_action.macro_text);
+                self.active_macro =
+                    Some(crate::core::macros::MacroRun::new(&macro_action.macro_text));
+                Ok(vec![])
             }
         }
     }
 
     /// Execute a KeyAction (dispatch to the appropriate method)
-    fn execute_key_action(&mut self, action: crate::config::KeyAction) -> Result<()> {
+    pub fn execute_key_action(&mut self, action: crate::config::KeyAction) -> Result<()> {
         use crate::config::KeyAction;
 
         match action {
@@ -397,7 +824,8 @@ impl AppCore {
             | KeyAction::PreviousCommand
             | KeyAction::NextCommand
             | KeyAction::SendLastCommand
-            | KeyAction::SendSecondLastCommand => {
+            | KeyAction::SendSecondLastCommand
+            | KeyAction::HistorySearch => {
                 // These actions are now handled by the CommandInput widget
                 // via frontend.command_input_key() in main.rs
                 // If we get here, it means the routing logic in main.rs missed something
@@ -408,10 +836,8 @@ impl AppCore {
             }
 
             // Window actions
-            KeyAction::SwitchCurrentWindow => {
-                // TODO: Implement window switching logic
-                tracing::debug!("SwitchCurrentWindow not yet implemented");
-            }
+            KeyAction::SwitchCurrentWindow => self.cycle_focus(true),
+            KeyAction::SwitchCurrentWindowBack => self.cycle_focus(false),
             KeyAction::ScrollCurrentWindowUpOne => self.scroll_current_window_up_one(),
             KeyAction::ScrollCurrentWindowDownOne => self.scroll_current_window_down_one(),
             KeyAction::ScrollCurrentWindowUpPage => self.scroll_current_window_up_page(),
@@ -437,8 +863,8 @@ impl AppCore {
 
             // Debug/Performance actions
             KeyAction::TogglePerformanceStats => {
-                // TODO: Toggle performance stats overlay
-                tracing::debug!("TogglePerformanceStats not yet implemented");
+                self.show_perf_stats = !self.show_perf_stats;
+                self.needs_render = true;
             }
 
             // TTS (Text-to-Speech) actions - Accessibility
@@ -462,6 +888,28 @@ impl AppCore {
                     tracing::warn!("TTS stop failed: {}", e);
                 }
             }
+            KeyAction::ToggleSound => self.toggle_sound(),
+            KeyAction::TtsPause => match self.tts_manager.toggle_pause() {
+                Ok(paused) => {
+                    let status = if paused { "paused" } else { "resumed" };
+                    self.add_system_message(&format!("TTS {}", status));
+                }
+                Err(e) => tracing::warn!("TTS pause failed: {}", e),
+            },
+            KeyAction::TtsSkip => {
+                if let Err(e) = self.tts_manager.skip() {
+                    tracing::warn!("TTS skip failed: {}", e);
+                }
+            }
+            KeyAction::TtsFlush => {
+                let dropped = self.tts_manager.flush();
+                self.add_system_message(&format!("TTS queue flushed ({} dropped)", dropped));
+            }
+            KeyAction::TtsRepeatLast => {
+                if let Err(e) = self.tts_manager.repeat_last() {
+                    tracing::warn!("TTS repeat_last failed: {}", e);
+                }
+            }
             KeyAction::TtsMuteToggle => {
                 self.tts_manager.toggle_mute();
                 let status = if self.tts_manager.is_muted() { "muted" } else { "unmuted" };
@@ -496,15 +944,47 @@ impl AppCore {
                 }
             }
 
+            KeyAction::ReviewPrevious | KeyAction::ReviewNext | KeyAction::ReviewLatest => {
+                self.review_step(&action)
+            }
+
+            KeyAction::CycleRecentUrls => {
+                let message = match self.recent_urls.cycle() {
+                    Some((n, total, url)) => {
+                        format!("Link {}/{}: {} (open_recent_url opens it)", n, total, url)
+                    }
+                    None => "No links seen yet".to_string(),
+                };
+                self.add_system_message(&message);
+            }
+            KeyAction::OpenRecentUrl => match self.recent_urls.take_selected() {
+                Some(url) => self.open_url(&url),
+                None => self.add_system_message("No links seen yet"),
+            },
+
+            // STT (Speech-to-Text) actions - Accessibility
+            KeyAction::SttPushToTalk => {
+                if let Err(e) = self.stt_manager.toggle() {
+                    self.add_system_message(&format!("[STT] {}", e));
+                }
+                self.needs_render = true;
+            }
+
             // Macro actions (should not reach here - handled by execute_keybind_action)
             KeyAction::SendMacro(text) => {
-                self.send_command(text)?;
+                self.send_scripted_command(text)?;
             }
         }
 
         Ok(())
     }
 
+    /// Record terminal focus changes (used by `only_when_unfocused` for TTS and highlight sounds)
+    pub fn set_terminal_focused(&mut self, focused: bool) {
+        self.terminal_focused = focused;
+        self.tts_manager.set_app_focused(focused);
+    }
+
     /// Poll TTS events from callback channel and handle them
     /// Should be called in the main event loop to enable auto-play
     pub fn poll_tts_events(&mut self) {
@@ -515,10 +995,12 @@ impl AppCore {
                 Ok(event) => {
                     match event {
                         crate::tts::TtsEvent::UtteranceEnded(id) => {
-                            // Check if this was the current utterance
+                            // Lines that queued behind this one play now
                             if self.tts_manager.is_current_utterance(id) {
-                                tracing::debug!("Utterance {:?} ended (manual control - no auto-play)", id);
-                                // Auto-play disabled - user has full manual control with Ctrl+Alt+Left/Right/Up
+                                tracing::debug!("Utterance {:?} ended", id);
+                                if let Err(e) = self.tts_manager.auto_play_next() {
+                                    tracing::warn!("Failed to play queued TTS entry: {}", e);
+                                }
                             }
                         }
                         crate::tts::TtsEvent::UtteranceStarted(id) => {
@@ -541,6 +1023,227 @@ impl AppCore {
         }
     }
 
+    /// Advance the running keybind macro
+    ///
+    /// Commands are passed through send_command (so they echo and dot commands
+    /// work; only pastes and composed lines echo as typed); `Send` carries its
+    /// result, which may be an "action:" string.
+    pub fn poll_macro(&mut self) -> Result<Vec<crate::core::macros::MacroEffect>> {
+        use crate::core::macros::MacroEffect;
+
+        let Some(run) = self.active_macro.as_mut() else {
+            return Ok(Vec::new());
+        };
+        let effects = run.advance(std::time::Instant::now());
+        let scripted = !run.is_typed();
+        if run.is_finished() {
+            self.active_macro = None;
+        }
+
+        let mut out = Vec::with_capacity(effects.len());
+        for effect in effects {
+            self.needs_render = true;
+            match effect {
+                MacroEffect::Send(command) => out.push(MacroEffect::Send(
+                    self.send_command_from(command, scripted)?,
+                )),
+                other => out.push(other),
+            }
+        }
+        Ok(out)
+    }
+
+    /// Send pasted lines one after another, through send_command like a macro
+    pub fn send_pasted_lines(&mut self, lines: &[String]) {
+        if self.active_macro.is_some() {
+            tracing::debug!("Paste replaces the running macro");
+        }
+        self.active_macro = Some(
+            crate::core::macros::MacroRun::from_lines(lines, std::time::Duration::ZERO).typed(),
+        );
+    }
+
+    /// Send the composer's lines in order, `ui.compose_line_delay_ms` apart
+    pub fn send_composed_lines(&mut self, lines: &[String]) {
+        if self.active_macro.is_some() {
+            tracing::debug!("Composed lines replace the running macro");
+        }
+        let delay = std::time::Duration::from_millis(self.config.ui.compose_line_delay_ms);
+        self.active_macro = Some(crate::core::macros::MacroRun::from_lines(lines, delay).typed());
+    }
+
+    /// Hold a multi-line paste and ask what to do with it
+    ///
+    /// Returns the dialog, or None when confirmation is off and the lines
+    /// were queued to send right away.
+    pub fn hold_paste(&mut self, lines: Vec<String>) -> Option<crate::data::ui_state::PopupMenu> {
+        if !self.config.ui.confirm_multiline_paste {
+            self.send_pasted_lines(&lines);
+            return None;
+        }
+        let menu = paste_menu(&lines);
+        self.ui_state.pending_paste = Some(lines);
+        Some(menu)
+    }
+
+    /// Run snippet `name`, filling placeholders from `args` in order
+    ///
+    /// Placeholders left over are asked for in the command input; the
+    /// snippet is sent once the last one is answered.
+    pub fn run_snippet(&mut self, name: &str, args: &[&str]) {
+        let Some(snippet) = self.config.snippets.get(name) else {
+            self.add_system_message(&format!("No snippet named '{}'", name));
+            return;
+        };
+        let mut run = crate::core::snippets::SnippetRun::new(name, &snippet.text);
+        for arg in args {
+            run.answer(arg);
+        }
+        self.pending_snippet = Some(run);
+        self.advance_snippet();
+    }
+
+    /// Use a submitted line as the value of the snippet's next placeholder
+    ///
+    /// Returns false when no snippet is waiting (the line is an ordinary command).
+    pub fn answer_snippet(&mut self, value: &str) -> bool {
+        let Some(run) = self.pending_snippet.as_mut() else {
+            return false;
+        };
+        run.answer(value.trim());
+        self.advance_snippet();
+        true
+    }
+
+    /// Drop a snippet that is waiting on values (false if none was)
+    pub fn cancel_snippet(&mut self) -> bool {
+        match self.pending_snippet.take() {
+            Some(run) => {
+                self.add_system_message(&format!("Snippet {} cancelled", run.name));
+                self.needs_render = true;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Send the pending snippet once every placeholder has a value
+    fn advance_snippet(&mut self) {
+        self.needs_render = true;
+        let Some(ref run) = self.pending_snippet else {
+            return;
+        };
+        if run.prompt().is_some() {
+            return;
+        }
+        let text = run.expand();
+        self.pending_snippet = None;
+        if self.active_macro.is_some() {
+            tracing::debug!("Snippet replaces the running macro");
+        }
+        self.active_macro = Some(crate::core::macros::MacroRun::new(&text));
+    }
+
+    /// Save (or replace) a snippet's text and write snippets.toml
+    pub fn save_snippet(&mut self, name: &str, text: &str) {
+        let description = self
+            .config
+            .snippets
+            .get(name)
+            .and_then(|snippet| snippet.description.clone());
+        self.config.snippets.insert(
+            name.to_string(),
+            crate::config::Snippet {
+                text: text.to_string(),
+                description,
+            },
+        );
+        self.add_system_message(&format!("Saved snippet {}", name));
+        self.persist_snippets();
+    }
+
+    /// Delete a snippet and write snippets.toml
+    pub fn delete_snippet(&mut self, name: &str) -> bool {
+        if self.config.snippets.remove(name).is_none() {
+            return false;
+        }
+        self.add_system_message(&format!("Deleted snippet {}", name));
+        self.persist_snippets();
+        true
+    }
+
+    /// Persist snippets, reporting failures to the user
+    fn persist_snippets(&mut self) {
+        if let Err(e) = self.config.save_snippets(self.config.character.as_deref()) {
+            tracing::error!("Failed to save snippets: {}", e);
+            self.add_system_message(&format!("Warning: Failed to save snippets: {}", e));
+        }
+    }
+
+    /// The user submitted the command input; resume a macro waiting on `\?`
+    pub fn macro_input_submitted(&mut self) {
+        if let Some(run) = self.active_macro.as_mut() {
+            run.input_submitted();
+        }
+    }
+
+    /// Carry out what plugins asked for since the last poll
+    /// Returns commands to send; the caller runs them through send_command
+    pub fn poll_plugin_actions(&mut self) -> Vec<String> {
+        use crate::plugins::PluginAction;
+
+        let mut sends = Vec::new();
+        for action in std::mem::take(&mut self.plugin_actions) {
+            match action {
+                PluginAction::Send { command } => sends.push(command),
+                PluginAction::Message { text } => self.add_system_message(&text),
+                PluginAction::Widget { name, lines } => {
+                    self.plugins.set_widget_lines(name, lines);
+                    self.needs_render = true;
+                }
+                PluginAction::OpenWidget { name, title } => self.open_widget_window(&name, title),
+            }
+        }
+        sends
+    }
+
+    /// Poll STT events from the worker thread
+    /// Returns finished transcripts; the caller decides whether to send them
+    /// or drop them into the command input for confirmation
+    pub fn poll_stt_events(&mut self) -> Vec<String> {
+        use std::sync::mpsc::TryRecvError;
+
+        let mut transcripts = Vec::new();
+        loop {
+            match self.stt_manager.try_recv_event() {
+                Ok(event) => {
+                    match event {
+                        crate::stt::SttEvent::Transcribed(text) => {
+                            tracing::debug!("STT transcript: {}", text);
+                            transcripts.push(text);
+                        }
+                        crate::stt::SttEvent::NoSpeech => {
+                            self.add_system_message("[STT] No speech detected");
+                        }
+                        crate::stt::SttEvent::Error(e) => {
+                            tracing::warn!("STT error: {}", e);
+                            self.add_system_message(&format!("[STT] {}", e));
+                        }
+                        crate::stt::SttEvent::Listening | crate::stt::SttEvent::Transcribing => {}
+                    }
+                    // Status label in the command input changes with every event
+                    self.needs_render = true;
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    tracing::error!("STT event channel disconnected");
+                    break;
+                }
+            }
+        }
+        transcripts
+    }
+
     /// Initialize windows based on current layout
     pub fn init_windows(&mut self, terminal_width: u16, terminal_height: u16) {
         // Calculate window positions from layout
@@ -568,6 +1271,8 @@ impl AppCore {
                 "text" => WidgetType::Text,
                 "tabbedtext" => WidgetType::TabbedText,
                 "progress" => WidgetType::Progress,
+                "vitals" => WidgetType::Vitals,
+                "container" => WidgetType::Container,
                 "countdown" => WidgetType::Countdown,
                 "compass" => WidgetType::Compass,
                 "injury_doll" | "injuries" => WidgetType::InjuryDoll,
@@ -580,6 +1285,14 @@ impl AppCore {
                 "active_effects" => WidgetType::ActiveEffects,
                 "targets" => WidgetType::Targets,
                 "players" => WidgetType::Players,
+                "loot_tracker" => WidgetType::LootTracker,
+                "session_stats" => WidgetType::SessionStats,
+                "bounty_tracker" => WidgetType::BountyTracker,
+                "containers" => WidgetType::ContainerTree,
+                "plugin" => WidgetType::Plugin,
+                "watch_list" => WidgetType::WatchList,
+                "art" => WidgetType::Art,
+                "status_line" => WidgetType::StatusLine,
                 "spells" => WidgetType::Spells,
                 "quickbar" => WidgetType::QuickBar,
                 _ => WidgetType::Text,
@@ -617,6 +1330,25 @@ impl AppCore {
                     end_time: 0,
                     label: title.to_string(),
                 }),
+                WidgetType::Vitals => {
+                    let bars = if let crate::config::WindowDef::Vitals { data, .. } = window_def {
+                        data.children
+                            .iter()
+                            .map(|child| {
+                                let data = ProgressData {
+                                    value: 100,
+                                    max: 100,
+                                    label: String::new(),
+                                    color: None,
+                                };
+                                (child.id.clone(), data)
+                            })
+                            .collect()
+                    } else {
+                        Vec::new()
+                    };
+                    WindowContent::Vitals { bars }
+                }
                 WidgetType::Compass => WindowContent::Compass(CompassData {
                     directions: Vec::new(),
                 }),
@@ -657,6 +1389,14 @@ impl AppCore {
                 WidgetType::Players => WindowContent::Players {
                     players_text: String::new(),
                 },
+                WidgetType::LootTracker => WindowContent::LootTracker,
+                WidgetType::SessionStats => WindowContent::SessionStats,
+                WidgetType::BountyTracker => WindowContent::BountyTracker,
+                WidgetType::ContainerTree => WindowContent::ContainerTree,
+                WidgetType::Plugin => WindowContent::Plugin,
+                WidgetType::WatchList => WindowContent::WatchList,
+                WidgetType::Art => WindowContent::Art,
+                WidgetType::StatusLine => WindowContent::StatusLine,
                 WidgetType::Dashboard => WindowContent::Dashboard {
                     indicators: Vec::new(),
                 },
@@ -679,9 +1419,22 @@ impl AppCore {
                 .set_window(window_def.name().to_string(), window);
         }
 
+        self.stack_in_layout_order();
+        self.apply_container_layout();
         self.needs_render = true;
     }
 
+    /// Stack windows in the order the layout lists them (later = on top)
+    fn stack_in_layout_order(&mut self) {
+        let order: Vec<String> = self
+            .layout
+            .windows
+            .iter()
+            .map(|w| w.name().to_string())
+            .collect();
+        self.ui_state.set_z_order(&order);
+    }
+
     /// Add a single new window without destroying existing ones
     ///
     /// Uses absolute positioning from window definition with optional delta-based scaling.
@@ -721,6 +1474,8 @@ impl AppCore {
             "text" => WidgetType::Text,
             "tabbedtext" => WidgetType::TabbedText,
             "progress" => WidgetType::Progress,
+            "vitals" => WidgetType::Vitals,
+            "container" => WidgetType::Container,
             "countdown" => WidgetType::Countdown,
             "compass" => WidgetType::Compass,
             "injury_doll" | "injuries" => WidgetType::InjuryDoll,
@@ -733,6 +1488,14 @@ impl AppCore {
             "active_effects" => WidgetType::ActiveEffects,
             "targets" => WidgetType::Targets,
             "players" => WidgetType::Players,
+            "loot_tracker" => WidgetType::LootTracker,
+            "session_stats" => WidgetType::SessionStats,
+            "bounty_tracker" => WidgetType::BountyTracker,
+            "containers" => WidgetType::ContainerTree,
+            "plugin" => WidgetType::Plugin,
+            "watch_list" => WidgetType::WatchList,
+            "art" => WidgetType::Art,
+            "status_line" => WidgetType::StatusLine,
             "spells" => WidgetType::Spells,
             "quickbar" => WidgetType::QuickBar,
             _ => WidgetType::Text,
@@ -769,6 +1532,25 @@ impl AppCore {
                 end_time: 0,
                 label: title.to_string(),
             }),
+            WidgetType::Vitals => {
+                let bars = if let crate::config::WindowDef::Vitals { data, .. } = window_def {
+                    data.children
+                        .iter()
+                        .map(|child| {
+                            let data = ProgressData {
+                                value: 100,
+                                max: 100,
+                                label: String::new(),
+                                color: None,
+                            };
+                            (child.id.clone(), data)
+                        })
+                        .collect()
+                } else {
+                    Vec::new()
+                };
+                WindowContent::Vitals { bars }
+            }
             WidgetType::Compass => WindowContent::Compass(CompassData {
                 directions: Vec::new(),
             }),
@@ -809,6 +1591,14 @@ impl AppCore {
             WidgetType::Players => WindowContent::Players {
                 players_text: String::new(),
             },
+            WidgetType::LootTracker => WindowContent::LootTracker,
+            WidgetType::SessionStats => WindowContent::SessionStats,
+            WidgetType::BountyTracker => WindowContent::BountyTracker,
+            WidgetType::ContainerTree => WindowContent::ContainerTree,
+            WidgetType::Plugin => WindowContent::Plugin,
+            WidgetType::WatchList => WindowContent::WatchList,
+            WidgetType::Art => WindowContent::Art,
+            WidgetType::StatusLine => WindowContent::StatusLine,
             WidgetType::Dashboard => WindowContent::Dashboard {
                 indicators: Vec::new(),
             },
@@ -841,6 +1631,9 @@ impl AppCore {
             self.room_window_dirty = true;
         }
 
+        // New containers and container children take their place in the group
+        self.apply_container_layout();
+
         tracing::info!(
             "Created new window '{}' at ({}, {}) size {}x{}",
             window_def.name(),
@@ -882,36 +1675,433 @@ impl AppCore {
                 position.height
             );
         }
+        self.apply_container_layout();
     }
 
-    /// Remove a window from UI state
-    pub fn remove_window(&mut self, name: &str) {
-        self.ui_state.remove_window(name);
-        self.needs_render = true;
+    /// Position the children of every container inside the container's rectangle
+    ///
+    /// Containers can nest, so outer containers are laid out before the ones
+    /// they hold. Child WindowDefs are updated as well, keeping mouse syncs and
+    /// saves consistent with what is on screen. Hidden children give their
+    /// share to their visible siblings.
+    pub fn apply_container_layout(&mut self) {
+        use crate::config::{ContainerWidgetData, WindowDef};
+
+        let containers: HashMap<String, (ContainerWidgetData, crate::config::WindowBase)> = self
+            .layout
+            .windows
+            .iter()
+            .filter_map(|w| match w {
+                WindowDef::Container { base, data } => {
+                    Some((base.name.clone(), (data.clone(), base.clone())))
+                }
+                _ => None,
+            })
+            .collect();
+        if containers.is_empty() {
+            return;
+        }
+
+        let nested: std::collections::HashSet<&str> = containers
+            .values()
+            .flat_map(|(data, _)| data.children.iter().map(|c| c.name.as_str()))
+            .collect();
+        let mut queue: std::collections::VecDeque<&str> = containers
+            .keys()
+            .map(String::as_str)
+            .filter(|name| !nested.contains(name))
+            .collect();
+        let mut visited = std::collections::HashSet::new();
+
+        while let Some(name) = queue.pop_front() {
+            if !visited.insert(name) {
+                continue;
+            }
+            let (data, base) = &containers[name];
+            let Some(outer) = self.ui_state.get_window(name).map(|w| w.position.clone()) else {
+                continue;
+            };
+
+            // Children sit inside the container's border
+            let left = u16::from(base.show_border && base.border_sides.left);
+            let top = u16::from(base.show_border && base.border_sides.top);
+            let inner = WindowPosition {
+                x: outer.x + left,
+                y: outer.y + top,
+                width: outer.width.saturating_sub(base.vertical_border_units()),
+                height: outer.height.saturating_sub(base.horizontal_border_units()),
+            };
+
+            let children: Vec<&crate::config::ContainerChild> = data
+                .children
+                .iter()
+                .filter(|c| self.ui_state.windows.contains_key(&c.name))
+                .collect();
+            let weights: Vec<u16> = children.iter().map(|c| c.weight).collect();
+            let total = if data.is_columns() {
+                inner.width
+            } else {
+                inner.height
+            };
+
+            for (child, (offset, size)) in children
+                .iter()
+                .zip(ContainerWidgetData::split(&weights, total))
+            {
+                let position = if data.is_columns() {
+                    WindowPosition {
+                        x: inner.x + offset,
+                        y: inner.y,
+                        width: size,
+                        height: inner.height,
+                    }
+                } else {
+                    WindowPosition {
+                        x: inner.x,
+                        y: inner.y + offset,
+                        width: inner.width,
+                        height: size,
+                    }
+                };
+
+                if let Some(window_def) = self
+                    .layout
+                    .windows
+                    .iter_mut()
+                    .find(|w| w.name() == child.name)
+                {
+                    let child_base = window_def.base_mut();
+                    child_base.col = position.x;
+                    child_base.row = position.y;
+                    child_base.cols = position.width;
+                    child_base.rows = position.height;
+                }
+                if let Some(window) = self.ui_state.get_window_mut(&child.name) {
+                    window.position = position;
+                }
+                if containers.contains_key(&child.name) {
+                    queue.push_back(child.name.as_str());
+                }
+            }
+        }
+        self.needs_render = true;
+    }
+
+    /// Top-level container holding `name` (the window itself when it is not contained)
+    ///
+    /// Drags on a contained window are redirected here so the group moves as a unit.
+    pub fn container_root(&self, name: &str) -> String {
+        let mut current = name;
+        for _ in 0..self.layout.windows.len() {
+            match self.layout.container_of(current) {
+                Some(parent) => current = parent,
+                None => break,
+            }
+        }
+        current.to_string()
+    }
+
+    /// Put a window into a container (None = take it out of any container)
+    pub fn assign_container(&mut self, name: &str, container: Option<&str>) {
+        match self.layout.set_container(name, container) {
+            Ok(()) => {
+                self.apply_container_layout();
+                self.mark_layout_modified();
+            }
+            Err(e) => self.add_system_message(&format!("Container not changed: {}", e)),
+        }
+    }
+
+    /// Remove a window from UI state
+    pub fn remove_window(&mut self, name: &str) {
+        self.ui_state.remove_window(name);
+        self.needs_render = true;
         tracing::info!("Removed window '{}'", name);
     }
 
+    /// Write the performance stats as CSV (default: ~/.two-face/perf-<time>.csv)
+    fn dump_perf_csv(&mut self, path: Option<String>) {
+        let path = match path {
+            Some(path) => Ok(std::path::PathBuf::from(path)),
+            None => crate::config::Config::base_dir().map(|dir| {
+                dir.join(format!(
+                    "perf-{}.csv",
+                    chrono::Local::now().format("%Y%m%d-%H%M%S")
+                ))
+            }),
+        };
+        let written = path.and_then(|path| {
+            std::fs::write(&path, self.perf_stats.to_csv())
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            Ok(path)
+        });
+        match written {
+            Ok(path) => {
+                self.add_system_message(&format!("Performance stats written to {}", path.display()))
+            }
+            Err(e) => {
+                self.add_system_message(&format!("Could not write performance stats: {:#}", e))
+            }
+        }
+    }
+
+    /// Move server text parsing and sound matching onto a background thread
+    pub fn start_parse_worker(&mut self) {
+        match crate::core::parse_worker::ParseWorker::spawn(
+            self.parser.clone(),
+            self.sound_triggers.clone(),
+        ) {
+            Ok(worker) => self.parse_worker = Some(worker),
+            Err(e) => tracing::warn!("Parse worker not started, parsing on the UI thread: {}", e),
+        }
+    }
+
+    /// Recompile highlight sounds (call after highlights are added, edited, or removed)
+    pub fn refresh_sound_triggers(&mut self) {
+        self.sound_triggers =
+            crate::core::parse_worker::SoundTrigger::compile(&self.config.highlights);
+        if let Some(worker) = &self.parse_worker {
+            worker.set_sound_triggers(self.sound_triggers.clone());
+        }
+    }
+
+    /// Take one raw line from the server: queue it for the parse worker, or
+    /// process it right away when there is none
+    pub fn submit_server_line(&mut self, line: String) -> Result<()> {
+        let line = match self.parse_worker.as_mut() {
+            Some(worker) => match worker.submit(line) {
+                Ok(()) => return Ok(()),
+                Err(line) => {
+                    tracing::error!("Parse worker stopped; parsing on the UI thread");
+                    self.parse_worker = None;
+                    line
+                }
+            },
+            None => line,
+        };
+        self.process_server_data(&line)?;
+        let hits = crate::core::parse_worker::sound_hits(&self.sound_triggers, &line);
+        self.play_highlight_sounds(&hits);
+        Ok(())
+    }
+
+    /// Apply lines the parse worker has finished until `budget` is spent; the
+    /// rest wait for the next pass so input and rendering keep up during floods
+    pub fn apply_parsed_lines(&mut self, budget: std::time::Duration) -> Result<()> {
+        let started = std::time::Instant::now();
+        let mut applied = false;
+        while started.elapsed() < budget {
+            let Some(line) = self.parse_worker.as_mut().and_then(|w| w.try_recv()) else {
+                break;
+            };
+            if !applied {
+                self.message_processor.update_stream_routes(&self.layout);
+            }
+            self.perf_stats.record_parse(line.parse_time);
+            self.perf_stats
+                .record_elements_parsed(line.elements.len() as u64);
+            self.apply_line_elements(&line.elements)?;
+            self.play_highlight_sounds(&line.sound_hits);
+            applied = true;
+        }
+        if applied {
+            self.finish_server_chunk();
+        }
+        Ok(())
+    }
+
+    /// Refresh the overlay's buffered-line and window counts
+    pub fn update_perf_memory_stats(&mut self) {
+        let lines = self
+            .ui_state
+            .windows
+            .values()
+            .map(|window| match &window.content {
+                crate::data::WindowContent::Text(text) => text.lines.len(),
+                _ => 0,
+            })
+            .sum();
+        self.perf_stats
+            .update_memory_stats(lines, self.ui_state.windows.len());
+    }
+
+    /// Is anything still changing with the clock? (a running countdown, a
+    /// half-typed key chord, a running macro, the performance overlay)
+    pub fn has_running_timers(&self) -> bool {
+        let now = chrono::Utc::now().timestamp() + self.message_processor.server_time_offset;
+        let countdown_running = self.ui_state.windows.values().any(|window| {
+            matches!(&window.content, crate::data::WindowContent::Countdown(c) if c.end_time >= now)
+        });
+        let input_timer = self.config.ui.input_timer
+            && (self.game_state.in_roundtime() || self.game_state.in_casttime());
+        countdown_running
+            || input_timer
+            || !self.ui_state.flashes.is_empty()
+            || self.pending_chord_at.is_some()
+            || self.active_macro.is_some()
+            || self.show_perf_stats
+    }
+
+    /// Visible text windows showing relative timestamps, whose labels age
+    /// on screen without new text
+    pub fn relative_timestamp_windows(&self) -> Vec<String> {
+        self.ui_state
+            .windows
+            .iter()
+            .filter(|(_, window)| {
+                window.visible && matches!(window.content, crate::data::WindowContent::Text(_))
+            })
+            .filter(|(name, _)| {
+                let timestamp_override = match self.layout.get_window(name) {
+                    Some(crate::config::WindowDef::Text { data, .. }) => data.timestamps.as_ref(),
+                    _ => None,
+                };
+                let timestamps = self.config.ui.timestamps.with_override(timestamp_override);
+                timestamps.enabled && timestamps.relative
+            })
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    /// Server lines the parse worker has not handed back yet
+    pub fn parse_backlog(&self) -> usize {
+        self.parse_worker.as_ref().map_or(0, |w| w.backlog())
+    }
+
     /// Process incoming XML data from server
     pub fn process_server_data(&mut self, data: &str) -> Result<()> {
+        // Windows may have been added, edited or reloaded since the last chunk
+        self.message_processor.update_stream_routes(&self.layout);
+
         // Parse XML line by line
         for line in data.lines() {
+            let started = std::time::Instant::now();
             let elements = self.parser.parse_line(line);
+            self.perf_stats.record_parse(started.elapsed());
+            self.perf_stats
+                .record_elements_parsed(elements.len() as u64);
+            self.apply_line_elements(&elements)?;
+        }
 
-            // Process each element
-            for element in elements {
-                self.process_element(&element)?;
+        self.finish_server_chunk();
+        Ok(())
+    }
+
+    /// Process the elements of one server line, then end the line
+    fn apply_line_elements(&mut self, elements: &[ParsedElement]) -> Result<()> {
+        for element in elements {
+            self.process_element(element)?;
+        }
+
+        // Finish the current line after processing all elements from this network line
+        // This ensures newlines from the game are preserved (like VellumFE does)
+        self.message_processor
+            .flush_current_stream_with_tts(&mut self.ui_state, Some(&mut self.tts_manager));
+        Ok(())
+    }
+
+    /// Per-chunk follow-up once a run of server lines has been applied
+    fn finish_server_chunk(&mut self) {
+        for name in std::mem::take(&mut self.message_processor.creature_mentions) {
+            self.check_creature(&name);
+        }
+
+        for url in std::mem::take(&mut self.message_processor.urls) {
+            self.recent_urls.push(url);
+        }
+
+        for text in std::mem::take(&mut self.message_processor.bounty_lines) {
+            if self.bounty.record_line(&text) {
+                self.needs_render = true;
             }
+        }
 
-            // Finish the current line after processing all elements from this network line
-            // This ensures newlines from the game are preserved (like VellumFE does)
-            self.message_processor
-                .flush_current_stream_with_tts(&mut self.ui_state, Some(&mut self.tts_manager));
+        // Death, whisper and login sounds from sounds.toml
+        for (stream, text, whispered) in std::mem::take(&mut self.message_processor.added_lines) {
+            if self.screen_reader_on {
+                self.review.push(&stream, &text);
+            }
+            let sound = crate::core::sound_events::line_event(
+                &stream,
+                &text,
+                whispered,
+                &self.config.sound_events,
+            );
+            if let Some(event) = sound {
+                self.play_game_event_in(event, &stream);
+            }
+            let whisper = sound == Some(crate::core::sound_events::GameEvent::Whisper);
+            if whisper && self.is_away() {
+                self.push_event(crate::push::PushEvent::Whisper, &text);
+            }
+            if self.afk.is_away() {
+                self.record_afk_line(&stream, &text, whisper);
+            }
+            if !self.plugins.is_empty() {
+                let actions = self.plugins.on_line(&stream, &text);
+                self.plugin_actions.extend(actions);
+            }
+            self.websocket.publish(&crate::websocket::ApiEvent::Line {
+                stream: &stream,
+                text: &text,
+            });
+            self.relay.record_line(&stream, &text);
+            if stream == "main" {
+                // BOUNTY, EXPERIENCE and LOOK IN output land in main
+                let looted = self.loot.record_line(&text);
+                let bounty = self.bounty.record_line(&text);
+                let counted = self.session.record_line(&text);
+                let stowed = self.containers.record_line(&text);
+                if looted || bounty || counted || stowed {
+                    self.needs_render = true;
+                }
+            }
+            if let Some(presence) = self.watch.record_line(
+                &stream,
+                &text,
+                &self.config.watchlist.friends,
+                &self.config.watchlist.enemies,
+            ) {
+                self.on_watch_event(&presence, sound.is_some());
+            }
+        }
+
+        if self.session.record_dead(self.game_state.status.dead) {
+            let place = self
+                .room_subtitle
+                .as_deref()
+                .map(|s| format!(" in {}", s.trim_start_matches(" - ").trim()))
+                .unwrap_or_default();
+            self.push_event(
+                crate::push::PushEvent::Death,
+                &format!("You died{}.", place),
+            );
+            self.needs_render = true;
+        }
+
+        // Room and vitals go out only when they change
+        if self.websocket.is_running() {
+            use crate::websocket::ApiEvent;
+            let vitals = &self.game_state.vitals;
+            self.websocket.publish(&ApiEvent::Vitals {
+                health: vitals.health,
+                mana: vitals.mana,
+                stamina: vitals.stamina,
+                spirit: vitals.spirit,
+            });
+            self.websocket.publish(&ApiEvent::Room {
+                id: self.nav_room_id.as_deref(),
+                lich_id: self.lich_room_id.as_deref(),
+                name: self
+                    .room_subtitle
+                    .as_deref()
+                    .map(|s| s.trim_start_matches(" - ").trim()),
+                exits: &self.game_state.compass_dirs,
+            });
         }
 
         // Update QuickBar cache with any new content
         self.update_quickbar_cache();
-
-        Ok(())
     }
 
     /// Process a single parsed XML element
@@ -932,6 +2122,18 @@ impl AppCore {
             return Ok(());
         }
 
+        if matches!(element, ParsedElement::Prompt { .. })
+            && std::mem::take(&mut self.on_connect_pending)
+        {
+            self.run_on_connect();
+        }
+
+        if let ParsedElement::ProgressBar { id, text, .. } = element {
+            if id == "nextLvlPB" {
+                self.session.record_next_level(text);
+            }
+        }
+
         // Update game state and UI state via message processor
         self.message_processor.process_element(
             element,
@@ -946,75 +2148,58 @@ impl AppCore {
             Some(&mut self.tts_manager),
         );
 
-        // Mark that we need to render
-        self.needs_render = true;
+        // Mark that the changed windows need to render
+        self.content_changed = true;
 
         Ok(())
     }
 
-    /// Send command to server
+    /// Send a command the user typed (or pasted, dictated or composed)
     pub fn send_command(&mut self, command: String) -> Result<String> {
-        use crate::data::{SpanType, StyledLine, TextSegment, WindowContent};
+        self.send_command_from(command, false)
+    }
+
+    /// Send a command from a macro, the login commands, a plugin or a remote client
+    pub fn send_scripted_command(&mut self, command: String) -> Result<String> {
+        self.send_command_from(command, true)
+    }
+
+    /// Send command to server; `scripted` commands echo in their own color (or not at all)
+    fn send_command_from(&mut self, command: String, scripted: bool) -> Result<String> {
+        use crate::data::{StyledLine, WindowContent};
 
         // Check for dot commands (local client commands)
         if command.starts_with('.') {
             return self.handle_dot_command(&command);
         }
 
-        // Echo command to main window (prompt + command)
-        if !command.is_empty() {
+        if matches!(command.trim().to_lowercase().as_str(), "quit" | "exit") {
+            self.quitting_game = true;
+        }
+
+        // Echo command to main window (prompt + prefix + command)
+        let echo = command_echo(
+            &command,
+            scripted,
+            &self.game_state.last_prompt,
+            &self.config.colors,
+            &self.config.ui,
+        );
+        if let Some(segments) = echo {
             tracing::info!("[SEND_COMMAND] Echoing command to main window: '{}'", command);
             if let Some(main_window) = self.ui_state.windows.get_mut("main") {
                 if let WindowContent::Text(ref mut content) = main_window.content {
-                    let mut segments = Vec::new();
-
-                    // Add prompt with per-character coloring (same as prompt rendering)
-                    tracing::debug!("[SEND_COMMAND] Building styled line with prompt: '{}'", self.game_state.last_prompt);
-                    for ch in self.game_state.last_prompt.chars() {
-                        let char_str = ch.to_string();
-
-                        // Find color for this character in prompt_colors config
-                        let color = self
-                            .config
-                            .colors
-                            .prompt_colors
-                            .iter()
-                            .find(|pc| pc.character == char_str)
-                            .and_then(|pc| {
-                                // Prefer fg, fallback to color (legacy)
-                                pc.fg.as_ref().or(pc.color.as_ref()).cloned()
-                            })
-                            .unwrap_or_else(|| "#808080".to_string()); // Default dark gray
-
-                        segments.push(TextSegment {
-                            text: char_str,
-                            fg: Some(color),
-                            bg: None,
-                            bold: false,
-                            span_type: SpanType::Normal,
-                            link_data: None,
-                        });
-                    }
-
-                    // Add the command text (in default color)
-                    segments.push(TextSegment {
-                        text: command.clone(),
-                        fg: Some("#ffffff".to_string()), // White text for command
-                        bg: None,
-                        bold: false,
-                        span_type: SpanType::Normal,
-                        link_data: None,
-                    });
-
-                    // Add the styled line to the main window
-                    content.add_line(StyledLine { segments: segments.clone() });
-                    tracing::info!("[SEND_COMMAND] Added StyledLine with {} segments to main window", segments.len());
+                    content.add_line(StyledLine { segments });
                 }
             }
         }
 
         // Command history is now managed by the CommandInput widget
 
+        if !command.trim().is_empty() {
+            self.game_state.last_command = Some(command.trim().to_string());
+        }
+
         // Return formatted command for network layer to send
         Ok(format!("{}\n", command))
     }
@@ -1032,6 +2217,141 @@ impl AppCore {
             "help" | "h" | "?" => {
                 self.show_help();
             }
+            "spectator" => {
+                let status = self.spectator.status_line();
+                self.add_system_message(&status);
+            }
+            "passthrough" => {
+                let status = self.passthrough.status_line();
+                self.add_system_message(&status);
+            }
+            "websocket" => {
+                let status = self.websocket.status_line();
+                self.add_system_message(&status);
+            }
+            "relay" => {
+                let status = self.relay.status_line();
+                self.add_system_message(&status);
+            }
+            "redirects" => match parts.get(1).copied() {
+                None => self.add_system_message(&format!(
+                    "Redirects: {} of {} rule(s) in use (.redirects reload re-reads config.toml)",
+                    self.message_processor.redirect_count(),
+                    self.config.redirects.len()
+                )),
+                Some("reload") => {
+                    match crate::config::Config::load_redirects(self.config.character.as_deref()) {
+                        Ok(rules) => {
+                            self.config.redirects = rules;
+                            self.compile_redirects();
+                            self.add_system_message(&format!(
+                                "Reloaded redirects ({} rule(s) in use)",
+                                self.message_processor.redirect_count()
+                            ));
+                        }
+                        Err(e) => {
+                            self.add_system_message(&format!("Failed to reload redirects: {:#}", e))
+                        }
+                    }
+                }
+                Some(_) => self.add_system_message("Usage: .redirects [reload]"),
+            },
+            "push" => match parts.get(1).map(|s| s.to_lowercase()).as_deref() {
+                None => {
+                    let status = self.push.status_line();
+                    self.add_system_message(&status);
+                }
+                Some("test") if self.push.is_running() => {
+                    self.push.notify(
+                        crate::push::PushEvent::Test,
+                        "Test notification from two-face",
+                    );
+                    self.add_system_message("Test notification sent (.push shows any error)");
+                }
+                Some("test") => {
+                    let status = self.push.status_line();
+                    self.add_system_message(&status);
+                }
+                Some(_) => self.add_system_message("Usage: .push [test]"),
+            },
+            "screenreader" => match parts.get(1).map(|s| s.to_lowercase()).as_deref() {
+                None => {
+                    let on = !self.screen_reader_on;
+                    self.set_screen_reader(on);
+                }
+                Some("on") => self.set_screen_reader(true),
+                Some("off") => self.set_screen_reader(false),
+                Some(_) => self.add_system_message("Usage: .screenreader [on|off]"),
+            },
+            "perf" => match parts.get(1).copied() {
+                None => {
+                    self.show_perf_stats = !self.show_perf_stats;
+                    self.needs_render = true;
+                }
+                Some("on") | Some("off") => {
+                    self.show_perf_stats = parts[1] == "on";
+                    self.needs_render = true;
+                }
+                Some("reset") => {
+                    self.perf_stats.reset();
+                    self.add_system_message("Performance stats reset");
+                }
+                Some("csv") => {
+                    let path = (parts.len() > 2).then(|| parts[2..].join(" "));
+                    self.dump_perf_csv(path);
+                }
+                Some(_) => self.add_system_message("Usage: .perf [on|off|reset|csv [file]]"),
+            },
+            "ttssettings" => {
+                return Ok("action:ttssettings".to_string());
+            }
+            "ttsvoices" => match self.tts_manager.voice_names() {
+                Ok(names) if names.is_empty() => {
+                    self.add_system_message("The TTS engine reports no voices");
+                }
+                Ok(names) => {
+                    self.add_system_message(&format!("TTS voices: {}", names.join(", ")));
+                }
+                Err(e) => {
+                    self.add_system_message(&format!("TTS unavailable: {}", e));
+                }
+            },
+            "defaultlayout" => match parts.get(1).copied() {
+                None => {
+                    let message = match self.startup_layout() {
+                        Some(name) => format!("Startup layout: {}", name),
+                        None => "No startup layout (last session's layout is used)".to_string(),
+                    };
+                    self.add_system_message(&message);
+                }
+                Some("none") | Some("off") => self.set_startup_layout(None),
+                Some("ask") => self.set_ask_startup_layout(true),
+                Some(name) => {
+                    if Config::layout_path(name).is_ok_and(|path| path.exists()) {
+                        self.set_startup_layout(Some(name));
+                    } else {
+                        self.add_system_message(&format!("Layout '{}' not found", name));
+                    }
+                }
+            },
+            "mouse" => {
+                return Ok(match parts.get(1).map(|s| s.to_lowercase()).as_deref() {
+                    None | Some("toggle") => "action:togglemouse".to_string(),
+                    Some("on") => "action:mouse:on".to_string(),
+                    Some("off") => "action:mouse:off".to_string(),
+                    Some(_) => {
+                        self.add_system_message("Usage: .mouse [on|off]");
+                        String::new()
+                    }
+                });
+            }
+            "lastspells" => {
+                let count = parts
+                    .get(1)
+                    .and_then(|n| n.parse::<usize>().ok())
+                    .unwrap_or(10);
+                self.show_spell_history(count);
+            }
 
             // Layout commands
             "savelayout" => {
@@ -1060,6 +2380,31 @@ impl AppCore {
             "resize" => {
                 self.resize_to_current_terminal();
             }
+            "compact" => match parts.get(1).copied() {
+                None => self.add_system_message(&format!(
+                    "Compact mode is {} ({}; .compact on|off|auto)",
+                    if self.compact_active() { "on" } else { "off" },
+                    match self.compact_override {
+                        Some(_) => "set by hand".to_string(),
+                        None if self.config.ui.compact_width == 0 =>
+                            "automatic switching off".to_string(),
+                        None => format!("automatic below {} columns", self.config.ui.compact_width),
+                    }
+                )),
+                Some("on") => {
+                    self.compact_override = Some(true);
+                    self.add_system_message("Compact mode on");
+                }
+                Some("off") => {
+                    self.compact_override = Some(false);
+                    self.add_system_message("Compact mode off");
+                }
+                Some("auto") => {
+                    self.compact_override = None;
+                    self.add_system_message("Compact mode follows the terminal width");
+                }
+                Some(_) => self.add_system_message("Usage: .compact [on|off|auto]"),
+            },
 
             // Window management commands
             "windows" => {
@@ -1114,6 +2459,13 @@ impl AppCore {
                     self.add_system_message("Usage: .rename <window> <new title>");
                 }
             }
+            "raise" | "lower" => {
+                let name = parts
+                    .get(1)
+                    .map(|s| s.to_string())
+                    .unwrap_or_else(|| self.get_focused_window_name());
+                self.restack_window(&name, parts[0] == "raise");
+            }
             "border" => {
                 if parts.len() >= 3 {
                     let window_name = parts[1];
@@ -1166,36 +2518,23 @@ impl AppCore {
             "addspellcolor" | "newspellcolor" => {
                 return Ok("action:addspellcolor".to_string());
             }
+            "configdiff" => {
+                return Ok("action:configdiff".to_string());
+            }
 
             // Theme commands
             "themes" => {
                 return Ok("action:themes".to_string());
             }
             "settheme" | "theme" => {
+                if parts.get(1) == Some(&crate::theme::terminal::TERMINAL_THEME) {
+                    // Switching to it re-reads the palette, which needs the frontend
+                    return Ok("action:terminaltheme".to_string());
+                }
                 if let Some(theme_name) = parts.get(1) {
-                    // Validate theme exists (includes built-in and custom)
-                    let theme_presets = crate::theme::ThemePresets::all_with_custom(
-                        self.config.character.as_deref(),
-                    );
-                    let theme_list_message = Self::available_themes_message(&theme_presets);
-                    if theme_presets.contains_key(*theme_name) {
-                        self.config.active_theme = theme_name.to_string();
-                        self.add_system_message(&format!("Theme switched to: {}", theme_name));
-
-                        // Save config
-                        if let Err(e) = self.config.save(self.config.character.as_deref()) {
-                            tracing::error!("Failed to save config after theme change: {}", e);
-                            self.add_system_message(&format!(
-                                "Warning: Failed to save theme preference: {}",
-                                e
-                            ));
-                        }
-
+                    if self.switch_theme(theme_name) {
                         // Return action so main.rs can update frontend cache
                         return Ok(format!("action:settheme:{}", theme_name));
-                    } else {
-                        self.add_system_message(&format!("Unknown theme: {}", theme_name));
-                        self.add_system_message(&theme_list_message);
                     }
                 } else {
                     self.add_system_message("Usage: .settheme <name>");
@@ -1208,6 +2547,12 @@ impl AppCore {
             "edittheme" => {
                 return Ok("action:edittheme".to_string());
             }
+            "termtheme" | "terminaltheme" => {
+                return Ok("action:terminaltheme".to_string());
+            }
+            "importtheme" => {
+                self.import_theme(&parts[1..]);
+            }
 
             // Tab navigation commands
             "nexttab" => {
@@ -1220,6 +2565,312 @@ impl AppCore {
                 return Ok("action:gonew".to_string());
             }
 
+            "importkeys" => {
+                self.import_keybinds(&parts[1..]);
+            }
+            "importlayout" => {
+                self.import_layout(&parts[1..]);
+            }
+            "loot" => match parts.get(1).copied() {
+                None | Some("report") => self.loot_report(),
+                Some("reset") if parts.get(2).copied() == Some("session") => {
+                    self.loot.reset_session();
+                    self.add_system_message("Loot session reset");
+                    self.needs_render = true;
+                }
+                Some("reset") => {
+                    let hunt = self.loot.reset_hunt();
+                    self.add_system_message(&format!(
+                        "Hunt closed after {}: {} - new hunt started",
+                        crate::data::format_elapsed(hunt.elapsed()),
+                        hunt.totals.summary()
+                    ));
+                    self.needs_render = true;
+                }
+                Some(_) => self.add_system_message("Usage: .loot [report|reset|reset session]"),
+            },
+            "watch" => self.watch_command(&parts[1..]),
+            "creature" if parts.len() > 1 => {
+                let shown = parts[1..].join(" ");
+                match self.creatures.lookup(&shown) {
+                    Some((name, info)) => {
+                        let text = info.describe(name);
+                        self.add_system_message(&text);
+                    }
+                    None => self.add_system_message(&format!("No creature matching '{}'", shown)),
+                }
+            }
+            "creature" if self.creatures.is_empty() => {
+                self.add_system_message("The creature database is empty (see creatures.toml)")
+            }
+            "creature" => self.add_system_message(&format!(
+                "{} creatures known. Usage: .creature <name>",
+                self.creatures.len()
+            )),
+            "session" => match parts.get(1).copied() {
+                None | Some("report") => self.session_report(),
+                Some("save") => {
+                    let label = (parts.len() > 2).then(|| parts[2..].join(" "));
+                    self.save_session_snapshot(label);
+                }
+                Some("reset") => {
+                    self.session.reset();
+                    self.add_system_message("Session stats reset");
+                    self.needs_render = true;
+                }
+                Some(_) => self.add_system_message("Usage: .session [report|save [label]|reset]"),
+            },
+            "bounty" => match &self.bounty.task {
+                Some(task) => self.add_system_message(&format!("Bounty: {}", task.summary())),
+                None if self.bounty.updated.is_some() => self.add_system_message("Bounty: no task"),
+                None => self.add_system_message("Bounty: not seen yet (type BOUNTY to check)"),
+            },
+            "find" if parts.len() > 1 => self.find_item(&parts[1..].join(" ")),
+            "find" => self.add_system_message("Usage: .find <item>"),
+
+            // Keybind modes
+            "mode" => {
+                if let Some(name) = parts.get(1) {
+                    self.set_keybind_mode(Some(name));
+                } else {
+                    let mut modes: Vec<_> = self
+                        .mode_keybind_maps
+                        .keys()
+                        .filter(|m| m.as_str() != "search")
+                        .cloned()
+                        .collect();
+                    modes.sort();
+                    self.add_system_message(&format!(
+                        "Keybind mode: {} (available: {})",
+                        self.active_keybind_mode.as_deref().unwrap_or("default"),
+                        if modes.is_empty() {
+                            "none".to_string()
+                        } else {
+                            modes.join(", ")
+                        }
+                    ));
+                }
+            }
+
+            // Action registry
+            "action" | "do" => {
+                if parts.len() > 1 {
+                    let name = parts[1..].join(" ");
+                    if crate::core::actions::AppAction::resolve(&name, &self.config.actions)
+                        .is_some()
+                    {
+                        return Ok(format!("action:{}", name.trim_start_matches("action:")));
+                    }
+                    self.add_system_message(&format!("Unknown action: {} (see .actions)", name));
+                } else {
+                    self.add_system_message("Usage: .action <name>");
+                }
+            }
+            "actions" => {
+                self.list_actions();
+            }
+
+            // Search commands
+            "search" => {
+                if let Some((window, pattern)) = self.search_target("search", &parts[1..]) {
+                    return Ok(format!("action:search:{}:{}", window, pattern));
+                }
+            }
+            "pin" => {
+                if parts.len() == 1 {
+                    self.list_pinned_searches();
+                } else if let Some((window, pattern)) = self.search_target("pin", &parts[1..]) {
+                    match regex::Regex::new(&pattern) {
+                        Ok(_) => {
+                            self.add_system_message(&format!(
+                                "Pinned '{}' in {} - matches stay highlighted until .unpin {}",
+                                pattern, window, window
+                            ));
+                            self.ui_state.pinned_searches.insert(window, pattern);
+                            self.needs_render = true;
+                        }
+                        Err(e) => {
+                            self.add_system_message(&format!("Invalid regex '{}': {}", pattern, e))
+                        }
+                    }
+                }
+            }
+            "unpin" => match parts.get(1) {
+                None => {
+                    let count = self.ui_state.pinned_searches.len();
+                    self.ui_state.pinned_searches.clear();
+                    self.add_system_message(&format!("Cleared {} pinned search(es)", count));
+                    self.needs_render = true;
+                }
+                Some(window) => {
+                    if self.ui_state.pinned_searches.remove(*window).is_some() {
+                        self.add_system_message(&format!("Unpinned {}", window));
+                        self.needs_render = true;
+                    } else {
+                        self.add_system_message(&format!("Nothing pinned in {}", window));
+                    }
+                }
+            },
+            "savesearch" => {
+                if let Some(name) = parts.get(1) {
+                    let name = name.trim_start_matches('@').to_string();
+                    let search = if parts.len() > 2 {
+                        Some(crate::config::SavedSearch {
+                            pattern: parts[2..].join(" "),
+                            window: None,
+                        })
+                    } else {
+                        // No pattern given - save the focused window's most recent search
+                        let window = self.get_focused_window_name();
+                        self.ui_state
+                            .search_history
+                            .get(&window)
+                            .and_then(|h| h.last())
+                            .map(|pattern| crate::config::SavedSearch {
+                                pattern: pattern.clone(),
+                                window: Some(window.clone()),
+                            })
+                    };
+
+                    match search {
+                        Some(search) if regex::Regex::new(&search.pattern).is_ok() => {
+                            self.add_system_message(&format!(
+                                "Saved search @{}: '{}'",
+                                name, search.pattern
+                            ));
+                            self.config.saved_searches.insert(name, search);
+                            self.persist_saved_searches();
+                        }
+                        Some(search) => {
+                            self.add_system_message(&format!(
+                                "Invalid regex: '{}'",
+                                search.pattern
+                            ));
+                        }
+                        None => {
+                            self.add_system_message(
+                                "No recent search to save - use .savesearch <name> <pattern>",
+                            );
+                        }
+                    }
+                } else {
+                    self.add_system_message("Usage: .savesearch <name> [pattern]");
+                }
+            }
+            "dump" => {
+                let Some(window) = parts.get(1) else {
+                    self.add_system_message("Usage: .dump <window> [file] [--force]");
+                    return Ok(String::new());
+                };
+                match self.ui_state.windows.get(*window).map(|w| &w.widget_type) {
+                    Some(WidgetType::Text) | Some(WidgetType::TabbedText) => {
+                        let mut args = parts[2..].to_vec();
+                        let force = args.last().is_some_and(|a| *a == "--force");
+                        if force {
+                            args.pop();
+                        }
+                        let verb = if force { "dumpforce" } else { "dump" };
+                        return Ok(format!("action:{}:{}:{}", verb, window, args.join(" ")));
+                    }
+                    Some(_) => {
+                        self.add_system_message(&format!("'{}' is not a text window", window))
+                    }
+                    None => self.add_system_message(&format!("Window '{}' not found", window)),
+                }
+            }
+            "searches" => {
+                self.list_saved_searches();
+            }
+
+            // Snippets
+            "snippet" | "snip" => match parts.get(1) {
+                Some(name) => self.run_snippet(name, &parts[2..]),
+                None => self.add_system_message("Usage: .snippet <name> [values...]"),
+            },
+            "snippets" | "snips" => {
+                return Ok("action:snippets".to_string());
+            }
+            "addsnippet" | "editsnippet" => match parts.get(1) {
+                // Typed text uses macro escapes for line breaks (\r); without it the editor opens
+                Some(name) if parts.len() > 2 => self.save_snippet(name, &parts[2..].join(" ")),
+                Some(name) => return Ok(format!("action:editsnippet:{}", name)),
+                None => self.add_system_message("Usage: .addsnippet <name> [text]"),
+            },
+            "delsnippet" | "deletesnippet" => match parts.get(1) {
+                Some(name) if self.delete_snippet(name) => {}
+                Some(name) => self.add_system_message(&format!("No snippet named '{}'", name)),
+                None => self.add_system_message("Usage: .delsnippet <name>"),
+            },
+
+            // Event sounds
+            "sounds" => match parts.get(1).copied() {
+                None => self.list_sound_events(),
+                Some("reload") => {
+                    match crate::config::Config::load_sound_events(self.config.character.as_deref())
+                    {
+                        Ok(sounds) => {
+                            self.config.sound_events = sounds;
+                            self.add_system_message(&format!(
+                                "Reloaded sounds.toml ({} event(s))",
+                                self.config.sound_events.len()
+                            ));
+                        }
+                        Err(e) => self
+                            .add_system_message(&format!("Failed to reload sounds.toml: {:#}", e)),
+                    }
+                }
+                Some("test") => {
+                    match parts.get(2).and_then(|n| crate::core::sound_events::GameEvent::from_name(n)) {
+                        Some(event) => self.play_game_event(event),
+                        None => self.add_system_message(
+                            "Usage: .sounds test <death|whisper|roundtime_end|spell_expiring|friend_login>",
+                        ),
+                    }
+                }
+                Some("pack") => self.set_sound_pack(parts.get(2).copied()),
+                Some(_) => self
+                    .add_system_message("Usage: .sounds [reload|test <event>|pack [<name>|none]]"),
+            },
+            "mute" => self.toggle_sound(),
+            "dnd" => match parts.get(1).copied() {
+                None => match self.do_not_disturb_until {
+                    Some(until) if self.do_not_disturb_active() => {
+                        let left = until.saturating_duration_since(std::time::Instant::now());
+                        self.add_system_message(&format!(
+                            "Do not disturb: {} minute(s) left",
+                            left.as_secs().div_ceil(60)
+                        ));
+                    }
+                    _ => self.add_system_message("Do not disturb is off (.dnd <minutes> to start)"),
+                },
+                Some("off") => self.set_do_not_disturb(None),
+                Some(arg) => match arg.parse::<u64>() {
+                    Ok(minutes) if minutes > 0 => self.set_do_not_disturb(Some(minutes)),
+                    _ => self.add_system_message("Usage: .dnd [<minutes>|off]"),
+                },
+            },
+            "afk" => {
+                if self.afk.is_away() {
+                    self.add_system_message("Already AFK (any key ends it)");
+                } else {
+                    self.afk.set_away(std::time::Instant::now());
+                    self.add_system_message("You are now AFK (any key ends it)");
+                }
+            }
+            "delsearch" | "deletesearch" => {
+                if let Some(name) = parts.get(1) {
+                    let name = name.trim_start_matches('@');
+                    if self.config.saved_searches.remove(name).is_some() {
+                        self.add_system_message(&format!("Deleted saved search @{}", name));
+                        self.persist_saved_searches();
+                    } else {
+                        self.add_system_message(&format!("No saved search named '{}'", name));
+                    }
+                } else {
+                    self.add_system_message("Usage: .delsearch <name>");
+                }
+            }
+
             // Settings
             "settings" => {
                 return Ok("action:settings".to_string());
@@ -1255,9 +2906,21 @@ impl AppCore {
                 self.needs_render = true;
             }
 
+            "plugin" | "plugins" => self.plugin_command(&parts[1..]),
+            "widget" => self.widget_command(&parts[1..]),
+
             _ => {
-                self.add_system_message(&format!("Unknown command: {}", command));
-                self.add_system_message("Type .help for list of commands");
+                let args: Vec<String> = parts.iter().skip(1).map(|s| s.to_string()).collect();
+                match self.plugins.on_command(&cmd, &args) {
+                    Some(Ok(actions)) => self.plugin_actions.extend(actions),
+                    Some(Err(e)) => {
+                        self.add_system_message(&format!("Plugin command failed: {:#}", e))
+                    }
+                    None => {
+                        self.add_system_message(&format!("Unknown command: {}", command));
+                        self.add_system_message("Type .help for list of commands");
+                    }
+                }
             }
         }
 
@@ -1269,7 +2932,7 @@ impl AppCore {
 
     /// Get list of available dot commands for tab completion
     pub fn get_available_commands(&self) -> Vec<String> {
-        vec![
+        let mut commands = vec![
             // Application commands
             ".quit".to_string(),
             ".q".to_string(),
@@ -1281,6 +2944,7 @@ impl AppCore {
             ".loadlayout".to_string(),
             ".layouts".to_string(),
             ".resize".to_string(),
+            ".compact".to_string(),
             // Window management
             ".windows".to_string(),
             ".deletewindow".to_string(),
@@ -1288,6 +2952,8 @@ impl AppCore {
             ".addwindow".to_string(),
             ".rename".to_string(),
             ".border".to_string(),
+            ".raise".to_string(),
+            ".lower".to_string(),
             ".editwindow".to_string(),
             ".editwin".to_string(),
             // Highlight commands
@@ -1302,6 +2968,16 @@ impl AppCore {
             ".kb".to_string(),
             ".addkeybind".to_string(),
             ".addkey".to_string(),
+            ".importkeys".to_string(),
+            ".importlayout".to_string(),
+            ".loot".to_string(),
+            ".watch".to_string(),
+            ".bounty".to_string(),
+            ".find".to_string(),
+            ".plugin".to_string(),
+            ".widget".to_string(),
+            ".session".to_string(),
+            ".creature".to_string(),
             // Color commands
             ".colors".to_string(),
             ".colorpalette".to_string(),
@@ -1311,21 +2987,105 @@ impl AppCore {
             ".spellcolors".to_string(),
             ".addspellcolor".to_string(),
             ".newspellcolor".to_string(),
+            ".configdiff".to_string(),
             // Theme commands
             ".themes".to_string(),
             ".settheme".to_string(),
             ".theme".to_string(),
             ".edittheme".to_string(),
+            ".termtheme".to_string(),
+            ".importtheme".to_string(),
             // Tab navigation
             ".nexttab".to_string(),
             ".prevtab".to_string(),
             ".gonew".to_string(),
             ".nextunread".to_string(),
+            // Keybind modes
+            ".mode".to_string(),
+            // Action registry
+            ".action".to_string(),
+            ".actions".to_string(),
+            // Search commands
+            ".search".to_string(),
+            ".savesearch".to_string(),
+            ".searches".to_string(),
+            ".pin".to_string(),
+            ".unpin".to_string(),
+            ".delsearch".to_string(),
+            ".dump".to_string(),
+            // Snippets
+            ".snippet".to_string(),
+            ".snippets".to_string(),
+            ".addsnippet".to_string(),
+            ".delsnippet".to_string(),
             // Settings
             ".settings".to_string(),
+            ".spectator".to_string(),
+            ".passthrough".to_string(),
+            ".websocket".to_string(),
+            ".relay".to_string(),
+            ".redirects".to_string(),
+            ".push".to_string(),
+            ".screenreader".to_string(),
+            ".perf".to_string(),
+            ".lastspells".to_string(),
+            ".mouse".to_string(),
+            ".defaultlayout".to_string(),
+            ".ttssettings".to_string(),
+            ".ttsvoices".to_string(),
+            ".sounds".to_string(),
+            ".mute".to_string(),
+            ".dnd".to_string(),
+            ".afk".to_string(),
             // Menu system
             ".menu".to_string(),
-        ]
+        ];
+        // Commands registered by plugins
+        for plugin in self.plugins.plugins() {
+            commands.extend(plugin.manifest.commands.iter().map(|c| format!(".{}", c)));
+        }
+        commands
+    }
+
+    /// Game words for Tab completion: nouns in the room, inventory and hands, spell names
+    pub fn get_completion_words(&self) -> Vec<String> {
+        let mut words: Vec<String> = ["room objs", "room players"]
+            .iter()
+            .filter_map(|component| self.room_components.get(*component))
+            .flatten()
+            .flatten()
+            .filter_map(|segment| segment.link_data.as_ref())
+            .map(|link| link.noun.clone())
+            .collect();
+        words.extend(self.message_processor.inventory_nouns().map(str::to_string));
+        words.extend(self.game_state.spell.clone());
+        words.extend(
+            self.game_state
+                .spell_history
+                .iter()
+                .map(|record| record.spell.clone()),
+        );
+        for window in self.ui_state.windows.values() {
+            match window.content {
+                crate::data::WindowContent::Hand {
+                    link: Some(ref link),
+                    ..
+                } => {
+                    words.push(link.noun.clone());
+                }
+                crate::data::WindowContent::ActiveEffects(ref content)
+                    if content.category == "ActiveSpells" =>
+                {
+                    words.extend(content.effects.iter().map(|effect| effect.text.clone()));
+                }
+                _ => {}
+            }
+        }
+
+        words.retain(|word| !word.trim().is_empty());
+        words.sort();
+        words.dedup();
+        words
     }
 
     /// Get list of window names for tab completion
@@ -1357,84 +3117,665 @@ impl AppCore {
                     }
                     _ => None,
                 }
-            })
-            .max()
-            .unwrap_or(0);
+            })
+            .max()
+            .unwrap_or(0);
+
+        format!("spacer_{}", max_number + 1)
+    }
+
+    /// List all loaded highlights
+    fn list_highlights(&mut self) {
+        let count = self.config.highlights.len();
+
+        // Collect all highlight info first to avoid borrow checker issues
+        let mut lines = vec![format!("=== Highlights ({}) ===", count)];
+
+        for (name, pattern) in &self.config.highlights {
+            let mut info = format!("  {} - pattern: '{}'", name, pattern.pattern);
+            if let Some(ref fg) = pattern.fg {
+                info.push_str(&format!(" fg:{}", fg));
+            }
+            if let Some(ref bg) = pattern.bg {
+                info.push_str(&format!(" bg:{}", bg));
+            }
+            if pattern.bold {
+                info.push_str(" bold");
+            }
+            if !pattern.windows.is_empty() {
+                info.push_str(&format!(" windows:{}", pattern.windows.join(",")));
+            }
+            lines.push(info);
+        }
+
+        // Add all messages
+        for line in lines {
+            self.add_system_message(&line);
+        }
+    }
+
+    /// Add a system message to the main window
+    pub fn add_system_message(&mut self, message: &str) {
+        self.add_colored_system_message(message, "#00ff00");
+    }
+
+    /// Add a system message to the main window in a given color
+    pub fn add_colored_system_message(&mut self, message: &str, color: &str) {
+        use crate::data::{SpanType, StyledLine, TextSegment, WindowContent};
+
+        if let Some(main_window) = self.ui_state.get_window_mut("main") {
+            if let WindowContent::Text(ref mut content) = main_window.content {
+                let line = StyledLine {
+                    segments: vec![TextSegment {
+                        text: message.to_string(),
+                        fg: Some(color.to_string()),
+                        bg: None,
+                        bold: true,
+                        span_type: SpanType::Normal,
+                        link_data: None,
+                    }],
+                };
+                content.add_line(line);
+                self.needs_render = true;
+            }
+        }
+    }
+
+    /// Import a Wizard/StormFront macro file (`.importkeys <file> [format] [overwrite]`)
+    fn import_keybinds(&mut self, args: &[&str]) {
+        use crate::config::keybind_import::{import_file, ImportFormat};
+
+        let Some(path) = args.first() else {
+            self.add_system_message("Usage: .importkeys <file> [wizard|stormfront] [overwrite]");
+            return;
+        };
+        let format = args[1..].iter().find_map(|a| ImportFormat::from_str(a));
+        let overwrite = args[1..]
+            .iter()
+            .any(|a| a.eq_ignore_ascii_case("overwrite"));
+
+        let report = match import_file(std::path::Path::new(path), format) {
+            Ok(report) => report,
+            Err(e) => {
+                self.add_system_message(&format!("Import failed: {:#}", e));
+                return;
+            }
+        };
+        let skipped = report.skipped.len();
+        let (applied, kept) = report.merge_into(&mut self.config.keybinds, overwrite);
+        self.rebuild_keybind_map();
+
+        if let Err(e) =
+            Config::write_keybinds(&self.config.keybinds, self.config.character.as_deref())
+        {
+            tracing::error!("Failed to save imported keybinds: {}", e);
+        }
+        self.add_system_message(&format!(
+            "Imported {} keybind(s); {} unsupported, {} kept (add 'overwrite' to replace)",
+            applied,
+            skipped,
+            kept.len()
+        ));
+    }
+
+    /// `.plugin [list|settings <name>|set <name> <key> <value>]`
+    fn plugin_command(&mut self, args: &[&str]) {
+        match args {
+            [] | ["list"] => {
+                let mut lines = Vec::new();
+                if self.plugins.is_empty() {
+                    lines.push("No plugins loaded (put them in ~/.two-face/plugins/)".to_string());
+                }
+                for plugin in self.plugins.plugins() {
+                    let manifest = &plugin.manifest;
+                    let mut line = format!("{} {}", manifest.name, manifest.version);
+                    if !manifest.description.is_empty() {
+                        line.push_str(&format!(" - {}", manifest.description));
+                    }
+                    if !manifest.commands.is_empty() {
+                        line.push_str(&format!(" [commands: .{}]", manifest.commands.join(", .")));
+                    }
+                    if !manifest.widgets.is_empty() {
+                        line.push_str(&format!(" [widgets: {}]", manifest.widgets.join(", ")));
+                    }
+                    lines.push(line);
+                }
+                for error in &self.plugins.errors {
+                    lines.push(format!("Not loaded: {}", error));
+                }
+                for line in lines {
+                    self.add_system_message(&line);
+                }
+            }
+            ["settings", name] => match self.plugins.settings_of(name) {
+                Some(settings) if settings.is_empty() => {
+                    self.add_system_message(&format!("{} has no settings", name))
+                }
+                Some(settings) => {
+                    for (setting, value) in settings {
+                        let label = if setting.label.is_empty() {
+                            &setting.key
+                        } else {
+                            &setting.label
+                        };
+                        self.add_system_message(&format!(
+                            "  {} ({}) = {}",
+                            label, setting.key, value
+                        ));
+                    }
+                }
+                None => self.add_system_message(&format!("No plugin named {}", name)),
+            },
+            ["set", name, key, value @ ..] if !value.is_empty() => {
+                let value = value.join(" ");
+                let result = Config::plugins_dir().and_then(|dir| {
+                    self.plugins
+                        .set_setting(name, key, &value, &dir.join("settings.toml"))
+                });
+                match result {
+                    Ok(actions) => {
+                        self.plugin_actions.extend(actions);
+                        self.add_system_message(&format!("{}: {} = {}", name, key, value));
+                    }
+                    Err(e) => self.add_system_message(&format!("{:#}", e)),
+                }
+            }
+            _ => self.add_system_message(
+                "Usage: .plugin [list|settings <name>|set <name> <key> <value>]",
+            ),
+        }
+    }
+
+    /// Fill or open a script widget (`.widget <name> ...`)
+    ///
+    /// The lines live with the plugin widgets, so a `plugin` window shows
+    /// them whether a plugin or a script (through `two-face send`) wrote them.
+    fn widget_command(&mut self, args: &[&str]) {
+        match args {
+            [name, "open", title @ ..] => {
+                let title = (!title.is_empty()).then(|| title.join(" "));
+                self.open_widget_window(name, title);
+            }
+            [name, "add", text @ ..] => {
+                let lines = self.plugins.widget_lines_mut(name);
+                lines.push(text.join(" "));
+                let excess = lines.len().saturating_sub(SCRIPT_WIDGET_MAX_LINES);
+                lines.drain(..excess);
+            }
+            [name, "set", line, text @ ..] => {
+                let Some(index) = line
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|n| (1..=SCRIPT_WIDGET_MAX_LINES).contains(n))
+                else {
+                    self.add_system_message(&format!("Line must be 1-{}", SCRIPT_WIDGET_MAX_LINES));
+                    return;
+                };
+                let lines = self.plugins.widget_lines_mut(name);
+                if lines.len() < index {
+                    lines.resize(index, String::new());
+                }
+                lines[index - 1] = text.join(" ");
+            }
+            [name, "clear"] => self.plugins.widget_lines_mut(name).clear(),
+            _ => {
+                self.add_system_message(
+                    "Usage: .widget <name> [open [title]|add <text>|set <line> <text>|clear]",
+                );
+                return;
+            }
+        }
+        self.needs_render = true;
+    }
+
+    /// Add a `plugin` window showing widget `name`, unless the layout has one
+    fn open_widget_window(&mut self, name: &str, title: Option<String>) {
+        if self.ui_state.windows.contains_key(name) {
+            return;
+        }
+        let exists = self.layout.windows.iter().any(|w| w.name() == name);
+        let def = if exists {
+            let _ = self.layout.add_window(name);
+            self.layout
+                .windows
+                .iter()
+                .find(|w| w.name() == name)
+                .cloned()
+        } else {
+            Config::get_window_template("plugin").map(|mut def| {
+                let base = def.base_mut();
+                base.name = name.to_string();
+                base.title = Some(title.unwrap_or_else(|| name.to_string()));
+                base.visible = true;
+                self.layout.windows.push(def.clone());
+                def
+            })
+        };
+        let Some(def) = def else {
+            return;
+        };
+
+        let (width, height) = (
+            self.layout.terminal_width.unwrap_or_default(),
+            self.layout.terminal_height.unwrap_or_default(),
+        );
+        self.add_new_window(&def, width, height);
+        self.mark_layout_modified();
+        self.needs_render = true;
+        tracing::info!("Opened widget window '{}'", name);
+    }
+
+    /// Report which containers hold items matching `query` (`.find`)
+    fn find_item(&mut self, query: &str) {
+        let found = self.containers.find(query);
+        if found.is_empty() {
+            let hint = if self.containers.containers.is_empty() {
+                " (no containers seen yet - LOOK IN one first)"
+            } else {
+                ""
+            };
+            self.add_system_message(&format!("No '{}' in any known container{}", query, hint));
+            return;
+        }
+        for (item, path) in found {
+            self.add_system_message(&format!("{}: in your {}", item, path.join(", in your ")));
+        }
+    }
+
+    /// This session's rates plus the last few saved sessions (`.session report`)
+    fn session_report(&mut self) {
+        use crate::core::session_stats::load_snapshots;
+
+        let current = self.session.snapshot(self.loot.session.silvers, None);
+        let mut lines = vec![format!("This session: {}", current.summary())];
+        match Config::sessions_path(self.config.character.as_deref())
+            .and_then(|path| load_snapshots(&path))
+        {
+            Ok(saved) => {
+                for snapshot in saved.iter().rev().take(5) {
+                    lines.push(format!("  {}", snapshot.summary()));
+                }
+            }
+            Err(e) => lines.push(format!("  Couldn't read saved sessions: {:#}", e)),
+        }
+        for line in lines {
+            self.add_system_message(&line);
+        }
+    }
 
-        format!("spacer_{}", max_number + 1)
+    /// Append this session to sessions.toml (`.session save`, and on quit)
+    fn save_session_snapshot(&mut self, label: Option<String>) {
+        let snapshot = self.session.snapshot(self.loot.session.silvers, label);
+        let result = Config::sessions_path(self.config.character.as_deref())
+            .and_then(|path| crate::core::session_stats::save_snapshot(&path, snapshot));
+        match result {
+            Ok(()) => {
+                self.session.mark_saved(self.loot.session.silvers);
+                self.add_system_message("Session saved to sessions.toml");
+            }
+            Err(e) => self.add_system_message(&format!("Failed to save session: {:#}", e)),
+        }
     }
 
-    /// List all loaded highlights
-    fn list_highlights(&mut self) {
-        let count = self.config.highlights.len();
+    /// Warn about a dangerous creature the first time it shows up in a room
+    fn check_creature(&mut self, shown: &str) {
+        let alerts = &self.config.creatures;
+        let Some((name, info)) = self.creatures.lookup(shown) else {
+            return;
+        };
+        if info.danger < alerts.warn_at {
+            return;
+        }
 
-        // Collect all highlight info first to avoid borrow checker issues
-        let mut lines = vec![format!("=== Highlights ({}) ===", count)];
+        let (room, warned) = &mut self.creatures_warned;
+        if *room != self.nav_room_id {
+            *room = self.nav_room_id.clone();
+            warned.clear();
+        }
+        if !warned.insert(name.to_string()) {
+            return;
+        }
 
-        for (name, pattern) in &self.config.highlights {
-            let mut info = format!("  {} - pattern: '{}'", name, pattern.pattern);
-            if let Some(ref fg) = pattern.fg {
-                info.push_str(&format!(" fg:{}", fg));
+        let warning = format!("[Danger] {}", info.describe(name));
+        let spoken = format!("Danger: {}, level {}", name, info.level);
+        let (announce, speak, color) = (alerts.announce, alerts.speak, alerts.color.clone());
+        if announce {
+            self.add_colored_system_message(&warning, &color);
+        }
+        if speak {
+            if let Err(e) = self.tts_manager.announce(crate::tts::SpeechEntry {
+                text: spoken,
+                source_window: "main".to_string(),
+                priority: crate::tts::Priority::High,
+                spoken: false,
+            }) {
+                tracing::warn!("Failed to speak creature warning: {}", e);
             }
-            if let Some(ref bg) = pattern.bg {
-                info.push_str(&format!(" bg:{}", bg));
+        }
+        self.play_game_event(crate::core::sound_events::GameEvent::DangerousCreature);
+    }
+
+    /// Announce a watched character arriving, leaving or dying, and play its sound
+    fn on_watch_event(&mut self, presence: &crate::data::PresenceEvent, sound_played: bool) {
+        use crate::core::sound_events::GameEvent;
+        use crate::data::{PresenceChange, WatchKind};
+
+        let watchlist = &self.config.watchlist;
+        if watchlist.announce {
+            let color = match presence.kind {
+                WatchKind::Friend => watchlist.friend_color.clone(),
+                WatchKind::Enemy => watchlist.enemy_color.clone(),
+            };
+            self.add_colored_system_message(&format!("[Watch] {}", presence.describe()), &color);
+        }
+        // Deaths already played the death sound; friend_login may have fired
+        // for a sounds.toml name
+        if presence.change == PresenceChange::Arrived && !sound_played {
+            self.play_game_event(match presence.kind {
+                WatchKind::Friend => GameEvent::FriendLogin,
+                WatchKind::Enemy => GameEvent::EnemyLogin,
+            });
+        }
+        self.needs_render = true;
+    }
+
+    /// List, add to or remove from the watch list (`.watch`)
+    fn watch_command(&mut self, args: &[&str]) {
+        use crate::data::WatchKind;
+
+        match args {
+            [] | ["list"] => {
+                let names = |names: Vec<String>| {
+                    if names.is_empty() {
+                        "none".to_string()
+                    } else {
+                        names.join(", ")
+                    }
+                };
+                let online =
+                    |kind| names(self.watch.online_of(kind).map(|p| p.name.clone()).collect());
+                let lines = [
+                    format!("Friends: {}", names(self.config.watchlist.friends.clone())),
+                    format!("Enemies: {}", names(self.config.watchlist.enemies.clone())),
+                    format!(
+                        "Seen online: friends {}; enemies {}",
+                        online(WatchKind::Friend),
+                        online(WatchKind::Enemy)
+                    ),
+                ];
+                for line in lines {
+                    self.add_system_message(&line);
+                }
             }
-            if pattern.bold {
-                info.push_str(" bold");
+            ["add", name, rest @ ..] if rest.len() <= 1 => {
+                let Some(kind) = rest
+                    .first()
+                    .map_or(Some(WatchKind::Friend), |k| WatchKind::from_str(k))
+                else {
+                    self.add_system_message("Usage: .watch add <name> [friend|enemy]");
+                    return;
+                };
+                let watchlist = &mut self.config.watchlist;
+                watchlist.friends.retain(|n| !n.eq_ignore_ascii_case(name));
+                watchlist.enemies.retain(|n| !n.eq_ignore_ascii_case(name));
+                match kind {
+                    WatchKind::Friend => watchlist.friends.push(name.to_string()),
+                    WatchKind::Enemy => watchlist.enemies.push(name.to_string()),
+                }
+                self.save_watchlist();
+                self.add_system_message(&format!(
+                    "Watching {} as {}",
+                    name,
+                    kind.label().to_lowercase()
+                ));
             }
-            lines.push(info);
+            ["remove", name] => {
+                let watchlist = &mut self.config.watchlist;
+                let before = watchlist.friends.len() + watchlist.enemies.len();
+                watchlist.friends.retain(|n| !n.eq_ignore_ascii_case(name));
+                watchlist.enemies.retain(|n| !n.eq_ignore_ascii_case(name));
+                if watchlist.friends.len() + watchlist.enemies.len() == before {
+                    self.add_system_message(&format!("{} isn't on the watch list", name));
+                    return;
+                }
+                self.save_watchlist();
+                self.add_system_message(&format!("Stopped watching {}", name));
+            }
+            _ => self.add_system_message("Usage: .watch [add <name> [friend|enemy]|remove <name>]"),
+        }
+    }
+
+    /// Write watchlist.toml and drop anyone no longer listed from the online set
+    fn save_watchlist(&mut self) {
+        let watchlist = &self.config.watchlist;
+        self.watch
+            .retain_listed(&watchlist.friends, &watchlist.enemies);
+        if let Err(e) = self.config.save_watchlist(self.config.character.as_deref()) {
+            tracing::error!("Failed to save watch list: {}", e);
         }
+        self.needs_render = true;
+    }
 
-        // Add all messages
+    /// Loot totals for the session, the current hunt and closed hunts (`.loot report`)
+    fn loot_report(&mut self) {
+        use crate::data::{format_elapsed, with_commas};
+
+        let session_elapsed = self.loot.session_elapsed();
+        let mut lines = vec![
+            format!(
+                "Loot this session ({}): {}, {} silvers/hr",
+                format_elapsed(session_elapsed),
+                self.loot.session.summary(),
+                with_commas(self.loot.session.silvers_per_hour(session_elapsed))
+            ),
+            format!(
+                "  Current hunt ({}): {}",
+                format_elapsed(self.loot.hunt.elapsed()),
+                self.loot.hunt.totals.summary()
+            ),
+        ];
+        for (i, hunt) in self.loot.past_hunts.iter().enumerate().rev() {
+            lines.push(format!(
+                "  Hunt {} ({}, from {}): {}",
+                i + 1,
+                format_elapsed(hunt.elapsed()),
+                hunt.started.format("%H:%M"),
+                hunt.totals.summary()
+            ));
+        }
+        let gems: Vec<String> = self
+            .loot
+            .session
+            .top_gems()
+            .into_iter()
+            .map(|(name, count)| format!("{} {}", count, name))
+            .collect();
+        if !gems.is_empty() {
+            lines.push(format!("  Gems: {}", gems.join(", ")));
+        }
         for line in lines {
             self.add_system_message(&line);
         }
     }
 
-    /// Add a system message to the main window
-    pub fn add_system_message(&mut self, message: &str) {
-        use crate::data::{SpanType, StyledLine, TextSegment, WindowContent};
+    /// Convert a StormFront/VellumFE layout into a saved layout (`.importlayout <file> [name]`)
+    fn import_layout(&mut self, args: &[&str]) {
+        use crate::config::layout_import::import_file;
 
-        if let Some(main_window) = self.ui_state.get_window_mut("main") {
-            if let WindowContent::Text(ref mut content) = main_window.content {
-                let line = StyledLine {
-                    segments: vec![TextSegment {
-                        text: message.to_string(),
-                        fg: Some("#00ff00".to_string()),
-                        bg: None,
-                        bold: true,
-                        span_type: SpanType::Normal,
-                        link_data: None,
-                    }],
-                };
-                content.add_line(line);
-                self.needs_render = true;
+        let Some(path) = args.first().map(std::path::Path::new) else {
+            self.add_system_message("Usage: .importlayout <file.sal|file.toml> [name]");
+            return;
+        };
+        let name = match args.get(1) {
+            Some(name) => name.to_string(),
+            None => match path.file_stem().and_then(|s| s.to_str()) {
+                Some(stem) => stem.to_string(),
+                None => {
+                    self.add_system_message("Usage: .importlayout <file.sal|file.toml> [name]");
+                    return;
+                }
+            },
+        };
+        if Config::layout_path(&name).is_ok_and(|p| p.exists()) {
+            self.add_system_message(&format!(
+                "Layout '{}' already exists - pick another name: .importlayout <file> <name>",
+                name
+            ));
+            return;
+        }
+
+        let screen = (
+            self.layout.terminal_width.unwrap_or(80),
+            self.layout.terminal_height.unwrap_or(24),
+        );
+        let mut report = match import_file(path, None, screen) {
+            Ok(report) => report,
+            Err(e) => {
+                self.add_system_message(&format!("Layout import failed: {:#}", e));
+                return;
+            }
+        };
+        if let Err(e) = report.layout.save(&name, None, false) {
+            self.add_system_message(&format!("Failed to save imported layout: {}", e));
+            return;
+        }
+        for (source, reason) in &report.skipped {
+            self.add_system_message(&format!("  Skipped {}: {}", source, reason));
+        }
+        self.add_system_message(&format!(
+            "Imported layout '{}' ({} windows, {} skipped) - load it with .loadlayout {}",
+            name,
+            report.layout.windows.len(),
+            report.skipped.len(),
+            name
+        ));
+    }
+
+    /// Import a base16 YAML or iTerm2 scheme as a custom theme (`.importtheme <file> [name]`)
+    fn import_theme(&mut self, args: &[&str]) {
+        let Some(path) = args.first() else {
+            self.add_system_message("Usage: .importtheme <file.yaml|file.itermcolors> [name]");
+            return;
+        };
+
+        let theme = match crate::theme::import::import_scheme(
+            std::path::Path::new(path),
+            args.get(1).copied(),
+        ) {
+            Ok(theme) => theme,
+            Err(e) => {
+                self.add_system_message(&format!("Theme import failed: {:#}", e));
+                return;
+            }
+        };
+
+        match crate::theme::ThemePresets::save_custom_theme(
+            &theme,
+            self.config.character.as_deref(),
+        ) {
+            Ok(saved) => {
+                tracing::info!("Imported theme '{}' to {:?}", theme.name, saved);
+                self.add_system_message(&format!(
+                    "Imported theme '{}' - pick it in .themes or use .settheme {}",
+                    theme.name, theme.name
+                ));
             }
+            Err(e) => self.add_system_message(&format!("Failed to save imported theme: {}", e)),
+        }
+    }
+
+    /// List the most recent spells from the spell hand history
+    fn show_spell_history(&mut self, count: usize) {
+        let history = &self.game_state.spell_history;
+        if history.is_empty() {
+            self.add_system_message("No spells prepared this session");
+            return;
+        }
+        let lines: Vec<String> = history
+            .iter()
+            .skip(history.len().saturating_sub(count))
+            .map(|record| record.summary())
+            .collect();
+        self.add_system_message(&format!("=== Last {} spell(s) ===", lines.len()));
+        for line in lines {
+            self.add_system_message(&line);
         }
     }
 
     /// Show help for dot commands
     fn show_help(&mut self) {
         self.add_system_message("=== Two-Face Dot Commands ===");
-        self.add_system_message("Application: .quit/.q, .help/.h/.?, .menu, .settings");
         self.add_system_message(
-            "Layouts: .savelayout [name], .loadlayout [name], .layouts, .resize",
+            "Application: .quit/.q, .help/.h/.?, .menu, .settings, .spectator, .passthrough, .websocket, .relay, .redirects [reload], .push [test], .perf [on|off|reset|csv [file]], .lastspells [n], .mouse [on|off]",
+        );
+        self.add_system_message(
+            "             .screenreader [on|off] (plain chrome, spoken output, review keys)",
+        );
+        self.add_system_message(
+            "Layouts: .savelayout [name], .loadlayout [name], .layouts, .resize, .defaultlayout [name|none|ask]",
         );
+        self.add_system_message(
+            "         .importlayout <file> [name] (StormFront .sal or VellumFE layout)",
+        );
+        self.add_system_message("         .compact [on|off|auto] (narrow-terminal profile)");
         self.add_system_message("Windows: .windows, .addwindow <name> <type> <x> <y> <w> [h]");
         self.add_system_message(
             "         .deletewindow <name>, .rename <win> <title>, .editwindow [name]",
         );
-        self.add_system_message("         .border <win> <style> [color]");
+        self.add_system_message(
+            "         .border <win> <style> [color], .raise [win], .lower [win]",
+        );
         self.add_system_message("Highlights: .highlights, .addhighlight, .edithighlight <name>");
-        self.add_system_message("Keybinds: .keybinds, .addkeybind");
+        self.add_system_message(
+            "Keybinds: .keybinds, .addkeybind, .importkeys <file> [wizard|stormfront] [overwrite]",
+        );
         self.add_system_message(
             "Colors: .colors, .addcolor, .uicolors, .spellcolors, .addspellcolor",
         );
-        self.add_system_message("Themes: .themes, .settheme <name>");
+        self.add_system_message(
+            "Review: .configdiff (unsaved highlight/keybind/color edits; Del discards one)",
+        );
+        self.add_system_message("Themes: .themes, .settheme <name>, .termtheme (match terminal palette), .importtheme <file> [name]");
+        self.add_system_message("Actions: .actions, .action <name>, .mode [name|off]");
+        self.add_system_message("Speech: .ttssettings (voices per window), .ttsvoices");
+        self.add_system_message(
+            "Sounds: .sounds [reload|test <event>] (event sounds from sounds.toml)",
+        );
+        self.add_system_message(
+            "         .sounds pack [<name>|none] (swap in ~/.two-face/sounds/<name>/)",
+        );
+        self.add_system_message(
+            "         .mute (toggle all sounds), .dnd [<minutes>|off] (do not disturb)",
+        );
+        self.add_system_message("AFK: .afk (mark away now; whispers get [afk] auto_reply, missed lines go to the afk window)");
+        self.add_system_message(
+            "Search: .search <win> <pattern|@saved>, .savesearch <name> [pattern], .searches, .delsearch <name>, .pin <win> <pattern|@saved>, .unpin [win]",
+        );
+        self.add_system_message(
+            "Dump: .dump <win> [file] [--force] (buffer to ~/.two-face/<character>/dumps/)",
+        );
+        self.add_system_message(
+            "Snippets: .snippet <name> [values], .snippets (browser), .addsnippet <name> [text], .delsnippet <name>",
+        );
+        self.add_system_message("Tracking: .loot [report|reset|reset session] (loot_tracker window shows live totals), .bounty (bounty_tracker window), .creature <name>");
+        self.add_system_message("Containers: .find <item> - which container holds it (containers window shows the tree; LOOK IN a container to fill it in)");
+        self.add_system_message("Session: .session [report|save [label]|reset] (session_stats window; saved to sessions.toml on quit)");
+        self.add_system_message("Plugins: .plugin [list|settings <name>|set <name> <key> <value>] (libraries in ~/.two-face/plugins/)");
+        self.add_system_message(
+            "Script widgets: .widget <name> [open [title]|add <text>|set <line> <text>|clear]",
+        );
+        self.add_system_message("Watch list: .watch [add <name> [friend|enemy]|remove <name>] (watch_list window shows who's online)");
     }
 
     /// Save current layout
     pub fn save_layout(&mut self, name: &str, terminal_width: u16, terminal_height: u16) {
+        // Compact borders/titles are a view of the layout, not part of it
+        self.without_compact(|app| {
+            app.save_layout_as_written(name, terminal_width, terminal_height)
+        })
+    }
+
+    fn save_layout_as_written(&mut self, name: &str, terminal_width: u16, terminal_height: u16) {
         tracing::info!("========== SAVE LAYOUT: '{}' START ==========", name);
         tracing::info!(
             "Current terminal size: {}x{}",
@@ -1599,7 +3940,12 @@ impl AppCore {
         tracing::info!("Loading layout from: {}", layout_path.display());
 
         match Layout::load_from_file(&layout_path) {
-            Ok(new_layout) => {
+            Ok(mut new_layout) => {
+                // Percentage and flex sizes fit the layout to this terminal
+                if new_layout.resolve_sizes(terminal_width, terminal_height) {
+                    new_layout.terminal_width = Some(terminal_width);
+                    new_layout.terminal_height = Some(terminal_height);
+                }
                 let theme_update = self.apply_layout_theme(new_layout.theme.as_deref());
                 tracing::info!("Layout file loaded successfully");
                 tracing::info!("Loaded layout has {} windows", new_layout.windows.len());
@@ -1657,6 +4003,7 @@ impl AppCore {
                 // Store new layout
                 let old_layout = std::mem::replace(&mut self.layout, new_layout.clone());
                 self.baseline_layout = Some(new_layout);
+                self.compact_chrome = None; // Reapplied to the new layout on the next update
 
                 tracing::info!("Calling sync_layout_to_ui_state to apply changes...");
 
@@ -1734,6 +4081,7 @@ impl AppCore {
 
         // Reset layout to baseline (critical - prevents cumulative scaling errors)
         self.layout = baseline_layout;
+        self.compact_chrome = None; // Reapplied to the new layout on the next update
 
         tracing::info!("Reset to baseline layout - now applying proportional distribution...");
 
@@ -1777,6 +4125,7 @@ impl AppCore {
         // Update layout terminal size to current
         self.layout.terminal_width = Some(terminal_width);
         self.layout.terminal_height = Some(terminal_height);
+        self.layout.resolve_sizes(terminal_width, terminal_height);
 
         // Apply resized positions to UI state
         for window_def in &self.layout.windows {
@@ -1798,6 +4147,7 @@ impl AppCore {
                 );
             }
         }
+        self.apply_container_layout();
 
         self.needs_render = true;
         self.add_system_message(&format!(
@@ -1819,6 +4169,35 @@ impl AppCore {
         }
     }
 
+    /// Snap targets for dragging `window_name`: the edges of every other visible
+    /// window outside its container, per `ui.snap_grid` and `ui.snap_to_windows`
+    pub fn window_snapper(
+        &self,
+        window_name: &str,
+        screen: (u16, u16),
+    ) -> crate::core::snap::Snapper {
+        let others: Vec<ratatui::layout::Rect> = self
+            .ui_state
+            .windows
+            .values()
+            .filter(|w| w.visible && self.container_root(&w.name) != window_name)
+            .map(|w| {
+                ratatui::layout::Rect::new(
+                    w.position.x,
+                    w.position.y,
+                    w.position.width,
+                    w.position.height,
+                )
+            })
+            .collect();
+        crate::core::snap::Snapper::new(
+            &others,
+            screen,
+            self.config.ui.snap_grid,
+            self.config.ui.snap_to_windows,
+        )
+    }
+
     pub fn window_min_size(&self, window_name: &str) -> (u16, u16) {
         if let Some(window_def) = self.layout.windows.iter().find(|w| w.name() == window_name) {
             let (default_min_cols, default_min_rows) =
@@ -2450,6 +4829,8 @@ impl AppCore {
             tracing::info!("No windows to remove");
         }
 
+        self.stack_in_layout_order();
+        self.apply_container_layout();
         tracing::info!("--- sync_layout_to_ui_state COMPLETE ---");
     }
 
@@ -2464,10 +4845,15 @@ impl AppCore {
         };
 
         match Layout::load_from_file(&layout_path) {
-            Ok(new_layout) => {
+            Ok(mut new_layout) => {
+                if new_layout.resolve_sizes(width, height) {
+                    new_layout.terminal_width = Some(width);
+                    new_layout.terminal_height = Some(height);
+                }
                 self.apply_layout_theme(new_layout.theme.as_deref());
                 self.layout = new_layout.clone();
                 self.baseline_layout = Some(new_layout);
+                self.compact_chrome = None; // Reapplied to the new layout on the next update
                 self.add_system_message(&format!("Layout '{}' loaded", name));
 
                 // Clear modified flag and update base layout name
@@ -2671,6 +5057,158 @@ impl AppCore {
         self.resize_to_terminal(width, height);
     }
 
+    /// Is the compact profile (narrow terminals) in effect?
+    pub fn compact_active(&self) -> bool {
+        self.compact_on
+    }
+
+    /// Enter or leave compact mode to match the terminal width (or `.compact on|off`)
+    pub fn update_compact_mode(&mut self, terminal_width: u16) {
+        let wanted = self.compact_override.unwrap_or_else(|| {
+            let threshold = self.config.ui.compact_width;
+            threshold > 0 && terminal_width < threshold
+        });
+        // A reloaded layout comes back without its compact/screen reader chrome
+        let reloaded = self.compact_chrome.is_none() && (wanted || self.screen_reader_on);
+        if wanted != self.compact_active() || reloaded {
+            self.set_compact_mode(wanted);
+        }
+    }
+
+    /// Swap compact borders/titles in or out and show or hide low-priority windows
+    fn set_compact_mode(&mut self, wanted: bool) {
+        self.compact_on = wanted;
+        self.reapply_chrome();
+
+        // Low-priority windows keep their content while hidden
+        let min_priority = self.config.ui.compact_min_priority;
+        let mut hidden = 0;
+        for def in &self.layout.windows {
+            let Some(window) = self.ui_state.windows.get_mut(def.name()) else {
+                continue;
+            };
+            window.visible = !wanted || crate::core::compact::priority(def) >= min_priority;
+            if !window.visible {
+                hidden += 1;
+            }
+            if let WindowContent::Text(content) = &mut window.content {
+                content.title = def
+                    .base()
+                    .title
+                    .clone()
+                    .unwrap_or_else(|| def.name().to_string());
+            }
+        }
+        tracing::info!(
+            "Compact mode {} ({} window(s) hidden)",
+            if wanted { "on" } else { "off" },
+            hidden
+        );
+        self.needs_render = true;
+    }
+
+    /// Put back the written borders/titles, then apply compact or screen reader chrome
+    fn reapply_chrome(&mut self) {
+        if let Some(chrome) = self.compact_chrome.take() {
+            crate::core::compact::restore(&mut self.layout, &chrome);
+        }
+        if self.compact_on || self.screen_reader_on {
+            let borders = if self.screen_reader_on {
+                "none"
+            } else {
+                self.config.ui.compact_borders.as_str()
+            };
+            self.compact_chrome = Some(crate::core::compact::apply(
+                &mut self.layout,
+                borders,
+                self.compact_on,
+            ));
+        }
+    }
+
+    /// Run `f` against the layout as written (compact borders/titles undone), e.g. to save it
+    fn without_compact<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> R {
+        let Some(chrome) = self.compact_chrome.take() else {
+            return f(self);
+        };
+        crate::core::compact::restore(&mut self.layout, &chrome);
+        let result = f(self);
+        self.reapply_chrome();
+        result
+    }
+
+    /// Is screen reader mode on?
+    pub fn screen_reader_active(&self) -> bool {
+        self.screen_reader_on
+    }
+
+    /// Turn screen reader mode on or off (`.screenreader`, `screen_reader.enabled`)
+    pub fn set_screen_reader(&mut self, on: bool) {
+        self.screen_reader_on = on;
+        self.reapply_chrome();
+        self.review.release();
+        self.message_processor.set_screen_reader(
+            on,
+            std::time::Duration::from_secs(self.config.screen_reader.dedupe_seconds),
+        );
+        // Screen reader mode speaks even with [tts] disabled
+        self.tts_manager.set_enabled(on || self.config.tts.enabled);
+        self.needs_render = true;
+        self.add_system_message(if on {
+            "Screen reader mode on (review output with review_previous/review_next)"
+        } else {
+            "Screen reader mode off"
+        });
+    }
+
+    /// Line being reviewed with its position and the buffer size (None = following live output)
+    pub fn review_line(&self) -> Option<(usize, usize, &crate::core::screen_reader::ReviewLine)> {
+        self.review.current()
+    }
+
+    /// Open a web link from the game text in the default browser
+    pub fn open_url(&mut self, url: &str) {
+        match crate::core::urls::open_in_browser(url) {
+            Ok(()) => self.add_system_message(&format!("Opening {}", url)),
+            Err(e) => self.add_system_message(&format!("{:#}", e)),
+        }
+        self.needs_render = true;
+    }
+
+    /// Move through recent output and read the line aloud
+    fn review_step(&mut self, action: &crate::config::KeyAction) {
+        if !self.screen_reader_on {
+            self.add_system_message("Screen reader mode is off (.screenreader on)");
+            return;
+        }
+        let text = match action {
+            crate::config::KeyAction::ReviewPrevious => {
+                self.review.previous().map(|line| line.text.clone())
+            }
+            crate::config::KeyAction::ReviewNext => {
+                self.review.next().map(|line| line.text.clone())
+            }
+            _ => {
+                self.review.release();
+                Some("Back to live output".to_string())
+            }
+        };
+        self.needs_render = true;
+        let Some(text) = text else {
+            return;
+        };
+        // Cut off whatever is playing; the user asked for this line
+        let _ = self.tts_manager.stop();
+        if let Err(e) = self.tts_manager.announce(crate::tts::SpeechEntry {
+            text,
+            source_window: "review".to_string(),
+            priority: crate::tts::Priority::Critical,
+            spoken: false,
+        }) {
+            tracing::warn!("Failed to speak review line: {}", e);
+        }
+    }
+
     /// List all windows
     fn list_windows(&mut self) {
         let window_count = self.ui_state.windows.len();
@@ -2718,6 +5256,8 @@ impl AppCore {
         if found_count > 0 {
             // Remove from UI state (but keep in layout!)
             self.ui_state.remove_window(name);
+            // Siblings in the same container take over the freed space
+            self.apply_container_layout();
 
             let msg = if found_count > 1 {
                 format!(
@@ -2899,6 +5439,9 @@ impl AppCore {
             min_cols: None,
             max_cols: None,
             visible: true,
+            priority: None,
+            width: None,
+            height: None,
         };
 
         let window_def = match widget_type_str.to_lowercase().as_str() {
@@ -2907,6 +5450,11 @@ impl AppCore {
                 data: TextWidgetData {
                     streams: vec![],
                     buffer_size: 1000,
+                    include: Vec::new(),
+                    exclude: Vec::new(),
+                    timestamps: None,
+                    collapse_repeats: None,
+                    gap_separator_minutes: None,
                 },
             },
             "room" => WindowDef::Room {
@@ -2931,6 +5479,11 @@ impl AppCore {
                     data: TextWidgetData {
                         streams: vec![],
                         buffer_size: 1000,
+                        include: Vec::new(),
+                        exclude: Vec::new(),
+                        timestamps: None,
+                        collapse_repeats: None,
+                        gap_separator_minutes: None,
                     },
                 }
             }
@@ -2966,6 +5519,77 @@ impl AppCore {
         }
     }
 
+    /// Draw a window (and a container's members) above every other window, or
+    /// below them. The layout keeps the same order so it is saved.
+    fn restack_window(&mut self, window_name: &str, to_top: bool) {
+        if !self.ui_state.windows.contains_key(window_name) {
+            self.add_system_message(&format!("Window '{}' not found", window_name));
+            return;
+        }
+
+        // A container's members stay stacked just above it
+        let group: Vec<String> = self
+            .ui_state
+            .z_order
+            .iter()
+            .filter(|name| {
+                let mut current = name.as_str();
+                for _ in 0..=self.layout.windows.len() {
+                    if current == window_name {
+                        return true;
+                    }
+                    match self.layout.container_of(current) {
+                        Some(parent) => current = parent,
+                        None => break,
+                    }
+                }
+                false
+            })
+            .cloned()
+            .collect();
+        self.ui_state.restack(&group, to_top);
+
+        let (mut moved, rest): (Vec<_>, Vec<_>) = std::mem::take(&mut self.layout.windows)
+            .into_iter()
+            .partition(|w| group.iter().any(|name| name == w.name()));
+        moved.sort_by_key(|w| group.iter().position(|name| name == w.name()));
+        self.layout.windows = if to_top {
+            rest.into_iter().chain(moved).collect()
+        } else {
+            moved.into_iter().chain(rest).collect()
+        };
+        self.layout_modified_since_save = true;
+        self.needs_render = true;
+    }
+
+    /// Focus the next (or previous) text window in reading order: the target
+    /// of scrolling and search keys, shown with a highlighted border
+    pub fn cycle_focus(&mut self, forward: bool) {
+        let mut candidates: Vec<(&String, &crate::data::WindowState)> = self
+            .ui_state
+            .windows
+            .iter()
+            .filter(|(_, w)| w.visible && matches!(w.content, WindowContent::Text(_)))
+            .collect();
+        if candidates.is_empty() {
+            return;
+        }
+        candidates.sort_by_key(|(name, w)| (w.position.y, w.position.x, name.as_str()));
+        let names: Vec<String> = candidates
+            .into_iter()
+            .map(|(name, _)| name.clone())
+            .collect();
+
+        let current = self.get_focused_window_name();
+        let next = match names.iter().position(|name| *name == current) {
+            Some(i) if forward => (i + 1) % names.len(),
+            Some(i) => (i + names.len() - 1) % names.len(),
+            None => 0,
+        };
+        self.ui_state.set_focus(Some(names[next].clone()));
+        self.needs_render = true;
+    }
+
     /// Set window border style and color
     fn set_window_border(&mut self, window_name: &str, style: &str, color: Option<String>) {
         if let Some(window_def) = self
@@ -3049,6 +5673,7 @@ impl AppCore {
                 window.position = position;
             }
         }
+        self.apply_container_layout();
 
         self.needs_render = true;
     }
@@ -3170,6 +5795,115 @@ impl AppCore {
         positions
     }
 
+    /// Make `theme_id` the active theme and save it, as `.settheme` does
+    ///
+    /// Unknown ids are reported with the list of available themes; returns
+    /// whether the theme was switched.
+    pub fn switch_theme(&mut self, theme_id: &str) -> bool {
+        // Validate theme exists (includes built-in and custom)
+        let theme_presets =
+            crate::theme::ThemePresets::all_with_custom(self.config.character.as_deref());
+        if !theme_presets.contains_key(theme_id) {
+            self.add_system_message(&format!("Unknown theme: {}", theme_id));
+            self.add_system_message(&Self::available_themes_message(&theme_presets));
+            return false;
+        }
+
+        self.config.active_theme = theme_id.to_string();
+        self.add_system_message(&format!("Theme switched to: {}", theme_id));
+        if let Err(e) = self.config.save(self.config.character.as_deref()) {
+            tracing::error!("Failed to save config after theme change: {}", e);
+            self.add_system_message(&format!("Warning: Failed to save theme preference: {}", e));
+        }
+        true
+    }
+
+    /// Popup for a clicked injury doll body part: wound/scar rank, bleeding,
+    /// and what to treat it with
+    pub fn injury_details_menu(
+        &self,
+        window_name: &str,
+        part: &str,
+    ) -> Vec<crate::data::ui_state::PopupMenuItem> {
+        let level = match self.ui_state.get_window(window_name).map(|w| &w.content) {
+            Some(WindowContent::InjuryDoll(data)) => data.injuries.get(part).copied().unwrap_or(0),
+            _ => 0,
+        };
+        injury_menu_items(crate::core::injuries::details(
+            part,
+            level,
+            self.game_state.status.bleeding,
+            &self.config.injury_remedies,
+        ))
+    }
+
+    /// Expand or collapse an inventory container node, remembered in the layout
+    pub fn toggle_inventory_node(&mut self, window_name: &str, key: &str) {
+        let Some(crate::config::WindowDef::Inventory { data, .. }) = self
+            .layout
+            .windows
+            .iter_mut()
+            .find(|w| w.name() == window_name)
+        else {
+            return;
+        };
+        match data.collapsed.iter().position(|k| k == key) {
+            Some(idx) => {
+                data.collapsed.remove(idx);
+            }
+            None => data.collapsed.push(key.to_string()),
+        }
+        self.mark_layout_modified();
+
+        // Bump the generation so the window redraws
+        if let Some(WindowContent::Inventory(content)) = self
+            .ui_state
+            .get_window_mut(window_name)
+            .map(|w| &mut w.content)
+        {
+            content.generation = content.generation.wrapping_add(1);
+        }
+        self.needs_render = true;
+    }
+
+    /// Popup for a clicked name in the players window. Commands go out like
+    /// `_menu` items; "macro:" ones run as macro text so whisper can leave
+    /// the line in the command input and `.watch` runs locally.
+    pub fn player_menu(&self, name: &str) -> Vec<crate::data::ui_state::PopupMenuItem> {
+        let item = |text: &str, command: String| crate::data::ui_state::PopupMenuItem {
+            text: text.to_string(),
+            command,
+            disabled: false,
+        };
+        let mut items = vec![
+            item("Look", format!("look {}", name)),
+            item("Whisper...", format!("macro:whisper {} @", name)),
+            item("Group invite", format!("group {}", name)),
+        ];
+        let listed = self
+            .config
+            .watchlist
+            .friends
+            .iter()
+            .any(|friend| friend.eq_ignore_ascii_case(name));
+        if !listed {
+            items.push(item(
+                "Add to friends",
+                format!("macro:.watch add {} friend", name),
+            ));
+        }
+        items
+    }
+
+    /// Run macro text picked from a popup menu ("macro:" commands)
+    pub fn start_menu_macro(&mut self, text: &str) {
+        if self.active_macro.is_some() {
+            tracing::debug!("Menu macro replaces the running macro");
+        }
+        self.active_macro = Some(crate::core::macros::MacroRun::new(text));
+        self.needs_render = true;
+    }
+
     /// Build main menu for .menu command
     fn build_main_menu(&self) -> Vec<crate::data::ui_state::PopupMenuItem> {
         vec![
@@ -3208,6 +5942,16 @@ impl AppCore {
                 command: "__SUBMENU__windows".to_string(),
                 disabled: false,
             },
+            crate::data::ui_state::PopupMenuItem {
+                text: "Copy selection as ANSI".to_string(),
+                command: "action:copyansi".to_string(),
+                disabled: self.ui_state.last_copy.is_none(),
+            },
+            crate::data::ui_state::PopupMenuItem {
+                text: "Copy selection as HTML".to_string(),
+                command: "action:copyhtml".to_string(),
+                disabled: self.ui_state.last_copy.is_none(),
+            },
         ]
     }
 
@@ -3287,6 +6031,11 @@ impl AppCore {
                 command: ".edittheme".to_string(),
                 disabled: false,
             },
+            crate::data::ui_state::PopupMenuItem {
+                text: "Match terminal colors".to_string(),
+                command: ".termtheme".to_string(),
+                disabled: false,
+            },
         ]
     }
 
@@ -3344,8 +6093,117 @@ impl AppCore {
         items
     }
 
+    /// Saved layout to load at startup instead of last session's (ui.startup_layout)
+    pub fn startup_layout(&self) -> Option<String> {
+        let name = self.config.ui.startup_layout.trim();
+        if name.is_empty() {
+            return None;
+        }
+        if !Config::layout_path(name).is_ok_and(|path| path.exists()) {
+            tracing::warn!("Startup layout '{}' no longer exists", name);
+            return None;
+        }
+        Some(name.to_string())
+    }
+
+    /// Startup dialog offering the saved layouts
+    ///
+    /// Only shown when there is a real choice: several saved layouts, none
+    /// set as the default, and the user hasn't turned the question off.
+    pub fn build_startup_layout_menu(&self) -> Option<Vec<crate::data::ui_state::PopupMenuItem>> {
+        use crate::data::ui_state::PopupMenuItem;
+
+        if !self.config.ui.ask_startup_layout || self.startup_layout().is_some() {
+            return None;
+        }
+        let layouts = Config::list_layouts().ok()?;
+        if layouts.len() < 2 {
+            return None;
+        }
+
+        let mut items = Vec::new();
+        for name in layouts {
+            let summary = Config::layout_path(&name)
+                .and_then(|path| Layout::load_from_file(&path))
+                .map(|layout| {
+                    let windows = layout.windows.iter().filter(|w| w.base().visible).count();
+                    match (layout.terminal_width, layout.terminal_height) {
+                        (Some(w), Some(h)) => format!("{} windows, {}x{}", windows, w, h),
+                        _ => format!("{} windows", windows),
+                    }
+                })
+                .unwrap_or_else(|_| "unreadable".to_string());
+            items.push(PopupMenuItem {
+                text: format!("{} ({})", name, summary),
+                command: format!("__SUBMENU__startlayout:{}", name),
+                disabled: false,
+            });
+        }
+        items.push(PopupMenuItem {
+            text: "Keep last session's layout".to_string(),
+            command: String::new(),
+            disabled: false,
+        });
+        items.push(PopupMenuItem {
+            text: "Keep it and don't ask again".to_string(),
+            command: "action:nolayoutprompt".to_string(),
+            disabled: false,
+        });
+        Some(items)
+    }
+
+    /// Options for one layout in the startup dialog
+    fn build_startup_layout_submenu(
+        &self,
+        name: &str,
+    ) -> Vec<crate::data::ui_state::PopupMenuItem> {
+        vec![
+            crate::data::ui_state::PopupMenuItem {
+                text: "Load this time".to_string(),
+                command: format!("action:loadlayout:{}", name),
+                disabled: false,
+            },
+            crate::data::ui_state::PopupMenuItem {
+                text: "Load and set as default".to_string(),
+                command: format!("action:defaultlayout:{}", name),
+                disabled: false,
+            },
+        ]
+    }
+
+    /// Set or clear the layout loaded at startup, and save the config
+    pub fn set_startup_layout(&mut self, name: Option<&str>) {
+        self.config.ui.startup_layout = name.unwrap_or_default().to_string();
+        match self.save_config() {
+            Ok(()) => match name {
+                Some(name) => {
+                    self.add_system_message(&format!("Layout '{}' will load at startup", name))
+                }
+                None => self.add_system_message("Startup layout cleared"),
+            },
+            Err(e) => self.add_system_message(&format!("Failed to save config: {}", e)),
+        }
+    }
+
+    /// Turn the startup layout dialog on or off, and save the config
+    pub fn set_ask_startup_layout(&mut self, ask: bool) {
+        self.config.ui.ask_startup_layout = ask;
+        if let Err(e) = self.save_config() {
+            self.add_system_message(&format!("Failed to save config: {}", e));
+        } else if ask {
+            self.add_system_message("Startup layout dialog turned on");
+        } else {
+            self.add_system_message(
+                "Startup layout dialog turned off (.defaultlayout ask turns it back on)",
+            );
+        }
+    }
+
     /// Build submenu based on category name
     pub fn build_submenu(&self, category: &str) -> Vec<crate::data::ui_state::PopupMenuItem> {
+        if let Some(name) = category.strip_prefix("startlayout:") {
+            return self.build_startup_layout_submenu(name);
+        }
         match category {
             "colors" => self.build_colors_submenu(),
             "highlights" => self.build_highlights_submenu(),
@@ -3631,6 +6489,16 @@ impl AppCore {
             );
         }
 
+        if self.session.has_unsaved(self.loot.session.silvers) {
+            self.save_session_snapshot(None);
+        }
+        self.plugins.shutdown();
+
+        // Autosave the layout as written, not its compact view
+        if let Some(chrome) = self.compact_chrome.take() {
+            crate::core::compact::restore(&mut self.layout, &chrome);
+        }
+
         // Autosave to character-specific layout.toml (if character is set)
         if let Some(ref character) = self.config.character {
             let terminal_size = self
@@ -3665,41 +6533,407 @@ impl AppCore {
                 .terminal_width
                 .and_then(|w| self.layout.terminal_height.map(|h| (w, h)));
 
-            let base_layout_name = self
-                .base_layout_name
-                .clone()
-                .or_else(|| self.layout.base_layout.clone())
-                .unwrap_or_else(|| "default".to_string());
+            let base_layout_name = self
+                .base_layout_name
+                .clone()
+                .or_else(|| self.layout.base_layout.clone())
+                .unwrap_or_else(|| "default".to_string());
+
+            self.layout.theme = Some(self.config.active_theme.clone());
+            if let Err(e) = self
+                .layout
+                .save_auto("default", &base_layout_name, terminal_size)
+            {
+                tracing::warn!("Failed to autosave layout on quit: {}", e);
+            } else {
+                tracing::info!(
+                    "Layout autosaved to default profile (base: {}, terminal: {:?})",
+                    base_layout_name,
+                    terminal_size
+                );
+            }
+        }
+
+        self.running = false;
+    }
+
+    /// Save configuration to disk
+    pub fn save_config(&mut self) -> Result<()> {
+        self.config.save(self.config.character.as_deref())
+    }
+
+    /// Validate, store, and apply one setting from the settings editor
+    ///
+    /// Live settings take effect here (or are read from config on next use);
+    /// the returned outcome says whether a restart is needed and names any
+    /// action the frontend should run to finish applying it.
+    pub fn apply_setting(
+        &mut self,
+        key: &str,
+        value: &crate::config::settings::SettingValue,
+    ) -> Result<SettingApplied> {
+        use crate::config::settings::ApplyMode;
+
+        // Theme names depend on what's installed, so the registry can't check them.
+        // "terminal" is always accepted; the follow-up action reads the palette.
+        if matches!(
+            key,
+            "active_theme" | "theme_schedule.day_theme" | "theme_schedule.night_theme"
+        ) {
+            let name = value.to_display_string();
+            let name = name.trim();
+            let theme_presets =
+                crate::theme::ThemePresets::all_with_custom(self.config.character.as_deref());
+            if name != crate::theme::terminal::TERMINAL_THEME && !theme_presets.contains_key(name) {
+                anyhow::bail!("Unknown theme '{}'", name);
+            }
+        }
+
+        if matches!(
+            key,
+            "theme_schedule.day_start" | "theme_schedule.night_start"
+        ) {
+            crate::core::theme_schedule::parse_clock(&value.to_display_string())?;
+        }
+
+        if key == "ui.timestamps.format"
+            && !crate::config::TimestampConfig::format_is_valid(&value.to_display_string())
+        {
+            anyhow::bail!(
+                "'{}' is not a valid strftime format",
+                value.to_display_string()
+            );
+        }
+
+        let old_buffer_size = self.config.ui.buffer_size;
+        let apply = self.config.set_setting(key, value)?;
+
+        let mut follow_up = None;
+        match key {
+            "ui.buffer_size" => self.apply_buffer_size(old_buffer_size),
+            // Leave compact mode; the next update re-enters it with the new settings
+            key if key.starts_with("ui.compact_") && self.compact_active() => {
+                self.set_compact_mode(false)
+            }
+            key if key.starts_with("sound.") => {
+                if let Some(ref mut player) = self.sound_player {
+                    player.set_enabled(self.config.sound.enabled);
+                    player.set_volume(self.config.sound.volume);
+                    player.set_cooldown_ms(self.config.sound.cooldown_ms);
+                    for category in crate::sound::SoundCategory::ALL {
+                        player.set_category_volume(
+                            category,
+                            self.config.sound.category_volume(category),
+                        );
+                    }
+                    player.set_pack(Some(&self.config.sound.pack));
+                }
+            }
+            // Theme switching also refreshes the frontend's theme cache
+            "active_theme"
+                if self.config.active_theme == crate::theme::terminal::TERMINAL_THEME =>
+            {
+                follow_up = Some("action:terminaltheme".to_string())
+            }
+            "active_theme" => {
+                follow_up = Some(format!("action:settheme:{}", self.config.active_theme))
+            }
+            // Re-evaluate right away instead of at the next day/night change
+            key if key.starts_with("theme_schedule.") => self.theme_scheduler.reset(),
+            key if key.starts_with("tts.") => {
+                self.tts_manager.configure(&self.config.tts);
+                self.tts_manager
+                    .set_enabled(self.config.tts.enabled || self.screen_reader_on);
+                self.message_processor.set_tts_config(&self.config.tts);
+            }
+            "screen_reader.enabled" => self.set_screen_reader(self.config.screen_reader.enabled),
+            key if key.starts_with("redirects") => self.compile_redirects(),
+            _ => {}
+        }
+
+        let saved = if key.starts_with(crate::config::settings::SOUNDS_PREFIX) {
+            self.config
+                .save_sound_events(self.config.character.as_deref())
+        } else {
+            self.save_config()
+        };
+        if let Err(e) = saved {
+            tracing::warn!("Failed to save config after changing {}: {}", key, e);
+        }
+        self.needs_render = true;
+        Ok(SettingApplied {
+            restart_required: apply == ApplyMode::Restart,
+            follow_up,
+        })
+    }
+
+    /// Switch themes when the day/night schedule says so
+    ///
+    /// Returns the new theme for the frontend's theme cache. The switch isn't
+    /// saved; the schedule picks the theme again on the next start.
+    pub fn poll_theme_schedule(&mut self) -> Option<(String, crate::theme::AppTheme)> {
+        let theme_id = self
+            .theme_scheduler
+            .poll(&self.config.theme_schedule, std::time::Instant::now())?;
+        if theme_id == self.config.active_theme {
+            return None;
+        }
+        let theme_presets =
+            crate::theme::ThemePresets::all_with_custom(self.config.character.as_deref());
+        if !theme_presets.contains_key(&theme_id) {
+            self.add_system_message(&format!("Theme schedule: unknown theme '{}'", theme_id));
+            return None;
+        }
+
+        tracing::info!("Theme schedule switched to: {}", theme_id);
+        self.config.active_theme = theme_id.clone();
+        self.needs_render = true;
+        Some((theme_id, self.config.get_theme()))
+    }
+
+    /// Resize text windows that were following the old global buffer size
+    fn apply_buffer_size(&mut self, old_size: usize) {
+        let new_size = self.config.ui.buffer_size;
+        for def in self.layout.windows.iter_mut() {
+            if let crate::config::WindowDef::Text { base, data } = def {
+                if data.buffer_size != old_size {
+                    continue; // Per-window override
+                }
+                data.buffer_size = new_size;
+                if let Some(window) = self.ui_state.windows.get_mut(&base.name) {
+                    if let WindowContent::Text(ref mut content) = window.content {
+                        content.set_max_lines(new_size);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Start search mode (Ctrl+F)
+    pub fn start_search_mode(&mut self) {
+        self.ui_state.input_mode = crate::data::ui_state::InputMode::Search;
+        self.ui_state.search_input.clear();
+        self.ui_state.search_cursor = 0;
+        self.ui_state.search_history_index = None;
+        self.needs_render = true;
+    }
+
+    /// Write a window's buffer (from `.dump`) and report where it went
+    ///
+    /// Existing files are only replaced when `force` is set.
+    pub fn dump_window(
+        &mut self,
+        window_name: &str,
+        file: Option<&str>,
+        force: bool,
+        lines: &[String],
+    ) {
+        let result = Config::dumps_dir(self.config.character.as_deref()).and_then(|dir| {
+            let path = dump_path(&dir, window_name, file, chrono::Local::now())?;
+            write_dump(&path, lines, force)?;
+            Ok(path)
+        });
+        match result {
+            Ok(path) => self.add_system_message(&format!(
+                "Dumped {} lines of {} to {}",
+                lines.len(),
+                window_name,
+                path.display()
+            )),
+            Err(e) => {
+                tracing::warn!("Dump of {} failed: {:#}", window_name, e);
+                self.add_system_message(&format!("Dump failed: {:#}", e));
+            }
+        }
+    }
+
+    /// Remember a search pattern in a window's search history
+    /// Re-running an older pattern moves it to the most recent slot
+    pub fn record_search(&mut self, window_name: &str, pattern: &str) {
+        const MAX_SEARCH_HISTORY: usize = 50;
+
+        let history = self
+            .ui_state
+            .search_history
+            .entry(window_name.to_string())
+            .or_default();
+        history.retain(|p| p != pattern);
+        history.push(pattern.to_string());
+        if history.len() > MAX_SEARCH_HISTORY {
+            history.remove(0);
+        }
+        self.ui_state.search_history_index = None;
+    }
+
+    /// Recall an older search for the focused window (Up in Search mode)
+    pub fn search_history_previous(&mut self) {
+        let window_name = self.get_focused_window_name();
+        let Some(history) = self.ui_state.search_history.get(&window_name) else {
+            return;
+        };
+        if history.is_empty() {
+            return;
+        }
+
+        let index = match self.ui_state.search_history_index {
+            Some(i) => i.saturating_sub(1),
+            None => history.len() - 1,
+        };
+        self.ui_state.search_input = history[index].clone();
+        self.ui_state.search_cursor = self.ui_state.search_input.len();
+        self.ui_state.search_history_index = Some(index);
+        self.needs_render = true;
+    }
+
+    /// Step back toward newer searches (Down in Search mode); past the newest clears the input
+    pub fn search_history_next(&mut self) {
+        let window_name = self.get_focused_window_name();
+        let Some(index) = self.ui_state.search_history_index else {
+            return;
+        };
+        let history_len = self
+            .ui_state
+            .search_history
+            .get(&window_name)
+            .map_or(0, |h| h.len());
+
+        if index + 1 < history_len {
+            self.ui_state.search_input =
+                self.ui_state.search_history[&window_name][index + 1].clone();
+            self.ui_state.search_history_index = Some(index + 1);
+        } else {
+            self.ui_state.search_input.clear();
+            self.ui_state.search_history_index = None;
+        }
+        self.ui_state.search_cursor = self.ui_state.search_input.len();
+        self.needs_render = true;
+    }
+
+    /// Window and pattern for `.search`/`.pin` arguments, reporting problems
+    ///
+    /// `<window> <pattern>`, or `[window] @saved` where the window defaults to
+    /// the saved search's window and then the focused one.
+    fn search_target(&mut self, command: &str, args: &[&str]) -> Option<(String, String)> {
+        let (window, pattern) = match args.len() {
+            0 => (None, None),
+            1 => (None, Some(args.join(" "))),
+            _ => (Some(args[0].to_string()), Some(args[1..].join(" "))),
+        };
+        let Some(pattern) = pattern else {
+            self.add_system_message(&format!(
+                "Usage: .{} <window> <pattern>  or  .{} [window] @<saved>",
+                command, command
+            ));
+            return None;
+        };
+        if window.is_none() && !pattern.starts_with('@') {
+            self.add_system_message(&format!("Usage: .{} <window> <pattern>", command));
+            return None;
+        }
+
+        let Some(search) = self.resolve_search_pattern(&pattern) else {
+            self.add_system_message(&format!("No saved search named '{}'", &pattern[1..]));
+            return None;
+        };
+        let window = window
+            .or(search.window)
+            .unwrap_or_else(|| self.get_focused_window_name());
+        if !self.ui_state.windows.contains_key(&window) {
+            self.add_system_message(&format!("Window '{}' not found", window));
+            return None;
+        }
+        Some((window, search.pattern))
+    }
+
+    /// List pinned searches
+    fn list_pinned_searches(&mut self) {
+        let mut pins: Vec<_> = self.ui_state.pinned_searches.iter().collect();
+        pins.sort();
+        let mut lines = vec![format!("=== Pinned Searches ({}) ===", pins.len())];
+        for (window, pattern) in pins {
+            lines.push(format!("  {} - pattern: '{}'", window, pattern));
+        }
+        if lines.len() == 1 {
+            lines.push(
+                "  Nothing pinned - .pin <window> <pattern> or .pin [window] @<saved>".to_string(),
+            );
+        }
+        for line in lines {
+            self.add_system_message(&line);
+        }
+    }
+
+    /// Resolve a `.search` pattern argument: `@name` expands to a saved search
+    fn resolve_search_pattern(&self, pattern: &str) -> Option<crate::config::SavedSearch> {
+        match pattern.strip_prefix('@') {
+            Some(name) => self.config.saved_searches.get(name).cloned(),
+            None => Some(crate::config::SavedSearch {
+                pattern: pattern.to_string(),
+                window: None,
+            }),
+        }
+    }
+
+    /// List built-in menu actions and the user's custom actions
+    fn list_actions(&mut self) {
+        let mut lines = vec![
+            "=== Actions ===".to_string(),
+            format!(
+                "  Built-in: {}",
+                crate::core::actions::BUILTIN_ACTION_NAMES.join(", ")
+            ),
+        ];
+        lines.push("  Keybind actions (e.g. scroll_current_window_up_page) also work".to_string());
+
+        let mut names: Vec<_> = self.config.actions.keys().cloned().collect();
+        names.sort();
+        if names.is_empty() {
+            lines.push(
+                "  No custom actions - define them under [actions] in config.toml".to_string(),
+            );
+        }
+        for name in names {
+            lines.push(format!(
+                "  {} = {}",
+                name,
+                self.config.actions[&name].join(" -> ")
+            ));
+        }
+
+        for line in lines {
+            self.add_system_message(&line);
+        }
+    }
+
+    /// List saved searches
+    fn list_saved_searches(&mut self) {
+        let mut names: Vec<_> = self.config.saved_searches.keys().cloned().collect();
+        names.sort();
 
-            self.layout.theme = Some(self.config.active_theme.clone());
-            if let Err(e) = self
-                .layout
-                .save_auto("default", &base_layout_name, terminal_size)
-            {
-                tracing::warn!("Failed to autosave layout on quit: {}", e);
-            } else {
-                tracing::info!(
-                    "Layout autosaved to default profile (base: {}, terminal: {:?})",
-                    base_layout_name,
-                    terminal_size
-                );
+        let mut lines = vec![format!("=== Saved Searches ({}) ===", names.len())];
+        for name in names {
+            let search = &self.config.saved_searches[&name];
+            let mut info = format!("  @{} - pattern: '{}'", name, search.pattern);
+            if let Some(ref window) = search.window {
+                info.push_str(&format!(" window:{}", window));
             }
+            lines.push(info);
         }
 
-        self.running = false;
-    }
-
-    /// Save configuration to disk
-    pub fn save_config(&mut self) -> Result<()> {
-        self.config.save(self.config.character.as_deref())
+        for line in lines {
+            self.add_system_message(&line);
+        }
     }
 
-    /// Start search mode (Ctrl+F)
-    pub fn start_search_mode(&mut self) {
-        self.ui_state.input_mode = crate::data::ui_state::InputMode::Search;
-        self.ui_state.search_input.clear();
-        self.ui_state.search_cursor = 0;
-        self.needs_render = true;
+    /// Persist saved searches, reporting failures to the user
+    fn persist_saved_searches(&mut self) {
+        if let Err(e) = self
+            .config
+            .save_saved_searches(self.config.character.as_deref())
+        {
+            tracing::error!("Failed to save searches: {}", e);
+            self.add_system_message(&format!("Warning: Failed to save searches: {}", e));
+        }
     }
 
     /// Get the focused window name (or "main" as default)
@@ -3802,40 +7036,577 @@ impl AppCore {
             .unwrap_or_else(|| name.to_string())
     }
 
-    /// Check if text matches any highlight patterns with sounds and play them
-    pub fn check_sound_triggers(&self, text: &str) {
-        if let Some(ref sound_player) = self.sound_player {
-            for (_name, pattern) in &self.config.highlights {
-                // Skip if no sound configured for this pattern
-                if pattern.sound.is_none() {
-                    continue;
+    /// Play the sounds.toml sound (and bell/flash) for a game event, if one is configured
+    pub fn play_game_event(&mut self, event: crate::core::sound_events::GameEvent) {
+        self.play_game_event_in(event, event.window());
+    }
+
+    /// `play_game_event` for an event raised in `window` (a window name or stream id)
+    fn play_game_event_in(&mut self, event: crate::core::sound_events::GameEvent, window: &str) {
+        let Some(sound) = self.config.sound_events.get(event.name()) else {
+            return;
+        };
+        if !sound.enabled {
+            return;
+        }
+        let (bell, flash) = (sound.bell, sound.flash);
+        if !sound.file.is_empty() && !self.sounds_silenced() {
+            let category = sound
+                .category
+                .as_deref()
+                .and_then(crate::sound::SoundCategory::from_name)
+                .unwrap_or_else(|| event.default_category());
+            if let Some(ref sound_player) = self.sound_player {
+                if let Err(e) =
+                    sound_player.play_from_sounds_dir(&sound.file, sound.volume, category)
+                {
+                    tracing::warn!(
+                        "Failed to play sound '{}' for {}: {}",
+                        sound.file,
+                        event.name(),
+                        e
+                    );
                 }
+            }
+        }
+        let flash: &[String] = if flash { &[window.to_string()] } else { &[] };
+        self.raise_alert(bell, flash);
+    }
 
-                let matches = if pattern.fast_parse {
-                    // Fast parse: check if any of the pipe-separated patterns are in the text
-                    pattern.pattern.split('|').any(|p| text.contains(p.trim()))
-                } else {
-                    // Regex parse
-                    if let Ok(regex) = regex::Regex::new(&pattern.pattern) {
-                        regex.is_match(text)
+    /// Ring the terminal bell (unless muted) and flash windows for a highlight or event
+    ///
+    /// `flash` holds window names or stream ids; a stream flashes the windows showing it.
+    fn raise_alert(&mut self, bell: bool, flash: &[String]) {
+        if bell && !self.sounds_silenced() {
+            self.ui_state.bell_pending = true;
+            self.needs_render = true;
+        }
+        let now = std::time::Instant::now();
+        for target in flash {
+            let windows = if self.ui_state.windows.contains_key(target) {
+                vec![target.clone()]
+            } else {
+                self.message_processor.stream_windows(target)
+            };
+            for window in windows {
+                self.ui_state.flash_window(&window, now);
+                self.needs_render = true;
+            }
+        }
+    }
+
+    /// AFK, or no keyboard or mouse input for `push.afk_minutes`
+    fn is_away(&self) -> bool {
+        self.afk.is_away()
+            || self.afk.idle(std::time::Instant::now())
+                >= std::time::Duration::from_secs(self.config.push.afk_minutes * 60)
+    }
+
+    /// Keyboard, mouse or paste input: ends an absence with a summary of what was missed
+    pub fn record_user_input(&mut self) {
+        let Some(back) = self.afk.record_input(std::time::Instant::now()) else {
+            return;
+        };
+        let away = crate::core::afk::format_away(back.away);
+        match back.missed_total {
+            0 => self.add_system_message(&format!("Back after {}", away)),
+            1 => self.add_system_message(&format!("Back after {}; 1 line while away", away)),
+            n => self.add_system_message(&format!("Back after {}; {} lines while away", away, n)),
+        }
+        // Without an afk window the missed lines are replayed here
+        if !back.missed.is_empty() && self.message_processor.stream_windows("afk").is_empty() {
+            for line in &back.missed {
+                self.add_colored_system_message(line, "#dda0dd");
+            }
+            if back.missed_total > back.missed.len() {
+                self.add_colored_system_message(
+                    &format!("... and {} more", back.missed_total - back.missed.len()),
+                    "#dda0dd",
+                );
+            }
+        }
+    }
+
+    /// Mark the session AFK once `afk.minutes` pass without input
+    pub fn poll_afk(&mut self) {
+        let after = match self.config.afk.minutes {
+            0 => None,
+            minutes => Some(std::time::Duration::from_secs(minutes * 60)),
+        };
+        if self.afk.poll(std::time::Instant::now(), after) {
+            self.add_system_message("You are now AFK");
+        }
+    }
+
+    /// Whisper auto-replies queued since the last call
+    pub fn take_afk_replies(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.afk_replies)
+    }
+
+    /// Log a line that arrived while AFK and queue the auto-reply to a whisper
+    fn record_afk_line(&mut self, stream: &str, text: &str, whisper: bool) {
+        if !whisper && !self.config.afk.log_streams.iter().any(|s| s == stream) {
+            return;
+        }
+        let line = format!("[{}] {}", chrono::Local::now().format("%H:%M"), text);
+        for window in self.message_processor.stream_windows("afk") {
+            if let Some(state) = self.ui_state.get_window_mut(&window) {
+                if let crate::data::WindowContent::Text(ref mut content) = state.content {
+                    content.add_line(crate::data::StyledLine {
+                        segments: vec![crate::data::TextSegment {
+                            text: line.clone(),
+                            fg: None,
+                            bg: None,
+                            bold: false,
+                            span_type: crate::data::SpanType::Normal,
+                            link_data: None,
+                        }],
+                    });
+                    self.needs_render = true;
+                }
+            }
+        }
+        self.afk.record_missed(line);
+
+        let reply = self.config.afk.auto_reply.trim();
+        if !whisper || reply.is_empty() {
+            return;
+        }
+        if let Some(name) = crate::core::afk::whisperer(text) {
+            if self.afk.take_reply(name) {
+                self.afk_replies.push(format!("whisper {} {}", name, reply));
+            }
+        }
+    }
+
+    /// Send a push notification unless do-not-disturb is on
+    pub fn push_event(&mut self, event: crate::push::PushEvent, message: &str) {
+        if !self.do_not_disturb_active() {
+            self.push.notify(event, message);
+        }
+    }
+
+    /// Connected to the game; `[on_connect]` runs once login completes
+    pub fn notify_connected(&mut self) {
+        self.on_connect_pending = true;
+    }
+
+    /// Send the `[on_connect]` commands, `delay_ms` apart
+    fn run_on_connect(&mut self) {
+        let commands: Vec<String> = self
+            .config
+            .on_connect
+            .commands
+            .iter()
+            .map(|c| c.trim().to_string())
+            .filter(|c| !c.is_empty())
+            .collect();
+        if commands.is_empty() {
+            return;
+        }
+        if self.active_macro.is_some() {
+            tracing::debug!("Login commands replace the running macro");
+        }
+        tracing::info!("Sending {} login command(s)", commands.len());
+        let delay = std::time::Duration::from_millis(self.config.on_connect.delay_ms);
+        self.active_macro = Some(crate::core::macros::MacroRun::from_lines(&commands, delay));
+    }
+
+    /// The game connection dropped without QUIT
+    pub fn notify_disconnected(&mut self) {
+        self.on_connect_pending = false;
+        if !std::mem::take(&mut self.quitting_game) {
+            self.push_event(
+                crate::push::PushEvent::Disconnect,
+                "Lost the connection to the game.",
+            );
+        }
+    }
+
+    /// Is do-not-disturb still running?
+    pub fn do_not_disturb_active(&self) -> bool {
+        self.do_not_disturb_until
+            .is_some_and(|until| until > std::time::Instant::now())
+    }
+
+    /// Muted or in do-not-disturb: no sound effects at all
+    pub fn sounds_silenced(&self) -> bool {
+        self.sound_muted || self.do_not_disturb_active()
+    }
+
+    /// Master mute toggle (`.mute`, toggle_sound)
+    pub fn toggle_sound(&mut self) {
+        self.sound_muted = !self.sound_muted;
+        if self.sound_muted {
+            self.add_system_message("Sound muted");
+        } else if self.do_not_disturb_active() {
+            self.add_system_message("Sound unmuted (do not disturb is still on)");
+        } else {
+            self.add_system_message("Sound unmuted");
+        }
+    }
+
+    /// Start do-not-disturb for `minutes`, or end it (None)
+    pub fn set_do_not_disturb(&mut self, minutes: Option<u64>) {
+        match minutes {
+            Some(minutes) => {
+                self.do_not_disturb_until =
+                    Some(std::time::Instant::now() + std::time::Duration::from_secs(minutes * 60));
+                self.add_system_message(&format!(
+                    "Do not disturb for {} minute(s): sounds are off until then (.dnd off ends it)",
+                    minutes
+                ));
+            }
+            None => {
+                self.do_not_disturb_until = None;
+                self.add_system_message("Do not disturb off");
+            }
+        }
+    }
+
+    /// Announce the end of a timed do-not-disturb
+    pub fn poll_do_not_disturb(&mut self) {
+        if self.do_not_disturb_until.is_some() && !self.do_not_disturb_active() {
+            self.do_not_disturb_until = None;
+            self.add_system_message("Do not disturb ended");
+        }
+    }
+
+    /// Fire roundtime-end and spell-expiring sounds whose time has come
+    pub fn poll_sound_events(&mut self) {
+        use crate::core::sound_events::GameEvent;
+
+        let wanted = |event: GameEvent| self.config.sound_events.contains_key(event.name());
+        if !wanted(GameEvent::RoundtimeEnd) && !wanted(GameEvent::SpellExpiring) {
+            return;
+        }
+
+        let warn_seconds = self
+            .config
+            .sound_events
+            .get(GameEvent::SpellExpiring.name())
+            .map(|s| s.seconds)
+            .unwrap_or(0);
+        let spells = match self
+            .ui_state
+            .get_window("active_spells")
+            .map(|w| &w.content)
+        {
+            Some(crate::data::WindowContent::ActiveEffects(content)) => content.effects.as_slice(),
+            _ => &[],
+        };
+        let now = chrono::Utc::now().timestamp();
+        let events =
+            self.sound_event_tracker
+                .poll(now, self.game_state.roundtime_end, spells, warn_seconds);
+        for event in events {
+            self.play_game_event(event);
+        }
+    }
+
+    /// Compile `[[redirects]]` for the message processor, reporting rules
+    /// that were skipped
+    fn compile_redirects(&mut self) {
+        let skipped = self
+            .message_processor
+            .set_redirects(&self.config.redirects, self.config.character.as_deref());
+        for reason in skipped {
+            self.add_system_message(&format!("Redirect skipped: {}", reason));
+        }
+    }
+
+    /// List sounds.toml entries (`.sounds`)
+    fn list_sound_events(&mut self) {
+        use crate::core::sound_events::GameEvent;
+
+        self.add_system_message("=== Event Sounds (sounds.toml) ===");
+        for event in GameEvent::ALL {
+            let line = match self.config.sound_events.get(event.name()) {
+                Some(sound)
+                    if sound.enabled && (!sound.file.is_empty() || sound.bell || sound.flash) =>
+                {
+                    let volume = sound
+                        .volume
+                        .map(|v| format!(" at {:.0}%", v * 100.0))
+                        .unwrap_or_default();
+                    let file = if sound.file.is_empty() {
+                        "(no sound)"
                     } else {
-                        false
-                    }
-                };
+                        sound.file.as_str()
+                    };
+                    let bell = if sound.bell { " + bell" } else { "" };
+                    let flash = if sound.flash {
+                        format!(" + flash {}", event.window())
+                    } else {
+                        String::new()
+                    };
+                    format!("  {}: {}{}{}{}", event.name(), file, volume, bell, flash)
+                }
+                Some(_) => format!("  {}: (off)", event.name()),
+                None => format!("  {}: -", event.name()),
+            };
+            self.add_system_message(&line);
+        }
+        if self.sound_player.is_none() {
+            self.add_system_message("Sound output is unavailable (disabled or no audio device)");
+        }
+    }
 
-                if matches {
-                    if let Some(ref sound_file) = pattern.sound {
-                        // Play the sound
-                        if let Err(e) =
-                            sound_player.play_from_sounds_dir(sound_file, pattern.sound_volume)
-                        {
-                            tracing::warn!("Failed to play sound '{}': {}", sound_file, e);
-                        }
-                    }
+    /// `.sounds pack`: list packs, or switch to one (saved to config)
+    fn set_sound_pack(&mut self, name: Option<&str>) {
+        let packs = match crate::sound::list_sound_packs() {
+            Ok(packs) => packs,
+            Err(e) => {
+                self.add_system_message(&format!("Failed to list sound packs: {}", e));
+                return;
+            }
+        };
+
+        let Some(name) = name else {
+            let active = if self.config.sound.pack.is_empty() {
+                "none"
+            } else {
+                self.config.sound.pack.as_str()
+            };
+            self.add_system_message(&format!("Sound pack: {}", active));
+            if packs.is_empty() {
+                self.add_system_message(
+                    "No packs installed (add folders under ~/.two-face/sounds/)",
+                );
+            } else {
+                self.add_system_message(&format!("Installed: {}", packs.join(", ")));
+            }
+            return;
+        };
+
+        let pack = if name.eq_ignore_ascii_case("none") {
+            ""
+        } else {
+            name
+        };
+        if !pack.is_empty() && !packs.iter().any(|p| p == pack) {
+            self.add_system_message(&format!(
+                "No sound pack '{}' in ~/.two-face/sounds/ (.sounds pack lists them)",
+                pack
+            ));
+            return;
+        }
+
+        self.config.sound.pack = pack.to_string();
+        if let Some(ref mut player) = self.sound_player {
+            player.set_pack(Some(pack));
+        }
+        if let Err(e) = self.save_config() {
+            tracing::warn!("Failed to save sound pack setting: {}", e);
+        }
+        if pack.is_empty() {
+            self.add_system_message("Sound pack off: using ~/.two-face/sounds/");
+        } else {
+            self.add_system_message(&format!(
+                "Sound pack '{}' active (missing sounds fall back to ~/.two-face/sounds/)",
+                pack
+            ));
+        }
+    }
+
+    /// Play the sounds (and bells/flashes) of highlights a server line matched
+    /// (see `parse_worker::sound_hits`)
+    pub fn play_highlight_sounds(&mut self, hits: &[String]) {
+        if hits.is_empty() {
+            return;
+        }
+        let silenced = self.sounds_silenced();
+        let now = std::time::Instant::now();
+        let clock = chrono::Local::now().time();
+        let mut alerts = Vec::new();
+        for name in hits {
+            // The highlight may have been removed since the line was matched
+            let Some(pattern) = self.config.highlights.get(name) else {
+                continue;
+            };
+            // Muted: only a flash is left to show
+            if silenced && !pattern.flash {
+                continue;
+            }
+            if !self
+                .trigger_gate
+                .allow(name, pattern, self.terminal_focused, now, clock)
+            {
+                continue;
+            }
+            if let (Some(sound_file), Some(sound_player), false) =
+                (&pattern.sound, &self.sound_player, silenced)
+            {
+                let category = pattern
+                    .sound_category
+                    .as_deref()
+                    .and_then(crate::sound::SoundCategory::from_name)
+                    .unwrap_or(crate::sound::SoundCategory::Alerts);
+                // Play the sound
+                if let Err(e) =
+                    sound_player.play_from_sounds_dir(sound_file, pattern.sound_volume, category)
+                {
+                    tracing::warn!("Failed to play sound '{}': {}", sound_file, e);
                 }
             }
+            if pattern.bell || pattern.flash {
+                let flash = match (pattern.flash, pattern.windows.is_empty()) {
+                    (false, _) => Vec::new(),
+                    (true, true) => vec!["main".to_string()],
+                    (true, false) => pattern.windows.clone(),
+                };
+                alerts.push((pattern.bell, flash));
+            }
+        }
+        for (bell, flash) in alerts {
+            self.raise_alert(bell, &flash);
+        }
+    }
+}
+
+/// Where `.dump` writes: `file` under `dir` (unless absolute, ".txt" added
+/// when there's no extension), or `<window>-<timestamp>.txt`
+/// The echo of a sent command: the prompt (per-character colors, as
+/// prompts render), `ui.command_echo_prefix`, then the command. None when
+/// `ui.command_echo` leaves this command out.
+fn command_echo(
+    command: &str,
+    scripted: bool,
+    prompt: &str,
+    colors: &crate::config::ColorConfig,
+    ui: &crate::config::UiConfig,
+) -> Option<Vec<crate::data::TextSegment>> {
+    use crate::data::{SpanType, TextSegment};
+
+    let echoed = match ui.command_echo.as_str() {
+        "none" => false,
+        "typed" => !scripted,
+        _ => true,
+    };
+    if command.is_empty() || !echoed {
+        return None;
+    }
+    let segment = |text: String, color: String| TextSegment {
+        text,
+        fg: Some(color),
+        bg: None,
+        bold: false,
+        span_type: SpanType::Normal,
+        link_data: None,
+    };
+
+    let mut segments = Vec::new();
+    if ui.command_echo_prompt {
+        for ch in prompt.chars() {
+            let char_str = ch.to_string();
+            let color = colors
+                .prompt_colors
+                .iter()
+                .find(|pc| pc.character == char_str)
+                // Prefer fg, fallback to color (legacy)
+                .and_then(|pc| pc.fg.as_ref().or(pc.color.as_ref()).cloned())
+                .unwrap_or_else(|| "#808080".to_string()); // Default dark gray
+            segments.push(segment(char_str, color));
+        }
+    }
+    let color = if scripted {
+        colors.ui.scripted_echo_color.clone()
+    } else {
+        colors.ui.command_echo_color.clone()
+    };
+    if !ui.command_echo_prefix.is_empty() {
+        segments.push(segment(ui.command_echo_prefix.clone(), color.clone()));
+    }
+    segments.push(segment(command.to_string(), color));
+    Some(segments)
+}
+
+/// Where `.dump` writes: the given file inside the dumps directory, or a
+/// timestamped name. Absolute paths and `..` are refused so a dump can't
+/// land outside that directory.
+fn dump_path(
+    dir: &std::path::Path,
+    window_name: &str,
+    file: Option<&str>,
+    now: chrono::DateTime<chrono::Local>,
+) -> Result<std::path::PathBuf> {
+    use std::path::{Component, Path};
+
+    match file.map(str::trim).filter(|f| !f.is_empty()) {
+        Some(file) => {
+            let relative = Path::new(file);
+            if !relative
+                .components()
+                .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+            {
+                anyhow::bail!("'{}' must be a file name inside {}", file, dir.display());
+            }
+            let mut path = dir.join(relative);
+            if path.extension().is_none() {
+                path.set_extension("txt");
+            }
+            Ok(path)
         }
+        None => Ok(dir.join(format!(
+            "{}-{}.txt",
+            window_name,
+            now.format("%Y%m%d-%H%M%S")
+        ))),
+    }
+}
+
+/// Write dumped lines to `path`, refusing to replace an existing file unless forced
+fn write_dump(path: &std::path::Path, lines: &[String], force: bool) -> Result<()> {
+    if path.exists() && !force {
+        anyhow::bail!(
+            "{} already exists (add --force to overwrite)",
+            path.display()
+        );
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
     }
+    let mut contents = lines.join("\n");
+    contents.push('\n');
+    std::fs::write(path, contents).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Injury detail lines as menu rows; they're informational, so Enter just closes the popup
+///
+/// Disabled rows aren't drawn, so these stay enabled with no command.
+fn injury_menu_items(lines: Vec<String>) -> Vec<crate::data::ui_state::PopupMenuItem> {
+    lines
+        .into_iter()
+        .map(|text| crate::data::ui_state::PopupMenuItem {
+            text,
+            command: String::new(),
+            disabled: false,
+        })
+        .collect()
+}
+
+/// The multi-line paste dialog: the question in the title, one enabled row per choice
+fn paste_menu(lines: &[String]) -> crate::data::ui_state::PopupMenu {
+    use crate::data::ui_state::{PopupMenu, PopupMenuItem};
+
+    let item = |text: String, command: &str| PopupMenuItem {
+        text,
+        command: command.to_string(),
+        disabled: false,
+    };
+    let title = format!(
+        "Send {} pasted lines? ({})",
+        lines.len(),
+        crate::core::paste::preview(&lines[0], 30)
+    );
+    let items = vec![
+        item("Edit in the composer".to_string(), "action:pasteedit"),
+        item("Send the first line only".to_string(), "action:pastefirst"),
+        item(format!("Send all {} lines", lines.len()), "action:pasteall"),
+        item("Cancel".to_string(), ""),
+    ];
+    PopupMenu::new(items, (40, 12)).with_title(title)
 }
 
 #[cfg(test)]
@@ -3866,6 +7637,9 @@ mod tests {
             min_cols: None,
             max_cols: None,
             visible: true,
+            priority: None,
+            width: None,
+            height: None,
         }
     }
 
@@ -3961,6 +7735,11 @@ mod tests {
             data: crate::config::TextWidgetData {
                 streams: vec!["main".to_string()],
                 buffer_size: 1000,
+                include: Vec::new(),
+                exclude: Vec::new(),
+                timestamps: None,
+                collapse_repeats: None,
+                gap_separator_minutes: None,
             },
         };
         let spacer1 = WindowDef::Spacer {
@@ -4049,4 +7828,131 @@ mod tests {
         let name = AppCore::generate_spacer_name(&layout);
         assert_eq!(name, "spacer_100");
     }
+
+    #[test]
+    fn test_command_echo() {
+        let mut colors = crate::config::ColorConfig::default();
+        colors.ui.command_echo_color = "#ffffff".to_string();
+        colors.ui.scripted_echo_color = "#87ceeb".to_string();
+        let mut ui = crate::config::Config::default().ui;
+        let texts = |segments: Option<Vec<crate::data::TextSegment>>| {
+            segments.map(|s| {
+                s.iter()
+                    .map(|s| (s.text.clone(), s.fg.clone().unwrap()))
+                    .collect::<Vec<_>>()
+            })
+        };
+
+        let typed = texts(command_echo("look", false, ">", &colors, &ui)).unwrap();
+        assert_eq!(typed.len(), 2);
+        assert_eq!(typed[1], ("look".to_string(), "#ffffff".to_string()));
+        let scripted = texts(command_echo("look", true, ">", &colors, &ui)).unwrap();
+        assert_eq!(scripted[1].1, "#87ceeb");
+        assert!(command_echo("", false, ">", &colors, &ui).is_none());
+
+        ui.command_echo_prompt = false;
+        ui.command_echo_prefix = "> ".to_string();
+        assert_eq!(
+            texts(command_echo("look", false, "H>", &colors, &ui)),
+            Some(vec![
+                ("> ".to_string(), "#ffffff".to_string()),
+                ("look".to_string(), "#ffffff".to_string())
+            ])
+        );
+
+        ui.command_echo = "typed".to_string();
+        assert!(command_echo("look", true, ">", &colors, &ui).is_none());
+        assert!(command_echo("look", false, ">", &colors, &ui).is_some());
+        ui.command_echo = "none".to_string();
+        assert!(command_echo("look", false, ">", &colors, &ui).is_none());
+    }
+
+    #[test]
+    fn test_dump_path() {
+        use chrono::TimeZone;
+        let dir = std::path::Path::new("/home/ann/.two-face/Ann/dumps");
+        let now = chrono::Local
+            .with_ymd_and_hms(2024, 3, 9, 14, 5, 7)
+            .unwrap();
+        let path = |file| dump_path(dir, "main", file, now).ok();
+        assert_eq!(path(None), Some(dir.join("main-20240309-140507.txt")));
+        assert_eq!(path(Some(" ")), Some(dir.join("main-20240309-140507.txt")));
+        assert_eq!(path(Some("hunt")), Some(dir.join("hunt.txt")));
+        assert_eq!(path(Some("hunt.log")), Some(dir.join("hunt.log")));
+        assert_eq!(path(Some("week1/hunt")), Some(dir.join("week1/hunt.txt")));
+    }
+
+    #[test]
+    fn test_dump_path_stays_in_dumps_dir() {
+        use chrono::TimeZone;
+        let dir = std::path::Path::new("/home/ann/.two-face/Ann/dumps");
+        let now = chrono::Local
+            .with_ymd_and_hms(2024, 3, 9, 14, 5, 7)
+            .unwrap();
+        assert!(dump_path(dir, "main", Some("/tmp/hunt.log"), now).is_err());
+        assert!(dump_path(dir, "main", Some("../config.toml"), now).is_err());
+        assert!(dump_path(dir, "main", Some("logs/../../hunt"), now).is_err());
+    }
+
+    #[test]
+    fn test_dump_refuses_to_overwrite_without_force() {
+        let dir = std::env::temp_dir().join(format!(
+            "two-face-dump-overwrite-test-{}",
+            std::process::id()
+        ));
+        let path = dir.join("hunt.txt");
+
+        write_dump(&path, &["You see a troll.".to_string()], false).unwrap();
+        assert!(write_dump(&path, &["overwritten".to_string()], false).is_err());
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "You see a troll.\n"
+        );
+
+        write_dump(&path, &["overwritten".to_string()], true).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "overwritten\n");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_paste_menu_rows_run_their_own_choice() {
+        let lines = vec!["look".to_string(), "inv".to_string(), "exp".to_string()];
+        let mut menu = paste_menu(&lines);
+        assert_eq!(menu.title.as_deref(), Some("Send 3 pasted lines? (look)"));
+
+        // Every row is drawn, so the highlighted row is the one Enter runs
+        let rows: Vec<&str> = menu
+            .items
+            .iter()
+            .filter(|item| !item.disabled)
+            .map(|item| item.text.as_str())
+            .collect();
+        assert_eq!(rows.len(), menu.items.len());
+        assert_eq!(menu.selected, 0);
+
+        let expected = [
+            ("Edit in the composer", "action:pasteedit"),
+            ("Send the first line only", "action:pastefirst"),
+            ("Send all 3 lines", "action:pasteall"),
+            ("Cancel", ""),
+        ];
+        for (row, (text, command)) in expected.iter().enumerate() {
+            menu.selected = row;
+            assert_eq!(rows[row], *text);
+            assert_eq!(menu.selected_item().unwrap().command, *command);
+        }
+    }
+
+    #[test]
+    fn test_injury_menu_shows_detail_rows() {
+        let lines = crate::core::injuries::details("rightLeg", 2, true, &HashMap::new());
+        let items = injury_menu_items(lines.clone());
+        let shown: Vec<String> = items
+            .iter()
+            .filter(|item| !item.disabled)
+            .map(|item| item.text.clone())
+            .collect();
+        assert_eq!(shown, lines);
+        assert!(items.iter().all(|item| item.command.is_empty()));
+    }
 }
@@ -0,0 +1,197 @@
+//! Compact rendering profile for narrow terminals (phone SSH sessions)
+//!
+//! Below `ui.compact_width` columns (or after `.compact on`) every border
+//! collapses to a single line or disappears, titles shrink to their first
+//! word, and windows ranked below `ui.compact_min_priority` are hidden. The
+//! border and title each window had are remembered, so leaving compact mode
+//! and `.savelayout` both see the layout as it was written.
+
+use crate::config::{Layout, WindowDef};
+use crate::data::WindowPosition;
+use std::collections::HashMap;
+
+/// Longest title kept in compact mode
+const MAX_TITLE_CHARS: usize = 10;
+
+/// Rows above and below the command input that still count as tapping it
+const INPUT_HIT_MARGIN: u16 = 1;
+
+/// The parts of a window compact mode rewrites
+#[derive(Debug, Clone, PartialEq)]
+pub struct WindowChrome {
+    show_border: bool,
+    border_style: String,
+    title: Option<String>,
+}
+
+/// Rank used when a window doesn't set `priority` (higher survives longer)
+pub fn default_priority(widget_type: &str) -> u8 {
+    match widget_type {
+        "command_input" => 100,
+        "vitals" | "progress" | "countdown" => 70,
+        "room" => 60,
+        "text" | "tabbedtext" | "targets" | "container" => 50,
+        "hand" | "compass" | "active_effects" => 40,
+        "spacer" => 0,
+        _ => 30,
+    }
+}
+
+/// A window's compact ranking: its own `priority`, else the widget type's
+pub fn priority(def: &WindowDef) -> u8 {
+    def.base().priority.unwrap_or_else(|| {
+        if def.name() == "main" {
+            100
+        } else {
+            default_priority(def.widget_type())
+        }
+    })
+}
+
+/// First word of a title, capped at a few characters ("Room Description" -> "Room")
+pub fn shorten_title(title: &str) -> String {
+    let word = title.split_whitespace().next().unwrap_or("");
+    if word.chars().count() <= MAX_TITLE_CHARS {
+        word.to_string()
+    } else {
+        let mut short: String = word.chars().take(MAX_TITLE_CHARS - 1).collect();
+        short.push('…');
+        short
+    }
+}
+
+/// Does a click at (x, y) land on the command input or just next to it?
+pub fn hits_input(input: &WindowPosition, x: u16, y: u16) -> bool {
+    x >= input.x
+        && x < input.x + input.width
+        && y + INPUT_HIT_MARGIN >= input.y
+        && y < input.y + input.height + INPUT_HIT_MARGIN
+}
+
+/// Rewrite borders and titles for compact mode, returning the originals
+/// (`borders` is "single" or "none"; screen reader mode keeps full titles)
+pub fn apply(
+    layout: &mut Layout,
+    borders: &str,
+    shorten_titles: bool,
+) -> HashMap<String, WindowChrome> {
+    let mut originals = HashMap::new();
+    for def in &mut layout.windows {
+        let base = def.base_mut();
+        originals.insert(
+            base.name.clone(),
+            WindowChrome {
+                show_border: base.show_border,
+                border_style: base.border_style.clone(),
+                title: base.title.clone(),
+            },
+        );
+
+        if borders == "none" {
+            base.show_border = false;
+        } else if base.show_border {
+            base.border_style = "single".to_string();
+        }
+        if let Some(title) = base.title.as_ref().filter(|_| shorten_titles) {
+            base.title = Some(shorten_title(title));
+        }
+    }
+    originals
+}
+
+/// Put back the borders and titles `apply` replaced
+pub fn restore(layout: &mut Layout, originals: &HashMap<String, WindowChrome>) {
+    for def in &mut layout.windows {
+        let base = def.base_mut();
+        if let Some(chrome) = originals.get(&base.name) {
+            base.show_border = chrome.show_border;
+            base.border_style = chrome.border_style.clone();
+            base.title = chrome.title.clone();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout() -> Layout {
+        toml::from_str(
+            r#"
+            [[windows]]
+            widget_type = "text"
+            name = "main"
+            title = "Story"
+            border_style = "rounded"
+
+            [[windows]]
+            widget_type = "room"
+            name = "room"
+            title = "Room Description"
+
+            [[windows]]
+            widget_type = "compass"
+            name = "compass"
+
+            [[windows]]
+            widget_type = "indicator"
+            name = "stunned"
+            priority = 80
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_window_priorities() {
+        let ranks: Vec<u8> = layout().windows.iter().map(priority).collect();
+        assert_eq!(ranks, vec![100, 60, 40, 80]);
+    }
+
+    #[test]
+    fn test_shorten_title() {
+        assert_eq!(shorten_title("Room Description"), "Room");
+        assert_eq!(shorten_title("Conversations"), "Conversat…");
+        assert_eq!(shorten_title(""), "");
+    }
+
+    #[test]
+    fn test_apply_and_restore_compact_profile() {
+        let original = layout();
+        let mut layout = original.clone();
+        let originals = apply(&mut layout, "single", true);
+        assert_eq!(layout.windows[0].base().border_style, "single");
+        assert_eq!(layout.windows[1].base().title.as_deref(), Some("Room"));
+        assert_eq!(layout.windows[2].base().title, None);
+
+        restore(&mut layout, &originals);
+        for (restored, before) in layout.windows.iter().zip(&original.windows) {
+            assert_eq!(restored.base(), before.base());
+        }
+    }
+
+    #[test]
+    fn test_borderless_compact_keeps_titles() {
+        let mut borderless = layout();
+        apply(&mut borderless, "none", false);
+        assert!(borderless.windows.iter().all(|w| !w.base().show_border));
+        assert_eq!(
+            borderless.windows[1].base().title.as_deref(),
+            Some("Room Description")
+        );
+    }
+
+    #[test]
+    fn test_hits_input() {
+        let input = WindowPosition {
+            x: 0,
+            y: 20,
+            width: 80,
+            height: 3,
+        };
+        assert!(hits_input(&input, 5, 19));
+        assert!(hits_input(&input, 79, 23));
+        assert!(!hits_input(&input, 5, 18));
+        assert!(!hits_input(&input, 80, 21));
+    }
+}
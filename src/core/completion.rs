@@ -0,0 +1,81 @@
+//! Fuzzy matching for Tab completion in the command input.
+//!
+//! A candidate matches when it contains every typed character in order, so
+//! `.cwin` finds `.createwindow` and `brd` finds `broadsword`. Prefix matches
+//! come first, then matches inside the word, then scattered letters (closest
+//! together first), so plain prefix typing still completes the way it did.
+
+/// How well `query` matches `candidate`, lower is better (None = no match)
+pub fn fuzzy_rank(query: &str, candidate: &str) -> Option<(u8, usize)> {
+    let query = query.to_lowercase();
+    let candidate = candidate.to_lowercase();
+    if candidate.starts_with(&query) {
+        return Some((0, 0));
+    }
+    if let Some(pos) = candidate.find(&query) {
+        return Some((1, pos));
+    }
+
+    // Every query character in order; count the characters skipped to find them
+    let mut skipped = 0;
+    let mut chars = candidate.chars();
+    for wanted in query.chars() {
+        loop {
+            match chars.next() {
+                Some(c) if c == wanted => break,
+                Some(_) => skipped += 1,
+                None => return None,
+            }
+        }
+    }
+    Some((2, skipped))
+}
+
+/// Candidates matching `query`, best first, without duplicates
+pub fn fuzzy_matches(query: &str, candidates: &[String]) -> Vec<String> {
+    let mut ranked: Vec<((u8, usize), &String)> = candidates
+        .iter()
+        .filter_map(|candidate| Some((fuzzy_rank(query, candidate)?, candidate)))
+        .collect();
+    ranked.sort();
+    ranked.dedup_by(|a, b| a.1 == b.1);
+    ranked
+        .into_iter()
+        .map(|(_, candidate)| candidate.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_rank() {
+        assert_eq!(fuzzy_rank("bro", "broadsword"), Some((0, 0)));
+        assert_eq!(fuzzy_rank("SWORD", "broadsword"), Some((1, 5)));
+        assert_eq!(fuzzy_rank("brd", "broadsword"), Some((2, 2)));
+        assert_eq!(fuzzy_rank("drb", "broadsword"), None);
+    }
+
+    #[test]
+    fn test_fuzzy_matches_dedupe_and_rank() {
+        let candidates: Vec<String> = [
+            ".windows",
+            ".createwindow",
+            ".editwindow",
+            ".cw",
+            ".windows",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+        assert_eq!(
+            fuzzy_matches(".cw", &candidates),
+            vec![".cw", ".createwindow"]
+        );
+        assert_eq!(
+            fuzzy_matches("win", &candidates),
+            vec![".windows", ".editwindow", ".createwindow"]
+        );
+    }
+}
@@ -0,0 +1,180 @@
+//! Creature danger database (`creatures.toml`)
+//!
+//! A small table of creatures bundled with the client - level, a danger
+//! rating, combat maneuvers and special attacks - keyed by name. Entries in
+//! `~/.two-face/creatures.toml` are added on top, replacing bundled ones with
+//! the same name. Creature names from bold (monster) text are looked up here
+//! so dangerous arrivals can be announced, spoken and colored in targets.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// How worried to be about a creature
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Danger {
+    #[default]
+    Low,
+    Moderate,
+    High,
+    Deadly,
+}
+
+impl Danger {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Low => "low",
+            Self::Moderate => "moderate",
+            Self::High => "high",
+            Self::Deadly => "deadly",
+        }
+    }
+}
+
+/// One creature's entry
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CreatureInfo {
+    #[serde(default)]
+    pub level: u32,
+    #[serde(default)]
+    pub danger: Danger,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub maneuvers: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub special: Vec<String>,
+}
+
+impl CreatureInfo {
+    /// "troll king (level 35, high) - maneuvers: ...; special: ..."
+    pub fn describe(&self, name: &str) -> String {
+        let mut text = format!("{} (level {}, {})", name, self.level, self.danger.name());
+        let mut notes = Vec::new();
+        if !self.maneuvers.is_empty() {
+            notes.push(format!("maneuvers: {}", self.maneuvers.join(", ")));
+        }
+        if !self.special.is_empty() {
+            notes.push(format!("special: {}", self.special.join(", ")));
+        }
+        if !notes.is_empty() {
+            text.push_str(" - ");
+            text.push_str(&notes.join("; "));
+        }
+        text
+    }
+}
+
+/// Creatures by lowercased name
+#[derive(Debug, Clone, Default)]
+pub struct CreatureDb {
+    entries: HashMap<String, CreatureInfo>,
+}
+
+impl CreatureDb {
+    /// Parse a creatures.toml (`["troll king"]` tables)
+    pub fn parse(contents: &str) -> Result<Self, toml::de::Error> {
+        let entries: HashMap<String, CreatureInfo> = toml::from_str(contents)?;
+        Ok(Self {
+            entries: entries
+                .into_iter()
+                .map(|(name, info)| (name.trim().to_lowercase(), info))
+                .collect(),
+        })
+    }
+
+    /// Add `other`'s entries, replacing any with the same name
+    pub fn merge(&mut self, other: CreatureDb) {
+        self.entries.extend(other.entries);
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Find a creature by the name the game shows ("a massive troll king",
+    /// "[stu] hobgoblin"), dropping articles and leading adjectives until a
+    /// known name is left. Returns the database name and entry.
+    pub fn lookup(&self, shown: &str) -> Option<(&str, &CreatureInfo)> {
+        let mut name = shown.trim().to_lowercase();
+        if name.starts_with('[') {
+            if let Some(end) = name.find(']') {
+                name = name[end + 1..].trim().to_string();
+            }
+        }
+        let name = name.trim_end_matches(|c: char| !c.is_alphanumeric());
+
+        let words: Vec<&str> = name.split_whitespace().collect();
+        (0..words.len()).find_map(|skip| {
+            let candidate = words[skip..].join(" ");
+            self.entries
+                .get_key_value(&candidate)
+                .map(|(name, info)| (name.as_str(), info))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn db() -> CreatureDb {
+        CreatureDb::parse(
+            r#"
+            ["Troll King"]
+            level = 35
+            danger = "high"
+            special = ["regeneration"]
+
+            [troll]
+            level = 14
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_lookup_prefers_longest_name() {
+        let db = db();
+        assert_eq!(db.len(), 2);
+        let (name, info) = db.lookup("a massive troll king").unwrap();
+        assert_eq!((name, info.danger), ("troll king", Danger::High));
+        assert_eq!(
+            info.describe(name),
+            "troll king (level 35, high) - special: regeneration"
+        );
+    }
+
+    #[test]
+    fn test_lookup_ignores_status_tags_and_unknowns() {
+        let db = db();
+        assert_eq!(
+            db.lookup("[stu] a forest troll.").map(|(n, _)| n),
+            Some("troll")
+        );
+        assert!(db.lookup("a kobold").is_none());
+    }
+
+    #[test]
+    fn test_merge_overrides_entries() {
+        let mut db = db();
+        db.merge(CreatureDb::parse("[troll]\nlevel = 20\ndanger = \"moderate\"").unwrap());
+        assert_eq!(db.lookup("troll").unwrap().1.level, 20);
+    }
+
+    #[test]
+    fn test_danger_ordering() {
+        assert!(Danger::Deadly > Danger::High);
+    }
+
+    #[test]
+    fn test_bundled_table_parses() {
+        assert!(
+            !CreatureDb::parse(include_str!("../../defaults/creatures.toml"))
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
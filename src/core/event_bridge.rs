@@ -6,10 +6,35 @@
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::crossterm::event as rt_event;
 
+/// Map the custom fork's Keypad* codes to the characters they type
+///
+/// Keypad codes only matter for keybind lookups; forms, editors and other
+/// text fields should see the plain character.
+pub fn keypad_as_char(code: KeyCode) -> KeyCode {
+    match code {
+        KeyCode::Keypad0 => KeyCode::Char('0'),
+        KeyCode::Keypad1 => KeyCode::Char('1'),
+        KeyCode::Keypad2 => KeyCode::Char('2'),
+        KeyCode::Keypad3 => KeyCode::Char('3'),
+        KeyCode::Keypad4 => KeyCode::Char('4'),
+        KeyCode::Keypad5 => KeyCode::Char('5'),
+        KeyCode::Keypad6 => KeyCode::Char('6'),
+        KeyCode::Keypad7 => KeyCode::Char('7'),
+        KeyCode::Keypad8 => KeyCode::Char('8'),
+        KeyCode::Keypad9 => KeyCode::Char('9'),
+        KeyCode::KeypadPlus => KeyCode::Char('+'),
+        KeyCode::KeypadMinus => KeyCode::Char('-'),
+        KeyCode::KeypadMultiply => KeyCode::Char('*'),
+        KeyCode::KeypadDivide => KeyCode::Char('/'),
+        KeyCode::KeypadPeriod => KeyCode::Char('.'),
+        other => other,
+    }
+}
+
 /// Convert a crossterm KeyEvent to a ratatui::crossterm KeyEvent for tui-textarea
 pub fn to_textarea_event(key: KeyEvent) -> rt_event::KeyEvent {
-    // Convert KeyCode
-    let rt_code = match key.code {
+    // Convert KeyCode (keypad keys type their plain character)
+    let rt_code = match keypad_as_char(key.code) {
         KeyCode::Backspace => rt_event::KeyCode::Backspace,
         KeyCode::Enter => rt_event::KeyCode::Enter,
         KeyCode::Left => rt_event::KeyCode::Left,
@@ -28,22 +53,6 @@ pub fn to_textarea_event(key: KeyEvent) -> rt_event::KeyEvent {
         KeyCode::Char(c) => rt_event::KeyCode::Char(c),
         KeyCode::Null => rt_event::KeyCode::Null,
         KeyCode::Esc => rt_event::KeyCode::Esc,
-        // Keypad variants from custom fork - map to regular equivalents for textarea
-        KeyCode::Keypad0 => rt_event::KeyCode::Char('0'),
-        KeyCode::Keypad1 => rt_event::KeyCode::Char('1'),
-        KeyCode::Keypad2 => rt_event::KeyCode::Char('2'),
-        KeyCode::Keypad3 => rt_event::KeyCode::Char('3'),
-        KeyCode::Keypad4 => rt_event::KeyCode::Char('4'),
-        KeyCode::Keypad5 => rt_event::KeyCode::Char('5'),
-        KeyCode::Keypad6 => rt_event::KeyCode::Char('6'),
-        KeyCode::Keypad7 => rt_event::KeyCode::Char('7'),
-        KeyCode::Keypad8 => rt_event::KeyCode::Char('8'),
-        KeyCode::Keypad9 => rt_event::KeyCode::Char('9'),
-        KeyCode::KeypadPlus => rt_event::KeyCode::Char('+'),
-        KeyCode::KeypadMinus => rt_event::KeyCode::Char('-'),
-        KeyCode::KeypadMultiply => rt_event::KeyCode::Char('*'),
-        KeyCode::KeypadDivide => rt_event::KeyCode::Char('/'),
-        KeyCode::KeypadPeriod => rt_event::KeyCode::Char('.'),
         _ => rt_event::KeyCode::Null,
     };
 
@@ -66,3 +75,21 @@ pub fn to_textarea_event(key: KeyEvent) -> rt_event::KeyEvent {
         state: rt_event::KeyEventState::empty(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keypad_as_char() {
+        assert_eq!(keypad_as_char(KeyCode::Keypad7), KeyCode::Char('7'));
+        assert_eq!(keypad_as_char(KeyCode::KeypadPeriod), KeyCode::Char('.'));
+        assert_eq!(keypad_as_char(KeyCode::Enter), KeyCode::Enter);
+    }
+
+    #[test]
+    fn test_textarea_event_types_keypad_chars() {
+        let key = KeyEvent::new(KeyCode::KeypadMinus, KeyModifiers::NONE);
+        assert_eq!(to_textarea_event(key).code, rt_event::KeyCode::Char('-'));
+    }
+}
@@ -0,0 +1,196 @@
+//! Wound details for the injury doll popup
+//!
+//! The doll tracks one level per body part (1-3 wound ranks, 4-6 scar
+//! ranks). Clicking a part describes it and suggests the usual herb from
+//! the standard herb chart; `[injury_remedies]` in config.toml replaces any
+//! suggestion (with a spell, a different herb, or "" for none).
+
+use std::collections::HashMap;
+
+/// Which herb family treats a body part
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BodyArea {
+    Head,
+    Organ,
+    Nerves,
+    Limb,
+}
+
+impl BodyArea {
+    pub fn of(part: &str) -> Self {
+        match part {
+            "head" | "neck" => BodyArea::Head,
+            "leftEye" | "rightEye" | "chest" | "abdomen" | "back" => BodyArea::Organ,
+            "nsys" => BodyArea::Nerves,
+            _ => BodyArea::Limb,
+        }
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            BodyArea::Head => "head",
+            BodyArea::Organ => "organ",
+            BodyArea::Nerves => "nerves",
+            BodyArea::Limb => "limb",
+        }
+    }
+}
+
+/// Readable name for a doll body part id ("leftArm" -> "left arm")
+pub fn part_label(part: &str) -> String {
+    match part {
+        "nsys" => "nerves".to_string(),
+        _ => part
+            .chars()
+            .flat_map(|c| {
+                let space = c.is_ascii_uppercase().then_some(' ');
+                space
+                    .into_iter()
+                    .chain(std::iter::once(c.to_ascii_lowercase()))
+            })
+            .collect(),
+    }
+}
+
+/// Standard herb for an area, wound or scar, minor (rank 1) or major
+fn standard_remedy(area: BodyArea, scar: bool, major: bool) -> &'static str {
+    match (area, scar, major) {
+        (BodyArea::Head, false, false) => "rose-marrow potion",
+        (BodyArea::Head, false, true) => "aloeas stem",
+        (BodyArea::Head, true, false) => "brostheras potion",
+        (BodyArea::Head, true, true) => "bur-clover potion",
+        (BodyArea::Organ, false, false) => "basal moss",
+        (BodyArea::Organ, false, true) => "pothinir grass",
+        (BodyArea::Organ, true, false) => "talneo potion",
+        (BodyArea::Organ, true, true) => "wingstem potion",
+        (BodyArea::Nerves, false, false) => "wolifrew lichen",
+        (BodyArea::Nerves, false, true) => "bolmara potion",
+        (BodyArea::Nerves, true, false) => "woth flower",
+        (BodyArea::Nerves, true, true) => "torban leaf",
+        (BodyArea::Limb, false, false) => "ephlox moss",
+        (BodyArea::Limb, false, true) => "cactacae spine",
+        (BodyArea::Limb, true, false) => "calamia fruit",
+        (BodyArea::Limb, true, true) => "sovyn clove",
+    }
+}
+
+/// Suggested treatment for `level` on `part`, None when uninjured
+///
+/// Overrides are looked up most specific first: "limb wound 3", then
+/// "limb wound".
+pub fn remedy(part: &str, level: u8, overrides: &HashMap<String, String>) -> Option<String> {
+    if level == 0 {
+        return None;
+    }
+    let area = BodyArea::of(part);
+    let scar = level > 3;
+    let rank = if scar { level - 3 } else { level };
+    let kind = if scar { "scar" } else { "wound" };
+
+    let general = format!("{} {}", area.key(), kind);
+    let specific = format!("{} {}", general, rank);
+    match overrides.get(&specific).or_else(|| overrides.get(&general)) {
+        Some(custom) => Some(custom.clone()).filter(|c| !c.is_empty()),
+        None => Some(standard_remedy(area, scar, rank > 1).to_string()),
+    }
+}
+
+/// Popup lines for one body part
+pub fn details(
+    part: &str,
+    level: u8,
+    bleeding: bool,
+    overrides: &HashMap<String, String>,
+) -> Vec<String> {
+    let mut label = part_label(part);
+    if let Some(first) = label.get(..1) {
+        label = first.to_uppercase() + &label[1..];
+    }
+    let state = match level {
+        0 => "no injury".to_string(),
+        1..=3 => format!("rank {} wound", level),
+        _ => format!("rank {} scar", level.min(6) - 3),
+    };
+
+    let mut lines = vec![format!("{}: {}", label, state)];
+    lines.push(if bleeding {
+        "Bleeding".to_string()
+    } else {
+        "Not bleeding".to_string()
+    });
+    if let Some(remedy) = remedy(part, level, overrides) {
+        lines.push(format!("Treat with: {}", remedy));
+    }
+    if bleeding {
+        lines.push("Stop bleeding: acantha leaf".to_string());
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn overrides() -> HashMap<String, String> {
+        [
+            ("limb wound".to_string(), "Troll's Blood (1125)".to_string()),
+            ("limb wound 1".to_string(), String::new()),
+        ]
+        .into()
+    }
+
+    #[test]
+    fn test_part_label() {
+        assert_eq!(part_label("leftArm"), "left arm");
+        assert_eq!(part_label("nsys"), "nerves");
+    }
+
+    #[test]
+    fn test_wound_details() {
+        assert_eq!(
+            details("rightLeg", 2, false, &HashMap::new()),
+            vec![
+                "Right leg: rank 2 wound",
+                "Not bleeding",
+                "Treat with: cactacae spine"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bleeding_scar_details() {
+        assert_eq!(
+            details("nsys", 4, true, &HashMap::new()),
+            vec![
+                "Nerves: rank 1 scar",
+                "Bleeding",
+                "Treat with: woth flower",
+                "Stop bleeding: acantha leaf"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_uninjured_details() {
+        assert_eq!(
+            details("head", 0, false, &HashMap::new()),
+            vec!["Head: no injury", "Not bleeding"]
+        );
+    }
+
+    #[test]
+    fn test_remedy_overrides() {
+        let overrides = overrides();
+        assert_eq!(
+            remedy("leftHand", 3, &overrides).as_deref(),
+            Some("Troll's Blood (1125)")
+        );
+        // An empty rank override removes the suggestion
+        assert_eq!(remedy("leftHand", 1, &overrides), None);
+        // Other areas keep the standard herb
+        assert_eq!(
+            remedy("chest", 6, &overrides).as_deref(),
+            Some("wingstem potion")
+        );
+    }
+}
@@ -28,14 +28,17 @@ fn get_action_context(mode: &InputMode) -> ActionContext {
         | InputMode::ColorPaletteBrowser
         | InputMode::SpellColorsBrowser
         | InputMode::UIColorsBrowser
-        | InputMode::ThemeBrowser => ActionContext::Browser,
+        | InputMode::ThemeBrowser
+        | InputMode::ConfigDiff
+        | InputMode::SnippetBrowser => ActionContext::Browser,
 
         // Form widgets
         InputMode::HighlightForm
         | InputMode::KeybindForm
         | InputMode::ColorForm
         | InputMode::SpellColorForm
-        | InputMode::ThemeEditor => ActionContext::Form,
+        | InputMode::ThemeEditor
+        | InputMode::Composer => ActionContext::Form,
 
         // Settings editor (hybrid - has both navigation and inline editing)
         InputMode::SettingsEditor => ActionContext::SettingsEditor,
@@ -48,6 +51,7 @@ fn get_action_context(mode: &InputMode) -> ActionContext {
         | InputMode::Navigation
         | InputMode::History
         | InputMode::Search
+        | InputMode::CopyMode
         | InputMode::Menu => ActionContext::Browser, // Fallback (shouldn't be called)
     }
 }
@@ -0,0 +1,218 @@
+//! Item state hints (worn, held, closed, open, lit) for links
+//!
+//! The game doesn't tag item state, but it shows up in the text around a
+//! link: "a closed chest", "a lantern (lit)", "You are wearing ...", and the
+//! top level of the inventory panel. Flags are attached to each line's
+//! `LinkData` so widgets can annotate items and drag/drop can pick a command
+//! that will actually work.
+
+use crate::data::{ItemFlags, LinkData, TextSegment};
+
+/// Where a dragged item was dropped
+#[derive(Debug, Clone, PartialEq)]
+pub enum DropTarget {
+    /// "left" or "right"
+    Hand(String),
+    Item(LinkData),
+    Ground,
+}
+
+/// Fill in `LinkData::flags` for every link on one line of `stream`
+pub fn annotate_line(segments: &mut [TextSegment], stream: &str) {
+    let line: String = segments.iter().map(|s| s.text.as_str()).collect();
+    let trimmed = line.trim_start();
+
+    // The inventory panel lists worn items at the top level, their contents indented
+    let worn_context = (stream == "inv" && line.len() - trimmed.len() <= 2)
+        || trimmed.starts_with("You are wearing");
+
+    let mut i = 0;
+    while i < segments.len() {
+        let Some(exist_id) = segments[i].link_data.as_ref().map(|l| l.exist_id.clone()) else {
+            i += 1;
+            continue;
+        };
+        // A link can span several segments (color changes inside it)
+        let mut end = i + 1;
+        while end < segments.len()
+            && segments[end].link_data.as_ref().map(|l| &l.exist_id) == Some(&exist_id)
+        {
+            end += 1;
+        }
+
+        let link_text: String = segments[i..end].iter().map(|s| s.text.as_str()).collect();
+        let before = segments[..i]
+            .iter()
+            .rev()
+            .take_while(|s| s.link_data.is_none())
+            .map(|s| s.text.as_str())
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rev()
+            .collect::<String>();
+        let after: String = segments[end..]
+            .iter()
+            .take_while(|s| s.link_data.is_none())
+            .map(|s| s.text.as_str())
+            .collect();
+
+        let mut flags = segments[i]
+            .link_data
+            .as_ref()
+            .map(|l| l.flags)
+            .unwrap_or_default();
+        flags.worn |= worn_context && exist_id != "_direct_";
+
+        // Adjectives in the link text or right before it ("a closed <chest>")
+        let preceding_word = before.split_whitespace().last().unwrap_or("");
+        for word in link_text
+            .split_whitespace()
+            .chain(std::iter::once(preceding_word))
+        {
+            apply_word(&mut flags, word);
+        }
+        // Trailing note: "<lantern> (lit)", "<backpack> (closed)"
+        if let Some(note) = after.trim_start().strip_prefix('(') {
+            let note = note.split(')').next().unwrap_or("");
+            for word in note.split(|c: char| !c.is_alphanumeric()) {
+                apply_word(&mut flags, word);
+            }
+        }
+
+        for segment in &mut segments[i..end] {
+            if let Some(ref mut link) = segment.link_data {
+                link.flags = flags;
+            }
+        }
+        i = end;
+    }
+}
+
+fn apply_word(flags: &mut ItemFlags, word: &str) {
+    match word
+        .to_lowercase()
+        .trim_matches(|c: char| !c.is_alphanumeric())
+    {
+        "closed" => {
+            flags.closed = true;
+            flags.open = false;
+        }
+        "open" | "opened" => {
+            flags.open = true;
+            flags.closed = false;
+        }
+        "lit" => flags.lit = true,
+        "worn" => flags.worn = true,
+        "held" => flags.held = true,
+        _ => {}
+    }
+}
+
+/// Pick the command for dragging `item` onto `target`
+///
+/// Errors explain why the drop can't work, so nothing is sent.
+pub fn drag_command(item: &LinkData, target: &DropTarget) -> Result<String, String> {
+    let id = &item.exist_id;
+    match target {
+        // Worn gear has to come off before it can go anywhere
+        DropTarget::Hand(_) if item.flags.worn => Ok(format!("remove #{}", id)),
+        DropTarget::Hand(hand) => Ok(format!("_drag #{} {}", id, hand)),
+        _ if item.flags.worn => Err(format!(
+            "{} is worn - drag it to a hand to remove it first",
+            item.noun
+        )),
+        DropTarget::Item(container) if container.flags.closed => {
+            Err(format!("The {} is closed", container.noun))
+        }
+        DropTarget::Item(container) => Ok(format!("_drag #{} #{}", id, container.exist_id)),
+        DropTarget::Ground => Ok(format!("_drag #{} drop", id)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::SpanType;
+
+    fn segment(text: &str, link: Option<(&str, &str)>) -> TextSegment {
+        TextSegment {
+            text: text.to_string(),
+            fg: None,
+            bg: None,
+            bold: false,
+            span_type: if link.is_some() {
+                SpanType::Link
+            } else {
+                SpanType::Normal
+            },
+            link_data: link.map(|(id, noun)| LinkData {
+                exist_id: id.to_string(),
+                noun: noun.to_string(),
+                text: String::new(),
+                coord: None,
+                flags: ItemFlags::default(),
+            }),
+        }
+    }
+
+    /// The chest and lantern from a room line, annotated
+    fn room_items() -> (LinkData, LinkData) {
+        let mut line = vec![
+            segment("You also see a closed ", None),
+            segment("iron chest", Some(("1", "chest"))),
+            segment(" and a ", None),
+            segment("brass lantern", Some(("2", "lantern"))),
+            segment(" (lit).", None),
+        ];
+        annotate_line(&mut line, "main");
+        (
+            line[1].link_data.clone().unwrap(),
+            line[3].link_data.clone().unwrap(),
+        )
+    }
+
+    /// A top-level (worn) inventory item, annotated
+    fn worn_backpack() -> LinkData {
+        let mut inv = vec![
+            segment("  ", None),
+            segment("a backpack", Some(("3", "backpack"))),
+        ];
+        annotate_line(&mut inv, "inv");
+        inv[1].link_data.clone().unwrap()
+    }
+
+    #[test]
+    fn test_annotate_reads_state_words_around_links() {
+        let (chest, lantern) = room_items();
+        assert!(chest.flags.closed && !chest.flags.worn);
+        assert!(lantern.flags.lit && !lantern.flags.closed);
+        assert_eq!(lantern.flags.marker(), Some("☼"));
+    }
+
+    #[test]
+    fn test_annotate_marks_top_level_inventory_worn() {
+        assert!(worn_backpack().flags.worn);
+        let mut nested = vec![
+            segment("      ", None),
+            segment("a gem", Some(("4", "gem"))),
+        ];
+        annotate_line(&mut nested, "inv");
+        assert!(!nested[1].link_data.as_ref().unwrap().flags.worn);
+    }
+
+    #[test]
+    fn test_drag_command_respects_item_state() {
+        let (chest, lantern) = room_items();
+        let backpack = worn_backpack();
+        assert_eq!(
+            drag_command(&backpack, &DropTarget::Hand("left".into())),
+            Ok("remove #3".to_string())
+        );
+        assert!(drag_command(&backpack, &DropTarget::Item(chest.clone())).is_err());
+        assert!(drag_command(&lantern, &DropTarget::Item(chest)).is_err());
+        assert_eq!(
+            drag_command(&lantern, &DropTarget::Ground),
+            Ok("_drag #2 drop".to_string())
+        );
+    }
+}
@@ -0,0 +1,299 @@
+//! Macro interpreter for keybind macros.
+//!
+//! Macro text understands the classic front-end escapes:
+//! - carriage return (or `\r`, `\n`) sends the text typed so far
+//! - `\p` pauses for a second before continuing
+//! - `\?` puts the text so far in the command input and waits for Enter
+//! - `\x` clears the command input
+//! - `@` marks where the cursor goes when text lands in the command input
+//!   (`\@` and `\\` type a literal `@` or backslash)
+//!
+//! The interpreter only produces `MacroEffect`s; the main loop applies them,
+//! since the command input lives in the frontend.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Length of a `\p` pause
+pub const MACRO_PAUSE: Duration = Duration::from_secs(1);
+
+/// One parsed macro instruction
+#[derive(Debug, Clone, PartialEq)]
+pub enum MacroStep {
+    Text(String),
+    Send,
+    Pause,
+    WaitForInput,
+    ClearInput,
+    Cursor,
+    /// Wait before the next step (spacing between composed lines)
+    Delay(Duration),
+}
+
+/// Something the frontend has to do on the macro's behalf
+#[derive(Debug, Clone, PartialEq)]
+pub enum MacroEffect {
+    /// Send a command (goes through AppCore::send_command, so dot commands work)
+    Send(String),
+    /// Clear the command input
+    ClearInput,
+    /// Type text into the command input, leaving the cursor `cursor` chars in
+    TypeInput { text: String, cursor: usize },
+}
+
+/// Parse macro text into steps
+pub fn parse_macro(text: &str) -> Vec<MacroStep> {
+    let mut steps = Vec::new();
+    let mut literal = String::new();
+    let mut chars = text.chars();
+
+    let flush = |literal: &mut String, steps: &mut Vec<MacroStep>| {
+        if !literal.is_empty() {
+            steps.push(MacroStep::Text(std::mem::take(literal)));
+        }
+    };
+
+    while let Some(c) = chars.next() {
+        let step = match c {
+            '\r' | '\n' => MacroStep::Send,
+            '@' => MacroStep::Cursor,
+            '\\' => match chars.next() {
+                Some('r') | Some('R') | Some('n') | Some('N') => MacroStep::Send,
+                Some('p') | Some('P') => MacroStep::Pause,
+                Some('?') => MacroStep::WaitForInput,
+                Some('x') | Some('X') => MacroStep::ClearInput,
+                Some(other) if other == '@' || other == '\\' => {
+                    literal.push(other);
+                    continue;
+                }
+                Some(other) => {
+                    literal.push('\\');
+                    literal.push(other);
+                    continue;
+                }
+                None => {
+                    literal.push('\\');
+                    continue;
+                }
+            },
+            _ => {
+                literal.push(c);
+                continue;
+            }
+        };
+        flush(&mut literal, &mut steps);
+        // "\r\n" line endings would otherwise send twice
+        if step == MacroStep::Send && c == '\n' && steps.last() == Some(&MacroStep::Send) {
+            continue;
+        }
+        steps.push(step);
+    }
+    flush(&mut literal, &mut steps);
+    steps
+}
+
+/// A macro in progress; survives across frames while paused or waiting
+#[derive(Debug)]
+pub struct MacroRun {
+    steps: VecDeque<MacroStep>,
+    /// Text typed since the last send
+    line: String,
+    /// Cursor position within `line` set by `@`
+    cursor: Option<usize>,
+    resume_at: Option<Instant>,
+    waiting_for_input: bool,
+    /// Lines the user entered (a paste or the composer), echoed like typed commands
+    typed: bool,
+}
+
+impl MacroRun {
+    pub fn new(text: &str) -> Self {
+        Self {
+            steps: parse_macro(text).into(),
+            line: String::new(),
+            cursor: None,
+            resume_at: None,
+            waiting_for_input: false,
+            typed: false,
+        }
+    }
+
+    /// Mark the lines as the user's own rather than scripted
+    pub fn typed(mut self) -> Self {
+        self.typed = true;
+        self
+    }
+
+    pub fn is_typed(&self) -> bool {
+        self.typed
+    }
+
+    /// Send each line as-is (no escapes), `delay` apart, e.g. a confirmed paste
+    pub fn from_lines(lines: &[String], delay: Duration) -> Self {
+        let mut run = Self::new("");
+        for (i, line) in lines.iter().enumerate() {
+            if i > 0 && !delay.is_zero() {
+                run.steps.push_back(MacroStep::Delay(delay));
+            }
+            run.steps.push_back(MacroStep::Text(line.clone()));
+            run.steps.push_back(MacroStep::Send);
+        }
+        run
+    }
+
+    /// No steps left to run
+    pub fn is_finished(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// The user pressed Enter; a `\?` wait is over
+    pub fn input_submitted(&mut self) {
+        self.waiting_for_input = false;
+    }
+
+    /// Run until the macro ends, pauses, or waits for input
+    pub fn advance(&mut self, now: Instant) -> Vec<MacroEffect> {
+        let mut effects = Vec::new();
+        if self.waiting_for_input || self.resume_at.is_some_and(|t| now < t) {
+            return effects;
+        }
+        self.resume_at = None;
+
+        while let Some(step) = self.steps.pop_front() {
+            match step {
+                MacroStep::Text(text) => self.line.push_str(&text),
+                MacroStep::Cursor => self.cursor = Some(self.line.chars().count()),
+                MacroStep::Send => {
+                    let command = std::mem::take(&mut self.line);
+                    self.cursor = None;
+                    if !command.trim().is_empty() {
+                        effects.push(MacroEffect::Send(command));
+                    }
+                }
+                MacroStep::ClearInput => {
+                    self.line.clear();
+                    self.cursor = None;
+                    effects.push(MacroEffect::ClearInput);
+                }
+                MacroStep::Pause => {
+                    self.resume_at = Some(now + MACRO_PAUSE);
+                    return effects;
+                }
+                MacroStep::Delay(delay) => {
+                    self.resume_at = Some(now + delay);
+                    return effects;
+                }
+                MacroStep::WaitForInput => {
+                    effects.extend(self.type_line());
+                    self.waiting_for_input = true;
+                    return effects;
+                }
+            }
+        }
+
+        // Leftover text: sent as before, unless `@` asked for it to be edited
+        if self.cursor.is_some() {
+            effects.extend(self.type_line());
+        } else if !self.line.trim().is_empty() {
+            effects.push(MacroEffect::Send(std::mem::take(&mut self.line)));
+        }
+        effects
+    }
+
+    fn type_line(&mut self) -> Option<MacroEffect> {
+        let text = std::mem::take(&mut self.line);
+        let cursor = self.cursor.take().unwrap_or_else(|| text.chars().count());
+        (!text.is_empty()).then_some(MacroEffect::TypeInput { text, cursor })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_macro_escapes() {
+        assert_eq!(
+            parse_macro("sw\r"),
+            vec![MacroStep::Text("sw".to_string()), MacroStep::Send]
+        );
+        assert_eq!(
+            parse_macro(r"\xget @ from\@pack\p\r"),
+            vec![
+                MacroStep::ClearInput,
+                MacroStep::Text("get ".to_string()),
+                MacroStep::Cursor,
+                MacroStep::Text(" from@pack".to_string()),
+                MacroStep::Pause,
+                MacroStep::Send,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_run_pauses_and_waits() {
+        let start = Instant::now();
+        let mut run = MacroRun::new("stance off\r\\pattack\r");
+        assert_eq!(
+            run.advance(start),
+            vec![MacroEffect::Send("stance off".to_string())]
+        );
+        assert!(run.advance(start).is_empty());
+        assert_eq!(
+            run.advance(start + MACRO_PAUSE),
+            vec![MacroEffect::Send("attack".to_string())]
+        );
+        assert!(run.is_finished());
+
+        let mut run = MacroRun::new("prep 101\\?cast\r");
+        assert_eq!(
+            run.advance(start),
+            vec![MacroEffect::TypeInput {
+                text: "prep 101".to_string(),
+                cursor: 8
+            }]
+        );
+        assert!(run.advance(start).is_empty());
+        run.input_submitted();
+        assert_eq!(
+            run.advance(start),
+            vec![MacroEffect::Send("cast".to_string())]
+        );
+
+        // A cursor mark leaves the text in the input for editing
+        let mut run = MacroRun::new("get @ from pack");
+        assert_eq!(
+            run.advance(start),
+            vec![MacroEffect::TypeInput {
+                text: "get  from pack".to_string(),
+                cursor: 4
+            }]
+        );
+
+        // Pasted lines are sent verbatim, escapes and all
+        let lines = vec!["say @you \\p".to_string(), "look".to_string()];
+        let mut run = MacroRun::from_lines(&lines, Duration::ZERO);
+        assert_eq!(
+            run.advance(start),
+            vec![
+                MacroEffect::Send("say @you \\p".to_string()),
+                MacroEffect::Send("look".to_string())
+            ]
+        );
+        assert!(run.is_finished());
+
+        // Composed lines can be spaced out
+        let delay = Duration::from_millis(500);
+        let mut run = MacroRun::from_lines(&lines, delay);
+        assert_eq!(
+            run.advance(start),
+            vec![MacroEffect::Send("say @you \\p".to_string())]
+        );
+        assert!(run.advance(start + delay / 2).is_empty());
+        assert_eq!(
+            run.advance(start + delay),
+            vec![MacroEffect::Send("look".to_string())]
+        );
+        assert!(run.is_finished());
+    }
+}
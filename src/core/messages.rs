@@ -49,6 +49,26 @@ pub struct MessageProcessor {
 
     /// Previous room component values (for change detection to avoid unnecessary processing)
     previous_room_components: std::collections::HashMap<String, String>,
+
+    /// (stream, text, whisper-tagged) of lines added to windows since AppCore last drained them
+    pub added_lines: Vec<(String, String, bool)>,
+
+    /// Bounty stream lines for the bounty tracker since AppCore last drained them
+    pub bounty_lines: Vec<String>,
+
+    /// Creature names from bold (monster) text since AppCore last drained them
+    pub creature_mentions: Vec<String>,
+
+    /// Web links found in text since AppCore last drained them
+    pub urls: Vec<String>,
+
+    /// Text windows subscribed to each stream, with their line filters
+    stream_router: crate::core::stream_router::StreamRouter,
+
+    /// Screen reader mode: speak main-window lines, skipping recent repeats
+    screen_reader: bool,
+    speech_dedupe: crate::core::screen_reader::SpeechDedupe,
+    dedupe_window: std::time::Duration,
 }
 
 impl MessageProcessor {
@@ -62,6 +82,8 @@ impl MessageProcessor {
             .collect();
         let event_patterns = config.event_patterns.clone();
         let parser = crate::parser::XmlParser::with_presets(preset_list, event_patterns);
+        // [[redirects]] are compiled by AppCore so skipped rules can be reported
+        let stream_router = crate::core::stream_router::StreamRouter::default();
 
         Self {
             config,
@@ -77,6 +99,14 @@ impl MessageProcessor {
             combat_buffer: Vec::new(),
             playerlist_buffer: Vec::new(),
             previous_room_components: std::collections::HashMap::new(),
+            added_lines: Vec::new(),
+            bounty_lines: Vec::new(),
+            creature_mentions: Vec::new(),
+            urls: Vec::new(),
+            stream_router,
+            screen_reader: false,
+            speech_dedupe: Default::default(),
+            dedupe_window: std::time::Duration::ZERO,
         }
     }
 
@@ -129,7 +159,15 @@ impl MessageProcessor {
 
                 // Check if a window exists for this stream (map stream to window name first)
                 let window_name = self.map_stream_to_window(&id);
-                if should_discard_if_no_window && ui_state.get_window(&window_name).is_none() {
+                let has_bounty_tracker = id == "bounty"
+                    && ui_state
+                        .windows
+                        .values()
+                        .any(|w| matches!(w.content, WindowContent::BountyTracker));
+                if should_discard_if_no_window
+                    && ui_state.get_window(&window_name).is_none()
+                    && !has_bounty_tracker
+                {
                     self.discard_current_stream = true;
                     tracing::debug!("No window exists for stream '{}' (maps to window '{}'), discarding content", id, window_name);
                 } else {
@@ -327,6 +365,7 @@ impl MessageProcessor {
                     ParserSpanType::Monsterbold => DataSpanType::Monsterbold,
                     ParserSpanType::Spell => DataSpanType::Spell,
                     ParserSpanType::Speech => DataSpanType::Speech,
+                    ParserSpanType::Whisper => DataSpanType::Whisper,
                 };
 
                 self.current_segments.push(TextSegment {
@@ -417,6 +456,14 @@ impl MessageProcessor {
             ParsedElement::SpellHand { spell } => {
                 self.chunk_has_silent_updates = true; // Mark as silent update
 
+                if let Some(event) = game_state.track_spell_hand(spell) {
+                    if let Some(history_window) = ui_state.get_window_mut("spellhistory") {
+                        if let WindowContent::Text(ref mut content) = history_window.content {
+                            content.add_line(StyledLine::from_text(event));
+                        }
+                    }
+                }
+
                 game_state.spell = if spell.is_empty() {
                     None
                 } else {
@@ -500,6 +547,26 @@ impl MessageProcessor {
                     }
                 }
 
+                // Vitals clusters show the same bars as children
+                for window in ui_state.windows.values_mut() {
+                    if let WindowContent::Vitals { ref mut bars } = window.content {
+                        for (_, data) in bars.iter_mut().filter(|(bar_id, _)| bar_id == id) {
+                            data.value = *value;
+                            data.max = *max;
+                            data.label = text.clone();
+                        }
+                    }
+                }
+
+                game_state.bars.insert(
+                    id.clone(),
+                    crate::core::state::BarReading {
+                        value: *value,
+                        max: *max,
+                        text: text.clone(),
+                    },
+                );
+
                 // Also update vitals if it's a known vital
                 match id.as_str() {
                     "health" => game_state.vitals.health = (*value * 100 / *max) as u8,
@@ -809,6 +876,7 @@ impl MessageProcessor {
                             ParserSpanType::Monsterbold => DataSpanType::Monsterbold,
                             ParserSpanType::Spell => DataSpanType::Spell,
                             ParserSpanType::Speech => DataSpanType::Speech,
+                            ParserSpanType::Whisper => DataSpanType::Whisper,
                         };
 
                         // Link data is already the correct type from parser
@@ -844,6 +912,10 @@ impl MessageProcessor {
 
             // Add the line if we got any segments
             if !current_line_segments.is_empty() {
+                if id == "room objs" {
+                    self.collect_creature_mentions(&current_line_segments);
+                }
+                crate::core::item_flags::annotate_line(&mut current_line_segments, "room");
                 if let Some(buffer) = room_components.get_mut(id) {
                     buffer.push(current_line_segments);
                     *room_window_dirty = true;
@@ -861,6 +933,24 @@ impl MessageProcessor {
         }
     }
 
+    /// Note the creature names (runs of bold monster text) on a line
+    fn collect_creature_mentions(&mut self, segments: &[TextSegment]) {
+        let mut name = String::new();
+        for segment in segments {
+            if segment.span_type == SpanType::Monsterbold {
+                name.push_str(&segment.text);
+            } else if !name.trim().is_empty() {
+                self.creature_mentions
+                    .push(std::mem::take(&mut name).trim().to_string());
+            } else {
+                name.clear();
+            }
+        }
+        if !name.trim().is_empty() {
+            self.creature_mentions.push(name.trim().to_string());
+        }
+    }
+
     /// Flush current text to appropriate window
     pub fn flush_current_stream(&mut self, ui_state: &mut UiState) {
         self.flush_current_stream_with_tts(ui_state, None);
@@ -898,6 +988,7 @@ impl MessageProcessor {
         if line.segments.is_empty() {
             return;
         }
+        self.collect_creature_mentions(&line.segments);
 
         // Determine target window based on stream
         let window_name = self.map_stream_to_window(&self.current_stream);
@@ -911,6 +1002,16 @@ impl MessageProcessor {
             return;
         }
 
+        crate::core::item_flags::annotate_line(&mut line.segments, &self.current_stream);
+        let link_fg = self
+            .config
+            .colors
+            .presets
+            .get("links")
+            .and_then(|p| p.fg.clone());
+        let urls = crate::core::urls::mark_urls(&mut line.segments, link_fg.as_deref());
+        self.urls.extend(urls);
+
         // Special handling for inv stream - buffer instead of directly adding to window
         // Inventory updates are sent constantly with same items, so we buffer and compare
         // Inventory stream is always a silent update (shouldn't trigger prompts in main window)
@@ -972,33 +1073,81 @@ impl MessageProcessor {
             return;
         }
 
-        // Add line to window, fallback to main if target doesn't exist (except for inv/combat/playerlist streams)
+        // Add line to every window showing this stream: the stream's own window plus any
+        // text windows that list it in `streams` (mirrors). With neither, fall back to main.
         let mut text_added_to_window = None; // Track (window_name, line_text) for TTS
+        let mut destinations: Vec<String> = self
+            .stream_router
+            .subscribers(&self.current_stream)
+            .map(str::to_string)
+            .collect();
+        if ui_state.get_window(&window_name).is_some()
+            && (window_name != "main" || self.current_stream == "main" || destinations.is_empty())
+            && !destinations.contains(&window_name)
+        {
+            destinations.insert(0, window_name.clone());
+        }
+        let plain_text: String = line.segments.iter().map(|s| s.text.as_str()).collect();
 
-        if let Some(window) = ui_state.get_window_mut(&window_name) {
+        // The bounty tracker parses the stream whether or not a text window shows it
+        if self.current_stream == "bounty" {
+            self.chunk_has_silent_updates = true;
+            self.bounty_lines.push(plain_text.clone());
+        }
+
+        // [[redirects]] copy the line into more windows, or move it there
+        let (redirected, keep) = self
+            .stream_router
+            .redirects(&self.current_stream, &plain_text);
+        let redirected: Vec<String> = redirected
+            .into_iter()
+            .filter(|w| ui_state.get_window(w).is_some())
+            .map(str::to_string)
+            .collect();
+        let moved = !keep && !redirected.is_empty();
+        if moved {
+            destinations.clear();
+        }
+        let redirect_start = destinations.len();
+        for window in redirected {
+            if !destinations.contains(&window) {
+                destinations.push(window);
+            }
+        }
+
+        for (i, destination) in destinations.iter().enumerate() {
+            // Redirect targets take the line whatever their own filters say
+            if i < redirect_start && !self.stream_router.accepts(destination, &plain_text) {
+                continue;
+            }
+            let Some(window) = ui_state.get_window_mut(destination) else {
+                continue;
+            };
             match window.content {
                 WindowContent::Text(ref mut content) => {
                     content.add_line(line.clone());
-                    text_added_to_window = Some(window_name.clone());
                 }
                 WindowContent::Inventory(ref mut content) => {
                     content.add_line(line.clone());
-                    text_added_to_window = Some(window_name.clone());
                 }
                 WindowContent::Spells(ref mut content) => {
                     content.add_line(line.clone());
-                    text_added_to_window = Some(window_name.clone());
                 }
                 _ => {
                     // Other content types don't support text lines
                     tracing::trace!(
                         "Window '{}' doesn't support text content (type: {:?})",
-                        window_name,
+                        destination,
                         std::mem::discriminant(&window.content)
                     );
+                    continue;
                 }
             }
-        } else if window_name != "main" {
+            text_added_to_window.get_or_insert_with(|| destination.clone());
+        }
+
+        if redirect_start == 0 && !moved && window_name != "main" && self.current_stream != "bounty"
+        {
             // Target window doesn't exist, fallback to main (but NOT for inv stream!)
             tracing::trace!(
                 "Window '{}' doesn't exist, routing content to main window",
@@ -1012,6 +1161,16 @@ impl MessageProcessor {
             }
         }
 
+        // Event sounds look at every displayed line (once, however many windows show it)
+        if text_added_to_window.is_some() {
+            let whispered = line
+                .segments
+                .iter()
+                .any(|seg| seg.span_type == crate::data::SpanType::Whisper);
+            self.added_lines
+                .push((self.current_stream.clone(), plain_text, whispered));
+        }
+
         // Enqueue for TTS if enabled and text was added to a window
         if let (Some(window_name), Some(tts_mgr)) = (text_added_to_window, tts_manager) {
             self.enqueue_tts(tts_mgr, &window_name, &line);
@@ -1172,9 +1331,14 @@ impl MessageProcessor {
     }
 
     /// Enqueue text for TTS if enabled and configured for this window
-    fn enqueue_tts(&self, tts_manager: &mut crate::tts::TtsManager, window_name: &str, line: &StyledLine) {
-        // Early exit if TTS not enabled
-        if !self.config.tts.enabled {
+    fn enqueue_tts(
+        &mut self,
+        tts_manager: &mut crate::tts::TtsManager,
+        window_name: &str,
+        line: &StyledLine,
+    ) {
+        // Early exit if TTS not enabled (screen reader mode speaks regardless)
+        if !self.config.tts.enabled && !self.screen_reader {
             return;
         }
 
@@ -1182,7 +1346,8 @@ impl MessageProcessor {
         let should_speak = match window_name {
             "thoughts" => self.config.tts.speak_thoughts,
             "speech" => self.config.tts.speak_whispers, // Whispers go to speech window
-            "main" => self.config.tts.speak_main,
+            "main" => self.config.tts.speak_main || self.screen_reader,
+            "death" => self.config.tts.speak_deaths,
             _ => false, // Don't speak other windows by default
         };
 
@@ -1204,25 +1369,38 @@ impl MessageProcessor {
             return;
         }
 
+        // Screen reader mode: don't repeat a line heard moments ago
+        if self.screen_reader
+            && !self.speech_dedupe.should_speak(
+                &text,
+                self.dedupe_window,
+                std::time::Instant::now(),
+            )
+        {
+            return;
+        }
+
         // Determine priority based on window
         let priority = match window_name {
             "thoughts" => crate::tts::Priority::High, // Thoughts are important
             "speech" => crate::tts::Priority::High,   // Whispers are important
+            "death" => crate::tts::Priority::High,    // Deaths are rare and worth hearing
             "main" => crate::tts::Priority::Normal,   // Regular game text
             _ => crate::tts::Priority::Normal,
         };
 
-        // Enqueue speech entry
-        tts_manager.enqueue(crate::tts::SpeechEntry {
+        // Include/exclude lists and priority rules
+        let Some(priority) = tts_manager.filter_line(&text, priority) else {
+            return;
+        };
+
+        // Speak now, or queue behind more urgent speech
+        if let Err(e) = tts_manager.announce(crate::tts::SpeechEntry {
             text,
             source_window: window_name.to_string(),
             priority,
             spoken: false,
-        });
-
-        // Auto-speak the next item in queue (if not currently speaking)
-        // This ensures new text gets spoken immediately
-        if let Err(e) = tts_manager.speak_next() {
+        }) {
             tracing::warn!("Failed to speak TTS entry: {}", e);
         }
     }
@@ -1250,6 +1428,54 @@ impl MessageProcessor {
         .to_string()
     }
 
+    /// Windows that subscribe to `stream` in the layout
+    pub fn stream_windows(&self, stream: &str) -> Vec<String> {
+        self.stream_router
+            .subscribers(stream)
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Pick up the layout's stream subscriptions and window filters
+    pub fn update_stream_routes(&mut self, layout: &crate::config::Layout) {
+        self.stream_router.update(layout);
+    }
+
+    /// Compile `[[redirects]]`; returns why any rules were skipped
+    pub fn set_redirects(
+        &mut self,
+        rules: &[crate::config::RedirectRule],
+        character: Option<&str>,
+    ) -> Vec<String> {
+        self.config.redirects = rules.to_vec();
+        self.stream_router.set_redirects(rules, character)
+    }
+
+    /// Number of `[[redirects]]` rules in use
+    pub fn redirect_count(&self) -> usize {
+        self.stream_router.redirect_count()
+    }
+
+    /// Pick up TTS settings changed at runtime
+    pub fn set_tts_config(&mut self, tts: &crate::config::TtsConfig) {
+        self.config.tts = tts.clone();
+    }
+
+    /// Turn screen reader speech on or off (`dedupe` = repeat suppression window)
+    pub fn set_screen_reader(&mut self, enabled: bool, dedupe: std::time::Duration) {
+        self.screen_reader = enabled;
+        self.dedupe_window = dedupe;
+    }
+
+    /// Nouns of the linked items in the last inventory update
+    pub fn inventory_nouns(&self) -> impl Iterator<Item = &str> {
+        self.previous_inventory
+            .iter()
+            .flatten()
+            .filter_map(|segment| segment.link_data.as_ref())
+            .map(|link| link.noun.as_str())
+    }
+
     /// Clear inventory cache to force next inventory update to render
     /// Should be called when a new inventory window is added
     pub fn clear_inventory_cache(&mut self) {
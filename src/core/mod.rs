@@ -4,13 +4,32 @@
 //! NO imports from frontend/ or rendering code.
 //! Core updates data structures in the data layer, frontends read and render.
 
+pub mod actions;
+pub mod afk;
 pub mod app_core;
+pub mod compact;
+pub mod completion;
+pub mod creatures;
 pub mod event_bridge;
+pub mod injuries;
 pub mod input_result;
 pub mod input_router;
+pub mod item_flags;
+pub mod macros;
 pub mod menu_actions;
 pub mod messages;
+pub mod parse_worker;
+pub mod paste;
+pub mod screen_reader;
+pub mod session_stats;
+pub mod snap;
+pub mod snippets;
+pub mod sound_events;
 pub mod state;
+pub mod stream_router;
+pub mod theme_schedule;
+pub mod trigger_gate;
+pub mod urls;
 
 pub use app_core::AppCore;
 pub use messages::MessageProcessor;
@@ -0,0 +1,250 @@
+//! Background parsing of server text
+//!
+//! XML parsing and sound-highlight matching run on a worker thread, so a
+//! giant text dump (inventory, spell lists, `help` output) never holds up
+//! input handling or frame rendering. The worker owns its own clone of the
+//! stateful `XmlParser` and answers every raw line, in order, with its parsed
+//! elements plus the names of sound highlights the line matched. The UI
+//! thread applies finished lines a frame's worth at a time.
+
+use crate::config::HighlightPattern;
+use crate::parser::{ParsedElement, XmlParser};
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::{Duration, Instant};
+
+/// How a sound highlight recognizes its line
+#[derive(Clone)]
+enum Matcher {
+    /// `fast_parse`: any of the pipe-separated literals
+    Literals(Vec<String>),
+    Regex(Regex),
+}
+
+/// A highlight with a sound, bell or flash, compiled once instead of per line
+#[derive(Clone)]
+pub struct SoundTrigger {
+    name: String,
+    matcher: Matcher,
+}
+
+impl SoundTrigger {
+    /// Compile every highlight that has a sound, bell or flash (invalid regexes are skipped)
+    pub fn compile(highlights: &HashMap<String, HighlightPattern>) -> Vec<SoundTrigger> {
+        highlights
+            .iter()
+            .filter(|(_, pattern)| pattern.sound.is_some() || pattern.bell || pattern.flash)
+            .filter_map(|(name, pattern)| {
+                let matcher = if pattern.fast_parse {
+                    Matcher::Literals(
+                        pattern
+                            .pattern
+                            .split('|')
+                            .map(|p| p.trim().to_string())
+                            .collect(),
+                    )
+                } else {
+                    let regex = match &pattern.compiled_regex {
+                        Some(regex) => regex.clone(),
+                        None => Regex::new(&pattern.pattern).ok()?,
+                    };
+                    Matcher::Regex(regex)
+                };
+                Some(SoundTrigger {
+                    name: name.clone(),
+                    matcher,
+                })
+            })
+            .collect()
+    }
+
+    fn matches(&self, text: &str) -> bool {
+        match &self.matcher {
+            Matcher::Literals(literals) => literals.iter().any(|p| text.contains(p.as_str())),
+            Matcher::Regex(regex) => regex.is_match(text),
+        }
+    }
+}
+
+/// Names of the sound highlights `text` matches
+pub fn sound_hits(triggers: &[SoundTrigger], text: &str) -> Vec<String> {
+    triggers
+        .iter()
+        .filter(|trigger| trigger.matches(text))
+        .map(|trigger| trigger.name.clone())
+        .collect()
+}
+
+/// One server line, ready for the UI thread
+#[derive(Debug, Clone)]
+pub struct ParsedLine {
+    pub elements: Vec<ParsedElement>,
+    /// Sound highlights this line matched (firing limits are checked later)
+    pub sound_hits: Vec<String>,
+    /// Time the worker spent parsing and matching this line
+    pub parse_time: Duration,
+}
+
+enum Job {
+    Line(String),
+    SoundTriggers(Vec<SoundTrigger>),
+}
+
+/// Handle to the parse thread
+pub struct ParseWorker {
+    jobs: Sender<Job>,
+    results: Receiver<ParsedLine>,
+    /// Lines sent but not yet taken back
+    backlog: usize,
+}
+
+impl ParseWorker {
+    /// Start the thread with its own copy of `parser` (carrying over its state)
+    pub fn spawn(parser: XmlParser, triggers: Vec<SoundTrigger>) -> std::io::Result<Self> {
+        let (jobs, job_rx) = mpsc::channel();
+        let (result_tx, results) = mpsc::channel();
+        std::thread::Builder::new()
+            .name("parse-worker".to_string())
+            .spawn(move || run(parser, triggers, job_rx, result_tx))?;
+        Ok(Self {
+            jobs,
+            results,
+            backlog: 0,
+        })
+    }
+
+    /// Queue a raw server line (handed back if the thread has gone away)
+    pub fn submit(&mut self, line: String) -> Result<(), String> {
+        self.jobs.send(Job::Line(line)).map_err(|e| match e.0 {
+            Job::Line(line) => line,
+            Job::SoundTriggers(_) => unreachable!(),
+        })?;
+        self.backlog += 1;
+        Ok(())
+    }
+
+    /// Replace the sound highlights (lines already queued use the old set)
+    pub fn set_sound_triggers(&self, triggers: Vec<SoundTrigger>) {
+        let _ = self.jobs.send(Job::SoundTriggers(triggers));
+    }
+
+    /// Next finished line, in submission order
+    pub fn try_recv(&mut self) -> Option<ParsedLine> {
+        let line = self.results.try_recv().ok()?;
+        self.backlog -= 1;
+        Some(line)
+    }
+
+    /// Lines still being parsed or waiting to be applied
+    pub fn backlog(&self) -> usize {
+        self.backlog
+    }
+}
+
+fn run(
+    mut parser: XmlParser,
+    mut triggers: Vec<SoundTrigger>,
+    jobs: Receiver<Job>,
+    results: Sender<ParsedLine>,
+) {
+    for job in jobs {
+        match job {
+            Job::Line(line) => {
+                let started = Instant::now();
+                let elements = parser.parse_line(&line);
+                let parsed = ParsedLine {
+                    elements,
+                    sound_hits: sound_hits(&triggers, &line),
+                    parse_time: started.elapsed(),
+                };
+                if results.send(parsed).is_err() {
+                    return;
+                }
+            }
+            Job::SoundTriggers(new) => triggers = new,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn highlight(pattern: &str, fast_parse: bool, sound: Option<&str>) -> HighlightPattern {
+        let mut highlight: HighlightPattern =
+            toml::from_str(&format!("pattern = '{}'", pattern)).unwrap();
+        highlight.fast_parse = fast_parse;
+        highlight.sound = sound.map(str::to_string);
+        highlight
+    }
+
+    #[test]
+    fn test_parse_worker() {
+        let mut highlights = HashMap::new();
+        highlights.insert(
+            "stun".to_string(),
+            highlight("You are stunned", false, Some("stun")),
+        );
+        highlights.insert(
+            "web".to_string(),
+            highlight("webs | entangled", true, Some("web")),
+        );
+        highlights.insert("silent".to_string(), highlight("stunned", false, None));
+        highlights.insert("broken".to_string(), highlight("(", false, Some("x")));
+
+        let mut worker =
+            ParseWorker::spawn(XmlParser::new(), SoundTrigger::compile(&highlights)).unwrap();
+        worker.submit("You are stunned!".to_string()).unwrap();
+        worker
+            .submit("You are entangled in webs.".to_string())
+            .unwrap();
+        // Parser state carries from line to line, as it did on the UI thread
+        worker
+            .submit("<pushStream id=\"thoughts\"/>".to_string())
+            .unwrap();
+        worker.submit("Hello<popStream/>".to_string()).unwrap();
+
+        let mut lines = Vec::new();
+        let started = Instant::now();
+        while lines.len() < 4 && started.elapsed() < Duration::from_secs(5) {
+            match worker.try_recv() {
+                Some(line) => lines.push(line),
+                None => std::thread::sleep(Duration::from_millis(5)),
+            }
+        }
+        assert_eq!(lines.len(), 4);
+        assert_eq!(worker.backlog(), 0);
+        assert_eq!(lines[0].sound_hits, vec!["stun".to_string()]);
+        assert_eq!(lines[1].sound_hits, vec!["web".to_string()]);
+        assert!(lines[3].sound_hits.is_empty());
+        assert!(lines[3].elements.iter().any(|e| matches!(
+            e,
+            ParsedElement::Text { content, stream, .. } if content == "Hello" && stream == "thoughts"
+        )));
+    }
+
+    #[test]
+    fn test_bell_and_flash_highlights_trigger() {
+        let mut highlights = HashMap::new();
+        let mut bell = highlight("whispers,", false, None);
+        bell.bell = true;
+        highlights.insert("whisper".to_string(), bell);
+        let mut flash = highlight("joins the adventure", false, None);
+        flash.flash = true;
+        highlights.insert("logon".to_string(), flash);
+        highlights.insert("silent".to_string(), highlight("stunned", false, None));
+
+        let triggers = SoundTrigger::compile(&highlights);
+        assert_eq!(triggers.len(), 2);
+        assert_eq!(
+            sound_hits(&triggers, "Ann whispers, \"hi\""),
+            vec!["whisper".to_string()]
+        );
+        assert_eq!(
+            sound_hits(&triggers, "* Ann joins the adventure."),
+            vec!["logon".to_string()]
+        );
+        assert!(sound_hits(&triggers, "You are stunned!").is_empty());
+    }
+}
@@ -0,0 +1,58 @@
+//! Multi-line paste guard
+//!
+//! With bracketed paste on, the terminal hands a paste over in one piece
+//! instead of typing it key by key (where every newline would send a line to
+//! the game). A single line lands in the command input; several lines are
+//! held while a dialog asks whether to send them all, send only the first,
+//! or open them in the composer for editing. `ui.confirm_multiline_paste
+//! = false` sends them all without asking.
+
+/// What to do with a held multi-line paste
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PasteChoice {
+    /// Send every line, in order
+    All,
+    /// Send the first line and drop the rest
+    First,
+    /// Open the lines in the composer instead of sending
+    Edit,
+}
+
+/// Pasted text as commands: one per non-blank line, any line ending
+pub fn split_lines(text: &str) -> Vec<String> {
+    text.split(['\n', '\r'])
+        .map(str::trim_end)
+        .filter(|line| !line.trim().is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// First line for the dialog, cut to fit
+pub fn preview(line: &str, max_chars: usize) -> String {
+    if line.chars().count() <= max_chars {
+        return line.to_string();
+    }
+    let cut: String = line.chars().take(max_chars.saturating_sub(3)).collect();
+    format!("{}...", cut)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_lines() {
+        assert_eq!(
+            split_lines("look\r\n\r\n  get gem  \rput gem in pack\n"),
+            vec!["look", "  get gem", "put gem in pack"]
+        );
+        assert_eq!(split_lines("say hi"), vec!["say hi"]);
+        assert!(split_lines("\n \r\n").is_empty());
+    }
+
+    #[test]
+    fn test_preview_truncates() {
+        assert_eq!(preview("look", 10), "look");
+        assert_eq!(preview("put my gem in my pack", 10), "put my ...");
+    }
+}
@@ -0,0 +1,162 @@
+//! Screen reader mode
+//!
+//! With `[screen_reader] enabled = true` (or `.screenreader on`) borders come
+//! off every window and progress bars/countdowns draw as plain text, so a
+//! terminal screen reader isn't reading box art. Every displayed line also
+//! lands in a linear review buffer the user can walk with the `review_*`
+//! keybind actions, and new main-window lines are spoken through TTS with
+//! repeats inside `dedupe_seconds` dropped.
+
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// One line of recent output
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReviewLine {
+    /// Stream the line arrived on ("main", "thoughts", ...)
+    pub stream: String,
+    pub text: String,
+}
+
+/// Recent output in arrival order, with a cursor for keyboard review
+#[derive(Debug)]
+pub struct ReviewBuffer {
+    lines: VecDeque<ReviewLine>,
+    capacity: usize,
+    /// Line being reviewed (None = following live output)
+    cursor: Option<usize>,
+}
+
+impl ReviewBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            lines: VecDeque::new(),
+            capacity: capacity.max(1),
+            cursor: None,
+        }
+    }
+
+    /// Add a displayed line (blank lines aren't worth reviewing)
+    pub fn push(&mut self, stream: &str, text: &str) {
+        let text = text.trim();
+        if text.is_empty() {
+            return;
+        }
+        self.lines.push_back(ReviewLine {
+            stream: stream.to_string(),
+            text: text.to_string(),
+        });
+        if self.lines.len() > self.capacity {
+            self.pop_oldest();
+        }
+    }
+
+    /// Drop the oldest line, keeping the cursor on the same line
+    fn pop_oldest(&mut self) {
+        self.lines.pop_front();
+        self.cursor = self.cursor.map(|c| c.saturating_sub(1));
+    }
+
+    /// Step back one line (starting from the newest)
+    pub fn previous(&mut self) -> Option<&ReviewLine> {
+        let last = self.lines.len().checked_sub(1)?;
+        let cursor = self.cursor.map_or(last, |c| c.saturating_sub(1));
+        self.cursor = Some(cursor);
+        self.lines.get(cursor)
+    }
+
+    /// Step forward one line (stays on the newest)
+    pub fn next(&mut self) -> Option<&ReviewLine> {
+        let last = self.lines.len().checked_sub(1)?;
+        let cursor = self.cursor.map_or(last, |c| (c + 1).min(last));
+        self.cursor = Some(cursor);
+        self.lines.get(cursor)
+    }
+
+    /// Stop reviewing and follow live output again
+    pub fn release(&mut self) {
+        self.cursor = None;
+    }
+
+    /// Line under the cursor with its 1-based position and the total
+    pub fn current(&self) -> Option<(usize, usize, &ReviewLine)> {
+        let cursor = self.cursor?;
+        self.lines
+            .get(cursor)
+            .map(|line| (cursor + 1, self.lines.len(), line))
+    }
+}
+
+/// Drops speech that repeats a recently spoken line
+#[derive(Debug, Default)]
+pub struct SpeechDedupe {
+    recent: HashMap<String, Instant>,
+}
+
+impl SpeechDedupe {
+    /// Should `text` be spoken? (false if the same words were spoken within `window`)
+    pub fn should_speak(&mut self, text: &str, window: Duration, now: Instant) -> bool {
+        if window.is_zero() {
+            return true;
+        }
+        self.recent
+            .retain(|_, spoken| now.duration_since(*spoken) < window);
+
+        // Case and spacing differences don't make a line new
+        let key = text
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase();
+        if self.recent.contains_key(&key) {
+            return false;
+        }
+        self.recent.insert(key, now);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_review_buffer() {
+        let mut review = ReviewBuffer::new(3);
+        assert!(review.previous().is_none());
+        for text in ["one", "  ", "two", "three", "four"] {
+            review.push("main", text);
+        }
+        // "one" fell off; the blank line was never kept
+        assert!(review.current().is_none());
+        assert_eq!(review.previous().unwrap().text, "four");
+        assert_eq!(review.previous().unwrap().text, "three");
+        assert_eq!(review.previous().unwrap().text, "two");
+        assert_eq!(review.previous().unwrap().text, "two");
+        assert_eq!(review.next().unwrap().text, "three");
+
+        // New output doesn't move a cursor that is reviewing
+        review.push("thoughts", "five");
+        let (position, total, line) = review.current().unwrap();
+        assert_eq!((position, total, line.text.as_str()), (1, 3, "three"));
+
+        review.release();
+        assert!(review.current().is_none());
+        assert_eq!(review.next().unwrap().stream, "thoughts");
+    }
+
+    #[test]
+    fn test_speech_dedupe() {
+        let mut dedupe = SpeechDedupe::default();
+        let window = Duration::from_secs(10);
+        let start = Instant::now();
+
+        assert!(dedupe.should_speak("You are stunned!", window, start));
+        assert!(!dedupe.should_speak("you are  STUNNED!", window, start + Duration::from_secs(2)));
+        assert!(dedupe.should_speak("You are no longer stunned.", window, start));
+        assert!(dedupe.should_speak("You are stunned!", window, start + Duration::from_secs(11)));
+
+        // A zero window turns de-duplication off
+        assert!(dedupe.should_speak("You are stunned!", Duration::ZERO, start));
+    }
+}
@@ -0,0 +1,357 @@
+//! Session statistics - experience, kills, deaths and silvers per hour
+//!
+//! Experience comes from the `nextLvlPB` progress bar ("12,345 until next
+//! level" counting down) and from EXPERIENCE output ("Experience: 1,234,567"),
+//! whichever has seen more. Kills are death lines in main for the creature
+//! you last attacked, deaths are the dead indicator turning on, and silvers
+//! come from the loot tracker.
+//!
+//! Snapshots are appended to `sessions.toml` in the character's profile with
+//! `.session save`, and on quit if anything changed since the last save, so
+//! hunts can be compared across days.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Local};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Snapshots kept in sessions.toml
+pub const SNAPSHOT_HISTORY_LEN: usize = 50;
+
+/// Main-window lines after an attack in which its target's death still counts as ours
+const KILL_WINDOW_LINES: usize = 8;
+
+/// Counters for the running session
+#[derive(Debug, Clone)]
+pub struct SessionStats {
+    pub started: DateTime<Local>,
+    /// Experience counted down on the next-level bar
+    bar_experience: u64,
+    /// First and latest total from EXPERIENCE output
+    total_experience: Option<(u64, u64)>,
+    pub kills: u32,
+    pub deaths: u32,
+    until_next: Option<u64>,
+    dead: bool,
+    /// Noun of the creature we last attacked, and main-window lines since
+    target: Option<(String, usize)>,
+    /// (experience, kills, deaths, silvers) when last saved
+    saved: Option<(u64, u32, u32, u64)>,
+    attack: Regex,
+    kill: Regex,
+    total: Regex,
+}
+
+impl SessionStats {
+    pub fn new() -> Self {
+        Self {
+            started: Local::now(),
+            bar_experience: 0,
+            total_experience: None,
+            kills: 0,
+            deaths: 0,
+            until_next: None,
+            dead: false,
+            target: None,
+            saved: None,
+            attack: Regex::new(
+                r"(?i)^You (?:swing|thrust|slash|jab|punch|kick|fire|hurl|throw|gesture|channel|feint|ambush|lunge|bash)\b.*? at (.+?)[.!]",
+            )
+            .unwrap(),
+            kill: Regex::new(r"(?i)\b(?:dies|motionless|lies still|goes still)[.!]$").unwrap(),
+            total: Regex::new(r"\b(?:Total Exp|Experience):\s*([\d,]+)").unwrap(),
+        }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        Local::now() - self.started
+    }
+
+    /// Experience gained this session
+    pub fn experience(&self) -> u64 {
+        let from_totals = self
+            .total_experience
+            .map_or(0, |(first, latest)| latest.saturating_sub(first));
+        self.bar_experience.max(from_totals)
+    }
+
+    /// Count a kill or read an EXPERIENCE total from a main-window line;
+    /// false if the line is neither
+    pub fn record_line(&mut self, text: &str) -> bool {
+        let text = text.trim();
+        if let Some(caps) = self.total.captures(text) {
+            if let Ok(total) = caps[1].replace(',', "").parse::<u64>() {
+                let first = self.total_experience.map_or(total, |(first, _)| first);
+                self.total_experience = Some((first, total));
+                return true;
+            }
+        }
+        // "You swing a broadsword at a kobold!" - the next death of a kobold is ours
+        if let Some(caps) = self.attack.captures(text) {
+            let noun = caps[1]
+                .rsplit(' ')
+                .next()
+                .unwrap_or("")
+                .trim_matches(|c: char| !c.is_alphanumeric())
+                .to_lowercase();
+            self.target = (!noun.is_empty()).then_some((noun, 0));
+            return false;
+        }
+        let Some((noun, age)) = self.target.as_mut() else {
+            return false;
+        };
+        *age += 1;
+        if *age > KILL_WINDOW_LINES {
+            self.target = None;
+            return false;
+        }
+        // "The kobold falls to the ground and dies." - not us, and not speech
+        let ours = text.to_lowercase().contains(noun.as_str());
+        if ours && !text.starts_with("You") && !text.contains('"') && self.kill.is_match(text) {
+            self.target = None;
+            self.kills += 1;
+            return true;
+        }
+        false
+    }
+
+    /// Follow the next-level bar's text ("12,345 until next level")
+    pub fn record_next_level(&mut self, text: &str) {
+        let Some(until) = text
+            .split_whitespace()
+            .next()
+            .and_then(|n| n.replace(',', "").parse::<u64>().ok())
+        else {
+            return;
+        };
+        // Going up means a new level (or a new bar); only count the drops
+        if let Some(previous) = self.until_next {
+            self.bar_experience += previous.saturating_sub(until);
+        }
+        self.until_next = Some(until);
+    }
+
+    /// Count a death when the dead indicator turns on; true if it just did
+    pub fn record_dead(&mut self, dead: bool) -> bool {
+        let died = dead && !self.dead;
+        self.dead = dead;
+        if died {
+            self.deaths += 1;
+        }
+        died
+    }
+
+    /// Per-hour rate of `count` over the session (0 for less than a minute)
+    pub fn per_hour(&self, count: u64) -> u64 {
+        match self.elapsed().num_minutes() {
+            0 => 0,
+            minutes => count * 60 / minutes as u64,
+        }
+    }
+
+    pub fn is_empty(&self, silvers: u64) -> bool {
+        self.experience() == 0 && self.kills == 0 && self.deaths == 0 && silvers == 0
+    }
+
+    /// Whether there's anything to save that the last save didn't already have
+    pub fn has_unsaved(&self, silvers: u64) -> bool {
+        !self.is_empty(silvers) && self.saved != Some(self.counts(silvers))
+    }
+
+    /// Remember what was just saved
+    pub fn mark_saved(&mut self, silvers: u64) {
+        self.saved = Some(self.counts(silvers));
+    }
+
+    fn counts(&self, silvers: u64) -> (u64, u32, u32, u64) {
+        (self.experience(), self.kills, self.deaths, silvers)
+    }
+
+    /// The session so far, for sessions.toml
+    pub fn snapshot(&self, silvers: u64, label: Option<String>) -> SessionSnapshot {
+        SessionSnapshot {
+            started: self.started.format("%Y-%m-%d %H:%M").to_string(),
+            minutes: self.elapsed().num_minutes(),
+            label,
+            experience: self.experience(),
+            kills: self.kills,
+            deaths: self.deaths,
+            silvers,
+        }
+    }
+
+    /// Start counting over
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+impl Default for SessionStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A saved session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionSnapshot {
+    /// Local start time, "2024-05-01 19:30"
+    pub started: String,
+    pub minutes: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(default)]
+    pub experience: u64,
+    #[serde(default)]
+    pub kills: u32,
+    #[serde(default)]
+    pub deaths: u32,
+    #[serde(default)]
+    pub silvers: u64,
+}
+
+impl SessionSnapshot {
+    /// "2024-05-01 19:30 (1h 05m): 12,345 exp (11,395/hr), 52 kills, 0 deaths, 4,210 silvers"
+    pub fn summary(&self) -> String {
+        use crate::data::{format_elapsed, with_commas};
+
+        let per_hour = |n: u64| {
+            if self.minutes > 0 {
+                n * 60 / self.minutes as u64
+            } else {
+                0
+            }
+        };
+        format!(
+            "{}{} ({}): {} exp ({}/hr), {} kills, {} deaths, {} silvers ({}/hr)",
+            self.started,
+            self.label
+                .as_ref()
+                .map(|l| format!(" [{}]", l))
+                .unwrap_or_default(),
+            format_elapsed(Duration::minutes(self.minutes)),
+            with_commas(self.experience),
+            with_commas(per_hour(self.experience)),
+            self.kills,
+            self.deaths,
+            with_commas(self.silvers),
+            with_commas(per_hour(self.silvers))
+        )
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SessionsFile {
+    #[serde(default)]
+    sessions: Vec<SessionSnapshot>,
+}
+
+/// Saved sessions, oldest first (empty if the file doesn't exist)
+pub fn load_snapshots(path: &Path) -> Result<Vec<SessionSnapshot>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let contents = std::fs::read_to_string(path).context("Failed to read sessions.toml")?;
+    let file: SessionsFile = toml::from_str(&contents).context("Failed to parse sessions.toml")?;
+    Ok(file.sessions)
+}
+
+/// Append a snapshot, keeping the last `SNAPSHOT_HISTORY_LEN`
+pub fn save_snapshot(path: &Path, snapshot: SessionSnapshot) -> Result<()> {
+    let mut sessions = load_snapshots(path)?;
+    sessions.push(snapshot);
+    let excess = sessions.len().saturating_sub(SNAPSHOT_HISTORY_LEN);
+    sessions.drain(..excess);
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let contents = toml::to_string_pretty(&SessionsFile { sessions })
+        .context("Failed to serialize sessions")?;
+    std::fs::write(path, contents).context("Failed to write sessions.toml")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_stats() {
+        let mut stats = SessionStats::new();
+        stats.record_line("You swing a broadsword at a kobold!");
+        assert!(stats.record_line("The kobold falls to the ground and dies."));
+        stats.record_line("You hurl a dagger at a cave troll!");
+        assert!(stats.record_line("The troll crumples to the ground motionless."));
+        assert_eq!(stats.kills, 2);
+
+        stats.record_next_level("12,000 until next level");
+        stats.record_next_level("11,850 until next level");
+        stats.record_next_level("40,000 until next level");
+        stats.record_next_level("39,900 until next level");
+        assert_eq!(stats.experience(), 250);
+
+        assert!(stats.record_line("     Experience: 1,000,000        Field Exp: 200/1,050"));
+        assert!(stats.record_line("     Experience: 1,000,400        Field Exp: 0/1,050"));
+        assert_eq!(stats.experience(), 400);
+
+        assert!(!stats.record_dead(false));
+        assert!(stats.record_dead(true));
+        assert!(!stats.record_dead(true));
+        assert_eq!(stats.deaths, 1);
+
+        let snapshot = stats.snapshot(1500, Some("trolls".to_string()));
+        assert_eq!(
+            (snapshot.experience, snapshot.kills, snapshot.silvers),
+            (400, 2, 1500)
+        );
+
+        let dir =
+            std::env::temp_dir().join(format!("two-face-sessions-test-{}", std::process::id()));
+        let path = dir.join("sessions.toml");
+        save_snapshot(&path, snapshot.clone()).unwrap();
+        save_snapshot(&path, snapshot.clone()).unwrap();
+        assert_eq!(
+            load_snapshots(&path).unwrap(),
+            vec![snapshot.clone(), snapshot]
+        );
+        std::fs::remove_dir_all(&dir).ok();
+
+        stats.reset();
+        assert!(stats.is_empty(0));
+    }
+
+    #[test]
+    fn test_kills_count_only_creatures_you_attacked() {
+        let mut stats = SessionStats::new();
+        // Someone else's kill, before we attacked anything
+        assert!(!stats.record_line("The kobold falls to the ground and dies."));
+
+        stats.record_line("You swing a broadsword at a kobold!");
+        assert!(!stats.record_line("The orc falls to the ground and dies."));
+        assert!(!stats.record_line("You feel like you could die."));
+        assert!(!stats.record_line("Bob says, \"The kobold dies.\""));
+        assert!(stats.record_line("The kobold falls to the ground and dies."));
+        // Only once per attack
+        assert!(!stats.record_line("The kobold falls to the ground and dies."));
+
+        // Too long after the attack
+        stats.record_line("You thrust a spear at a rat.");
+        for _ in 0..KILL_WINDOW_LINES {
+            stats.record_line("A breeze blows past.");
+        }
+        assert!(!stats.record_line("The rat goes still."));
+        assert_eq!(stats.kills, 1);
+    }
+
+    #[test]
+    fn test_unsaved_changes() {
+        let mut stats = SessionStats::new();
+        assert!(!stats.has_unsaved(0));
+        assert!(stats.has_unsaved(10));
+        stats.mark_saved(10);
+        assert!(!stats.has_unsaved(10));
+        assert!(stats.has_unsaved(25));
+    }
+}
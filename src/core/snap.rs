@@ -0,0 +1,175 @@
+//! Snapping for mouse window moves and resizes.
+//!
+//! An edge dragged within `SNAP_DISTANCE` cells of another window's edge (or
+//! the screen's) jumps onto it, either lining up with it or butting against
+//! it, and a guide line shows where. Otherwise, with `ui.snap_grid` set,
+//! positions round to the nearest grid line.
+
+use ratatui::layout::Rect;
+
+/// How close (in cells) an edge must come before it snaps
+pub const SNAP_DISTANCE: u16 = 2;
+
+/// A temporary alignment line drawn while dragging
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SnapGuide {
+    Column(u16),
+    Row(u16),
+}
+
+/// Snap targets for one drag
+#[derive(Clone, Debug, Default)]
+pub struct Snapper {
+    /// Left and right (exclusive) edges of the other windows and the screen
+    columns: Vec<u16>,
+    /// Top and bottom (exclusive) edges
+    rows: Vec<u16>,
+    grid: u16,
+}
+
+impl Snapper {
+    /// `others` are the windows not being dragged; `to_windows` false snaps to the grid only
+    pub fn new(others: &[Rect], screen: (u16, u16), grid: u16, to_windows: bool) -> Self {
+        let mut columns = vec![0, screen.0];
+        let mut rows = vec![0, screen.1];
+        if to_windows {
+            for rect in others {
+                columns.extend([rect.x, rect.x + rect.width]);
+                rows.extend([rect.y, rect.y + rect.height]);
+            }
+        }
+        columns.sort_unstable();
+        columns.dedup();
+        rows.sort_unstable();
+        rows.dedup();
+        Self {
+            columns,
+            rows,
+            grid,
+        }
+    }
+
+    /// Snap a window being moved; either of its edges can catch
+    pub fn snap_move(&self, x: u16, y: u16, width: u16, height: u16) -> (u16, u16, Vec<SnapGuide>) {
+        let mut guides = Vec::new();
+        let (x, column) = snap_span(&self.columns, self.grid, x, width);
+        guides.extend(column.map(SnapGuide::Column));
+        let (y, row) = snap_span(&self.rows, self.grid, y, height);
+        guides.extend(row.map(SnapGuide::Row));
+        (x, y, guides)
+    }
+
+    /// Snap the exclusive right edge of a window being resized
+    pub fn snap_right(&self, right: u16) -> (u16, Option<SnapGuide>) {
+        let (right, guide) = snap_edge(&self.columns, self.grid, right);
+        (
+            right,
+            guide.map(|col| SnapGuide::Column(col.saturating_sub(1))),
+        )
+    }
+
+    /// Snap the exclusive bottom edge of a window being resized
+    pub fn snap_bottom(&self, bottom: u16) -> (u16, Option<SnapGuide>) {
+        let (bottom, guide) = snap_edge(&self.rows, self.grid, bottom);
+        (
+            bottom,
+            guide.map(|row| SnapGuide::Row(row.saturating_sub(1))),
+        )
+    }
+}
+
+/// The target closest to `value`, if it is within snapping distance
+fn nearest(targets: &[u16], value: u16) -> Option<u16> {
+    targets
+        .iter()
+        .copied()
+        .filter(|t| t.abs_diff(value) <= SNAP_DISTANCE)
+        .min_by_key(|t| t.abs_diff(value))
+}
+
+fn round_to_grid(value: u16, grid: u16) -> u16 {
+    if grid <= 1 {
+        return value;
+    }
+    (value + grid / 2) / grid * grid
+}
+
+/// Snap a span starting at `start`: its near or far edge onto a target, else
+/// its start onto the grid. Returns the new start and the guide position.
+fn snap_span(targets: &[u16], grid: u16, start: u16, len: u16) -> (u16, Option<u16>) {
+    let near = nearest(targets, start).map(|t| (t.abs_diff(start), t, t));
+    let far = nearest(targets, start + len)
+        .filter(|t| *t >= len)
+        .map(|t| (t.abs_diff(start + len), t - len, t.saturating_sub(1)));
+    match [near, far]
+        .into_iter()
+        .flatten()
+        .min_by_key(|(distance, _, _)| *distance)
+    {
+        Some((_, snapped, guide)) => (snapped, Some(guide)),
+        None => (round_to_grid(start, grid), None),
+    }
+}
+
+/// Snap a single edge onto a target, else onto the grid
+fn snap_edge(targets: &[u16], grid: u16, edge: u16) -> (u16, Option<u16>) {
+    match nearest(targets, edge) {
+        Some(t) => (t, Some(t)),
+        None => (round_to_grid(edge, grid), None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OTHERS: [Rect; 2] = [
+        Rect {
+            x: 0,
+            y: 0,
+            width: 40,
+            height: 20,
+        },
+        Rect {
+            x: 40,
+            y: 0,
+            width: 30,
+            height: 10,
+        },
+    ];
+
+    fn snapper() -> Snapper {
+        Snapper::new(&OTHERS, (120, 40), 5, true)
+    }
+
+    #[test]
+    fn test_move_snaps_to_window_edges() {
+        // Left edge butts against the right of the first window, top lines up with it
+        assert_eq!(
+            snapper().snap_move(41, 1, 20, 8),
+            (40, 0, vec![SnapGuide::Column(40), SnapGuide::Row(0)])
+        );
+        // Bottom edge lines up with the second window's bottom (row 10 exclusive)
+        assert_eq!(
+            snapper().snap_move(80, 3, 20, 6),
+            (80, 4, vec![SnapGuide::Row(9)])
+        );
+    }
+
+    #[test]
+    fn test_move_falls_back_to_grid() {
+        assert_eq!(snapper().snap_move(83, 27, 10, 5), (85, 25, vec![]));
+    }
+
+    #[test]
+    fn test_resize_snaps_right_and_bottom() {
+        assert_eq!(snapper().snap_right(71), (70, Some(SnapGuide::Column(69))));
+        assert_eq!(snapper().snap_bottom(33), (35, None));
+    }
+
+    #[test]
+    fn test_without_grid_or_windows_nothing_snaps() {
+        let snapper = Snapper::new(&OTHERS, (120, 40), 0, false);
+        assert_eq!(snapper.snap_move(41, 13, 20, 8), (41, 13, vec![]));
+    }
+}
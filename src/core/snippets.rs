@@ -0,0 +1,127 @@
+//! Command snippets: named canned interactions with placeholders.
+//!
+//! A snippet is macro text, one command per line (`\p` pauses and the other
+//! macro escapes work too), that may contain `${name}` placeholders:
+//!
+//! ```toml
+//! [bounty-check]
+//! text = "ask ${npc} about bounty\nlook ${npc}"
+//! ```
+//!
+//! Running one fills placeholders from the arguments given, then asks for the
+//! rest in the command input, in order of first use. Snippets live in
+//! snippets.toml and are managed with `.snippets` (browser) or `.addsnippet`.
+
+/// Placeholder names in order of first use, each listed once
+pub fn placeholders(text: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut rest = text;
+    while let Some((_, name, end)) = next_placeholder(rest) {
+        if !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+        rest = &rest[end..];
+    }
+    names
+}
+
+/// Replace each `${name}` with its value; unknown names are left as they are
+pub fn fill(text: &str, values: &[(String, String)]) -> String {
+    let mut out = String::new();
+    let mut rest = text;
+    while let Some((start, name, end)) = next_placeholder(rest) {
+        match values.iter().find(|(n, _)| n == name) {
+            Some((_, value)) => {
+                out.push_str(&rest[..start]);
+                out.push_str(value);
+            }
+            None => out.push_str(&rest[..end]),
+        }
+        rest = &rest[end..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// The first well-formed `${name}` in `text`: where it starts, the name, and where it ends
+fn next_placeholder(text: &str) -> Option<(usize, &str, usize)> {
+    let mut from = 0;
+    loop {
+        let start = text[from..].find("${")? + from;
+        let name_start = start + 2;
+        let name_end = text[name_start..].find('}')? + name_start;
+        let name = &text[name_start..name_end];
+        if !name.is_empty() && !name.contains(char::is_whitespace) {
+            return Some((start, name, name_end + 1));
+        }
+        from = name_start;
+    }
+}
+
+/// A snippet being run, waiting on placeholder values
+#[derive(Debug, Clone)]
+pub struct SnippetRun {
+    pub name: String,
+    text: String,
+    names: Vec<String>,
+    values: Vec<(String, String)>,
+}
+
+impl SnippetRun {
+    pub fn new(name: &str, text: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            text: text.to_string(),
+            names: placeholders(text),
+            values: Vec::new(),
+        }
+    }
+
+    /// The placeholder asked for next (None = ready to send)
+    pub fn prompt(&self) -> Option<&str> {
+        self.names.get(self.values.len()).map(String::as_str)
+    }
+
+    /// Fill the next placeholder
+    pub fn answer(&mut self, value: &str) {
+        if let Some(name) = self.prompt().map(str::to_string) {
+            self.values.push((name, value.to_string()));
+        }
+    }
+
+    /// The macro text with every answered placeholder filled in
+    pub fn expand(&self) -> String {
+        fill(&self.text, &self.values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "ask ${npc} about bounty\nlook ${npc}\nsay ${ msg }${}$npc ${item}";
+
+    #[test]
+    fn test_placeholders_in_order_without_repeats() {
+        assert_eq!(placeholders(TEXT), vec!["npc", "item"]);
+    }
+
+    #[test]
+    fn test_run_prompts_then_expands() {
+        let mut run = SnippetRun::new("bounty-check", TEXT);
+        assert_eq!(run.prompt(), Some("npc"));
+        run.answer("guard");
+        assert_eq!(run.prompt(), Some("item"));
+        run.answer("gem");
+        assert_eq!(run.prompt(), None);
+        assert_eq!(
+            run.expand(),
+            "ask guard about bounty\nlook guard\nsay ${ msg }${}$npc gem"
+        );
+    }
+
+    #[test]
+    fn test_run_without_placeholders_never_prompts() {
+        assert_eq!(SnippetRun::new("plain", "look").prompt(), None);
+    }
+}
@@ -0,0 +1,283 @@
+//! Sounds for named game events (`sounds.toml`)
+//!
+//! Highlight sounds fire on text patterns. These fire on things the client
+//! already knows about: a line landing in the death window, a whisper, the
+//! end of roundtime, an active spell about to run out, a friend or enemy
+//! from the watch list logging on, or a dangerous creature showing up. Each event maps to a file in the shared sounds directory, with its
+//! own volume, and can also ring the terminal bell or flash a window's
+//! border for playing muted.
+
+use crate::config::SoundEventConfig;
+use crate::data::ActiveEffect;
+use crate::sound::SoundCategory;
+use std::collections::{HashMap, HashSet};
+
+/// Game events that can play a sound
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GameEvent {
+    Death,
+    Whisper,
+    RoundtimeEnd,
+    SpellExpiring,
+    FriendLogin,
+    EnemyLogin,
+    DangerousCreature,
+}
+
+impl GameEvent {
+    pub const ALL: [GameEvent; 7] = [
+        Self::Death,
+        Self::Whisper,
+        Self::RoundtimeEnd,
+        Self::SpellExpiring,
+        Self::FriendLogin,
+        Self::EnemyLogin,
+        Self::DangerousCreature,
+    ];
+
+    /// Table name in sounds.toml
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Death => "death",
+            Self::Whisper => "whisper",
+            Self::RoundtimeEnd => "roundtime_end",
+            Self::SpellExpiring => "spell_expiring",
+            Self::FriendLogin => "friend_login",
+            Self::EnemyLogin => "enemy_login",
+            Self::DangerousCreature => "dangerous_creature",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|e| e.name() == name)
+    }
+
+    /// Window whose border a `flash` entry flashes
+    pub fn window(&self) -> &'static str {
+        match self {
+            Self::Death => "death",
+            Self::FriendLogin | Self::EnemyLogin => "logons",
+            _ => "main",
+        }
+    }
+
+    /// Volume category used when the sounds.toml entry doesn't name one
+    pub fn default_category(&self) -> SoundCategory {
+        match self {
+            Self::RoundtimeEnd | Self::SpellExpiring => SoundCategory::Ui,
+            _ => SoundCategory::Alerts,
+        }
+    }
+}
+
+/// Event raised by a line of text added to `window` (`whispered`: the line has whisper-tagged text)
+pub fn line_event(
+    window: &str,
+    text: &str,
+    whispered: bool,
+    sounds: &HashMap<String, SoundEventConfig>,
+) -> Option<GameEvent> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    if window == "death" {
+        return Some(GameEvent::Death);
+    }
+    if window == "logons" {
+        // "* Zoleta joins the adventure."
+        let name = text.trim_start_matches('*').split_whitespace().next()?;
+        let friends = sounds
+            .get(GameEvent::FriendLogin.name())
+            .map(|s| s.names.as_slice())
+            .unwrap_or_default();
+        let is_friend = text.contains("joins the adventure")
+            && (friends.is_empty() || friends.iter().any(|f| f.eq_ignore_ascii_case(name)));
+        return is_friend.then_some(GameEvent::FriendLogin);
+    }
+    // "Zoleta whispers, ..." / "Zoleta whispers to you, ..." on the whisper stream or in whisper-tagged text
+    let whisper_stream = matches!(window, "whisper" | "whispers");
+    let whisper = (whisper_stream || whispered) && crate::core::afk::whisperer(text).is_some();
+    whisper.then_some(GameEvent::Whisper)
+}
+
+/// Parse an active spell's remaining time ("1:02:03", "02:03" or "45")
+pub fn parse_remaining(time: &str) -> Option<u32> {
+    time.trim().split(':').try_fold(0u32, |total, part| {
+        Some(total * 60 + part.trim().parse::<u32>().ok()?)
+    })
+}
+
+/// Watches timers for edge-triggered events
+#[derive(Debug, Default)]
+pub struct SoundEventTracker {
+    /// Roundtime end that hasn't been announced yet
+    roundtime_pending: Option<i64>,
+    /// Spells already warned about (cleared when they're refreshed)
+    warned_spells: HashSet<String>,
+}
+
+impl SoundEventTracker {
+    /// Timer events due at `now` (Unix seconds)
+    pub fn poll(
+        &mut self,
+        now: i64,
+        roundtime_end: Option<i64>,
+        spells: &[ActiveEffect],
+        warn_seconds: u32,
+    ) -> Vec<GameEvent> {
+        let mut events = Vec::new();
+
+        // Only a roundtime seen while it was still running counts
+        match roundtime_end {
+            Some(end) if end > now => self.roundtime_pending = Some(end),
+            _ => {
+                if self.roundtime_pending.take().is_some() {
+                    events.push(GameEvent::RoundtimeEnd);
+                }
+            }
+        }
+
+        let mut expiring = false;
+        for spell in spells {
+            let Some(remaining) = parse_remaining(&spell.time) else {
+                continue;
+            };
+            if remaining > warn_seconds {
+                self.warned_spells.remove(&spell.id);
+            } else if remaining > 0 && self.warned_spells.insert(spell.id.clone()) {
+                expiring = true;
+            }
+        }
+        self.warned_spells
+            .retain(|id| spells.iter().any(|s| &s.id == id));
+        if expiring {
+            events.push(GameEvent::SpellExpiring);
+        }
+
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spell(id: &str, time: &str) -> ActiveEffect {
+        ActiveEffect {
+            id: id.to_string(),
+            text: String::new(),
+            value: 0,
+            time: time.to_string(),
+            bar_color: None,
+            text_color: None,
+        }
+    }
+
+    #[test]
+    fn test_line_events() {
+        let sounds = HashMap::new();
+        assert_eq!(
+            line_event("death", " * Bob was slain.", false, &sounds),
+            Some(GameEvent::Death)
+        );
+        assert_eq!(
+            line_event("main", "Bob whispers, \"psst\"", true, &sounds),
+            Some(GameEvent::Whisper)
+        );
+        assert_eq!(
+            line_event("whisper", "Bob whispers to you, \"psst\"", false, &sounds),
+            Some(GameEvent::Whisper)
+        );
+        assert_eq!(
+            line_event("main", "You whisper to Bob, \"hi\"", true, &sounds),
+            None
+        );
+    }
+
+    #[test]
+    fn test_quoted_whisper_is_not_a_whisper() {
+        let sounds = HashMap::new();
+        // Untagged speech that merely quotes a whisper
+        assert_eq!(
+            line_event("main", "Bob whispers, \"psst\"", false, &sounds),
+            None
+        );
+        assert_eq!(
+            line_event("speech", "Ann says, \"Bob whispers, psst\"", true, &sounds),
+            None
+        );
+    }
+
+    #[test]
+    fn test_friend_login_names() {
+        let mut sounds = HashMap::new();
+        assert_eq!(
+            line_event("logons", "* Bob joins the adventure.", false, &sounds),
+            Some(GameEvent::FriendLogin)
+        );
+        sounds.insert(
+            "friend_login".to_string(),
+            SoundEventConfig {
+                names: vec!["alice".to_string()],
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            line_event("logons", "* Bob joins the adventure.", false, &sounds),
+            None
+        );
+        assert_eq!(
+            line_event("logons", "* Alice joins the adventure.", false, &sounds),
+            Some(GameEvent::FriendLogin)
+        );
+    }
+
+    #[test]
+    fn test_sound_categories() {
+        assert_eq!(GameEvent::Whisper.default_category(), SoundCategory::Alerts);
+        assert_eq!(
+            GameEvent::RoundtimeEnd.default_category(),
+            SoundCategory::Ui
+        );
+        assert_eq!(
+            SoundCategory::from_name(" Ambience"),
+            Some(SoundCategory::Ambience)
+        );
+    }
+
+    #[test]
+    fn test_parse_remaining() {
+        assert_eq!(parse_remaining("1:02:03"), Some(3723));
+        assert_eq!(parse_remaining("00:45"), Some(45));
+        assert_eq!(parse_remaining("Indefinite"), None);
+    }
+
+    #[test]
+    fn test_roundtime_end_fires_once() {
+        let mut tracker = SoundEventTracker::default();
+        assert!(tracker.poll(100, Some(105), &[], 30).is_empty());
+        assert_eq!(
+            tracker.poll(105, Some(105), &[], 30),
+            vec![GameEvent::RoundtimeEnd]
+        );
+        assert!(tracker.poll(106, Some(105), &[], 30).is_empty());
+    }
+
+    #[test]
+    fn test_spell_expiring_warns_once_per_cast() {
+        let mut tracker = SoundEventTracker::default();
+        let spells = [spell("401", "00:25"), spell("101", "10:00")];
+        assert_eq!(
+            tracker.poll(200, None, &spells, 30),
+            vec![GameEvent::SpellExpiring]
+        );
+        assert!(tracker.poll(201, None, &spells, 30).is_empty());
+        // Recast: warns again next time it runs low
+        tracker.poll(202, None, &[spell("401", "05:00")], 30);
+        assert_eq!(
+            tracker.poll(203, None, &[spell("401", "00:10")], 30),
+            vec![GameEvent::SpellExpiring]
+        );
+    }
+}
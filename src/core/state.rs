@@ -3,7 +3,11 @@
 //! Tracks the current state of the game session: connection status,
 //! character info, room state, inventory, etc.
 
-use std::collections::HashMap;
+use chrono::{DateTime, Local};
+use std::collections::{HashMap, VecDeque};
+
+/// How many prepared spells `spell_history` remembers
+pub const SPELL_HISTORY_LEN: usize = 25;
 
 /// Game session state
 #[derive(Clone, Debug)]
@@ -32,6 +36,12 @@ pub struct GameState {
     /// Current spell being prepared
     pub spell: Option<String>,
 
+    /// Recently prepared spells, oldest first
+    pub spell_history: VecDeque<SpellRecord>,
+
+    /// Last command sent to the game (used to tell a cast from a release)
+    pub last_command: Option<String>,
+
     /// Active game streams (tags like "inv", "assess", etc.)
     pub active_streams: HashMap<String, bool>,
 
@@ -41,6 +51,9 @@ pub struct GameState {
     /// Vitals (health, mana, etc.)
     pub vitals: Vitals,
 
+    /// Latest reading of every progress bar the game sent, by bar id
+    pub bars: HashMap<String, BarReading>,
+
     /// Inventory items
     pub inventory: Vec<String>,
 
@@ -60,6 +73,70 @@ pub struct GameState {
     pub last_prompt: String,
 }
 
+/// How a prepared spell left the spell hand
+#[derive(Clone, Debug, PartialEq)]
+pub enum SpellOutcome {
+    /// Still in hand
+    Prepared,
+    Cast,
+    Released,
+    /// Cleared by something we didn't send (a script, another prep, logout)
+    Cleared,
+}
+
+impl SpellOutcome {
+    /// Classify by the command that was sent before the hand emptied
+    fn from_command(command: Option<&str>) -> Self {
+        let verb = command
+            .and_then(|c| c.split_whitespace().next())
+            .unwrap_or("")
+            .to_lowercase();
+        match verb.as_str() {
+            "cast" | "incant" | "channel" | "evoke" | "ch" | "inc" | "ev" => Self::Cast,
+            "release" | "rel" => Self::Released,
+            _ => Self::Cleared,
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Prepared => "prepared",
+            Self::Cast => "cast",
+            Self::Released => "released",
+            Self::Cleared => "cleared",
+        }
+    }
+}
+
+/// One spell that passed through the spell hand
+#[derive(Clone, Debug)]
+pub struct SpellRecord {
+    pub spell: String,
+    pub prepared_at: DateTime<Local>,
+    pub ended_at: Option<DateTime<Local>>,
+    pub outcome: SpellOutcome,
+    /// Command that emptied the hand, when we sent one
+    pub ended_by: Option<String>,
+}
+
+impl SpellRecord {
+    /// One-line summary for `.lastspells`
+    pub fn summary(&self) -> String {
+        let mut text = format!("{}  {}", self.prepared_at.format("%H:%M:%S"), self.spell);
+        match self.ended_at {
+            None => text.push_str("  (in hand)"),
+            Some(ended_at) => {
+                let held = (ended_at - self.prepared_at).num_seconds().max(0);
+                text.push_str(&format!("  {} after {}s", self.outcome.label(), held));
+                if let Some(ref command) = self.ended_by {
+                    text.push_str(&format!(" ({})", command));
+                }
+            }
+        }
+        text
+    }
+}
+
 /// Player status information
 #[derive(Clone, Debug, Default)]
 pub struct StatusInfo {
@@ -76,6 +153,15 @@ pub struct StatusInfo {
     pub dead: bool,
 }
 
+/// One progress bar as the game last sent it
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BarReading {
+    pub value: u32,
+    pub max: u32,
+    /// The game's label ("defensive (100%)", "clear as a bell", "None")
+    pub text: String,
+}
+
 /// Player vitals
 #[derive(Clone, Debug)]
 pub struct Vitals {
@@ -96,9 +182,12 @@ impl GameState {
             roundtime_end: None,
             casttime_end: None,
             spell: None,
+            spell_history: VecDeque::new(),
+            last_command: None,
             active_streams: HashMap::new(),
             status: StatusInfo::default(),
             vitals: Vitals::default(),
+            bars: HashMap::new(),
             inventory: Vec::new(),
             left_hand: None,
             right_hand: None,
@@ -108,6 +197,58 @@ impl GameState {
         }
     }
 
+    /// Record a spell hand change in `spell_history`
+    ///
+    /// Returns a line describing what happened, for the spell history window.
+    /// Call before `spell` is updated to the new value.
+    pub fn track_spell_hand(&mut self, spell: &str) -> Option<String> {
+        // The game reports an empty hand as "None"
+        let spell = Some(spell).filter(|s| !s.is_empty() && *s != "None");
+        let held = self
+            .spell
+            .as_deref()
+            .filter(|s| !s.is_empty() && *s != "None");
+        if spell == held {
+            return None;
+        }
+
+        let now = Local::now();
+        let mut events = Vec::new();
+
+        if let Some(record) = self
+            .spell_history
+            .back_mut()
+            .filter(|r| r.outcome == SpellOutcome::Prepared)
+        {
+            record.outcome = if spell.is_some() {
+                SpellOutcome::Cleared // Replaced by another prep
+            } else {
+                SpellOutcome::from_command(self.last_command.as_deref())
+            };
+            if record.outcome != SpellOutcome::Cleared {
+                record.ended_by = self.last_command.clone();
+            }
+            record.ended_at = Some(now);
+            events.push(format!("{} {}", record.outcome.label(), record.spell));
+        }
+
+        if let Some(spell) = spell {
+            self.spell_history.push_back(SpellRecord {
+                spell: spell.to_string(),
+                prepared_at: now,
+                ended_at: None,
+                outcome: SpellOutcome::Prepared,
+                ended_by: None,
+            });
+            while self.spell_history.len() > SPELL_HISTORY_LEN {
+                self.spell_history.pop_front();
+            }
+            events.push(format!("prepared {}", spell));
+        }
+
+        (!events.is_empty()).then(|| format!("{}  {}", now.format("%H:%M:%S"), events.join("; ")))
+    }
+
     /// Check if currently in roundtime
     pub fn in_roundtime(&self) -> bool {
         if let Some(end_time) = self.roundtime_end {
@@ -121,6 +262,61 @@ impl GameState {
         }
     }
 
+    /// Seconds of roundtime left at `now` (unix seconds; 0 when none)
+    pub fn roundtime_left(&self, now: i64) -> u32 {
+        self.roundtime_end
+            .map_or(0, |end| (end - now).max(0) as u32)
+    }
+
+    /// Seconds of casttime left at `now` (unix seconds; 0 when none)
+    pub fn casttime_left(&self, now: i64) -> u32 {
+        self.casttime_end.map_or(0, |end| (end - now).max(0) as u32)
+    }
+
+    /// Value of a `{field}` in a status line template at `now` (unix seconds)
+    ///
+    /// `rt`/`ct` are seconds left, `room`, `spell`, `left`, `right` and
+    /// `character` are names, and anything else is a progress bar: its value
+    /// for the vitals, otherwise its label without a trailing "(...)".
+    /// `_max` and `_pct` suffixes give a bar's maximum and percentage.
+    /// `stance`, `encumbrance` and `mind` stand for the game's bar ids.
+    pub fn status_field(&self, field: &str, now: i64) -> Option<String> {
+        let name = |value: &Option<String>| Some(value.clone().unwrap_or_default());
+        match field {
+            "rt" => return Some(self.roundtime_left(now).to_string()),
+            "ct" => return Some(self.casttime_left(now).to_string()),
+            "room" => return name(&self.room_name),
+            "spell" => return name(&self.spell.clone().filter(|s| s != "None")),
+            "left" => return name(&self.left_hand),
+            "right" => return name(&self.right_hand),
+            "character" => return name(&self.character_name),
+            _ => {}
+        }
+
+        let (bar, part) = match field.rsplit_once('_') {
+            Some((bar, part @ ("max" | "pct"))) => (bar, part),
+            _ => (field, ""),
+        };
+        let id = match bar {
+            "stance" => "pbarStance",
+            "encumbrance" => "encumlevel",
+            "mind" => "mindState",
+            other => other,
+        };
+        let reading = self.bars.get(id)?;
+        Some(match part {
+            "max" => reading.max.to_string(),
+            "pct" => (reading.value * 100 / reading.max.max(1)).to_string(),
+            _ if matches!(id, "health" | "mana" | "stamina" | "spirit") => {
+                reading.value.to_string()
+            }
+            _ => match reading.text.rsplit_once(" (") {
+                Some((label, _)) if reading.text.ends_with(')') => label.to_string(),
+                _ => reading.text.clone(),
+            },
+        })
+    }
+
     /// Check if currently in casttime
     pub fn in_casttime(&self) -> bool {
         if let Some(end_time) = self.casttime_end {
@@ -151,3 +347,78 @@ impl Default for Vitals {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_track_spell_hand() {
+        let mut state = GameState::new();
+        let set_hand = |state: &mut GameState, spell: &str| {
+            let event = state.track_spell_hand(spell);
+            state.spell = Some(spell.to_string());
+            event
+        };
+
+        assert!(set_hand(&mut state, "None").is_none());
+        assert!(set_hand(&mut state, "Spirit Warding I")
+            .unwrap()
+            .ends_with("prepared Spirit Warding I"));
+        state.last_command = Some("cast at troll".to_string());
+        assert!(set_hand(&mut state, "None")
+            .unwrap()
+            .ends_with("cast Spirit Warding I"));
+
+        set_hand(&mut state, "Minor Sanctuary");
+        state.last_command = Some("release".to_string());
+        set_hand(&mut state, "None");
+
+        let outcomes: Vec<_> = state
+            .spell_history
+            .iter()
+            .map(|r| r.outcome.clone())
+            .collect();
+        assert_eq!(outcomes, vec![SpellOutcome::Cast, SpellOutcome::Released]);
+        assert_eq!(
+            state.spell_history[0].ended_by.as_deref(),
+            Some("cast at troll")
+        );
+    }
+
+    #[test]
+    fn test_status_field() {
+        let mut state = GameState::new();
+        let bar = |value, max, text: &str| BarReading {
+            value,
+            max,
+            text: text.to_string(),
+        };
+        state
+            .bars
+            .insert("mana".to_string(), bar(40, 160, "mana 40/160"));
+        state
+            .bars
+            .insert("pbarStance".to_string(), bar(100, 100, "defensive (100%)"));
+        state
+            .bars
+            .insert("encumlevel".to_string(), bar(0, 100, "None"));
+        state.roundtime_end = Some(1_000_005);
+
+        assert_eq!(state.status_field("rt", 1_000_002).as_deref(), Some("3"));
+        assert_eq!(state.status_field("ct", 1_000_002).as_deref(), Some("0"));
+        assert_eq!(state.status_field("mana", 0).as_deref(), Some("40"));
+        assert_eq!(state.status_field("mana_max", 0).as_deref(), Some("160"));
+        assert_eq!(state.status_field("mana_pct", 0).as_deref(), Some("25"));
+        assert_eq!(
+            state.status_field("stance", 0).as_deref(),
+            Some("defensive")
+        );
+        assert_eq!(
+            state.status_field("encumbrance", 0).as_deref(),
+            Some("None")
+        );
+        assert_eq!(state.status_field("spell", 0).as_deref(), Some(""));
+        assert_eq!(state.status_field("health", 0), None);
+    }
+}
@@ -0,0 +1,322 @@
+//! Stream subscriptions and per-window line filters
+//!
+//! Text windows list the streams they show (`streams = ["main"]`). Any number
+//! of windows can list the same stream, so a line can fan out to several
+//! windows: `main` plus a "combat only" mirror of main, each with its own
+//! scrollback and scroll position. A window can also narrow what it shows
+//! with `include` / `exclude` regexes matched against the plain line text.
+//!
+//! `[[redirects]]` rules in config.toml work the other way round: a line of
+//! any stream matching the rule's pattern is copied into the rule's window,
+//! or moved there (taken out of the windows it would otherwise land in).
+
+use crate::config::{Layout, RedirectRule, WindowDef};
+use regex::Regex;
+
+/// Filters for one window (empty include = everything)
+#[derive(Debug, Clone, Default)]
+struct LineFilter {
+    include: Vec<Regex>,
+    exclude: Vec<Regex>,
+}
+
+impl LineFilter {
+    fn new(window: &str, include: &[String], exclude: &[String]) -> Self {
+        let compile = |patterns: &[String]| -> Vec<Regex> {
+            patterns
+                .iter()
+                .filter_map(|p| match Regex::new(p) {
+                    Ok(regex) => Some(regex),
+                    Err(e) => {
+                        tracing::warn!("Invalid filter '{}' on window '{}': {}", p, window, e);
+                        None
+                    }
+                })
+                .collect()
+        };
+        Self {
+            include: compile(include),
+            exclude: compile(exclude),
+        }
+    }
+
+    fn accepts(&self, text: &str) -> bool {
+        (self.include.is_empty() || self.include.iter().any(|r| r.is_match(text)))
+            && !self.exclude.iter().any(|r| r.is_match(text))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct WindowRoute {
+    window: String,
+    streams: Vec<String>,
+    include: Vec<String>,
+    exclude: Vec<String>,
+    filter: LineFilter,
+}
+
+/// A `[[redirects]]` rule, compiled
+#[derive(Debug, Clone)]
+struct Redirect {
+    pattern: Regex,
+    window: String,
+    streams: Vec<String>,
+    keep: bool, // "copy": the line stays in its own windows too
+}
+
+/// Which text windows subscribe to which streams
+#[derive(Debug, Default)]
+pub struct StreamRouter {
+    routes: Vec<WindowRoute>,
+    redirects: Vec<Redirect>,
+}
+
+impl StreamRouter {
+    /// Pick up the layout's subscriptions (filters are only recompiled when they change)
+    pub fn update(&mut self, layout: &Layout) {
+        let text_windows = || {
+            layout.windows.iter().filter_map(|w| match w {
+                WindowDef::Text { base, data } => Some((base, data)),
+                _ => None,
+            })
+        };
+
+        let unchanged = self.routes.len() == text_windows().count()
+            && self
+                .routes
+                .iter()
+                .zip(text_windows())
+                .all(|(route, (base, data))| {
+                    route.window == base.name
+                        && route.streams == data.streams
+                        && route.include == data.include
+                        && route.exclude == data.exclude
+                });
+        if unchanged {
+            return;
+        }
+
+        self.routes = text_windows()
+            .map(|(base, data)| WindowRoute {
+                window: base.name.clone(),
+                streams: data.streams.clone(),
+                include: data.include.clone(),
+                exclude: data.exclude.clone(),
+                filter: LineFilter::new(&base.name, &data.include, &data.exclude),
+            })
+            .collect();
+        tracing::debug!(
+            "Stream routes rebuilt for {} text window(s)",
+            self.routes.len()
+        );
+    }
+
+    /// Windows that list `stream` in their `streams`
+    pub fn subscribers<'a>(&'a self, stream: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.routes
+            .iter()
+            .filter(move |r| r.streams.iter().any(|s| s == stream))
+            .map(|r| r.window.as_str())
+    }
+
+    /// Does `window`'s filter let this line through? (windows without one take everything)
+    pub fn accepts(&self, window: &str, text: &str) -> bool {
+        self.routes
+            .iter()
+            .find(|r| r.window == window)
+            .is_none_or(|r| r.filter.accepts(text))
+    }
+
+    /// Compile the `[[redirects]]` rules, filling in `{character}`
+    ///
+    /// Returns why each skipped rule was skipped: a pattern that doesn't
+    /// compile, or one naming `{character}` when no character is set.
+    pub fn set_redirects(
+        &mut self,
+        rules: &[RedirectRule],
+        character: Option<&str>,
+    ) -> Vec<String> {
+        let mut skipped = Vec::new();
+        self.redirects = rules
+            .iter()
+            .filter_map(|rule| {
+                let pattern = match character {
+                    Some(name) => rule.pattern.replace("{character}", &regex::escape(name)),
+                    None if rule.pattern.contains("{character}") => {
+                        skipped.push(format!(
+                            "'{}' (to {}) uses {{character}} but no character is set",
+                            rule.pattern, rule.window
+                        ));
+                        return None;
+                    }
+                    None => rule.pattern.clone(),
+                };
+                if !matches!(rule.mode.as_str(), "copy" | "move") {
+                    tracing::warn!(
+                        "Redirect to '{}' has unknown mode '{}', copying",
+                        rule.window,
+                        rule.mode
+                    );
+                }
+                match Regex::new(&pattern) {
+                    Ok(regex) => Some(Redirect {
+                        pattern: regex,
+                        window: rule.window.clone(),
+                        streams: rule.streams.clone(),
+                        keep: rule.mode != "move",
+                    }),
+                    Err(e) => {
+                        skipped.push(format!(
+                            "'{}' (to {}) is not a valid regex: {}",
+                            rule.pattern, rule.window, e
+                        ));
+                        None
+                    }
+                }
+            })
+            .collect();
+        for reason in &skipped {
+            tracing::warn!("Redirect skipped: {}", reason);
+        }
+        skipped
+    }
+
+    /// Number of redirect rules in use
+    pub fn redirect_count(&self) -> usize {
+        self.redirects.len()
+    }
+
+    /// Windows this line is redirected to, and whether it also stays in its own windows
+    pub fn redirects<'a>(&'a self, stream: &str, text: &str) -> (Vec<&'a str>, bool) {
+        let mut windows = Vec::new();
+        let mut keep = true;
+        for redirect in &self.redirects {
+            if (redirect.streams.is_empty() || redirect.streams.iter().any(|s| s == stream))
+                && redirect.pattern.is_match(text)
+            {
+                if !windows.contains(&redirect.window.as_str()) {
+                    windows.push(redirect.window.as_str());
+                }
+                keep &= redirect.keep;
+            }
+        }
+        (windows, keep)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stream_fan_out() {
+        let layout: Layout = toml::from_str(
+            r#"
+            [[windows]]
+            widget_type = "text"
+            name = "main"
+            streams = ["main"]
+
+            [[windows]]
+            widget_type = "text"
+            name = "combat"
+            streams = ["main"]
+            include = ["(?i)swing", "(?i)strike"]
+            exclude = ["misses"]
+
+            [[windows]]
+            widget_type = "text"
+            name = "chat"
+            streams = ["thoughts", "speech"]
+            "#,
+        )
+        .unwrap();
+
+        let mut router = StreamRouter::default();
+        router.update(&layout);
+        assert_eq!(
+            router.subscribers("main").collect::<Vec<_>>(),
+            vec!["main", "combat"]
+        );
+        assert_eq!(
+            router.subscribers("speech").collect::<Vec<_>>(),
+            vec!["chat"]
+        );
+        assert_eq!(router.subscribers("logons").count(), 0);
+
+        assert!(router.accepts("main", "You swing a broadsword at a kobold!"));
+        assert!(router.accepts("combat", "You swing a broadsword at a kobold!"));
+        assert!(!router.accepts("combat", "You swing a broadsword and miss. It misses."));
+        assert!(!router.accepts("combat", "Obvious paths: north"));
+        assert!(router.accepts("unknown", "anything"));
+    }
+
+    #[test]
+    fn test_redirects() {
+        let rule = |pattern: &str, window: &str, mode: &str, streams: &[&str]| RedirectRule {
+            pattern: pattern.to_string(),
+            window: window.to_string(),
+            mode: mode.to_string(),
+            streams: streams.iter().map(|s| s.to_string()).collect(),
+        };
+        let mut router = StreamRouter::default();
+        let skipped = router.set_redirects(
+            &[
+                rule(r"\b{character}\b", "mentions", "copy", &[]),
+                rule("^\\[Merchant\\]", "trade", "move", &["thoughts"]),
+                rule("(", "broken", "copy", &[]),
+            ],
+            Some("Nisugi"),
+        );
+        assert_eq!(skipped.len(), 1);
+        assert!(skipped[0].contains("broken"));
+
+        assert_eq!(
+            router.redirects("speech", "Ann says, \"Hi Nisugi.\""),
+            (vec!["mentions"], true)
+        );
+        assert_eq!(
+            router.redirects("main", "Nisugiland is north."),
+            (vec![], true)
+        );
+        assert_eq!(
+            router.redirects("thoughts", "[Merchant] Bob: selling"),
+            (vec!["trade"], false)
+        );
+        assert_eq!(
+            router.redirects("main", "[Merchant] Bob: selling"),
+            (vec![], true)
+        );
+        assert_eq!(
+            router.redirects("thoughts", "[Merchant] Nisugi: buying"),
+            (vec!["mentions", "trade"], false)
+        );
+    }
+    #[test]
+    fn test_character_redirects_skipped_without_character() {
+        let rules = [
+            RedirectRule {
+                pattern: r"\b{character}\b".to_string(),
+                window: "mentions".to_string(),
+                mode: "copy".to_string(),
+                streams: Vec::new(),
+            },
+            RedirectRule {
+                pattern: "^\\[Merchant\\]".to_string(),
+                window: "trade".to_string(),
+                mode: "copy".to_string(),
+                streams: Vec::new(),
+            },
+        ];
+        let mut router = StreamRouter::default();
+        let skipped = router.set_redirects(&rules, None);
+        assert_eq!(skipped.len(), 1);
+        assert!(skipped[0].contains("no character is set"));
+        assert_eq!(router.redirect_count(), 1);
+        assert_eq!(router.redirects("main", "Anything at all"), (vec![], true));
+
+        // Recompiling once the character is known picks the rule back up
+        assert!(router.set_redirects(&rules, Some("Nisugi")).is_empty());
+        assert_eq!(router.redirect_count(), 2);
+    }
+}
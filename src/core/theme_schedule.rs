@@ -0,0 +1,240 @@
+//! Automatic day/night theme switching
+//!
+//! `[theme_schedule]` picks a day and a night theme, and decides which one
+//! applies either from a daily schedule or from the OS light/dark preference.
+//! Only transitions switch the theme, so a theme chosen by hand stays until
+//! the next change of period.
+
+use crate::config::ThemeScheduleConfig;
+use anyhow::{anyhow, Result};
+use chrono::NaiveTime;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::time::{Duration, Instant};
+
+/// How often the schedule (or OS preference) is re-checked
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    Day,
+    Night,
+}
+
+impl Period {
+    fn theme<'a>(&self, config: &'a ThemeScheduleConfig) -> &'a str {
+        match self {
+            Self::Day => &config.day_theme,
+            Self::Night => &config.night_theme,
+        }
+    }
+}
+
+/// Parse "HH:MM" (24-hour)
+pub fn parse_clock(value: &str) -> Result<NaiveTime> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M")
+        .map_err(|_| anyhow!("'{}' is not a time of day (expected HH:MM)", value))
+}
+
+/// Which period `time` falls in; the night may wrap past midnight
+pub fn period_at(config: &ThemeScheduleConfig, time: NaiveTime) -> Result<Period> {
+    let day_start = parse_clock(&config.day_start)?;
+    let night_start = parse_clock(&config.night_start)?;
+    let is_day = if day_start <= night_start {
+        time >= day_start && time < night_start
+    } else {
+        time >= day_start || time < night_start
+    };
+    Ok(if is_day { Period::Day } else { Period::Night })
+}
+
+/// Ask the OS whether dark mode is on (None = unknown)
+///
+/// Shells out to the platform's settings tool, so call it off the UI thread.
+pub fn os_prefers_dark() -> Option<bool> {
+    use std::process::Command;
+
+    let output = |cmd: &mut Command| {
+        cmd.output().ok().map(|o| {
+            (
+                o.status.success(),
+                String::from_utf8_lossy(&o.stdout).to_lowercase(),
+            )
+        })
+    };
+
+    if cfg!(target_os = "macos") {
+        // The key only exists while dark mode is on
+        let (ok, stdout) =
+            output(Command::new("defaults").args(["read", "-g", "AppleInterfaceStyle"]))?;
+        return Some(ok && stdout.contains("dark"));
+    }
+    if cfg!(windows) {
+        let (ok, stdout) = output(Command::new("reg").args([
+            "query",
+            r"HKCU\Software\Microsoft\Windows\CurrentVersion\Themes\Personalize",
+            "/v",
+            "AppsUseLightTheme",
+        ]))?;
+        return ok.then(|| stdout.contains("0x0"));
+    }
+
+    // GNOME 42+ (also honored by most other desktops via the portal setting)
+    let (ok, stdout) = output(Command::new("gsettings").args([
+        "get",
+        "org.gnome.desktop.interface",
+        "color-scheme",
+    ]))?;
+    if ok && !stdout.contains("default") {
+        return Some(stdout.contains("dark"));
+    }
+    // Older desktops only name the GTK theme
+    let (ok, stdout) = output(Command::new("gsettings").args([
+        "get",
+        "org.gnome.desktop.interface",
+        "gtk-theme",
+    ]))?;
+    ok.then(|| stdout.contains("dark"))
+}
+
+/// Tracks the current period and reports when the theme should change
+#[derive(Default)]
+pub struct ThemeScheduler {
+    next_check: Option<Instant>,
+    period: Option<Period>,
+    /// OS preference lookup running in the background
+    os_query: Option<Receiver<Option<bool>>>,
+    last_error: Option<String>,
+}
+
+impl ThemeScheduler {
+    /// Forget the current period so the next poll applies the schedule again
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Theme to switch to, if the period changed since the last poll
+    pub fn poll(&mut self, config: &ThemeScheduleConfig, now: Instant) -> Option<String> {
+        if !config.enabled {
+            return None;
+        }
+
+        let period = if config.mode == "os" {
+            self.poll_os(now)?
+        } else {
+            if self.next_check.is_some_and(|next| now < next) {
+                return None;
+            }
+            self.next_check = Some(now + CHECK_INTERVAL);
+            match period_at(config, chrono::Local::now().time()) {
+                Ok(period) => period,
+                Err(e) => {
+                    let message = e.to_string();
+                    if self.last_error.as_ref() != Some(&message) {
+                        tracing::warn!("Theme schedule: {}", message);
+                        self.last_error = Some(message);
+                    }
+                    return None;
+                }
+            }
+        };
+
+        if self.period == Some(period) {
+            return None;
+        }
+        self.period = Some(period);
+        Some(period.theme(config).to_string())
+    }
+
+    fn poll_os(&mut self, now: Instant) -> Option<Period> {
+        if let Some(rx) = &self.os_query {
+            return match rx.try_recv() {
+                Ok(dark) => {
+                    self.os_query = None;
+                    dark.map(|dark| if dark { Period::Night } else { Period::Day })
+                }
+                Err(TryRecvError::Empty) => None,
+                Err(TryRecvError::Disconnected) => {
+                    self.os_query = None;
+                    None
+                }
+            };
+        }
+
+        if self.next_check.is_some_and(|next| now < next) {
+            return None;
+        }
+        self.next_check = Some(now + CHECK_INTERVAL);
+        let (tx, rx) = mpsc::channel();
+        let spawned = std::thread::Builder::new()
+            .name("os-theme-query".to_string())
+            .spawn(move || {
+                let _ = tx.send(os_prefers_dark());
+            });
+        if spawned.is_ok() {
+            self.os_query = Some(rx);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule(day_start: &str, night_start: &str) -> ThemeScheduleConfig {
+        ThemeScheduleConfig {
+            enabled: true,
+            day_start: day_start.to_string(),
+            night_start: night_start.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn at(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn test_schedule_periods() {
+        let config = schedule("07:00", "19:30");
+        assert_eq!(period_at(&config, at(7, 0)).unwrap(), Period::Day);
+        assert_eq!(period_at(&config, at(19, 29)).unwrap(), Period::Day);
+        assert_eq!(period_at(&config, at(19, 30)).unwrap(), Period::Night);
+        assert_eq!(period_at(&config, at(3, 0)).unwrap(), Period::Night);
+    }
+
+    #[test]
+    fn test_schedule_day_can_span_midnight() {
+        // Night shift: "day" theme from evening until morning
+        let config = schedule("22:00", "06:00");
+        assert_eq!(period_at(&config, at(23, 0)).unwrap(), Period::Day);
+        assert_eq!(period_at(&config, at(12, 0)).unwrap(), Period::Night);
+    }
+
+    #[test]
+    fn test_schedule_rejects_bad_times() {
+        assert!(period_at(&schedule("22:00", "7pm"), at(12, 0)).is_err());
+    }
+
+    #[test]
+    fn test_scheduler_reports_each_period_once() {
+        let config = schedule("22:00", "06:00");
+        let mut scheduler = ThemeScheduler::default();
+        let now = Instant::now();
+        assert!(scheduler.poll(&config, now).is_some());
+        assert!(scheduler.poll(&config, now + CHECK_INTERVAL * 2).is_none());
+        scheduler.reset();
+        assert!(scheduler.poll(&config, now).is_some());
+    }
+
+    #[test]
+    fn test_scheduler_off_when_disabled() {
+        let config = ThemeScheduleConfig {
+            enabled: false,
+            ..schedule("22:00", "06:00")
+        };
+        assert!(ThemeScheduler::default()
+            .poll(&config, Instant::now())
+            .is_none());
+    }
+}
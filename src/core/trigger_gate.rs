@@ -0,0 +1,144 @@
+//! Firing limits for highlight sounds
+//!
+//! A highlight with a sound can narrow when that sound plays: a cooldown of
+//! its own (on top of the per-file `sound.cooldown_ms`), a cap on how many
+//! times it fires per session (`max_fires = 1` for once-per-session alerts),
+//! only while the terminal is in the background, and not during quiet hours.
+
+use crate::config::HighlightPattern;
+use crate::core::theme_schedule::parse_clock;
+use anyhow::{anyhow, Result};
+use chrono::NaiveTime;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Parse quiet hours ("22:00-07:00"; the range may wrap past midnight)
+pub fn parse_quiet_hours(value: &str) -> Result<(NaiveTime, NaiveTime)> {
+    let (start, end) = value
+        .split_once('-')
+        .ok_or_else(|| anyhow!("'{}' is not a time range (expected HH:MM-HH:MM)", value))?;
+    Ok((parse_clock(start)?, parse_clock(end)?))
+}
+
+/// Is `time` inside the quiet hours `value`? (invalid ranges are never quiet)
+pub fn in_quiet_hours(value: &str, time: NaiveTime) -> bool {
+    match parse_quiet_hours(value) {
+        Ok((start, end)) if start <= end => time >= start && time < end,
+        Ok((start, end)) => time >= start || time < end,
+        Err(_) => false,
+    }
+}
+
+/// Per-highlight firing history for this session
+#[derive(Debug, Default)]
+pub struct TriggerGate {
+    last_fired: HashMap<String, Instant>,
+    fires: HashMap<String, u32>,
+}
+
+impl TriggerGate {
+    /// Decide whether highlight `name` may play its sound now, recording the fire if so
+    pub fn allow(
+        &mut self,
+        name: &str,
+        pattern: &HighlightPattern,
+        focused: bool,
+        now: Instant,
+        clock: NaiveTime,
+    ) -> bool {
+        if pattern.only_when_unfocused && focused {
+            return false;
+        }
+        if pattern
+            .quiet_hours
+            .as_deref()
+            .is_some_and(|hours| in_quiet_hours(hours, clock))
+        {
+            return false;
+        }
+        let fired = self.fires.get(name).copied().unwrap_or(0);
+        if pattern.max_fires.is_some_and(|max| fired >= max) {
+            return false;
+        }
+        if let (Some(cooldown), Some(last)) = (pattern.sound_cooldown_ms, self.last_fired.get(name))
+        {
+            if now.duration_since(*last) < Duration::from_millis(cooldown) {
+                return false;
+            }
+        }
+
+        self.last_fired.insert(name.to_string(), now);
+        self.fires.insert(name.to_string(), fired + 1);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clock(value: &str) -> NaiveTime {
+        parse_clock(value).unwrap()
+    }
+
+    fn pattern(extra: &str) -> HighlightPattern {
+        toml::from_str(&format!(
+            "pattern = \"You are stunned\"\nsound = \"stun\"\n{}",
+            extra
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_cooldown_and_session_cap() {
+        let pattern = pattern("sound_cooldown_ms = 2000\nmax_fires = 2");
+        let mut gate = TriggerGate::default();
+        let start = Instant::now();
+        let noon = clock("12:00");
+
+        assert!(gate.allow("stun", &pattern, true, start, noon));
+        assert!(!gate.allow(
+            "stun",
+            &pattern,
+            true,
+            start + Duration::from_millis(500),
+            noon
+        ));
+        assert!(gate.allow("stun", &pattern, true, start + Duration::from_secs(3), noon));
+        // Cap reached for the session
+        assert!(!gate.allow(
+            "stun",
+            &pattern,
+            true,
+            start + Duration::from_secs(60),
+            noon
+        ));
+    }
+
+    #[test]
+    fn test_only_when_unfocused() {
+        let pattern = pattern("only_when_unfocused = true");
+        let mut gate = TriggerGate::default();
+        let start = Instant::now();
+        assert!(!gate.allow("stun", &pattern, true, start, clock("12:00")));
+        assert!(gate.allow("stun", &pattern, false, start, clock("12:00")));
+    }
+
+    #[test]
+    fn test_quiet_hours_span_midnight() {
+        let pattern = pattern("quiet_hours = \"22:00-07:00\"");
+        let mut gate = TriggerGate::default();
+        let start = Instant::now();
+        assert!(!gate.allow("stun", &pattern, false, start, clock("23:30")));
+        assert!(!gate.allow("stun", &pattern, false, start, clock("06:59")));
+        assert!(gate.allow("stun", &pattern, false, start, clock("07:00")));
+    }
+
+    #[test]
+    fn test_in_quiet_hours() {
+        assert!(in_quiet_hours("13:00-14:00", clock("13:30")));
+        assert!(!in_quiet_hours("13:00-14:00", clock("14:00")));
+        assert!(!in_quiet_hours("soon", clock("13:30")));
+        assert!(parse_quiet_hours("22:00").is_err());
+    }
+}
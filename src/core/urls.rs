@@ -0,0 +1,235 @@
+//! Web links in game text
+//!
+//! Announcements and calendar posts carry play.net URLs. They're split out
+//! of the text they arrive in as `SpanType::Url` segments, which text
+//! windows underline and open in the browser on Ctrl/Alt+click. The most
+//! recent are kept for the `cycle_recent_urls` and `open_recent_url`
+//! keybinds.
+
+use crate::data::{LinkData, SpanType, TextSegment};
+use anyhow::{bail, Context, Result};
+use regex::Regex;
+use std::collections::VecDeque;
+use std::sync::OnceLock;
+
+/// `LinkData::exist_id` of URL links (game objects have numeric ids, <d> links "_direct_")
+pub const URL_LINK_ID: &str = "_url_";
+
+/// URLs kept for the keybinds
+const MAX_RECENT_URLS: usize = 20;
+
+fn url_regex() -> &'static Regex {
+    static URL: OnceLock<Regex> = OnceLock::new();
+    URL.get_or_init(|| Regex::new(r#"(?i)\bhttps?://[^\s<>"'`]+"#).expect("URL regex"))
+}
+
+/// Drop punctuation that ends the sentence rather than the URL
+fn trim_url(url: &str) -> &str {
+    let mut url = url;
+    while let Some(last) = url.chars().last() {
+        let trailing = match last {
+            '.' | ',' | ';' | ':' | '!' | '?' => true,
+            // Keep the closing bracket of "wiki/Foo_(bar)", not of "(see http://...)"
+            ')' => url.matches('(').count() < url.matches(')').count(),
+            ']' => url.matches('[').count() < url.matches(']').count(),
+            _ => false,
+        };
+        if !trailing {
+            break;
+        }
+        url = &url[..url.len() - last.len_utf8()];
+    }
+    url
+}
+
+/// Split the URLs out of a line's plain segments; returns the URLs found
+///
+/// Segments that are already links are left alone. URL segments keep the
+/// surrounding segment's style, with `fg` (the link color) if given.
+pub fn mark_urls(segments: &mut Vec<TextSegment>, fg: Option<&str>) -> Vec<String> {
+    if !segments
+        .iter()
+        .any(|s| s.link_data.is_none() && s.text.contains("://"))
+    {
+        return Vec::new();
+    }
+    let mut found = Vec::new();
+    let mut marked = Vec::with_capacity(segments.len() + 2);
+    for segment in segments.drain(..) {
+        if segment.link_data.is_some() {
+            marked.push(segment);
+            continue;
+        }
+        let mut last = 0;
+        for m in url_regex().find_iter(&segment.text) {
+            let url = trim_url(m.as_str());
+            if !url.contains("://") || url.ends_with("://") {
+                continue;
+            }
+            if m.start() > last {
+                marked.push(TextSegment {
+                    text: segment.text[last..m.start()].to_string(),
+                    ..segment.clone()
+                });
+            }
+            marked.push(TextSegment {
+                text: url.to_string(),
+                fg: fg.map(str::to_string).or_else(|| segment.fg.clone()),
+                span_type: SpanType::Url,
+                link_data: Some(LinkData {
+                    exist_id: URL_LINK_ID.to_string(),
+                    noun: url.to_string(),
+                    text: url.to_string(),
+                    coord: None,
+                    flags: Default::default(),
+                }),
+                ..segment.clone()
+            });
+            found.push(url.to_string());
+            last = m.start() + url.len();
+        }
+        if last == 0 {
+            marked.push(segment);
+        } else if last < segment.text.len() {
+            marked.push(TextSegment {
+                text: segment.text[last..].to_string(),
+                ..segment
+            });
+        }
+    }
+    *segments = marked;
+    found
+}
+
+/// Recently seen URLs, newest first, with the one the keybind has stepped to
+#[derive(Debug, Default)]
+pub struct RecentUrls {
+    urls: VecDeque<String>,
+    cursor: Option<usize>,
+}
+
+impl RecentUrls {
+    /// Remember a URL (seeing one again moves it to the front)
+    pub fn push(&mut self, url: String) {
+        self.urls.retain(|u| *u != url);
+        self.urls.push_front(url);
+        self.urls.truncate(MAX_RECENT_URLS);
+        self.cursor = None;
+    }
+
+    /// Step to the next older URL, wrapping; returns it with its 1-based position
+    pub fn cycle(&mut self) -> Option<(usize, usize, &str)> {
+        if self.urls.is_empty() {
+            return None;
+        }
+        let next = self.cursor.map_or(0, |c| (c + 1) % self.urls.len());
+        self.cursor = Some(next);
+        Some((next + 1, self.urls.len(), self.urls[next].as_str()))
+    }
+
+    /// The URL stepped to, or the newest; stepping starts over afterwards
+    pub fn take_selected(&mut self) -> Option<String> {
+        let url = self.urls.get(self.cursor.unwrap_or(0)).cloned();
+        self.cursor = None;
+        url
+    }
+}
+
+/// Open a web link in the default browser without waiting for it
+pub fn open_in_browser(url: &str) -> Result<()> {
+    let lower = url.to_ascii_lowercase();
+    if !lower.starts_with("http://") && !lower.starts_with("https://") {
+        bail!("Not a web link: {}", url);
+    }
+
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut command = std::process::Command::new("rundll32");
+        command.arg("url.dll,FileProtocolHandler");
+        command
+    };
+    #[cfg(target_os = "macos")]
+    let mut command = std::process::Command::new("open");
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let mut command = std::process::Command::new("xdg-open");
+
+    let mut child = command
+        .arg(url)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .with_context(|| format!("Couldn't start a browser for {}", url))?;
+    // Reap the launcher once it hands off to the browser
+    std::thread::spawn(move || {
+        let _ = child.wait();
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(text: &str) -> TextSegment {
+        TextSegment {
+            text: text.to_string(),
+            fg: Some("#aaaaaa".to_string()),
+            bg: None,
+            bold: false,
+            span_type: SpanType::Normal,
+            link_data: None,
+        }
+    }
+
+    #[test]
+    fn test_mark_urls() {
+        let mut segments = vec![segment(
+            "See https://www.play.net/gs4/events/calendar.asp. (Or http://gswiki.play.net/Foo_(bar))",
+        )];
+        let found = mark_urls(&mut segments, Some("#477ab3"));
+        assert_eq!(
+            found,
+            vec![
+                "https://www.play.net/gs4/events/calendar.asp",
+                "http://gswiki.play.net/Foo_(bar)"
+            ]
+        );
+        let texts: Vec<&str> = segments.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            vec![
+                "See ",
+                "https://www.play.net/gs4/events/calendar.asp",
+                ". (Or ",
+                "http://gswiki.play.net/Foo_(bar)",
+                ")"
+            ]
+        );
+        assert_eq!(segments[1].span_type, SpanType::Url);
+        assert_eq!(segments[1].fg.as_deref(), Some("#477ab3"));
+        assert_eq!(
+            segments[1].link_data.as_ref().unwrap().exist_id,
+            URL_LINK_ID
+        );
+        assert_eq!(segments[2].fg.as_deref(), Some("#aaaaaa"));
+
+        let mut plain = vec![segment("Nothing here but http:// and text")];
+        assert!(mark_urls(&mut plain, None).is_empty());
+        assert_eq!(plain, vec![segment("Nothing here but http:// and text")]);
+    }
+
+    #[test]
+    fn test_recent_urls_cycle() {
+        let mut recent = RecentUrls::default();
+        assert!(recent.cycle().is_none());
+        for url in ["https://a", "https://b", "https://a"] {
+            recent.push(url.to_string());
+        }
+        assert_eq!(recent.cycle(), Some((1, 2, "https://a")));
+        assert_eq!(recent.cycle(), Some((2, 2, "https://b")));
+        assert_eq!(recent.take_selected().as_deref(), Some("https://b"));
+        assert_eq!(recent.take_selected().as_deref(), Some("https://a"));
+        assert!(open_in_browser("file:///etc/passwd").is_err());
+    }
+}
@@ -0,0 +1,344 @@
+//! Bounty task tracker - the Adventurer's Guild task, parsed
+//!
+//! The game resends the whole task description on the bounty stream whenever
+//! it changes ("You need to kill 12 more of them..."), and the same text
+//! comes back in main for BOUNTY. Each description is parsed into a
+//! `BountyTask`; the first count seen for a task becomes its total, so the
+//! widget can show progress as the remaining count drops.
+
+use chrono::{DateTime, Local};
+use regex::Regex;
+
+/// What the guild has asked for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BountyKind {
+    /// Go see someone (guard, gem dealer, furrier, healer) to get the details
+    Report,
+    Cull,
+    Bandits,
+    Dangerous,
+    Heirloom,
+    /// Heirloom found, take it back
+    HeirloomFound,
+    Gems,
+    Skins,
+    Herbs,
+    Rescue,
+    Escort,
+    /// Done, collect the reward at the guild
+    Succeeded,
+    Failed,
+}
+
+impl BountyKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Report => "Report",
+            Self::Cull => "Cull",
+            Self::Bandits => "Bandits",
+            Self::Dangerous => "Dangerous creature",
+            Self::Heirloom => "Heirloom",
+            Self::HeirloomFound => "Return heirloom",
+            Self::Gems => "Gems",
+            Self::Skins => "Skins",
+            Self::Herbs => "Herbs",
+            Self::Rescue => "Rescue",
+            Self::Escort => "Escort",
+            Self::Succeeded => "Complete",
+            Self::Failed => "Failed",
+        }
+    }
+}
+
+/// The current task, as far as the description says
+#[derive(Debug, Clone, PartialEq)]
+pub struct BountyTask {
+    pub kind: BountyKind,
+    /// Creature, gem, skin, herb or heirloom
+    pub target: Option<String>,
+    pub area: Option<String>,
+    pub town: Option<String>,
+    /// Who to see or sell to
+    pub npc: Option<String>,
+    /// Kills or items still needed
+    pub remaining: Option<u32>,
+    /// Largest count seen for this task
+    pub total: Option<u32>,
+}
+
+impl BountyTask {
+    fn new(kind: BountyKind) -> Self {
+        Self {
+            kind,
+            target: None,
+            area: None,
+            town: None,
+            npc: None,
+            remaining: None,
+            total: None,
+        }
+    }
+
+    /// (done, total) for tasks with a count
+    pub fn progress(&self) -> Option<(u32, u32)> {
+        let (remaining, total) = (self.remaining?, self.total?);
+        Some((total.saturating_sub(remaining), total))
+    }
+
+    /// "Cull: kobold in the Kobold Village near Wehnimer's Landing (6/18)"
+    pub fn summary(&self) -> String {
+        let mut text = self.kind.label().to_string();
+        if let Some(target) = &self.target {
+            text.push_str(&format!(": {}", target));
+        }
+        if let Some(area) = &self.area {
+            text.push_str(&format!(" in {}", area));
+        }
+        if let Some(town) = &self.town {
+            text.push_str(&format!(" near {}", town));
+        }
+        if let Some(npc) = &self.npc {
+            text.push_str(&format!(" (see {})", npc));
+        }
+        if let Some((done, total)) = self.progress() {
+            text.push_str(&format!(" ({}/{})", done, total));
+        }
+        text
+    }
+}
+
+/// Parses bounty descriptions into the current task
+#[derive(Debug, Clone)]
+pub struct BountyTracker {
+    /// None when not on a task (or before the first BOUNTY)
+    pub task: Option<BountyTask>,
+    pub updated: Option<DateTime<Local>>,
+    patterns: Vec<(BountyKind, Regex)>,
+}
+
+impl BountyTracker {
+    pub fn new() -> Self {
+        // Named groups: target, area, town, npc, count. Order matters - the
+        // bandit pattern has to win over the general cull one.
+        let patterns = [
+            (
+                BountyKind::Succeeded,
+                r"You have succeeded in your task|You succeeded in your task",
+            ),
+            (
+                BountyKind::Failed,
+                r"You have failed in your task|your task is failed",
+            ),
+            (
+                BountyKind::Bandits,
+                r"You have been tasked to suppress bandit activity (?:in|on|near) (?:the )?(?P<area>.+?)(?: near (?P<town>[^.]+))?\.\s+You need to kill (?P<count>\d+) (?:more )?of them",
+            ),
+            (
+                BountyKind::Cull,
+                r"You have been tasked to (?:suppress|help suppress) (?P<target>.+?) activity (?:in|on|near) (?:the )?(?P<area>.+?)(?: near (?P<town>[^.]+))?\.\s+You need to kill (?P<count>\d+) (?:more )?of them",
+            ),
+            (
+                BountyKind::Dangerous,
+                r"You have been tasked to hunt down and kill a particularly dangerous (?P<target>.+?) that has established a territory (?:in|on|near) (?:the )?(?P<area>.+?)(?: near (?P<town>[^.]+))?\.",
+            ),
+            (
+                BountyKind::HeirloomFound,
+                r"You have located (?P<target>.+?) and should bring it back to (?P<npc>[^.]+)",
+            ),
+            (
+                BountyKind::Heirloom,
+                r"You have been tasked to recover (?P<target>.+?) that an unfortunate citizen lost after being attacked by (?:an? )?.+? (?:in|on|near) (?:the )?(?P<area>.+?)(?: near (?P<town>[^.]+))?\.",
+            ),
+            (
+                BountyKind::Gems,
+                r"The gem dealer in (?P<town>[^,]+), (?P<npc>[^,]+), has received orders from multiple customers requesting (?:an? |some )?(?P<target>.+?)\.\s+You have been tasked to retrieve (?P<count>\d+) (?:more )?of them",
+            ),
+            (
+                BountyKind::Skins,
+                r"You have been tasked to retrieve (?P<count>\d+) (?:more )?(?P<target>.+?) of at least \w+ quality for (?P<npc>.+?) in (?P<town>[^.]+)\.",
+            ),
+            (
+                BountyKind::Herbs,
+                r"The (?:healer|herbalist|alchemist)(?: in (?P<town>[^,]+))?, (?P<npc>[^,]+), is working on a concoction that requires (?:an? |some )?(?P<target>.+?) found (?:in|on|near) (?:the )?(?P<area>.+?)(?: near [^.]+)?\..*?You have been tasked to retrieve (?P<count>\d+) (?:more )?samples?",
+            ),
+            (
+                BountyKind::Rescue,
+                r"You have been tasked to rescue the young .+? of a local citizen\..*? fleeing from (?:an? )?(?P<target>.+?) (?:in|on|near) (?:the )?(?P<area>.+?)(?: near (?P<town>[^.]+))?\.",
+            ),
+            (
+                BountyKind::Escort,
+                r"protective escort on (?:his|her) upcoming journey\..*?Go to (?P<area>.+?) and WAIT for (?:him|her).*?safety to (?P<town>.+?) as soon as you can",
+            ),
+            (
+                BountyKind::Report,
+                r"(?i:report) to (?:one of )?(?:the )?(?P<npc>guardsmen|guard|gem dealer|furrier|healer|herbalist|alchemist|taskmaster)[^.]*?(?: (?:in|just inside|near) (?:the )?(?P<area>[^.]+?))?(?: to find out more)?\.",
+            ),
+        ];
+        Self {
+            task: None,
+            updated: None,
+            patterns: patterns
+                .into_iter()
+                .map(|(kind, pattern)| (kind, Regex::new(pattern).unwrap()))
+                .collect(),
+        }
+    }
+
+    /// Update the task from a bounty or main line; false if it isn't a bounty description
+    pub fn record_line(&mut self, text: &str) -> bool {
+        let text = text.trim();
+        if text.contains("You are not currently assigned a task") {
+            self.task = None;
+            self.updated = Some(Local::now());
+            return true;
+        }
+
+        let Some((kind, caps)) = self
+            .patterns
+            .iter()
+            .find_map(|(kind, re)| re.captures(text).map(|caps| (*kind, caps)))
+        else {
+            return false;
+        };
+        let group = |name: &str| caps.name(name).map(|m| m.as_str().trim().to_string());
+
+        let mut task = BountyTask::new(kind);
+        task.target = group("target");
+        task.area = group("area");
+        task.town = group("town");
+        task.npc = group("npc");
+        task.remaining = group("count").and_then(|c| c.parse().ok());
+
+        // Same task with a lower count: keep the total from before
+        task.total = match &self.task {
+            Some(old) if old.kind == task.kind && old.target == task.target => {
+                match (old.total, task.remaining) {
+                    (Some(total), Some(remaining)) => Some(total.max(remaining)),
+                    (_, remaining) => remaining,
+                }
+            }
+            // Finishing keeps the old progress around, filled up
+            Some(old) if kind == BountyKind::Succeeded => {
+                task.target = old.target.clone();
+                task.area = old.area.clone();
+                task.town = old.town.clone();
+                task.remaining = old.total.map(|_| 0);
+                old.total
+            }
+            _ => task.remaining,
+        };
+
+        self.task = Some(task);
+        self.updated = Some(Local::now());
+        true
+    }
+}
+
+impl Default for BountyTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CULL: &str = "You have been tasked to suppress kobold activity in the Kobold Village near Wehnimer's Landing.  You need to kill 18 of them to complete your task.";
+
+    fn task_after(lines: &[&str]) -> BountyTask {
+        let mut bounty = BountyTracker::new();
+        for line in lines {
+            bounty.record_line(line);
+        }
+        bounty.task.unwrap()
+    }
+
+    #[test]
+    fn test_unrelated_lines_ignored() {
+        let mut bounty = BountyTracker::new();
+        assert!(!bounty.record_line("You search the kobold."));
+        assert!(bounty.task.is_none());
+    }
+
+    #[test]
+    fn test_cull_task() {
+        let mut bounty = BountyTracker::new();
+        assert!(bounty.record_line(CULL));
+        let task = bounty.task.unwrap();
+        assert_eq!(task.kind, BountyKind::Cull);
+        assert_eq!(task.target.as_deref(), Some("kobold"));
+        assert_eq!(task.area.as_deref(), Some("Kobold Village"));
+        assert_eq!(task.town.as_deref(), Some("Wehnimer's Landing"));
+        assert_eq!(task.progress(), Some((0, 18)));
+    }
+
+    #[test]
+    fn test_cull_progress_from_remaining_count() {
+        let task = task_after(&[
+            CULL,
+            "You have been tasked to suppress kobold activity in the Kobold Village near Wehnimer's Landing.  You need to kill 12 more of them to complete your task.",
+        ]);
+        assert_eq!(task.progress(), Some((6, 18)));
+    }
+
+    #[test]
+    fn test_success_keeps_target() {
+        let task = task_after(&[
+            CULL,
+            "You have succeeded in your task and can return to the Adventurer's Guild to receive your reward.",
+        ]);
+        assert_eq!(
+            (task.kind, task.progress()),
+            (BountyKind::Succeeded, Some((18, 18)))
+        );
+        assert_eq!(task.target.as_deref(), Some("kobold"));
+    }
+
+    #[test]
+    fn test_gem_task() {
+        let task = task_after(&[
+            "The gem dealer in Wehnimer's Landing, Kirsy, has received orders from multiple customers requesting an uncut diamond.  You have been tasked to retrieve 4 more of them.  You can SELL them to the gem dealer as you find them.",
+        ]);
+        assert_eq!(task.kind, BountyKind::Gems);
+        assert_eq!(
+            (task.npc.as_deref(), task.target.as_deref()),
+            (Some("Kirsy"), Some("uncut diamond"))
+        );
+        assert_eq!(
+            task.summary(),
+            "Gems: uncut diamond near Wehnimer's Landing (see Kirsy) (0/4)"
+        );
+    }
+
+    #[test]
+    fn test_bandit_task() {
+        let task = task_after(&[
+            "You have been tasked to suppress bandit activity on the Old Logging Road near Wehnimer's Landing.  You need to kill 20 more of them to complete your task.",
+        ]);
+        assert_eq!(
+            (task.kind, task.area.as_deref()),
+            (BountyKind::Bandits, Some("Old Logging Road"))
+        );
+    }
+
+    #[test]
+    fn test_report_task() {
+        let task = task_after(&[
+            "It appears they have a creature problem they'd like you to solve.  Go report to one of the guardsmen just inside the Ta'Vaalor City Gate to find out more.",
+        ]);
+        assert_eq!(
+            (task.kind, task.npc.as_deref()),
+            (BountyKind::Report, Some("guardsmen"))
+        );
+        assert_eq!(task.area.as_deref(), Some("Ta'Vaalor City Gate"));
+    }
+
+    #[test]
+    fn test_no_task_clears() {
+        let mut bounty = BountyTracker::new();
+        bounty.record_line(CULL);
+        assert!(bounty.record_line("You are not currently assigned a task."));
+        assert!(bounty.task.is_none());
+    }
+}
@@ -0,0 +1,363 @@
+//! Container contents - what's in each bag, as far as main has said
+//!
+//! LOOK IN output ("In the leather backpack you see a blue gem, a dagger and
+//! some silver coins.") sets a container's whole contents; put/stow lines
+//! ("You put a blue gem in your backpack.") and get/remove lines ("You
+//! remove a blue gem from in your backpack.") add and take single items in
+//! between. Containers never looked in only know what went in this session.
+
+use chrono::{DateTime, Local};
+use regex::Regex;
+use std::collections::BTreeMap;
+
+/// Deepest nesting followed when walking containers inside containers
+const MAX_DEPTH: usize = 8;
+
+/// One container and what's known to be in it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Container {
+    /// Name as the game shows it, without "your"/"the" ("leather backpack")
+    pub name: String,
+    /// Items as shown, without a leading article ("blue gem")
+    pub items: Vec<String>,
+    /// Contents came from LOOK IN rather than only from puts and gets
+    pub looked: bool,
+    pub updated: DateTime<Local>,
+}
+
+/// One row of the containers tree
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContainerRow {
+    pub depth: usize,
+    pub text: String,
+    pub is_container: bool,
+    /// `container_key` of the row's path (containers) or of the container
+    /// holding it plus its position (items)
+    pub key: String,
+}
+
+/// Stable key for a container nested in others, outermost first
+/// (["a leather backpack", "small pouch"] -> "leather backpack/small pouch")
+///
+/// Window state that remembers containers (the inventory tree's collapsed
+/// nodes) uses the same key as this tracker, so it survives reordering and
+/// duplicate item names elsewhere in the list.
+pub fn container_key<'a>(path: impl IntoIterator<Item = &'a str>) -> String {
+    path.into_iter()
+        .map(|name| strip_article(name).to_lowercase())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Containers by lowercased name
+#[derive(Debug, Clone)]
+pub struct ContainerTracker {
+    pub containers: BTreeMap<String, Container>,
+    put: Regex,
+    take: Regex,
+    look: Regex,
+    empty: Regex,
+}
+
+impl ContainerTracker {
+    pub fn new() -> Self {
+        Self {
+            containers: BTreeMap::new(),
+            put: Regex::new(
+                r"^You (?:put|place|slip|tuck|stow) (?P<item>.+?) (?:in|into|inside) (?:your|my) (?P<container>.+?)\.$",
+            )
+            .unwrap(),
+            take: Regex::new(
+                r"^You (?:remove|get|take|grab|pull) (?P<item>.+?) from (?:in |inside )?(?:your|my) (?P<container>.+?)\.$",
+            )
+            .unwrap(),
+            look: Regex::new(r"^In (?:the|your|my) (?P<container>.+?) you see (?P<items>.+?)\.$").unwrap(),
+            empty: Regex::new(r"^There is nothing in (?:the|your|my) (?P<container>.+?)\.$").unwrap(),
+        }
+    }
+
+    /// Update contents from a main-window line; false if it isn't about a container
+    pub fn record_line(&mut self, text: &str) -> bool {
+        let text = text.trim();
+        if let Some(caps) = self.look.captures(text) {
+            let items = split_list(&caps["items"]);
+            self.set_contents(&caps["container"], items);
+        } else if let Some(caps) = self.empty.captures(text) {
+            self.set_contents(&caps["container"], Vec::new());
+        } else if let Some(caps) = self.put.captures(text) {
+            let item = strip_article(&caps["item"]);
+            let container = self.entry(&caps["container"]);
+            container.items.push(item);
+            container.updated = Local::now();
+        } else if let Some(caps) = self.take.captures(text) {
+            let item = strip_article(&caps["item"]);
+            let container = self.entry(&caps["container"]);
+            if let Some(i) = container
+                .items
+                .iter()
+                .position(|i| i.eq_ignore_ascii_case(&item))
+            {
+                container.items.remove(i);
+            }
+            container.updated = Local::now();
+        } else {
+            return false;
+        }
+        true
+    }
+
+    fn entry(&mut self, name: &str) -> &mut Container {
+        let name = strip_article(name);
+        self.containers
+            .entry(name.to_lowercase())
+            .or_insert_with(|| Container {
+                name,
+                items: Vec::new(),
+                looked: false,
+                updated: Local::now(),
+            })
+    }
+
+    fn set_contents(&mut self, name: &str, items: Vec<String>) {
+        let container = self.entry(name);
+        container.items = items;
+        container.looked = true;
+        container.updated = Local::now();
+    }
+
+    /// The tracked container an item is ("leather pouch" in a backpack)
+    fn as_container(&self, item: &str) -> Option<&Container> {
+        self.containers.get(&item.to_lowercase())
+    }
+
+    /// Containers that aren't inside another tracked container
+    fn top_level(&self) -> impl Iterator<Item = &Container> {
+        self.containers.values().filter(|c| {
+            !self
+                .containers
+                .values()
+                .any(|other| other.items.iter().any(|i| i.eq_ignore_ascii_case(&c.name)))
+        })
+    }
+
+    /// The containers as a tree, nested containers under the bag holding them
+    pub fn tree(&self) -> Vec<ContainerRow> {
+        let mut rows = Vec::new();
+        for container in self.top_level() {
+            self.push_rows(container, &mut Vec::new(), &mut rows);
+        }
+        rows
+    }
+
+    fn push_rows<'a>(
+        &'a self,
+        container: &'a Container,
+        path: &mut Vec<&'a str>,
+        rows: &mut Vec<ContainerRow>,
+    ) {
+        let depth = path.len();
+        path.push(&container.name);
+        let key = container_key(path.iter().copied());
+        let count = if container.looked {
+            format!("{}", container.items.len())
+        } else {
+            format!("{}+", container.items.len())
+        };
+        rows.push(ContainerRow {
+            depth,
+            text: format!("{} ({})", container.name, count),
+            is_container: true,
+            key: key.clone(),
+        });
+        for (i, item) in container.items.iter().enumerate() {
+            match self.as_container(item) {
+                Some(inner) if depth < MAX_DEPTH && !path.contains(&inner.name.as_str()) => {
+                    self.push_rows(inner, path, rows)
+                }
+                _ => rows.push(ContainerRow {
+                    depth: depth + 1,
+                    text: item.clone(),
+                    is_container: false,
+                    key: format!("{}#{}", key, i),
+                }),
+            }
+        }
+        path.pop();
+    }
+
+    /// Items whose name contains `query`, with the containers holding them,
+    /// innermost first ("blue gem", ["pouch", "backpack"])
+    pub fn find(&self, query: &str) -> Vec<(String, Vec<String>)> {
+        let query = query.trim().to_lowercase();
+        let mut found = Vec::new();
+        for container in self.containers.values() {
+            for item in &container.items {
+                if item.to_lowercase().contains(&query) {
+                    found.push((item.clone(), self.path_of(container)));
+                }
+            }
+        }
+        found
+    }
+
+    /// A container and the ones holding it, innermost first
+    fn path_of(&self, container: &Container) -> Vec<String> {
+        let mut path = vec![container.name.clone()];
+        let mut current = container;
+        while path.len() <= MAX_DEPTH {
+            let Some(parent) = self.containers.values().find(|c| {
+                c.items
+                    .iter()
+                    .any(|i| i.eq_ignore_ascii_case(&current.name))
+            }) else {
+                break;
+            };
+            if path.iter().any(|p| p == &parent.name) {
+                break;
+            }
+            path.push(parent.name.clone());
+            current = parent;
+        }
+        path
+    }
+}
+
+impl Default for ContainerTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// "a blue gem" -> "blue gem"
+fn strip_article(text: &str) -> String {
+    let text = text.trim();
+    for article in ["a ", "an ", "some ", "the ", "your ", "my "] {
+        if let Some(rest) = text
+            .get(..article.len())
+            .filter(|start| start.eq_ignore_ascii_case(article))
+            .and_then(|_| text.get(article.len()..))
+        {
+            if !rest.trim().is_empty() {
+                return rest.trim().to_string();
+            }
+        }
+    }
+    text.to_string()
+}
+
+/// "a blue gem, a dagger, and some coins" -> ["blue gem", "dagger", "coins"]
+fn split_list(text: &str) -> Vec<String> {
+    let mut items: Vec<&str> = text.split(", ").collect();
+    if let Some(last) = items.pop() {
+        let last = last.strip_prefix("and ").unwrap_or(last);
+        match last.rsplit_once(" and ") {
+            Some((before, after)) => {
+                items.push(before);
+                items.push(after);
+            }
+            None => items.push(last),
+        }
+    }
+    items
+        .into_iter()
+        .map(strip_article)
+        .filter(|i| !i.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOOK: &str =
+        "In the leather backpack you see a blue gem, a small pouch, and some silver coins.";
+
+    /// Backpack looked in, then a ruby and the backpack's gem put in the pouch
+    fn packed() -> ContainerTracker {
+        let mut tracker = ContainerTracker::new();
+        for line in [
+            LOOK,
+            "You put a red ruby in your small pouch.",
+            "You remove a blue gem from in your leather backpack.",
+            "You put the blue gem in your small pouch.",
+        ] {
+            assert!(tracker.record_line(line));
+        }
+        tracker
+    }
+
+    #[test]
+    fn test_look_records_contents() {
+        let mut tracker = ContainerTracker::new();
+        assert!(!tracker.record_line("You search the kobold."));
+        assert!(tracker.record_line(LOOK));
+        let backpack = &tracker.containers["leather backpack"];
+        assert!(backpack.looked);
+        assert_eq!(
+            backpack.items,
+            vec!["blue gem", "small pouch", "silver coins"]
+        );
+    }
+
+    #[test]
+    fn test_put_and_remove_move_items() {
+        let tracker = packed();
+        assert_eq!(
+            tracker.containers["leather backpack"].items,
+            vec!["small pouch", "silver coins"]
+        );
+        assert_eq!(
+            tracker.containers["small pouch"].items,
+            vec!["red ruby", "blue gem"]
+        );
+    }
+
+    #[test]
+    fn test_tree_nests_containers() {
+        let rows = packed().tree();
+        assert_eq!(rows[1].key, "leather backpack/small pouch");
+        let rendered: Vec<(usize, &str)> =
+            rows.iter().map(|r| (r.depth, r.text.as_str())).collect();
+        assert_eq!(
+            rendered,
+            vec![
+                (0, "leather backpack (2)"),
+                (1, "small pouch (2+)"),
+                (2, "red ruby"),
+                (2, "blue gem"),
+                (1, "silver coins"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_container_key_drops_articles_and_case() {
+        assert_eq!(
+            container_key(["a Leather Backpack", "the small pouch"]),
+            "leather backpack/small pouch"
+        );
+    }
+
+    #[test]
+    fn test_find_returns_container_path() {
+        assert_eq!(
+            packed().find("GEM"),
+            vec![(
+                "blue gem".to_string(),
+                vec!["small pouch".to_string(), "leather backpack".to_string()]
+            )]
+        );
+    }
+
+    #[test]
+    fn test_nothing_in_empties_container() {
+        let mut tracker = packed();
+        assert!(tracker.record_line("There is nothing in the small pouch."));
+        assert!(tracker.find("ruby").is_empty());
+    }
+
+    #[test]
+    fn test_split_list() {
+        assert_eq!(split_list("a dagger and an apple"), vec!["dagger", "apple"]);
+    }
+}
@@ -0,0 +1,328 @@
+//! Loot tracker - silvers, boxes and gems picked up this session
+//!
+//! Main-window lines that report loot ("You pick up 120 silvers.", "He had
+//! 34 silvers on him.") are added to both the session totals and the current
+//! hunt. Boxes and gems a search turns up fall to the ground, so they count
+//! when picked up, not when the search reports them. `.loot reset` closes
+//! the hunt and starts another; the last few closed hunts are kept for
+//! `.loot report`.
+
+use chrono::{DateTime, Duration, Local};
+use regex::Regex;
+use std::collections::{BTreeMap, VecDeque};
+
+/// Closed hunts kept for the report
+pub const HUNT_HISTORY_LEN: usize = 10;
+
+const BOX_NOUNS: &[&str] = &["box", "chest", "coffer", "strongbox", "trunk", "casket"];
+
+const GEM_NOUNS: &[&str] = &[
+    "agate",
+    "amber",
+    "amethyst",
+    "aquamarine",
+    "beryl",
+    "bloodstone",
+    "carbuncle",
+    "chalcedony",
+    "chrysoberyl",
+    "chrysoprase",
+    "citrine",
+    "coral",
+    "diamond",
+    "doomstone",
+    "dreamstone",
+    "emerald",
+    "garnet",
+    "gem",
+    "heliotrope",
+    "jade",
+    "jasper",
+    "lapis",
+    "moonstone",
+    "morganite",
+    "obsidian",
+    "onyx",
+    "opal",
+    "pearl",
+    "peridot",
+    "quartz",
+    "ruby",
+    "sapphire",
+    "spinel",
+    "starstone",
+    "sunstone",
+    "topaz",
+    "tourmaline",
+    "turquoise",
+    "zircon",
+];
+
+/// What one hunt (or the whole session) brought in
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LootTotals {
+    pub silvers: u64,
+    pub boxes: u32,
+    pub gems: u32,
+    /// Gems by name ("blue sapphire" -> 2)
+    pub gem_names: BTreeMap<String, u32>,
+}
+
+impl LootTotals {
+    pub fn is_empty(&self) -> bool {
+        self.silvers == 0 && self.boxes == 0 && self.gems == 0
+    }
+
+    /// "1,234 silvers, 3 boxes, 5 gems"
+    pub fn summary(&self) -> String {
+        format!(
+            "{} silvers, {} box{}, {} gem{}",
+            with_commas(self.silvers),
+            self.boxes,
+            if self.boxes == 1 { "" } else { "es" },
+            self.gems,
+            if self.gems == 1 { "" } else { "s" }
+        )
+    }
+
+    /// Silvers per hour over `elapsed` (0 for less than a minute)
+    pub fn silvers_per_hour(&self, elapsed: Duration) -> u64 {
+        match elapsed.num_minutes() {
+            0 => 0,
+            minutes => self.silvers * 60 / minutes as u64,
+        }
+    }
+
+    /// Most common gems first
+    pub fn top_gems(&self) -> Vec<(&str, u32)> {
+        let mut gems: Vec<(&str, u32)> = self
+            .gem_names
+            .iter()
+            .map(|(n, c)| (n.as_str(), *c))
+            .collect();
+        gems.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        gems
+    }
+}
+
+/// One stretch of hunting between resets
+#[derive(Clone, Debug)]
+pub struct Hunt {
+    pub started: DateTime<Local>,
+    /// None while the hunt is running
+    pub ended: Option<DateTime<Local>>,
+    pub totals: LootTotals,
+}
+
+impl Hunt {
+    fn new() -> Self {
+        Self {
+            started: Local::now(),
+            ended: None,
+            totals: LootTotals::default(),
+        }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.ended.unwrap_or_else(Local::now) - self.started
+    }
+}
+
+/// Session and per-hunt loot counts
+#[derive(Clone, Debug)]
+pub struct LootTracker {
+    pub session_started: DateTime<Local>,
+    pub session: LootTotals,
+    pub hunt: Hunt,
+    /// Closed hunts, oldest first
+    pub past_hunts: VecDeque<Hunt>,
+    pickup: Regex,
+    search: Regex,
+    coins: Regex,
+}
+
+impl LootTracker {
+    pub fn new() -> Self {
+        Self {
+            session_started: Local::now(),
+            session: LootTotals::default(),
+            hunt: Hunt::new(),
+            past_hunts: VecDeque::new(),
+            pickup: Regex::new(
+                r"(?i)^\s*you (?:pick up|gather(?: up)?|collect|scoop up|grab|find|loot) (.+?)[.!]?\s*$",
+            )
+            .unwrap(),
+            search: Regex::new(r"(?i)\b(?:he|she|it|they) had (.+?)(?: on (?:him|her|it|them))?[.!]").unwrap(),
+            coins: Regex::new(r"(?i)\b([\d,]+) (?:silver|silvers|coins?|silver coins)\b").unwrap(),
+        }
+    }
+
+    pub fn session_elapsed(&self) -> Duration {
+        Local::now() - self.session_started
+    }
+
+    /// Count the loot a main-window line reports; false if it reports none
+    pub fn record_line(&mut self, text: &str) -> bool {
+        // A line is a pickup or a search, never both; searched items are
+        // counted when they're picked up off the ground
+        let (phrase, picked_up) = if let Some(caps) = self.pickup.captures(text) {
+            (caps[1].to_string(), true)
+        } else if let Some(caps) = self.search.captures(text) {
+            (caps[1].to_string(), false)
+        } else {
+            return false;
+        };
+
+        let mut found = LootTotals::default();
+        for item in phrase.split(", ").flat_map(|part| part.split(" and ")) {
+            let item = item.trim();
+            if let Some(caps) = self.coins.captures(item) {
+                found.silvers += caps[1].replace(',', "").parse::<u64>().unwrap_or(0);
+                continue;
+            }
+            if !picked_up {
+                continue;
+            }
+            let noun = item
+                .rsplit(' ')
+                .next()
+                .unwrap_or("")
+                .trim_matches(|c: char| !c.is_alphanumeric())
+                .to_lowercase();
+            // "diamonds", "boxes" count as well
+            let forms = [
+                noun.as_str(),
+                noun.strip_suffix('s').unwrap_or(&noun),
+                noun.strip_suffix("es").unwrap_or(&noun),
+            ];
+            if forms.iter().any(|form| BOX_NOUNS.contains(form)) {
+                found.boxes += 1;
+            } else if forms.iter().any(|form| GEM_NOUNS.contains(form)) {
+                found.gems += 1;
+                *found.gem_names.entry(strip_article(item)).or_insert(0) += 1;
+            }
+        }
+        if found.is_empty() {
+            return false;
+        }
+
+        for totals in [&mut self.session, &mut self.hunt.totals] {
+            totals.silvers += found.silvers;
+            totals.boxes += found.boxes;
+            totals.gems += found.gems;
+            for (name, count) in &found.gem_names {
+                *totals.gem_names.entry(name.clone()).or_insert(0) += count;
+            }
+        }
+        true
+    }
+
+    /// Close the current hunt and start another, returning the closed one
+    pub fn reset_hunt(&mut self) -> Hunt {
+        let mut finished = std::mem::replace(&mut self.hunt, Hunt::new());
+        finished.ended = Some(Local::now());
+        self.past_hunts.push_back(finished.clone());
+        while self.past_hunts.len() > HUNT_HISTORY_LEN {
+            self.past_hunts.pop_front();
+        }
+        finished
+    }
+
+    /// Forget everything and start the session over
+    pub fn reset_session(&mut self) {
+        *self = Self::new();
+    }
+}
+
+impl Default for LootTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn strip_article(item: &str) -> String {
+    let lower = item.to_lowercase();
+    for article in ["a ", "an ", "some ", "the "] {
+        if lower.starts_with(article) {
+            return item[article.len()..].trim().to_string();
+        }
+    }
+    item.trim().to_string()
+}
+
+/// 1234567 -> "1,234,567"
+pub fn with_commas(n: u64) -> String {
+    let digits = n.to_string();
+    let mut out = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// "1h 05m", "12m", "40s"
+pub fn format_elapsed(elapsed: Duration) -> String {
+    let seconds = elapsed.num_seconds().max(0);
+    match (seconds / 3600, seconds / 60 % 60) {
+        (0, 0) => format!("{}s", seconds),
+        (0, minutes) => format!("{}m", minutes),
+        (hours, minutes) => format!("{}h {:02}m", hours, minutes),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_loot_tracking() {
+        let mut loot = LootTracker::new();
+        assert!(loot.record_line("You pick up 1,250 silvers."));
+        assert!(loot
+            .record_line("You search the kobold.  It had 34 silvers and a blue sapphire on it."));
+        assert!(loot.record_line("You pick up a blue sapphire."));
+        assert!(loot
+            .record_line("You pick up a small iron box, some uncut diamonds and an uncut ruby."));
+        assert!(loot.record_line("You gather the remaining 16 coins from inside an oak strongbox."));
+        assert!(!loot.record_line("You pick up a steel dagger."));
+        assert!(!loot.record_line("You search the kobold.  You find nothing of interest."));
+        assert!(!loot.record_line("Xorlak says, \"I had 3 silvers once.\""));
+
+        assert_eq!(loot.session.silvers, 1300);
+        assert_eq!(loot.session.boxes, 1);
+        assert_eq!(loot.session.gems, 3);
+        assert_eq!(loot.session.gem_names.get("blue sapphire"), Some(&1));
+        assert_eq!(loot.session.gem_names.get("uncut diamonds"), Some(&1));
+        assert_eq!(loot.hunt.totals, loot.session);
+
+        let finished = loot.reset_hunt();
+        assert_eq!(finished.totals.silvers, 1300);
+        assert!(finished.ended.is_some());
+        assert!(loot.hunt.totals.is_empty());
+        loot.record_line("You pick up 5 silvers.");
+        assert_eq!((loot.hunt.totals.silvers, loot.session.silvers), (5, 1305));
+        assert_eq!(loot.past_hunts.len(), 1);
+
+        loot.reset_session();
+        assert!(loot.session.is_empty() && loot.past_hunts.is_empty());
+
+        assert_eq!(with_commas(1234567), "1,234,567");
+        assert_eq!(with_commas(999), "999");
+        assert_eq!(format_elapsed(Duration::seconds(3900)), "1h 05m");
+        assert_eq!(format_elapsed(Duration::seconds(720)), "12m");
+    }
+
+    #[test]
+    fn test_searched_box_and_gem_count_once() {
+        let mut loot = LootTracker::new();
+        // The search drops them on the ground; picking them up is what counts
+        assert!(!loot.record_line("He had a small iron box and an uncut ruby on him!"));
+        assert!(loot.record_line("You pick up a small iron box."));
+        assert!(loot.record_line("You pick up an uncut ruby."));
+        assert_eq!((loot.session.boxes, loot.session.gems), (1, 1));
+        assert_eq!(loot.session.gem_names.get("uncut ruby"), Some(&1));
+    }
+}
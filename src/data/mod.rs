@@ -4,10 +4,18 @@
 //! NO imports from frontend/ or any rendering code.
 //! Both TUI and GUI frontends read from these structures to render.
 
+pub mod bounty;
+pub mod containers;
+pub mod loot;
 pub mod ui_state;
 pub mod widget;
+pub mod watchlist;
 pub mod window;
 
+pub use bounty::*;
+pub use containers::*;
+pub use loot::*;
 pub use ui_state::*;
 pub use widget::*;
+pub use watchlist::*;
 pub use window::*;
@@ -7,6 +7,10 @@ use super::window::WindowState;
 use crate::data::LinkData;
 use crate::selection::SelectionState;
 use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// How long an alert flashes a window's border
+const FLASH_DURATION: Duration = Duration::from_millis(500);
 
 /// Application UI state
 #[derive(Clone, Debug)]
@@ -18,6 +22,10 @@ pub struct UiState {
     /// Rebuilt when windows are added/removed
     widget_type_index: HashMap<super::window::WidgetType, Vec<String>>,
 
+    /// Window names from bottom to top; later windows draw over earlier ones
+    /// and take mouse clicks where they overlap
+    pub z_order: Vec<String>,
+
     /// Currently focused window name
     pub focused_window: Option<String>,
 
@@ -28,6 +36,21 @@ pub struct UiState {
     pub search_input: String,
     pub search_cursor: usize,
 
+    /// Past search patterns per window (oldest first), navigable with Up/Down in Search mode
+    pub search_history: HashMap<String, Vec<String>>,
+
+    /// Position in the focused window's search history while browsing (None = editing new input)
+    pub search_history_index: Option<usize>,
+
+    /// Pinned search pattern per window (`.pin`); matches stay highlighted until `.unpin`
+    pub pinned_searches: HashMap<String, String>,
+
+    /// Windows whose border is flashing for an alert, with when the flash ends
+    pub flashes: HashMap<String, Instant>,
+
+    /// Ring the terminal bell at the next render (bell alerts)
+    pub bell_pending: bool,
+
     /// Popup menu state (main menu or level 1)
     pub popup_menu: Option<PopupMenu>,
 
@@ -49,6 +72,15 @@ pub struct UiState {
     /// Mouse position when drag started (for detecting drag vs click)
     pub selection_drag_start: Option<(u16, u16)>,
 
+    /// Last completed selection, kept for copying again as ANSI or HTML
+    pub last_copy: Option<crate::selection::SelectionCopy>,
+
+    /// Recent clicks in text, for double/triple click selection
+    pub click_counter: crate::selection::ClickCounter,
+
+    /// Multi-line paste waiting on the send/edit dialog
+    pub pending_paste: Option<Vec<String>>,
+
     /// Link drag state (Ctrl+drag from link)
     pub link_drag_state: Option<LinkDragState>,
 
@@ -122,12 +154,20 @@ pub enum InputMode {
     SpellColorsBrowser,
     /// Spell color form is open (create/edit spell color)
     SpellColorForm,
+    /// Unsaved config changes are being reviewed
+    ConfigDiff,
     /// Theme browser is open
     ThemeBrowser,
     /// Theme editor is open (create/edit theme)
     ThemeEditor,
     /// Settings editor is open
     SettingsEditor,
+    /// Keyboard copy mode (cursor in a text window)
+    CopyMode,
+    /// Multi-line command composer is open
+    Composer,
+    /// Snippet browser is open
+    SnippetBrowser,
 }
 
 /// Popup menu state
@@ -136,6 +176,7 @@ pub struct PopupMenu {
     pub items: Vec<PopupMenuItem>,
     pub selected: usize,
     pub position: (u16, u16), // x, y position
+    pub title: Option<String>, // Shown in the top border
 }
 
 /// A single popup menu item
@@ -151,10 +192,16 @@ impl UiState {
         Self {
             windows: HashMap::new(),
             widget_type_index: HashMap::new(),
+            z_order: Vec::new(),
             focused_window: None,
             input_mode: InputMode::Normal,
             search_input: String::new(),
             search_cursor: 0,
+            search_history: HashMap::new(),
+            search_history_index: None,
+            pinned_searches: HashMap::new(),
+            flashes: HashMap::new(),
+            bell_pending: false,
             popup_menu: None,
             submenu: None,
             nested_submenu: None,
@@ -162,6 +209,9 @@ impl UiState {
             mouse_drag: None,
             selection_state: None,
             selection_drag_start: None,
+            last_copy: None,
+            click_counter: Default::default(),
+            pending_paste: None,
             link_drag_state: None,
             pending_link_click: None,
         }
@@ -177,8 +227,11 @@ impl UiState {
         self.windows.get_mut(name)
     }
 
-    /// Add or update a window
+    /// Add or update a window (new windows go on top)
     pub fn set_window(&mut self, name: String, window: WindowState) {
+        if !self.z_order.contains(&name) {
+            self.z_order.push(name.clone());
+        }
         self.windows.insert(name, window);
         self.rebuild_widget_index();
     }
@@ -187,11 +240,99 @@ impl UiState {
     pub fn remove_window(&mut self, name: &str) -> Option<WindowState> {
         let result = self.windows.remove(name);
         if result.is_some() {
+            self.z_order.retain(|n| n != name);
             self.rebuild_widget_index();
         }
         result
     }
 
+    /// All windows from bottom to top
+    pub fn windows_in_z_order(&self) -> Vec<(&String, &WindowState)> {
+        self.z_order
+            .iter()
+            .filter_map(|name| self.windows.get_key_value(name))
+            .collect()
+    }
+
+    /// The topmost visible window under a screen cell (containers are skipped,
+    /// their children own the space inside them)
+    pub fn window_at(&self, x: u16, y: u16) -> Option<&String> {
+        self.windows_in_z_order()
+            .into_iter()
+            .rev()
+            .find(|(_, window)| {
+                let pos = &window.position;
+                window.visible
+                    && window.widget_type != super::window::WidgetType::Container
+                    && x >= pos.x
+                    && x < pos.x + pos.width
+                    && y >= pos.y
+                    && y < pos.y + pos.height
+            })
+            .map(|(name, _)| name)
+    }
+
+    /// Whether a visible window higher in the stack covers any part of `name`
+    pub fn is_overlapped(&self, name: &str) -> bool {
+        let Some(window) = self.windows.get(name) else {
+            return false;
+        };
+        let pos = &window.position;
+        self.z_order
+            .iter()
+            .skip_while(|n| n.as_str() != name)
+            .skip(1)
+            .filter_map(|n| self.windows.get(n))
+            .any(|other| {
+                let o = &other.position;
+                other.visible
+                    && other.widget_type != super::window::WidgetType::Container
+                    && o.x < pos.x + pos.width
+                    && pos.x < o.x + o.width
+                    && o.y < pos.y + pos.height
+                    && pos.y < o.y + o.height
+            })
+    }
+
+    /// Flash a window's border briefly (restarts a flash already running)
+    pub fn flash_window(&mut self, name: &str, now: Instant) {
+        if self.windows.contains_key(name) {
+            self.flashes.insert(name.to_string(), now + FLASH_DURATION);
+        }
+    }
+
+    /// Drop finished flashes; true if any ended, so their windows need redrawing
+    pub fn expire_flashes(&mut self, now: Instant) -> bool {
+        let before = self.flashes.len();
+        self.flashes.retain(|_, until| *until > now);
+        self.flashes.len() != before
+    }
+
+    /// Move `names` to the top (or bottom) of the stack, keeping their order
+    pub fn restack(&mut self, names: &[String], to_top: bool) {
+        self.z_order.retain(|n| !names.contains(n));
+        let moved = names
+            .iter()
+            .filter(|n| self.windows.contains_key(*n))
+            .cloned();
+        if to_top {
+            self.z_order.extend(moved);
+        } else {
+            self.z_order.splice(0..0, moved);
+        }
+    }
+
+    /// Stack windows in the given order (bottom to top); unlisted windows stay on top
+    pub fn set_z_order(&mut self, order: &[String]) {
+        let mut z_order: Vec<String> = order
+            .iter()
+            .filter(|n| self.windows.contains_key(*n))
+            .cloned()
+            .collect();
+        z_order.extend(self.z_order.iter().filter(|n| !order.contains(n)).cloned());
+        self.z_order = z_order;
+    }
+
     /// Rebuild the widget type index cache
     /// Called whenever windows are added/removed
     pub fn rebuild_widget_index(&mut self) {
@@ -294,9 +435,15 @@ impl PopupMenu {
             items,
             selected: 0,
             position,
+            title: None,
         }
     }
 
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
     pub fn select_next(&mut self) {
         if !self.items.is_empty() {
             self.selected = (self.selected + 1) % self.items.len();
@@ -358,3 +505,67 @@ impl PopupMenu {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_z_order() {
+        let mut state = UiState::new();
+        for name in ["main", "thoughts", "deaths"] {
+            state.set_window(name.to_string(), WindowState::new_text(name, 100));
+        }
+        // All three cover (0, 0); the last one added is on top
+        assert_eq!(state.window_at(0, 0).map(String::as_str), Some("deaths"));
+
+        state.restack(&["main".to_string()], true);
+        assert_eq!(state.z_order, vec!["thoughts", "deaths", "main"]);
+        assert_eq!(state.window_at(0, 0).map(String::as_str), Some("main"));
+
+        state.restack(&["main".to_string()], false);
+        state.set_z_order(&["deaths".to_string(), "gone".to_string()]);
+        assert_eq!(state.z_order, vec!["deaths", "main", "thoughts"]);
+
+        state.remove_window("main");
+        assert_eq!(state.z_order, vec!["deaths", "thoughts"]);
+    }
+
+    #[test]
+    fn test_flash_window() {
+        let mut state = UiState::new();
+        state.set_window("death".to_string(), WindowState::new_text("death", 100));
+        let now = Instant::now();
+        state.flash_window("death", now);
+        state.flash_window("gone", now);
+        assert_eq!(state.flashes.len(), 1);
+        assert!(!state.expire_flashes(now));
+        assert!(state.expire_flashes(now + FLASH_DURATION));
+        assert!(state.flashes.is_empty());
+    }
+
+    #[test]
+    fn test_is_overlapped() {
+        let mut state = UiState::new();
+        for (name, x) in [("main", 0), ("thoughts", 30), ("deaths", 100)] {
+            let mut window = WindowState::new_text(name, 100);
+            window.position = crate::data::WindowPosition {
+                x,
+                y: 0,
+                width: 40,
+                height: 10,
+            };
+            state.set_window(name.to_string(), window);
+        }
+        assert!(state.is_overlapped("main"));
+        assert!(!state.is_overlapped("thoughts"));
+        assert!(!state.is_overlapped("deaths"));
+
+        state.windows.get_mut("thoughts").unwrap().visible = false;
+        assert!(!state.is_overlapped("main"));
+        state.windows.get_mut("thoughts").unwrap().visible = true;
+        state.restack(&["main".to_string()], true);
+        assert!(state.is_overlapped("thoughts"));
+        assert!(!state.is_overlapped("main"));
+    }
+}
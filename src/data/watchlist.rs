@@ -0,0 +1,252 @@
+//! Friends/enemies watch list - who on the list is online
+//!
+//! Lines on the logons stream ("* Zoleta joins the adventure.", "* Zoleta
+//! returns home from a hard day of adventuring.") and the death stream
+//! ("* Zoleta was slain by a troll on the Rocky Trail.") are matched against
+//! the names in watchlist.toml. Only arrivals seen this session are known, so
+//! someone already logged in shows up the next time they come or go.
+
+use chrono::{DateTime, Local};
+use std::collections::BTreeMap;
+
+/// Which list a character is on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum WatchKind {
+    Friend,
+    Enemy,
+}
+
+impl WatchKind {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "friend" | "friends" => Some(Self::Friend),
+            "enemy" | "enemies" => Some(Self::Enemy),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Friend => "Friend",
+            Self::Enemy => "Enemy",
+        }
+    }
+}
+
+/// What a logons/death line said about a watched character
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PresenceChange {
+    Arrived,
+    Departed,
+    Died,
+}
+
+/// A watched character arriving, leaving or dying
+#[derive(Debug, Clone, PartialEq)]
+pub struct PresenceEvent {
+    pub name: String,
+    pub kind: WatchKind,
+    pub change: PresenceChange,
+}
+
+impl PresenceEvent {
+    /// "Friend Zoleta joins the adventure"
+    pub fn describe(&self) -> String {
+        let what = match self.change {
+            PresenceChange::Arrived => "joins the adventure",
+            PresenceChange::Departed => "has left",
+            PresenceChange::Died => "has died",
+        };
+        format!("{} {} {}", self.kind.label(), self.name, what)
+    }
+}
+
+/// A watched character seen online
+#[derive(Debug, Clone)]
+pub struct Presence {
+    pub name: String,
+    pub kind: WatchKind,
+    pub since: DateTime<Local>,
+    /// Last death seen since they arrived
+    pub died: Option<DateTime<Local>>,
+}
+
+/// Watched characters currently online, by lowercased name
+#[derive(Debug, Clone, Default)]
+pub struct WatchTracker {
+    pub online: BTreeMap<String, Presence>,
+}
+
+impl WatchTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Update presence from a logons or death line, returning the change if
+    /// it's about someone on `friends` or `enemies`
+    pub fn record_line(
+        &mut self,
+        stream: &str,
+        text: &str,
+        friends: &[String],
+        enemies: &[String],
+    ) -> Option<PresenceEvent> {
+        let text = text.trim().trim_start_matches('*').trim_start();
+        let name = text
+            .split_whitespace()
+            .next()?
+            .trim_end_matches(|c: char| !c.is_alphanumeric());
+        let listed = |list: &[String]| list.iter().any(|n| n.eq_ignore_ascii_case(name));
+        let kind = if listed(friends) {
+            WatchKind::Friend
+        } else if listed(enemies) {
+            WatchKind::Enemy
+        } else {
+            return None;
+        };
+
+        let change = match stream {
+            "death" => PresenceChange::Died,
+            "logons" if text.contains("joins the adventure") => PresenceChange::Arrived,
+            "logons"
+                if text.contains("returns home")
+                    || text.contains("has disconnected")
+                    || text.contains("has departed") =>
+            {
+                PresenceChange::Departed
+            }
+            _ => return None,
+        };
+
+        let key = name.to_lowercase();
+        match change {
+            PresenceChange::Arrived => {
+                self.online.insert(
+                    key,
+                    Presence {
+                        name: name.to_string(),
+                        kind,
+                        since: Local::now(),
+                        died: None,
+                    },
+                );
+            }
+            PresenceChange::Departed => {
+                self.online.remove(&key);
+            }
+            PresenceChange::Died => {
+                // Dying means they were online, even if we missed the arrival
+                let presence = self.online.entry(key).or_insert_with(|| Presence {
+                    name: name.to_string(),
+                    kind,
+                    since: Local::now(),
+                    died: None,
+                });
+                presence.died = Some(Local::now());
+            }
+        }
+
+        Some(PresenceEvent {
+            name: name.to_string(),
+            kind,
+            change,
+        })
+    }
+
+    /// Online characters of one kind, alphabetically
+    pub fn online_of(&self, kind: WatchKind) -> impl Iterator<Item = &Presence> {
+        self.online.values().filter(move |p| p.kind == kind)
+    }
+
+    /// Drop (or re-file) anyone whose name left or moved between the lists
+    pub fn retain_listed(&mut self, friends: &[String], enemies: &[String]) {
+        let listed =
+            |list: &[String], name: &str| list.iter().any(|n| n.eq_ignore_ascii_case(name));
+        self.online.retain(|_, presence| {
+            if listed(friends, &presence.name) {
+                presence.kind = WatchKind::Friend;
+            } else if listed(enemies, &presence.name) {
+                presence.kind = WatchKind::Enemy;
+            } else {
+                return false;
+            }
+            true
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARRIVAL: &str = "* Zoleta joins the adventure.";
+    const DEATH: &str = " * Grimjaw was slain by a troll on the Rocky Trail.";
+    const DEPARTURE: &str = "* Zoleta returns home from a hard day of adventuring.";
+
+    fn friends() -> Vec<String> {
+        vec!["Zoleta".to_string()]
+    }
+
+    fn enemies() -> Vec<String> {
+        vec!["grimjaw".to_string()]
+    }
+
+    fn record(watch: &mut WatchTracker, stream: &str, line: &str) -> Option<PresenceEvent> {
+        watch.record_line(stream, line, &friends(), &enemies())
+    }
+
+    #[test]
+    fn test_listed_arrival() {
+        let mut watch = WatchTracker::new();
+        let event = record(&mut watch, "logons", ARRIVAL).unwrap();
+        assert_eq!(
+            (event.kind, event.change),
+            (WatchKind::Friend, PresenceChange::Arrived)
+        );
+        assert_eq!(event.describe(), "Friend Zoleta joins the adventure");
+    }
+
+    #[test]
+    fn test_ignores_unlisted_names_and_other_streams() {
+        let mut watch = WatchTracker::new();
+        assert!(record(&mut watch, "logons", "* Bob joins the adventure.").is_none());
+        assert!(record(&mut watch, "main", "Zoleta joins the adventure.").is_none());
+        assert!(watch.online.is_empty());
+    }
+
+    #[test]
+    fn test_death_marks_online_without_arrival() {
+        let mut watch = WatchTracker::new();
+        let event = record(&mut watch, "death", DEATH).unwrap();
+        assert_eq!(
+            (event.kind, event.change),
+            (WatchKind::Enemy, PresenceChange::Died)
+        );
+        assert!(watch.online["grimjaw"].died.is_some());
+        assert_eq!(watch.online_of(WatchKind::Enemy).count(), 1);
+    }
+
+    #[test]
+    fn test_departure_goes_offline() {
+        let mut watch = WatchTracker::new();
+        record(&mut watch, "logons", ARRIVAL);
+        let event = record(&mut watch, "logons", DEPARTURE).unwrap();
+        assert_eq!(event.change, PresenceChange::Departed);
+        assert_eq!(watch.online_of(WatchKind::Friend).count(), 0);
+    }
+
+    #[test]
+    fn test_retain_listed_drops_removed_names() {
+        let mut watch = WatchTracker::new();
+        record(&mut watch, "logons", ARRIVAL);
+        record(&mut watch, "death", DEATH);
+        watch.retain_listed(&friends(), &[]);
+        assert_eq!(watch.online.len(), 1);
+        assert_eq!(watch.online_of(WatchKind::Enemy).count(), 0);
+    }
+
+    #[test]
+    fn test_watch_kind_from_str() {
+        assert_eq!(WatchKind::from_str("Enemies"), Some(WatchKind::Enemy));
+    }
+}
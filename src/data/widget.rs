@@ -46,6 +46,8 @@ pub enum SpanType {
     Monsterbold, // <preset id="monsterbold"> from parser (monsters)
     Spell,       // <spell> tag from parser (spells)
     Speech,      // <preset id="speech"> from parser (player speech)
+    Whisper,     // <preset id="whisper"> from parser (whispers to us)
+    Url,         // http(s) link found in the text (see core::urls)
 }
 
 /// Link metadata for clickable text
@@ -55,6 +57,35 @@ pub struct LinkData {
     pub noun: String,
     pub text: String,
     pub coord: Option<String>, // Optional coord for direct commands (e.g., "2524,1864" for movement)
+    pub flags: ItemFlags,      // Item state hints from the surrounding text
+}
+
+/// Item state picked up from the text around a link ("a closed chest", "(lit)")
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ItemFlags {
+    pub worn: bool,
+    pub held: bool,
+    pub closed: bool,
+    pub open: bool,
+    pub lit: bool,
+}
+
+impl ItemFlags {
+    pub fn held() -> Self {
+        Self {
+            held: true,
+            ..Self::default()
+        }
+    }
+
+    /// Short marker a widget can show after the item, if any applies
+    pub fn marker(&self) -> Option<&'static str> {
+        match (self.lit, self.closed) {
+            (true, _) => Some("☼"),
+            (false, true) => Some("▪"),
+            _ => None,
+        }
+    }
 }
 
 /// Progress bar state
@@ -191,6 +222,15 @@ impl TextContent {
         self.generation = self.generation.wrapping_add(1);
     }
 
+    /// Change the line limit, dropping the oldest lines if it shrank
+    pub fn set_max_lines(&mut self, max_lines: usize) {
+        self.max_lines = max_lines;
+        while self.lines.len() > max_lines {
+            self.lines.pop_front();
+        }
+        self.scroll_offset = self.scroll_offset.min(self.lines.len().saturating_sub(1));
+    }
+
     pub fn scroll_up(&mut self, amount: usize) {
         let max_scroll = self.lines.len().saturating_sub(1);
         self.scroll_offset = (self.scroll_offset + amount).min(max_scroll);
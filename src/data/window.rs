@@ -21,6 +21,7 @@ pub enum WidgetType {
     Text,
     TabbedText,
     Progress,
+    Vitals,
     Countdown,
     Compass,
     Indicator,
@@ -37,6 +38,15 @@ pub enum WidgetType {
     Spells,
     Spacer,
     QuickBar,
+    Container,
+    LootTracker,
+    SessionStats,
+    BountyTracker,
+    ContainerTree,
+    WatchList,
+    Art,
+    StatusLine,
+    Plugin,
 }
 
 // helper maybe not needed currently
@@ -47,6 +57,9 @@ pub enum WindowContent {
     Text(TextContent),
     TabbedText(TabbedTextContent),
     Progress(ProgressData),
+    Vitals {
+        bars: Vec<(String, ProgressData)>, // (progress id, data) in display order
+    },
     Countdown(CountdownData),
     Compass(CompassData),
     InjuryDoll(InjuryDollData),
@@ -77,6 +90,14 @@ pub enum WindowContent {
     QuickBar {
         content: String, // Raw content for currently active bar
     },
+    LootTracker,   // Counts live in AppCore::loot
+    SessionStats,  // Counters live in AppCore::session
+    BountyTracker, // Task lives in AppCore::bounty
+    ContainerTree, // Contents live in AppCore::containers
+    Plugin,        // Lines live in AppCore::plugins
+    WatchList,     // Presence lives in AppCore::watch
+    Art,           // Image path lives in the layout
+    StatusLine,    // Fields come from GameState each frame
     Empty, // For spacers or not-yet-implemented widgets
 }
 
@@ -25,6 +25,8 @@ pub enum FrontendEvent {
     Resize { width: u16, height: u16 },
     /// Paste event (text from clipboard)
     Paste { text: String },
+    /// Terminal/window gained or lost focus
+    Focus { gained: bool },
     /// Application quit signal
     Quit,
 }
@@ -2,22 +2,29 @@
 //!
 //! This module will implement the Frontend trait for native GUI rendering.
 //! Currently a stub - will be implemented after TUI frontend is stable.
+//! Fonts come from `[gui.fonts]` in config.toml (see `GuiFontConfig`).
 
 use crate::core::AppCore;
 use anyhow::Result;
 
 pub struct EguiApp {
-    _app_core: AppCore,
+    app_core: AppCore,
 }
 
 impl EguiApp {
     pub fn new(app_core: AppCore) -> Self {
-        Self {
-            _app_core: app_core,
-        }
+        Self { app_core }
     }
 
     pub fn run(self) -> Result<()> {
+        let fonts = &self.app_core.config.gui.fonts;
+        tracing::debug!(
+            "GUI fonts: ui {:?} {}pt, text {:?} {}pt",
+            fonts.ui_chain(),
+            fonts.ui_size(),
+            fonts.text_chain(),
+            fonts.text_size_for("main")
+        );
         eprintln!("GUI frontend not yet implemented. Use --frontend tui for now.");
         Ok(())
     }
@@ -0,0 +1,351 @@
+//! Art window: one image file (portrait, area map) in a bordered window.
+//!
+//! With a kitty or sixel terminal the window reserves blank cells and the
+//! image is written over them after the frame (`graphics_output`); otherwise
+//! it's drawn with half-block characters. A missing or unreadable file shows
+//! the window's alt text instead.
+//!
+//! Files are decoded on a worker thread so a large image doesn't stall the
+//! frame; the alt text shows until the decode lands.
+
+use super::graphics::{self, GraphicsProtocol};
+use image::RgbaImage;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    widgets::{Block, BorderType, Clear, Paragraph, Widget, Wrap},
+};
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+
+pub struct ArtWindow {
+    title: String,
+    show_border: bool,
+    border_style: Option<String>,
+    border_color: Option<Color>,
+    border_sides: crate::config::BorderSides,
+    text_color: Option<Color>,
+    background_color: Option<Color>,
+    transparent_background: bool,
+    source: Option<PathBuf>,
+    alt: Option<String>,
+    image: Option<RgbaImage>,
+    error: Option<String>,
+    /// Decode of `source` still running on its worker thread
+    loading: Option<Receiver<Result<RgbaImage, String>>>,
+    /// Bumped whenever the image changes
+    generation: u64,
+    /// Kitty image id (stable per window name)
+    kitty_id: u32,
+    /// Cells reserved for the image in the frame being drawn
+    placement: Option<Rect>,
+    /// What the terminal is showing: (generation, cells)
+    shown: Option<(u64, Rect)>,
+    /// What kitty has been sent: (generation, cells)
+    kitty_sent: Option<(u64, Rect)>,
+}
+
+impl ArtWindow {
+    pub fn new(name: &str) -> Self {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        name.hash(&mut hasher);
+        Self {
+            title: name.to_string(),
+            show_border: true,
+            border_style: None,
+            border_color: None,
+            border_sides: crate::config::BorderSides::default(),
+            text_color: None,
+            background_color: None,
+            transparent_background: true,
+            source: None,
+            alt: None,
+            image: None,
+            error: None,
+            loading: None,
+            generation: 0,
+            kitty_id: (hasher.finish() as u32).max(1),
+            placement: None,
+            shown: None,
+            kitty_sent: None,
+        }
+    }
+
+    /// Point the window at an image file, starting a decode if it changed
+    pub fn set_source(&mut self, path: Option<PathBuf>, alt: Option<String>) {
+        self.alt = alt;
+        if path != self.source {
+            self.generation += 1;
+            self.image = None;
+            self.error = None;
+            // Dropping an older receiver lets its decode finish unheard
+            self.loading = path.clone().and_then(|path| self.start_decode(path));
+            self.source = path;
+        }
+        self.finish_decode();
+    }
+
+    fn start_decode(&mut self, path: PathBuf) -> Option<Receiver<Result<RgbaImage, String>>> {
+        let (tx, rx) = mpsc::channel();
+        let spawned = std::thread::Builder::new()
+            .name("art-decode".to_string())
+            .spawn(move || {
+                let decoded = image::open(&path)
+                    .map(|image| image.to_rgba8())
+                    .map_err(|e| e.to_string());
+                let _ = tx.send(decoded);
+            });
+        match spawned {
+            Ok(_) => Some(rx),
+            Err(e) => {
+                self.error = Some(format!("Can't load image: {}", e));
+                None
+            }
+        }
+    }
+
+    /// Pick up the decoded image once the worker is done
+    fn finish_decode(&mut self) {
+        let Some(rx) = &self.loading else {
+            return;
+        };
+        let result = match rx.try_recv() {
+            Ok(result) => result,
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => Err("decoder stopped".to_string()),
+        };
+        self.loading = None;
+        let path = self
+            .source
+            .as_deref()
+            .map(|p| p.display().to_string())
+            .unwrap_or_default();
+        match result {
+            Ok(image) => {
+                self.image = Some(image);
+                self.generation += 1;
+            }
+            Err(e) => {
+                tracing::debug!("Art window '{}': can't load {}: {}", self.title, path, e);
+                self.error = Some(format!("Can't load {}", path));
+            }
+        }
+    }
+
+    /// Forget last frame's placement (hidden windows aren't rendered)
+    pub fn begin_frame(&mut self) {
+        self.placement = None;
+    }
+
+    pub fn render(&mut self, area: Rect, buf: &mut Buffer, protocol: GraphicsProtocol) {
+        Clear.render(area, buf);
+        if !self.transparent_background {
+            if let Some(bg) = self.background_color {
+                buf.set_style(area, Style::default().bg(bg));
+            }
+        }
+
+        let inner = if self.show_border {
+            let mut block = Block::default()
+                .borders(crate::config::parse_border_sides(&self.border_sides))
+                .border_style(Style::default().fg(self.border_color.unwrap_or(Color::Reset)))
+                .title(self.title.as_str());
+            block = block.border_type(match self.border_style.as_deref() {
+                Some("double") => BorderType::Double,
+                Some("rounded") => BorderType::Rounded,
+                Some("thick") => BorderType::Thick,
+                _ => BorderType::Plain,
+            });
+            let inner = block.inner(area);
+            block.render(area, buf);
+            inner
+        } else {
+            area
+        };
+        if inner.width == 0 || inner.height == 0 {
+            return;
+        }
+
+        let Some(image) = &self.image else {
+            let text = self
+                .alt
+                .clone()
+                .or_else(|| self.error.clone())
+                .unwrap_or_else(|| {
+                    if self.loading.is_some() {
+                        "Loading...".to_string()
+                    } else {
+                        "No image".to_string()
+                    }
+                });
+            Paragraph::new(text)
+                .style(Style::default().fg(self.text_color.unwrap_or(Color::DarkGray)))
+                .wrap(Wrap { trim: true })
+                .render(inner, buf);
+            return;
+        };
+
+        if protocol == GraphicsProtocol::Text {
+            let (placed, cells) = graphics::half_blocks(image, inner);
+            for (row, line) in cells.iter().enumerate() {
+                for (col, (top, bottom)) in line.iter().enumerate() {
+                    let cell = &mut buf[(placed.x + col as u16, placed.y + row as u16)];
+                    cell.set_symbol("▀").set_fg(*top).set_bg(*bottom);
+                }
+            }
+        } else {
+            self.placement = Some(inner);
+        }
+    }
+
+    /// Escape sequences to put the image on screen after a frame, or nothing
+    /// if the terminal already shows it. `previous` is the frame before this
+    /// one: anything drawn over the image's cells since means it was erased
+    /// (sixel) or its placement needs refreshing (kitty).
+    pub fn graphics_output(
+        &mut self,
+        protocol: GraphicsProtocol,
+        previous: Option<&Buffer>,
+        current: &Buffer,
+    ) -> Vec<u8> {
+        let (Some(mut area), Some(image)) = (self.placement, &self.image) else {
+            return self.hide();
+        };
+        if protocol == GraphicsProtocol::Text {
+            return self.hide();
+        }
+        // A sixel touching the last row would scroll the screen
+        if protocol == GraphicsProtocol::Sixel && area.bottom() >= current.area.bottom() {
+            area.height = area.height.saturating_sub(1);
+        }
+        if area.is_empty() {
+            return Vec::new();
+        }
+
+        let overdrawn = match previous {
+            Some(prev) if prev.area == current.area => area
+                .positions()
+                .any(|pos| prev.cell(pos) != current.cell(pos)),
+            _ => true,
+        };
+        if !overdrawn && self.shown == Some((self.generation, area)) {
+            return Vec::new();
+        }
+
+        let (scaled, cells) = graphics::fit(image, area, graphics::cell_pixels());
+        let mut out = format!("\x1b7\x1b[{};{}H", cells.y + 1, cells.x + 1).into_bytes();
+        match protocol {
+            GraphicsProtocol::Kitty if self.kitty_sent == Some((self.generation, area)) => {
+                out.extend(graphics::kitty_place(self.kitty_id, cells));
+            }
+            GraphicsProtocol::Kitty => {
+                out.extend(graphics::kitty_transmit(self.kitty_id, &scaled, cells));
+                self.kitty_sent = Some((self.generation, area));
+            }
+            _ => out.extend(graphics::sixel_encode(&scaled)),
+        }
+        out.extend_from_slice(b"\x1b8");
+        self.shown = Some((self.generation, area));
+        out
+    }
+
+    /// The terminal was cleared: send the image again next frame
+    pub fn invalidate(&mut self) {
+        self.shown = None;
+        self.kitty_sent = None;
+    }
+
+    /// Take the image off screen; sixel pixels are already gone once the
+    /// cells under them are redrawn
+    pub fn hide(&mut self) -> Vec<u8> {
+        self.shown = None;
+        if self.kitty_sent.take().is_some() {
+            graphics::kitty_delete(self.kitty_id)
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+impl super::StyledWindow for ArtWindow {
+    fn set_title(&mut self, title: String) {
+        self.title = title;
+    }
+
+    fn set_border_config(&mut self, show: bool, style: Option<String>, color: Option<String>) {
+        self.show_border = show;
+        self.border_style = style;
+        self.border_color = color.and_then(|c| super::parse_hex_color(&c).ok());
+    }
+
+    fn set_border_sides(&mut self, sides: crate::config::BorderSides) {
+        self.border_sides = sides;
+    }
+
+    fn set_text_color(&mut self, color: Option<String>) {
+        self.text_color = color.and_then(|c| super::parse_hex_color(&c).ok());
+    }
+
+    fn set_background_color(&mut self, color: Option<String>) {
+        self.background_color = color
+            .filter(|c| c != "-")
+            .and_then(|c| super::parse_hex_color(&c).ok());
+    }
+
+    fn set_transparent_background(&mut self, transparent: bool) {
+        self.transparent_background = transparent;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    fn art_dir(name: &str) -> std::path::PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("two-face-art-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Keep polling `set_source` until the background decode has finished
+    fn load(art: &mut ArtWindow, path: &std::path::Path) {
+        art.set_source(Some(path.to_path_buf()), None);
+        let started = Instant::now();
+        while art.loading.is_some() && started.elapsed() < Duration::from_secs(5) {
+            std::thread::sleep(Duration::from_millis(5));
+            art.set_source(Some(path.to_path_buf()), None);
+        }
+    }
+
+    #[test]
+    fn test_art_decodes_off_the_render_thread() {
+        let dir = art_dir("decode");
+        let path = dir.join("portrait.png");
+        RgbaImage::from_pixel(4, 2, image::Rgba([255, 0, 0, 255]))
+            .save(&path)
+            .unwrap();
+
+        let mut art = ArtWindow::new("art");
+        load(&mut art, &path);
+        assert_eq!(
+            art.image.as_ref().map(|image| image.dimensions()),
+            Some((4, 2))
+        );
+        assert!(art.loading.is_none());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_missing_art_reports_error() {
+        let dir = art_dir("missing");
+        let mut art = ArtWindow::new("art");
+        load(&mut art, &dir.join("missing.png"));
+        assert!(art.image.is_none());
+        assert!(art.error.as_deref().unwrap().starts_with("Can't load"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
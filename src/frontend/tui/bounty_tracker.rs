@@ -0,0 +1,39 @@
+//! Bounty tracker rows: the current Adventurer's Guild task with a progress
+//! bar for kill and item counts.
+//!
+//! Rows are rebuilt from `AppCore::bounty` on every sync and drawn through a
+//! `ListWidget`; the progress row uses the container's bar.
+
+use super::list_widget::ListRows;
+use crate::data::{BountyKind, BountyTracker};
+
+/// Fill the rows from the tracker
+pub fn fill(rows: &mut ListRows, bounty: &BountyTracker) {
+    let Some(task) = &bounty.task else {
+        let status = if bounty.updated.is_some() {
+            "No task"
+        } else {
+            "Type BOUNTY to check"
+        };
+        rows.row("status", status, None);
+        return;
+    };
+
+    rows.row("kind", task.kind.label(), task.town.clone());
+    if let Some(target) = &task.target {
+        rows.row("target", &format!("  {}", target), None);
+    }
+    if let Some(area) = &task.area {
+        rows.row("area", &format!("  {}", area), None);
+    }
+    if let Some(npc) = &task.npc {
+        rows.row("npc", &format!("  See {}", npc), None);
+    }
+    if let Some((done, total)) = task.progress() {
+        let count = Some(format!("{}/{}", done, total));
+        rows.row_full("progress", "  Progress", count, done, total.max(1), None);
+    }
+    if task.kind == BountyKind::Succeeded {
+        rows.row("next", "  Return to the guild", None);
+    }
+}
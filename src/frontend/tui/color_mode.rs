@@ -0,0 +1,227 @@
+//! Terminal color capability detection and fallback
+//!
+//! Themes, highlights and presets are all authored as RGB. Terminals without
+//! truecolor support render those as garbage or as the wrong palette entry,
+//! so once a frame is drawn every RGB cell is mapped to the nearest color the
+//! terminal can actually show.
+
+use ratatui::buffer::Buffer;
+use ratatui::style::Color;
+
+/// How many colors the terminal can display
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorMode {
+    TrueColor,
+    Ansi256,
+    Ansi16,
+}
+
+/// xterm's default values for the 16 ANSI colors, in palette order
+const ANSI16: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+/// Channel values of the 6x6x6 cube in the 256-color palette
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+impl ColorMode {
+    /// Resolve the `ui.color_mode` setting ("auto" detects)
+    pub fn from_setting(setting: &str) -> Self {
+        match setting.trim().to_lowercase().as_str() {
+            "truecolor" | "24bit" | "rgb" => Self::TrueColor,
+            "256" | "ansi256" => Self::Ansi256,
+            "16" | "ansi16" | "ansi" => Self::Ansi16,
+            _ => Self::detect(),
+        }
+    }
+
+    /// Guess from the environment the way most terminal apps do
+    pub fn detect() -> Self {
+        let var = |name: &str| std::env::var(name).unwrap_or_default().to_lowercase();
+        Self::detect_from(&var("COLORTERM"), &var("TERM"), &var("TERM_PROGRAM"))
+    }
+
+    fn detect_from(colorterm: &str, term: &str, term_program: &str) -> Self {
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            return Self::TrueColor;
+        }
+        // Windows Terminal and modern conhost don't set COLORTERM or TERM
+        if cfg!(windows) && term.is_empty() {
+            return Self::TrueColor;
+        }
+        if matches!(term_program, "iterm.app" | "wezterm" | "vscode" | "ghostty")
+            || term.contains("truecolor")
+            || term.contains("direct")
+            || term.starts_with("xterm-kitty")
+            || term.starts_with("alacritty")
+        {
+            return Self::TrueColor;
+        }
+        if term.contains("256color") {
+            return Self::Ansi256;
+        }
+        if term.is_empty() || term == "linux" || term == "dumb" || term.starts_with("vt") {
+            return Self::Ansi16;
+        }
+        // Plain "xterm", "screen", etc: 256 colors is the safe common ground
+        Self::Ansi256
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::TrueColor => "truecolor",
+            Self::Ansi256 => "256",
+            Self::Ansi16 => "16",
+        }
+    }
+
+    /// Map one color into this mode
+    pub fn convert(&self, color: Color) -> Color {
+        match (self, color) {
+            (Self::TrueColor, _) => color,
+            (Self::Ansi256, Color::Rgb(r, g, b)) => Color::Indexed(nearest_256(r, g, b)),
+            (Self::Ansi16, Color::Rgb(r, g, b)) => nearest_16(r, g, b),
+            (Self::Ansi16, Color::Indexed(index)) if index >= 16 => {
+                let (r, g, b) = indexed_to_rgb(index);
+                nearest_16(r, g, b)
+            }
+            _ => color,
+        }
+    }
+
+    /// Downsample every cell of a rendered frame
+    pub fn apply(&self, buffer: &mut Buffer) {
+        if *self == Self::TrueColor {
+            return;
+        }
+        for cell in buffer.content.iter_mut() {
+            cell.fg = self.convert(cell.fg);
+            cell.bg = self.convert(cell.bg);
+            cell.underline_color = self.convert(cell.underline_color);
+        }
+    }
+}
+
+/// RGB value of any color (named and palette colors use xterm's defaults; None for Reset)
+pub fn to_rgb(color: Color) -> Option<(u8, u8, u8)> {
+    match color {
+        Color::Reset => None,
+        Color::Rgb(r, g, b) => Some((r, g, b)),
+        Color::Indexed(index) => Some(indexed_to_rgb(index)),
+        named => ANSI16
+            .iter()
+            .find(|(c, _)| *c == named)
+            .map(|(_, rgb)| *rgb),
+    }
+}
+
+fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+    // Weighted for perceived brightness (green counts most, blue least)
+    let dr = r1 as i32 - r2 as i32;
+    let dg = g1 as i32 - g2 as i32;
+    let db = b1 as i32 - b2 as i32;
+    (3 * dr * dr + 4 * dg * dg + 2 * db * db) as u32
+}
+
+fn nearest_16(r: u8, g: u8, b: u8) -> Color {
+    ANSI16
+        .iter()
+        .min_by_key(|(_, rgb)| distance(*rgb, (r, g, b)))
+        .map(|(color, _)| *color)
+        .unwrap_or(Color::Reset)
+}
+
+/// Best of the color cube entry and the gray ramp entry
+fn nearest_256(r: u8, g: u8, b: u8) -> u8 {
+    let cube_index = |v: u8| {
+        CUBE_LEVELS
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, level)| (**level as i32 - v as i32).abs())
+            .map(|(i, _)| i as u8)
+            .unwrap_or(0)
+    };
+    let (ri, gi, bi) = (cube_index(r), cube_index(g), cube_index(b));
+    let cube = 16 + 36 * ri + 6 * gi + bi;
+
+    let average = (r as u32 + g as u32 + b as u32) / 3;
+    let gray_step = (average.saturating_sub(3) / 10).min(23) as u8;
+    let gray = 232 + gray_step;
+
+    if distance(indexed_to_rgb(gray), (r, g, b)) < distance(indexed_to_rgb(cube), (r, g, b)) {
+        gray
+    } else {
+        cube
+    }
+}
+
+fn indexed_to_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => ANSI16[index as usize].1,
+        16..=231 => {
+            let level = index - 16;
+            (
+                CUBE_LEVELS[(level / 36) as usize],
+                CUBE_LEVELS[((level % 36) / 6) as usize],
+                CUBE_LEVELS[(level % 6) as usize],
+            )
+        }
+        _ => {
+            let gray = 8 + (index - 232) * 10;
+            (gray, gray, gray)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_color_mode() {
+        assert_eq!(
+            ColorMode::detect_from("truecolor", "xterm", ""),
+            ColorMode::TrueColor
+        );
+        assert_eq!(
+            ColorMode::detect_from("", "xterm-256color", "apple_terminal"),
+            ColorMode::Ansi256
+        );
+        assert_eq!(ColorMode::detect_from("", "linux", ""), ColorMode::Ansi16);
+    }
+
+    #[test]
+    fn test_ansi256_fallback() {
+        let c256 = ColorMode::Ansi256;
+        assert_eq!(c256.convert(Color::Rgb(255, 0, 0)), Color::Indexed(196));
+        assert_eq!(c256.convert(Color::Rgb(128, 128, 128)), Color::Indexed(244));
+        assert_eq!(c256.convert(Color::Cyan), Color::Cyan);
+    }
+
+    #[test]
+    fn test_ansi16_fallback() {
+        let c16 = ColorMode::Ansi16;
+        assert_eq!(c16.convert(Color::Rgb(250, 10, 10)), Color::LightRed);
+        assert_eq!(c16.convert(Color::Rgb(20, 20, 20)), Color::Black);
+        assert_eq!(c16.convert(Color::Indexed(231)), Color::White);
+        assert_eq!(
+            ColorMode::TrueColor.convert(Color::Rgb(1, 2, 3)),
+            Color::Rgb(1, 2, 3)
+        );
+    }
+}
@@ -1,7 +1,16 @@
 //! Stateful command line widget that mimics Profanity's behavior.
 //!
 //! Handles multi-byte cursoring, cut/copy selection, history persistence, and
-//! autocomplete for both dot-commands and window names.
+//! fuzzy Tab completion: dot-commands, window names after a dot-command, and
+//! room nouns, inventory items and spell names in game commands. Ctrl+R starts a
+//! reverse incremental history search: typing narrows it to the newest
+//! command containing the text, Ctrl+R again steps to older matches, Enter
+//! sends the match, Esc (or Ctrl+G) puts back what was typed before, and any
+//! other editing key keeps the match in the input to edit.
+//!
+//! During roundtime the border turns red, the typed text is ghosted and a
+//! small meter counts down on the border; casttime does the same in blue
+//! without ghosting the text.
 
 use crate::config::{self, BorderSides};
 use ratatui::{
@@ -12,10 +21,43 @@ use ratatui::{
     widgets::{Block, Paragraph, Widget},
 };
 use std::collections::VecDeque;
+use regex::Regex;
 use std::fs;
 use std::io::{BufRead, BufReader, Write as _};
 use std::path::PathBuf;
 
+/// Ctrl+R search through history
+struct HistorySearch {
+    query: String,
+    /// History index of the match shown in the input
+    matched: Option<usize>,
+    /// The last search step found nothing
+    failing: bool,
+    /// Input before the search, restored on cancel
+    saved_input: String,
+}
+
+/// Roundtime or casttime seconds shown on the border
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputTimer {
+    Roundtime(u32),
+    Casttime(u32),
+}
+
+/// Which list Tab completes the current word from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompletionSource {
+    /// Dot commands, for a word starting with '.'
+    Commands,
+    /// Window and template names, for a dot command's arguments
+    WindowNames,
+    /// Room nouns, inventory items and spell names, for game commands
+    GameWords,
+}
+
+/// Longest timer meter drawn on the border
+const MAX_TIMER_BLOCKS: u32 = 10;
+
 pub struct CommandInput {
     input: String,
     cursor_pos: usize,
@@ -38,6 +80,10 @@ pub struct CommandInput {
     completion_prefix: Option<String>,  // Original text before completion started
     is_user_typed: bool,                // True if current text was typed by user (not from history)
     selection_start: Option<usize>,     // Start of selection (None if no selection)
+    history_search: Option<HistorySearch>, // Active Ctrl+R search
+    session_commands: Vec<String>, // Added to history since it was loaded, oldest first
+    history_denylist: Vec<Regex>, // Commands matching any of these are never written to disk
+    timer: Option<InputTimer>, // Roundtime/casttime coloring the border
 }
 
 impl CommandInput {
@@ -64,6 +110,10 @@ impl CommandInput {
             completion_prefix: None,
             is_user_typed: false,
             selection_start: None,
+            history_search: None,
+            session_commands: Vec::new(),
+            history_denylist: Vec::new(),
+            timer: None,
         }
     }
 
@@ -71,6 +121,26 @@ impl CommandInput {
         self.min_command_length = min_length;
     }
 
+    /// History size and the patterns of commands that must never be saved
+    pub fn set_history_limits(&mut self, max_entries: usize, denylist: &[String]) {
+        self.max_history = max_entries.max(1);
+        self.history.truncate(self.max_history);
+        self.history_denylist = denylist
+            .iter()
+            .filter_map(|pattern| match Regex::new(pattern) {
+                Ok(regex) => Some(regex),
+                Err(e) => {
+                    tracing::warn!(
+                        "Ignoring invalid history_denylist pattern '{}': {}",
+                        pattern,
+                        e
+                    );
+                    None
+                }
+            })
+            .collect();
+    }
+
     pub fn set_border_config(
         &mut self,
         show_border: bool,
@@ -107,6 +177,24 @@ impl CommandInput {
         self.cursor_bg_color = bg;
     }
 
+    /// Roundtime or casttime left, if any (None = plain border)
+    pub fn set_timer(&mut self, timer: Option<InputTimer>) {
+        self.timer = timer;
+    }
+
+    /// Border color and meter label for the running timer
+    fn timer_display(&self) -> Option<(Color, String)> {
+        let (label, seconds, color) = match self.timer? {
+            InputTimer::Roundtime(s) => ("RT", s, Color::Rgb(0xFF, 0x00, 0x00)),
+            InputTimer::Casttime(s) => ("CT", s, Color::Rgb(0x00, 0xBF, 0xFF)),
+        };
+        if seconds == 0 {
+            return None;
+        }
+        let meter = "▮".repeat(seconds.min(MAX_TIMER_BLOCKS) as usize);
+        Some((color, format!(" {} {} {} ", label, seconds, meter)))
+    }
+
     pub fn insert_char(&mut self, c: char) {
         // Find the byte index for cursor position
         let byte_idx = self.char_pos_to_byte_idx(self.cursor_pos);
@@ -278,6 +366,8 @@ impl CommandInput {
     }
 
     pub fn submit(&mut self) -> Option<String> {
+        // Enter during a history search sends the match
+        self.history_search = None;
         if self.input.is_empty() {
             return None;
         }
@@ -299,6 +389,7 @@ impl CommandInput {
                 if self.history.len() > self.max_history {
                     self.history.pop_back();
                 }
+                self.session_commands.push(command.clone());
             }
         }
 
@@ -353,12 +444,124 @@ impl CommandInput {
         }
     }
 
+    /// Start a reverse history search, or step to the next older match
+    pub fn history_search(&mut self) {
+        match self.history_search.as_ref() {
+            Some(search) => {
+                let from = search.matched.map_or(0, |i| i + 1);
+                self.find_history_match(from);
+            }
+            None => {
+                self.reset_completion();
+                self.history_search = Some(HistorySearch {
+                    query: String::new(),
+                    matched: None,
+                    failing: false,
+                    saved_input: self.input.clone(),
+                });
+            }
+        }
+    }
+
+    /// Give up the search and put back the input from before it
+    pub fn cancel_history_search(&mut self) -> bool {
+        let Some(search) = self.history_search.take() else {
+            return false;
+        };
+        self.input = search.saved_input;
+        self.cursor_pos = self.input.chars().count();
+        self.history_index = None;
+        true
+    }
+
+    /// Handle a key during a history search; false means it ends the search
+    /// (keeping the match) and should be handled as a normal key
+    pub fn history_search_key(
+        &mut self,
+        code: crossterm::event::KeyCode,
+        modifiers: crossterm::event::KeyModifiers,
+    ) -> bool {
+        use crossterm::event::{KeyCode, KeyModifiers};
+
+        let Some(search) = self.history_search.as_mut() else {
+            return false;
+        };
+        let ctrl = modifiers.contains(KeyModifiers::CONTROL);
+        match code {
+            KeyCode::Char('r') if ctrl => self.history_search(),
+            KeyCode::Char('g') if ctrl => {
+                self.cancel_history_search();
+            }
+            KeyCode::Esc => {
+                self.cancel_history_search();
+            }
+            KeyCode::Char(c) if !ctrl && !modifiers.contains(KeyModifiers::ALT) => {
+                search.query.push(c);
+                // A longer query can still match the current command
+                let from = search.matched.unwrap_or(0);
+                self.find_history_match(from);
+            }
+            KeyCode::Backspace => {
+                search.query.pop();
+                self.find_history_match(0);
+            }
+            _ => {
+                self.history_search = None;
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Show the newest history entry at or after `from` containing the query
+    fn find_history_match(&mut self, from: usize) {
+        let Some(search) = self.history_search.as_mut() else {
+            return;
+        };
+        if search.query.is_empty() {
+            search.matched = None;
+            search.failing = false;
+            self.input = search.saved_input.clone();
+            self.cursor_pos = self.input.chars().count();
+            return;
+        }
+
+        let query = search.query.to_lowercase();
+        let found = self
+            .history
+            .iter()
+            .enumerate()
+            .skip(from)
+            .find_map(|(i, command)| {
+                let lower = command.to_lowercase();
+                lower
+                    .find(&query)
+                    .map(|byte| (i, lower[..byte].chars().count()))
+            });
+        search.failing = found.is_none();
+        if let Some((i, cursor)) = found {
+            search.matched = Some(i);
+            self.input = self.history[i].clone();
+            self.cursor_pos = cursor;
+            // Up/Down carry on from the match
+            self.history_index = Some(i);
+            self.is_user_typed = false;
+        }
+    }
+
     pub fn render(&self, area: Rect, buf: &mut Buffer) {
         self.render_with_status(area, buf, None);
     }
 
     pub fn render_with_status(&self, area: Rect, buf: &mut Buffer, status: Option<&str>) {
-        let title = if let Some(status_text) = status {
+        let title = if let Some(search) = &self.history_search {
+            let label = if search.failing {
+                "failing search"
+            } else {
+                "history search"
+            };
+            format!("{} [{}: {}]", self.title, label, search.query)
+        } else if let Some(status_text) = status {
             format!("{} [{}]", self.title, status_text)
         } else {
             self.title.clone()
@@ -387,8 +590,12 @@ impl CommandInput {
                 block = block.border_type(border_type);
             }
 
-            // Apply border color if specified
-            if let Some(color_str) = &self.border_color {
+            // Apply border color if specified; a running timer colors it instead
+            if let Some((color, meter)) = self.timer_display() {
+                block = block.border_style(Style::default().fg(color)).title(
+                    Line::from(Span::styled(meter, Style::default().fg(color))).right_aligned(),
+                );
+            } else if let Some(color_str) = &self.border_color {
                 if let Some(color) = Self::parse_color(color_str) {
                     block = block.border_style(Style::default().fg(color));
                 }
@@ -443,36 +650,7 @@ impl CommandInput {
         // Calculate horizontal scroll to keep cursor visible
         let available_width = inner.width as usize;
         let chars: Vec<char> = self.input.chars().collect();
-        let total_chars = chars.len();
-
-        // We need space for: text before cursor + cursor block + text after cursor
-        // The cursor block takes 1 position, so max visible cursor position is (available_width - 1)
-        let max_visible_cursor_pos = available_width.saturating_sub(1);
-
-        let scroll_offset = if available_width == 0 {
-            0
-        } else if total_chars < available_width {
-            // Everything fits - no scroll needed
-            0
-        } else {
-            // Text is longer than visible area - need to scroll
-            // Keep cursor at 30% from left edge when scrolling
-            let target_cursor_pos = (available_width * 3 / 10).min(max_visible_cursor_pos);
-
-            // Calculate scroll to position cursor at target_cursor_pos from left
-            if self.cursor_pos < target_cursor_pos {
-                // Near start - show from beginning
-                0
-            } else if self.cursor_pos
-                >= total_chars.saturating_sub(available_width - target_cursor_pos)
-            {
-                // Near end - anchor to end, ensuring cursor stays within bounds
-                total_chars.saturating_sub(available_width)
-            } else {
-                // Middle - keep cursor at target position from left
-                self.cursor_pos.saturating_sub(target_cursor_pos)
-            }
-        };
+        let scroll_offset = self.scroll_offset(available_width);
 
         // Extract visible portion of text with scroll applied
         // Take up to available_width chars, which includes the cursor position
@@ -496,12 +674,15 @@ impl CommandInput {
             .unwrap_or(' ');
         let after_cursor: String = visible_chars.iter().skip(visible_cursor_pos + 1).collect();
 
-        // Get text color (default to white if not set)
-        let text_color = self
-            .text_color
-            .as_ref()
-            .and_then(|c| Self::parse_color(c))
-            .unwrap_or(Color::White);
+        // Get text color (default to white if not set); ghosted during roundtime
+        let text_color = match self.timer {
+            Some(InputTimer::Roundtime(s)) if s > 0 => Color::DarkGray,
+            _ => self
+                .text_color
+                .as_ref()
+                .and_then(|c| Self::parse_color(c))
+                .unwrap_or(Color::White),
+        };
 
         // Get cursor colors
         let cursor_fg = self
@@ -528,6 +709,54 @@ impl CommandInput {
         paragraph.render(inner, buf);
     }
 
+    /// First visible character when the text area is `available_width` wide
+    fn scroll_offset(&self, available_width: usize) -> usize {
+        let total_chars = self.input.chars().count();
+
+        // We need space for: text before cursor + cursor block + text after cursor
+        // The cursor block takes 1 position, so max visible cursor position is (available_width - 1)
+        let max_visible_cursor_pos = available_width.saturating_sub(1);
+
+        if available_width == 0 {
+            0
+        } else if total_chars < available_width {
+            // Everything fits - no scroll needed
+            0
+        } else {
+            // Text is longer than visible area - need to scroll
+            // Keep cursor at 30% from left edge when scrolling
+            let target_cursor_pos = (available_width * 3 / 10).min(max_visible_cursor_pos);
+
+            // Calculate scroll to position cursor at target_cursor_pos from left
+            if self.cursor_pos < target_cursor_pos {
+                // Near start - show from beginning
+                0
+            } else if self.cursor_pos
+                >= total_chars.saturating_sub(available_width - target_cursor_pos)
+            {
+                // Near end - anchor to end, ensuring cursor stays within bounds
+                total_chars.saturating_sub(available_width)
+            } else {
+                // Middle - keep cursor at target position from left
+                self.cursor_pos.saturating_sub(target_cursor_pos)
+            }
+        }
+    }
+
+    /// Move the cursor to the character under screen column `x` (past the end = end)
+    pub fn click_at(&mut self, area: Rect, x: u16) {
+        let bordered = self.show_border && self.border_style.as_deref().is_none_or(|s| s != "none");
+        let left = u16::from(bordered && self.border_sides.left);
+        let right = u16::from(bordered && self.border_sides.right);
+        let available_width = area.width.saturating_sub(left + right) as usize;
+
+        let column = x.saturating_sub(area.x + left) as usize;
+        let target = self.scroll_offset(available_width) + column;
+        self.cursor_pos = target.min(self.input.chars().count());
+        self.clear_selection();
+        self.reset_completion();
+    }
+
     /// Reset completion state
     fn reset_completion(&mut self) {
         self.completion_candidates.clear();
@@ -537,11 +766,10 @@ impl CommandInput {
 
     /// Try to complete the current input
     /// Returns true if a completion was performed
-    pub fn try_complete(
-        &mut self,
-        available_commands: &[String],
-        available_names: &[String],
-    ) -> bool {
+    ///
+    /// `words` is only asked for a list when a completion session starts;
+    /// further Tabs cycle the cached candidates until the input changes.
+    pub fn try_complete(&mut self, words: impl FnOnce(CompletionSource) -> Vec<String>) -> bool {
         // Only complete if cursor is at the end
         if self.cursor_pos != self.input.chars().count() {
             return false;
@@ -566,30 +794,21 @@ impl CommandInput {
                 return false;
             }
 
-            // Find candidates
-            let mut candidates = Vec::new();
-
-            // If completing a dot command (starts with .)
-            if word_to_complete.starts_with('.') {
-                for cmd in available_commands {
-                    if cmd.starts_with(word_to_complete) {
-                        candidates.push(cmd.clone());
-                    }
-                }
+            // Dot commands, window/template names for their arguments, game words otherwise
+            let source = if word_to_complete.starts_with('.') {
+                CompletionSource::Commands
+            } else if input.starts_with('.') {
+                CompletionSource::WindowNames
             } else {
-                // Completing a window/template name
-                for name in available_names {
-                    if name.starts_with(word_to_complete) {
-                        candidates.push(name.clone());
-                    }
-                }
-            }
+                CompletionSource::GameWords
+            };
+            let candidates =
+                crate::core::completion::fuzzy_matches(word_to_complete, &words(source));
 
             if candidates.is_empty() {
                 return false;
             }
 
-            candidates.sort();
             self.completion_candidates = candidates;
             self.completion_prefix = Some(prefix);
             self.completion_index = Some(0);
@@ -627,37 +846,59 @@ impl CommandInput {
             return Ok(()); // No history file yet, that's fine
         }
 
-        let file = fs::File::open(&history_path)?;
-        let reader = BufReader::new(file);
-
-        self.history.clear();
-
-        for line in reader.lines() {
-            let line = line?;
-            if !line.trim().is_empty() {
-                self.history.push_back(line);
-                if self.history.len() > self.max_history {
-                    self.history.pop_front();
-                }
-            }
-        }
+        self.history = read_history_file(&history_path)?
+            .into_iter()
+            .take(self.max_history)
+            .collect();
+        self.session_commands.clear();
 
         tracing::debug!("Loaded {} commands from history", self.history.len());
         Ok(())
     }
 
     /// Save command history to disk
+    ///
+    /// Another session of the same character may have saved since we loaded,
+    /// so this session's commands are put in front of the file as it is now
+    /// rather than overwriting it. The file is replaced in one step, so a
+    /// session reading it never sees half a write.
     pub fn save_history(&self, character: Option<&str>) -> Result<(), std::io::Error> {
         let history_path = Self::get_history_path(character)?;
 
-        let mut file = fs::File::create(&history_path)?;
+        let on_disk = if history_path.exists() {
+            read_history_file(&history_path)?
+        } else {
+            Vec::new()
+        };
+        let merged = merge_history(
+            &self.session_commands,
+            on_disk,
+            &self.history_denylist,
+            self.max_history,
+        );
 
-        // Save in reverse order (most recent first in file)
-        for cmd in &self.history {
+        // Most recent first in file; the temp name is unique to this save so
+        // instances saving at once never write into each other's file
+        static SAVES: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+        let save = SAVES.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        let temp_path =
+            history_path.with_extension(format!("txt.{}.{}.tmp", std::process::id(), save));
+        let mut file = fs::File::create(&temp_path)?;
+        for cmd in &merged {
             writeln!(file, "{}", cmd)?;
         }
+        file.sync_all()?;
+        drop(file);
+        if let Err(e) = fs::rename(&temp_path, &history_path) {
+            let _ = fs::remove_file(&temp_path);
+            return Err(e);
+        }
 
-        tracing::debug!("Saved {} commands to history", self.history.len());
+        tracing::debug!(
+            "Saved {} commands to history ({} from this session)",
+            merged.len(),
+            self.session_commands.len()
+        );
         Ok(())
     }
 
@@ -690,3 +931,169 @@ impl CommandInput {
         self.selection_start = None;
     }
 }
+
+/// History file lines, most recent first, skipping blanks
+fn read_history_file(path: &std::path::Path) -> Result<Vec<String>, std::io::Error> {
+    let reader = BufReader::new(fs::File::open(path)?);
+    let mut lines = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        if !line.trim().is_empty() {
+            lines.push(line);
+        }
+    }
+    Ok(lines)
+}
+
+/// This session's commands (oldest first) in front of the saved history,
+/// without denied commands or repeats in a row, cut to `max_entries`
+fn merge_history(
+    session: &[String],
+    on_disk: Vec<String>,
+    denylist: &[Regex],
+    max_entries: usize,
+) -> Vec<String> {
+    let mut merged: Vec<String> = Vec::new();
+    for cmd in session.iter().rev().cloned().chain(on_disk) {
+        if merged.len() >= max_entries {
+            break;
+        }
+        if denylist.iter().any(|regex| regex.is_match(&cmd)) || merged.last() == Some(&cmd) {
+            continue;
+        }
+        merged.push(cmd);
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    #[test]
+    fn test_history_search() {
+        let mut input = CommandInput::new(10);
+        for command in ["prep 101", "look", "cast at kobold", "prepare 118"] {
+            input.input = command.to_string();
+            input.submit();
+        }
+        input.insert_char('x');
+
+        let none = KeyModifiers::NONE;
+        let ctrl = KeyModifiers::CONTROL;
+        input.history_search();
+        assert!(input.history_search_key(KeyCode::Char('P'), none));
+        assert!(input.history_search_key(KeyCode::Char('r'), none));
+        assert_eq!(input.input, "prepare 118");
+        assert_eq!(input.cursor_pos, 0);
+
+        // Ctrl+R steps back to older matches, then stays put once they run out
+        assert!(input.history_search_key(KeyCode::Char('r'), ctrl));
+        assert_eq!(input.input, "prep 101");
+        assert!(input.history_search_key(KeyCode::Char('r'), ctrl));
+        assert_eq!(input.input, "prep 101");
+        assert!(input.history_search.as_ref().unwrap().failing);
+
+        // Backspace starts over from the newest command (an empty query shows the typed text)
+        assert!(input.history_search_key(KeyCode::Backspace, none));
+        assert!(input.history_search_key(KeyCode::Backspace, none));
+        assert_eq!(input.input, "x");
+        assert!(input.history_search_key(KeyCode::Char('k'), none));
+        assert!(input.history_search_key(KeyCode::Char('o'), none));
+        assert_eq!(input.input, "cast at kobold");
+        assert_eq!(input.cursor_pos, 8);
+
+        // Esc puts back what was typed
+        assert!(input.cancel_history_search());
+        assert_eq!(input.input, "x");
+        assert!(!input.cancel_history_search());
+
+        // Another key keeps the match for editing; Enter sends it
+        input.history_search();
+        input.history_search_key(KeyCode::Char('l'), none);
+        input.history_search_key(KeyCode::Char('o'), none);
+        assert_eq!(input.input, "look");
+        assert!(!input.history_search_key(KeyCode::End, none));
+        assert!(input.history_search.is_none());
+        input.history_search();
+        assert_eq!(input.submit().as_deref(), Some("look"));
+    }
+
+    #[test]
+    fn test_timer_border() {
+        let mut input = CommandInput::new(10);
+        input.input = "attack".to_string();
+        let area = Rect::new(0, 0, 40, 3);
+        let screen = |input: &CommandInput| {
+            let mut buf = Buffer::empty(area);
+            input.render(area, &mut buf);
+            buf
+        };
+
+        input.set_timer(Some(InputTimer::Roundtime(3)));
+        let buf = screen(&input);
+        let top: String = (0..40).map(|x| buf[(x, 0)].symbol()).collect();
+        assert!(top.contains(" RT 3 ▮▮▮ "));
+        assert_eq!(buf[(0, 1)].fg, Color::Rgb(0xFF, 0x00, 0x00));
+        assert_eq!(buf[(2, 1)].fg, Color::DarkGray);
+
+        input.set_timer(Some(InputTimer::Casttime(12)));
+        let buf = screen(&input);
+        let top: String = (0..40).map(|x| buf[(x, 0)].symbol()).collect();
+        assert!(top.contains(&format!(" CT 12 {} ", "▮".repeat(10))));
+        assert_eq!(buf[(2, 1)].fg, Color::White);
+
+        input.set_timer(Some(InputTimer::Roundtime(0)));
+        let buf = screen(&input);
+        let top: String = (0..40).map(|x| buf[(x, 0)].symbol()).collect();
+        assert!(!top.contains("RT"));
+    }
+
+    #[test]
+    fn test_merge_history() {
+        let strings = |items: &[&str]| items.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let denylist = vec![Regex::new("(?i)password").unwrap()];
+
+        // Ours go in front of what another session saved meanwhile
+        let session = strings(&["look", "say my password is hunter2", "stance off"]);
+        let on_disk = strings(&["stance off", "exp", "PASSWORD foo", "health"]);
+        assert_eq!(
+            merge_history(&session, on_disk.clone(), &denylist, 10),
+            strings(&["stance off", "look", "stance off", "exp", "health"])
+        );
+        assert_eq!(
+            merge_history(&session, on_disk, &[], 3),
+            strings(&["stance off", "say my password is hunter2", "look"])
+        );
+    }
+
+    #[test]
+    fn test_completion_asks_for_words_once_per_session() {
+        let mut input = CommandInput::new(10);
+        for c in "get bro".chars() {
+            input.insert_char(c);
+        }
+
+        let asked = std::cell::RefCell::new(Vec::new());
+        let words = |source| {
+            asked.borrow_mut().push(source);
+            vec!["broadsword".to_string(), "brooch".to_string()]
+        };
+        assert!(input.try_complete(words));
+        assert_eq!(input.input, "get broadsword");
+        assert!(input.try_complete(words));
+        assert_eq!(input.input, "get brooch");
+        assert_eq!(*asked.borrow(), vec![CompletionSource::GameWords]);
+
+        // Typing ends the session; the next Tab looks the words up again
+        input.insert_char(' ');
+        input.insert_char('b');
+        assert!(input.try_complete(words));
+        assert_eq!(input.input, "get brooch broadsword");
+        assert_eq!(
+            *asked.borrow(),
+            vec![CompletionSource::GameWords, CompletionSource::GameWords]
+        );
+    }
+}
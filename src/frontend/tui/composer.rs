@@ -0,0 +1,210 @@
+//! Multi-line command composer.
+//!
+//! A popup editor for writing several commands at once: a long conversation,
+//! a string of whispers, or a quick script. Ctrl+S sends every non-blank line
+//! in order (spaced by `ui.compose_line_delay_ms`), Esc throws the draft away.
+//! The same editor writes snippet text, where Ctrl+S saves instead of sending.
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    widgets::{Clear, Widget},
+};
+use tui_textarea::TextArea;
+
+pub struct Composer {
+    textarea: TextArea<'static>,
+    popup_position: (u16, u16),
+    /// Snippet being edited (None = compose commands to send)
+    pub snippet: Option<String>,
+}
+
+impl Composer {
+    /// Open with `lines` already in the editor (empty for a blank draft)
+    pub fn new(lines: Vec<String>) -> Self {
+        let mut textarea = if lines.is_empty() {
+            TextArea::default()
+        } else {
+            TextArea::new(lines)
+        };
+        textarea.set_cursor_line_style(Style::default());
+        textarea.move_cursor(tui_textarea::CursorMove::Bottom);
+        textarea.move_cursor(tui_textarea::CursorMove::End);
+        Self {
+            textarea,
+            popup_position: (0, 0),
+            snippet: None,
+        }
+    }
+
+    /// Edit the text of snippet `name`
+    pub fn for_snippet(name: &str, text: &str) -> Self {
+        let mut composer = Self::new(text.lines().map(str::to_string).collect());
+        composer.snippet = Some(name.to_string());
+        composer
+    }
+
+    /// Type into the editor
+    pub fn input(&mut self, key: crossterm::event::KeyEvent) {
+        self.textarea
+            .input(crate::core::event_bridge::to_textarea_event(key));
+    }
+
+    /// Paste text at the cursor; its line breaks become new lines
+    pub fn insert_text(&mut self, text: &str) {
+        self.textarea
+            .insert_str(text.replace("\r\n", "\n").replace('\r', "\n"));
+    }
+
+    /// Everything typed, lines joined with newlines (snippet text)
+    pub fn text(&self) -> String {
+        self.textarea.lines().join("\n").trim_end().to_string()
+    }
+
+    /// The commands to send: non-blank lines, in order
+    pub fn lines(&self) -> Vec<String> {
+        crate::core::paste::split_lines(&self.textarea.lines().join("\n"))
+    }
+
+    pub fn render(
+        &mut self,
+        area: Rect,
+        buf: &mut Buffer,
+        config: &crate::config::Config,
+        theme: &crate::theme::AppTheme,
+    ) {
+        let popup_width = 80.min(area.width);
+        let popup_height = 16.min(area.height);
+        if popup_width < 20 || popup_height < 6 {
+            return;
+        }
+
+        // Center on first render
+        if self.popup_position == (0, 0) {
+            self.popup_position = (
+                (area.width.saturating_sub(popup_width)) / 2,
+                (area.height.saturating_sub(popup_height)) / 2,
+            );
+        }
+        let (popup_col, popup_row) = self.popup_position;
+        let popup_area = Rect {
+            x: popup_col,
+            y: popup_row,
+            width: popup_width,
+            height: popup_height,
+        }
+        .intersection(area);
+        Clear.render(popup_area, buf);
+
+        let background = Style::default().bg(theme.browser_background);
+        for row in popup_area.top()..popup_area.bottom() {
+            for col in popup_area.left()..popup_area.right() {
+                buf.set_string(col, row, " ", background);
+            }
+        }
+
+        // Border and title
+        let border_style = Style::default().fg(theme.browser_border);
+        let inner_width = popup_width as usize - 2;
+        buf.set_string(
+            popup_col,
+            popup_row,
+            format!("┌{}┐", "─".repeat(inner_width)),
+            border_style,
+        );
+        let title = match self.snippet {
+            Some(ref name) => format!(" Edit Snippet: {} ", name),
+            None => " Compose Commands ".to_string(),
+        };
+        buf.set_string(
+            popup_col + 2,
+            popup_row,
+            title,
+            border_style.add_modifier(Modifier::BOLD),
+        );
+        for i in 1..popup_height - 1 {
+            buf.set_string(popup_col, popup_row + i, "│", border_style);
+            buf.set_string(
+                popup_col + popup_width - 1,
+                popup_row + i,
+                "│",
+                border_style,
+            );
+        }
+        buf.set_string(
+            popup_col,
+            popup_row + popup_height - 1,
+            format!("└{}┘", "─".repeat(inner_width)),
+            border_style,
+        );
+
+        // Editor
+        let editor_area = Rect {
+            x: popup_col + 2,
+            y: popup_row + 1,
+            width: popup_width - 4,
+            height: popup_height - 3,
+        };
+        self.textarea
+            .set_block(ratatui::widgets::Block::default().style(background));
+        self.textarea.set_style(
+            Style::default()
+                .fg(theme.text_primary)
+                .bg(theme.browser_background),
+        );
+        self.textarea.render(editor_area, buf);
+
+        // Status bar
+        let status = if self.snippet.is_some() {
+            " Ctrl+S:Save  Esc:Cancel  (${name} asks for a value when run) ".to_string()
+        } else {
+            let count = self.lines().len();
+            let delay = match config.ui.compose_line_delay_ms {
+                0 => String::new(),
+                ms => format!(", {}ms apart", ms),
+            };
+            format!(
+                " Ctrl+S:Send {} line{}{}  Esc:Cancel ",
+                count,
+                if count == 1 { "" } else { "s" },
+                delay
+            )
+        };
+        let status: String = status.chars().take(popup_width as usize - 4).collect();
+        buf.set_string(
+            popup_col + 2,
+            popup_row + popup_height - 2,
+            status,
+            Style::default()
+                .fg(theme.menu_separator)
+                .bg(theme.browser_background),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    #[test]
+    fn test_composer_skips_blank_lines() {
+        let mut composer = Composer::new(vec!["say Hello there".to_string()]);
+        for key in [
+            KeyCode::Enter,
+            KeyCode::Enter,
+            KeyCode::Char('n'),
+            KeyCode::Char('o'),
+            KeyCode::Char('d'),
+        ] {
+            composer.input(KeyEvent::new(key, KeyModifiers::NONE));
+        }
+        assert_eq!(composer.lines(), vec!["say Hello there", "nod"]);
+    }
+
+    #[test]
+    fn test_empty_composer_has_no_lines() {
+        assert!(Composer::new(Vec::new()).lines().is_empty());
+    }
+}
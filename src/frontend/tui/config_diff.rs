@@ -0,0 +1,256 @@
+//! Review popup for highlight/keybind/color edits that haven't been written yet.
+//!
+//! Lists every entry that differs from the files on disk and shows a diff of
+//! the selected one. Entries can be discarded one at a time before saving.
+
+use crate::config::diff::{ChangeKind, ConfigChange};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    widgets::{Clear, Widget},
+};
+
+/// Rows given to the change list; the rest of the popup shows the diff
+const LIST_ROWS: usize = 7;
+
+pub struct ConfigDiffView {
+    changes: Vec<ConfigChange>,
+    selected_index: usize,
+    scroll_offset: usize,
+    popup_position: (u16, u16),
+}
+
+impl ConfigDiffView {
+    pub fn new(changes: Vec<ConfigChange>) -> Self {
+        Self {
+            changes,
+            selected_index: 0,
+            scroll_offset: 0,
+            popup_position: (0, 0),
+        }
+    }
+
+    /// Swap in a fresh change list (after a discard), keeping the selection in range
+    pub fn set_changes(&mut self, changes: Vec<ConfigChange>) {
+        self.changes = changes;
+        self.selected_index = self
+            .selected_index
+            .min(self.changes.len().saturating_sub(1));
+        self.scroll_offset = self.scroll_offset.min(self.selected_index);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub fn selected(&self) -> Option<&ConfigChange> {
+        self.changes.get(self.selected_index)
+    }
+
+    fn select(&mut self, index: usize) {
+        self.selected_index = index.min(self.changes.len().saturating_sub(1));
+        if self.selected_index < self.scroll_offset {
+            self.scroll_offset = self.selected_index;
+        } else if self.selected_index >= self.scroll_offset + LIST_ROWS {
+            self.scroll_offset = self.selected_index + 1 - LIST_ROWS;
+        }
+    }
+
+    pub fn render(
+        &mut self,
+        area: Rect,
+        buf: &mut Buffer,
+        _config: &crate::config::Config,
+        theme: &crate::theme::AppTheme,
+    ) {
+        let popup_width = 76.min(area.width);
+        let popup_height = 24.min(area.height);
+        if popup_width < 20 || popup_height < LIST_ROWS as u16 + 6 {
+            return;
+        }
+
+        // Center on first render
+        if self.popup_position == (0, 0) {
+            self.popup_position = (
+                (area.width.saturating_sub(popup_width)) / 2,
+                (area.height.saturating_sub(popup_height)) / 2,
+            );
+        }
+        let (popup_col, popup_row) = self.popup_position;
+        let popup_area = Rect {
+            x: popup_col,
+            y: popup_row,
+            width: popup_width,
+            height: popup_height,
+        }
+        .intersection(area);
+        Clear.render(popup_area, buf);
+
+        let background = Style::default().bg(theme.browser_background);
+        for row in popup_area.top()..popup_area.bottom() {
+            for col in popup_area.left()..popup_area.right() {
+                buf.set_string(col, row, " ", background);
+            }
+        }
+
+        // Border and title
+        let border_style = Style::default().fg(theme.browser_border);
+        let inner_width = popup_width as usize - 2;
+        buf.set_string(
+            popup_col,
+            popup_row,
+            format!("┌{}┐", "─".repeat(inner_width)),
+            border_style,
+        );
+        buf.set_string(
+            popup_col + 2,
+            popup_row,
+            " Unsaved Config Changes ",
+            border_style.add_modifier(Modifier::BOLD),
+        );
+        for i in 1..popup_height - 1 {
+            buf.set_string(popup_col, popup_row + i, "│", border_style);
+            buf.set_string(
+                popup_col + popup_width - 1,
+                popup_row + i,
+                "│",
+                border_style,
+            );
+        }
+        let separator_row = popup_row + 1 + LIST_ROWS as u16;
+        buf.set_string(
+            popup_col,
+            separator_row,
+            format!("├{}┤", "─".repeat(inner_width)),
+            border_style,
+        );
+        buf.set_string(
+            popup_col,
+            popup_row + popup_height - 1,
+            format!("└{}┘", "─".repeat(inner_width)),
+            border_style,
+        );
+
+        let text_x = popup_col + 2;
+        let text_width = popup_width as usize - 4;
+
+        // Change list
+        if self.changes.is_empty() {
+            buf.set_string(
+                text_x,
+                popup_row + 1,
+                "Nothing to save: memory matches the files on disk",
+                Style::default()
+                    .fg(theme.menu_separator)
+                    .bg(theme.browser_background),
+            );
+        }
+        for (row, (index, change)) in self
+            .changes
+            .iter()
+            .enumerate()
+            .skip(self.scroll_offset)
+            .take(LIST_ROWS)
+            .enumerate()
+        {
+            let style = if index == self.selected_index {
+                Style::default()
+                    .fg(theme.browser_item_focused)
+                    .bg(theme.browser_background)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+                    .fg(theme.browser_item_normal)
+                    .bg(theme.browser_background)
+            };
+            let line = format!(
+                "{} {:<16} {}",
+                change.kind.symbol(),
+                change.file.file_name(),
+                change.key()
+            );
+            buf.set_string(
+                text_x,
+                popup_row + 1 + row as u16,
+                truncate(&line, text_width),
+                style,
+            );
+        }
+
+        // Diff of the selected change
+        if let Some(change) = self.selected() {
+            let diff_rows = (popup_height as usize).saturating_sub(LIST_ROWS + 4);
+            for (row, (marker, text)) in change.diff_lines().into_iter().take(diff_rows).enumerate()
+            {
+                let fg = match marker {
+                    '+' => Color::Green,
+                    '-' => Color::Red,
+                    _ => theme.browser_item_normal,
+                };
+                let line = format!("{} {}", marker, text);
+                buf.set_string(
+                    text_x,
+                    separator_row + 1 + row as u16,
+                    truncate(&line, text_width),
+                    Style::default().fg(fg).bg(theme.browser_background),
+                );
+            }
+        }
+
+        // Status bar
+        let total = self.changes.len();
+        let current = if total == 0 {
+            0
+        } else {
+            self.selected_index + 1
+        };
+        let discard = match self.selected().map(|c| c.kind) {
+            Some(ChangeKind::Added) => "Del:Drop",
+            Some(_) => "Del:Revert",
+            None => "",
+        };
+        let status = format!(
+            " ↑/↓:Nav  Enter/Ctrl+S:Write  {}  Esc:Later  ({}/{}) ",
+            discard, current, total
+        );
+        buf.set_string(
+            text_x,
+            popup_row + popup_height - 2,
+            truncate(&status, text_width),
+            Style::default()
+                .fg(theme.menu_separator)
+                .bg(theme.browser_background),
+        );
+    }
+}
+
+fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        text.to_string()
+    } else {
+        let mut short: String = text.chars().take(width.saturating_sub(3)).collect();
+        short.push_str("...");
+        short
+    }
+}
+
+use super::widget_traits::Navigable;
+
+impl Navigable for ConfigDiffView {
+    fn navigate_up(&mut self) {
+        self.select(self.selected_index.saturating_sub(1));
+    }
+
+    fn navigate_down(&mut self) {
+        self.select(self.selected_index + 1);
+    }
+
+    fn page_up(&mut self) {
+        self.select(self.selected_index.saturating_sub(LIST_ROWS));
+    }
+
+    fn page_down(&mut self) {
+        self.select(self.selected_index + LIST_ROWS);
+    }
+}
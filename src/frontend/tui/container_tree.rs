@@ -0,0 +1,21 @@
+//! Containers rows: a tree of what is known to be in each container, with
+//! nested bags under the container holding them.
+//!
+//! Rows are rebuilt from `AppCore::containers` on every sync and drawn
+//! through a `ListWidget`.
+
+use super::list_widget::ListRows;
+use crate::data::ContainerTracker;
+
+/// Fill the tree from the tracker
+pub fn fill(rows: &mut ListRows, tracker: &ContainerTracker) {
+    let tree = tracker.tree();
+    if tree.is_empty() {
+        rows.row("empty", "LOOK IN a container to track it", None);
+    }
+    for row in tree {
+        let marker = if row.is_container { "+ " } else { "" };
+        let text = format!("{}{}{}", "  ".repeat(row.depth), marker, row.text);
+        rows.row(&row.key, &text, None);
+    }
+}
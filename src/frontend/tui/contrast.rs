@@ -0,0 +1,198 @@
+//! Minimum-contrast enforcement
+//!
+//! With `ui.min_contrast` above 0, every finished frame gets one pass that
+//! checks each RGB foreground against the background it is drawn on (the
+//! cell's own background, else the theme's window background) and nudges it
+//! toward white or black until the WCAG contrast ratio reaches the minimum.
+//! Because it runs on the rendered buffer it covers window text, highlights,
+//! presets and spell colors alike. Hue is kept as far as the ratio allows.
+
+use ratatui::buffer::Buffer;
+use ratatui::style::Color;
+use std::collections::HashMap;
+
+/// Highest ratio WCAG defines (black on white)
+pub const MAX_RATIO: f64 = 21.0;
+
+type Rgb = (u8, u8, u8);
+
+pub struct ContrastFilter {
+    min_ratio: f64,
+    /// (foreground, background) -> adjusted foreground, reused across frames
+    cache: HashMap<(Rgb, Rgb), Rgb>,
+}
+
+impl ContrastFilter {
+    pub fn new(min_ratio: f64) -> Self {
+        Self {
+            min_ratio: min_ratio.clamp(0.0, MAX_RATIO),
+            cache: HashMap::new(),
+        }
+    }
+
+    /// Change the minimum (0 or 1 turns enforcement off)
+    pub fn set_min_ratio(&mut self, min_ratio: f64) {
+        let min_ratio = min_ratio.clamp(0.0, MAX_RATIO);
+        if min_ratio != self.min_ratio {
+            self.min_ratio = min_ratio;
+            self.cache.clear();
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.min_ratio > 1.0
+    }
+
+    /// Adjust every low-contrast RGB foreground in a rendered frame
+    ///
+    /// `default_bg` stands in for cells without an RGB background of their own.
+    pub fn apply(&mut self, buffer: &mut Buffer, default_bg: Color) {
+        if !self.is_enabled() {
+            return;
+        }
+        let default_bg = rgb(default_bg).unwrap_or((0, 0, 0));
+        for cell in buffer.content.iter_mut() {
+            let Some(fg) = rgb(cell.fg) else { continue };
+            if cell.symbol() == " " {
+                continue;
+            }
+            let bg = rgb(cell.bg).unwrap_or(default_bg);
+            let min_ratio = self.min_ratio;
+            let (r, g, b) = *self
+                .cache
+                .entry((fg, bg))
+                .or_insert_with(|| adjust(fg, bg, min_ratio));
+            cell.fg = Color::Rgb(r, g, b);
+        }
+    }
+}
+
+fn rgb(color: Color) -> Option<Rgb> {
+    match color {
+        Color::Rgb(r, g, b) => Some((r, g, b)),
+        _ => None,
+    }
+}
+
+/// WCAG relative luminance
+fn luminance((r, g, b): Rgb) -> f64 {
+    let channel = |v: u8| {
+        let v = v as f64 / 255.0;
+        if v <= 0.03928 {
+            v / 12.92
+        } else {
+            ((v + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * channel(r) + 0.7152 * channel(g) + 0.0722 * channel(b)
+}
+
+/// WCAG contrast ratio between two colors (1.0 to 21.0)
+pub fn contrast_ratio(a: Rgb, b: Rgb) -> f64 {
+    let (la, lb) = (luminance(a), luminance(b));
+    let (light, dark) = if la > lb { (la, lb) } else { (lb, la) };
+    (light + 0.05) / (dark + 0.05)
+}
+
+fn mix(from: Rgb, to: Rgb, t: f64) -> Rgb {
+    let lerp = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+    (lerp(from.0, to.0), lerp(from.1, to.1), lerp(from.2, to.2))
+}
+
+/// The closest color to `fg` (mixed toward white or black) that reaches `min_ratio` on `bg`
+pub fn adjust(fg: Rgb, bg: Rgb, min_ratio: f64) -> Rgb {
+    if contrast_ratio(fg, bg) >= min_ratio {
+        return fg;
+    }
+    // Go whichever way has more room
+    let white = (255, 255, 255);
+    let black = (0, 0, 0);
+    let target = if contrast_ratio(white, bg) >= contrast_ratio(black, bg) {
+        white
+    } else {
+        black
+    };
+    if contrast_ratio(target, bg) < min_ratio {
+        return target;
+    }
+
+    // Smallest mix that gets there
+    let (mut low, mut high) = (0.0, 1.0);
+    for _ in 0..12 {
+        let t = (low + high) / 2.0;
+        if contrast_ratio(mix(fg, target, t), bg) >= min_ratio {
+            high = t;
+        } else {
+            low = t;
+        }
+    }
+    mix(fg, target, high)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::layout::Rect;
+    use ratatui::style::Style;
+
+    const BLACK: (u8, u8, u8) = (0, 0, 0);
+    const WHITE: (u8, u8, u8) = (255, 255, 255);
+
+    /// Dark text on the default background, dark text on its own white
+    /// background, and a blank cell
+    fn frame() -> Buffer {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 3, 1));
+        buffer.set_string(0, 0, "ab", Style::default().fg(Color::Rgb(20, 20, 20)));
+        buffer[(1, 0)].set_bg(Color::Rgb(255, 255, 255));
+        buffer[(2, 0)].set_fg(Color::Rgb(20, 20, 20));
+        buffer
+    }
+
+    #[test]
+    fn test_contrast_ratio() {
+        assert!((contrast_ratio(WHITE, BLACK) - 21.0).abs() < 0.01);
+        assert!((contrast_ratio(BLACK, BLACK) - 1.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_adjust_leaves_readable_colors() {
+        assert_eq!(adjust((200, 200, 200), BLACK, 4.5), (200, 200, 200));
+    }
+
+    #[test]
+    fn test_adjust_lightens_on_dark_background() {
+        let fixed = adjust((0, 0, 139), BLACK, 4.5);
+        assert!(contrast_ratio(fixed, BLACK) >= 4.5);
+        // Stays blue-ish
+        assert!(fixed.2 > fixed.0);
+    }
+
+    #[test]
+    fn test_adjust_darkens_on_light_background() {
+        let fixed = adjust((255, 255, 0), WHITE, 4.5);
+        assert!(contrast_ratio(fixed, WHITE) >= 4.5);
+        assert!(luminance(fixed) < luminance((255, 255, 0)));
+    }
+
+    #[test]
+    fn test_filter_off_at_zero_ratio() {
+        let mut buffer = frame();
+        ContrastFilter::new(0.0).apply(&mut buffer, Color::Rgb(0, 0, 0));
+        assert_eq!(buffer[(0, 0)].fg, Color::Rgb(20, 20, 20));
+    }
+
+    #[test]
+    fn test_filter_fixes_only_unreadable_text() {
+        let mut buffer = frame();
+        let mut filter = ContrastFilter::new(0.0);
+        filter.set_min_ratio(7.0);
+        filter.apply(&mut buffer, Color::Rgb(0, 0, 0));
+        let Color::Rgb(r, g, b) = buffer[(0, 0)].fg else {
+            panic!()
+        };
+        assert!(contrast_ratio((r, g, b), BLACK) >= 7.0);
+        assert_eq!(buffer[(1, 0)].fg, Color::Rgb(20, 20, 20));
+        // Blank cells are left alone
+        assert_eq!(buffer[(2, 0)].fg, Color::Rgb(20, 20, 20));
+    }
+}
@@ -0,0 +1,225 @@
+//! Keyboard copy mode
+//!
+//! For terminals without usable mouse support, the `copymode` action puts a
+//! cursor in the focused text window, tmux style: arrows or h/j/k/l move it,
+//! PageUp/PageDown a page at a time, Home/End (or 0/$) to the ends of the
+//! line, g/G to the oldest/newest line. `v` starts a selection at the cursor,
+//! Ctrl+V or `r` switches it to a block selection, `y` or Enter yanks it (or
+//! the cursor's line, if nothing is selected) and Esc or `q` leaves.
+//! Positions are wrapped display lines, the same space mouse selection uses.
+
+use crate::selection::SelectionState;
+use crossterm::event::{KeyCode, KeyModifiers};
+
+/// What a key did
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopyModeOutcome {
+    /// Cursor or selection changed
+    Moved,
+    /// Copy the selection and leave
+    Yank,
+    /// Leave without copying
+    Exit,
+    /// Not a copy mode key
+    Ignored,
+}
+
+pub struct CopyMode {
+    pub window_name: String,
+    window_index: usize,
+    pub line: usize,
+    pub col: usize,
+    /// Column to return to when moving through shorter lines
+    want_col: usize,
+    /// Where `v` was pressed
+    anchor: Option<(usize, usize)>,
+    block: bool,
+}
+
+impl CopyMode {
+    pub fn new(window_name: &str, window_index: usize, line: usize) -> Self {
+        Self {
+            window_name: window_name.to_string(),
+            window_index,
+            line,
+            col: 0,
+            want_col: 0,
+            anchor: None,
+            block: false,
+        }
+    }
+
+    /// Apply one key; `line_len` gives the character count of a wrapped line
+    pub fn handle_key(
+        &mut self,
+        code: KeyCode,
+        modifiers: KeyModifiers,
+        total_lines: usize,
+        page: usize,
+        line_len: impl Fn(usize) -> usize,
+    ) -> CopyModeOutcome {
+        let last_line = total_lines.saturating_sub(1);
+        let last_col = |line: usize| line_len(line).saturating_sub(1);
+        let ctrl = modifiers.contains(KeyModifiers::CONTROL);
+
+        match code {
+            KeyCode::Esc | KeyCode::Char('q') => return CopyModeOutcome::Exit,
+            KeyCode::Enter | KeyCode::Char('y') => return CopyModeOutcome::Yank,
+            KeyCode::Char('v') if ctrl => self.toggle_block(),
+            KeyCode::Char('r') => self.toggle_block(),
+            KeyCode::Char('v') => {
+                self.anchor = match self.anchor {
+                    Some(_) => None,
+                    None => Some((self.line, self.col)),
+                };
+                self.block = false;
+            }
+            KeyCode::Left | KeyCode::Char('h') => self.set_col(self.col.saturating_sub(1)),
+            KeyCode::Right | KeyCode::Char('l') => {
+                self.set_col((self.col + 1).min(last_col(self.line)))
+            }
+            KeyCode::Home | KeyCode::Char('0') => self.set_col(0),
+            KeyCode::End | KeyCode::Char('$') => self.set_col(last_col(self.line)),
+            KeyCode::Up | KeyCode::Char('k') => self.line = self.line.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => self.line = (self.line + 1).min(last_line),
+            KeyCode::PageUp => self.line = self.line.saturating_sub(page.max(1)),
+            KeyCode::PageDown => self.line = (self.line + page.max(1)).min(last_line),
+            KeyCode::Char('g') => self.line = 0,
+            KeyCode::Char('G') => self.line = last_line,
+            _ => return CopyModeOutcome::Ignored,
+        }
+        // Vertical moves keep the column the user was on where the line allows
+        self.col = self.want_col.min(last_col(self.line));
+        CopyModeOutcome::Moved
+    }
+
+    fn set_col(&mut self, col: usize) {
+        self.col = col;
+        self.want_col = col;
+    }
+
+    fn toggle_block(&mut self) {
+        if self.anchor.is_none() {
+            self.anchor = Some((self.line, self.col));
+        }
+        self.block = !self.block;
+    }
+
+    /// The selection between the anchor and the cursor (both ends included)
+    pub fn selection(&self) -> Option<SelectionState> {
+        let (anchor_line, anchor_col) = self.anchor?;
+        let index = self.window_index;
+        if self.block {
+            // Block columns run left..right exclusive, so widen by the cursor cell
+            let (left, right) = (anchor_col.min(self.col), anchor_col.max(self.col) + 1);
+            let mut selection = SelectionState::new_block(index, anchor_line, left);
+            selection.update_end(index, self.line, right);
+            return Some(selection);
+        }
+        let (first, last) = if (anchor_line, anchor_col) <= (self.line, self.col) {
+            ((anchor_line, anchor_col), (self.line, self.col))
+        } else {
+            ((self.line, self.col), (anchor_line, anchor_col))
+        };
+        let mut selection = SelectionState::new(index, first.0, first.1);
+        selection.update_end(index, last.0, last.1 + 1);
+        Some(selection)
+    }
+
+    /// What `y` copies: the selection, or the whole cursor line
+    pub fn yank_selection(&self, line_len: usize) -> SelectionState {
+        self.selection().unwrap_or_else(|| {
+            let mut line = SelectionState::new(self.window_index, self.line, 0);
+            line.update_end(self.window_index, self.line, line_len);
+            line
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LINES: [&str; 4] = [
+        "Your skills:",
+        "  Edged Weapons  42",
+        "",
+        "  Armor Use      30",
+    ];
+
+    fn len(line: usize) -> usize {
+        LINES.get(line).map_or(0, |l| l.chars().count())
+    }
+
+    fn key(mode: &mut CopyMode, code: KeyCode, modifiers: KeyModifiers) -> CopyModeOutcome {
+        mode.handle_key(code, modifiers, LINES.len(), 2, len)
+    }
+
+    #[test]
+    fn test_cursor_keeps_column_across_short_lines() {
+        let none = KeyModifiers::NONE;
+        let mut mode = CopyMode::new("main", 3, 3);
+        key(&mut mode, KeyCode::Char('$'), none);
+        assert_eq!((mode.line, mode.col), (3, 18));
+        key(&mut mode, KeyCode::Up, none);
+        assert_eq!((mode.line, mode.col), (2, 0));
+        key(&mut mode, KeyCode::Char('k'), none);
+        assert_eq!((mode.line, mode.col), (1, 18));
+        assert!(mode.selection().is_none());
+    }
+
+    #[test]
+    fn test_stream_selection_includes_both_ends() {
+        let none = KeyModifiers::NONE;
+        let mut mode = CopyMode::new("main", 3, 1);
+        key(&mut mode, KeyCode::Char('$'), none);
+        key(&mut mode, KeyCode::Char('v'), none);
+        key(&mut mode, KeyCode::Char('0'), none);
+        key(&mut mode, KeyCode::Up, none);
+        let selection = mode.selection().unwrap();
+        assert!(!selection.block);
+        assert_eq!(selection.start.window_index, 3);
+        assert_eq!((selection.start.line, selection.start.col), (0, 0));
+        assert_eq!((selection.end.line, selection.end.col), (1, 19));
+    }
+
+    #[test]
+    fn test_block_selection() {
+        let none = KeyModifiers::NONE;
+        let mut mode = CopyMode::new("main", 0, 1);
+        for code in [KeyCode::Right, KeyCode::Right] {
+            key(&mut mode, code, none);
+        }
+        key(&mut mode, KeyCode::Char('v'), KeyModifiers::CONTROL);
+        key(&mut mode, KeyCode::PageDown, none);
+        for _ in 0..12 {
+            key(&mut mode, KeyCode::Char('l'), none);
+        }
+        let selection = mode.selection().unwrap();
+        assert!(selection.block);
+        assert_eq!(selection.block_lines(), (1, 3));
+        assert_eq!(selection.block_columns(), (2, 15));
+    }
+
+    #[test]
+    fn test_yank_exit_and_ignored_keys() {
+        let none = KeyModifiers::NONE;
+        let mut mode = CopyMode::new("main", 0, 1);
+        assert_eq!(
+            key(&mut mode, KeyCode::Char('y'), none),
+            CopyModeOutcome::Yank
+        );
+        assert_eq!(key(&mut mode, KeyCode::Esc, none), CopyModeOutcome::Exit);
+        assert_eq!(
+            key(&mut mode, KeyCode::Char('x'), none),
+            CopyModeOutcome::Ignored
+        );
+    }
+
+    #[test]
+    fn test_yank_without_selection_takes_cursor_line() {
+        let mode = CopyMode::new("main", 0, 1);
+        let line = mode.yank_selection(len(1));
+        assert_eq!((line.start.col, line.end.col), (0, 19));
+    }
+}
@@ -21,6 +21,7 @@ pub struct Countdown {
     text_color: Option<String>,
     transparent_background: bool,
     icon: char, // Character to use for countdown blocks
+    plain_text: bool, // Screen reader mode: "label seconds", no blocks
 }
 
 impl Countdown {
@@ -34,6 +35,7 @@ impl Countdown {
             text_color: None,
             transparent_background: true,
             icon: '█', // Default to filled block
+            plain_text: false,
         }
     }
 
@@ -64,6 +66,10 @@ impl Countdown {
         self.transparent_background = transparent;
     }
 
+    pub fn set_plain_text(&mut self, plain: bool) {
+        self.plain_text = plain;
+    }
+
     pub fn set_end_time(&mut self, end_time: i64) {
         self.end_time = end_time;
     }
@@ -174,6 +180,22 @@ impl Countdown {
             return;
         }
 
+        if self.plain_text {
+            let text = format!("{} {}", self.label, remaining);
+            let mut style = Style::default().fg(text_color);
+            if let Some(bg) = bg_color {
+                style = style.bg(bg);
+            }
+            buf.set_stringn(
+                inner_area.x,
+                y,
+                text.trim(),
+                inner_area.width as usize,
+                style,
+            );
+            return;
+        }
+
         // Right-align the number so it doesn't shift when going from 10->9
         // Reserve 2 chars for the number + 1 for space = 3 total
         // Format: " 9 ████████" or "10 ████████"
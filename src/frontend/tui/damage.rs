@@ -0,0 +1,310 @@
+//! Damage tracking: redraw only the windows that changed
+//!
+//! A full redraw re-renders every widget. Most frames are caused by server
+//! text or the once-a-second countdown tick, which touch a handful of
+//! windows, so those frames start from a copy of the previous one and only
+//! re-render windows whose damage stamp moved. The stamp covers position,
+//! focus and content (the text generation for text windows, the data itself
+//! for small widgets), plus the current second for countdowns and the current
+//! minute for windows with relative timestamps. Input and UI changes
+//! (scrolling, popups, layout edits) still ask for a full redraw.
+
+use crate::data::{WindowContent, WindowState};
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+
+/// What else a window's look depends on besides its own data
+#[derive(Debug, Clone, Copy)]
+pub struct FrameClock<'a> {
+    /// Unix seconds (countdowns)
+    pub now_secs: i64,
+    /// Age-fading step length for text windows (0 = fading off)
+    pub fade_minutes: u32,
+    /// Text windows whose timestamps show line age ("3m")
+    pub relative_timestamps: &'a [String],
+}
+
+/// Stamps from the last frame and a copy of what it drew
+#[derive(Default)]
+pub struct DamageTracker {
+    stamps: HashMap<String, u64>,
+    last_frame: Option<Buffer>,
+}
+
+impl DamageTracker {
+    /// Windows to re-render this frame (None = redraw everything)
+    pub fn plan(
+        &mut self,
+        windows: &HashMap<String, WindowState>,
+        focused: Option<&str>,
+        screen: Rect,
+        full: bool,
+        clock: FrameClock<'_>,
+    ) -> Option<HashSet<String>> {
+        let stamps: HashMap<String, u64> = windows
+            .iter()
+            .filter(|(_, w)| w.visible)
+            .map(|(name, w)| {
+                (
+                    name.clone(),
+                    stamp(name, w, focused == Some(name.as_str()), clock),
+                )
+            })
+            .collect();
+        let previous = std::mem::replace(&mut self.stamps, stamps);
+
+        let same_windows = previous.len() == self.stamps.len()
+            && self.stamps.keys().all(|name| previous.contains_key(name));
+        let same_screen = self.last_frame.as_ref().is_some_and(|b| b.area == screen);
+        if full || !same_windows || !same_screen {
+            return None;
+        }
+
+        let damaged: HashSet<String> = self
+            .stamps
+            .iter()
+            .filter(|(name, stamp)| {
+                always_redraw(&windows[*name]) || previous.get(*name) != Some(stamp)
+            })
+            .map(|(name, _)| name.clone())
+            .collect();
+
+        // Overlapping windows paint over each other, so redrawing one alone isn't safe
+        let overlaps = damaged.iter().any(|name| {
+            let rect = rect(&windows[name]);
+            self.stamps
+                .keys()
+                .filter(|other| *other != name)
+                .any(|other| rect.intersects(self::rect(&windows[other])))
+        });
+        (!overlaps).then_some(damaged)
+    }
+
+    /// The previous frame, to start a partial redraw from
+    pub fn last_frame(&self) -> Option<&Buffer> {
+        self.last_frame.as_ref()
+    }
+
+    /// Keep the finished frame for the next partial redraw
+    pub fn store_frame(&mut self, frame: &Buffer) {
+        match self.last_frame.as_mut() {
+            Some(last) if last.area == frame.area => last.content.clone_from(&frame.content),
+            _ => self.last_frame = Some(frame.clone()),
+        }
+    }
+}
+
+fn rect(window: &WindowState) -> Rect {
+    let pos = &window.position;
+    Rect::new(pos.x, pos.y, pos.width, pos.height)
+}
+
+/// Widgets fed from state the stamp can't see (room components, tab buffers,
+/// art images, status line fields, tracker and plugin lists)
+fn always_redraw(window: &WindowState) -> bool {
+    matches!(
+        window.content,
+        WindowContent::Room(_)
+            | WindowContent::TabbedText(_)
+            | WindowContent::Art
+            | WindowContent::StatusLine
+            | WindowContent::LootTracker
+            | WindowContent::SessionStats
+            | WindowContent::BountyTracker
+            | WindowContent::ContainerTree
+            | WindowContent::WatchList
+            | WindowContent::Plugin
+    )
+}
+
+fn stamp(name: &str, window: &WindowState, focused: bool, clock: FrameClock<'_>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    let pos = &window.position;
+    (pos.x, pos.y, pos.width, pos.height, focused).hash(&mut hasher);
+    match &window.content {
+        WindowContent::Text(text) => {
+            (text.generation, &text.title).hash(&mut hasher);
+            if clock.fade_minutes > 0 {
+                (clock.now_secs / (60 * clock.fade_minutes as i64)).hash(&mut hasher);
+            }
+            if clock.relative_timestamps.iter().any(|w| w == name) {
+                (clock.now_secs / 60).hash(&mut hasher);
+            }
+        }
+        WindowContent::Inventory(text) | WindowContent::Spells(text) => {
+            (text.generation, &text.title).hash(&mut hasher);
+        }
+        WindowContent::Countdown(countdown) => {
+            format!("{:?}", countdown).hash(&mut hasher);
+            clock.now_secs.hash(&mut hasher);
+        }
+        // The border counts down roundtime and the title can show a timer
+        WindowContent::CommandInput { .. } => clock.now_secs.hash(&mut hasher),
+        content => format!("{:?}", content).hash(&mut hasher),
+    }
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{CountdownData, StyledLine, WidgetType, WindowPosition};
+
+    fn window(name: &str, y: u16, content: WindowContent) -> WindowState {
+        WindowState {
+            name: name.to_string(),
+            widget_type: WidgetType::Text,
+            content,
+            position: WindowPosition {
+                x: 0,
+                y,
+                width: 40,
+                height: 5,
+            },
+            visible: true,
+            focused: false,
+        }
+    }
+
+    const SCREEN: Rect = Rect {
+        x: 0,
+        y: 0,
+        width: 40,
+        height: 15,
+    };
+    const CLOCK: FrameClock<'static> = FrameClock {
+        now_secs: 1000,
+        fade_minutes: 0,
+        relative_timestamps: &[],
+    };
+    const LATER: FrameClock<'static> = FrameClock {
+        now_secs: 1001,
+        fade_minutes: 0,
+        relative_timestamps: &[],
+    };
+
+    /// A main window above a roundtime countdown, with one frame already drawn
+    fn setup() -> (DamageTracker, HashMap<String, WindowState>) {
+        let mut windows = HashMap::new();
+        windows.insert(
+            "main".to_string(),
+            window(
+                "main",
+                0,
+                WindowContent::Text(crate::data::TextContent::new("Main", 100)),
+            ),
+        );
+        windows.insert(
+            "roundtime".to_string(),
+            window(
+                "roundtime",
+                5,
+                WindowContent::Countdown(CountdownData {
+                    end_time: 1005,
+                    label: "RT".to_string(),
+                }),
+            ),
+        );
+        let mut tracker = DamageTracker::default();
+        tracker.store_frame(&Buffer::empty(SCREEN));
+        tracker.plan(&windows, None, SCREEN, false, CLOCK);
+        (tracker, windows)
+    }
+
+    fn add_line(windows: &mut HashMap<String, WindowState>) {
+        if let WindowContent::Text(text) = &mut windows.get_mut("main").unwrap().content {
+            text.add_line(StyledLine { segments: vec![] });
+        }
+    }
+
+    fn only(names: &[&str]) -> Option<HashSet<String>> {
+        Some(names.iter().map(|n| n.to_string()).collect())
+    }
+
+    #[test]
+    fn test_first_frame_redraws_everything() {
+        let (_, windows) = setup();
+        let mut tracker = DamageTracker::default();
+        assert_eq!(tracker.plan(&windows, None, SCREEN, false, CLOCK), None);
+    }
+
+    #[test]
+    fn test_unchanged_frame_redraws_nothing() {
+        let (mut tracker, windows) = setup();
+        assert_eq!(
+            tracker.plan(&windows, None, SCREEN, false, CLOCK),
+            only(&[])
+        );
+    }
+
+    #[test]
+    fn test_new_line_damages_only_its_window() {
+        let (mut tracker, mut windows) = setup();
+        add_line(&mut windows);
+        assert_eq!(
+            tracker.plan(&windows, None, SCREEN, false, CLOCK),
+            only(&["main"])
+        );
+    }
+
+    #[test]
+    fn test_clock_damages_countdowns() {
+        let (mut tracker, windows) = setup();
+        assert_eq!(
+            tracker.plan(&windows, None, SCREEN, false, LATER),
+            only(&["roundtime"])
+        );
+    }
+
+    #[test]
+    fn test_clock_damages_relative_timestamps() {
+        let (mut tracker, windows) = setup();
+        let relative = ["main".to_string()];
+        let clock = |now_secs| FrameClock {
+            now_secs,
+            fade_minutes: 0,
+            relative_timestamps: &relative,
+        };
+        tracker.plan(&windows, None, SCREEN, false, clock(1000));
+        // Labels count clock minutes, so main's change when the minute turns at 1020
+        assert_eq!(
+            tracker.plan(&windows, None, SCREEN, false, clock(1019)),
+            only(&["roundtime"])
+        );
+        assert_eq!(
+            tracker.plan(&windows, None, SCREEN, false, clock(1020)),
+            only(&["main", "roundtime"])
+        );
+    }
+
+    #[test]
+    fn test_focus_change_damages_window() {
+        let (mut tracker, windows) = setup();
+        assert_eq!(
+            tracker.plan(&windows, Some("main"), SCREEN, false, CLOCK),
+            only(&["main"])
+        );
+    }
+
+    #[test]
+    fn test_full_redraw_and_resize_redraw_everything() {
+        let (mut tracker, windows) = setup();
+        assert_eq!(tracker.plan(&windows, None, SCREEN, true, CLOCK), None);
+        assert_eq!(
+            tracker.plan(&windows, None, Rect::new(0, 0, 80, 24), false, CLOCK),
+            None
+        );
+    }
+
+    #[test]
+    fn test_overlapping_windows_redraw_everything() {
+        let (mut tracker, mut windows) = setup();
+        windows.get_mut("roundtime").unwrap().position.y = 3;
+        tracker.plan(&windows, None, SCREEN, false, CLOCK);
+        add_line(&mut windows);
+        assert_eq!(tracker.plan(&windows, None, SCREEN, false, CLOCK), None);
+    }
+}
@@ -0,0 +1,328 @@
+//! Inline terminal graphics: kitty and sixel output for art windows
+//!
+//! Images are decoded once, scaled to the window and written straight to the
+//! terminal after ratatui finishes a frame (the same way the bell is rung).
+//! Terminals without either protocol get a half-block rendering instead,
+//! drawn into the ratatui buffer like any other widget.
+
+use base64::Engine;
+use image::imageops::FilterType;
+use image::RgbaImage;
+use ratatui::layout::Rect;
+use ratatui::style::Color;
+use std::io::Write;
+
+/// Cell size assumed when the terminal won't report its pixel size
+const FALLBACK_CELL_PX: (u32, u32) = (8, 16);
+
+/// Largest base64 payload kitty accepts per escape sequence
+const KITTY_CHUNK: usize = 4096;
+
+/// How images reach the screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphicsProtocol {
+    Kitty,
+    Sixel,
+    /// Half-block characters in the normal buffer
+    Text,
+}
+
+impl GraphicsProtocol {
+    /// Resolve the `ui.graphics` setting ("auto", "kitty", "sixel", "off")
+    pub fn from_setting(setting: &str) -> Self {
+        match setting.to_lowercase().as_str() {
+            "kitty" => GraphicsProtocol::Kitty,
+            "sixel" => GraphicsProtocol::Sixel,
+            "off" | "text" | "none" => GraphicsProtocol::Text,
+            _ => Self::detect(|key| std::env::var(key).ok()),
+        }
+    }
+
+    /// Guess from the environment; anything unrecognised (or tmux, which
+    /// needs passthrough) falls back to text
+    fn detect(env: impl Fn(&str) -> Option<String>) -> Self {
+        if env("TMUX").is_some() {
+            return GraphicsProtocol::Text;
+        }
+        let term = env("TERM").unwrap_or_default().to_lowercase();
+        let program = env("TERM_PROGRAM").unwrap_or_default().to_lowercase();
+        if env("KITTY_WINDOW_ID").is_some()
+            || term.contains("kitty")
+            || term.contains("ghostty")
+            || matches!(program.as_str(), "wezterm" | "ghostty")
+        {
+            return GraphicsProtocol::Kitty;
+        }
+        if ["sixel", "foot", "mlterm", "contour", "yaft"]
+            .iter()
+            .any(|t| term.contains(t))
+        {
+            return GraphicsProtocol::Sixel;
+        }
+        GraphicsProtocol::Text
+    }
+}
+
+/// Pixel size of one terminal cell
+pub fn cell_pixels() -> (u32, u32) {
+    match crossterm::terminal::window_size() {
+        Ok(size) if size.width > 0 && size.height > 0 && size.columns > 0 && size.rows > 0 => (
+            (size.width / size.columns).max(1) as u32,
+            (size.height / size.rows).max(1) as u32,
+        ),
+        _ => FALLBACK_CELL_PX,
+    }
+}
+
+/// Scale `image` to fit inside `area` (keeping its aspect ratio) and return
+/// it with the cells it covers, centred in `area`
+pub fn fit(image: &RgbaImage, area: Rect, cell: (u32, u32)) -> (RgbaImage, Rect) {
+    let max_w = area.width as u32 * cell.0;
+    let max_h = area.height as u32 * cell.1;
+    let scale = (max_w as f64 / image.width().max(1) as f64)
+        .min(max_h as f64 / image.height().max(1) as f64);
+    let w = ((image.width() as f64 * scale) as u32).clamp(1, max_w.max(1));
+    let h = ((image.height() as f64 * scale) as u32).clamp(1, max_h.max(1));
+    let scaled = image::imageops::resize(image, w, h, FilterType::Triangle);
+
+    let cols = w.div_ceil(cell.0).min(area.width as u32) as u16;
+    let rows = h.div_ceil(cell.1).min(area.height as u32) as u16;
+    let placed = Rect::new(
+        area.x + (area.width - cols) / 2,
+        area.y + (area.height - rows) / 2,
+        cols,
+        rows,
+    );
+    (scaled, placed)
+}
+
+/// Kitty graphics: send the pixels (zlib-compressed RGBA) under `id` and
+/// place them at the cursor, below the text layer so popups stay readable
+pub fn kitty_transmit(id: u32, image: &RgbaImage, cells: Rect) -> Vec<u8> {
+    let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::fast());
+    let _ = encoder.write_all(image.as_raw());
+    let compressed = encoder.finish().unwrap_or_default();
+    let payload = base64::engine::general_purpose::STANDARD.encode(compressed);
+
+    let mut out = Vec::with_capacity(payload.len() + 64);
+    let chunks: Vec<&[u8]> = payload.as_bytes().chunks(KITTY_CHUNK).collect();
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = u8::from(i + 1 < chunks.len());
+        if i == 0 {
+            let _ = write!(
+                out,
+                "\x1b_Ga=T,f=32,o=z,q=2,i={},p=1,z=-1,s={},v={},c={},r={},m={};",
+                id,
+                image.width(),
+                image.height(),
+                cells.width,
+                cells.height,
+                more
+            );
+        } else {
+            let _ = write!(out, "\x1b_Gm={};", more);
+        }
+        out.extend_from_slice(chunk);
+        out.extend_from_slice(b"\x1b\\");
+    }
+    out
+}
+
+/// Kitty graphics: show an already-sent image again at the cursor
+pub fn kitty_place(id: u32, cells: Rect) -> Vec<u8> {
+    format!(
+        "\x1b_Ga=p,q=2,i={},p=1,z=-1,c={},r={}\x1b\\",
+        id, cells.width, cells.height
+    )
+    .into_bytes()
+}
+
+/// Kitty graphics: forget an image and its placements
+pub fn kitty_delete(id: u32) -> Vec<u8> {
+    format!("\x1b_Ga=d,d=I,q=2,i={}\x1b\\", id).into_bytes()
+}
+
+/// Sixel: quantise to the 6x6x6 color cube and run-length encode in
+/// six-row bands. Pixels under half alpha are left transparent.
+pub fn sixel_encode(image: &RgbaImage) -> Vec<u8> {
+    let (w, h) = image.dimensions();
+    let mut out = Vec::new();
+    let _ = write!(out, "\x1bP0;1;0q\"1;1;{};{}", w, h);
+    for index in 0..216u32 {
+        let level = |v: u32| v * 100 / 5;
+        let _ = write!(
+            out,
+            "#{};2;{};{};{}",
+            index,
+            level(index / 36),
+            level(index / 6 % 6),
+            level(index % 6)
+        );
+    }
+
+    let cube = |v: u8| (v as u32 * 5 + 127) / 255;
+    let indices: Vec<Option<u8>> = image
+        .pixels()
+        .map(|p| (p[3] >= 128).then(|| (cube(p[0]) * 36 + cube(p[1]) * 6 + cube(p[2])) as u8))
+        .collect();
+
+    for band in (0..h).step_by(6) {
+        let mut used = [false; 216];
+        for y in band..(band + 6).min(h) {
+            for x in 0..w {
+                if let Some(i) = indices[(y * w + x) as usize] {
+                    used[i as usize] = true;
+                }
+            }
+        }
+        let mut first = true;
+        for color in (0..216u8).filter(|c| used[*c as usize]) {
+            if !first {
+                out.push(b'$');
+            }
+            first = false;
+            let _ = write!(out, "#{}", color);
+            let mut run: Option<(u8, u32)> = None;
+            for x in 0..w {
+                let mut bits = 0u8;
+                for dy in 0..6 {
+                    let y = band + dy;
+                    if y < h && indices[(y * w + x) as usize] == Some(color) {
+                        bits |= 1 << dy;
+                    }
+                }
+                let ch = b'?' + bits;
+                run = match run {
+                    Some((c, n)) if c == ch => Some((c, n + 1)),
+                    Some((c, n)) => {
+                        push_sixel_run(&mut out, c, n);
+                        Some((ch, 1))
+                    }
+                    None => Some((ch, 1)),
+                };
+            }
+            if let Some((c, n)) = run {
+                push_sixel_run(&mut out, c, n);
+            }
+        }
+        out.push(b'-');
+    }
+    out.extend_from_slice(b"\x1b\\");
+    out
+}
+
+fn push_sixel_run(out: &mut Vec<u8>, ch: u8, count: u32) {
+    if count > 3 {
+        let _ = write!(out, "!{}", count);
+        out.push(ch);
+    } else {
+        out.extend(std::iter::repeat_n(ch, count as usize));
+    }
+}
+
+/// Text fallback: each cell shows two stacked pixels as "▀" (top pixel in
+/// the foreground, bottom in the background). Rows are top to bottom.
+pub fn half_blocks(image: &RgbaImage, area: Rect) -> (Rect, Vec<Vec<(Color, Color)>>) {
+    // Half-block pixels are roughly square, so fit against a 1x2 cell
+    let (scaled, placed) = fit(image, area, (1, 2));
+    let pixel = |x: u32, y: u32| -> Color {
+        if x >= scaled.width() || y >= scaled.height() {
+            return Color::Reset;
+        }
+        let p = scaled.get_pixel(x, y);
+        if p[3] < 128 {
+            Color::Reset
+        } else {
+            Color::Rgb(p[0], p[1], p[2])
+        }
+    };
+    let rows = (0..placed.height as u32)
+        .map(|row| {
+            (0..placed.width as u32)
+                .map(|col| (pixel(col, row * 2), pixel(col, row * 2 + 1)))
+                .collect()
+        })
+        .collect();
+    (placed, rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    fn env(vars: &'static [(&'static str, &'static str)]) -> impl Fn(&str) -> Option<String> {
+        move |key: &str| {
+            vars.iter()
+                .find(|(k, _)| *k == key)
+                .map(|(_, v)| v.to_string())
+        }
+    }
+
+    fn red(width: u32, height: u32) -> RgbaImage {
+        RgbaImage::from_pixel(width, height, Rgba([255, 0, 0, 255]))
+    }
+
+    #[test]
+    fn test_detect_protocol() {
+        assert_eq!(
+            GraphicsProtocol::detect(env(&[("KITTY_WINDOW_ID", "1")])),
+            GraphicsProtocol::Kitty
+        );
+        assert_eq!(
+            GraphicsProtocol::detect(env(&[("TERM", "foot")])),
+            GraphicsProtocol::Sixel
+        );
+        assert_eq!(
+            GraphicsProtocol::detect(env(&[("TERM", "xterm-256color")])),
+            GraphicsProtocol::Text
+        );
+    }
+
+    #[test]
+    fn test_detect_falls_back_to_text_inside_tmux() {
+        assert_eq!(
+            GraphicsProtocol::detect(env(&[("TERM", "xterm-kitty"), ("TMUX", "/tmp/t")])),
+            GraphicsProtocol::Text
+        );
+    }
+
+    #[test]
+    fn test_protocol_from_setting() {
+        assert_eq!(
+            GraphicsProtocol::from_setting("off"),
+            GraphicsProtocol::Text
+        );
+    }
+
+    #[test]
+    fn test_fit_keeps_aspect_centred() {
+        // 1x1 pixel cells
+        let (scaled, placed) = fit(&red(4, 2), Rect::new(0, 0, 10, 10), (1, 1));
+        assert_eq!(scaled.dimensions(), (10, 5));
+        assert_eq!(placed, Rect::new(0, 2, 10, 5));
+    }
+
+    #[test]
+    fn test_sixel_encode() {
+        // Red in the cube is index 180; one band, six rows set, run-length encoded
+        let sixel = String::from_utf8(sixel_encode(&red(5, 6))).unwrap();
+        assert!(sixel.starts_with("\x1bP0;1;0q\"1;1;5;6"));
+        assert!(sixel.ends_with("#180!5~-\x1b\\"));
+    }
+
+    #[test]
+    fn test_kitty_transmit() {
+        let kitty =
+            String::from_utf8(kitty_transmit(7, &red(4, 2), Rect::new(0, 0, 2, 1))).unwrap();
+        assert!(kitty.starts_with("\x1b_Ga=T,f=32,o=z,q=2,i=7,p=1,z=-1,s=4,v=2,c=2,r=1,m=0;"));
+        assert!(kitty.ends_with("\x1b\\"));
+    }
+
+    #[test]
+    fn test_half_blocks() {
+        let (placed, cells) = half_blocks(&red(4, 2), Rect::new(0, 0, 4, 4));
+        assert_eq!(placed, Rect::new(0, 1, 4, 1));
+        assert_eq!(cells[0][0], (Color::Rgb(255, 0, 0), Color::Rgb(255, 0, 0)));
+    }
+}
@@ -1,7 +1,7 @@
 //! Popup form for creating, editing, and validating highlight patterns.
 //!
 //! Mirrors the VellumFE workflow: regex pattern entry, optional colors/sounds,
-//! and checkbox flags for rendering behavior.
+//! limits on when the sound fires, and checkbox flags for rendering behavior.
 
 use crate::config::{Config, HighlightPattern};
 use ratatui::{
@@ -25,7 +25,7 @@ pub enum FormMode {
 pub enum FormResult {
     Save {
         name: String,
-        pattern: HighlightPattern,
+        pattern: Box<HighlightPattern>,
     },
     Delete {
         name: String,
@@ -43,16 +43,27 @@ pub struct HighlightFormWidget {
     bg_color: TextArea<'static>,
     sound: TextArea<'static>,
     sound_volume: TextArea<'static>,
+    sound_cooldown: TextArea<'static>,
+    max_fires: TextArea<'static>,
+    quiet_hours: TextArea<'static>,
+
+    // Window/stream scope and sound category (not editable in the form, preserved across edits)
+    windows: Vec<String>,
+    sound_category: Option<String>,
 
     // Checkbox states
     bold: bool,
     color_entire_line: bool,
     fast_parse: bool,
+    only_when_unfocused: bool,
+    bell: bool,
+    flash: bool,
 
     // Form state
-    focused_field: usize, // 0-9: which field has focus (0-6 text, 7-9 checkboxes)
+    focused_field: usize, // 0-15: which field has focus (0-9 text, 10-15 checkboxes)
     status_message: String,
     pattern_error: Option<String>,
+    quiet_hours_error: Option<String>,
     mode: FormMode,
 
     // Sound dropdown
@@ -129,6 +140,18 @@ impl HighlightFormWidget {
         sound_volume.set_cursor_line_style(Style::default());
         sound_volume.set_placeholder_text("0.0-1.0 (e.g., 0.8)");
 
+        let mut sound_cooldown = TextArea::default();
+        sound_cooldown.set_cursor_line_style(Style::default());
+        sound_cooldown.set_placeholder_text("(optional)");
+
+        let mut max_fires = TextArea::default();
+        max_fires.set_cursor_line_style(Style::default());
+        max_fires.set_placeholder_text("(unlimited)");
+
+        let mut quiet_hours = TextArea::default();
+        quiet_hours.set_cursor_line_style(Style::default());
+        quiet_hours.set_placeholder_text("22:00-07:00");
+
         Self {
             name,
             pattern,
@@ -137,12 +160,21 @@ impl HighlightFormWidget {
             bg_color,
             sound,
             sound_volume,
+            sound_cooldown,
+            max_fires,
+            quiet_hours,
+            windows: Vec::new(),
+            sound_category: None,
             bold: false,
             color_entire_line: false,
             fast_parse: false,
+            only_when_unfocused: false,
+            bell: false,
+            flash: false,
             focused_field: 0,
             status_message: "Ready".to_string(),
             pattern_error: None,
+            quiet_hours_error: None,
             mode: FormMode::Create,
             sound_files: Self::load_sound_files(),
             sound_file_index: 0, // Default to "none"
@@ -166,6 +198,9 @@ impl HighlightFormWidget {
         form.pattern = TextArea::from([pattern.pattern.clone()]);
         form.pattern.set_cursor_line_style(Style::default());
 
+        form.windows = pattern.windows.clone();
+        form.sound_category = pattern.sound_category.clone();
+
         if let Some(ref cat) = pattern.category {
             form.category = TextArea::from([cat.clone()]);
             form.category.set_cursor_line_style(Style::default());
@@ -196,9 +231,27 @@ impl HighlightFormWidget {
             form.sound_volume.set_cursor_line_style(Style::default());
         }
 
+        if let Some(cooldown) = pattern.sound_cooldown_ms {
+            form.sound_cooldown = TextArea::from([cooldown.to_string()]);
+            form.sound_cooldown.set_cursor_line_style(Style::default());
+        }
+
+        if let Some(max) = pattern.max_fires {
+            form.max_fires = TextArea::from([max.to_string()]);
+            form.max_fires.set_cursor_line_style(Style::default());
+        }
+
+        if let Some(ref hours) = pattern.quiet_hours {
+            form.quiet_hours = TextArea::from([hours.clone()]);
+            form.quiet_hours.set_cursor_line_style(Style::default());
+        }
+
         form.bold = pattern.bold;
         form.color_entire_line = pattern.color_entire_line;
         form.fast_parse = pattern.fast_parse;
+        form.only_when_unfocused = pattern.only_when_unfocused;
+        form.bell = pattern.bell;
+        form.flash = pattern.flash;
 
         form.status_message = "Editing highlight".to_string();
         form
@@ -211,13 +264,13 @@ impl HighlightFormWidget {
 
     /// Move focus to next field
     pub fn focus_next(&mut self) {
-        self.focused_field = (self.focused_field + 1) % 10;
+        self.focused_field = (self.focused_field + 1) % 16;
     }
 
     /// Move focus to previous field
     pub fn focus_prev(&mut self) {
         self.focused_field = if self.focused_field == 0 {
-            9
+            15
         } else {
             self.focused_field - 1
         };
@@ -288,14 +341,9 @@ impl HighlightFormWidget {
                 // Ctrl+s to save
                 self.save_internal()
             }
-            KeyCode::Char(' ') | KeyCode::Enter if (7..=9).contains(&self.focused_field) => {
-                // Toggle checkboxes (fields 7-9)
-                match self.focused_field {
-                    7 => self.bold = !self.bold,
-                    8 => self.color_entire_line = !self.color_entire_line,
-                    9 => self.fast_parse = !self.fast_parse,
-                    _ => {}
-                }
+            KeyCode::Char(' ') | KeyCode::Enter if (10..=15).contains(&self.focused_field) => {
+                // Toggle checkboxes (fields 10-15)
+                self.toggle_focused();
                 None
             }
             KeyCode::Char('d') | KeyCode::Char('D')
@@ -319,6 +367,9 @@ impl HighlightFormWidget {
                     4 => &mut self.bg_color,
                     5 => &mut self.sound,
                     6 => &mut self.sound_volume,
+                    7 => &mut self.sound_cooldown,
+                    8 => &mut self.max_fires,
+                    9 => &mut self.quiet_hours,
                     _ => return None,
                 };
                 textarea.select_all();
@@ -341,6 +392,13 @@ impl HighlightFormWidget {
                     4 => self.bg_color.input(rt_key.clone()),
                     5 => self.sound.input(rt_key.clone()),
                     6 => self.sound_volume.input(rt_key.clone()),
+                    7 => self.sound_cooldown.input(rt_key),
+                    8 => self.max_fires.input(rt_key),
+                    9 => {
+                        let result = self.quiet_hours.input(rt_key);
+                        self.validate_quiet_hours();
+                        result
+                    }
                     _ => false,
                 };
 
@@ -374,6 +432,26 @@ impl HighlightFormWidget {
         }
     }
 
+    /// Validate the quiet hours range (empty = none)
+    fn validate_quiet_hours(&mut self) {
+        let hours = self.quiet_hours.lines()[0].as_str().trim();
+        if hours.is_empty() {
+            self.quiet_hours_error = None;
+            return;
+        }
+
+        match crate::core::trigger_gate::parse_quiet_hours(hours) {
+            Ok(_) => {
+                self.quiet_hours_error = None;
+                self.status_message = "Quiet hours valid".to_string();
+            }
+            Err(e) => {
+                self.quiet_hours_error = Some(e.to_string());
+                self.status_message = "Invalid quiet hours!".to_string();
+            }
+        }
+    }
+
     /// Internal save logic (called by Saveable trait implementation)
     fn save_internal(&self) -> Option<FormResult> {
         // Validate required fields
@@ -388,8 +466,8 @@ impl HighlightFormWidget {
             return None;
         }
 
-        // Check pattern is valid
-        if self.pattern_error.is_some() {
+        // Check pattern and quiet hours are valid
+        if self.pattern_error.is_some() || self.quiet_hours_error.is_some() {
             return None;
         }
 
@@ -430,6 +508,13 @@ impl HighlightFormWidget {
             }
         };
 
+        let sound_cooldown_ms = self.sound_cooldown.lines()[0].trim().parse::<u64>().ok();
+        let max_fires = self.max_fires.lines()[0].trim().parse::<u32>().ok();
+        let quiet_hours = {
+            let hours = self.quiet_hours.lines()[0].as_str().trim();
+            (!hours.is_empty()).then(|| hours.to_string())
+        };
+
         let category = {
             let cat_text = self.category.lines()[0].as_str().trim();
             if cat_text.is_empty() {
@@ -449,12 +534,20 @@ impl HighlightFormWidget {
             fast_parse: self.fast_parse,
             sound,
             sound_volume,
+            sound_category: self.sound_category.clone(),
+            sound_cooldown_ms,
+            max_fires,
+            only_when_unfocused: self.only_when_unfocused,
+            bell: self.bell,
+            flash: self.flash,
+            quiet_hours,
+            windows: self.windows.clone(),
             compiled_regex: None, // Will be compiled when config is loaded
         };
 
         Some(FormResult::Save {
             name: name.to_string(),
-            pattern,
+            pattern: Box::new(pattern),
         })
     }
 
@@ -467,7 +560,7 @@ impl HighlightFormWidget {
         theme: &crate::theme::AppTheme,
     ) {
         let width = 62;
-        let height = 20; // Reduced from 40 to fit style guide pattern
+        let height = 22; // Reduced from 40 to fit style guide pattern
 
         // Center popup initially
         if self.popup_x == 0 && self.popup_y == 0 {
@@ -733,117 +826,82 @@ impl HighlightFormWidget {
             buf,
             &theme,
         );
-        current_y += 2;
+        current_y += 1;
 
-        // Checkboxes (Fields 7-9)
-        buf[(x + 2, current_y)]
-            .set_char('[')
-            .set_fg(if self.focused_field == 7 {
-                theme.form_label_focused
-            } else {
-                theme.form_label
-            })
-            .set_bg(theme.browser_background);
-        buf[(x + 3, current_y)]
-            .set_char(if self.bold { '✓' } else { ' ' })
-            .set_fg(if self.focused_field == 7 {
-                theme.form_label_focused
-            } else {
-                theme.form_label
-            })
-            .set_bg(theme.browser_background);
-        buf[(x + 4, current_y)]
-            .set_char(']')
-            .set_fg(if self.focused_field == 7 {
-                theme.form_label_focused
-            } else {
-                theme.form_label
-            })
-            .set_bg(theme.browser_background);
-        let bold_label = " Bold";
-        for (i, ch) in bold_label.chars().enumerate() {
-            buf[(x + 5 + i as u16, current_y)]
-                .set_char(ch)
-                .set_fg(if self.focused_field == 7 {
-                    theme.form_label_focused
-                } else {
-                    theme.form_label
-                })
-                .set_bg(theme.browser_background);
-        }
+        // Field 7: Cooldown
+        Self::render_text_row(
+            focused_field,
+            7,
+            "Cooldown (ms):",
+            &mut self.sound_cooldown,
+            "2000",
+            x + 2,
+            current_y,
+            input_start,
+            10,
+            txtbg,
+            buf,
+            theme,
+        );
         current_y += 1;
 
-        buf[(x + 2, current_y)]
-            .set_char('[')
-            .set_fg(if self.focused_field == 8 {
-                theme.form_label_focused
-            } else {
-                theme.form_label
-            })
-            .set_bg(theme.browser_background);
-        buf[(x + 3, current_y)]
-            .set_char(if self.color_entire_line { '✓' } else { ' ' })
-            .set_fg(if self.focused_field == 8 {
-                theme.form_label_focused
-            } else {
-                theme.form_label
-            })
-            .set_bg(theme.browser_background);
-        buf[(x + 4, current_y)]
-            .set_char(']')
-            .set_fg(if self.focused_field == 8 {
-                theme.form_label_focused
-            } else {
-                theme.form_label
-            })
-            .set_bg(theme.browser_background);
-        let cel_label = " Color entire line";
-        for (i, ch) in cel_label.chars().enumerate() {
-            buf[(x + 5 + i as u16, current_y)]
-                .set_char(ch)
-                .set_fg(if self.focused_field == 8 {
-                    theme.form_label_focused
-                } else {
-                    theme.form_label
-                })
-                .set_bg(theme.browser_background);
-        }
+        // Field 8: Max fires per session
+        Self::render_text_row(
+            focused_field,
+            8,
+            "Max fires:",
+            &mut self.max_fires,
+            "1",
+            x + 2,
+            current_y,
+            input_start,
+            10,
+            txtbg,
+            buf,
+            theme,
+        );
         current_y += 1;
 
-        buf[(x + 2, current_y)]
-            .set_char('[')
-            .set_fg(if self.focused_field == 9 {
-                theme.form_label_focused
-            } else {
-                theme.form_label
-            })
-            .set_bg(theme.browser_background);
-        buf[(x + 3, current_y)]
-            .set_char(if self.fast_parse { '✓' } else { ' ' })
-            .set_fg(if self.focused_field == 9 {
-                theme.form_label_focused
-            } else {
-                theme.form_label
-            })
-            .set_bg(theme.browser_background);
-        buf[(x + 4, current_y)]
-            .set_char(']')
-            .set_fg(if self.focused_field == 9 {
+        // Field 9: Quiet hours
+        Self::render_text_row(
+            focused_field,
+            9,
+            "Quiet hours:",
+            &mut self.quiet_hours,
+            "22:00-07:00",
+            x + 2,
+            current_y,
+            input_start,
+            12,
+            txtbg,
+            buf,
+            theme,
+        );
+        current_y += 2;
+
+        // Checkboxes (Fields 10-15)
+        let checkboxes = [
+            (10, self.bold, " Bold"),
+            (11, self.color_entire_line, " Color entire line"),
+            (12, self.fast_parse, " Fast parse"),
+            (13, self.only_when_unfocused, " Sound only when unfocused"),
+            (14, self.bell, " Ring terminal bell"),
+            (15, self.flash, " Flash window border"),
+        ];
+        for (field_id, checked, label) in checkboxes {
+            let color = if self.focused_field == field_id {
                 theme.form_label_focused
             } else {
                 theme.form_label
-            })
-            .set_bg(theme.browser_background);
-        let fp_label = " Fast parse";
-        for (i, ch) in fp_label.chars().enumerate() {
-            buf[(x + 5 + i as u16, current_y)]
-                .set_char(ch)
-                .set_fg(if self.focused_field == 9 {
-                    theme.form_label_focused
-                } else {
-                    theme.form_label
-                })
-                .set_bg(theme.browser_background);
+            };
+            let text = format!("[{}]{}", if checked { '✓' } else { ' ' }, label);
+            for (i, ch) in text.chars().enumerate() {
+                buf[(x + 2 + i as u16, current_y)]
+                    .set_char(ch)
+                    .set_fg(color)
+                    .set_bg(theme.browser_background);
+            }
+            current_y += 1;
         }
     }
 
@@ -1026,7 +1084,7 @@ impl HighlightFormWidget {
     /// Handle mouse events for dragging
     pub fn handle_mouse(&mut self, col: u16, row: u16, pressed: bool, terminal_area: Rect) -> bool {
         let popup_width = 62;
-        let popup_height = 20;
+        let popup_height = 22;
 
         let popup_area = Rect {
             x: self.popup_x,
@@ -1082,6 +1140,9 @@ impl TextEditable for HighlightFormWidget {
             4 => Some(&self.bg_color),
             5 => Some(&self.sound),
             6 => Some(&self.sound_volume),
+            7 => Some(&self.sound_cooldown),
+            8 => Some(&self.max_fires),
+            9 => Some(&self.quiet_hours),
             _ => None,
         }
     }
@@ -1095,6 +1156,9 @@ impl TextEditable for HighlightFormWidget {
             4 => Some(&mut self.bg_color),
             5 => Some(&mut self.sound),
             6 => Some(&mut self.sound_volume),
+            7 => Some(&mut self.sound_cooldown),
+            8 => Some(&mut self.max_fires),
+            9 => Some(&mut self.quiet_hours),
             _ => None,
         }
     }
@@ -1110,7 +1174,7 @@ impl FieldNavigable for HighlightFormWidget {
     }
 
     fn field_count(&self) -> usize {
-        10
+        16
     }
 
     fn current_field(&self) -> usize {
@@ -1131,18 +1195,30 @@ impl super::widget_traits::Saveable for HighlightFormWidget {
 impl Toggleable for HighlightFormWidget {
     fn toggle_focused(&mut self) -> Option<bool> {
         match self.focused_field {
-            7 => {
+            10 => {
                 self.bold = !self.bold;
                 Some(self.bold)
             }
-            8 => {
+            11 => {
                 self.color_entire_line = !self.color_entire_line;
                 Some(self.color_entire_line)
             }
-            9 => {
+            12 => {
                 self.fast_parse = !self.fast_parse;
                 Some(self.fast_parse)
             }
+            13 => {
+                self.only_when_unfocused = !self.only_when_unfocused;
+                Some(self.only_when_unfocused)
+            }
+            14 => {
+                self.bell = !self.bell;
+                Some(self.bell)
+            }
+            15 => {
+                self.flash = !self.flash;
+                Some(self.flash)
+            }
             _ => None,
         }
     }
@@ -0,0 +1,124 @@
+//! Adaptive input polling
+//!
+//! The main loop waits in `poll_events` for up to the poll timeout. Once the
+//! client has been idle (no input, no server data, no running timers) for a
+//! short grace period, that wait doubles every pass up to `ui.idle_poll_max_ms`,
+//! so a client left open in the background uses next to no CPU. Keys still
+//! wake the poll immediately; the cost is that the first server line after a
+//! quiet spell can wait up to the idle timeout. Any activity drops straight
+//! back to `ui.poll_timeout_ms`.
+
+use std::time::{Duration, Instant};
+
+/// How long the client must be idle before the timeout starts growing
+const GRACE: Duration = Duration::from_secs(2);
+
+pub struct IdlePoll {
+    base: Duration,
+    max: Duration,
+    current: Duration,
+    idle_since: Option<Instant>,
+}
+
+impl IdlePoll {
+    pub fn new(base: Duration, max: Duration) -> Self {
+        Self {
+            base,
+            max,
+            current: base,
+            idle_since: None,
+        }
+    }
+
+    /// New limits from settings (takes effect on the next pass)
+    pub fn set_limits(&mut self, base: Duration, max: Duration) {
+        self.base = base;
+        self.max = max;
+        self.current = base;
+    }
+
+    /// Report whether the last pass was idle
+    pub fn update(&mut self, idle: bool, now: Instant) {
+        if !idle {
+            self.idle_since = None;
+            self.current = self.base;
+            return;
+        }
+        let since = *self.idle_since.get_or_insert(now);
+        if now.duration_since(since) >= GRACE {
+            self.current = (self.current * 2).min(self.max).max(self.base);
+        }
+    }
+
+    /// Has the timeout started backing off?
+    pub fn is_backed_off(&self) -> bool {
+        self.current > self.base
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.current
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    /// A poll that has been idle past the grace period
+    fn backed_off(start: Instant) -> IdlePoll {
+        let mut poll = IdlePoll::new(ms(16), ms(200));
+        poll.update(true, start);
+        poll.update(true, start + GRACE);
+        poll
+    }
+
+    #[test]
+    fn test_no_backoff_during_grace_period() {
+        let mut poll = IdlePoll::new(ms(16), ms(200));
+        let start = Instant::now();
+        poll.update(true, start);
+        poll.update(true, start + ms(1000));
+        assert_eq!(poll.timeout(), ms(16));
+        assert!(!poll.is_backed_off());
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_max() {
+        let start = Instant::now();
+        let mut poll = IdlePoll::new(ms(16), ms(200));
+        poll.update(true, start);
+        let timeouts: Vec<Duration> = (0..5)
+            .map(|_| {
+                poll.update(true, start + GRACE);
+                poll.timeout()
+            })
+            .collect();
+        assert_eq!(timeouts, vec![ms(32), ms(64), ms(128), ms(200), ms(200)]);
+        assert!(poll.is_backed_off());
+    }
+
+    #[test]
+    fn test_activity_resets_backoff_and_grace() {
+        let start = Instant::now();
+        let mut poll = backed_off(start);
+        assert!(poll.is_backed_off());
+        poll.update(false, start + GRACE);
+        assert_eq!(poll.timeout(), ms(16));
+        poll.update(true, start + GRACE + ms(500));
+        assert_eq!(poll.timeout(), ms(16));
+    }
+
+    #[test]
+    fn test_max_at_or_below_base_never_backs_off() {
+        let start = Instant::now();
+        let mut poll = IdlePoll::new(ms(16), ms(10));
+        poll.update(true, start);
+        poll.update(true, start + GRACE * 3);
+        assert_eq!(poll.timeout(), ms(16));
+        assert!(!poll.is_backed_off());
+    }
+}
@@ -11,6 +11,40 @@ use ratatui::{
 };
 use std::collections::HashMap;
 
+/// Injury doll content is 5 cols x 6 rows (plus the text indicators)
+const CONTENT_WIDTH: u16 = 5;
+const CONTENT_HEIGHT: u16 = 6;
+
+/// Body part positions (col, row, char, body_part_name)
+const POSITIONS: [(u16, u16, char, &str); 13] = [
+    // Row 0: Eyes
+    (0, 0, '\u{f06e}', "leftEye"), // Nerd Font eye icon
+    (4, 0, '\u{f06e}', "rightEye"),
+    // Row 1: Head
+    (2, 1, '0', "head"),
+    // Row 2: Arms/Chest
+    (1, 2, '/', "leftArm"),
+    (2, 2, '|', "chest"),
+    (3, 2, '\\', "rightArm"),
+    // Row 3: Hands/Abdomen
+    (0, 3, 'o', "leftHand"),
+    (2, 3, '|', "abdomen"),
+    (4, 3, 'o', "rightHand"),
+    // Row 4: Leg tops
+    (1, 4, '/', "leftLeg"),
+    (3, 4, '\\', "rightLeg"),
+    // Row 5: Leg bottoms (same body parts, just visual continuation)
+    (0, 5, 'o', "leftLeg"),
+    (4, 5, 'o', "rightLeg"),
+];
+
+/// Special indicators on the right with text labels: nk, bk, ns
+const TEXT_INDICATORS: [(u16, u16, &str, &str); 3] = [
+    (6, 1, "nk", "neck"), // neck - row 1
+    (6, 3, "bk", "back"), // back - row 3
+    (6, 5, "ns", "nsys"), // nerves - row 5
+];
+
 /// Injury doll widget showing body part injuries/scars
 /// Layout:
 ///  👁   👁
@@ -127,6 +161,53 @@ impl InjuryDoll {
         }
     }
 
+    /// Area inside the border (if any) plus the (row, col) content offset
+    fn content_origin(&self, area: Rect) -> (Rect, u16, u16) {
+        let inner_area = if self.show_border {
+            Block::default()
+                .borders(crate::config::parse_border_sides(&self.border_sides))
+                .inner(area)
+        } else {
+            area
+        };
+
+        let (row_offset, col_offset) = if let Some(ref align_str) = self.content_align {
+            let align = crate::config::ContentAlign::from_str(align_str);
+            align.calculate_offset(
+                CONTENT_WIDTH,
+                CONTENT_HEIGHT,
+                inner_area.width,
+                inner_area.height,
+            )
+        } else {
+            (0, 0) // Default to top-left
+        };
+        (inner_area, row_offset, col_offset)
+    }
+
+    /// Body part drawn at screen position (x, y) when rendered into `area`
+    pub fn part_at(&self, area: Rect, x: u16, y: u16) -> Option<&'static str> {
+        let (inner_area, row_offset, col_offset) = self.content_origin(area);
+        if !inner_area.contains((x, y).into()) {
+            return None;
+        }
+        let col = x.checked_sub(inner_area.x + col_offset)?;
+        let row = y.checked_sub(inner_area.y + row_offset)?;
+
+        POSITIONS
+            .iter()
+            .find(|(c, r, _, _)| *c == col && *r == row)
+            .map(|(_, _, _, part)| *part)
+            .or_else(|| {
+                TEXT_INDICATORS
+                    .iter()
+                    .find(|(c, r, text, _)| {
+                        *r == row && (*c..*c + text.len() as u16).contains(&col)
+                    })
+                    .map(|(_, _, _, part)| *part)
+            })
+    }
+
     fn get_injury_color(&self, body_part: &str) -> Color {
         let level = self.injuries.get(body_part).copied().unwrap_or(0);
         let color_hex = &self.colors[level as usize];
@@ -171,11 +252,7 @@ impl InjuryDoll {
             block = block.title(self.label.as_str());
         }
 
-        let inner_area = if self.show_border {
-            block.inner(area)
-        } else {
-            area
-        };
+        let (inner_area, row_offset, col_offset) = self.content_origin(area);
 
         if self.show_border {
             block.render(area, buf);
@@ -187,48 +264,8 @@ impl InjuryDoll {
 
         let bg_color = self.background_color;
 
-        // Calculate content alignment offset
-        // Injury doll content is 5 cols x 6 rows
-        const CONTENT_WIDTH: u16 = 5;
-        const CONTENT_HEIGHT: u16 = 6;
-
-        let (row_offset, col_offset) = if let Some(ref align_str) = self.content_align {
-            let align = crate::config::ContentAlign::from_str(align_str);
-            align.calculate_offset(
-                CONTENT_WIDTH,
-                CONTENT_HEIGHT,
-                inner_area.width,
-                inner_area.height,
-            )
-        } else {
-            (0, 0) // Default to top-left
-        };
-
-        // Define all body part positions (col, row, char, body_part_name)
-        let positions = [
-            // Row 0: Eyes
-            (0, 0, '\u{f06e}', "leftEye"), // Nerd Font eye icon
-            (4, 0, '\u{f06e}', "rightEye"),
-            // Row 1: Head
-            (2, 1, '0', "head"),
-            // Row 2: Arms/Chest
-            (1, 2, '/', "leftArm"),
-            (2, 2, '|', "chest"),
-            (3, 2, '\\', "rightArm"),
-            // Row 3: Hands/Abdomen
-            (0, 3, 'o', "leftHand"),
-            (2, 3, '|', "abdomen"),
-            (4, 3, 'o', "rightHand"),
-            // Row 4: Leg tops
-            (1, 4, '/', "leftLeg"),
-            (3, 4, '\\', "rightLeg"),
-            // Row 5: Leg bottoms (same body parts, just visual continuation)
-            (0, 5, 'o', "leftLeg"),
-            (4, 5, 'o', "rightLeg"),
-        ];
-
         // Render body parts
-        for (col, row, ch, body_part) in positions.iter() {
+        for (col, row, ch, body_part) in POSITIONS.iter() {
             let x = inner_area.x + col + col_offset;
             let y = inner_area.y + row + row_offset;
 
@@ -245,14 +282,7 @@ impl InjuryDoll {
             }
         }
 
-        // Render special indicators on the right with text labels: nk, bk, ns
-        let text_indicators = [
-            (6, 1, "nk", "neck"), // neck - row 1
-            (6, 3, "bk", "back"), // back - row 3
-            (6, 5, "ns", "nsys"), // nerves - row 5
-        ];
-
-        for (start_col, row, text, body_part) in text_indicators.iter() {
+        for (start_col, row, text, body_part) in TEXT_INDICATORS.iter() {
             let color = self.get_injury_color(body_part);
 
             for (i, ch) in text.chars().enumerate() {
@@ -272,3 +302,28 @@ impl InjuryDoll {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part_at() {
+        let mut doll = InjuryDoll::new("injuries");
+        let area = Rect::new(10, 5, 12, 8);
+        assert_eq!(doll.part_at(area, 12, 6), Some("head"));
+        assert_eq!(doll.part_at(area, 14, 10), Some("rightLeg"));
+        assert_eq!(doll.part_at(area, 17, 8), Some("back"));
+        assert_eq!(doll.part_at(area, 11, 5), None);
+
+        // Border and centering shift the content
+        doll.set_border_config(true, None, None);
+        doll.set_content_align(Some("center".to_string()));
+        let (inner, row, col) = doll.content_origin(area);
+        assert_eq!(
+            doll.part_at(area, inner.x + col + 2, inner.y + row + 1),
+            Some("head")
+        );
+        assert_eq!(doll.part_at(area, 10, 5), None);
+    }
+}
@@ -2,8 +2,16 @@
 //!
 //! Unlike scrolling text buffers, the inventory view replaces its content on
 //! each update and keeps a small recent-link cache for click detection.
+//!
+//! Indentation in the inv stream nests items inside containers; any line
+//! followed by deeper lines is shown as a collapsible node (`▾`/`▸`) with its
+//! item count. Collapsed nodes are keyed by their container path, the same
+//! `container_key` the containers tracker uses, so the state survives
+//! updates and is saved with the layout.
 
+use super::text_window::TextWindow;
 use crate::data::widget::TextSegment;
+use crate::data::{container_key, SpanType};
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
@@ -11,13 +19,14 @@ use ratatui::{
     text::{Line, Span},
     widgets::{Block, Borders, Clear, Paragraph},
 };
-use std::collections::VecDeque;
+use std::collections::{HashSet, VecDeque};
 
 /// Inventory window widget - displays worn/carried items
 /// Content is completely replaced on each update (no appending/scrollback)
 pub struct InventoryWindow {
     title: String,
     show_border: bool,
+    border_sides: crate::config::BorderSides,
     border_color: Option<Color>,
     text_color: Option<Color>,
     background_color: Option<Color>,
@@ -30,6 +39,18 @@ pub struct InventoryWindow {
     /// Current line being built (before finish_line is called)
     current_line: Vec<TextSegment>,
 
+    /// Visible tree rows built from `lines` (collapsed contents left out)
+    rows: Vec<Vec<TextSegment>>,
+
+    /// Per row: the node key and marker column, for container rows
+    row_nodes: Vec<Option<(String, usize)>>,
+
+    /// Keys of collapsed container nodes
+    collapsed: HashSet<String>,
+
+    /// `lines` changed since `rows` was built
+    tree_dirty: bool,
+
     /// Scroll offset for navigation
     scroll_offset: usize,
 
@@ -51,12 +72,17 @@ impl InventoryWindow {
         Self {
             title,
             show_border: true,
+            border_sides: crate::config::BorderSides::default(),
             border_color: None,
             text_color: None,
             background_color: None,
             transparent_background: true,
             lines: Vec::new(),
             current_line: Vec::new(),
+            rows: Vec::new(),
+            row_nodes: Vec::new(),
+            collapsed: HashSet::new(),
+            tree_dirty: false,
             scroll_offset: 0,
             inner_width: 80,
             inner_height: 20,
@@ -70,6 +96,7 @@ impl InventoryWindow {
     pub fn clear(&mut self) {
         self.lines.clear();
         self.current_line.clear();
+        self.tree_dirty = true;
         self.scroll_offset = 0;
         // Don't clear recent_links - keep for deduplication across updates
     }
@@ -120,6 +147,145 @@ impl InventoryWindow {
         // Wrap the line to window width
         let wrapped = self.wrap_line(line);
         self.lines.extend(wrapped);
+        self.tree_dirty = true;
+    }
+
+    /// Apply the saved collapsed nodes and rebuild the tree rows if anything changed
+    pub fn update_tree(&mut self, collapsed: &[String]) {
+        if !self.tree_dirty
+            && collapsed.len() == self.collapsed.len()
+            && collapsed.iter().all(|key| self.collapsed.contains(key))
+        {
+            return;
+        }
+        self.collapsed = collapsed.iter().cloned().collect();
+        self.tree_dirty = false;
+        self.rows.clear();
+        self.row_nodes.clear();
+
+        let indents: Vec<usize> = self.lines.iter().map(|line| indent_of(line)).collect();
+        let has_nodes = indents.windows(2).any(|pair| pair[1] > pair[0]);
+        let mut hidden_below: Option<usize> = None;
+        // Containers holding the current line: (indent, text)
+        let mut parents: Vec<(usize, String)> = Vec::new();
+
+        for (i, line) in self.lines.iter().enumerate() {
+            let indent = indents[i];
+            while parents.last().is_some_and(|(depth, _)| *depth >= indent) {
+                parents.pop();
+            }
+            if hidden_below.is_some_and(|depth| indent > depth) {
+                continue;
+            }
+            hidden_below = None;
+
+            let mut row = Vec::with_capacity(line.len() + 3);
+            if indent > 0 {
+                row.push(plain_segment(" ".repeat(indent)));
+            }
+            let child_indent = indents.get(i + 1).copied().filter(|&next| next > indent);
+            let node = match child_indent {
+                Some(child_indent) => {
+                    let count = indents[i + 1..]
+                        .iter()
+                        .take_while(|&&depth| depth > indent)
+                        .filter(|&&depth| depth == child_indent)
+                        .count();
+                    let text = line_text(line);
+                    let key = container_key(
+                        parents
+                            .iter()
+                            .map(|(_, name)| name.as_str())
+                            .chain([text.as_str()]),
+                    );
+                    parents.push((indent, text));
+                    let collapsed = self.collapsed.contains(&key);
+                    if collapsed {
+                        hidden_below = Some(indent);
+                    }
+                    row.push(plain_segment(
+                        if collapsed { "▸ " } else { "▾ " }.to_string(),
+                    ));
+                    row.extend(strip_indent(line));
+                    row.push(plain_segment(format!(" ({})", count)));
+                    Some((key, indent))
+                }
+                None => {
+                    if has_nodes {
+                        row.push(plain_segment("  ".to_string()));
+                    }
+                    row.extend(strip_indent(line));
+                    None
+                }
+            };
+            self.rows.push(row);
+            self.row_nodes.push(node);
+        }
+    }
+
+    /// The window's borders (none when the border is hidden)
+    fn borders(&self) -> Borders {
+        if self.show_border {
+            crate::config::parse_border_sides(&self.border_sides)
+        } else {
+            Borders::NONE
+        }
+    }
+
+    /// Index into `rows` of the row under screen position (x, y)
+    fn row_at(&self, area: Rect, x: u16, y: u16) -> Option<usize> {
+        let inner = Block::default().borders(self.borders()).inner(area);
+        if !inner.contains((x, y).into()) {
+            return None;
+        }
+        let idx = self.get_start_line() + (y - inner.y) as usize;
+        (idx < self.rows.len()).then_some(idx)
+    }
+
+    /// Container node to expand or collapse for a click at (x, y): on its
+    /// marker, or anywhere on its row that isn't a link
+    pub fn node_at(&self, area: Rect, x: u16, y: u16) -> Option<&str> {
+        let idx = self.row_at(area, x, y)?;
+        let (key, marker_col) = self.row_nodes[idx].as_ref()?;
+
+        let inner = Block::default().borders(self.borders()).inner(area);
+        let col = (x - inner.x) as usize;
+        if col == *marker_col || col == marker_col + 1 {
+            return Some(key);
+        }
+        match self.segment_at(idx, col) {
+            Some(seg) => seg.link_data.is_none().then_some(key.as_str()),
+            None => Some(key),
+        }
+    }
+
+    /// Segment of a row covering column `col`, counted in terminal cells
+    fn segment_at(&self, idx: usize, col: usize) -> Option<&TextSegment> {
+        let mut start = 0;
+        for seg in &self.rows[idx] {
+            let width = TextWindow::text_width(&seg.text);
+            if col < start + width {
+                return Some(seg);
+            }
+            start += width;
+        }
+        None
+    }
+
+    /// The link under screen position (x, y), if any
+    pub fn link_at(&self, area: Rect, x: u16, y: u16) -> Option<crate::data::LinkData> {
+        let idx = self.row_at(area, x, y)?;
+        let inner = Block::default().borders(self.borders()).inner(area);
+        self.segment_at(idx, (x - inner.x) as usize)?
+            .link_data
+            .clone()
+    }
+
+    /// The item on the row under (x, y), for dropping dragged items onto
+    /// a container anywhere on its row
+    pub fn item_at(&self, area: Rect, x: u16, y: u16) -> Option<crate::data::LinkData> {
+        let idx = self.row_at(area, x, y)?;
+        self.rows[idx].iter().find_map(|seg| seg.link_data.clone())
     }
 
     /// Wrap a line of styled segments to window width (word-boundary aware)
@@ -327,18 +493,10 @@ impl InventoryWindow {
         }
     }
 
-    /// Update inner dimensions based on window size
+    /// Update inner dimensions based on the window's area inside its borders
     pub fn update_inner_size(&mut self, width: u16, height: u16) {
-        let new_width = if self.show_border {
-            (width.saturating_sub(2)) as usize
-        } else {
-            width as usize
-        };
-        let new_height = if self.show_border {
-            (height.saturating_sub(2)) as usize
-        } else {
-            height as usize
-        };
+        let new_width = width as usize;
+        let new_height = height as usize;
 
         // If width changed, we need to rewrap all lines
         if new_width != self.inner_width {
@@ -351,7 +509,7 @@ impl InventoryWindow {
     /// Scroll up by N lines
     pub fn scroll_up(&mut self, lines: usize) {
         self.scroll_offset = self.scroll_offset.saturating_add(lines);
-        let max_scroll = self.lines.len().saturating_sub(self.inner_height);
+        let max_scroll = self.rows.len().saturating_sub(self.inner_height);
         self.scroll_offset = self.scroll_offset.min(max_scroll);
     }
 
@@ -367,18 +525,13 @@ impl InventoryWindow {
 
     /// Get all lines (for text selection)
     pub fn get_lines(&self) -> &[Vec<TextSegment>] {
-        &self.lines
-    }
-
-    /// Get wrapped lines for mouse click detection
-    pub fn get_wrapped_lines(&self) -> &Vec<Vec<TextSegment>> {
-        &self.lines
+        &self.rows
     }
 
     /// Get the start line offset (which line is shown at the top of the visible area)
     /// This is needed for click detection to map visual rows to actual line indices
     pub fn get_start_line(&self) -> usize {
-        let total_lines = self.lines.len();
+        let total_lines = self.rows.len();
         if total_lines > self.inner_height {
             total_lines
                 .saturating_sub(self.inner_height)
@@ -399,6 +552,10 @@ impl InventoryWindow {
         self.border_color = border_color.and_then(|hex| parse_hex_color(&hex));
     }
 
+    pub fn set_border_sides(&mut self, sides: crate::config::BorderSides) {
+        self.border_sides = sides;
+    }
+
     pub fn set_text_color(&mut self, color: Option<String>) {
         self.text_color = color.and_then(|hex| parse_hex_color(&hex));
     }
@@ -437,7 +594,8 @@ impl InventoryWindow {
         }
 
         // Update inner size
-        self.update_inner_size(area.width, area.height);
+        let inner = Block::default().borders(self.borders()).inner(area);
+        self.update_inner_size(inner.width, inner.height);
 
         // Create border block
         let mut block = Block::default();
@@ -446,13 +604,13 @@ impl InventoryWindow {
             let border_color = self.border_color.unwrap_or(Color::White);
 
             block = block
-                .borders(Borders::ALL)
+                .borders(self.borders())
                 .border_style(Style::default().fg(border_color))
                 .title(self.title.as_str());
         }
 
         // Calculate visible range
-        let total_lines = self.lines.len();
+        let total_lines = self.rows.len();
         let start_line = if total_lines > self.inner_height {
             total_lines
                 .saturating_sub(self.inner_height)
@@ -463,13 +621,24 @@ impl InventoryWindow {
         let end_line = start_line + self.inner_height.min(total_lines);
 
         // Get visible lines
-        let visible_lines: Vec<Line> = self.lines[start_line..end_line.min(total_lines)]
+        let visible_lines: Vec<Line> = self.rows[start_line..end_line.min(total_lines)]
             .iter()
             .map(|segments| {
-                let spans: Vec<Span> = segments
-                    .iter()
-                    .map(|seg| Span::styled(seg.text.clone(), self.apply_style(seg)))
-                    .collect();
+                let mut spans: Vec<Span> = Vec::with_capacity(segments.len());
+                for (i, seg) in segments.iter().enumerate() {
+                    spans.push(Span::styled(seg.text.clone(), self.apply_style(seg)));
+                    // Mark lit/closed items once, after the last segment of the link
+                    let Some(ref link) = seg.link_data else {
+                        continue;
+                    };
+                    let link_continues = segments
+                        .get(i + 1)
+                        .and_then(|next| next.link_data.as_ref())
+                        .is_some_and(|next| next.exist_id == link.exist_id);
+                    if let (false, Some(marker)) = (link_continues, link.flags.marker()) {
+                        spans.push(Span::styled(format!(" {}", marker), self.apply_style(seg)));
+                    }
+                }
                 Line::from(spans)
             })
             .collect();
@@ -513,6 +682,57 @@ impl InventoryWindow {
     }
 }
 
+/// Leading spaces on a line: its depth in the container tree
+fn indent_of(line: &[TextSegment]) -> usize {
+    let mut indent = 0;
+    for seg in line {
+        let spaces = seg.text.chars().take_while(|c| *c == ' ').count();
+        indent += spaces;
+        if spaces < seg.text.chars().count() {
+            break;
+        }
+    }
+    indent
+}
+
+/// A line's text without indentation (its container name in node keys)
+fn line_text(line: &[TextSegment]) -> String {
+    line.iter()
+        .map(|seg| seg.text.as_str())
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+/// The line's segments with the leading indentation removed
+fn strip_indent(line: &[TextSegment]) -> Vec<TextSegment> {
+    let mut out = Vec::with_capacity(line.len());
+    let mut leading = true;
+    for seg in line {
+        let mut seg = seg.clone();
+        if leading {
+            seg.text = seg.text.trim_start_matches(' ').to_string();
+            leading = seg.text.is_empty();
+            if leading {
+                continue;
+            }
+        }
+        out.push(seg);
+    }
+    out
+}
+
+fn plain_segment(text: String) -> TextSegment {
+    TextSegment {
+        text,
+        fg: None,
+        bg: None,
+        bold: false,
+        span_type: SpanType::Normal,
+        link_data: None,
+    }
+}
+
 /// Parse hex color string to ratatui Color
 fn parse_hex_color(hex: &str) -> Option<Color> {
     let hex = hex.trim_start_matches('#');
@@ -526,3 +746,156 @@ fn parse_hex_color(hex: &str) -> Option<Color> {
 
     Some(Color::Rgb(r, g, b))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::LinkData;
+
+    fn add_line(window: &mut InventoryWindow, indent: &str, item: &str, exist_id: &str) {
+        window.add_segment(plain_segment(format!("{}a ", indent)));
+        window.add_segment(TextSegment {
+            link_data: Some(LinkData {
+                exist_id: exist_id.to_string(),
+                noun: item.to_string(),
+                text: item.to_string(),
+                coord: None,
+                flags: Default::default(),
+            }),
+            span_type: SpanType::Link,
+            ..plain_segment(item.to_string())
+        });
+        window.finish_line();
+    }
+
+    fn row_text(window: &InventoryWindow) -> Vec<String> {
+        window
+            .get_lines()
+            .iter()
+            .map(|row| row.iter().map(|seg| seg.text.as_str()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_inventory_tree() {
+        let mut window = InventoryWindow::new("Inventory".to_string());
+        window.add_segment(plain_segment("Your worn items are:".to_string()));
+        window.finish_line();
+        add_line(&mut window, "  ", "backpack", "1");
+        add_line(&mut window, "    ", "dagger", "2");
+        add_line(&mut window, "    ", "pouch", "3");
+        add_line(&mut window, "      ", "ruby", "4");
+        add_line(&mut window, "  ", "cloak", "5");
+        window.update_tree(&[]);
+
+        assert_eq!(
+            row_text(&window),
+            vec![
+                "▾ Your worn items are: (2)",
+                "  ▾ a backpack (2)",
+                "      a dagger",
+                "    ▾ a pouch (1)",
+                "        a ruby",
+                "    a cloak",
+            ]
+        );
+
+        window.update_tree(&["worn items are:/backpack".to_string()]);
+        assert_eq!(
+            row_text(&window)[1..],
+            ["  ▸ a backpack (2)", "    a cloak"]
+        );
+
+        // Marker and plain text toggle; the item link and leaves don't
+        let area = Rect::new(0, 0, 30, 10);
+        let mut buf = Buffer::empty(area);
+        window.render(area, &mut buf);
+        assert_eq!(window.node_at(area, 3, 2), Some("worn items are:/backpack"));
+        assert_eq!(window.node_at(area, 7, 2), None);
+        assert_eq!(window.node_at(area, 1, 1), Some("worn items are:"));
+        assert_eq!(window.node_at(area, 5, 3), None);
+
+        // Dropping anywhere on a row targets that row's item
+        assert_eq!(
+            window.item_at(area, 20, 2).map(|l| l.exist_id),
+            Some("1".to_string())
+        );
+        assert_eq!(window.item_at(area, 5, 1), None);
+    }
+    #[test]
+    fn test_inventory_nodes_keyed_by_container_path() {
+        let mut window = InventoryWindow::new("Inventory".to_string());
+        add_line(&mut window, "", "backpack", "1");
+        add_line(&mut window, "  ", "pouch", "2");
+        add_line(&mut window, "    ", "ruby", "3");
+        add_line(&mut window, "", "sack", "4");
+        add_line(&mut window, "  ", "pouch", "5");
+        add_line(&mut window, "    ", "gem", "6");
+
+        // Only the pouch in the backpack collapses, not the one in the sack
+        window.update_tree(&["backpack/pouch".to_string()]);
+        assert_eq!(
+            row_text(&window),
+            vec![
+                "▾ a backpack (1)",
+                "  ▸ a pouch (1)",
+                "▾ a sack (1)",
+                "  ▾ a pouch (1)",
+                "      a gem"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_inventory_hit_testing_follows_border_sides() {
+        let mut window = InventoryWindow::new("Inventory".to_string());
+        add_line(&mut window, "", "backpack", "1");
+        add_line(&mut window, "  ", "dagger", "2");
+        window.update_tree(&[]);
+        window.set_border_sides(crate::config::BorderSides {
+            top: true,
+            bottom: false,
+            left: false,
+            right: false,
+        });
+
+        let area = Rect::new(0, 0, 30, 10);
+        let mut buf = Buffer::empty(area);
+        window.render(area, &mut buf);
+        // No left border: the marker is in the first column, rows start under the top border
+        assert_eq!(window.node_at(area, 0, 1), Some("backpack"));
+        assert_eq!(
+            window.item_at(area, 20, 2).map(|l| l.exist_id),
+            Some("2".to_string())
+        );
+    }
+
+    #[test]
+    fn test_inventory_link_hit_testing_counts_cells() {
+        let mut window = InventoryWindow::new("Inventory".to_string());
+        window.add_segment(plain_segment("a 黒い ".to_string()));
+        window.add_segment(TextSegment {
+            link_data: Some(LinkData {
+                exist_id: "1".to_string(),
+                noun: "dagger".to_string(),
+                text: "dagger".to_string(),
+                coord: None,
+                flags: Default::default(),
+            }),
+            span_type: SpanType::Link,
+            ..plain_segment("dagger".to_string())
+        });
+        window.finish_line();
+        window.update_tree(&[]);
+
+        let area = Rect::new(0, 0, 30, 10);
+        let mut buf = Buffer::empty(area);
+        window.render(area, &mut buf);
+        // "a 黒い " is 5 chars but 7 cells wide; the link starts at cell 7
+        assert_eq!(window.link_at(area, 1 + 6, 1), None);
+        assert_eq!(
+            window.link_at(area, 1 + 7, 1).map(|l| l.exist_id),
+            Some("1".to_string())
+        );
+    }
+}
@@ -19,6 +19,9 @@ pub enum KeybindFormResult {
         key_combo: String,
         action_type: KeybindActionType,
         value: String,
+        /// None = not yet checked for conflicts; Some = user resolved them,
+        /// listing existing keybinds to remove first (empty = keep both)
+        replace: Option<Vec<String>>,
     },
     Delete {
         key_combo: String,
@@ -44,6 +47,9 @@ pub struct KeybindFormWidget {
     key_combo_error: Option<String>,
     mode: FormMode,
 
+    // Conflicts awaiting resolution (non-empty = resolution dialog showing)
+    conflicts: Vec<crate::config::KeybindConflict>,
+
     // Popup position (for dragging)
     pub popup_x: u16,
     pub popup_y: u16,
@@ -72,6 +78,7 @@ const AVAILABLE_ACTIONS: &[&str] = &[
     "cursor_delete_word",
     "cursor_clear_line",
     "switch_current_window",
+    "switch_current_window_back",
     "scroll_current_window_up_one",
     "scroll_current_window_down_one",
     "scroll_current_window_up_page",
@@ -80,16 +87,23 @@ const AVAILABLE_ACTIONS: &[&str] = &[
     "scroll_current_window_end",
     "previous_command",
     "next_command",
+    "history_search",
     "start_search",
     "prev_search_match",
     "next_search_match",
     "toggle_performance_stats",
+    "stt_push_to_talk",
+    "review_previous",
+    "review_next",
+    "review_latest",
+    "cycle_recent_urls",
+    "open_recent_url",
 ];
 
 impl KeybindFormWidget {
     pub fn new() -> Self {
         let mut key_combo = TextArea::default();
-        key_combo.set_placeholder_text("e.g., ctrl+e, f5, alt+shift+a");
+        key_combo.set_placeholder_text("e.g., ctrl+e, f5, alt+shift+a, ctrl+x h");
 
         let mut macro_text = TextArea::default();
         macro_text.set_placeholder_text("e.g., run left\\r");
@@ -103,6 +117,7 @@ impl KeybindFormWidget {
             status_message: String::new(),
             key_combo_error: None,
             mode: FormMode::Create,
+            conflicts: Vec::new(),
             popup_x: 0,
             popup_y: 0,
             is_dragging: false,
@@ -134,12 +149,81 @@ impl KeybindFormWidget {
         form
     }
 
+    /// Keybind being edited (None when creating a new one)
+    pub fn original_key(&self) -> Option<&str> {
+        match self.mode {
+            FormMode::Edit { ref original_key } => Some(original_key),
+            FormMode::Create => None,
+        }
+    }
+
+    /// Show the conflict resolution dialog instead of saving
+    pub fn show_conflicts(&mut self, conflicts: Vec<crate::config::KeybindConflict>) {
+        self.conflicts = conflicts;
+    }
+
+    pub fn has_conflicts(&self) -> bool {
+        !self.conflicts.is_empty()
+    }
+
+    /// Keys while the conflict dialog is up: overwrite, keep both, or go back and edit
+    fn handle_conflict_key(
+        &mut self,
+        key: crossterm::event::KeyEvent,
+    ) -> Option<KeybindFormResult> {
+        use crossterm::event::KeyCode;
+
+        match key.code {
+            KeyCode::Char('o') | KeyCode::Char('O') => {
+                let replace: Vec<String> = self
+                    .conflicts
+                    .iter()
+                    .filter_map(|c| c.existing.clone())
+                    .collect();
+                self.resolve_conflicts(replace)
+            }
+            KeyCode::Char('k') | KeyCode::Char('K') => self.resolve_conflicts(Vec::new()),
+            KeyCode::Char('e') | KeyCode::Char('E') => {
+                self.conflicts.clear();
+                self.focused_field = 2;
+                None
+            }
+            KeyCode::Esc => {
+                self.conflicts.clear();
+                None
+            }
+            _ => None,
+        }
+    }
+
+    fn resolve_conflicts(&mut self, replace: Vec<String>) -> Option<KeybindFormResult> {
+        self.conflicts.clear();
+        match self.save_internal() {
+            Some(KeybindFormResult::Save {
+                key_combo,
+                action_type,
+                value,
+                ..
+            }) => Some(KeybindFormResult::Save {
+                key_combo,
+                action_type,
+                value,
+                replace: Some(replace),
+            }),
+            other => other,
+        }
+    }
+
     pub fn handle_key(
         &mut self,
         key: crossterm::event::KeyEvent,
     ) -> Option<KeybindFormResult> {
         use crossterm::event::{KeyCode, KeyModifiers};
 
+        if self.has_conflicts() {
+            return self.handle_conflict_key(key);
+        }
+
         match key.code {
             KeyCode::Tab => {
                 // Tab: go forwards with wraparound
@@ -239,7 +323,7 @@ impl KeybindFormWidget {
     }
 
     fn validate_key_combo(&mut self) {
-        let combo = self.key_combo.lines()[0].as_str();
+        let combo = self.key_combo.lines()[0].clone();
         if combo.is_empty() {
             self.key_combo_error = None;
             return;
@@ -247,7 +331,21 @@ impl KeybindFormWidget {
 
         // Basic validation - check if it looks like a valid key combo
         // Valid formats: "a", "ctrl+a", "alt+shift+f5", etc.
-        let parts: Vec<&str> = combo.split('+').collect();
+        // Multi-key chords ("ctrl+x h" or "ctrl+x then h") are checked step by step
+        for step in combo
+            .split_whitespace()
+            .filter(|step| !step.eq_ignore_ascii_case("then"))
+        {
+            if !self.validate_key_step(step) {
+                return;
+            }
+        }
+        self.key_combo_error = None;
+    }
+
+    /// Validate a single step of a key combo, setting key_combo_error on failure
+    fn validate_key_step(&mut self, step: &str) -> bool {
+        let parts: Vec<&str> = step.split('+').collect();
         let mut has_key = false;
 
         for part in &parts {
@@ -324,15 +422,15 @@ impl KeybindFormWidget {
                 has_key = true;
             } else if !matches!(normalized.as_str(), "ctrl" | "alt" | "shift") {
                 self.key_combo_error = Some(format!("Invalid key: '{}'", part));
-                return;
+                return false;
             }
         }
 
         if !has_key {
             self.key_combo_error = Some("Must specify a key (not just modifiers)".to_string());
-        } else {
-            self.key_combo_error = None;
+            return false;
         }
+        true
     }
 
     fn save_internal(&mut self) -> Option<KeybindFormResult> {
@@ -366,6 +464,7 @@ impl KeybindFormWidget {
             key_combo,
             action_type: self.action_type.clone(),
             value,
+            replace: None,
         })
     }
 
@@ -387,7 +486,12 @@ impl KeybindFormWidget {
         theme: &crate::theme::AppTheme,
     ) {
         let width = 52;
-        let height = 9;
+        // Conflict dialog adds a header, one row per conflict, and its own footer
+        let height = if self.conflicts.is_empty() {
+            9
+        } else {
+            11 + self.conflicts.len() as u16
+        };
 
         // Center on first render
         if self.popup_x == 0 && self.popup_y == 0 {
@@ -436,6 +540,11 @@ impl KeybindFormWidget {
         // Render fields
         self.render_fields(x, y, width, buf, config, theme);
 
+        if !self.conflicts.is_empty() {
+            self.render_conflicts(x, y + 7, width, buf, theme);
+            return;
+        }
+
         // Footer (centered at row 7)
         let footer = "Ctrl+s:Save Ctrl+D:Delete Esc:Cancel";
         let footer_y = y + 7;
@@ -450,6 +559,39 @@ impl KeybindFormWidget {
         }
     }
 
+    /// Conflict list and resolution choices, drawn below the fields
+    fn render_conflicts(
+        &self,
+        x: u16,
+        y: u16,
+        width: u16,
+        buf: &mut Buffer,
+        theme: &crate::theme::AppTheme,
+    ) {
+        let max_len = width.saturating_sub(4) as usize;
+        let mut lines: Vec<(String, Color)> = vec![("Conflicts:".to_string(), theme.form_error)];
+        for conflict in &self.conflicts {
+            lines.push((format!("- {}", conflict.description), theme.text_primary));
+        }
+        lines.push((String::new(), theme.text_primary));
+        lines.push((
+            "o:Overwrite k:Keep both e:Edit key Esc:Back".to_string(),
+            theme.form_label_focused,
+        ));
+
+        for (row, (text, color)) in lines.iter().enumerate() {
+            if y + row as u16 >= buf.area().bottom() {
+                break;
+            }
+            for (i, ch) in text.chars().take(max_len).enumerate() {
+                buf[(x + 2 + i as u16, y + row as u16)]
+                    .set_char(ch)
+                    .set_fg(*color)
+                    .set_bg(theme.browser_background);
+            }
+        }
+    }
+
     fn draw_border(
         &self,
         x: u16,
@@ -0,0 +1,104 @@
+//! List widget shared by the tracker windows (loot, session stats, bounty,
+//! containers, watch list).
+//!
+//! Each tracker module only knows how to fill rows; this widget owns the
+//! `ScrollableContainer`, rebuilds it on every sync and keeps the scroll
+//! position across rebuilds.
+
+use super::scrollable_container::ScrollableContainer;
+use ratatui::{buffer::Buffer, layout::Rect};
+
+pub struct ListWidget {
+    container: ScrollableContainer,
+}
+
+/// Row sink handed to a tracker's fill function
+pub struct ListRows<'a> {
+    container: &'a mut ScrollableContainer,
+}
+
+impl ListRows<'_> {
+    /// Label with an optional value pinned to the right edge
+    pub fn row(&mut self, id: &str, label: &str, suffix: Option<String>) {
+        self.row_full(id, label, suffix, 0, 1, None);
+    }
+
+    /// Row with a progress bar (`value` of `max`) and an optional text color
+    pub fn row_full(
+        &mut self,
+        id: &str,
+        label: &str,
+        suffix: Option<String>,
+        value: u32,
+        max: u32,
+        color: Option<String>,
+    ) {
+        let suffix = suffix.filter(|s| !s.is_empty());
+        self.container.add_or_update_item_full(
+            id.to_string(),
+            label.to_string(),
+            None,
+            value,
+            max,
+            suffix,
+            None,
+            color,
+        );
+    }
+}
+
+impl ListWidget {
+    pub fn new(title: &str) -> Self {
+        let mut container = ScrollableContainer::new(title);
+        container.set_display_options(false, false);
+        Self { container }
+    }
+
+    /// Replace the rows with what `fill` adds, keeping the scroll position
+    pub fn rebuild(&mut self, fill: impl FnOnce(&mut ListRows)) {
+        let scroll = self.container.scroll_position();
+        self.container.clear();
+        fill(&mut ListRows {
+            container: &mut self.container,
+        });
+        self.container.restore_scroll_position(scroll);
+    }
+
+    pub fn scroll_up(&mut self, amount: usize) {
+        self.container.scroll_up(amount);
+    }
+
+    pub fn scroll_down(&mut self, amount: usize) {
+        self.container.scroll_down(amount);
+    }
+
+    pub fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        self.container.render(area, buf);
+    }
+}
+
+impl super::StyledWindow for ListWidget {
+    fn set_title(&mut self, title: String) {
+        self.container.set_title(title);
+    }
+
+    fn set_border_config(&mut self, show: bool, style: Option<String>, color: Option<String>) {
+        self.container.set_border_config(show, style, color);
+    }
+
+    fn set_border_sides(&mut self, sides: crate::config::BorderSides) {
+        self.container.set_border_sides(sides);
+    }
+
+    fn set_text_color(&mut self, color: Option<String>) {
+        self.container.set_text_color(color);
+    }
+
+    fn set_background_color(&mut self, color: Option<String>) {
+        self.container.set_background_color(color);
+    }
+
+    fn set_transparent_background(&mut self, transparent: bool) {
+        self.container.set_transparent_background(transparent);
+    }
+}
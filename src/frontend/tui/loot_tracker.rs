@@ -0,0 +1,63 @@
+//! Loot tracker rows: silvers, boxes and gems for the current hunt and the
+//! session, with the most common gems underneath.
+//!
+//! Rows are rebuilt from `AppCore::loot` on every sync and drawn through a
+//! `ListWidget` with the values pinned to the right edge.
+
+use super::list_widget::ListRows;
+use crate::data::{format_elapsed, with_commas, LootTotals, LootTracker};
+
+/// Fill the rows from the tracker
+pub fn fill(rows: &mut ListRows, loot: &LootTracker, gem_rows: usize) {
+    let session_elapsed = loot.session_elapsed();
+    section(
+        rows,
+        "hunt",
+        "Hunt",
+        format_elapsed(loot.hunt.elapsed()),
+        &loot.hunt.totals,
+    );
+    section(
+        rows,
+        "session",
+        "Session",
+        format_elapsed(session_elapsed),
+        &loot.session,
+    );
+    rows.row(
+        "session_rate",
+        "  Silvers/hr",
+        Some(with_commas(loot.session.silvers_per_hour(session_elapsed))),
+    );
+
+    let gems = loot.session.top_gems();
+    if gem_rows > 0 && !gems.is_empty() {
+        rows.row("gems", "Gems", None);
+        for (name, count) in gems.into_iter().take(gem_rows) {
+            rows.row(
+                &format!("gem_{}", name),
+                &format!("  {}", name),
+                Some(count.to_string()),
+            );
+        }
+    }
+}
+
+fn section(rows: &mut ListRows, id: &str, label: &str, elapsed: String, totals: &LootTotals) {
+    rows.row(id, label, Some(elapsed));
+    rows.row(
+        &format!("{}_silvers", id),
+        "  Silvers",
+        Some(with_commas(totals.silvers)),
+    );
+    rows.row(
+        &format!("{}_boxes", id),
+        "  Boxes",
+        Some(totals.boxes.to_string()),
+    );
+    rows.row(
+        &format!("{}_gems", id),
+        "  Gems",
+        Some(totals.gems.to_string()),
+    );
+}
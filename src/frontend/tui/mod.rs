@@ -3,39 +3,61 @@
 //! This module implements the Frontend trait for terminal rendering.
 
 mod active_effects;
+mod art_window;
+mod bounty_tracker;
 pub mod color_form;
 pub mod color_palette_browser;
+mod color_mode;
 mod color_picker;
+mod contrast;
+pub mod copy_mode;
+mod rich_copy;
 mod command_input;
 mod compass;
+mod container_tree;
+pub mod composer;
+pub mod config_diff;
 mod countdown;
+mod damage;
 mod dashboard;
+mod graphics;
 mod hand;
 pub mod highlight_browser;
 pub mod highlight_form;
+mod idle_poll;
 mod indicator;
 mod injury_doll;
 mod inventory_window;
 pub mod keybind_browser;
 pub mod keybind_form;
+mod list_widget;
 mod performance_stats;
+mod plugin_widget;
+mod loot_tracker;
 mod players;
 mod popup_menu;
+pub mod profile_picker;
 mod progress_bar;
 mod quickbar;
 mod room_window;
 mod scrollable_container;
+mod scrollback_spill;
+mod session_stats;
 pub mod settings_editor;
 mod spacer;
+pub mod snippet_browser;
 pub mod spell_color_browser;
 pub mod spell_color_form;
 mod spells_window;
+mod status_line;
 mod tabbed_text_window;
 mod targets;
-mod text_window;
+pub mod text_window;
 pub mod theme_browser;
 pub mod theme_editor;
 pub mod uicolors_browser;
+mod vitals_cluster;
+mod watch_list;
 pub mod window_editor;
 
 use crate::frontend::{Frontend, FrontendEvent};
@@ -43,7 +65,7 @@ pub mod widget_traits;
 use crate::core::AppCore;
 use anyhow::Result;
 use crossterm::{
-    event::{self, Event, KeyEventKind},
+    event::{self, Event, KeyCode, KeyEventKind, KeyEventState},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
@@ -65,6 +87,8 @@ pub struct TuiFrontend {
     spells_windows: HashMap<String, spells_window::SpellsWindow>,
     /// Cache of ProgressBar widgets per window name
     progress_bars: HashMap<String, progress_bar::ProgressBar>,
+    /// Cache of VitalsCluster widgets per window name
+    vitals_clusters: HashMap<String, vitals_cluster::VitalsCluster>,
     /// Cache of Countdown widgets per window name
     countdowns: HashMap<String, countdown::Countdown>,
     /// Cache of ActiveEffects widgets per window name
@@ -79,6 +103,14 @@ pub struct TuiFrontend {
     targets_widgets: HashMap<String, targets::Targets>,
     /// Cache of Players widgets per window name
     players_widgets: HashMap<String, players::Players>,
+    /// Cache of tracker list widgets (loot, session, bounty, containers, watch list) per window name
+    list_widgets: HashMap<String, list_widget::ListWidget>,
+    /// Cache of Plugin widgets per window name
+    plugin_widgets: HashMap<String, plugin_widget::PluginWidget>,
+    /// Cache of Art windows per window name
+    art_windows: HashMap<String, art_window::ArtWindow>,
+    /// Cache of StatusLine widgets per window name
+    status_lines: HashMap<String, status_line::StatusLine>,
     /// Cache of Dashboard widgets per window name
     dashboard_widgets: HashMap<String, dashboard::Dashboard>,
     /// Cache of TabbedTextWindow widgets per window name
@@ -89,6 +121,8 @@ pub struct TuiFrontend {
     injury_doll_widgets: HashMap<String, injury_doll::InjuryDoll>,
     /// Cache of QuickBar widgets per window name
     quickbar_widgets: HashMap<String, quickbar::QuickBar>,
+    /// Per-window stamps and the last frame, for partial redraws
+    damage: damage::DamageTracker,
     /// Performance stats widget (singleton overlay)
     performance_stats_widget: Option<performance_stats::PerformanceStatsWidget>,
     /// Track last synced generation per text window to know what's new
@@ -120,18 +154,65 @@ pub struct TuiFrontend {
     pub spell_color_browser: Option<spell_color_browser::SpellColorBrowser>,
     /// Active spell color form (if any)
     pub spell_color_form: Option<spell_color_form::SpellColorFormWidget>,
+    /// Active review of unsaved config changes (if any)
+    pub config_diff: Option<config_diff::ConfigDiffView>,
     /// Active theme browser (if any)
     pub theme_browser: Option<theme_browser::ThemeBrowser>,
     /// Active theme editor (if any)
     pub theme_editor: Option<theme_editor::ThemeEditor>,
     /// Active settings editor (if any)
     pub settings_editor: Option<settings_editor::SettingsEditor>,
+    /// Keyboard copy mode cursor (if active)
+    pub copy_mode: Option<copy_mode::CopyMode>,
+    /// Multi-line command composer (if open)
+    pub composer: Option<composer::Composer>,
+    pub snippet_browser: Option<snippet_browser::SnippetBrowser>,
     /// Debouncer for terminal resize events (100ms debounce)
     resize_debouncer: ResizeDebouncer,
     /// Cached theme to avoid HashMap lookup + clone every render
     cached_theme: crate::theme::AppTheme,
     /// Cached theme ID to detect theme changes
     cached_theme_id: String,
+    /// Kitty keyboard protocol flags were pushed (must be popped on exit)
+    keyboard_enhanced: bool,
+    /// Mouse events are captured (off lets the terminal select and paste natively)
+    mouse_captured: bool,
+    /// How long poll_events waits for input (ui.poll_timeout_ms, backing off while idle)
+    idle_poll: idle_poll::IdlePoll,
+    /// Colors the terminal can show; RGB is downsampled after each frame
+    color_mode: color_mode::ColorMode,
+    /// How art windows put images on screen (ui.graphics)
+    graphics: graphics::GraphicsProtocol,
+    /// Lifts low-contrast text after each frame (ui.min_contrast)
+    contrast: contrast::ContrastFilter,
+}
+
+/// Map a kitty-protocol keypad key onto crossterm's dedicated Keypad* codes
+///
+/// With keyboard enhancement on, the terminal tags numpad keys with
+/// `KeyEventState::KEYPAD` but reports them as plain characters.
+fn translate_keypad_key(code: KeyCode, state: KeyEventState) -> KeyCode {
+    if !state.contains(KeyEventState::KEYPAD) {
+        return code;
+    }
+    match code {
+        KeyCode::Char('0') => KeyCode::Keypad0,
+        KeyCode::Char('1') => KeyCode::Keypad1,
+        KeyCode::Char('2') => KeyCode::Keypad2,
+        KeyCode::Char('3') => KeyCode::Keypad3,
+        KeyCode::Char('4') => KeyCode::Keypad4,
+        KeyCode::Char('5') => KeyCode::Keypad5,
+        KeyCode::Char('6') => KeyCode::Keypad6,
+        KeyCode::Char('7') => KeyCode::Keypad7,
+        KeyCode::Char('8') => KeyCode::Keypad8,
+        KeyCode::Char('9') => KeyCode::Keypad9,
+        KeyCode::Char('.') => KeyCode::KeypadPeriod,
+        KeyCode::Char('+') => KeyCode::KeypadPlus,
+        KeyCode::Char('-') => KeyCode::KeypadMinus,
+        KeyCode::Char('*') => KeyCode::KeypadMultiply,
+        KeyCode::Char('/') => KeyCode::KeypadDivide,
+        other => other,
+    }
 }
 
 /// Parse a hex color string like "#RRGGBB" into ratatui Color
@@ -279,6 +360,142 @@ pub fn resolve_window_colors(
     }
 }
 
+/// Setters shared by windows styled straight from their layout entry
+trait StyledWindow {
+    fn set_title(&mut self, title: String);
+    fn set_border_config(&mut self, show: bool, style: Option<String>, color: Option<String>);
+    fn set_border_sides(&mut self, sides: crate::config::BorderSides);
+    fn set_text_color(&mut self, color: Option<String>);
+    fn set_background_color(&mut self, color: Option<String>);
+    fn set_transparent_background(&mut self, transparent: bool);
+}
+
+/// Apply a window's title, border and colors (theme defaults filled in)
+fn apply_window_style(
+    widget: &mut impl StyledWindow,
+    name: &str,
+    base: &crate::config::WindowBase,
+    theme: &crate::theme::AppTheme,
+) {
+    let colors = resolve_window_colors(base, theme);
+    widget.set_title(if base.show_title {
+        base.title.clone().unwrap_or_else(|| name.to_string())
+    } else {
+        String::new()
+    });
+    widget.set_border_config(
+        base.show_border,
+        Some(base.border_style.clone()),
+        colors.border,
+    );
+    widget.set_border_sides(base.border_sides.clone());
+    widget.set_text_color(colors.text);
+    widget.set_background_color(colors.background);
+    widget.set_transparent_background(base.transparent_background);
+}
+
+/// Draw the alignment guides of a window drag: dotted lines across the
+/// screen that keep the text under them readable
+fn render_snap_guides(
+    guides: &[crate::core::snap::SnapGuide],
+    area: ratatui::layout::Rect,
+    buf: &mut ratatui::buffer::Buffer,
+    theme: &crate::theme::AppTheme,
+) {
+    use crate::core::snap::SnapGuide;
+
+    for guide in guides {
+        let (cells, symbol): (Vec<(u16, u16)>, &str) = match *guide {
+            SnapGuide::Column(col) if col < area.right() => (
+                (area.top()..area.bottom()).map(|row| (col, row)).collect(),
+                "┊",
+            ),
+            SnapGuide::Row(row) if row < area.bottom() => (
+                (area.left()..area.right()).map(|col| (col, row)).collect(),
+                "┄",
+            ),
+            _ => continue,
+        };
+        for (col, row) in cells {
+            if let Some(cell) = buf.cell_mut((col, row)) {
+                if cell.symbol() == " " {
+                    cell.set_symbol(symbol);
+                }
+                cell.set_fg(theme.browser_border);
+            }
+        }
+    }
+}
+
+/// Invert a window's outer edge for an alert flash (`flash` on highlights and sounds.toml events)
+fn render_flash_border(area: ratatui::layout::Rect, buf: &mut ratatui::buffer::Buffer) {
+    use ratatui::style::{Color, Modifier, Style};
+
+    let style = Style::default()
+        .fg(Color::Black)
+        .bg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+    for x in area.left()..area.right() {
+        buf[(x, area.top())].set_style(style);
+        buf[(x, area.bottom() - 1)].set_style(style);
+    }
+    for y in area.top()..area.bottom() {
+        buf[(area.left(), y)].set_style(style);
+        buf[(area.right() - 1, y)].set_style(style);
+    }
+}
+
+/// Draw a container's border and title without touching its interior
+fn render_container_frame(
+    base: &crate::config::WindowBase,
+    area: ratatui::layout::Rect,
+    buf: &mut ratatui::buffer::Buffer,
+    theme: &crate::theme::AppTheme,
+) {
+    use ratatui::style::{Color, Style};
+    use ratatui::widgets::{Block, BorderType, Borders, Widget};
+
+    if !base.show_border {
+        return;
+    }
+
+    let mut borders = Borders::NONE;
+    if base.border_sides.top {
+        borders |= Borders::TOP;
+    }
+    if base.border_sides.bottom {
+        borders |= Borders::BOTTOM;
+    }
+    if base.border_sides.left {
+        borders |= Borders::LEFT;
+    }
+    if base.border_sides.right {
+        borders |= Borders::RIGHT;
+    }
+
+    let border_type = match base.border_style.as_str() {
+        "double" => BorderType::Double,
+        "rounded" => BorderType::Rounded,
+        "thick" => BorderType::Thick,
+        _ => BorderType::Plain,
+    };
+    let border_color = resolve_window_colors(base, theme)
+        .border
+        .and_then(|c| parse_hex_color(&c).ok())
+        .unwrap_or(Color::Gray);
+
+    let mut block = Block::default()
+        .borders(borders)
+        .border_type(border_type)
+        .border_style(Style::default().fg(border_color));
+    if base.show_title {
+        if let Some(ref title) = base.title {
+            block = block.title(title.as_str());
+        }
+    }
+    block.render(area, buf);
+}
+
 /// Debouncer for terminal resize events to prevent excessive layout recalculations
 struct ResizeDebouncer {
     last_resize_time: Option<std::time::Instant>,
@@ -437,6 +654,29 @@ mod tests {
         assert_eq!(debouncer.pending_size, None);
     }
 
+    #[test]
+    fn test_translate_keypad_key() {
+        use crossterm::event::{KeyCode, KeyEventState};
+
+        assert_eq!(
+            translate_keypad_key(KeyCode::Char('1'), KeyEventState::KEYPAD),
+            KeyCode::Keypad1
+        );
+        assert_eq!(
+            translate_keypad_key(KeyCode::Char('+'), KeyEventState::KEYPAD),
+            KeyCode::KeypadPlus
+        );
+        // Digit row and non-character keypad keys pass through untouched
+        assert_eq!(
+            translate_keypad_key(KeyCode::Char('1'), KeyEventState::NONE),
+            KeyCode::Char('1')
+        );
+        assert_eq!(
+            translate_keypad_key(KeyCode::Enter, KeyEventState::KEYPAD),
+            KeyCode::Enter
+        );
+    }
+
     #[test]
     fn test_resize_after_debounce_period_immediate() {
         let mut debouncer = ResizeDebouncer::new(10);
@@ -454,15 +694,33 @@ mod tests {
 }
 
 impl TuiFrontend {
-    pub fn new() -> Result<Self> {
+    pub fn new(keyboard_enhancement: bool) -> Result<Self> {
         // Setup terminal
         enable_raw_mode()?;
         let mut stdout = io::stdout();
         execute!(
             stdout,
             EnterAlternateScreen,
-            crossterm::event::EnableMouseCapture
+            crossterm::event::EnableMouseCapture,
+            crossterm::event::EnableFocusChange,
+            crossterm::event::EnableBracketedPaste
         )?;
+
+        // Kitty keyboard protocol lets us tell numpad keys apart from the digit row
+        let keyboard_enhanced = keyboard_enhancement
+            && matches!(
+                crossterm::terminal::supports_keyboard_enhancement(),
+                Ok(true)
+            )
+            && execute!(
+                stdout,
+                crossterm::event::PushKeyboardEnhancementFlags(
+                    crossterm::event::KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
+                )
+            )
+            .is_ok();
+        tracing::debug!("Keyboard enhancement active: {}", keyboard_enhanced);
+
         let backend = CrosstermBackend::new(stdout);
         let terminal = Terminal::new(backend)?;
 
@@ -474,6 +732,7 @@ impl TuiFrontend {
             inventory_windows: HashMap::new(),
             spells_windows: HashMap::new(),
             progress_bars: HashMap::new(),
+            vitals_clusters: HashMap::new(),
             countdowns: HashMap::new(),
             active_effects_windows: HashMap::new(),
             hand_widgets: HashMap::new(),
@@ -481,11 +740,16 @@ impl TuiFrontend {
             indicator_widgets: HashMap::new(),
             targets_widgets: HashMap::new(),
             players_widgets: HashMap::new(),
+            list_widgets: HashMap::new(),
+            plugin_widgets: HashMap::new(),
+            art_windows: HashMap::new(),
+            status_lines: HashMap::new(),
             dashboard_widgets: HashMap::new(),
             tabbed_text_windows: HashMap::new(),
             compass_widgets: HashMap::new(),
             injury_doll_widgets: HashMap::new(),
             quickbar_widgets: HashMap::new(),
+            damage: damage::DamageTracker::default(),
             performance_stats_widget: None,
             last_synced_generation: HashMap::new(),
             popup_menu: None,
@@ -501,21 +765,131 @@ impl TuiFrontend {
             uicolors_browser: None,
             spell_color_browser: None,
             spell_color_form: None,
+            config_diff: None,
             theme_browser: None,
             theme_editor: None,
             settings_editor: None,
+            copy_mode: None,
+            composer: None,
+            snippet_browser: None,
             resize_debouncer: ResizeDebouncer::new(300), // 300ms debounce
             cached_theme: crate::theme::ThemePresets::dark(),
             cached_theme_id: "dark".to_string(),
+            keyboard_enhanced,
+            mouse_captured: true,
+            idle_poll: idle_poll::IdlePoll::new(
+                std::time::Duration::from_millis(16),
+                std::time::Duration::from_millis(16),
+            ),
+            color_mode: color_mode::ColorMode::TrueColor,
+            graphics: graphics::GraphicsProtocol::Text,
+            contrast: contrast::ContrastFilter::new(0.0),
         })
     }
 
+    /// Tell the input loop whether the last pass was idle (no input, data or running timers)
+    pub fn set_idle(&mut self, idle: bool) {
+        self.idle_poll.update(idle, std::time::Instant::now());
+    }
+
+    /// Has polling backed off for idleness? (the main loop ticks less often too)
+    pub fn is_idle(&self) -> bool {
+        self.idle_poll.is_backed_off()
+    }
+
+    /// Push live-applicable settings into frontend widgets (startup and after settings edits)
+    pub fn apply_settings(&mut self, config: &crate::config::Config) {
+        self.idle_poll.set_limits(
+            std::time::Duration::from_millis(config.ui.poll_timeout_ms.max(1)),
+            std::time::Duration::from_millis(config.ui.idle_poll_max_ms),
+        );
+        let color_mode = color_mode::ColorMode::from_setting(&config.ui.color_mode);
+        if color_mode != self.color_mode {
+            tracing::info!(
+                "Color mode: {} (setting '{}')",
+                color_mode.name(),
+                config.ui.color_mode
+            );
+            self.color_mode = color_mode;
+            // Force a full repaint so cells drawn in the old mode are replaced
+            let _ = self.terminal.clear();
+            self.art_windows
+                .values_mut()
+                .for_each(|art| art.invalidate());
+        }
+        let graphics = graphics::GraphicsProtocol::from_setting(&config.ui.graphics);
+        if graphics != self.graphics {
+            tracing::info!(
+                "Graphics: {:?} (setting '{}')",
+                graphics,
+                config.ui.graphics
+            );
+            self.graphics = graphics;
+        }
+        self.contrast.set_min_ratio(config.ui.min_contrast);
+        for cmd_input in self.command_inputs.values_mut() {
+            cmd_input.set_min_command_length(config.ui.min_command_length);
+            cmd_input
+                .set_history_limits(config.ui.history_max_entries, &config.ui.history_denylist);
+        }
+    }
+
     /// Update cached theme (call this when theme changes via command/browser)
     pub fn update_theme_cache(&mut self, theme_id: String, theme: crate::theme::AppTheme) {
         self.cached_theme = theme;
         self.cached_theme_id = theme_id;
     }
 
+    pub fn mouse_captured(&self) -> bool {
+        self.mouse_captured
+    }
+
+    /// Turn crossterm mouse capture on or off without restarting
+    pub fn set_mouse_capture(&mut self, enabled: bool) -> Result<()> {
+        if enabled {
+            execute!(
+                self.terminal.backend_mut(),
+                crossterm::event::EnableMouseCapture
+            )?;
+        } else {
+            execute!(
+                self.terminal.backend_mut(),
+                crossterm::event::DisableMouseCapture
+            )?;
+        }
+        self.mouse_captured = enabled;
+        Ok(())
+    }
+
+    /// Show the theme browser's settled selection on the running UI
+    ///
+    /// Returns true if the theme changed and a render is needed.
+    pub fn poll_theme_preview(&mut self) -> bool {
+        let Some(browser) = self.theme_browser.as_mut() else {
+            return false;
+        };
+        match browser.take_due_preview(std::time::Instant::now()) {
+            Some((theme_id, theme)) => {
+                self.update_theme_cache(theme_id, theme);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Close the theme browser without applying, undoing any preview
+    pub fn close_theme_browser(
+        &mut self,
+        active_theme_id: String,
+        active_theme: crate::theme::AppTheme,
+    ) {
+        if let Some(browser) = self.theme_browser.take() {
+            if browser.is_previewing() {
+                self.update_theme_cache(active_theme_id, active_theme);
+            }
+        }
+    }
+
     /// Navigate to next tab in all tabbed windows
     pub fn next_tab_all(&mut self) {
         for widget in self.tabbed_text_windows.values_mut() {
@@ -541,6 +915,34 @@ impl TuiFrontend {
         false
     }
 
+    /// Collect the highlight patterns that apply to a window, honoring each
+    /// pattern's `windows` scope against the window name and its streams
+    fn highlights_for_window(
+        app_core: &crate::core::AppCore,
+        name: &str,
+    ) -> Vec<crate::config::HighlightPattern> {
+        let streams: &[String] = match app_core.layout.windows.iter().find(|wd| wd.name() == name) {
+            Some(crate::config::WindowDef::Text { data, .. }) => &data.streams,
+            _ => &[],
+        };
+
+        app_core
+            .config
+            .highlights
+            .values()
+            .filter(|h| h.applies_to_window(name, streams))
+            .cloned()
+            .collect()
+    }
+
+    /// Rebuild every text window's precompiled highlight set from config
+    /// (call after highlights are added, edited, or removed)
+    pub fn refresh_highlights(&mut self, app_core: &crate::core::AppCore) {
+        for (name, text_window) in self.text_windows.iter_mut() {
+            text_window.set_highlights(Self::highlights_for_window(app_core, name));
+        }
+    }
+
     /// Sync data from TextContent into TextWindow widgets
     fn sync_text_windows(
         &mut self,
@@ -569,10 +971,8 @@ impl TuiFrontend {
                         tw.set_text_color(colors.text.clone());
                     }
 
-                    // Set highlights from config
-                    let highlights_vec: Vec<_> =
-                        app_core.config.highlights.values().cloned().collect();
-                    tw.set_highlights(highlights_vec);
+                    // Set highlights from config (only those scoped to this window)
+                    tw.set_highlights(Self::highlights_for_window(app_core, name));
 
                     tw
                 });
@@ -590,8 +990,53 @@ impl TuiFrontend {
                     text_window.set_text_color(colors.text.clone());
                 }
 
+                text_window.set_title(text_content.title.clone());
+                text_window.set_pin(
+                    app_core
+                        .ui_state
+                        .pinned_searches
+                        .get(name)
+                        .map(String::as_str),
+                );
+                let timestamp_override = match window_def {
+                    Some(crate::config::WindowDef::Text { data, .. }) => data.timestamps.as_ref(),
+                    _ => None,
+                };
+                text_window.set_timestamps(
+                    &app_core
+                        .config
+                        .ui
+                        .timestamps
+                        .with_override(timestamp_override),
+                );
+                text_window.set_fade_minutes(app_core.config.ui.text_fade_minutes);
+                text_window.set_more_prompt(app_core.config.ui.more_prompt && name == "main");
+                let gap_override = match window_def {
+                    Some(crate::config::WindowDef::Text { data, .. }) => data.gap_separator_minutes,
+                    _ => None,
+                };
+                text_window.set_gap_minutes(app_core.config.ui.gap_minutes_for(name, gap_override));
+                let collapse_override = match window_def {
+                    Some(crate::config::WindowDef::Text { data, .. }) => data.collapse_repeats,
+                    _ => None,
+                };
+                text_window.set_collapse_repeats(
+                    collapse_override.unwrap_or(app_core.config.ui.collapse_repeats),
+                );
+                text_window.set_obscured(app_core.ui_state.is_overlapped(name));
+                text_window.set_max_lines(text_content.max_lines);
+                let disk_lines = app_core.config.ui.scrollback_disk_lines;
+                if text_window.disk_scrollback_limit() != disk_lines {
+                    match crate::config::Config::scrollback_dir(
+                        app_core.config.character.as_deref(),
+                    ) {
+                        Ok(dir) => text_window.set_disk_scrollback(&dir, name, disk_lines),
+                        Err(e) => tracing::warn!("No scrollback directory: {:#}", e),
+                    }
+                }
+
                 // Update width for proper wrapping
-                text_window.set_width(window.position.width);
+                text_window.set_outer_width(window.position.width);
 
                 // Get last synced generation
                 let last_synced_gen = self.last_synced_generation.get(name).copied().unwrap_or(0);
@@ -629,11 +1074,14 @@ impl TuiFrontend {
                             // Map data layer SpanType to TextWindow SpanType
                             use crate::data::SpanType as DataSpanType;
                             let tw_span_type = match segment.span_type {
-                                DataSpanType::Normal => text_window::SpanType::Normal,
+                                DataSpanType::Normal | DataSpanType::Whisper => {
+                                    text_window::SpanType::Normal
+                                }
                                 DataSpanType::Link => text_window::SpanType::Link,
                                 DataSpanType::Monsterbold => text_window::SpanType::Monsterbold,
                                 DataSpanType::Spell => text_window::SpanType::Spell,
                                 DataSpanType::Speech => text_window::SpanType::Speech,
+                                DataSpanType::Url => text_window::SpanType::Url,
                             };
 
                             let styled_text = text_window::StyledText {
@@ -654,6 +1102,7 @@ impl TuiFrontend {
                                         noun: ld.noun.clone(),
                                         text: ld.text.clone(),
                                         coord: ld.coord.clone(),
+                                        flags: ld.flags,
                                     }
                                 }),
                             };
@@ -714,6 +1163,15 @@ impl TuiFrontend {
         app_core: &crate::core::AppCore,
         theme: &crate::theme::AppTheme,
     ) {
+        // Hard roundtime wins over casttime
+        let timer = app_core.config.ui.input_timer.then(|| {
+            let now = chrono::Utc::now().timestamp();
+            match app_core.game_state.roundtime_left(now) {
+                0 => command_input::InputTimer::Casttime(app_core.game_state.casttime_left(now)),
+                rt => command_input::InputTimer::Roundtime(rt),
+            }
+        });
+
         for (name, window) in &app_core.ui_state.windows {
             if !matches!(
                 window.content,
@@ -798,6 +1256,7 @@ impl TuiFrontend {
                     .or_else(|| color_to_hex_string(&theme.text_primary));
                 cmd_input.set_cursor_colors(cursor_fg, cursor_bg);
             }
+            cmd_input.set_timer(timer);
         }
     }
 
@@ -839,6 +1298,7 @@ impl TuiFrontend {
                     if let Some(def) = window_def {
                         let colors = resolve_window_colors(def.base(), theme);
                         inv_window.set_border_config(def.base().show_border, colors.border.clone());
+                        inv_window.set_border_sides(def.base().border_sides.clone());
                         inv_window.set_transparent_background(def.base().transparent_background);
                         inv_window.set_background_color(colors.background.clone());
                         inv_window.set_text_color(colors.text.clone());
@@ -864,6 +1324,14 @@ impl TuiFrontend {
                         self.last_synced_generation
                             .insert(name.clone(), current_gen);
                     }
+
+                    let collapsed = match window_def {
+                        Some(crate::config::WindowDef::Inventory { data, .. }) => {
+                            data.collapsed.as_slice()
+                        }
+                        _ => &[],
+                    };
+                    inv_window.update_tree(collapsed);
                 } else {
                     tracing::warn!(
                         "Inventory widget '{}' not found in inventory_windows HashMap!",
@@ -1066,22 +1534,10 @@ impl TuiFrontend {
 
                         // Get bar color from ProgressWidgetData, or fallback to VellumFE defaults
                         if let crate::config::WindowDef::Progress { data, .. } = def {
-                            let bar_color = if let Some(ref color) = data.color {
-                                Some(color.clone())
-                            } else {
-                                // Fallback to VellumFE template colors for known progress bars
-                                match name.as_str() {
-                                    "health" => Some("#6e0202".to_string()),     // Dark red
-                                    "mana" => Some("#08086d".to_string()),       // Dark blue
-                                    "stamina" => Some("#bd7b00".to_string()),    // Orange
-                                    "spirit" => Some("#6e727c".to_string()),     // Gray
-                                    "encumlevel" => Some("#ffff00".to_string()), // Yellow
-                                    "pbarStance" => Some("#ffa500".to_string()), // Orange
-                                    "mindState" => Some("#9370db".to_string()),  // Purple
-                                    "lblBPs" => Some("#ff4500".to_string()),     // Orange-red
-                                    _ => None,
-                                }
-                            };
+                            // Fallback to VellumFE template colors for known progress bars
+                            let bar_color = data.color.clone().or_else(|| {
+                                progress_bar::default_fill_color(name).map(str::to_string)
+                            });
 
                             if let Some(color) = bar_color {
                                 progress_bar.set_colors(Some(color), None);
@@ -1095,11 +1551,71 @@ impl TuiFrontend {
                         progress_bar.set_transparent_background(def.base().transparent_background);
                         progress_bar.set_background_color(colors.background.clone());
                     }
+                    progress_bar.set_plain_text(app_core.screen_reader_active());
                 }
             }
         }
     }
 
+    /// Sync vitals clusters - create/configure widgets and rebuild their rows
+    fn sync_vitals_clusters(
+        &mut self,
+        app_core: &crate::core::AppCore,
+        theme: &crate::theme::AppTheme,
+    ) {
+        for (name, window) in &app_core.ui_state.windows {
+            if let crate::data::WindowContent::Vitals { bars } = &window.content {
+                let Some(crate::config::WindowDef::Vitals { base, data }) =
+                    app_core.layout.windows.iter().find(|wd| wd.name() == *name)
+                else {
+                    continue;
+                };
+
+                let cluster = self.vitals_clusters.entry(name.clone()).or_insert_with(|| {
+                    tracing::debug!("Created VitalsCluster widget for '{}'", name);
+                    vitals_cluster::VitalsCluster::new(name)
+                });
+
+                let colors = resolve_window_colors(base, theme);
+                cluster.set_title(if base.show_title {
+                    base.title.clone().unwrap_or_else(|| name.clone())
+                } else {
+                    String::new()
+                });
+                cluster.set_border_config(
+                    base.show_border,
+                    Some(base.border_style.clone()),
+                    colors.border.clone(),
+                );
+                cluster.set_text_color(colors.text.clone());
+                cluster.set_transparent_background(base.transparent_background);
+                cluster.set_background_color(colors.background.clone());
+
+                // Rows follow the configured children; values come from the game
+                let rows = data
+                    .children
+                    .iter()
+                    .map(|child| {
+                        let progress = bars.iter().find(|(id, _)| *id == child.id).map(|(_, p)| p);
+                        let fill = child
+                            .color
+                            .as_deref()
+                            .or_else(|| progress_bar::default_fill_color(&child.id))
+                            .and_then(vitals_cluster::VitalsCluster::parse_color);
+                        vitals_cluster::VitalsRow {
+                            label: child.label.clone().unwrap_or_else(|| child.id.clone()),
+                            current: progress.map(|p| p.value).unwrap_or(0),
+                            max: progress.map(|p| p.max).unwrap_or(100),
+                            text: progress.map(|p| p.label.clone()).unwrap_or_default(),
+                            fill,
+                        }
+                    })
+                    .collect();
+                cluster.set_rows(rows);
+            }
+        }
+    }
+
     /// Sync countdown data - create/configure countdown widgets
     fn sync_countdowns(&mut self, app_core: &crate::core::AppCore, theme: &crate::theme::AppTheme) {
         // Find countdown windows in ui_state
@@ -1145,6 +1661,7 @@ impl TuiFrontend {
                         countdown_widget
                             .set_transparent_background(def.base().transparent_background);
                     }
+                    countdown_widget.set_plain_text(app_core.screen_reader_active());
                 }
             }
         }
@@ -1315,7 +1832,12 @@ impl TuiFrontend {
 
                 // Update widget
                 if let Some(widget) = self.targets_widgets.get_mut(name) {
-                    widget.set_targets_from_text(targets_text);
+                    let alerts = &app_core.config.creatures;
+                    widget.set_targets_from_text(targets_text, |target| {
+                        let (_, info) = app_core.creatures.lookup(target)?;
+                        (alerts.color_targets && info.danger >= alerts.warn_at)
+                            .then(|| alerts.color.clone())
+                    });
 
                     // Apply configuration
                     if let Some(window_def) =
@@ -1377,6 +1899,160 @@ impl TuiFrontend {
         }
     }
 
+    /// Sync the tracker list widgets (loot, session stats, bounty, containers,
+    /// watch list) with their trackers
+    fn sync_list_widgets(
+        &mut self,
+        app_core: &crate::core::AppCore,
+        theme: &crate::theme::AppTheme,
+    ) {
+        use crate::config::WindowDef;
+        use crate::data::WindowContent;
+
+        for (name, window) in &app_core.ui_state.windows {
+            if !matches!(
+                window.content,
+                WindowContent::LootTracker
+                    | WindowContent::SessionStats
+                    | WindowContent::BountyTracker
+                    | WindowContent::ContainerTree
+                    | WindowContent::WatchList
+            ) {
+                continue;
+            }
+            let Some(def) = app_core.layout.windows.iter().find(|w| w.name() == name) else {
+                continue;
+            };
+
+            let widget = self
+                .list_widgets
+                .entry(name.clone())
+                .or_insert_with(|| list_widget::ListWidget::new(name));
+            widget.rebuild(|rows| match (&window.content, def) {
+                (WindowContent::LootTracker, WindowDef::LootTracker { data, .. }) => {
+                    loot_tracker::fill(rows, &app_core.loot, data.gem_rows)
+                }
+                (WindowContent::SessionStats, _) => {
+                    session_stats::fill(rows, &app_core.session, app_core.loot.session.silvers)
+                }
+                (WindowContent::BountyTracker, _) => bounty_tracker::fill(rows, &app_core.bounty),
+                (WindowContent::ContainerTree, _) => {
+                    container_tree::fill(rows, &app_core.containers)
+                }
+                (WindowContent::WatchList, WindowDef::WatchList { data, .. }) => watch_list::fill(
+                    rows,
+                    &app_core.watch,
+                    &app_core.config.watchlist,
+                    data.show_offline,
+                ),
+                _ => {}
+            });
+            apply_window_style(widget, name, def.base(), theme);
+        }
+    }
+
+    /// Sync plugin widgets with the lines their providers last sent
+    fn sync_plugin_widgets(
+        &mut self,
+        app_core: &crate::core::AppCore,
+        theme: &crate::theme::AppTheme,
+    ) {
+        for (name, window) in &app_core.ui_state.windows {
+            if !matches!(window.content, crate::data::WindowContent::Plugin) {
+                continue;
+            }
+            let Some(def) = app_core.layout.windows.iter().find(|w| w.name() == name) else {
+                continue;
+            };
+            let provider = match def {
+                crate::config::WindowDef::Plugin { data, .. } => {
+                    data.provider.as_deref().unwrap_or(name)
+                }
+                _ => name,
+            };
+
+            let widget = self
+                .plugin_widgets
+                .entry(name.clone())
+                .or_insert_with(|| plugin_widget::PluginWidget::new(name));
+            widget.set_lines(provider, app_core.plugins.widget_lines(provider));
+            apply_window_style(widget, name, def.base(), theme);
+        }
+    }
+
+    /// Sync art windows with their image files ("{room}" follows the current room)
+    fn sync_art_windows(
+        &mut self,
+        app_core: &crate::core::AppCore,
+        theme: &crate::theme::AppTheme,
+    ) {
+        let art_dir = crate::config::Config::base_dir()
+            .ok()
+            .map(|dir| dir.join("art"));
+        for (name, window) in &app_core.ui_state.windows {
+            if !matches!(window.content, crate::data::WindowContent::Art) {
+                continue;
+            }
+            let Some(crate::config::WindowDef::Art { base, data }) =
+                app_core.layout.windows.iter().find(|w| w.name() == name)
+            else {
+                continue;
+            };
+
+            let path = data.path.as_deref().and_then(|path| {
+                let mut path = path.to_string();
+                for (key, id) in [
+                    ("{room}", &app_core.nav_room_id),
+                    ("{lich_room}", &app_core.lich_room_id),
+                ] {
+                    if path.contains(key) {
+                        path = path.replace(key, id.as_deref()?);
+                    }
+                }
+                let path = std::path::PathBuf::from(path);
+                match &art_dir {
+                    Some(dir) if path.is_relative() => Some(dir.join(path)),
+                    _ => Some(path),
+                }
+            });
+
+            let widget = self
+                .art_windows
+                .entry(name.clone())
+                .or_insert_with(|| art_window::ArtWindow::new(name));
+            widget.set_source(path, data.alt.clone());
+            apply_window_style(widget, name, base, theme);
+        }
+    }
+
+    /// Sync status lines with their templates filled from game state
+    fn sync_status_lines(
+        &mut self,
+        app_core: &crate::core::AppCore,
+        theme: &crate::theme::AppTheme,
+    ) {
+        let now = chrono::Utc::now().timestamp();
+        for (name, window) in &app_core.ui_state.windows {
+            if !matches!(window.content, crate::data::WindowContent::StatusLine) {
+                continue;
+            }
+            let Some(crate::config::WindowDef::StatusLine { base, data }) =
+                app_core.layout.windows.iter().find(|w| w.name() == name)
+            else {
+                continue;
+            };
+
+            let widget = self
+                .status_lines
+                .entry(name.clone())
+                .or_insert_with(|| status_line::StatusLine::new(name));
+            widget.set_runs(status_line::expand_template(&data.template, |field| {
+                app_core.game_state.status_field(field, now)
+            }));
+            apply_window_style(widget, name, base, theme);
+        }
+    }
+
     /// Sync dashboard widget data from AppCore to dashboard widgets
     fn sync_dashboard_widgets(
         &mut self,
@@ -1459,7 +2135,31 @@ impl TuiFrontend {
                         widget.set_transparent_background(window_def.base().transparent_background);
                         widget.set_background_color(colors.background.clone());
                         widget.apply_window_colors(colors.text.clone(), colors.background.clone());
+
+                        // Window-wide overrides, then each tab's show_timestamps
+                        if let crate::config::WindowDef::TabbedText { data, .. } = window_def {
+                            let timestamps = app_core
+                                .config
+                                .ui
+                                .timestamps
+                                .with_override(data.timestamps.as_ref());
+                            for tab in &data.tabs {
+                                let mut tab_timestamps = timestamps.clone();
+                                if let Some(enabled) = tab.show_timestamps {
+                                    tab_timestamps.enabled = enabled;
+                                }
+                                widget.set_tab_timestamps(&tab.name, &tab_timestamps);
+                            }
+                            widget.set_gap_minutes(
+                                app_core
+                                    .config
+                                    .ui
+                                    .gap_minutes_for(name, data.gap_separator_minutes),
+                            );
+                        }
                     }
+                    widget.set_fade_minutes(app_core.config.ui.text_fade_minutes);
+                    widget.set_collapse_repeats(app_core.config.ui.collapse_repeats);
                 }
             }
         }
@@ -1848,6 +2548,24 @@ impl TuiFrontend {
             return;
         }
 
+        if let Some(list) = self.list_widgets.get_mut(window_name) {
+            if lines > 0 {
+                list.scroll_up(lines as usize);
+            } else if lines < 0 {
+                list.scroll_down((-lines) as usize);
+            }
+            return;
+        }
+
+        if let Some(plugin) = self.plugin_widgets.get_mut(window_name) {
+            if lines > 0 {
+                plugin.scroll_up(lines as usize);
+            } else if lines < 0 {
+                plugin.scroll_down((-lines) as usize);
+            }
+            return;
+        }
+
         // Try quickbar widget
         if let Some(quickbar) = self.quickbar_widgets.get_mut(window_name) {
             // QuickBar scrolls 1 row at a time
@@ -1888,17 +2606,128 @@ impl TuiFrontend {
         text_window.mouse_to_text_coords(mouse_col, mouse_row, window_rect)
     }
 
-    /// Extract selected text from a text window
-    pub fn extract_selection_text(
+    /// A selection as plain text, ANSI and HTML (colors as drawn in the window)
+    pub fn selection_copy(
         &self,
         window_name: &str,
-        start_line: usize,
-        start_col: usize,
-        end_line: usize,
-        end_col: usize,
-    ) -> Option<String> {
+        selection: &crate::selection::SelectionState,
+    ) -> Option<crate::selection::SelectionCopy> {
+        let text_window = self.text_windows.get(window_name)?;
+        let plain = if selection.block {
+            let (top, bottom) = selection.block_lines();
+            let (left, right) = selection.block_columns();
+            text_window.extract_block_text(top, bottom, left, right)
+        } else {
+            let (start, end) = selection.normalized_range();
+            text_window.extract_selection_text(start.line, start.col, end.line, end.col)
+        };
+        let rows = text_window.selected_spans(selection);
+        Some(crate::selection::SelectionCopy {
+            plain,
+            ansi: rich_copy::to_ansi(&rows),
+            html: rich_copy::to_html(&rows, Some(self.cached_theme.window_background)),
+        })
+    }
+
+    /// Selection for a double (word) or triple (whole line) click at a text position
+    pub fn click_selection(
+        &self,
+        window_name: &str,
+        window_index: usize,
+        line: usize,
+        col: usize,
+        clicks: u8,
+    ) -> Option<crate::selection::SelectionState> {
+        use crate::selection::SelectionState;
         let text_window = self.text_windows.get(window_name)?;
-        Some(text_window.extract_selection_text(start_line, start_col, end_line, end_col))
+        let (first, last, start, end) = match clicks {
+            2 => {
+                let (start, end) =
+                    crate::selection::word_bounds(&text_window.line_text(line), col)?;
+                (line, line, start, end)
+            }
+            3 => {
+                let (first, last) = text_window.logical_line_rows(line);
+                (first, last, 0, text_window.line_char_count(last))
+            }
+            _ => return None,
+        };
+        let mut selection = SelectionState::new(window_index, first, start);
+        selection.update_end(window_index, last, end);
+        Some(selection)
+    }
+
+    /// Put a copy mode cursor on the last visible line of a text window
+    pub fn start_copy_mode(&mut self, window_name: &str, window_index: usize) -> bool {
+        let Some(text_window) = self.text_windows.get_mut(window_name) else {
+            return false;
+        };
+        let total_lines = text_window.wrapped_line_count();
+        if total_lines == 0 {
+            return false;
+        }
+        // New output shouldn't move the text out from under the cursor
+        text_window.hold_view();
+        let visible_height = text_window.visible_height();
+        let line = text_window
+            .relative_row_to_absolute_line(visible_height.saturating_sub(1), visible_height)
+            .min(total_lines - 1);
+        self.copy_mode = Some(copy_mode::CopyMode::new(window_name, window_index, line));
+        true
+    }
+
+    /// Feed a key to copy mode, keeping the cursor in view
+    pub fn copy_mode_key(
+        &mut self,
+        code: KeyCode,
+        modifiers: crossterm::event::KeyModifiers,
+    ) -> Option<copy_mode::CopyModeOutcome> {
+        let mode = self.copy_mode.as_mut()?;
+        let text_window = self.text_windows.get_mut(&mode.window_name)?;
+        let outcome = mode.handle_key(
+            code,
+            modifiers,
+            text_window.wrapped_line_count(),
+            text_window.visible_height(),
+            |line| text_window.line_char_count(line),
+        );
+        text_window.scroll_to_line(mode.line);
+        Some(outcome)
+    }
+
+    /// The copy mode selection to highlight (None when nothing is marked)
+    pub fn copy_mode_selection(&self) -> Option<crate::selection::SelectionState> {
+        self.copy_mode.as_ref()?.selection()
+    }
+
+    /// What copy mode's yank takes: the selection, or the cursor's line
+    pub fn copy_mode_yank(&self) -> Option<crate::selection::SelectionCopy> {
+        let mode = self.copy_mode.as_ref()?;
+        let text_window = self.text_windows.get(&mode.window_name)?;
+        let selection = mode.yank_selection(text_window.line_char_count(mode.line));
+        self.selection_copy(&mode.window_name, &selection)
+    }
+
+    /// Leave copy mode and go back to following live output
+    pub fn end_copy_mode(&mut self) {
+        if let Some(mode) = self.copy_mode.take() {
+            if let Some(text_window) = self.text_windows.get_mut(&mode.window_name) {
+                text_window.scroll_to_live();
+            }
+        }
+    }
+
+    /// Open the composer with `lines`, or with whatever is in the command input
+    pub fn open_composer(&mut self, mut lines: Vec<String>) {
+        if lines.is_empty() {
+            if let Some(cmd_input) = self.command_inputs.get_mut("command_input") {
+                if let Some(text) = cmd_input.get_input() {
+                    lines.push(text);
+                    cmd_input.clear();
+                }
+            }
+        }
+        self.composer = Some(composer::Composer::new(lines));
     }
 
     /// Ensure a command input widget exists (should be called during init)
@@ -1918,8 +2747,7 @@ impl TuiFrontend {
         window_name: &str,
         code: crossterm::event::KeyCode,
         modifiers: crossterm::event::KeyModifiers,
-        available_commands: &[String],
-        available_window_names: &[String],
+        app_core: &crate::core::AppCore,
     ) {
         use crossterm::event::{KeyCode, KeyModifiers};
 
@@ -1932,12 +2760,19 @@ impl TuiFrontend {
             self.ensure_command_input_exists(window_name);
         }
 
+        // Unbound numpad keys type their character
+        let code = crate::config::keypad_char(code).map_or(code, KeyCode::Char);
+
         if let Some(cmd_input) = self.command_inputs.get_mut(window_name) {
+            if cmd_input.history_search_key(code, modifiers) {
+                return;
+            }
             match code {
                 KeyCode::Char(c) => {
                     if modifiers.contains(KeyModifiers::CONTROL) {
                         match c {
                             'a' => cmd_input.move_cursor_home(),
+                            'r' => cmd_input.history_search(),
                             'e' => cmd_input.move_cursor_end(),
                             'u' => cmd_input.clear(),
                             'w' => {
@@ -2003,14 +2838,71 @@ impl TuiFrontend {
                 KeyCode::Up => cmd_input.history_previous(),
                 KeyCode::Down => cmd_input.history_next(),
                 KeyCode::Tab => {
-                    // Tab completion for commands and window names
-                    cmd_input.try_complete(available_commands, available_window_names);
+                    // Tab completion for commands, window names and game words
+                    cmd_input.try_complete(|source| match source {
+                        command_input::CompletionSource::Commands => {
+                            app_core.get_available_commands()
+                        }
+                        command_input::CompletionSource::WindowNames => app_core.get_window_names(),
+                        command_input::CompletionSource::GameWords => {
+                            app_core.get_completion_words()
+                        }
+                    });
                 }
                 _ => {}
             }
         }
     }
 
+    /// Whether a command input has anything typed in it
+    pub fn command_input_has_text(&self, window_name: &str) -> bool {
+        self.command_inputs
+            .get(window_name)
+            .is_some_and(|cmd_input| cmd_input.get_input().is_some())
+    }
+
+    /// Insert text at the cursor of a command input (e.g. dictated speech)
+    pub fn command_input_insert_text(&mut self, window_name: &str, text: &str) {
+        if let Some(cmd_input) = self.command_inputs.get_mut(window_name) {
+            for c in text.chars() {
+                cmd_input.insert_char(c);
+            }
+        }
+    }
+
+    /// Type macro text into a command input, leaving the cursor `cursor` chars into it
+    pub fn command_input_type(&mut self, window_name: &str, text: &str, cursor: usize) {
+        if let Some(cmd_input) = self.command_inputs.get_mut(window_name) {
+            for c in text.chars() {
+                cmd_input.insert_char(c);
+            }
+            for _ in cursor..text.chars().count() {
+                cmd_input.move_cursor_left();
+            }
+        }
+    }
+
+    /// Put a command input's cursor under a click at column `x`
+    pub fn command_input_click(&mut self, window_name: &str, x: u16, area: ratatui::layout::Rect) {
+        if let Some(cmd_input) = self.command_inputs.get_mut(window_name) {
+            cmd_input.click_at(area, x);
+        }
+    }
+
+    /// End a Ctrl+R history search in a command input (false if none was running)
+    pub fn command_input_cancel_search(&mut self, window_name: &str) -> bool {
+        self.command_inputs
+            .get_mut(window_name)
+            .is_some_and(|cmd_input| cmd_input.cancel_history_search())
+    }
+
+    /// Clear a command input
+    pub fn command_input_clear(&mut self, window_name: &str) {
+        if let Some(cmd_input) = self.command_inputs.get_mut(window_name) {
+            cmd_input.clear();
+        }
+    }
+
     /// Submit command from command input and return the command string
     pub fn command_input_submit(&mut self, window_name: &str) -> Option<String> {
         self.command_inputs.get_mut(window_name)?.submit()
@@ -2112,6 +3004,59 @@ impl TuiFrontend {
         }
     }
 
+    /// Inventory container node to expand/collapse for a click at the mouse
+    pub fn inventory_node_at(
+        &self,
+        window_name: &str,
+        mouse_col: u16,
+        mouse_row: u16,
+        window_rect: ratatui::layout::Rect,
+    ) -> Option<String> {
+        self.inventory_windows
+            .get(window_name)?
+            .node_at(window_rect, mouse_col, mouse_row)
+            .map(str::to_string)
+    }
+
+    /// Item on the inventory row under the mouse (drop target for dragged items)
+    pub fn inventory_item_at(
+        &self,
+        window_name: &str,
+        mouse_col: u16,
+        mouse_row: u16,
+        window_rect: ratatui::layout::Rect,
+    ) -> Option<crate::data::LinkData> {
+        self.inventory_windows
+            .get(window_name)?
+            .item_at(window_rect, mouse_col, mouse_row)
+    }
+
+    /// Player name under the mouse in a players window
+    pub fn player_at(
+        &self,
+        window_name: &str,
+        mouse_col: u16,
+        mouse_row: u16,
+        window_rect: ratatui::layout::Rect,
+    ) -> Option<String> {
+        self.players_widgets
+            .get(window_name)?
+            .name_at(window_rect, mouse_col, mouse_row)
+    }
+
+    /// Body part under the mouse in an injury doll window
+    pub fn injury_part_at(
+        &self,
+        window_name: &str,
+        mouse_col: u16,
+        mouse_row: u16,
+        window_rect: ratatui::layout::Rect,
+    ) -> Option<&'static str> {
+        self.injury_doll_widgets
+            .get(window_name)?
+            .part_at(window_rect, mouse_col, mouse_row)
+    }
+
     /// Find a link at a given mouse position in a text or room window
     pub fn link_at_position(
         &self,
@@ -2146,7 +3091,7 @@ impl TuiFrontend {
             let line = &visible_lines[line_idx];
             let mut col = 0usize;
             for seg in &line.segments {
-                let seg_len = seg.text.chars().count();
+                let seg_len = text_window::TextWindow::text_width(&seg.text);
                 if col_offset >= col && col_offset < col + seg_len {
                     // Inside this segment
                     if let Some(link) = seg.link_data.clone() {
@@ -2156,6 +3101,7 @@ impl TuiFrontend {
                             noun: link.noun,
                             text: link.text,
                             coord: link.coord,
+                            flags: link.flags,
                         };
                         // For <d> tags without cmd attribute, populate text from segment
                         if data_link.text.is_empty() {
@@ -2223,7 +3169,7 @@ impl TuiFrontend {
             );
             let mut col = 0usize;
             for (seg_idx, seg) in line.iter().enumerate() {
-                let seg_len = seg.text.chars().count();
+                let seg_len = text_window::TextWindow::text_width(&seg.text);
                 tracing::debug!(
                     "  Segment {}: text='{}', col={}, len={}, has_link={}",
                     seg_idx,
@@ -2247,6 +3193,7 @@ impl TuiFrontend {
                             noun: link.noun.clone(),
                             text: link.text.clone(),
                             coord: link.coord.clone(),
+                            flags: link.flags,
                         };
                         // For <d> tags without cmd attribute, populate text from segment
                         if data_link.text.is_empty() {
@@ -2266,91 +3213,7 @@ impl TuiFrontend {
 
         // Try inventory window
         if let Some(inventory_window) = self.inventory_windows.get(window_name) {
-            tracing::debug!(
-                "Checking inventory window '{}' for link at ({}, {})",
-                window_name,
-                mouse_col,
-                mouse_row
-            );
-            let border_offset = 1u16; // Inventory windows always have borders
-
-            // Bounds check within content area
-            if mouse_col < window_rect.x + border_offset
-                || mouse_col >= window_rect.x + window_rect.width - border_offset
-                || mouse_row < window_rect.y + border_offset
-                || mouse_row >= window_rect.y + window_rect.height - border_offset
-            {
-                tracing::debug!("Mouse click outside inventory window content area");
-                return None;
-            }
-
-            let wrapped_lines = inventory_window.get_wrapped_lines();
-            let start_line = inventory_window.get_start_line(); // Get scroll offset
-            tracing::debug!(
-                "Inventory window has {} wrapped lines, start_line={}",
-                wrapped_lines.len(),
-                start_line
-            );
-
-            // Map visual row to actual line index (accounting for scroll/overflow)
-            let visual_line_idx = (mouse_row - window_rect.y - border_offset) as usize;
-            let line_idx = start_line + visual_line_idx;
-            let col_offset = (mouse_col - window_rect.x - border_offset) as usize;
-
-            if line_idx >= wrapped_lines.len() {
-                tracing::debug!(
-                    "Line index {} (visual={}, start={}) out of range",
-                    line_idx,
-                    visual_line_idx,
-                    start_line
-                );
-                return None;
-            }
-
-            let line = &wrapped_lines[line_idx];
-            tracing::debug!(
-                "Checking line {} with {} segments, col_offset={}",
-                line_idx,
-                line.len(),
-                col_offset
-            );
-            let mut col = 0usize;
-            for (seg_idx, seg) in line.iter().enumerate() {
-                let seg_len = seg.text.chars().count();
-                tracing::debug!(
-                    "  Segment {}: text='{}', col={}, len={}, has_link={}",
-                    seg_idx,
-                    seg.text,
-                    col,
-                    seg_len,
-                    seg.link_data.is_some()
-                );
-
-                if col_offset >= col && col_offset < col + seg_len {
-                    // Inside this segment
-                    tracing::debug!("  Click is inside this segment!");
-                    if let Some(link) = seg.link_data.clone() {
-                        tracing::debug!(
-                            "  Found link: exist_id={}, noun={}",
-                            link.exist_id,
-                            link.noun
-                        );
-                        let data_link = crate::data::LinkData {
-                            exist_id: link.exist_id.clone(),
-                            noun: link.noun.clone(),
-                            text: link.text.clone(),
-                            coord: link.coord.clone(),
-                        };
-                        return Some(data_link);
-                    }
-                    tracing::debug!("  Segment has no link data");
-                    return None;
-                }
-                col += seg_len;
-            }
-
-            tracing::debug!("No segment matched at col_offset={}", col_offset);
-            return None;
+            return inventory_window.link_at(window_rect, mouse_col, mouse_row);
         }
 
         // Try hand widget
@@ -2387,6 +3250,7 @@ impl TuiFrontend {
                         noun: link.noun.clone(),
                         text: link.text.clone(),
                         coord: link.coord.clone(),
+                        flags: link.flags,
                     });
                 }
             }
@@ -2414,6 +3278,30 @@ impl TuiFrontend {
         }
     }
 
+    /// Page on from "-- more --" in the main window, or mark what's shown as read
+    pub fn acknowledge_more(&mut self) {
+        if let Some(text_window) = self.text_windows.get_mut("main") {
+            text_window.acknowledge_more();
+        }
+    }
+
+    /// Whether the main window is holding output at "-- more --"
+    pub fn is_more_paused(&self) -> bool {
+        self.text_windows
+            .get("main")
+            .is_some_and(|w| w.is_more_paused())
+    }
+
+    /// Plain text of a text window's whole buffer (a tabbed window's active tab)
+    pub fn window_buffer_text(&mut self, window_name: &str) -> Option<Vec<String>> {
+        if let Some(text_window) = self.text_windows.get_mut(window_name) {
+            return Some(text_window.buffer_text());
+        }
+        self.tabbed_text_windows
+            .get_mut(window_name)
+            .map(|tabbed| tabbed.buffer_text())
+    }
+
     /// Go to next search match
     pub fn next_search_match(&mut self, window_name: &str) -> bool {
         if let Some(text_window) = self.text_windows.get_mut(window_name) {
@@ -2452,13 +3340,13 @@ impl Frontend for TuiFrontend {
         let mut events = Vec::new();
 
         // Poll for events (non-blocking)
-        if event::poll(std::time::Duration::from_millis(16))? {
+        if event::poll(self.idle_poll.timeout())? {
             match event::read()? {
                 Event::Key(key) => {
                     // Only process key press events, not release events
                     if key.kind == KeyEventKind::Press {
                         events.push(FrontendEvent::Key {
-                            code: key.code,
+                            code: translate_keypad_key(key.code, key.state),
                             modifiers: key.modifiers,
                         });
                     }
@@ -2480,6 +3368,8 @@ impl Frontend for TuiFrontend {
                 Event::Paste(text) => {
                     events.push(FrontendEvent::Paste { text });
                 }
+                Event::FocusGained => events.push(FrontendEvent::Focus { gained: true }),
+                Event::FocusLost => events.push(FrontendEvent::Focus { gained: false }),
                 _ => {}
             }
         }
@@ -2500,38 +3390,80 @@ impl Frontend for TuiFrontend {
 
         // Clone theme once so all sync tasks share the same palette
         let theme = self.cached_theme.clone();
+        let render_started = std::time::Instant::now();
+
+        // Time each widget kind's sync for the performance overlay
+        let mut sync_timer = crate::performance::LapTimer::start();
 
         // Sync data from data layer into TextWindows
         self.sync_text_windows(app_core, &theme);
+        sync_timer.lap("text");
 
         // Sync CommandInput widget configuration from layout
         self.sync_command_inputs(app_core, &theme);
+        sync_timer.lap("command_input");
 
         // Sync room window data from AppCore
         self.sync_room_windows(app_core, &theme);
+        sync_timer.lap("room");
 
         // Sync inventory window data from AppCore
         self.sync_inventory_windows(app_core, &theme);
+        sync_timer.lap("inventory");
 
         // Sync spells window data from AppCore
         self.sync_spells_windows(app_core, &theme);
+        sync_timer.lap("spells");
 
         // Sync quickbar widgets from AppCore
         self.sync_quickbar_widgets(app_core, &theme);
+        sync_timer.lap("quickbar");
 
         // Sync progress bar data from AppCore
         self.sync_progress_bars(app_core, &theme);
+        sync_timer.lap("progress");
+        self.sync_vitals_clusters(app_core, &theme);
+        sync_timer.lap("vitals");
         self.sync_countdowns(app_core, &theme);
+        sync_timer.lap("countdown");
         self.sync_active_effects(app_core, &theme);
+        sync_timer.lap("active_effects");
         self.sync_hand_widgets(app_core, &theme);
+        sync_timer.lap("hands");
         self.sync_spacer_widgets(app_core, &theme);
+        sync_timer.lap("spacer");
         self.sync_indicator_widgets(app_core, &theme);
+        sync_timer.lap("indicator");
         self.sync_targets_widgets(app_core, &theme);
+        sync_timer.lap("targets");
         self.sync_players_widgets(app_core, &theme);
+        sync_timer.lap("players");
+        self.sync_list_widgets(app_core, &theme);
+        sync_timer.lap("lists");
+        self.sync_plugin_widgets(app_core, &theme);
+        sync_timer.lap("plugins");
+        self.sync_art_windows(app_core, &theme);
+        sync_timer.lap("art");
+        self.sync_status_lines(app_core, &theme);
+        sync_timer.lap("status_line");
         self.sync_dashboard_widgets(app_core, &theme);
+        sync_timer.lap("dashboard");
         self.sync_tabbed_text_windows(app_core, &theme);
+        sync_timer.lap("tabbed_text");
         self.sync_compass_widgets(app_core, &theme);
+        sync_timer.lap("compass");
         self.sync_injury_doll_widgets(app_core, &theme);
+        sync_timer.lap("injury_doll");
+        app_core.perf_stats.record_sync_times(sync_timer.finish());
+
+        // Copy mode cursor cell (while the text windows are still in place)
+        let copy_cursor = self.copy_mode.as_ref().and_then(|mode| {
+            let pos = &app_core.ui_state.windows.get(&mode.window_name)?.position;
+            let rect = ratatui::layout::Rect::new(pos.x, pos.y, pos.width, pos.height);
+            self.text_windows
+                .get(&mode.window_name)?
+                .text_to_screen_coords(mode.line, mode.col, rect)
+        });
 
         // Temporarily take ownership of widgets to use in render
         let mut text_windows = std::mem::take(&mut self.text_windows);
@@ -2540,6 +3472,7 @@ impl Frontend for TuiFrontend {
         let mut inventory_windows = std::mem::take(&mut self.inventory_windows);
         let mut spells_windows = std::mem::take(&mut self.spells_windows);
         let mut progress_bars = std::mem::take(&mut self.progress_bars);
+        let mut vitals_clusters = std::mem::take(&mut self.vitals_clusters);
         let mut countdowns = std::mem::take(&mut self.countdowns);
         let mut active_effects_windows = std::mem::take(&mut self.active_effects_windows);
         let mut hand_widgets = std::mem::take(&mut self.hand_widgets);
@@ -2547,6 +3480,11 @@ impl Frontend for TuiFrontend {
         let mut indicator_widgets = std::mem::take(&mut self.indicator_widgets);
         let mut targets_widgets = std::mem::take(&mut self.targets_widgets);
         let mut players_widgets = std::mem::take(&mut self.players_widgets);
+        let mut list_widgets = std::mem::take(&mut self.list_widgets);
+        let mut plugin_widgets = std::mem::take(&mut self.plugin_widgets);
+        let mut art_windows = std::mem::take(&mut self.art_windows);
+        art_windows.values_mut().for_each(|art| art.begin_frame());
+        let status_lines = std::mem::take(&mut self.status_lines);
         let mut dashboard_widgets = std::mem::take(&mut self.dashboard_widgets);
         let mut tabbed_text_windows = std::mem::take(&mut self.tabbed_text_windows);
         let mut compass_widgets = std::mem::take(&mut self.compass_widgets);
@@ -2555,17 +3493,40 @@ impl Frontend for TuiFrontend {
 
         // Clone cached theme for use in render closure (cheaper than HashMap lookup + clone per widget)
         let theme_for_render = theme.clone();
-
-        self.terminal.draw(|f| {
+        let color_mode = self.color_mode;
+        let graphics = self.graphics;
+
+        // Server text and timer ticks only redraw the windows they changed
+        let (width, height) = self.size();
+        let relative_timestamps = app_core.relative_timestamp_windows();
+        let damaged = self.damage.plan(
+            &app_core.ui_state.windows,
+            app_core.ui_state.focused_window.as_deref(),
+            ratatui::layout::Rect::new(0, 0, width, height),
+            app_core.needs_render,
+            damage::FrameClock {
+                now_secs: chrono::Utc::now().timestamp(),
+                fade_minutes: app_core.config.ui.text_fade_minutes,
+                relative_timestamps: &relative_timestamps,
+            },
+        );
+        let last_frame = self.damage.last_frame().filter(|_| damaged.is_some());
+
+        let completed = self.terminal.draw(|f| {
             use crate::data::WindowContent;
             use ratatui::layout::Rect;
             use ratatui::style::{Color, Style};
             use ratatui::text::{Line, Span};
-            use ratatui::widgets::{Block, Borders, Paragraph};
+            use ratatui::widgets::{Block, Borders, Clear, Paragraph};
 
             let theme = theme_for_render.clone();
             let screen_area = f.area();
 
+            // Partial redraw: start from the last frame and repaint only damaged windows
+            if let Some(last_frame) = last_frame {
+                f.buffer_mut().content.clone_from(&last_frame.content);
+            }
+
             // Create stable window index mapping (sorted by window name for consistency)
             let mut window_names: Vec<&String> = app_core.ui_state.windows.keys().collect();
             window_names.sort();
@@ -2575,8 +3536,8 @@ impl Frontend for TuiFrontend {
                 .map(|(idx, name)| (*name, idx))
                 .collect();
 
-            // Render each window at its position
-            for (name, window) in &app_core.ui_state.windows {
+            // Render each window at its position, bottom of the stack first
+            for (name, window) in app_core.ui_state.windows_in_z_order() {
                 if !window.visible {
                     continue;
                 }
@@ -2594,6 +3555,13 @@ impl Frontend for TuiFrontend {
                     continue;
                 }
 
+                if let Some(damaged) = &damaged {
+                    if !damaged.contains(name) {
+                        continue;
+                    }
+                    f.render_widget(Clear, area);
+                }
+
                 match &window.content {
                     WindowContent::Text(_) => {
                         // Use the TextWindow widget for proper text rendering with wrapping, scrolling, etc.
@@ -2686,7 +3654,11 @@ impl Frontend for TuiFrontend {
                         } else {
                             // Normal mode - render command input
                             if let Some(cmd_input) = command_inputs.get(name) {
-                                cmd_input.render(area, f.buffer_mut());
+                                cmd_input.render_with_status(
+                                    area,
+                                    f.buffer_mut(),
+                                    app_core.input_status_label().as_deref(),
+                                );
                             } else {
                                 tracing::error!(
                                     "CommandInput widget '{}' doesn't exist during render!",
@@ -2706,6 +3678,12 @@ impl Frontend for TuiFrontend {
                             progress_bar.render_themed(area, f.buffer_mut(), &theme);
                         }
                     }
+                    WindowContent::Vitals { .. } => {
+                        // One border, aligned label/bar/value columns for every child
+                        if let Some(cluster) = vitals_clusters.get_mut(name) {
+                            cluster.render(area, f.buffer_mut());
+                        }
+                    }
                     WindowContent::Countdown(_) => {
                         // Use the Countdown widget for proper rendering
                         if let Some(countdown_widget) = countdowns.get_mut(name) {
@@ -2780,6 +3758,30 @@ impl Frontend for TuiFrontend {
                             players_widget.render(area, f.buffer_mut());
                         }
                     }
+                    WindowContent::LootTracker
+                    | WindowContent::SessionStats
+                    | WindowContent::BountyTracker
+                    | WindowContent::ContainerTree
+                    | WindowContent::WatchList => {
+                        if let Some(list_widget) = list_widgets.get_mut(name) {
+                            list_widget.render(area, f.buffer_mut());
+                        }
+                    }
+                    WindowContent::Plugin => {
+                        if let Some(plugin_widget) = plugin_widgets.get_mut(name) {
+                            plugin_widget.render(area, f.buffer_mut());
+                        }
+                    }
+                    WindowContent::Art => {
+                        if let Some(art) = art_windows.get_mut(name) {
+                            art.render(area, f.buffer_mut(), graphics);
+                        }
+                    }
+                    WindowContent::StatusLine => {
+                        if let Some(status_line) = status_lines.get(name) {
+                            status_line.render(area, f.buffer_mut());
+                        }
+                    }
                     WindowContent::Dashboard { .. } => {
                         // Use the Dashboard widget
                         if let Some(dashboard_widget) = dashboard_widgets.get_mut(name) {
@@ -2817,6 +3819,11 @@ impl Frontend for TuiFrontend {
                             if let Some(spacer_widget) = spacer_widgets.get_mut(name) {
                                 spacer_widget.render(area, f.buffer_mut());
                             }
+                        } else if window.widget_type == crate::data::WidgetType::Container {
+                            // Containers only draw their frame; children render themselves inside
+                            if let Some(def) = app_core.layout.get_window(name) {
+                                render_container_frame(def.base(), area, f.buffer_mut(), &theme);
+                            }
                         }
                         // Otherwise render nothing (empty placeholder)
                     }
@@ -2828,8 +3835,14 @@ impl Frontend for TuiFrontend {
                         f.render_widget(block, area);
                     }
                 }
+
+                if app_core.ui_state.flashes.contains_key(name) {
+                    render_flash_border(area, f.buffer_mut());
+                }
             }
 
+            render_snap_guides(&app_core.snap_guides, screen_area, f.buffer_mut(), &theme);
+
             // Render popup menu if active
             if let Some(ref popup_menu) = app_core.ui_state.popup_menu {
                 // Convert from ui_state::PopupMenu to rendering popup_menu::PopupMenu
@@ -2848,7 +3861,8 @@ impl Frontend for TuiFrontend {
                     menu_items,
                     popup_menu.position,
                     popup_menu.selected,
-                );
+                )
+                .with_title(popup_menu.title.clone());
                 render_menu.render(screen_area, f.buffer_mut(), &theme);
             }
 
@@ -2922,6 +3936,15 @@ impl Frontend for TuiFrontend {
             if let Some(ref mut spell_color_form) = self.spell_color_form {
                 spell_color_form.render(screen_area, f.buffer_mut(), &app_core.config, &theme);
             }
+            if let Some(ref mut config_diff) = self.config_diff {
+                config_diff.render(screen_area, f.buffer_mut(), &app_core.config, &theme);
+            }
+            if let Some(ref mut snippet_browser) = self.snippet_browser {
+                snippet_browser.render(screen_area, f.buffer_mut(), &app_core.config, &theme);
+            }
+            if let Some(ref mut composer) = self.composer {
+                composer.render(screen_area, f.buffer_mut(), &app_core.config, &theme);
+            }
             if let Some(ref mut theme_editor) = self.theme_editor {
                 theme_editor.render(screen_area, f.buffer_mut(), &app_core.config, &theme);
             }
@@ -2939,8 +3962,88 @@ impl Frontend for TuiFrontend {
                 let editor_theme = theme.to_editor_theme();
                 window_editor.render(screen_area, f.buffer_mut(), &editor_theme);
             }
+
+            if let Some((x, y)) = copy_cursor {
+                if let Some(cell) = f.buffer_mut().cell_mut((x, y)) {
+                    cell.modifier.toggle(ratatui::style::Modifier::REVERSED);
+                }
+            }
+
+            // Performance overlay goes on top of everything (redrawn every frame)
+            if app_core.show_perf_stats {
+                let ui = &app_core.config.ui;
+                // x = 0 means "pin to the right edge"
+                let x = if ui.perf_stats_x == 0 {
+                    screen_area.width.saturating_sub(ui.perf_stats_width)
+                } else {
+                    ui.perf_stats_x
+                };
+                let area = Rect::new(
+                    x,
+                    ui.perf_stats_y,
+                    ui.perf_stats_width,
+                    ui.perf_stats_height,
+                )
+                .intersection(screen_area);
+                self.performance_stats_widget
+                    .get_or_insert_with(performance_stats::PerformanceStatsWidget::new)
+                    .render(area, f.buffer_mut(), &app_core.perf_stats);
+            }
+
+            // Screen reader review: the line being read, across the top row
+            if let Some((position, total, line)) = app_core.review_line() {
+                let area = Rect::new(0, 0, screen_area.width, 1).intersection(screen_area);
+                let text = format!(
+                    "Review {}/{} [{}]: {}",
+                    position, total, line.stream, line.text
+                );
+                f.render_widget(Clear, area);
+                f.render_widget(
+                    Paragraph::new(text).style(
+                        Style::default()
+                            .fg(theme.text_primary)
+                            .bg(theme.window_background)
+                            .add_modifier(ratatui::style::Modifier::REVERSED),
+                    ),
+                    area,
+                );
+            }
+
+            // Contrast is measured on the RGB colors, before any downsampling
+            self.contrast.apply(f.buffer_mut(), theme.window_background);
+            color_mode.apply(f.buffer_mut());
         })?;
 
+        // Images go straight to the terminal, over the cells their windows left blank
+        let mut image_output = Vec::new();
+        for art in art_windows.values_mut() {
+            image_output.extend(art.graphics_output(
+                graphics,
+                self.damage.last_frame(),
+                completed.buffer,
+            ));
+        }
+        if !image_output.is_empty() {
+            use std::io::Write;
+            let mut stdout = std::io::stdout();
+            if let Err(e) = stdout.write_all(&image_output).and_then(|_| stdout.flush()) {
+                tracing::warn!("Failed to draw art window images: {}", e);
+            }
+        }
+        self.damage.store_frame(completed.buffer);
+        app_core.perf_stats.record_frame();
+
+        if std::mem::take(&mut app_core.ui_state.bell_pending) {
+            use std::io::Write;
+            let mut stdout = std::io::stdout();
+            if let Err(e) = stdout.write_all(b"\x07").and_then(|_| stdout.flush()) {
+                tracing::warn!("Failed to ring the terminal bell: {}", e);
+            }
+        }
+        app_core
+            .perf_stats
+            .record_render_time(render_started.elapsed());
+
         // Restore widgets
         self.text_windows = text_windows;
         self.command_inputs = command_inputs;
@@ -2948,6 +4051,7 @@ impl Frontend for TuiFrontend {
         self.inventory_windows = inventory_windows;
         self.spells_windows = spells_windows;
         self.progress_bars = progress_bars;
+        self.vitals_clusters = vitals_clusters;
         self.countdowns = countdowns;
         self.active_effects_windows = active_effects_windows;
         self.hand_widgets = hand_widgets;
@@ -2955,6 +4059,10 @@ impl Frontend for TuiFrontend {
         self.indicator_widgets = indicator_widgets;
         self.targets_widgets = targets_widgets;
         self.players_widgets = players_widgets;
+        self.list_widgets = list_widgets;
+        self.plugin_widgets = plugin_widgets;
+        self.art_windows = art_windows;
+        self.status_lines = status_lines;
         self.dashboard_widgets = dashboard_widgets;
         self.tabbed_text_windows = tabbed_text_windows;
         self.compass_widgets = compass_widgets;
@@ -2965,11 +4073,19 @@ impl Frontend for TuiFrontend {
     }
 
     fn cleanup(&mut self) -> Result<()> {
+        if self.keyboard_enhanced {
+            execute!(
+                self.terminal.backend_mut(),
+                crossterm::event::PopKeyboardEnhancementFlags
+            )?;
+        }
         disable_raw_mode()?;
         execute!(
             self.terminal.backend_mut(),
             LeaveAlternateScreen,
-            crossterm::event::DisableMouseCapture
+            crossterm::event::DisableMouseCapture,
+            crossterm::event::DisableFocusChange,
+            crossterm::event::DisableBracketedPaste
         )?;
         Ok(())
     }
@@ -89,7 +89,9 @@ impl PerformanceStatsWidget {
         block.render(area, buf);
 
         // Format stats into lines
-        let lines = vec![
+        let (net_backlog, net_peak) = stats.network_backlog();
+        let (parse_backlog, parse_peak) = stats.parse_backlog();
+        let mut lines = vec![
             // Frame stats
             Line::from(vec![
                 Span::styled("FPS: ", Style::default().fg(Color::Cyan)),
@@ -120,6 +122,18 @@ impl PerformanceStatsWidget {
                     Style::default().fg(Color::White),
                 ),
             ]),
+            Line::from(vec![
+                Span::styled("p50/95/99: ", Style::default().fg(Color::Cyan)),
+                Span::styled(
+                    format!(
+                        "{:.1}/{:.1}/{:.1}ms",
+                        stats.render_time_percentile_ms(50.0),
+                        stats.render_time_percentile_ms(95.0),
+                        stats.render_time_percentile_ms(99.0)
+                    ),
+                    Style::default().fg(Color::White),
+                ),
+            ]),
             Line::from(vec![
                 Span::styled("UI: ", Style::default().fg(Color::Cyan)),
                 Span::styled(
@@ -150,6 +164,17 @@ impl PerformanceStatsWidget {
                     Style::default().fg(Color::White),
                 ),
             ]),
+            Line::from(vec![
+                Span::styled("Mirror: ", Style::default().fg(Color::Green)),
+                Span::styled(
+                    format!(
+                        "{:.1} KB ({} tools)",
+                        stats.passthrough_bytes() as f64 / 1024.0,
+                        stats.passthrough_clients()
+                    ),
+                    Style::default().fg(Color::White),
+                ),
+            ]),
             Line::from(""),
             // Parser stats
             Line::from(vec![
@@ -160,9 +185,9 @@ impl PerformanceStatsWidget {
                 ),
             ]),
             Line::from(vec![
-                Span::styled("Chunks/s: ", Style::default().fg(Color::Magenta)),
+                Span::styled("Lines/s: ", Style::default().fg(Color::Magenta)),
                 Span::styled(
-                    format!("{}", stats.chunks_per_sec()),
+                    format!("{}", stats.lines_per_sec()),
                     Style::default().fg(Color::White),
                 ),
             ]),
@@ -173,6 +198,16 @@ impl PerformanceStatsWidget {
                     Style::default().fg(Color::White),
                 ),
             ]),
+            Line::from(vec![
+                Span::styled("Backlog: ", Style::default().fg(Color::Magenta)),
+                Span::styled(
+                    format!(
+                        "net {} ({}) parse {} ({})",
+                        net_backlog, net_peak, parse_backlog, parse_peak
+                    ),
+                    Style::default().fg(Color::White),
+                ),
+            ]),
             Line::from(""),
             // Event stats
             Line::from(vec![
@@ -215,8 +250,24 @@ impl PerformanceStatsWidget {
                 Span::styled("Uptime: ", Style::default().fg(Color::Blue)),
                 Span::styled(stats.uptime_formatted(), Style::default().fg(Color::White)),
             ]),
+            Line::from(""),
         ];
 
+        // Slowest widget syncs, as many as fit
+        lines.push(Line::from(Span::styled(
+            "Sync (avg):",
+            Style::default().fg(Color::LightYellow),
+        )));
+        for (name, us) in stats.sync_times_us().into_iter().take(5) {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("  {}: ", name),
+                    Style::default().fg(Color::LightYellow),
+                ),
+                Span::styled(format!("{:.0}μs", us), Style::default().fg(Color::White)),
+            ]));
+        }
+
         // Ratatui takes care of wrapping/truncation. We simply hand off the
         // computed lines as a paragraph.
         let paragraph = Paragraph::new(lines);
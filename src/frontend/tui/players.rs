@@ -90,6 +90,14 @@ impl Players {
         self.update_title();
     }
 
+    /// Name of the player on the row under (x, y), without titles
+    /// ("Lord Deddalus" -> "Deddalus")
+    pub fn name_at(&self, area: Rect, x: u16, y: u16) -> Option<String> {
+        let item = self.container.item_at(area, x, y)?;
+        let text = item.text.split(" who ").next().unwrap_or(&item.text);
+        text.split_whitespace().last().map(str::to_string)
+    }
+
     /// Strip all XML tags from a string
     fn strip_xml_tags(input: &str) -> String {
         let mut result = String::new();
@@ -147,3 +155,22 @@ impl Players {
         self.container.render_with_focus(area, buf, focused);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_name_at() {
+        let mut players = Players::new("players");
+        players.set_players_from_text("<b>[sit] Lord Deddalus</b>, <b>Zoleta</b>");
+        let area = Rect::new(0, 0, 20, 5);
+        let mut buf = Buffer::empty(area);
+        players.render(area, &mut buf);
+
+        assert_eq!(players.name_at(area, 3, 1).as_deref(), Some("Deddalus"));
+        assert_eq!(players.name_at(area, 3, 2).as_deref(), Some("Zoleta"));
+        assert_eq!(players.name_at(area, 3, 3), None);
+        assert_eq!(players.name_at(area, 0, 0), None);
+    }
+}
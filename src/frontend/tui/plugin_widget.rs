@@ -0,0 +1,198 @@
+//! Plugin widget: lines a plugin's widget provider (or a `.widget` script)
+//! last sent.
+//!
+//! Plugins push whole line lists with a `widget` action; each sync copies
+//! the current list for this window's provider. Lines use the status line's
+//! `{#rrggbb}` ... `{/}` markup for colored runs.
+
+use super::status_line::expand_template;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Clear, Paragraph, Widget},
+};
+
+pub struct PluginWidget {
+    title: String,
+    lines: Vec<Vec<(String, Option<Color>)>>,
+    scroll_offset: usize,
+    last_height: usize,
+    show_border: bool,
+    border_style: Option<String>,
+    border_color: Option<Color>,
+    border_sides: crate::config::BorderSides,
+    text_color: Option<Color>,
+    background_color: Option<Color>,
+    transparent_background: bool,
+}
+
+impl PluginWidget {
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            lines: Vec::new(),
+            scroll_offset: 0,
+            last_height: 10,
+            show_border: true,
+            border_style: None,
+            border_color: None,
+            border_sides: crate::config::BorderSides::default(),
+            text_color: None,
+            background_color: None,
+            transparent_background: true,
+        }
+    }
+
+    /// Replace the rows with the provider's lines, keeping the scroll position
+    pub fn set_lines(&mut self, provider: &str, lines: Option<&[String]>) {
+        self.lines = match lines {
+            Some(lines) => lines
+                .iter()
+                .map(|line| expand_template(line, |_| None))
+                .collect(),
+            None => vec![vec![(
+                format!("Waiting for plugin widget '{}'", provider),
+                None,
+            )]],
+        };
+        self.scroll_offset = self.scroll_offset.min(self.max_scroll());
+    }
+
+    fn max_scroll(&self) -> usize {
+        self.lines.len().saturating_sub(self.last_height)
+    }
+
+    pub fn scroll_up(&mut self, amount: usize) {
+        self.scroll_offset = self.scroll_offset.saturating_sub(amount);
+    }
+
+    pub fn scroll_down(&mut self, amount: usize) {
+        self.scroll_offset = (self.scroll_offset + amount).min(self.max_scroll());
+    }
+
+    pub fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+        if !self.transparent_background {
+            if let Some(bg) = self.background_color {
+                buf.set_style(area, Style::default().bg(bg));
+            }
+        }
+
+        let inner = if self.show_border {
+            let block = Block::default()
+                .borders(crate::config::parse_border_sides(&self.border_sides))
+                .border_style(Style::default().fg(self.border_color.unwrap_or(Color::Reset)))
+                .border_type(match self.border_style.as_deref() {
+                    Some("double") => BorderType::Double,
+                    Some("rounded") => BorderType::Rounded,
+                    Some("thick") => BorderType::Thick,
+                    _ => BorderType::Plain,
+                })
+                .title(self.title.as_str());
+            let inner = block.inner(area);
+            block.render(area, buf);
+            inner
+        } else {
+            area
+        };
+
+        self.last_height = inner.height as usize;
+        self.scroll_offset = self.scroll_offset.min(self.max_scroll());
+        let text_color = self.text_color.unwrap_or(Color::Reset);
+        let lines: Vec<Line> = self
+            .lines
+            .iter()
+            .skip(self.scroll_offset)
+            .take(inner.height as usize)
+            .map(|runs| {
+                Line::from(
+                    runs.iter()
+                        .map(|(text, color)| {
+                            Span::styled(
+                                text.as_str(),
+                                Style::default().fg(color.unwrap_or(text_color)),
+                            )
+                        })
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect();
+        Paragraph::new(lines).render(inner, buf);
+    }
+}
+
+impl super::StyledWindow for PluginWidget {
+    fn set_title(&mut self, title: String) {
+        self.title = title;
+    }
+
+    fn set_border_config(&mut self, show: bool, style: Option<String>, color: Option<String>) {
+        self.show_border = show;
+        self.border_style = style;
+        self.border_color = color.and_then(|c| super::parse_hex_color(&c).ok());
+    }
+
+    fn set_border_sides(&mut self, sides: crate::config::BorderSides) {
+        self.border_sides = sides;
+    }
+
+    fn set_text_color(&mut self, color: Option<String>) {
+        self.text_color = color.and_then(|c| super::parse_hex_color(&c).ok());
+    }
+
+    fn set_background_color(&mut self, color: Option<String>) {
+        self.background_color = color
+            .filter(|c| c != "-")
+            .and_then(|c| super::parse_hex_color(&c).ok());
+    }
+
+    fn set_transparent_background(&mut self, transparent: bool) {
+        self.transparent_background = transparent;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frontend::tui::StyledWindow;
+
+    /// Borderless widget with three lines, the first with colored markup
+    fn widget() -> PluginWidget {
+        let mut widget = PluginWidget::new("tracker");
+        widget.set_border_config(false, None, None);
+        widget.set_lines(
+            "tracker",
+            Some(&[
+                "Kills {#ff0000}12{/}".to_string(),
+                "second".to_string(),
+                "third".to_string(),
+            ]),
+        );
+        widget
+    }
+
+    #[test]
+    fn test_markup_colors_spans() {
+        let area = Rect::new(0, 0, 12, 2);
+        let mut buf = Buffer::empty(area);
+        widget().render(area, &mut buf);
+        assert_eq!(buf[(6, 0)].symbol(), "1");
+        assert_eq!(buf[(6, 0)].fg, Color::Rgb(255, 0, 0));
+        assert_eq!(buf[(0, 0)].fg, Color::Reset);
+        assert_eq!(buf[(0, 1)].symbol(), "s");
+    }
+
+    #[test]
+    fn test_scroll_stops_at_last_page() {
+        let area = Rect::new(0, 0, 12, 2);
+        let mut buf = Buffer::empty(area);
+        let mut widget = widget();
+        // Scrolling is clamped to the height of the last render
+        widget.render(area, &mut buf);
+        widget.scroll_down(5);
+        widget.render(area, &mut buf);
+        assert_eq!(buf[(0, 1)].symbol(), "t");
+    }
+}
@@ -22,6 +22,7 @@ pub struct PopupMenu {
     items: Vec<MenuItem>,
     selected: usize,
     position: (u16, u16), // (col, row)
+    title: Option<String>,
 }
 
 impl PopupMenu {
@@ -30,6 +31,7 @@ impl PopupMenu {
             items,
             selected: 0,
             position,
+            title: None,
         }
     }
 
@@ -39,9 +41,16 @@ impl PopupMenu {
             items,
             selected,
             position,
+            title: None,
         }
     }
 
+    /// Show `title` in the top border
+    pub fn with_title(mut self, title: Option<String>) -> Self {
+        self.title = title;
+        self
+    }
+
     /// Navigate forward (Tab) - wraps around
     pub fn select_next(&mut self) {
         if self.selected < self.items.len().saturating_sub(1) {
@@ -105,6 +114,7 @@ impl PopupMenu {
             .items
             .iter()
             .map(|item| item.text.len())
+            .chain(self.title.iter().map(|title| title.len()))
             .max()
             .unwrap_or(20)
             .min(60);
@@ -148,10 +158,13 @@ impl PopupMenu {
         }
 
         // Create block with border
-        let block = Block::default()
+        let mut block = Block::default()
             .borders(Borders::ALL)
             .border_style(Style::default().fg(theme.menu_border))
             .style(Style::default().bg(theme.browser_background));
+        if let Some(title) = &self.title {
+            block = block.title(format!(" {} ", title));
+        }
 
         let paragraph = Paragraph::new(lines).block(block);
 
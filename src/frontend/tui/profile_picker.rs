@@ -0,0 +1,317 @@
+//! Start screen for picking a character profile
+//!
+//! Shown when two-face is launched without `--character` and at least one
+//! character profile exists. Lists every profile with how it was last
+//! launched, most recent first, so Enter relaunches the last character.
+//! `n` starts a new profile.
+
+use crate::config::launch_history::ProfileSummary;
+use anyhow::Result;
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::execute;
+use crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use ratatui::{
+    backend::CrosstermBackend,
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Widget},
+    Terminal,
+};
+
+/// Longest profile name that can be typed
+const MAX_NAME_LEN: usize = 32;
+
+/// What the user chose
+#[derive(Debug, Clone, PartialEq)]
+pub enum PickerResult {
+    /// Launch this profile ("default" is the profile used without --character)
+    Profile(String),
+    Quit,
+}
+
+/// Profile list plus the new-profile name being typed
+pub struct ProfilePicker {
+    profiles: Vec<ProfileSummary>,
+    selected: usize, // profiles.len() is the "New character" row
+    new_name: Option<String>,
+    now: chrono::DateTime<chrono::Local>,
+}
+
+impl ProfilePicker {
+    pub fn new(profiles: Vec<ProfileSummary>) -> Self {
+        Self {
+            profiles,
+            selected: 0,
+            new_name: None,
+            now: chrono::Local::now(),
+        }
+    }
+
+    /// Handle a key; Some once the user has chosen
+    pub fn handle_key(&mut self, code: KeyCode, modifiers: KeyModifiers) -> Option<PickerResult> {
+        if code == KeyCode::Char('c') && modifiers.contains(KeyModifiers::CONTROL) {
+            return Some(PickerResult::Quit);
+        }
+        if let Some(name) = self.new_name.as_mut() {
+            match code {
+                KeyCode::Esc => self.new_name = None,
+                KeyCode::Backspace => {
+                    name.pop();
+                }
+                KeyCode::Char(c)
+                    if (c.is_alphanumeric() || c == '-' || c == '_')
+                        && name.len() < MAX_NAME_LEN =>
+                {
+                    name.push(c)
+                }
+                KeyCode::Enter if !name.is_empty() => {
+                    let name = name.clone();
+                    return Some(PickerResult::Profile(self.profile_name(&name)));
+                }
+                _ => {}
+            }
+            return None;
+        }
+
+        let rows = self.profiles.len() + 1;
+        match code {
+            KeyCode::Esc | KeyCode::Char('q') => return Some(PickerResult::Quit),
+            KeyCode::Up | KeyCode::Char('k') => self.selected = (self.selected + rows - 1) % rows,
+            KeyCode::Down | KeyCode::Char('j') => self.selected = (self.selected + 1) % rows,
+            KeyCode::Home => self.selected = 0,
+            KeyCode::End => self.selected = rows - 1,
+            KeyCode::Char('n') => self.new_name = Some(String::new()),
+            KeyCode::Enter => match self.profiles.get(self.selected) {
+                Some(profile) => return Some(PickerResult::Profile(profile.name.clone())),
+                None => self.new_name = Some(String::new()),
+            },
+            _ => {}
+        }
+        None
+    }
+
+    /// An existing profile's spelling, else the name capitalized like the game does
+    fn profile_name(&self, typed: &str) -> String {
+        if let Some(existing) = self
+            .profiles
+            .iter()
+            .find(|p| p.name.eq_ignore_ascii_case(typed))
+        {
+            return existing.name.clone();
+        }
+        let mut chars = typed.chars();
+        chars
+            .next()
+            .map(|first| {
+                first
+                    .to_uppercase()
+                    .chain(chars.flat_map(|c| c.to_lowercase()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn row(&self, profile: &ProfileSummary, width: usize) -> String {
+        let record = &profile.record;
+        let name = if profile.name == "default" {
+            "(no character)".to_string()
+        } else {
+            profile.name.clone()
+        };
+        let frontend = record.frontend.as_deref().unwrap_or("");
+        let line = format!(
+            "{:<18} {:<18} {:<5} {}",
+            name,
+            record.connection_label(),
+            frontend,
+            record.last_played_label(self.now)
+        );
+        line.chars().take(width).collect()
+    }
+}
+
+impl Widget for &ProfilePicker {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let width = 64.min(area.width.saturating_sub(2));
+        let height = (self.profiles.len() as u16 + 7)
+            .min(area.height.saturating_sub(2))
+            .max(7);
+        let popup = Rect {
+            x: area.x + area.width.saturating_sub(width) / 2,
+            y: area.y + area.height.saturating_sub(height) / 2,
+            width,
+            height: height.min(area.height),
+        };
+        Clear.render(popup, buf);
+        let block = Block::default()
+            .title(" two-face: choose a character ")
+            .borders(Borders::ALL)
+            .border_type(BorderType::Double)
+            .border_style(Style::default().fg(Color::Cyan));
+        let inner = block.inner(popup);
+        block.render(popup, buf);
+
+        let text_width = inner.width as usize;
+        let mut lines = vec![Line::from(Span::styled(
+            format!(
+                "{:<18} {:<18} {:<5} {}",
+                "Character", "Connection", "UI", "Last played"
+            ),
+            Style::default().fg(Color::DarkGray),
+        ))];
+        // Keep the selection in view on short terminals
+        let visible = (inner.height as usize).saturating_sub(4).max(1);
+        let first = (self.selected + 1)
+            .saturating_sub(visible)
+            .min(self.profiles.len().saturating_sub(visible));
+        for (i, profile) in self.profiles.iter().enumerate().skip(first).take(visible) {
+            let style = if i == self.selected && self.new_name.is_none() {
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            lines.push(Line::from(Span::styled(
+                self.row(profile, text_width),
+                style,
+            )));
+        }
+        let new_row = match &self.new_name {
+            Some(name) => Span::styled(
+                format!("New character: {}_", name),
+                Style::default().fg(Color::Yellow),
+            ),
+            None if self.selected == self.profiles.len() => Span::styled(
+                "+ New character...",
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            None => Span::styled("+ New character...", Style::default().fg(Color::Green)),
+        };
+        lines.push(Line::from(new_row));
+        lines.push(Line::from(""));
+        let hint = if self.new_name.is_some() {
+            "Type a name  Enter:Create  Esc:Back"
+        } else {
+            "↑/↓:Select  Enter:Play  n:New  Esc:Quit"
+        };
+        lines.push(Line::from(Span::styled(
+            hint,
+            Style::default().fg(Color::DarkGray),
+        )));
+        Paragraph::new(lines).render(inner, buf);
+    }
+}
+
+/// Show the picker full-screen until the user chooses
+pub fn run(profiles: Vec<ProfileSummary>) -> Result<PickerResult> {
+    let mut picker = ProfilePicker::new(profiles);
+    enable_raw_mode()?;
+    let mut stdout = std::io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+
+    let result = (|| -> Result<PickerResult> {
+        loop {
+            terminal.draw(|frame| frame.render_widget(&picker, frame.area()))?;
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Release {
+                    continue;
+                }
+                if let Some(result) = picker.handle_key(key.code, key.modifiers) {
+                    return Ok(result);
+                }
+            }
+        }
+    })();
+
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::launch_history::LaunchRecord;
+
+    fn profile(name: &str) -> ProfileSummary {
+        ProfileSummary {
+            name: name.to_string(),
+            record: LaunchRecord::default(),
+        }
+    }
+
+    fn picker() -> ProfilePicker {
+        ProfilePicker::new(vec![profile("Ann"), profile("default")])
+    }
+
+    fn key(picker: &mut ProfilePicker, code: KeyCode) -> Option<PickerResult> {
+        picker.handle_key(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_enter_opens_selected_profile() {
+        let mut picker = picker();
+        assert_eq!(
+            key(&mut picker, KeyCode::Enter),
+            Some(PickerResult::Profile("Ann".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_new_character_name_is_cleaned() {
+        let mut picker = picker();
+        // Up wraps to the "New character" row
+        key(&mut picker, KeyCode::Up);
+        assert_eq!(picker.selected, 2);
+        assert_eq!(key(&mut picker, KeyCode::Enter), None);
+        for c in "bOB!".chars() {
+            key(&mut picker, KeyCode::Char(c));
+        }
+        assert_eq!(
+            key(&mut picker, KeyCode::Enter),
+            Some(PickerResult::Profile("Bob".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_new_name_matching_existing_profile_opens_it() {
+        let mut picker = picker();
+        picker.new_name = Some("ann".to_string());
+        assert_eq!(
+            key(&mut picker, KeyCode::Enter),
+            Some(PickerResult::Profile("Ann".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_esc_cancels_new_name_then_q_quits() {
+        let mut picker = picker();
+        picker.new_name = Some("Bo".to_string());
+        key(&mut picker, KeyCode::Esc);
+        assert!(picker.new_name.is_none());
+        assert_eq!(
+            key(&mut picker, KeyCode::Char('q')),
+            Some(PickerResult::Quit)
+        );
+    }
+
+    #[test]
+    fn test_render_lists_profiles_and_new_row() {
+        let picker = picker();
+        let area = Rect::new(0, 0, 80, 20);
+        let mut buf = Buffer::empty(area);
+        (&picker).render(area, &mut buf);
+        let screen: String = buf.content.iter().map(|c| c.symbol()).collect();
+        assert!(screen.contains("(no character)") && screen.contains("New character"));
+    }
+}
@@ -10,6 +10,21 @@ use ratatui::{
     widgets::{Block, BorderType, Borders, Clear, Widget},
 };
 
+/// Default fill color for the game's known progress bars (VellumFE template colors)
+pub fn default_fill_color(id: &str) -> Option<&'static str> {
+    match id {
+        "health" => Some("#6e0202"),     // Dark red
+        "mana" => Some("#08086d"),       // Dark blue
+        "stamina" => Some("#bd7b00"),    // Orange
+        "spirit" => Some("#6e727c"),     // Gray
+        "encumlevel" => Some("#ffff00"), // Yellow
+        "pbarStance" => Some("#ffa500"), // Orange
+        "mindState" => Some("#9370db"),  // Purple
+        "lblBPs" => Some("#ff4500"),     // Orange-red
+        _ => None,
+    }
+}
+
 /// A progress bar widget for displaying vitals (health, mana, stamina, spirit)
 pub struct ProgressBar {
     label: String,
@@ -24,6 +39,8 @@ pub struct ProgressBar {
     window_background: Option<Color>,
     transparent_background: bool,
     text_color: Option<Color>,
+    /// Screen reader mode: "label value" as text, no bar fill
+    plain_text: bool,
 }
 
 impl ProgressBar {
@@ -41,6 +58,7 @@ impl ProgressBar {
             window_background: None,
             transparent_background: true,
             text_color: Some(Color::White),
+            plain_text: false,
         }
     }
 
@@ -84,6 +102,10 @@ impl ProgressBar {
         self.transparent_background = transparent;
     }
 
+    pub fn set_plain_text(&mut self, plain: bool) {
+        self.plain_text = plain;
+    }
+
     pub fn set_text_color(&mut self, color: Option<String>) {
         self.text_color = color.and_then(|c| Self::parse_color(&c));
     }
@@ -177,6 +199,20 @@ impl ProgressBar {
             format!("{}/{}", self.current, self.max)
         };
 
+        // Plain text for screen readers: no fill to read out as box characters
+        if self.plain_text {
+            let text = format!("{} {}", self.label, display_text);
+            let style = Style::default().fg(self.text_color.unwrap_or(Color::White));
+            buf.set_stringn(
+                inner_area.x,
+                inner_area.y,
+                text.trim(),
+                inner_area.width as usize,
+                style,
+            );
+            return;
+        }
+
         let text_width = display_text.len() as u16;
         let available_width = inner_area.width;
 
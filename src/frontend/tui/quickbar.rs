@@ -101,6 +101,7 @@ impl QuickBar {
                             noun: link_text.to_string(),
                             text: link_text.to_string(),
                             coord: None,
+                            flags: crate::data::ItemFlags::default(),
                         }),
                     });
 
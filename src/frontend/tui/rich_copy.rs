@@ -0,0 +1,180 @@
+//! Copying a selection with its colors
+//!
+//! Turns the styled pieces of a selection into ANSI-escaped text (for pasting
+//! into another terminal, a log, or a Discord ```ansi block) or HTML (for
+//! forums and documents). ANSI keeps palette colors as palette codes; HTML
+//! writes every color as its RGB value.
+
+use super::color_mode;
+use ratatui::style::{Color, Modifier, Style};
+
+/// One selected line: its text in pieces with the style each was drawn in
+pub type StyledRow = Vec<(String, Style)>;
+
+/// Text with SGR escape codes, reset after every styled piece
+pub fn to_ansi(rows: &[StyledRow]) -> String {
+    let mut out = String::new();
+    for (i, row) in rows.iter().enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        for (text, style) in row {
+            let codes = sgr_codes(style);
+            if codes.is_empty() {
+                out.push_str(text);
+            } else {
+                out.push_str(&format!("\x1b[{}m{}\x1b[0m", codes.join(";"), text));
+            }
+        }
+    }
+    out
+}
+
+/// A `<pre>` block with one `<span>` per styled piece
+pub fn to_html(rows: &[StyledRow], background: Option<Color>) -> String {
+    let mut out = String::from("<pre style=\"font-family:monospace");
+    if let Some(bg) = background.and_then(hex) {
+        out.push_str(&format!(";background-color:{}", bg));
+    }
+    out.push_str("\">");
+    for (i, row) in rows.iter().enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        for (text, style) in row {
+            let css = css(style);
+            if css.is_empty() {
+                out.push_str(&escape_html(text));
+            } else {
+                out.push_str(&format!(
+                    "<span style=\"{}\">{}</span>",
+                    css,
+                    escape_html(text)
+                ));
+            }
+        }
+    }
+    out.push_str("</pre>");
+    out
+}
+
+fn sgr_codes(style: &Style) -> Vec<String> {
+    let mut codes = Vec::new();
+    if style.add_modifier.contains(Modifier::BOLD) {
+        codes.push("1".to_string());
+    }
+    if style.add_modifier.contains(Modifier::ITALIC) {
+        codes.push("3".to_string());
+    }
+    if style.add_modifier.contains(Modifier::UNDERLINED) {
+        codes.push("4".to_string());
+    }
+    if let Some(fg) = style.fg.and_then(|c| color_code(c, false)) {
+        codes.push(fg);
+    }
+    if let Some(bg) = style.bg.and_then(|c| color_code(c, true)) {
+        codes.push(bg);
+    }
+    codes
+}
+
+fn color_code(color: Color, background: bool) -> Option<String> {
+    let base = if background { 40 } else { 30 };
+    let basic = |offset: u8| Some((base + offset as u16).to_string());
+    let bright = |offset: u8| Some((base + 60 + offset as u16).to_string());
+    match color {
+        Color::Reset => None,
+        Color::Black => basic(0),
+        Color::Red => basic(1),
+        Color::Green => basic(2),
+        Color::Yellow => basic(3),
+        Color::Blue => basic(4),
+        Color::Magenta => basic(5),
+        Color::Cyan => basic(6),
+        Color::Gray => basic(7),
+        Color::DarkGray => bright(0),
+        Color::LightRed => bright(1),
+        Color::LightGreen => bright(2),
+        Color::LightYellow => bright(3),
+        Color::LightBlue => bright(4),
+        Color::LightMagenta => bright(5),
+        Color::LightCyan => bright(6),
+        Color::White => bright(7),
+        Color::Indexed(index) => Some(format!("{};5;{}", base + 8, index)),
+        Color::Rgb(r, g, b) => Some(format!("{};2;{};{};{}", base + 8, r, g, b)),
+    }
+}
+
+fn css(style: &Style) -> String {
+    let mut parts = Vec::new();
+    if let Some(fg) = style.fg.and_then(hex) {
+        parts.push(format!("color:{}", fg));
+    }
+    if let Some(bg) = style.bg.and_then(hex) {
+        parts.push(format!("background-color:{}", bg));
+    }
+    if style.add_modifier.contains(Modifier::BOLD) {
+        parts.push("font-weight:bold".to_string());
+    }
+    if style.add_modifier.contains(Modifier::ITALIC) {
+        parts.push("font-style:italic".to_string());
+    }
+    if style.add_modifier.contains(Modifier::UNDERLINED) {
+        parts.push("text-decoration:underline".to_string());
+    }
+    parts.join(";")
+}
+
+fn hex(color: Color) -> Option<String> {
+    let (r, g, b) = color_mode::to_rgb(color)?;
+    Some(format!("#{:02x}{:02x}{:02x}", r, g, b))
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Plain text, bold truecolor text with HTML specials, and an indexed background
+    fn rows() -> Vec<StyledRow> {
+        vec![
+            vec![
+                ("You say, ".to_string(), Style::default()),
+                (
+                    "\"<hi>\"".to_string(),
+                    Style::default()
+                        .fg(Color::Rgb(255, 128, 0))
+                        .add_modifier(Modifier::BOLD),
+                ),
+            ],
+            vec![(
+                "Also".to_string(),
+                Style::default().fg(Color::Cyan).bg(Color::Indexed(17)),
+            )],
+        ]
+    }
+
+    #[test]
+    fn test_to_ansi() {
+        assert_eq!(
+            to_ansi(&rows()),
+            "You say, \x1b[1;38;2;255;128;0m\"<hi>\"\x1b[0m\n\x1b[36;48;5;17mAlso\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn test_to_html_escapes_and_styles_spans() {
+        assert_eq!(
+            to_html(&rows(), Some(Color::Black)),
+            "<pre style=\"font-family:monospace;background-color:#000000\">You say, \
+             <span style=\"color:#ff8000;font-weight:bold\">&quot;&lt;hi&gt;&quot;</span>\n\
+             <span style=\"color:#00cdcd;background-color:#00005f\">Also</span></pre>"
+        );
+    }
+}
@@ -112,6 +112,27 @@ impl ScrollableContainer {
         self.scroll_offset = desired_offset.min(max_scroll);
     }
 
+    /// Item on the row under screen position (x, y) when rendered into `area`
+    pub fn item_at(&self, area: Rect, x: u16, y: u16) -> Option<&ScrollableItem> {
+        let inner_area = if self.show_border {
+            Block::default()
+                .borders(crate::config::parse_border_sides(&self.border_sides))
+                .inner(area)
+        } else {
+            area
+        };
+        if !inner_area.contains((x, y).into()) {
+            return None;
+        }
+
+        let row = (y - inner_area.y) as usize;
+        if row >= self.visible_count.unwrap_or(usize::MAX) {
+            return None;
+        }
+        let id = self.item_order.get(self.scroll_offset + row)?;
+        self.items.get(id)
+    }
+
     pub fn add_or_update_item(&mut self, id: String, text: String, value: u32, max: u32) {
         self.add_or_update_item_full(id, text, None, value, max, None, None, None);
     }
@@ -0,0 +1,267 @@
+//! Disk-backed overflow for text window scrollback.
+//!
+//! Lines pushed out of a text window's in-memory buffer are appended to a
+//! spill file instead of being dropped. The spill is a ring of two segment
+//! files: once the active segment holds half the limit, the older one is
+//! emptied and the two swap roles, so between half and all of the limit stays
+//! on disk. A byte-offset index per segment makes any range of lines readable
+//! with a single seek, which is how the window pages history back in when the
+//! user scrolls past what is in memory.
+//!
+//! Spilled lines keep their text, colors and bold; link metadata is dropped.
+//! The files only live as long as the window does, and carry the process ID
+//! so a second instance on the same profile never touches this one's files.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+/// One styled run of a spilled line (colors as ratatui color strings)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpilledSpan {
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fg: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bg: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub bold: bool,
+}
+
+/// One logical line on disk
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpilledLine {
+    pub spans: Vec<SpilledSpan>,
+    /// When the line arrived (unix seconds), so age fading still works
+    pub time: i64,
+}
+
+struct Segment {
+    path: PathBuf,
+    file: File,
+    /// Byte offset where each line starts
+    offsets: Vec<u64>,
+    size: u64,
+}
+
+impl Segment {
+    fn create(path: PathBuf) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .write(true)
+            .truncate(true)
+            .open(&path)
+            .with_context(|| format!("Failed to create scrollback file {:?}", path))?;
+        Ok(Self {
+            path,
+            file,
+            offsets: Vec::new(),
+            size: 0,
+        })
+    }
+
+    fn clear(&mut self) -> Result<()> {
+        self.file.set_len(0)?;
+        self.offsets.clear();
+        self.size = 0;
+        Ok(())
+    }
+
+    fn append(&mut self, encoded: &str) -> Result<()> {
+        self.file.seek(SeekFrom::Start(self.size))?;
+        self.file.write_all(encoded.as_bytes())?;
+        self.file.write_all(b"\n")?;
+        self.offsets.push(self.size);
+        self.size += encoded.len() as u64 + 1;
+        Ok(())
+    }
+
+    /// Lines `start..end` of this segment
+    fn read(&mut self, start: usize, end: usize, out: &mut Vec<SpilledLine>) -> Result<()> {
+        if start >= end {
+            return Ok(());
+        }
+        let from = self.offsets[start];
+        let to = self.offsets.get(end).copied().unwrap_or(self.size);
+        let mut bytes = vec![0; (to - from) as usize];
+        self.file.seek(SeekFrom::Start(from))?;
+        self.file.read_exact(&mut bytes)?;
+
+        for line in String::from_utf8_lossy(&bytes).lines() {
+            out.push(serde_json::from_str(line).context("Corrupt scrollback line")?);
+        }
+        Ok(())
+    }
+}
+
+/// Spill ring for one text window
+pub struct ScrollbackSpill {
+    limit: usize,
+    segments: [Segment; 2],
+    /// Index of the segment being appended to (the other one is older)
+    active: usize,
+}
+
+impl ScrollbackSpill {
+    /// Start an empty spill for `window` in `dir`, keeping up to `limit` lines
+    pub fn create(dir: &Path, window: &str, limit: usize) -> Result<Self> {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create scrollback directory {:?}", dir))?;
+        let stem = file_stem(window);
+        Ok(Self {
+            limit,
+            segments: [
+                Segment::create(dir.join(format!("{}.0", stem)))?,
+                Segment::create(dir.join(format!("{}.1", stem)))?,
+            ],
+            active: 0,
+        })
+    }
+
+    /// Lines currently on disk
+    pub fn len(&self) -> usize {
+        self.segments[0].offsets.len() + self.segments[1].offsets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Append the newest evicted line, dropping the oldest half when full
+    pub fn push(&mut self, line: &SpilledLine) -> Result<()> {
+        if self.segments[self.active].offsets.len() >= (self.limit / 2).max(1) {
+            self.active = 1 - self.active;
+            self.segments[self.active].clear()?;
+        }
+        let encoded = serde_json::to_string(line)?;
+        self.segments[self.active].append(&encoded)
+    }
+
+    /// Empty both segments
+    pub fn clear(&mut self) -> Result<()> {
+        for segment in &mut self.segments {
+            segment.clear()?;
+        }
+        self.active = 0;
+        Ok(())
+    }
+
+    /// Lines `start..end` (0 = oldest line on disk)
+    pub fn read(&mut self, start: usize, end: usize) -> Result<Vec<SpilledLine>> {
+        let end = end.min(self.len());
+        let older = 1 - self.active;
+        let older_len = self.segments[older].offsets.len();
+
+        let mut lines = Vec::with_capacity(end.saturating_sub(start));
+        self.segments[older].read(start.min(older_len), end.min(older_len), &mut lines)?;
+        self.segments[self.active].read(
+            start.saturating_sub(older_len),
+            end.saturating_sub(older_len),
+            &mut lines,
+        )?;
+        Ok(lines)
+    }
+}
+
+/// `<pid>-<window>`, with every byte outside `[A-Za-z0-9-]` written as `_xx`
+/// so distinct window names never share files
+fn file_stem(window: &str) -> String {
+    let mut stem = format!("{}-", std::process::id());
+    for byte in window.bytes() {
+        if byte.is_ascii_alphanumeric() || byte == b'-' {
+            stem.push(byte as char);
+        } else {
+            stem.push_str(&format!("_{:02x}", byte));
+        }
+    }
+    stem
+}
+
+impl Drop for ScrollbackSpill {
+    fn drop(&mut self) {
+        for segment in &self.segments {
+            let _ = std::fs::remove_file(&segment.path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(n: usize) -> SpilledLine {
+        SpilledLine {
+            spans: vec![SpilledSpan {
+                text: format!("line {}", n),
+                fg: n.is_multiple_of(2).then(|| "#ff0000".to_string()),
+                bg: None,
+                bold: n.is_multiple_of(3),
+            }],
+            time: n as i64,
+        }
+    }
+
+    /// A spill in a fresh directory for one test
+    fn spill(name: &str) -> (std::path::PathBuf, ScrollbackSpill) {
+        let dir =
+            std::env::temp_dir().join(format!("two-face-spill-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let spill = ScrollbackSpill::create(&dir, "main window", 10).unwrap();
+        (dir, spill)
+    }
+
+    #[test]
+    fn test_spill_reads_ranges() {
+        let (dir, mut spill) = spill("read");
+        for n in 0..8 {
+            spill.push(&line(n)).unwrap();
+        }
+        assert_eq!(spill.len(), 8);
+        assert_eq!(spill.read(3, 6).unwrap(), vec![line(3), line(4), line(5)]);
+        drop(spill);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_spill_drops_oldest_segment_past_limit() {
+        let (dir, mut spill) = spill("ring");
+        for n in 0..13 {
+            spill.push(&line(n)).unwrap();
+        }
+        // 5..=12 remain
+        assert_eq!(spill.len(), 8);
+        let all = spill.read(0, 100).unwrap();
+        assert_eq!(all.first(), Some(&line(5)));
+        assert_eq!(all.last(), Some(&line(12)));
+        assert_eq!(spill.read(4, 6).unwrap(), vec![line(9), line(10)]);
+        drop(spill);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_spill_files_removed_on_drop() {
+        let (dir, mut spill) = spill("drop");
+        for n in 0..8 {
+            spill.push(&line(n)).unwrap();
+        }
+        let stem = format!("{}-main_20window", std::process::id());
+        let files = [
+            dir.join(format!("{}.0", stem)),
+            dir.join(format!("{}.1", stem)),
+        ];
+        assert!(files.iter().all(|f| f.exists()));
+        drop(spill);
+        assert!(files.iter().all(|f| !f.exists()));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_file_stems_stay_distinct() {
+        assert_ne!(file_stem("my.win"), file_stem("my_win"));
+        assert_ne!(file_stem("a_2e"), file_stem("a."));
+        assert!(file_stem("main").starts_with(&format!("{}-", std::process::id())));
+    }
+}
@@ -0,0 +1,25 @@
+//! Session stats rows: elapsed time, experience, kills, deaths and silvers
+//! for this session, with per-hour rates.
+//!
+//! Rows are rebuilt from `AppCore::session` (and the loot tracker's session
+//! silvers) on every sync and drawn through a `ListWidget`.
+
+use super::list_widget::ListRows;
+use crate::core::session_stats::SessionStats;
+use crate::data::{format_elapsed, with_commas};
+
+/// Fill the rows from the session counters
+pub fn fill(rows: &mut ListRows, stats: &SessionStats, silvers: u64) {
+    let rate = |n: u64| {
+        Some(format!(
+            "{} ({}/hr)",
+            with_commas(n),
+            with_commas(stats.per_hour(n))
+        ))
+    };
+    rows.row("elapsed", "Elapsed", Some(format_elapsed(stats.elapsed())));
+    rows.row("experience", "Experience", rate(stats.experience()));
+    rows.row("kills", "Kills", rate(stats.kills as u64));
+    rows.row("deaths", "Deaths", Some(stats.deaths.to_string()));
+    rows.row("silvers", "Silvers", rate(silvers));
+}
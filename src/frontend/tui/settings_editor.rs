@@ -1,7 +1,9 @@
 //! In-terminal settings editor that spans categories and value types.
 //!
 //! Provides table-style navigation, inline editing, and trait-based controls so
-//! it matches the ergonomic expectations set by other popups.
+//! it matches the ergonomic expectations set by other popups. Tab cycles a
+//! category filter and `/` opens a filter box that narrows the list as you
+//! type; every word must appear in the name, key, category or description.
 
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{
@@ -11,56 +13,10 @@ use ratatui::{
     widgets::{Clear, Widget},
 };
 
-#[derive(Debug, Clone, PartialEq)]
-pub enum SettingValue {
-    String(String),
-    Number(i64),
-    Float(f64),
-    Boolean(bool),
-    Color(String),
-    Enum(String, Vec<String>), // (current_value, all_options)
-}
+pub use crate::config::settings::SettingValue;
 
-impl SettingValue {
-    pub fn to_display_string(&self) -> String {
-        match self {
-            SettingValue::String(s) => s.clone(),
-            SettingValue::Number(n) => n.to_string(),
-            SettingValue::Float(f) => format!("{:.2}", f),
-            SettingValue::Boolean(b) => if *b { "true" } else { "false" }.to_string(),
-            SettingValue::Color(c) => c.clone(),
-            SettingValue::Enum(val, _) => val.clone(),
-        }
-    }
-
-    pub fn parse_from_string(&self, s: &str) -> Option<SettingValue> {
-        match self {
-            SettingValue::String(_) => Some(SettingValue::String(s.to_string())),
-            SettingValue::Number(_) => s.parse::<i64>().ok().map(SettingValue::Number),
-            SettingValue::Float(_) => s.parse::<f64>().ok().map(SettingValue::Float),
-            SettingValue::Boolean(_) => match s.to_lowercase().as_str() {
-                "true" | "t" | "1" | "yes" | "y" => Some(SettingValue::Boolean(true)),
-                "false" | "f" | "0" | "no" | "n" => Some(SettingValue::Boolean(false)),
-                _ => None,
-            },
-            SettingValue::Color(_) => {
-                // Basic hex color validation
-                if s.starts_with('#') && s.len() == 7 {
-                    Some(SettingValue::Color(s.to_string()))
-                } else {
-                    Some(SettingValue::Color(s.to_string()))
-                }
-            }
-            SettingValue::Enum(_, options) => {
-                if options.contains(&s.to_string()) {
-                    Some(SettingValue::Enum(s.to_string(), options.clone()))
-                } else {
-                    None
-                }
-            }
-        }
-    }
-}
+/// Shown after the name of settings that only apply after a restart
+const RESTART_MARKER: char = '↻';
 
 #[derive(Debug, Clone)]
 pub struct SettingItem {
@@ -71,6 +27,15 @@ pub struct SettingItem {
     pub description: Option<String>,
     pub editable: bool,
     pub name_width: Option<u16>, // Custom width for name column
+    pub restart_required: bool,  // Change only takes effect after restarting
+}
+
+/// An edit made in the editor, waiting to be validated and applied
+#[derive(Debug, Clone)]
+pub struct SettingChange {
+    pub key: String,
+    pub previous: SettingValue,
+    pub value: SettingValue,
 }
 
 pub struct SettingsEditor {
@@ -80,6 +45,12 @@ pub struct SettingsEditor {
     editing_index: Option<usize>,
     edit_buffer: String,
     category_filter: Option<String>,
+    search: String,
+    searching: bool, // Typing goes to the search query
+    /// Edits not yet collected by take_changes()
+    changes: Vec<SettingChange>,
+    /// Last validation error or apply notice, shown above the help line
+    status: Option<String>,
 
     // Popup dragging
     popup_x: u16,
@@ -98,6 +69,10 @@ impl SettingsEditor {
             editing_index: None,
             edit_buffer: String::new(),
             category_filter: None,
+            search: String::new(),
+            searching: false,
+            changes: Vec::new(),
+            status: None,
             popup_x: 0,
             popup_y: 0,
             is_dragging: false,
@@ -112,17 +87,92 @@ impl SettingsEditor {
         self.scroll_offset = 0;
     }
 
+    /// Step the category filter through All and each category in list order
+    fn cycle_category(&mut self, forward: bool) {
+        let mut categories: Vec<&str> = Vec::new();
+        for item in &self.items {
+            if !categories.contains(&item.category.as_str()) {
+                categories.push(&item.category);
+            }
+        }
+        // Position 0 is "All"
+        let count = categories.len() + 1;
+        let current = self
+            .category_filter
+            .as_deref()
+            .and_then(|filter| categories.iter().position(|c| *c == filter))
+            .map_or(0, |i| i + 1);
+        let next = if forward {
+            (current + 1) % count
+        } else {
+            (current + count - 1) % count
+        };
+        let filter = next.checked_sub(1).map(|i| categories[i].to_string());
+        self.set_category_filter(filter);
+    }
+
+    pub fn is_searching(&self) -> bool {
+        self.searching
+    }
+
+    /// Change the query, staying on the selected setting if it still matches
+    fn set_search(&mut self, query: String) {
+        let selected = self
+            .filtered_items()
+            .get(self.selected_index)
+            .map(|(idx, _)| *idx);
+        self.search = query;
+        self.selected_index = selected
+            .and_then(|selected| {
+                self.filtered_items()
+                    .iter()
+                    .position(|(idx, _)| *idx == selected)
+            })
+            .unwrap_or(0);
+        self.scroll_offset = 0;
+        self.adjust_scroll();
+    }
+
+    /// Whether the filter box is shown above the list
+    fn filter_box_visible(&self) -> bool {
+        self.searching || !self.search.is_empty()
+    }
+
+    /// Rows available for settings and category headers
+    fn list_rows(&self) -> usize {
+        if self.filter_box_visible() {
+            14
+        } else {
+            15
+        }
+    }
+
+    /// Whether an item passes the category filter and search query
+    fn matches(&self, item: &SettingItem) -> bool {
+        if let Some(ref filter) = self.category_filter {
+            if &item.category != filter {
+                return false;
+            }
+        }
+        let text = format!(
+            "{} {} {} {}",
+            item.display_name,
+            item.key,
+            item.category,
+            item.description.as_deref().unwrap_or("")
+        )
+        .to_lowercase();
+        self.search
+            .to_lowercase()
+            .split_whitespace()
+            .all(|word| text.contains(word))
+    }
+
     fn filtered_items(&self) -> Vec<(usize, &SettingItem)> {
         self.items
             .iter()
             .enumerate()
-            .filter(|(_, item)| {
-                if let Some(ref filter) = self.category_filter {
-                    &item.category == filter
-                } else {
-                    true
-                }
-            })
+            .filter(|(_, item)| self.matches(item))
             .collect()
     }
 
@@ -182,7 +232,7 @@ impl SettingsEditor {
             total_display_rows += 1;
         }
 
-        let visible_rows = 15;
+        let visible_rows = self.list_rows();
 
         // Adjust scroll to keep selected item in view
         if selected_display_row < self.scroll_offset {
@@ -198,23 +248,11 @@ impl SettingsEditor {
     }
 
     pub fn get_selected_mut(&mut self) -> Option<&mut SettingItem> {
-        // Find the absolute index without borrowing items
-        let selected_idx = self.selected_index;
-        let category_filter = self.category_filter.clone();
-
+        // Find the absolute index before borrowing items mutably
         let abs_idx = self
-            .items
-            .iter()
-            .enumerate()
-            .filter(|(_, item)| {
-                if let Some(ref filter) = category_filter {
-                    &item.category == filter
-                } else {
-                    true
-                }
-            })
-            .nth(selected_idx)
-            .map(|(idx, _)| idx);
+            .filtered_items()
+            .get(self.selected_index)
+            .map(|(idx, _)| *idx);
 
         if let Some(idx) = abs_idx {
             self.items.get_mut(idx)
@@ -258,8 +296,19 @@ impl SettingsEditor {
         if let Some(editing_idx) = self.editing_index {
             if save {
                 if let Some(item) = self.items.get_mut(editing_idx) {
-                    if let Some(new_value) = item.value.parse_from_string(&self.edit_buffer) {
-                        item.value = new_value;
+                    match item.value.parse_from_string(&self.edit_buffer) {
+                        Some(new_value) if new_value != item.value => {
+                            let previous = std::mem::replace(&mut item.value, new_value);
+                            self.changes.push(SettingChange {
+                                key: item.key.clone(),
+                                previous,
+                                value: item.value.clone(),
+                            });
+                        }
+                        Some(_) => {}
+                        None => {
+                            self.status = Some(format!("Invalid value for {}", item.display_name));
+                        }
                     }
                 }
             }
@@ -272,15 +321,50 @@ impl SettingsEditor {
         self.editing_index.is_some()
     }
 
+    /// Collect edits made since the last call, oldest first
+    pub fn take_changes(&mut self) -> Vec<SettingChange> {
+        std::mem::take(&mut self.changes)
+    }
+
+    /// Put back the previous value of a change that failed to apply
+    pub fn reject_change(&mut self, change: &SettingChange, message: String) {
+        if let Some(item) = self.items.iter_mut().find(|i| i.key == change.key) {
+            item.value = change.previous.clone();
+        }
+        self.status = Some(message);
+    }
+
+    pub fn set_status(&mut self, status: Option<String>) {
+        self.status = status;
+    }
+
+    /// Record a change made in place by toggling or cycling
+    fn record_change(&mut self, previous: SettingValue) {
+        if let Some(item) = self.get_selected() {
+            let change = SettingChange {
+                key: item.key.clone(),
+                previous,
+                value: item.value.clone(),
+            };
+            self.changes.push(change);
+        }
+    }
+
     fn toggle_boolean(&mut self) {
+        let mut previous = None;
         if let Some(item) = self.get_selected_mut() {
             if let SettingValue::Boolean(ref mut val) = item.value {
+                previous = Some(SettingValue::Boolean(*val));
                 *val = !*val;
             }
         }
+        if let Some(previous) = previous {
+            self.record_change(previous);
+        }
     }
 
     fn cycle_enum(&mut self, forward: bool) {
+        let previous = self.get_selected().map(|item| item.value.clone());
         if let Some(item) = self.get_selected_mut() {
             if let SettingValue::Enum(ref mut current, ref options) = item.value {
                 if let Some(current_idx) = options.iter().position(|o| o == current) {
@@ -297,9 +381,38 @@ impl SettingsEditor {
                 }
             }
         }
+        if let Some(previous) = previous {
+            if self
+                .get_selected()
+                .is_some_and(|item| item.value != previous)
+            {
+                self.record_change(previous);
+            }
+        }
     }
 
     pub fn handle_input(&mut self, key: KeyEvent) -> bool {
+        if self.searching {
+            match key.code {
+                KeyCode::Esc => {
+                    self.searching = false;
+                    self.set_search(String::new());
+                }
+                KeyCode::Enter | KeyCode::Down => self.searching = false,
+                KeyCode::Backspace => {
+                    let mut query = self.search.clone();
+                    query.pop();
+                    self.set_search(query);
+                }
+                KeyCode::Char(c) => {
+                    let query = format!("{}{}", self.search, c);
+                    self.set_search(query);
+                }
+                _ => {}
+            }
+            return true;
+        }
+
         if self.is_editing() {
             match key.code {
                 KeyCode::Esc => {
@@ -347,6 +460,18 @@ impl SettingsEditor {
                     self.start_editing();
                     return true;
                 }
+                KeyCode::Char('/') => {
+                    self.searching = true;
+                    return true;
+                }
+                KeyCode::Tab => {
+                    self.cycle_category(true);
+                    return true;
+                }
+                KeyCode::BackTab => {
+                    self.cycle_category(false);
+                    return true;
+                }
                 KeyCode::Char(' ') => {
                     // Toggle boolean or start editing enum
                     if let Some(item) = self.get_selected() {
@@ -499,11 +624,21 @@ impl SettingsEditor {
         } else {
             (self.selected_index + 1).min(total)
         };
-        let help = format!(
-            " ↑/↓:Nav  Enter:Edit  Space:Toggle  Esc:Close  ({}/{}) ",
-            current, total
-        );
-        let help_x = popup_area.x + popup_area.width.saturating_sub(help.len() as u16 + 1);
+        let help = if self.searching {
+            format!(
+                " Type to search  Enter:Done  Esc:Clear  ({}/{}) ",
+                current, total
+            )
+        } else {
+            format!(
+                " ↑/↓:Nav  Enter:Edit  Space:Toggle  /:Search  Tab:Filter  ({}/{}) ",
+                current, total
+            )
+        };
+        let help_x = popup_area.x
+            + popup_area
+                .width
+                .saturating_sub(help.chars().count() as u16 + 1);
         let start_x = if help_x > popup_area.x + 1 {
             help_x
         } else {
@@ -524,17 +659,68 @@ impl SettingsEditor {
             }
         }
 
+        // Status line: last error/notice, otherwise the restart marker legend
+        let status_y = popup_area.y + popup_area.height.saturating_sub(3);
+        if status_y > popup_area.y {
+            let (status, status_fg) = match self.status {
+                Some(ref status) => (status.clone(), theme.form_label_focused),
+                None => (
+                    format!("{} = applies after restart", RESTART_MARKER),
+                    theme.text_disabled,
+                ),
+            };
+            for (i, ch) in status.chars().enumerate() {
+                let x = popup_area.x + 2 + i as u16;
+                if x >= popup_area.x + popup_area.width.saturating_sub(2) {
+                    break;
+                }
+                if let Some(cell) = buf.cell_mut((x, status_y)) {
+                    cell.set_char(ch);
+                    cell.set_fg(status_fg);
+                    cell.set_bg(theme.browser_background);
+                }
+            }
+        }
+
+        // Filter box above the list while searching or filtered
+        let mut list_top = popup_area.y + 1;
+        if self.filter_box_visible() && popup_area.height > 5 {
+            let cursor = if self.searching { "_" } else { "" };
+            let filter_line = format!("Filter: {}{}", self.search, cursor);
+            let fg = if self.searching {
+                theme.form_label_focused
+            } else {
+                theme.text_disabled
+            };
+            for (i, ch) in filter_line.chars().enumerate() {
+                let x = popup_area.x + 2 + i as u16;
+                if x >= popup_area.x + popup_area.width.saturating_sub(2) {
+                    break;
+                }
+                if let Some(cell) = buf.cell_mut((x, list_top)) {
+                    cell.set_char(ch);
+                    cell.set_fg(fg);
+                    cell.set_bg(theme.browser_background);
+                }
+            }
+            list_top += 1;
+        }
+
         // Draw settings list
         let list_area = Rect {
             x: popup_area.x + 2,
-            y: popup_area.y + 1,
+            y: list_top,
             width: popup_area.width.saturating_sub(4),
-            height: popup_area.height.saturating_sub(4),
+            height: (popup_area.height.saturating_sub(5) as usize).min(self.list_rows()) as u16,
         };
 
         if filtered.is_empty() {
             // Show "No settings" message
-            let msg = "No settings available";
+            let msg = if self.search.trim().is_empty() {
+                "No settings available"
+            } else {
+                "No settings match the filter"
+            };
             let x = list_area.x + (list_area.width.saturating_sub(msg.len() as u16)) / 2;
             let y = list_area.y + list_area.height / 2;
             for (i, ch) in msg.chars().enumerate() {
@@ -695,6 +881,15 @@ impl SettingsEditor {
             }
         }
 
+        if item.restart_required {
+            let px = x
+                + (item.display_name.chars().count() as u16 + 1).min(name_width.saturating_sub(1));
+            if let Some(cell) = buf.cell_mut((px, y)) {
+                cell.set_char(RESTART_MARKER);
+                cell.set_fg(theme.text_disabled);
+            }
+        }
+
         // Render separator
         let sep_x = x + name_width;
         if let Some(cell) = buf.cell_mut((sep_x, y)) {
@@ -883,3 +1078,69 @@ impl Cyclable for SettingsEditor {
 
 // Note: TextEditable trait not implemented - SettingsEditor uses a String edit_buffer
 // rather than TextArea fields. Clipboard operations are handled internally via handle_input().
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(category: &str, key: &str, name: &str) -> SettingItem {
+        SettingItem {
+            category: category.to_string(),
+            key: key.to_string(),
+            display_name: name.to_string(),
+            value: SettingValue::Boolean(false),
+            description: None,
+            editable: true,
+            name_width: None,
+            restart_required: false,
+        }
+    }
+
+    #[test]
+    fn test_category_cycle_and_search() {
+        let mut editor = SettingsEditor::new(vec![
+            item("UI", "ui.buffer_size", "Buffer Size"),
+            item("UI", "ui.show_clock", "Show Clock"),
+            item("Sound", "sound.enabled", "Enabled"),
+        ]);
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+
+        editor.handle_input(key(KeyCode::Tab));
+        assert_eq!(editor.filtered_items().len(), 2);
+        editor.handle_input(key(KeyCode::Tab));
+        assert_eq!(editor.get_selected().unwrap().key, "sound.enabled");
+        editor.handle_input(key(KeyCode::Tab));
+        assert_eq!(editor.filtered_items().len(), 3);
+        editor.handle_input(key(KeyCode::BackTab));
+        assert_eq!(editor.category_filter.as_deref(), Some("Sound"));
+        editor.set_category_filter(None);
+
+        editor.handle_input(key(KeyCode::Char('/')));
+        assert!(editor.is_searching());
+        for c in "CLOCK".chars() {
+            editor.handle_input(key(KeyCode::Char(c)));
+        }
+        editor.handle_input(key(KeyCode::Enter));
+        assert!(!editor.is_searching());
+        assert_eq!(editor.filtered_items().len(), 1);
+
+        // Toggling acts on the filtered selection
+        editor.handle_input(key(KeyCode::Char(' ')));
+        let changes = editor.take_changes();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].key, "ui.show_clock");
+
+        editor.handle_input(key(KeyCode::Char('/')));
+        editor.handle_input(key(KeyCode::Esc));
+        assert_eq!(editor.filtered_items().len(), 3);
+        // Clearing the filter stays on the setting that was selected
+        assert_eq!(editor.get_selected().unwrap().key, "ui.show_clock");
+
+        // Every word has to match, in any field
+        editor.set_search("ui size".to_string());
+        assert_eq!(editor.get_selected().unwrap().key, "ui.buffer_size");
+        assert_eq!(editor.filtered_items().len(), 1);
+        editor.set_search("sound clock".to_string());
+        assert!(editor.filtered_items().is_empty());
+    }
+}
@@ -0,0 +1,261 @@
+//! Browser popup for command snippets.
+//!
+//! Lists each snippet with its placeholders and a preview of its text.
+//! Enter runs the selected snippet, E edits it in the composer, Del deletes it.
+
+use crate::config::Snippet;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    widgets::{Clear, Widget},
+};
+use std::collections::HashMap;
+
+const VISIBLE_ROWS: usize = 14;
+
+pub struct SnippetEntry {
+    pub name: String,
+    pub placeholders: Vec<String>,
+    pub preview: String,
+}
+
+pub struct SnippetBrowser {
+    entries: Vec<SnippetEntry>,
+    selected_index: usize,
+    scroll_offset: usize,
+    popup_position: (u16, u16),
+}
+
+impl SnippetBrowser {
+    pub fn new(snippets: &HashMap<String, Snippet>) -> Self {
+        let mut entries: Vec<SnippetEntry> = snippets
+            .iter()
+            .map(|(name, snippet)| {
+                let lines = snippet.text.lines().count();
+                let preview = match snippet.description {
+                    Some(ref description) => description.clone(),
+                    None if lines > 1 => format!(
+                        "{} (+{} more)",
+                        snippet.text.lines().next().unwrap_or_default(),
+                        lines - 1
+                    ),
+                    None => snippet.text.clone(),
+                };
+                SnippetEntry {
+                    name: name.clone(),
+                    placeholders: crate::core::snippets::placeholders(&snippet.text),
+                    preview,
+                }
+            })
+            .collect();
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Self {
+            entries,
+            selected_index: 0,
+            scroll_offset: 0,
+            popup_position: (0, 0),
+        }
+    }
+
+    fn select(&mut self, index: usize) {
+        self.selected_index = index.min(self.entries.len().saturating_sub(1));
+        if self.selected_index < self.scroll_offset {
+            self.scroll_offset = self.selected_index;
+        } else if self.selected_index >= self.scroll_offset + VISIBLE_ROWS {
+            self.scroll_offset = self.selected_index + 1 - VISIBLE_ROWS;
+        }
+    }
+
+    pub fn render(
+        &mut self,
+        area: Rect,
+        buf: &mut Buffer,
+        _config: &crate::config::Config,
+        theme: &crate::theme::AppTheme,
+    ) {
+        let popup_width = 76.min(area.width);
+        let popup_height = (VISIBLE_ROWS as u16 + 5).min(area.height);
+        if popup_width < 30 || popup_height < 6 {
+            return;
+        }
+
+        // Center on first render
+        if self.popup_position == (0, 0) {
+            self.popup_position = (
+                (area.width.saturating_sub(popup_width)) / 2,
+                (area.height.saturating_sub(popup_height)) / 2,
+            );
+        }
+        let (popup_col, popup_row) = self.popup_position;
+        let popup_area = Rect {
+            x: popup_col,
+            y: popup_row,
+            width: popup_width,
+            height: popup_height,
+        }
+        .intersection(area);
+        Clear.render(popup_area, buf);
+
+        let background = Style::default().bg(theme.browser_background);
+        for row in popup_area.top()..popup_area.bottom() {
+            for col in popup_area.left()..popup_area.right() {
+                buf.set_string(col, row, " ", background);
+            }
+        }
+
+        // Border and title
+        let border_style = Style::default().fg(theme.browser_border);
+        let inner_width = popup_width as usize - 2;
+        buf.set_string(
+            popup_col,
+            popup_row,
+            format!("┌{}┐", "─".repeat(inner_width)),
+            border_style,
+        );
+        buf.set_string(
+            popup_col + 2,
+            popup_row,
+            " Snippets ",
+            border_style.add_modifier(Modifier::BOLD),
+        );
+        for i in 1..popup_height - 1 {
+            buf.set_string(popup_col, popup_row + i, "│", border_style);
+            buf.set_string(
+                popup_col + popup_width - 1,
+                popup_row + i,
+                "│",
+                border_style,
+            );
+        }
+        buf.set_string(
+            popup_col,
+            popup_row + popup_height - 1,
+            format!("└{}┘", "─".repeat(inner_width)),
+            border_style,
+        );
+
+        let text_x = popup_col + 2;
+        let text_width = popup_width as usize - 4;
+        let rows = (popup_height as usize).saturating_sub(4);
+
+        if self.entries.is_empty() {
+            buf.set_string(
+                text_x,
+                popup_row + 1,
+                "No snippets yet: .addsnippet <name> to write one",
+                Style::default()
+                    .fg(theme.menu_separator)
+                    .bg(theme.browser_background),
+            );
+        }
+        for (row, (index, entry)) in self
+            .entries
+            .iter()
+            .enumerate()
+            .skip(self.scroll_offset)
+            .take(rows)
+            .enumerate()
+        {
+            let style = if index == self.selected_index {
+                Style::default()
+                    .fg(theme.browser_item_focused)
+                    .bg(theme.browser_background)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+                    .fg(theme.browser_item_normal)
+                    .bg(theme.browser_background)
+            };
+            let placeholders = entry
+                .placeholders
+                .iter()
+                .map(|name| format!("${{{}}}", name))
+                .collect::<Vec<_>>()
+                .join(" ");
+            let line = format!("{:<18} {:<20} {}", entry.name, placeholders, entry.preview);
+            buf.set_string(
+                text_x,
+                popup_row + 1 + row as u16,
+                truncate(&line, text_width),
+                style,
+            );
+        }
+
+        // Status bar
+        let total = self.entries.len();
+        let current = if total == 0 {
+            0
+        } else {
+            self.selected_index + 1
+        };
+        let status = format!(
+            " ↑/↓:Nav  Enter:Run  E:Edit  Del:Delete  Esc:Close  ({}/{}) ",
+            current, total
+        );
+        buf.set_string(
+            text_x,
+            popup_row + popup_height - 2,
+            truncate(&status, text_width),
+            Style::default()
+                .fg(theme.menu_separator)
+                .bg(theme.browser_background),
+        );
+    }
+}
+
+fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        text.to_string()
+    } else {
+        let mut short: String = text.chars().take(width.saturating_sub(3)).collect();
+        short.push_str("...");
+        short
+    }
+}
+
+use super::widget_traits::{Navigable, Selectable};
+
+impl Navigable for SnippetBrowser {
+    fn navigate_up(&mut self) {
+        self.select(self.selected_index.saturating_sub(1));
+    }
+
+    fn navigate_down(&mut self) {
+        self.select(self.selected_index + 1);
+    }
+
+    fn page_up(&mut self) {
+        self.select(self.selected_index.saturating_sub(VISIBLE_ROWS));
+    }
+
+    fn page_down(&mut self) {
+        self.select(self.selected_index + VISIBLE_ROWS);
+    }
+
+    fn home(&mut self) {
+        self.select(0);
+    }
+
+    fn end(&mut self) {
+        self.select(self.entries.len());
+    }
+}
+
+impl Selectable for SnippetBrowser {
+    fn get_selected(&self) -> Option<String> {
+        self.entries
+            .get(self.selected_index)
+            .map(|entry| entry.name.clone())
+    }
+
+    fn delete_selected(&mut self) -> Option<String> {
+        if self.selected_index >= self.entries.len() {
+            return None;
+        }
+        let entry = self.entries.remove(self.selected_index);
+        self.select(self.selected_index);
+        Some(entry.name)
+    }
+}
@@ -0,0 +1,183 @@
+//! Status line: a one-row HUD laid out by a user template.
+//!
+//! `{field}` placeholders are filled from `GameState::status_field` each sync;
+//! `{#rrggbb}` starts a colored run and `{/}` goes back to the window's text
+//! color. Unknown fields are left as written so typos show up on screen.
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Clear, Paragraph, Widget},
+};
+
+/// Fill in a template: runs of text, each with its color (None = text color)
+pub fn expand_template(
+    template: &str,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Vec<(String, Option<Color>)> {
+    let mut runs: Vec<(String, Option<Color>)> = Vec::new();
+    let mut color = None;
+    let mut rest = template;
+    let push = |runs: &mut Vec<(String, Option<Color>)>, text: &str, color| match runs.last_mut() {
+        Some((last, last_color)) if *last_color == color => last.push_str(text),
+        _ if text.is_empty() => {}
+        _ => runs.push((text.to_string(), color)),
+    };
+
+    while let Some(open) = rest.find('{') {
+        push(&mut runs, &rest[..open], color);
+        let Some(close) = rest[open..].find('}').map(|i| open + i) else {
+            rest = &rest[open..];
+            break;
+        };
+        let field = &rest[open + 1..close];
+        if field == "/" {
+            color = None;
+        } else if let Some(hex) = field.strip_prefix('#') {
+            color = super::parse_hex_color(hex).ok().or(color);
+        } else {
+            match lookup(field) {
+                Some(value) => push(&mut runs, &value, color),
+                None => push(&mut runs, &rest[open..=close], color),
+            }
+        }
+        rest = &rest[close + 1..];
+    }
+    push(&mut runs, rest, color);
+    runs
+}
+
+pub struct StatusLine {
+    title: String,
+    runs: Vec<(String, Option<Color>)>,
+    show_border: bool,
+    border_style: Option<String>,
+    border_color: Option<Color>,
+    border_sides: crate::config::BorderSides,
+    text_color: Option<Color>,
+    background_color: Option<Color>,
+    transparent_background: bool,
+}
+
+impl StatusLine {
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            runs: Vec::new(),
+            show_border: false,
+            border_style: None,
+            border_color: None,
+            border_sides: crate::config::BorderSides::default(),
+            text_color: None,
+            background_color: None,
+            transparent_background: true,
+        }
+    }
+
+    pub fn set_runs(&mut self, runs: Vec<(String, Option<Color>)>) {
+        self.runs = runs;
+    }
+
+    pub fn render(&self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+        if !self.transparent_background {
+            if let Some(bg) = self.background_color {
+                buf.set_style(area, Style::default().bg(bg));
+            }
+        }
+
+        let inner = if self.show_border {
+            let block = Block::default()
+                .borders(crate::config::parse_border_sides(&self.border_sides))
+                .border_style(Style::default().fg(self.border_color.unwrap_or(Color::Reset)))
+                .border_type(match self.border_style.as_deref() {
+                    Some("double") => BorderType::Double,
+                    Some("rounded") => BorderType::Rounded,
+                    Some("thick") => BorderType::Thick,
+                    _ => BorderType::Plain,
+                })
+                .title(self.title.as_str());
+            let inner = block.inner(area);
+            block.render(area, buf);
+            inner
+        } else {
+            area
+        };
+
+        let spans: Vec<Span> = self
+            .runs
+            .iter()
+            .map(|(text, color)| {
+                let fg = color.or(self.text_color).unwrap_or(Color::Reset);
+                Span::styled(text.as_str(), Style::default().fg(fg))
+            })
+            .collect();
+        Paragraph::new(Line::from(spans)).render(inner, buf);
+    }
+}
+
+impl super::StyledWindow for StatusLine {
+    fn set_title(&mut self, title: String) {
+        self.title = title;
+    }
+
+    fn set_border_config(&mut self, show: bool, style: Option<String>, color: Option<String>) {
+        self.show_border = show;
+        self.border_style = style;
+        self.border_color = color.and_then(|c| super::parse_hex_color(&c).ok());
+    }
+
+    fn set_border_sides(&mut self, sides: crate::config::BorderSides) {
+        self.border_sides = sides;
+    }
+
+    fn set_text_color(&mut self, color: Option<String>) {
+        self.text_color = color.and_then(|c| super::parse_hex_color(&c).ok());
+    }
+
+    fn set_background_color(&mut self, color: Option<String>) {
+        self.background_color = color
+            .filter(|c| c != "-")
+            .and_then(|c| super::parse_hex_color(&c).ok());
+    }
+
+    fn set_transparent_background(&mut self, transparent: bool) {
+        self.transparent_background = transparent;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(field: &str) -> Option<String> {
+        match field {
+            "rt" => Some("3".to_string()),
+            "stance" => Some("defensive".to_string()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_expand_fields_and_colors() {
+        assert_eq!(
+            expand_template("RT {#ff0000}{rt}{/} | {stance}", lookup),
+            vec![
+                ("RT ".to_string(), None),
+                ("3".to_string(), Some(Color::Rgb(255, 0, 0))),
+                (" | defensive".to_string(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unknown_fields_kept_as_typed() {
+        // Unclosed braces too
+        assert_eq!(
+            expand_template("{mana} {rt", lookup),
+            vec![("{mana} {rt".to_string(), None)]
+        );
+    }
+}
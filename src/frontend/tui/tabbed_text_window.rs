@@ -67,7 +67,7 @@ impl TabbedTextWindow {
     pub fn with_tabs(title: &str, tabs: Vec<(String, String)>, max_lines_per_tab: usize) -> Self {
         let mut window = Self::new(title, TabBarPosition::Top);
         for (name, stream) in tabs {
-            window.add_tab(name, stream, max_lines_per_tab, false);
+            window.add_tab(name, stream, max_lines_per_tab, &Default::default());
         }
         window
     }
@@ -111,10 +111,10 @@ impl TabbedTextWindow {
         name: String,
         stream: String,
         max_lines: usize,
-        show_timestamps: bool,
+        timestamps: &crate::config::TimestampConfig,
     ) {
         let mut window = TextWindow::new(&name, max_lines);
-        window.set_show_timestamps(show_timestamps);
+        window.set_timestamps(timestamps);
         window.set_border_config(false, None, None); // Tabs don't have their own borders
         window.set_background_color(self.background_color.clone());
         window.set_text_color(self.content_text_color.clone());
@@ -334,6 +334,25 @@ impl TabbedTextWindow {
         }
     }
 
+    /// Plain text of the active tab's buffer
+    pub fn buffer_text(&mut self) -> Vec<String> {
+        self.tabs
+            .get_mut(self.active_tab_index)
+            .map(|tab| tab.window.buffer_text())
+            .unwrap_or_default()
+    }
+
+    /// Timestamp settings for one tab's new lines
+    pub fn set_tab_timestamps(
+        &mut self,
+        tab_name: &str,
+        timestamps: &crate::config::TimestampConfig,
+    ) {
+        if let Some(tab) = self.tabs.iter_mut().find(|tab| tab.name == tab_name) {
+            tab.window.set_timestamps(timestamps);
+        }
+    }
+
     pub fn next_match(&mut self) -> bool {
         if let Some(tab) = self.tabs.get_mut(self.active_tab_index) {
             tab.window.next_match()
@@ -374,6 +393,24 @@ impl TabbedTextWindow {
         self.background_color = color;
     }
 
+    pub fn set_fade_minutes(&mut self, minutes: u32) {
+        for tab in &mut self.tabs {
+            tab.window.set_fade_minutes(minutes);
+        }
+    }
+
+    pub fn set_gap_minutes(&mut self, minutes: u32) {
+        for tab in &mut self.tabs {
+            tab.window.set_gap_minutes(minutes);
+        }
+    }
+
+    pub fn set_collapse_repeats(&mut self, enabled: bool) {
+        for tab in &mut self.tabs {
+            tab.window.set_collapse_repeats(enabled);
+        }
+    }
+
     pub fn apply_window_colors(
         &mut self,
         text_color: Option<String>,
@@ -25,7 +25,13 @@ impl Targets {
 
     /// Parse targets from formatted game text
     /// Format: "[stu] goblin, <b>[sit] troll</b>, <color ul='true'><b>bandit</b></color>"
-    pub fn set_targets_from_text(&mut self, text: &str) {
+    ///
+    /// `color_for` picks a text color per target name (dangerous creatures).
+    pub fn set_targets_from_text(
+        &mut self,
+        text: &str,
+        color_for: impl Fn(&str) -> Option<String>,
+    ) {
         self.container.clear();
         self.count = 0;
 
@@ -74,6 +80,8 @@ impl Targets {
                 continue;
             }
 
+            let text_color = color_for(&clean_name);
+
             // Add prefix for current target
             let display_name = if is_current {
                 format!("► {}", clean_name)
@@ -93,7 +101,7 @@ impl Targets {
                 1,      // max (hidden)
                 status, // suffix (status like "[sit]")
                 None,   // no color override
-                None,
+                text_color,
             );
 
             self.count += 1;
@@ -3,6 +3,7 @@
 //! Responsible for buffering, wrapping, highlighting, search, and selection
 //! logic in a way that mirrors Profanity/Vellum's behavior.
 
+use super::scrollback_spill::{ScrollbackSpill, SpilledLine, SpilledSpan};
 use crate::config::HighlightPattern;
 use aho_corasick::{AhoCorasick, AhoCorasickBuilder, MatchKind};
 use ratatui::{
@@ -14,6 +15,8 @@ use ratatui::{
 };
 use regex::Regex;
 use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+use unicode_width::UnicodeWidthChar;
 
 // Per-character style info for layering
 #[derive(Clone, Copy)]
@@ -31,6 +34,8 @@ pub enum SpanType {
     Monsterbold, // <preset id="monsterbold"> from parser
     Spell,       // <spell> tag from parser
     Speech,      // <preset id="speech"> from parser
+    Timestamp,   // Added by the window; relabelled at render time when relative
+    Url,         // http(s) link, underlined
 }
 
 /// Link metadata for clickable game objects
@@ -40,6 +45,7 @@ pub struct LinkData {
     pub noun: String,          // The noun/name of the object
     pub text: String,          // The actual displayed text (e.g., "nacreous pearl bangle")
     pub coord: Option<String>, // Optional coord for direct commands (e.g., "2524,1864" for movement)
+    pub flags: crate::data::ItemFlags, // Item state hints (worn, closed, lit...)
 }
 
 #[derive(Clone)]
@@ -56,14 +62,26 @@ pub struct StyledText {
 #[derive(Clone)]
 struct WrappedLine {
     spans: Vec<(String, Style, SpanType, Option<LinkData>)>,
+    created: Instant, // When the logical line arrived (drives age fading)
 }
 
 // One logical line (before wrapping) - stores original styled content
 #[derive(Clone)]
 struct LogicalLine {
     spans: Vec<(String, Style, SpanType, Option<LinkData>)>,
+    created: Instant,
+    rows: usize, // Wrapped lines it currently occupies
 }
 
+/// Logical lines read back from disk per step when scrolling past the top of memory
+const DISK_PAGE_LINES: usize = 500;
+
+/// Logical lines above the view re-wrapped per frame after a resize
+const REWRAP_CHUNK_LINES: usize = 1000;
+
+/// Brightness multiplier for each age band (band 1 = older than one fade period)
+const FADE_BAND_FACTORS: [f32; 3] = [0.8, 0.65, 0.5];
+
 // Match location: (line_index, start_char, end_char)
 #[derive(Clone, Debug)]
 struct SearchMatch {
@@ -95,6 +113,7 @@ pub struct TextWindow {
     title: String,
     last_width: u16,
     needs_rewrap: bool, // Flag to trigger re-wrapping
+    stale_lines: usize, // Oldest logical lines still wrapped at an earlier width
     // Border configuration
     show_border: bool,
     border_style: Option<String>,
@@ -105,6 +124,8 @@ pub struct TextWindow {
     content_align: Option<String>,
     // Search functionality
     search_state: Option<SearchState>,
+    // Pinned search: matched on every rendered line, so new text is marked too
+    pin: Option<Regex>,
     // Highlight patterns
     highlights: Vec<HighlightPattern>,
     // Precompiled highlight regexes (parallel to highlights vec, only for non-fast_parse)
@@ -119,7 +140,29 @@ pub struct TextWindow {
     recent_links: VecDeque<LinkData>,
     max_recent_links: usize,
     // Timestamp configuration
-    show_timestamps: bool,
+    timestamps: crate::config::TimestampConfig,
+    // Dim lines older than this (None = fading disabled)
+    fade_after: Option<Duration>,
+    // Separate output from the previous line after a silence this long (None = off)
+    gap_after: Option<Duration>,
+    // More-prompt paging: hold the view once a screen of unacknowledged text arrives
+    more_prompt: bool,
+    unseen_rows: usize, // Rows added in live view since the last acknowledgment
+    more_paused: bool,  // Holding at "-- more --" (the view is scrolled back)
+    // Lines that arrived while scrolled back or covered by another window
+    activity: usize,
+    obscured: bool,
+    // Fold a line identical to the previous one into it with an "(xN)" count
+    collapse_repeats: bool,
+    last_line_text: Option<String>, // Text of the newest line, as it arrived
+    repeats: usize,                 // Times the newest line has arrived in a row
+    // Lines evicted from memory are appended here when disk scrollback is on
+    spill: Option<ScrollbackSpill>,
+    spill_limit: usize, // Requested disk scrollback (0 = off)
+    // Spilled lines paged back in at the front of logical_lines while scrolled back
+    paged_in: usize,
+    // Spilled lines between the paged-in block and the live lines that aren't in memory
+    paged_gap: usize,
 }
 
 impl Clone for TextWindow {
@@ -136,6 +179,7 @@ impl Clone for TextWindow {
             title: self.title.clone(),
             last_width: self.last_width,
             needs_rewrap: self.needs_rewrap,
+            stale_lines: self.stale_lines,
             show_border: self.show_border,
             border_style: self.border_style.clone(),
             border_color: self.border_color.clone(),
@@ -145,6 +189,7 @@ impl Clone for TextWindow {
             content_align: self.content_align.clone(),
             // Skip search_state (contains Regex which doesn't implement Clone)
             search_state: None,
+            pin: self.pin.clone(),
             highlights: self.highlights.clone(),
             // Skip highlight_regexes (contains Regex)
             highlight_regexes: vec![],
@@ -154,7 +199,22 @@ impl Clone for TextWindow {
             fast_pattern_map: self.fast_pattern_map.clone(),
             recent_links: self.recent_links.clone(),
             max_recent_links: self.max_recent_links,
-            show_timestamps: self.show_timestamps,
+            timestamps: self.timestamps.clone(),
+            fade_after: self.fade_after,
+            gap_after: self.gap_after,
+            more_prompt: self.more_prompt,
+            unseen_rows: self.unseen_rows,
+            more_paused: self.more_paused,
+            activity: self.activity,
+            obscured: self.obscured,
+            collapse_repeats: self.collapse_repeats,
+            last_line_text: self.last_line_text.clone(),
+            repeats: self.repeats,
+            // Skip spill (a clone doesn't own the window's files)
+            spill: None,
+            spill_limit: 0,
+            paged_in: self.paged_in,
+            paged_gap: self.paged_gap,
         }
     }
 }
@@ -170,6 +230,7 @@ impl TextWindow {
             title: title.into(),
             last_width: 0,
             needs_rewrap: false,
+            stale_lines: 0,
             last_render_range: None,
             show_border: true,
             border_style: None,
@@ -178,17 +239,32 @@ impl TextWindow {
             background_color: None,
             default_text_color: None,
             content_align: None,
-            scroll_position: None,         // Start in live view mode
-            last_visible_height: 20,       // Reasonable default
-            search_state: None,            // No active search
-            highlights: Vec::new(),        // No highlights by default
-            highlight_regexes: Vec::new(), // No precompiled regexes by default
-            fast_matcher: None,            // No Aho-Corasick matcher by default
-            fast_pattern_map: Vec::new(),  // No fast pattern mapping by default
-            recent_links: VecDeque::new(), // No recent links yet
-            max_recent_links: 100,         // Keep last 100 links
-            show_timestamps: false,        // Timestamps off by default
-            links_enabled: true,           // Links enabled by default
+            scroll_position: None,          // Start in live view mode
+            last_visible_height: 20,        // Reasonable default
+            search_state: None,             // No active search
+            pin: None,                      // Nothing pinned
+            highlights: Vec::new(),         // No highlights by default
+            highlight_regexes: Vec::new(),  // No precompiled regexes by default
+            fast_matcher: None,             // No Aho-Corasick matcher by default
+            fast_pattern_map: Vec::new(),   // No fast pattern mapping by default
+            recent_links: VecDeque::new(),  // No recent links yet
+            max_recent_links: 100,          // Keep last 100 links
+            timestamps: Default::default(), // Timestamps off by default
+            fade_after: None,               // Fading off by default
+            gap_after: None,                // No idle separators by default
+            more_prompt: false,             // Output never pauses by default
+            unseen_rows: 0,
+            more_paused: false,
+            activity: 0,
+            obscured: false,
+            collapse_repeats: false, // Every line kept by default
+            last_line_text: None,
+            repeats: 0,
+            links_enabled: true, // Links enabled by default
+            spill: None,         // Disk scrollback off by default
+            spill_limit: 0,
+            paged_in: 0,
+            paged_gap: 0,
         }
     }
 
@@ -290,8 +366,135 @@ impl TextWindow {
         self.title = title;
     }
 
-    pub fn set_show_timestamps(&mut self, show: bool) {
-        self.show_timestamps = show;
+    /// Timestamp settings for lines added from now on
+    pub fn set_timestamps(&mut self, timestamps: &crate::config::TimestampConfig) {
+        if self.timestamps != *timestamps {
+            self.timestamps = timestamps.clone();
+        }
+    }
+
+    /// Change the scrollback limit, dropping the oldest lines if it shrank
+    pub fn set_max_lines(&mut self, max_lines: usize) {
+        if max_lines == self.max_lines {
+            return;
+        }
+        self.max_lines = max_lines;
+        self.evict_overflow();
+    }
+
+    /// Requested disk scrollback in lines (0 = off)
+    pub fn disk_scrollback_limit(&self) -> usize {
+        self.spill_limit
+    }
+
+    /// Keep up to `limit` evicted lines in spill files under `dir` (0 = off).
+    /// Changing the limit starts a fresh spill.
+    pub fn set_disk_scrollback(&mut self, dir: &std::path::Path, window: &str, limit: usize) {
+        self.spill_limit = limit;
+        self.spill = None;
+        if limit == 0 {
+            return;
+        }
+        match ScrollbackSpill::create(dir, window, limit) {
+            Ok(spill) => self.spill = Some(spill),
+            Err(e) => tracing::warn!("Disk scrollback unavailable for '{}': {:#}", window, e),
+        }
+    }
+
+    /// Dim lines as they age: one band per `minutes` elapsed, up to three (0 = off)
+    pub fn set_fade_minutes(&mut self, minutes: u32) {
+        self.fade_after = if minutes > 0 {
+            Some(Duration::from_secs(minutes as u64 * 60))
+        } else {
+            None
+        };
+    }
+
+    /// Insert an idle separator before output that follows `minutes` of silence (0 = off)
+    pub fn set_gap_minutes(&mut self, minutes: u32) {
+        self.gap_after = if minutes > 0 {
+            Some(Duration::from_secs(minutes as u64 * 60))
+        } else {
+            None
+        };
+    }
+
+    /// Fold repeats of the newest line into it as "(xN)"
+    pub fn set_collapse_repeats(&mut self, enabled: bool) {
+        self.collapse_repeats = enabled;
+    }
+
+    /// Hold at "-- more --" whenever a screen of text arrives while scrolled back
+    pub fn set_more_prompt(&mut self, enabled: bool) {
+        if enabled == self.more_prompt {
+            return;
+        }
+        self.more_prompt = enabled;
+        self.unseen_rows = 0;
+        if !enabled && self.more_paused {
+            self.more_paused = false;
+            self.scroll_to_live();
+        }
+    }
+
+    /// Whether output is held at "-- more --"
+    pub fn is_more_paused(&self) -> bool {
+        self.more_paused && self.scroll_position.is_some()
+    }
+
+    /// Acknowledge what's on screen: show the next page if paused, else restart the count
+    pub fn acknowledge_more(&mut self) {
+        if self.is_more_paused() {
+            self.scroll_down(self.more_page_rows());
+        } else {
+            self.more_paused = false;
+            self.unseen_rows = 0;
+        }
+    }
+
+    /// New rows per page, keeping the last row of the previous page for context
+    fn more_page_rows(&self) -> usize {
+        self.last_visible_height.saturating_sub(1).max(1)
+    }
+
+    /// Count rows arriving while scrolled back; once they fill a page, hold
+    /// at "-- more --" so Enter pages through them. The live view never pauses.
+    fn track_unseen_rows(&mut self, rows: usize) {
+        if !self.more_prompt || self.scroll_position.is_none() || self.last_visible_height == 0 {
+            return;
+        }
+        self.unseen_rows += rows;
+        if self.unseen_rows > self.more_page_rows() {
+            self.more_paused = true;
+        }
+    }
+
+    /// Whether another window covers part of this one (new lines then count as unseen)
+    pub fn set_obscured(&mut self, obscured: bool) {
+        self.obscured = obscured;
+        if !obscured && self.scroll_position.is_none() {
+            self.activity = 0;
+        }
+    }
+
+    /// Lines that arrived while they couldn't be seen
+    pub fn activity(&self) -> usize {
+        self.activity
+    }
+
+    /// "── 14:05, 12 min idle ──"
+    fn gap_label(idle: Duration) -> String {
+        let minutes = idle.as_secs() / 60;
+        let idle = match (minutes / 60, minutes % 60) {
+            (0, m) => format!("{} min", m),
+            (h, 0) => format!("{}h", h),
+            (h, m) => format!("{}h {}m", h, m),
+        };
+        format!(
+            "── {}, {} idle ──",
+            chrono::Local::now().format("%H:%M"),
+            idle
+        )
     }
 
     pub fn toggle_links(&mut self) {
@@ -306,11 +509,78 @@ impl TextWindow {
         self.show_border
     }
 
-    /// Format current time as timestamp (e.g., "[7:08 AM]")
-    fn format_timestamp() -> String {
-        use chrono::Local;
-        let now = Local::now();
-        format!(" [{}]", now.format("%l:%M %p").to_string().trim())
+    /// Timestamp span text for a line arriving now (" [7:08 AM]" at the end)
+    fn format_timestamp(&self) -> String {
+        let label = if self.timestamps.relative {
+            Self::relative_label(0)
+        } else {
+            let format = if crate::config::TimestampConfig::format_is_valid(&self.timestamps.format)
+            {
+                self.timestamps.format.as_str()
+            } else {
+                "%l:%M %p"
+            };
+            chrono::Local::now()
+                .format(format)
+                .to_string()
+                .trim()
+                .to_string()
+        };
+        self.frame_timestamp(&label)
+    }
+
+    fn frame_timestamp(&self, label: &str) -> String {
+        if self.timestamps.position == "start" {
+            format!("[{}] ", label)
+        } else {
+            format!(" [{}]", label)
+        }
+    }
+
+    /// Fixed-width age ("<1m", " 3m", "12h", " 2d") so rows don't rewrap
+    fn relative_label(minutes: i64) -> String {
+        let label = match minutes {
+            ..=0 => "<1m".to_string(),
+            1..=59 => format!("{}m", minutes),
+            60..=1439 => format!("{}h", minutes / 60),
+            _ => format!("{}d", (minutes / 1440).min(99)),
+        };
+        format!("{:>3}", label)
+    }
+
+    /// Clock minutes turned over since a line arrived, so relative labels
+    /// only change on the minute, when the damage stamp and idle tick redraw
+    fn age_minutes(created: Instant, now: Instant, now_secs: i64) -> i64 {
+        let created_secs = now_secs - now.saturating_duration_since(created).as_secs() as i64;
+        now_secs.div_euclid(60) - created_secs.div_euclid(60)
+    }
+
+    /// Style for timestamp spans: dim, and distinct from the message text
+    fn timestamp_style(&self) -> Style {
+        let color = self
+            .timestamps
+            .color
+            .as_deref()
+            .and_then(Self::parse_hex_color)
+            .unwrap_or(Color::DarkGray);
+        Style::default().fg(color).add_modifier(Modifier::DIM)
+    }
+
+    /// Copy of a row with its relative timestamp showing the line's current age
+    fn with_current_age(&self, wrapped: &WrappedLine, now: Instant, now_secs: i64) -> WrappedLine {
+        let label = self.frame_timestamp(&Self::relative_label(Self::age_minutes(
+            wrapped.created,
+            now,
+            now_secs,
+        )));
+        let mut row = wrapped.clone();
+        for (text, _, span_type, _) in &mut row.spans {
+            // A stamp split across rows keeps its arrival text
+            if *span_type == SpanType::Timestamp && text.chars().count() == label.chars().count() {
+                *text = label.clone();
+            }
+        }
+        row
     }
 
     pub fn add_text(&mut self, styled: StyledText) {
@@ -324,6 +594,9 @@ impl TextWindow {
         if styled.bold {
             style = style.add_modifier(Modifier::BOLD);
         }
+        if styled.span_type == SpanType::Url {
+            style = style.add_modifier(Modifier::UNDERLINED);
+        }
 
         // Only process links if links_enabled is true
         let link_data = if self.links_enabled {
@@ -368,43 +641,388 @@ impl TextWindow {
             return;
         }
 
+        let created = Instant::now();
+        let gap_due = match (self.gap_after, self.logical_lines.back()) {
+            (Some(gap), Some(last)) => created.saturating_duration_since(last.created) >= gap,
+            _ => false,
+        };
+        let text: String = self
+            .current_line_spans
+            .iter()
+            .map(|(t, ..)| t.as_str())
+            .collect();
+        if self.collapse_repeats
+            && !gap_due
+            && self.last_line_text.as_deref() == Some(text.as_str())
+        {
+            self.current_line_spans.clear();
+            self.bump_repeats(created);
+            if self.obscured || self.scroll_position.is_some() {
+                self.activity += 1;
+            }
+            return;
+        }
+        self.last_line_text = Some(text);
+        self.repeats = 1;
+
         // Apply highlights before storing/wrapping
         self.apply_highlights();
 
         // Add timestamp if enabled (before storing/wrapping)
-        if self.show_timestamps {
-            let timestamp = Self::format_timestamp();
-            let timestamp_style = Style::default().fg(Color::DarkGray);
-            self.current_line_spans
-                .push((timestamp, timestamp_style, SpanType::Normal, None));
+        if self.timestamps.enabled {
+            let stamp = (
+                self.format_timestamp(),
+                self.timestamp_style(),
+                SpanType::Timestamp,
+                None,
+            );
+            if self.timestamps.position == "start" {
+                self.current_line_spans.insert(0, stamp);
+            } else {
+                self.current_line_spans.push(stamp);
+            }
         }
 
-        // Store the original logical line
-        let logical_line = LogicalLine {
-            spans: self.current_line_spans.clone(),
-        };
-        self.logical_lines.push_back(logical_line);
+        if let (true, Some(last)) = (gap_due, self.logical_lines.back()) {
+            let idle = created.saturating_duration_since(last.created);
+            let style = Style::default()
+                .fg(Color::DarkGray)
+                .add_modifier(Modifier::DIM);
+            self.push_logical_line(
+                vec![(Self::gap_label(idle), style, SpanType::Normal, None)],
+                created,
+            );
+        }
+        let spans = std::mem::take(&mut self.current_line_spans);
+        self.push_logical_line(spans, created);
+        if self.obscured || self.scroll_position.is_some() {
+            self.activity += 1;
+        }
 
-        // Remove oldest logical line if we exceed buffer
-        if self.logical_lines.len() > self.max_lines {
-            self.logical_lines.pop_front();
+        // Remove oldest logical lines if we exceed buffer
+        self.evict_overflow();
+    }
+
+    /// Count another arrival of the newest line in its "(xN)" suffix and re-wrap it
+    fn bump_repeats(&mut self, created: Instant) {
+        let Some(last) = self.logical_lines.back_mut() else {
+            return;
+        };
+        if self.repeats > 1 {
+            last.spans.pop(); // The previous count
+        }
+        self.repeats += 1;
+        let style = Style::default().fg(Color::DarkGray);
+        last.spans.push((
+            format!(" (x{})", self.repeats),
+            style,
+            SpanType::Normal,
+            None,
+        ));
+        last.created = created;
+
+        let idx = self.logical_lines.len() - 1;
+        let added = self.rewrap_lines(idx..idx + 1);
+        if added > 0 {
+            self.track_unseen_rows(added as usize);
         }
+        self.refresh_search();
+    }
 
-        // Wrap this logical line and add to wrapped cache
+    /// Wrap a finished line and append it to the buffer
+    fn push_logical_line(
+        &mut self,
+        spans: Vec<(String, Style, SpanType, Option<LinkData>)>,
+        created: Instant,
+    ) {
         let actual_width = if self.last_width > 0 {
             self.last_width
         } else {
             80 // Fallback
         };
+        let wrapped = self.wrap_styled_spans(&spans, actual_width as usize, created);
 
-        let wrapped = self.wrap_styled_spans(&self.current_line_spans, actual_width as usize);
+        // Store the original logical line
+        self.logical_lines.push_back(LogicalLine {
+            spans,
+            created,
+            rows: wrapped.len(),
+        });
 
         // Add wrapped lines to the END
-        for line in wrapped {
-            self.wrapped_lines.push_back(line);
+        let rows = wrapped.len();
+        self.wrapped_lines.extend(wrapped);
+        self.track_unseen_rows(rows);
+    }
+
+    /// Drop the oldest lines beyond `max_lines`, spilling them to disk if enabled
+    fn evict_overflow(&mut self) {
+        // Paged-in history stays in front; the oldest live line behind it goes to
+        // disk and is read back in if the view scrolls down to it
+        let boundary = self.paged_rows();
+        while self.logical_lines.len() - self.paged_in > self.max_lines {
+            let Some(line) = self.logical_lines.remove(self.paged_in) else {
+                break;
+            };
+            if self.paged_in < self.stale_lines {
+                self.stale_lines -= 1;
+            }
+            self.remove_wrapped_rows(boundary, line.rows);
+            if let Some(spill) = self.spill.as_mut() {
+                match spill.push(&Self::to_spilled(&line)) {
+                    Ok(()) if self.paged_in > 0 => self.paged_gap += 1,
+                    Ok(()) => {}
+                    Err(e) => {
+                        tracing::warn!("Disk scrollback write failed, turning it off: {:#}", e);
+                        self.spill = None;
+                    }
+                }
+            }
         }
+        self.trim_paged_history();
+    }
 
-        self.current_line_spans.clear();
+    /// Wrapped rows taken up by the paged-in block
+    fn paged_rows(&self) -> usize {
+        self.logical_lines
+            .iter()
+            .take(self.paged_in)
+            .map(|line| line.rows)
+            .sum()
+    }
+
+    /// Remove wrapped lines from the front, keeping a scrolled-back view and search hits in place
+    fn drop_wrapped_rows(&mut self, rows: usize) {
+        self.remove_wrapped_rows(0, rows);
+    }
+
+    /// Remove `rows` wrapped lines at `start`, keeping a scrolled-back view and search hits in place
+    fn remove_wrapped_rows(&mut self, start: usize, rows: usize) {
+        let end = start.saturating_add(rows).min(self.wrapped_lines.len());
+        if start >= end {
+            return;
+        }
+        let rows = end - start;
+        self.wrapped_lines.drain(start..end);
+        if let Some(pos) = self.scroll_position {
+            self.scroll_position = Some(if pos >= end {
+                pos - rows
+            } else {
+                pos.min(start)
+            });
+        }
+        if let Some(state) = self.search_state.as_mut() {
+            let current = state.current_match_idx;
+            let mut removed_before = 0;
+            let mut idx = 0;
+            state.matches.retain(|m| {
+                let keep = !(start..end).contains(&m.line_idx);
+                if !keep && idx < current {
+                    removed_before += 1;
+                }
+                idx += 1;
+                keep
+            });
+            for m in &mut state.matches {
+                if m.line_idx >= end {
+                    m.line_idx -= rows;
+                }
+            }
+            state.current_match_idx = current.saturating_sub(removed_before);
+        }
+    }
+
+    /// Spilled lines `start..end` turned back into logical lines, wrapped at the current width
+    fn read_spilled(&mut self, start: usize, end: usize) -> Vec<(LogicalLine, Vec<WrappedLine>)> {
+        let Some(spill) = self.spill.as_mut().filter(|_| start < end) else {
+            return Vec::new();
+        };
+        let lines = match spill.read(start, end) {
+            Ok(lines) => lines,
+            Err(e) => {
+                tracing::warn!("Disk scrollback read failed: {:#}", e);
+                return Vec::new();
+            }
+        };
+
+        let width = if self.last_width > 0 {
+            self.last_width as usize
+        } else {
+            80
+        };
+        lines
+            .iter()
+            .map(|spilled| {
+                let mut line = Self::from_spilled(spilled);
+                let wrapped = self.wrap_styled_spans(&line.spans, width, line.created);
+                line.rows = wrapped.len();
+                (line, wrapped)
+            })
+            .collect()
+    }
+
+    /// Read the page of spilled lines just older than memory back in; returns wrapped rows added
+    fn page_in_from_disk(&mut self) -> usize {
+        let Some(len) = self
+            .spill
+            .as_ref()
+            .filter(|s| !s.is_empty())
+            .map(|s| s.len())
+        else {
+            return 0;
+        };
+        let end = len.saturating_sub(self.paged_gap + self.paged_in);
+        let lines = self.read_spilled(end.saturating_sub(DISK_PAGE_LINES), end);
+
+        let mut added = 0;
+        for (line, wrapped) in lines.into_iter().rev() {
+            added += wrapped.len();
+            for row in wrapped.into_iter().rev() {
+                self.wrapped_lines.push_front(row);
+            }
+            self.logical_lines.push_front(line);
+            self.paged_in += 1;
+        }
+
+        if let Some(state) = self.search_state.as_mut() {
+            for m in &mut state.matches {
+                m.line_idx += added;
+            }
+        }
+        added
+    }
+
+    /// Read the page of the gap just below paged-in history back in; returns wrapped rows added
+    fn page_in_gap(&mut self) -> usize {
+        let Some(len) = self.spill.as_ref().map(|s| s.len()) else {
+            return 0;
+        };
+        let start = len.saturating_sub(self.paged_gap);
+        let lines = self.read_spilled(start, (start + DISK_PAGE_LINES).min(len));
+        if lines.is_empty() {
+            // Nothing left on disk to fill it with
+            self.paged_gap = 0;
+            return 0;
+        }
+
+        let at = self.paged_rows();
+        let mut added = 0;
+        for (line, wrapped) in lines {
+            for row in wrapped {
+                self.wrapped_lines.insert(at + added, row);
+                added += 1;
+            }
+            self.logical_lines.insert(self.paged_in, line);
+            self.paged_in += 1;
+            self.paged_gap -= 1;
+        }
+
+        if let Some(pos) = self.scroll_position.filter(|&pos| pos >= at) {
+            self.scroll_position = Some(pos + added);
+        }
+        if let Some(state) = self.search_state.as_mut() {
+            for m in state.matches.iter_mut().filter(|m| m.line_idx >= at) {
+                m.line_idx += added;
+            }
+        }
+        added
+    }
+
+    /// Hand paged-in lines outside the view back to disk once there are more than a buffer's worth
+    fn trim_paged_history(&mut self) {
+        let Some(top) = self.scroll_position else {
+            return;
+        };
+        let budget = self.max_lines.max(DISK_PAGE_LINES);
+
+        // Oldest lines, scrolled off the top
+        let mut top = top;
+        while self.paged_in > budget {
+            let rows = self.logical_lines[0].rows;
+            if rows > top {
+                break;
+            }
+            self.logical_lines.pop_front();
+            self.stale_lines = self.stale_lines.saturating_sub(1);
+            self.drop_wrapped_rows(rows);
+            self.paged_in -= 1;
+            top -= rows;
+        }
+
+        // Newest lines, below the bottom of the view; they join the gap
+        let bottom = top + self.last_visible_height;
+        let mut end = self.paged_rows();
+        while self.paged_in > budget {
+            let idx = self.paged_in - 1;
+            let rows = self.logical_lines[idx].rows;
+            let start = end - rows;
+            if start < bottom {
+                break;
+            }
+            self.logical_lines.remove(idx);
+            if idx < self.stale_lines {
+                self.stale_lines -= 1;
+            }
+            self.remove_wrapped_rows(start, rows);
+            self.paged_in -= 1;
+            self.paged_gap += 1;
+            end = start;
+        }
+    }
+
+    /// Back at the live view: forget paged-in history (it's still on disk)
+    fn release_paged_history(&mut self) {
+        for _ in 0..std::mem::take(&mut self.paged_in) {
+            if let Some(line) = self.logical_lines.pop_front() {
+                self.stale_lines = self.stale_lines.saturating_sub(1);
+                self.drop_wrapped_rows(line.rows);
+            }
+        }
+        self.paged_gap = 0;
+        self.evict_overflow();
+    }
+
+    fn to_spilled(line: &LogicalLine) -> SpilledLine {
+        SpilledLine {
+            spans: line
+                .spans
+                .iter()
+                .map(|(text, style, _, _)| SpilledSpan {
+                    text: text.clone(),
+                    fg: style.fg.map(|c| c.to_string()),
+                    bg: style.bg.map(|c| c.to_string()),
+                    bold: style.add_modifier.contains(Modifier::BOLD),
+                })
+                .collect(),
+            time: chrono::Utc::now().timestamp() - line.created.elapsed().as_secs() as i64,
+        }
+    }
+
+    fn from_spilled(line: &SpilledLine) -> LogicalLine {
+        let age = (chrono::Utc::now().timestamp() - line.time).max(0) as u64;
+        LogicalLine {
+            spans: line
+                .spans
+                .iter()
+                .map(|span| {
+                    let mut style = Style::default();
+                    if let Some(fg) = span.fg.as_deref().and_then(|c| c.parse::<Color>().ok()) {
+                        style = style.fg(fg);
+                    }
+                    if let Some(bg) = span.bg.as_deref().and_then(|c| c.parse::<Color>().ok()) {
+                        style = style.bg(bg);
+                    }
+                    if span.bold {
+                        style = style.add_modifier(Modifier::BOLD);
+                    }
+                    (span.text.clone(), style, SpanType::Normal, None)
+                })
+                .collect(),
+            created: Instant::now()
+                .checked_sub(Duration::from_secs(age))
+                .unwrap_or_else(Instant::now),
+            rows: 0,
+        }
     }
 
     /// Apply highlight patterns to current line spans with proper priority layering
@@ -544,9 +1162,10 @@ impl TextWindow {
                 for char_style in char_styles.iter_mut() {
                     // For links/monsterbold: preserve original foreground, apply highlight background
                     // For normal text: apply full highlight (fg + bg + bold)
-                    if char_style.span_type == SpanType::Link
-                        || char_style.span_type == SpanType::Monsterbold
-                    {
+                    if matches!(
+                        char_style.span_type,
+                        SpanType::Link | SpanType::Url | SpanType::Monsterbold
+                    ) {
                         // Keep original foreground color for links/monsterbold
                         tracing::debug!(
                             "Preserving link/monsterbold fg color: {:?}, span_type={:?}",
@@ -659,10 +1278,22 @@ impl TextWindow {
     }
 
     // Wrap a series of styled spans into multiple display lines
+    /// Terminal cells a character takes: 2 for wide CJK and emoji, 0 for combining marks
+    fn char_width(ch: char) -> usize {
+        ch.width().unwrap_or(0)
+    }
+
+    /// Terminal cells a string takes, measured the way wrapping and
+    /// `char_to_column` measure it (for hit-testing other widgets' segments)
+    pub fn text_width(text: &str) -> usize {
+        text.chars().map(Self::char_width).sum()
+    }
+
     fn wrap_styled_spans(
         &self,
         spans: &[(String, Style, SpanType, Option<LinkData>)],
         width: usize,
+        created: Instant,
     ) -> Vec<WrappedLine> {
         if width == 0 {
             return vec![];
@@ -680,6 +1311,7 @@ impl TextWindow {
         for (text, style, span_type, link) in spans {
             for ch in text.chars() {
                 let is_whitespace = ch.is_whitespace();
+                let ch_width = Self::char_width(ch);
 
                 if is_whitespace {
                     // Flush word buffer if we have one
@@ -704,6 +1336,7 @@ impl TextWindow {
                             if !current_line_spans.is_empty() {
                                 result.push(WrappedLine {
                                     spans: current_line_spans.clone(),
+                                    created,
                                 });
                                 current_line_spans.clear();
                                 current_line_len = 0;
@@ -727,9 +1360,13 @@ impl TextWindow {
                                 word_buffer.drain(..)
                             {
                                 for word_ch in word_text.chars() {
-                                    if current_line_len >= width {
+                                    let word_ch_width = Self::char_width(word_ch);
+                                    if current_line_len > 0
+                                        && current_line_len + word_ch_width > width
+                                    {
                                         result.push(WrappedLine {
                                             spans: current_line_spans.clone(),
+                                            created,
                                         });
                                         current_line_spans.clear();
                                         current_line_len = 0;
@@ -741,7 +1378,7 @@ impl TextWindow {
                                         word_type,
                                         word_link.clone(),
                                     );
-                                    current_line_len += 1;
+                                    current_line_len += word_ch_width;
                                 }
                             }
                         }
@@ -750,10 +1387,11 @@ impl TextWindow {
                     }
 
                     // Add whitespace immediately (don't buffer it)
-                    if current_line_len >= width {
+                    if current_line_len + ch_width > width {
                         // Wrap before whitespace
                         result.push(WrappedLine {
                             spans: current_line_spans.clone(),
+                            created,
                         });
                         current_line_spans.clear();
                         current_line_len = 0;
@@ -767,7 +1405,7 @@ impl TextWindow {
                         *span_type,
                         link.clone(),
                     );
-                    current_line_len += 1;
+                    current_line_len += ch_width;
                 } else {
                     // Non-whitespace character - add to word buffer
                     in_word = true;
@@ -778,7 +1416,7 @@ impl TextWindow {
                         *span_type,
                         link.clone(),
                     );
-                    word_buffer_len += 1;
+                    word_buffer_len += ch_width;
                 }
             }
         }
@@ -801,6 +1439,7 @@ impl TextWindow {
                 if !current_line_spans.is_empty() {
                     result.push(WrappedLine {
                         spans: current_line_spans.clone(),
+                        created,
                     });
                     current_line_spans.clear();
                 }
@@ -817,9 +1456,11 @@ impl TextWindow {
                 // Word is too long - must break it
                 for (word_text, word_style, word_type, word_link) in word_buffer {
                     for word_ch in word_text.chars() {
-                        if current_line_len >= width {
+                        let word_ch_width = Self::char_width(word_ch);
+                        if current_line_len > 0 && current_line_len + word_ch_width > width {
                             result.push(WrappedLine {
                                 spans: current_line_spans.clone(),
+                                created,
                             });
                             current_line_spans.clear();
                             current_line_len = 0;
@@ -831,7 +1472,7 @@ impl TextWindow {
                             word_type,
                             word_link.clone(),
                         );
-                        current_line_len += 1;
+                        current_line_len += word_ch_width;
                     }
                 }
             }
@@ -841,12 +1482,16 @@ impl TextWindow {
         if !current_line_spans.is_empty() {
             result.push(WrappedLine {
                 spans: current_line_spans,
+                created,
             });
         }
 
         if result.is_empty() {
             // Return at least one empty line
-            result.push(WrappedLine { spans: vec![] });
+            result.push(WrappedLine {
+                spans: vec![],
+                created,
+            });
         }
 
         result
@@ -890,43 +1535,64 @@ impl TextWindow {
         }
     }
 
-    pub fn update_inner_width(&mut self, width: u16) {
-        self.last_width = width;
-        // Note: No rewrapping needed - lines are already character-wrapped at exact width
-    }
-
     pub fn scroll_up(&mut self, amount: usize) {
+        // Lines above the view must be at the current width before scrolling into them
+        self.rewrap_stale(usize::MAX);
+
         // Scrolling up = viewing older lines
         let total_lines = self.wrapped_lines.len();
 
-        if let Some(pos) = self.scroll_position {
+        let current_start = if let Some(pos) = self.scroll_position {
             // Already scrolled - move the absolute position up (to older lines)
-            self.scroll_position = Some(pos.saturating_sub(amount));
+            pos
         } else {
             // First scroll up from live view - convert to absolute position
             // We're currently viewing the last last_visible_height lines
             // The view starts at (total_lines - visible_height)
-            let current_start = total_lines.saturating_sub(self.last_visible_height);
-            // Scroll up means move the start position back
-            self.scroll_position = Some(current_start.saturating_sub(amount));
-        }
+            total_lines.saturating_sub(self.last_visible_height)
+        };
+
+        // Scrolling past the oldest line in memory pages history back in from disk
+        let current_start = if current_start < amount {
+            current_start + self.page_in_from_disk()
+        } else {
+            current_start
+        };
+
+        // Scroll up means move the start position back
+        self.scroll_position = Some(current_start.saturating_sub(amount));
+        self.trim_paged_history();
     }
 
     pub fn scroll_down(&mut self, amount: usize) {
-        // Scrolling down = viewing newer lines
-        let total_lines = self.wrapped_lines.len();
-
         if let Some(pos) = self.scroll_position {
             let new_pos = pos.saturating_add(amount);
 
+            // Lines spilled behind paged-in history come back before they scroll into view
+            while self.paged_gap > 0 && new_pos + self.last_visible_height > self.paged_rows() {
+                if self.page_in_gap() == 0 {
+                    break;
+                }
+            }
+
+            // Scrolling down = viewing newer lines
+            let total_lines = self.wrapped_lines.len();
+
             // Check if we've scrolled back to the bottom (within visible_height of end)
             let bottom_threshold = total_lines.saturating_sub(self.last_visible_height);
             if new_pos >= bottom_threshold {
-                // Return to live view mode
+                // Return to live view mode; everything on the last page has been seen
                 self.scroll_position = None;
                 self.scroll_offset = 0;
+                self.release_paged_history();
+                self.more_paused = false;
+                self.unseen_rows = 0;
+                if !self.obscured {
+                    self.activity = 0;
+                }
             } else {
                 self.scroll_position = Some(new_pos);
+                self.trim_paged_history();
             }
         } else {
             // Already in live view, just decrease offset (shouldn't normally happen)
@@ -940,13 +1606,26 @@ impl TextWindow {
         }
 
         self.last_width = width;
-        self.needs_rewrap = true; // Mark that we need to re-wrap all lines
+        self.needs_rewrap = true; // Re-wrapped at the next render
+    }
+
+    /// Set the wrap width from the window's outer width, less its left/right borders
+    pub fn set_outer_width(&mut self, width: u16) {
+        let borders = crate::config::parse_border_sides(&self.border_sides);
+        let sides = if self.show_border {
+            borders.contains(ratatui::widgets::Borders::LEFT) as u16
+                + borders.contains(ratatui::widgets::Borders::RIGHT) as u16
+        } else {
+            0
+        };
+        self.set_width(width.saturating_sub(sides));
     }
 
     /// Start a new search with the given regex pattern
     /// Returns Ok(match_count) or Err(regex_error)
     pub fn start_search(&mut self, pattern: &str) -> Result<usize, regex::Error> {
         let regex = Regex::new(pattern)?;
+        self.rewrap_stale(usize::MAX);
 
         // Search through all wrapped lines
         let mut matches = Vec::new();
@@ -992,6 +1671,48 @@ impl TextWindow {
         self.search_state = None;
     }
 
+    /// Keep matches of a pattern highlighted as text arrives (None = unpin)
+    ///
+    /// Case-insensitive like `.search` unless the pattern sets its own flags.
+    pub fn set_pin(&mut self, pattern: Option<&str>) {
+        let pattern = pattern.map(|p| {
+            if p.starts_with("(?") {
+                p.to_string()
+            } else {
+                format!("(?i){}", p)
+            }
+        });
+        if self.pin.as_ref().map(Regex::as_str) == pattern.as_deref() {
+            return;
+        }
+        self.pin = pattern.and_then(|p| match Regex::new(&p) {
+            Ok(regex) => Some(regex),
+            Err(e) => {
+                tracing::warn!("Ignoring invalid pinned search '{}': {}", p, e);
+                None
+            }
+        });
+    }
+
+    /// Matches of the pinned search in one wrapped line
+    fn pin_matches(&self, line_idx: usize, wrapped: &WrappedLine) -> Vec<SearchMatch> {
+        let Some(ref pin) = self.pin else {
+            return Vec::new();
+        };
+        let line_text: String = wrapped
+            .spans
+            .iter()
+            .map(|(text, _, _, _)| text.as_str())
+            .collect();
+        pin.find_iter(&line_text)
+            .map(|mat| SearchMatch {
+                line_idx,
+                start: mat.start(),
+                end: mat.end(),
+            })
+            .collect()
+    }
+
     /// Get the number of wrapped lines (for memory tracking)
     pub fn wrapped_line_count(&self) -> usize {
         self.wrapped_lines.len()
@@ -1071,6 +1792,7 @@ impl TextWindow {
         wrapped: &WrappedLine,
         line_matches: &[&SearchMatch],
         current_match: Option<&SearchMatch>,
+        match_style: Style,
     ) -> Vec<Span<'_>> {
         // Build the full line text to know character positions
         let _full_text: String = wrapped
@@ -1140,7 +1862,7 @@ impl TextWindow {
                             .fg(Color::Black)
                             .add_modifier(Modifier::BOLD)
                     } else {
-                        Style::default().bg(Color::DarkGray).fg(Color::White)
+                        match_style
                     };
 
                     let highlight_style = self.style_with_defaults(highlight_style);
@@ -1269,25 +1991,123 @@ impl TextWindow {
         start_line + rel_row
     }
 
-    /// Re-wrap all logical lines with the current width
-    fn rewrap_all(&mut self) {
-        self.wrapped_lines.clear();
-
+    /// Re-wrap logical lines `range` at the current width; returns the change in row count
+    fn rewrap_lines(&mut self, range: std::ops::Range<usize>) -> isize {
         let width = if self.last_width > 0 {
             self.last_width as usize
         } else {
             80
         };
+        let first_row: usize = self
+            .logical_lines
+            .range(..range.start)
+            .map(|l| l.rows)
+            .sum();
+        let old_rows: usize = self
+            .logical_lines
+            .range(range.clone())
+            .map(|l| l.rows)
+            .sum();
+
+        let mut rows = Vec::new();
+        for idx in range {
+            let logical_line = &self.logical_lines[idx];
+            let wrapped = self.wrap_styled_spans(&logical_line.spans, width, logical_line.created);
+            self.logical_lines[idx].rows = wrapped.len();
+            rows.extend(wrapped);
+        }
 
-        // Wrap each logical line
-        for logical_line in &self.logical_lines {
-            let wrapped = self.wrap_styled_spans(&logical_line.spans, width);
-            for line in wrapped {
-                self.wrapped_lines.push_back(line);
+        let new_rows = rows.len();
+        let mut tail = self
+            .wrapped_lines
+            .split_off(first_row.min(self.wrapped_lines.len()));
+        tail.drain(..old_rows.min(tail.len()));
+        self.wrapped_lines.extend(rows);
+        self.wrapped_lines.append(&mut tail);
+        new_rows as isize - old_rows as isize
+    }
+
+    /// Logical line holding wrapped row `row`, and the row's offset within it
+    fn logical_line_at(&self, row: usize) -> (usize, usize) {
+        let mut start = 0;
+        for (idx, line) in self.logical_lines.iter().enumerate() {
+            if row < start + line.rows {
+                return (idx, row - start);
             }
+            start += line.rows;
         }
+        (self.logical_lines.len(), 0)
+    }
 
+    /// Re-wrap after a width change
+    ///
+    /// Only the lines from the top of the view down are re-wrapped straight
+    /// away (in the live view, as many older lines as it takes to fill it).
+    /// Lines above that are left stale and caught up a chunk per frame, so
+    /// dragging a window edge doesn't re-wrap the whole buffer every step.
+    fn rewrap_from_view(&mut self) {
         self.needs_rewrap = false;
+        let end = self.logical_lines.len();
+        let mut first = match self.scroll_position {
+            Some(pos) => {
+                let (first, offset) = self.logical_line_at(pos);
+                self.rewrap_lines(first..end);
+                // The lines above are untouched, so the anchor line starts on the same row
+                let rows = self.logical_lines.get(first).map_or(1, |l| l.rows);
+                self.scroll_position = Some(pos - offset + offset.min(rows.saturating_sub(1)));
+                first
+            }
+            None => end,
+        };
+        if self.scroll_position.is_none() {
+            let mut rows = 0;
+            while first > 0 && rows < self.last_visible_height + self.scroll_offset {
+                first -= 1;
+                self.rewrap_lines(first..first + 1);
+                rows += self.logical_lines[first].rows;
+            }
+        }
+        self.stale_lines = first;
+        self.refresh_search();
+    }
+
+    /// Re-wrap up to `max_lines` of the newest stale lines, keeping a scrolled-back view in place
+    fn rewrap_stale(&mut self, max_lines: usize) {
+        if self.stale_lines == 0 {
+            return;
+        }
+        let start = self.stale_lines.saturating_sub(max_lines);
+        let delta = self.rewrap_lines(start..self.stale_lines);
+        self.stale_lines = start;
+        if let Some(pos) = self.scroll_position {
+            self.scroll_position = Some(pos.saturating_add_signed(delta));
+        }
+        self.refresh_search();
+    }
+
+    /// Find the active search's matches again after rows moved
+    fn refresh_search(&mut self) {
+        let Some(state) = self.search_state.as_mut() else {
+            return;
+        };
+        state.matches.clear();
+        for (line_idx, wrapped_line) in self.wrapped_lines.iter().enumerate() {
+            let line_text: String = wrapped_line
+                .spans
+                .iter()
+                .map(|(text, _, _, _)| text.as_str())
+                .collect();
+            for mat in state.regex.find_iter(&line_text) {
+                state.matches.push(SearchMatch {
+                    line_idx,
+                    start: mat.start(),
+                    end: mat.end(),
+                });
+            }
+        }
+        state.current_match_idx = state
+            .current_match_idx
+            .min(state.matches.len().saturating_sub(1));
     }
 
     /// Get the wrapped lines for text selection/extraction
@@ -1315,6 +2135,51 @@ impl TextWindow {
             .collect()
     }
 
+    /// Plain text of every line in the buffer, oldest first, including disk scrollback
+    pub fn buffer_text(&mut self) -> Vec<String> {
+        // Paged-in history is already at the front of logical_lines, with any gap after it
+        let len = self.spill.as_ref().map_or(0, |s| s.len());
+        let gap_start = len.saturating_sub(self.paged_gap);
+        let mut lines = self.spilled_text(0, gap_start.saturating_sub(self.paged_in));
+        let memory_text = |line: &LogicalLine| {
+            line.spans
+                .iter()
+                .map(|(text, _, _, _)| text.as_str())
+                .collect::<String>()
+        };
+        lines.extend(
+            self.logical_lines
+                .iter()
+                .take(self.paged_in)
+                .map(memory_text),
+        );
+        lines.extend(self.spilled_text(gap_start, len));
+        lines.extend(
+            self.logical_lines
+                .iter()
+                .skip(self.paged_in)
+                .map(memory_text),
+        );
+        lines
+    }
+
+    /// Plain text of spilled lines `start..end`
+    fn spilled_text(&mut self, start: usize, end: usize) -> Vec<String> {
+        let Some(spill) = self.spill.as_mut().filter(|_| start < end) else {
+            return Vec::new();
+        };
+        match spill.read(start, end) {
+            Ok(spilled) => spilled
+                .iter()
+                .map(|line| line.spans.iter().map(|span| span.text.as_str()).collect())
+                .collect(),
+            Err(e) => {
+                tracing::warn!("Disk scrollback read failed: {:#}", e);
+                Vec::new()
+            }
+        }
+    }
+
     /// Get the last N wrapped lines for saving to widget state
     /// Returns lines as Vec of Vec<TextSegment> (line segments)
     pub fn get_lines_for_save(&self, max: usize) -> Vec<Vec<TextSegment>> {
@@ -1384,7 +2249,7 @@ impl TextWindow {
         None
     }
 
-    /// Convert mouse position to text coordinates (line index, column index)
+    /// Convert mouse position to text coordinates (line index, character index)
     /// Returns None if position is outside content area
     pub fn mouse_to_text_coords(
         &self,
@@ -1422,7 +2287,125 @@ impl TextWindow {
             return None;
         }
 
-        Some((line_idx, col_offset))
+        Some((line_idx, self.column_to_char(line_idx, col_offset)))
+    }
+
+    /// Character index on a wrapped line under screen column `col`
+    ///
+    /// Both cells of a wide character map to it; columns past the end
+    /// count on from the last character.
+    fn column_to_char(&self, line: usize, col: usize) -> usize {
+        let text = self.line_text(line);
+        let mut cells = 0;
+        for (idx, ch) in text.chars().enumerate() {
+            let width = Self::char_width(ch);
+            if width > 0 && col < cells + width {
+                return idx;
+            }
+            cells += width;
+        }
+        text.chars().count() + col.saturating_sub(cells)
+    }
+
+    /// Screen column of character index `idx` on a wrapped line (inverse of column_to_char)
+    fn char_to_column(&self, line: usize, idx: usize) -> usize {
+        let text = self.line_text(line);
+        let count = text.chars().count();
+        let cells: usize = text.chars().take(idx).map(Self::char_width).sum();
+        cells + idx.saturating_sub(count)
+    }
+
+    /// Screen cell of a text position, if it is in view (inverse of mouse_to_text_coords)
+    pub fn text_to_screen_coords(
+        &self,
+        line: usize,
+        col: usize,
+        window_rect: ratatui::layout::Rect,
+    ) -> Option<(u16, u16)> {
+        let border_offset = if self.has_border() { 1 } else { 0 };
+        let visible_height = (window_rect.height.saturating_sub(2 * border_offset)) as usize;
+        let visible_width = (window_rect.width.saturating_sub(2 * border_offset)) as usize;
+        let start_line = self.relative_row_to_absolute_line(0, visible_height);
+
+        let row = line
+            .checked_sub(start_line)
+            .filter(|row| *row < visible_height)?;
+        let col = self.char_to_column(line, col);
+        if col >= visible_width {
+            return None;
+        }
+        Some((
+            window_rect.x + border_offset + col as u16,
+            window_rect.y + border_offset + row as u16,
+        ))
+    }
+
+    /// Number of characters on a wrapped line (0 past the end)
+    pub fn line_char_count(&self, line: usize) -> usize {
+        self.wrapped_lines.get(line).map_or(0, |wrapped| {
+            wrapped
+                .spans
+                .iter()
+                .map(|(text, _, _, _)| text.chars().count())
+                .sum()
+        })
+    }
+
+    /// Plain text of a wrapped line
+    pub fn line_text(&self, line: usize) -> String {
+        self.wrapped_lines
+            .get(line)
+            .map_or_else(String::new, |wrapped| {
+                wrapped
+                    .spans
+                    .iter()
+                    .map(|(text, _, _, _)| text.as_str())
+                    .collect()
+            })
+    }
+
+    /// First and last wrapped line of the logical line that `line` belongs to
+    pub fn logical_line_rows(&self, line: usize) -> (usize, usize) {
+        let mut first = 0;
+        for logical in &self.logical_lines {
+            let last = first + logical.rows.max(1) - 1;
+            if line <= last {
+                return (first, last.min(self.wrapped_lines.len().saturating_sub(1)));
+            }
+            first = last + 1;
+        }
+        (line, line)
+    }
+
+    /// Rows of text shown at the last render
+    pub fn visible_height(&self) -> usize {
+        self.last_visible_height
+    }
+
+    /// Stop following live output, keeping the current view on screen
+    pub fn hold_view(&mut self) {
+        if self.scroll_position.is_none() {
+            self.scroll_position =
+                Some(self.relative_row_to_absolute_line(0, self.last_visible_height));
+        }
+    }
+
+    /// Scroll just far enough to bring a line into view
+    pub fn scroll_to_line(&mut self, line: usize) {
+        let visible_height = self.last_visible_height.max(1);
+        let start_line = self.relative_row_to_absolute_line(0, visible_height);
+        if line < start_line {
+            self.scroll_position = Some(line);
+        } else if line >= start_line + visible_height {
+            self.scroll_position = Some(line + 1 - visible_height);
+        }
+    }
+
+    /// Return to live view at the bottom
+    pub fn scroll_to_live(&mut self) {
+        if self.scroll_position.is_some() {
+            self.scroll_down(usize::MAX);
+        }
     }
 
     /// Extract text from a selection range
@@ -1486,6 +2469,66 @@ impl TextWindow {
         result
     }
 
+    /// Selected text in styled pieces, one row per wrapped line (for copying with colors)
+    pub fn selected_spans(
+        &self,
+        selection: &crate::selection::SelectionState,
+    ) -> Vec<super::rich_copy::StyledRow> {
+        let (top, bottom) = if selection.block {
+            selection.block_lines()
+        } else {
+            let (start, end) = selection.normalized_range();
+            (start.line, end.line)
+        };
+        let window_index = selection.start.window_index;
+
+        (top..=bottom)
+            .filter_map(|line_idx| Some((line_idx, self.wrapped_lines.get(line_idx)?)))
+            .map(|(line_idx, wrapped)| {
+                let mut row = Vec::new();
+                let mut col = 0;
+                for (text, style, _span_type, _link) in &wrapped.spans {
+                    let piece: String = text
+                        .chars()
+                        .enumerate()
+                        .filter(|(i, _)| selection.contains(window_index, line_idx, col + i))
+                        .map(|(_, c)| c)
+                        .collect();
+                    col += text.chars().count();
+                    if !piece.is_empty() {
+                        row.push((piece, self.style_with_defaults(*style)));
+                    }
+                }
+                row
+            })
+            .collect()
+    }
+
+    /// Extract a block (rectangular) selection from the wrapped display lines
+    pub fn extract_block_text(
+        &self,
+        start_line: usize,
+        end_line: usize,
+        left: usize,
+        right: usize,
+    ) -> String {
+        let rows: Vec<String> = self
+            .wrapped_lines
+            .range(
+                start_line.min(self.wrapped_lines.len())
+                    ..(end_line + 1).min(self.wrapped_lines.len()),
+            )
+            .map(|wrapped| {
+                wrapped
+                    .spans
+                    .iter()
+                    .map(|(text, _, _, _)| text.as_str())
+                    .collect()
+            })
+            .collect();
+        crate::selection::extract_block(rows.iter().map(String::as_str), left, right)
+    }
+
     /// Get visible line information for click detection
     /// Returns (start_line_index, visible_lines)
     pub fn get_visible_lines_info(&self, visible_height: usize) -> (usize, Vec<LineSegments>) {
@@ -1577,13 +2620,14 @@ impl TextWindow {
 
         // Update width for wrapping - only subtract for borders if they're shown
         let border_padding = if self.show_border { 2 } else { 0 };
-        let inner_width = area.width.saturating_sub(border_padding);
-        self.set_width(inner_width);
+        self.set_outer_width(area.width);
+        self.last_visible_height = area.height.saturating_sub(border_padding) as usize;
 
-        // Re-wrap all lines if width changed
+        // Re-wrap for a new width, then catch up on lines above the view
         if self.needs_rewrap {
-            self.rewrap_all();
+            self.rewrap_from_view();
         }
+        self.rewrap_stale(REWRAP_CHUNK_LINES);
 
         // Build visible lines for display
         // Buffer storage: wrapped_lines[0] = oldest, wrapped_lines[end] = newest
@@ -1604,11 +2648,21 @@ impl TextWindow {
         } else {
             self.title.clone()
         };
+        let mut title = Line::from(title);
+        if self.activity > 0 {
+            // Unseen lines: "● 12" after the title until they're scrolled to or uncovered
+            title.push_span(Span::styled(
+                format!(" \u{25cf} {}", self.activity),
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            ));
+        }
 
         // Configure block (border style/color) before rendering any content so empty windows update
         let mut block = if self.show_border {
             let borders = crate::config::parse_border_sides(&self.border_sides);
-            Block::default().title(title.as_str()).borders(borders)
+            Block::default().title(title).borders(borders)
         } else {
             Block::default()
         };
@@ -1668,9 +2722,23 @@ impl TextWindow {
         let selection_bg = Self::parse_hex_color(selection_bg_color);
 
         // Collect lines from buffer (oldest to newest order)
+        let now = Instant::now();
+        let now_secs = chrono::Utc::now().timestamp();
         let mut display_lines: Vec<Line> = Vec::new();
         for idx in start_line..end_line {
             if let Some(wrapped) = self.wrapped_lines.get(idx) {
+                let aged;
+                let wrapped = if self.timestamps.relative
+                    && wrapped
+                        .spans
+                        .iter()
+                        .any(|span| span.2 == SpanType::Timestamp)
+                {
+                    aged = self.with_current_age(wrapped, now, now_secs);
+                    &aged
+                } else {
+                    wrapped
+                };
                 // Check if this line has search matches
                 let line_matches: Vec<&SearchMatch> = self
                     .search_state
@@ -1683,7 +2751,30 @@ impl TextWindow {
                     .as_ref()
                     .and_then(|state| state.matches.get(state.current_match_idx));
 
-                let spans: Vec<Span> = if line_matches.is_empty() {
+                // Pinned matches show wherever the active search doesn't
+                let pin_matches = if line_matches.is_empty() {
+                    self.pin_matches(idx, wrapped)
+                } else {
+                    Vec::new()
+                };
+
+                let mut spans: Vec<Span> = if !line_matches.is_empty() {
+                    // Has matches - need to highlight them
+                    self.create_highlighted_spans(
+                        wrapped,
+                        &line_matches,
+                        current_match,
+                        Style::default().bg(Color::DarkGray).fg(Color::White),
+                    )
+                } else if !pin_matches.is_empty() {
+                    let pin_matches: Vec<&SearchMatch> = pin_matches.iter().collect();
+                    self.create_highlighted_spans(
+                        wrapped,
+                        &pin_matches,
+                        None,
+                        Style::default().bg(Color::Magenta).fg(Color::White),
+                    )
+                } else {
                     // No search matches - check for selection
                     self.create_spans_with_selection(
                         wrapped,
@@ -1692,11 +2783,14 @@ impl TextWindow {
                         selection_bg,
                         window_index,
                     )
-                } else {
-                    // Has matches - need to highlight them
-                    self.create_highlighted_spans(wrapped, &line_matches, current_match)
                 };
 
+                if let Some(factor) = self.fade_factor(wrapped.created, now) {
+                    for span in &mut spans {
+                        span.style = Self::fade_style(span.style, factor);
+                    }
+                }
+
                 display_lines.push(Line::from(spans));
             }
         }
@@ -1740,6 +2834,12 @@ impl TextWindow {
             padded_lines.splice(0..0, empty_lines);
         }
 
+        let inner_area = if self.show_border {
+            block.inner(area)
+        } else {
+            area
+        };
+
         // Attach block to paragraph (like VellumFE) so ratatui handles borders correctly
         // This prevents span backgrounds from bleeding into border cells during scrolling
         let paragraph = if self.show_border {
@@ -1748,6 +2848,45 @@ impl TextWindow {
             Paragraph::new(padded_lines)
         };
         paragraph.render(area, buf);
+
+        if self.is_more_paused() && inner_area.height > 0 {
+            let waiting = total_lines.saturating_sub(start_line + visible_height);
+            let prompt = format!(" -- more ({}) -- ", waiting);
+            let width = (prompt.chars().count() as u16).min(inner_area.width);
+            buf.set_stringn(
+                inner_area.right() - width,
+                inner_area.bottom() - 1,
+                &prompt,
+                width as usize,
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            );
+        }
+    }
+
+    /// Brightness factor for a line of the given age, or None if it is still fresh
+    fn fade_factor(&self, created: Instant, now: Instant) -> Option<f32> {
+        let period = self.fade_after?;
+        let bands =
+            (now.saturating_duration_since(created).as_secs() / period.as_secs().max(1)) as usize;
+        if bands == 0 {
+            return None;
+        }
+        Some(FADE_BAND_FACTORS[bands.min(FADE_BAND_FACTORS.len()) - 1])
+    }
+
+    /// Darken the foreground; palette colors can't be scaled, so they fall back to DIM
+    fn fade_style(style: Style, factor: f32) -> Style {
+        match style.fg {
+            Some(Color::Rgb(r, g, b)) => style.fg(Color::Rgb(
+                (r as f32 * factor) as u8,
+                (g as f32 * factor) as u8,
+                (b as f32 * factor) as u8,
+            )),
+            _ => style.add_modifier(Modifier::DIM),
+        }
     }
 
     fn fallback_text_color(&self) -> Color {
@@ -1783,8 +2922,18 @@ impl TextWindow {
     pub fn clear(&mut self) {
         self.logical_lines.clear();
         self.current_line_spans.clear();
+        self.last_line_text = None;
+        self.repeats = 0;
         self.scroll_offset = 0;
         self.wrapped_lines.clear();
+        self.paged_in = 0;
+        self.paged_gap = 0;
+        if let Some(spill) = self.spill.as_mut() {
+            if let Err(e) = spill.clear() {
+                tracing::warn!("Disk scrollback reset failed, turning it off: {:#}", e);
+                self.spill = None;
+            }
+        }
     }
 }
 
@@ -1796,3 +2945,423 @@ impl Widget for &mut TextWindow {
         self.render_with_focus(area, buf, false, None, "#4a4a4a", 0, &theme);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pinned_search_marks_new_lines() {
+        let mut window = TextWindow::new("main", 100);
+        let add_line = |window: &mut TextWindow, text: &str| {
+            window.add_text(StyledText {
+                content: text.to_string(),
+                fg: None,
+                bg: None,
+                bold: false,
+                span_type: SpanType::Normal,
+                link_data: None,
+            });
+            window.finish_line(40);
+        };
+        add_line(&mut window, "You search the troll.");
+        window.set_pin(Some("silver"));
+        add_line(&mut window, "You find 42 SILVER coins.");
+
+        let area = Rect::new(0, 0, 40, 6);
+        let mut buf = Buffer::empty(area);
+        let theme = crate::theme::AppTheme::default();
+        window.render_with_focus(area, &mut buf, false, None, "#404040", 0, &theme);
+        let pinned: String = (0..area.width)
+            .flat_map(|x| (0..area.height).map(move |y| (x, y)))
+            .filter(|&pos| buf[pos].bg == Color::Magenta)
+            .map(|pos| buf[pos].symbol().to_string())
+            .collect();
+        assert_eq!(pinned.len(), "SILVER".len());
+
+        window.set_pin(None);
+        let mut buf = Buffer::empty(area);
+        window.render_with_focus(area, &mut buf, false, None, "#404040", 0, &theme);
+        assert!(!buf.content.iter().any(|cell| cell.bg == Color::Magenta));
+    }
+
+    #[test]
+    fn test_timestamp_position_and_age() {
+        let mut window = TextWindow::new("main", 100);
+        window.set_timestamps(&crate::config::TimestampConfig {
+            enabled: true,
+            format: "%Y".to_string(),
+            position: "start".to_string(),
+            ..Default::default()
+        });
+        window.add_text(StyledText {
+            content: "You swing.".to_string(),
+            fg: None,
+            bg: None,
+            bold: false,
+            span_type: SpanType::Normal,
+            link_data: None,
+        });
+        window.finish_line(40);
+        let area = Rect::new(0, 0, 40, 3);
+        let mut buf = Buffer::empty(area);
+        let theme = crate::theme::AppTheme::default();
+        window.render_with_focus(area, &mut buf, false, None, "#404040", 0, &theme);
+        let screen: String = buf.content.iter().map(|c| c.symbol()).collect();
+        let year = chrono::Local::now().format("[%Y] You swing.").to_string();
+        assert!(screen.contains(&year), "{:?}", screen);
+
+        assert_eq!(TextWindow::relative_label(0), "<1m");
+        assert_eq!(TextWindow::relative_label(3), " 3m");
+        assert_eq!(TextWindow::relative_label(400 * 1440), "99d");
+    }
+
+    #[test]
+    fn test_relative_age_turns_on_the_minute() {
+        let now = Instant::now();
+        let Some(created) = now.checked_sub(Duration::from_secs(30)) else {
+            return;
+        };
+        // Arrived 30s earlier: the same clock minute at 1:59, one minute at 2:00
+        assert_eq!(TextWindow::age_minutes(created, now, 119), 0);
+        assert_eq!(TextWindow::age_minutes(created, now, 120), 1);
+    }
+
+    #[test]
+    fn test_resize_rewraps_and_keeps_view() {
+        let mut window = TextWindow::new("main", 100);
+        for i in 0..30 {
+            window.add_text(StyledText {
+                content: format!("Line {:02} of the troll fight goes on and on", i),
+                fg: None,
+                bg: None,
+                bold: false,
+                span_type: SpanType::Normal,
+                link_data: None,
+            });
+            window.finish_line(0);
+        }
+        let theme = crate::theme::AppTheme::default();
+        let render = |window: &mut TextWindow, width: u16| {
+            let area = Rect::new(0, 0, width, 7);
+            let mut buf = Buffer::empty(area);
+            window.render_with_focus(area, &mut buf, false, None, "#404040", 0, &theme);
+        };
+        render(&mut window, 62);
+        assert_eq!(window.wrapped_line_count(), 30);
+
+        // Scrolled back to line 10, then the window narrows
+        window.scroll_position = Some(10);
+        render(&mut window, 22);
+        assert_eq!(window.stale_lines, 0);
+        assert!(window.wrapped_lines.iter().all(|row| row
+            .spans
+            .iter()
+            .map(|s| s.0.chars().count())
+            .sum::<usize>()
+            <= 20));
+        assert_eq!(
+            window.wrapped_line_count(),
+            window.logical_lines.iter().map(|l| l.rows).sum::<usize>()
+        );
+        let top = window.scroll_position.unwrap();
+        assert_eq!(window.logical_line_at(top), (10, 0));
+        assert!(window.line_text(top).starts_with("Line 10"));
+
+        // The live view re-wraps its own rows first and the rest a chunk per frame
+        window.scroll_to_live();
+        window.set_outer_width(62);
+        window.rewrap_from_view();
+        assert!(window.stale_lines > 0 && window.stale_lines < 30);
+        window.rewrap_stale(usize::MAX);
+        assert_eq!(window.wrapped_line_count(), 30);
+    }
+
+    #[test]
+    fn test_wide_characters_wrap_and_map_to_cells() {
+        let mut window = TextWindow::new("main", 100);
+        window.set_width(10);
+        window.add_text(StyledText {
+            content: "Ann says, \"你好世界朋友\" 🎉e\u{301}".to_string(),
+            fg: None,
+            bg: None,
+            bold: false,
+            span_type: SpanType::Normal,
+            link_data: None,
+        });
+        window.finish_line(10);
+        let rows: Vec<String> = (0..window.wrapped_line_count())
+            .map(|i| window.line_text(i))
+            .collect();
+        assert_eq!(rows, vec!["Ann says, ", "\"你好世界", "朋友\" 🎉e\u{301}"]);
+
+        // Both cells of a wide character select it, and the cursor lands on its first cell
+        assert_eq!(window.column_to_char(1, 0), 0);
+        assert_eq!(window.column_to_char(1, 3), 2);
+        assert_eq!(window.column_to_char(1, 4), 2);
+        assert_eq!(window.char_to_column(1, 3), 5);
+        assert_eq!(window.column_to_char(2, 7), 4);
+        // The combining accent rides on its base letter
+        assert_eq!(window.column_to_char(2, 8), 5);
+        assert_eq!(window.column_to_char(2, 10), 8);
+        let area = Rect::new(0, 0, 12, 5);
+        assert_eq!(window.mouse_to_text_coords(5, 2, area), Some((1, 2)));
+        assert_eq!(window.text_to_screen_coords(1, 2, area), Some((4, 2)));
+    }
+
+    #[test]
+    fn test_idle_gap_separator() {
+        let mut window = TextWindow::new("main", 100);
+        window.set_gap_minutes(10);
+        let add_line = |window: &mut TextWindow, text: &str| {
+            window.add_text(StyledText {
+                content: text.to_string(),
+                fg: None,
+                bg: None,
+                bold: false,
+                span_type: SpanType::Normal,
+                link_data: None,
+            });
+            window.finish_line(80);
+        };
+        add_line(&mut window, "You bow.");
+        add_line(&mut window, "You smile.");
+        assert_eq!(window.logical_lines.len(), 2);
+
+        // Pretend the last line arrived twelve minutes ago
+        let Some(earlier) = Instant::now().checked_sub(Duration::from_secs(12 * 60)) else {
+            return;
+        };
+        window.logical_lines.back_mut().unwrap().created = earlier;
+        add_line(&mut window, "Ann waves.");
+        assert_eq!(window.logical_lines.len(), 4);
+        let separator = &window.logical_lines[2].spans[0].0;
+        assert!(separator.ends_with(", 12 min idle ──"), "{}", separator);
+        assert_eq!(
+            TextWindow::gap_label(Duration::from_secs(125 * 60))
+                .split(", ")
+                .nth(1),
+            Some("2h 5m idle ──")
+        );
+    }
+
+    #[test]
+    fn test_collapse_repeats() {
+        let mut window = TextWindow::new("main", 100);
+        let add_line = |window: &mut TextWindow, text: &str| {
+            window.add_text(StyledText {
+                content: text.to_string(),
+                fg: None,
+                bg: None,
+                bold: false,
+                span_type: SpanType::Normal,
+                link_data: None,
+            });
+            window.finish_line(80);
+        };
+        add_line(&mut window, "A gust of wind blows.");
+        add_line(&mut window, "A gust of wind blows.");
+        assert_eq!(window.logical_lines.len(), 2);
+
+        window.set_collapse_repeats(true);
+        add_line(&mut window, "A gust of wind blows.");
+        add_line(&mut window, "A gust of wind blows.");
+        assert_eq!(window.logical_lines.len(), 2);
+        assert_eq!(window.line_text(1), "A gust of wind blows. (x3)");
+        assert_eq!(window.wrapped_lines.len(), 2);
+
+        add_line(&mut window, "Ann waves.");
+        add_line(&mut window, "A gust of wind blows.");
+        assert_eq!(window.logical_lines.len(), 4);
+        assert_eq!(window.line_text(3), "A gust of wind blows.");
+    }
+
+    fn add_numbered_lines(window: &mut TextWindow, range: std::ops::Range<usize>) {
+        for i in range {
+            window.add_text(StyledText {
+                content: format!("L{}", i),
+                fg: None,
+                bg: None,
+                bold: false,
+                span_type: SpanType::Normal,
+                link_data: None,
+            });
+            window.finish_line(80);
+        }
+    }
+
+    #[test]
+    fn test_more_prompt_never_pauses_live_view() {
+        let mut window = TextWindow::new("main", 100);
+        window.last_visible_height = 5;
+        window.set_more_prompt(true);
+        add_numbered_lines(&mut window, 0..30);
+        assert!(!window.is_more_paused());
+        assert_eq!(window.scroll_position, None);
+    }
+
+    #[test]
+    fn test_more_prompt_pages_output_that_arrives_while_scrolled_back() {
+        let mut window = TextWindow::new("main", 100);
+        window.last_visible_height = 5;
+        window.set_more_prompt(true);
+        add_numbered_lines(&mut window, 0..20);
+        window.scroll_up(3);
+        assert_eq!(window.scroll_position, Some(12));
+
+        // A page is four new rows (one row of context is kept); the fifth holds
+        add_numbered_lines(&mut window, 20..24);
+        assert!(!window.is_more_paused());
+        add_numbered_lines(&mut window, 24..25);
+        assert!(window.is_more_paused());
+        assert_eq!(window.scroll_position, Some(12));
+
+        window.acknowledge_more();
+        assert_eq!(window.scroll_position, Some(16));
+        assert!(window.is_more_paused());
+        window.acknowledge_more();
+        assert!(!window.is_more_paused());
+        assert_eq!(window.scroll_position, None);
+    }
+
+    #[test]
+    fn test_more_prompt_off_never_pauses() {
+        let mut window = TextWindow::new("main", 100);
+        window.last_visible_height = 5;
+        add_numbered_lines(&mut window, 0..20);
+        window.scroll_up(3);
+        add_numbered_lines(&mut window, 20..40);
+        assert!(!window.is_more_paused());
+    }
+
+    #[test]
+    fn test_activity_counts_unseen_lines() {
+        let mut window = TextWindow::new("thoughts", 100);
+        window.last_visible_height = 3;
+        let add_lines = |window: &mut TextWindow, count: usize| {
+            for _ in 0..count {
+                window.add_text(StyledText {
+                    content: "You think.".to_string(),
+                    fg: None,
+                    bg: None,
+                    bold: false,
+                    span_type: SpanType::Normal,
+                    link_data: None,
+                });
+                window.finish_line(80);
+            }
+        };
+        add_lines(&mut window, 10);
+        assert_eq!(window.activity(), 0);
+
+        window.scroll_up(4);
+        add_lines(&mut window, 2);
+        assert_eq!(window.activity(), 2);
+        window.scroll_to_live();
+        assert_eq!(window.activity(), 0);
+
+        window.set_obscured(true);
+        add_lines(&mut window, 3);
+        window.set_obscured(true);
+        assert_eq!(window.activity(), 3);
+        window.set_obscured(false);
+        assert_eq!(window.activity(), 0);
+    }
+
+    #[test]
+    fn test_buffer_text_includes_disk_scrollback() {
+        let dir = std::env::temp_dir().join(format!(
+            "two-face-dump-scrollback-test-{}",
+            std::process::id()
+        ));
+        let mut window = TextWindow::new("main", 3);
+        window.set_disk_scrollback(&dir, "main", 100);
+        for i in 0..5 {
+            for part in [format!("Line {} ", i), "end".to_string()] {
+                window.add_text(StyledText {
+                    content: part,
+                    fg: None,
+                    bg: None,
+                    bold: false,
+                    span_type: SpanType::Normal,
+                    link_data: None,
+                });
+            }
+            window.finish_line(80);
+        }
+        assert_eq!(window.logical_lines.len(), 3);
+        let expected: Vec<String> = (0..5).map(|i| format!("Line {} end", i)).collect();
+        assert_eq!(window.buffer_text(), expected);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    fn add_numbered(window: &mut TextWindow, range: std::ops::Range<usize>) {
+        for i in range {
+            window.add_text(StyledText {
+                content: format!("L{}", i),
+                fg: None,
+                bg: None,
+                bold: false,
+                span_type: SpanType::Normal,
+                link_data: None,
+            });
+            window.finish_line(80);
+        }
+    }
+
+    fn row_text(window: &TextWindow, row: usize) -> String {
+        window.wrapped_lines[row]
+            .spans
+            .iter()
+            .map(|(text, _, _, _)| text.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_paged_history_stays_bounded_while_scrolled_back() {
+        let dir = std::env::temp_dir().join(format!("two-face-paged-test-{}", std::process::id()));
+        let mut window = TextWindow::new("main", 10);
+        window.last_visible_height = 5;
+        window.set_disk_scrollback(&dir, "main", 10_000);
+        add_numbered(&mut window, 0..1000);
+
+        window.scroll_up(20);
+        let top = window.scroll_position.unwrap();
+        assert_eq!(row_text(&window, top), "L975");
+
+        // Live output keeps arriving while reading paged-in history
+        add_numbered(&mut window, 1000..3000);
+        assert!(window.logical_lines.len() <= 10 + 2 * DISK_PAGE_LINES);
+        assert_eq!(row_text(&window, window.scroll_position.unwrap()), "L975");
+        let expected: Vec<String> = (0..3000).map(|i| format!("L{}", i)).collect();
+        assert_eq!(window.buffer_text(), expected);
+
+        // Scrolling down reads what was spilled behind the view back in, in order
+        let mut n = 975;
+        while let Some(pos) = window.scroll_position {
+            assert_eq!(row_text(&window, pos), format!("L{}", n));
+            assert!(window.logical_lines.len() <= 10 + 2 * DISK_PAGE_LINES);
+            window.scroll_down(5);
+            n += 5;
+        }
+        assert_eq!(n, 2995);
+        assert_eq!(window.logical_lines.len(), 10);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_clear_empties_disk_scrollback() {
+        let dir = std::env::temp_dir().join(format!("two-face-clear-test-{}", std::process::id()));
+        let mut window = TextWindow::new("main", 10);
+        window.last_visible_height = 5;
+        window.set_disk_scrollback(&dir, "main", 1000);
+        add_numbered(&mut window, 0..100);
+
+        window.clear();
+        assert!(window.buffer_text().is_empty());
+        add_numbered(&mut window, 0..3);
+        window.scroll_up(10);
+        assert_eq!(window.logical_lines.len(), 3);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,7 +1,8 @@
 //! Popup browser that lists every built-in/custom theme.
 //!
 //! Provides deletion for custom entries plus navigation hints that mirror other
-//! list dialogs.
+//! list dialogs. The highlighted theme is previewed on the running UI once the
+//! selection settles; the caller restores the active theme on cancel.
 
 use crate::theme::{AppTheme, ThemePresets};
 use ratatui::{
@@ -11,6 +12,10 @@ use ratatui::{
     text::{Line, Span},
     widgets::{Block, BorderType, Borders, Clear, Paragraph, Widget},
 };
+use std::time::{Duration, Instant};
+
+/// How long the selection must rest before it's previewed
+const PREVIEW_DELAY: Duration = Duration::from_millis(150);
 
 /// Browser for viewing and selecting application themes
 pub struct ThemeBrowser {
@@ -19,6 +24,8 @@ pub struct ThemeBrowser {
     selected_index: usize,
     scroll_offset: usize,
     active_theme_id: String, // Currently active theme to highlight
+    preview_due: Option<Instant>, // Selection changed; preview once this passes
+    previewed_id: String,         // Theme currently shown on the UI
 
     // Popup position (for dragging)
     pub popup_x: u16,
@@ -56,7 +63,9 @@ impl ThemeBrowser {
             custom_theme_ids,
             selected_index,
             scroll_offset: 0,
+            previewed_id: active_theme_id.clone(),
             active_theme_id,
+            preview_due: None,
             popup_x: 0,
             popup_y: 0,
             is_dragging: false,
@@ -100,6 +109,9 @@ impl ThemeBrowser {
     fn adjust_scroll(&mut self) {
         const VISIBLE_ITEMS: usize = 20;
 
+        // Every selection change goes through here; restart the debounce
+        self.preview_due = Some(Instant::now() + PREVIEW_DELAY);
+
         if self.selected_index < self.scroll_offset {
             self.scroll_offset = self.selected_index;
         } else if self.selected_index >= self.scroll_offset + VISIBLE_ITEMS {
@@ -107,6 +119,28 @@ impl ThemeBrowser {
         }
     }
 
+    /// Theme to preview, once the selection has rested for `PREVIEW_DELAY`
+    ///
+    /// Returns each settled selection once, so holding an arrow key through
+    /// the list doesn't re-theme the UI on every step.
+    pub fn take_due_preview(&mut self, now: Instant) -> Option<(String, AppTheme)> {
+        if self.preview_due.is_none_or(|due| now < due) {
+            return None;
+        }
+        self.preview_due = None;
+        let (id, theme) = self.themes.get(self.selected_index)?;
+        if *id == self.previewed_id {
+            return None;
+        }
+        self.previewed_id = id.clone();
+        Some((id.clone(), theme.clone()))
+    }
+
+    /// True if the UI is showing a theme other than the active one
+    pub fn is_previewing(&self) -> bool {
+        self.previewed_id != self.active_theme_id
+    }
+
     pub fn get_selected_theme_id(&self) -> Option<String> {
         self.themes
             .get(self.selected_index)
@@ -312,3 +346,28 @@ impl Widget for &ThemeBrowser {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preview_is_debounced() {
+        let dir = std::env::temp_dir().join("two-face-theme-browser-test");
+        let mut browser = ThemeBrowser::new("dark".to_string(), dir.to_str());
+        assert!(browser.take_due_preview(Instant::now()).is_none());
+
+        browser.next();
+        browser.next();
+        let start = Instant::now();
+        assert!(browser.take_due_preview(start).is_none());
+
+        let (id, _) = browser.take_due_preview(start + PREVIEW_DELAY * 2).unwrap();
+        assert_eq!(Some(id), browser.get_selected_theme_id());
+        assert!(browser.is_previewing());
+        // Each settled selection is previewed once
+        assert!(browser
+            .take_due_preview(start + PREVIEW_DELAY * 4)
+            .is_none());
+    }
+}
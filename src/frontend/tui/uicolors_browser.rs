@@ -464,6 +464,13 @@ impl UIColorsBrowser {
             bg_color: None,
             entry_type: UIColorEntryType::UIColor,
         });
+        entries.push(UIColorEntry {
+            name: "Scripted Echo".to_string(),
+            category: "UI".to_string(),
+            fg_color: Some(colors.ui.scripted_echo_color.clone()),
+            bg_color: None,
+            entry_type: UIColorEntryType::UIColor,
+        });
         entries.push(UIColorEntry {
             name: "Text".to_string(),
             category: "UI".to_string(),
@@ -0,0 +1,273 @@
+//! Vitals cluster: several progress bars stacked inside one border.
+//!
+//! Label and value columns are sized from the widest entry so the bars line
+//! up regardless of which vital has the longest name or number.
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    widgets::{Block, BorderType, Borders, Clear, Widget},
+};
+
+/// One row of the cluster
+#[derive(Debug, Clone)]
+pub struct VitalsRow {
+    pub label: String,
+    pub current: u32,
+    pub max: u32,
+    /// Game-provided text ("clear as a bell", "offensive"); shown instead of
+    /// current/max when it carries no numbers of its own
+    pub text: String,
+    pub fill: Option<Color>,
+}
+
+impl VitalsRow {
+    /// Text for the value column
+    pub fn value_text(&self) -> String {
+        let text = self.text.trim();
+        if !text.is_empty() && !text.chars().any(|c| c.is_ascii_digit()) {
+            text.to_string()
+        } else {
+            format!("{}/{}", self.current, self.max)
+        }
+    }
+}
+
+/// Column layout shared by every row: (label width, bar width, value width)
+pub fn column_widths(rows: &[VitalsRow], available: u16) -> (u16, u16, u16) {
+    let label_width = rows
+        .iter()
+        .map(|r| r.label.chars().count())
+        .max()
+        .unwrap_or(0) as u16;
+    let value_width = rows
+        .iter()
+        .map(|r| r.value_text().chars().count())
+        .max()
+        .unwrap_or(0) as u16;
+
+    // One space between label/bar and bar/value
+    let gaps = u16::from(label_width > 0) + u16::from(value_width > 0);
+    let bar_width = available.saturating_sub(label_width + value_width + gaps);
+    if bar_width == 0 {
+        // Too narrow for a bar: drop it and keep the numbers readable
+        let label_width = label_width.min(available);
+        let value_width = value_width.min(available.saturating_sub(label_width + 1));
+        return (label_width, 0, value_width);
+    }
+    (label_width, bar_width, value_width)
+}
+
+pub struct VitalsCluster {
+    title: String,
+    rows: Vec<VitalsRow>,
+    show_border: bool,
+    border_style: Option<String>,
+    border_color: Option<Color>,
+    bar_background: Option<Color>,
+    window_background: Option<Color>,
+    transparent_background: bool,
+    text_color: Option<Color>,
+}
+
+impl VitalsCluster {
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            rows: Vec::new(),
+            show_border: true,
+            border_style: None,
+            border_color: None,
+            bar_background: Some(Color::Rgb(40, 40, 40)),
+            window_background: None,
+            transparent_background: true,
+            text_color: Some(Color::White),
+        }
+    }
+
+    pub fn set_title(&mut self, title: String) {
+        self.title = title;
+    }
+
+    pub fn set_rows(&mut self, rows: Vec<VitalsRow>) {
+        self.rows = rows;
+    }
+
+    pub fn set_border_config(
+        &mut self,
+        show_border: bool,
+        border_style: Option<String>,
+        border_color: Option<String>,
+    ) {
+        self.show_border = show_border;
+        self.border_style = border_style;
+        self.border_color = border_color.and_then(|c| Self::parse_color(&c));
+    }
+
+    pub fn set_text_color(&mut self, color: Option<String>) {
+        self.text_color = color.and_then(|c| Self::parse_color(&c));
+    }
+
+    pub fn set_background_color(&mut self, color: Option<String>) {
+        self.window_background = color.and_then(|c| Self::parse_color(&c));
+    }
+
+    pub fn set_transparent_background(&mut self, transparent: bool) {
+        self.transparent_background = transparent;
+    }
+
+    pub fn parse_color(hex: &str) -> Option<Color> {
+        let hex = hex.trim_start_matches('#');
+        if hex.len() != 6 {
+            return None;
+        }
+
+        let r = u8::from_str_radix(&hex[0..2], 16).ok()?;
+        let g = u8::from_str_radix(&hex[2..4], 16).ok()?;
+        let b = u8::from_str_radix(&hex[4..6], 16).ok()?;
+
+        Some(Color::Rgb(r, g, b))
+    }
+
+    pub fn render(&self, area: Rect, buf: &mut Buffer) {
+        if area.width == 0 || area.height == 0 {
+            return;
+        }
+
+        Clear.render(area, buf);
+
+        if !self.transparent_background {
+            let bg = self.window_background.unwrap_or(Color::Reset);
+            for y in area.top()..area.bottom().min(buf.area().bottom()) {
+                for x in area.left()..area.right().min(buf.area().right()) {
+                    buf[(x, y)].set_bg(bg);
+                }
+            }
+        }
+
+        let inner = if self.show_border {
+            let mut block = Block::default().borders(Borders::ALL);
+            if let Some(ref style) = self.border_style {
+                block = block.border_type(match style.as_str() {
+                    "double" => BorderType::Double,
+                    "rounded" => BorderType::Rounded,
+                    "thick" => BorderType::Thick,
+                    _ => BorderType::Plain,
+                });
+            }
+            if let Some(color) = self.border_color {
+                block = block.border_style(Style::default().fg(color));
+            }
+            if !self.title.is_empty() {
+                block = block.title(self.title.as_str());
+            }
+            let inner = block.inner(area);
+            block.render(area, buf);
+            inner
+        } else {
+            area
+        };
+
+        if inner.width == 0 || inner.height == 0 {
+            return;
+        }
+
+        let (label_width, bar_width, value_width) = column_widths(&self.rows, inner.width);
+        let text_fg = self.text_color.unwrap_or(Color::White);
+        let bar_bg = self.bar_background.unwrap_or(Color::Reset);
+
+        for (row_index, row) in self.rows.iter().enumerate().take(inner.height as usize) {
+            let y = inner.y + row_index as u16;
+            if y >= buf.area().bottom() {
+                break;
+            }
+            let mut x = inner.x;
+
+            // Label column (left-aligned)
+            for ch in row.label.chars().take(label_width as usize) {
+                buf[(x, y)].set_char(ch).set_fg(text_fg);
+                x += 1;
+            }
+            x = inner.x + label_width;
+
+            // Bar column
+            if bar_width > 0 {
+                if label_width > 0 {
+                    x += 1;
+                }
+                let filled = if row.max > 0 {
+                    ((row.current.min(row.max) as u64 * bar_width as u64) / row.max as u64) as u16
+                } else {
+                    0
+                };
+                let fill = row.fill.unwrap_or(Color::Green);
+                for i in 0..bar_width {
+                    let cell = &mut buf[(x + i, y)];
+                    cell.set_char(' ');
+                    cell.set_bg(if i < filled { fill } else { bar_bg });
+                }
+                x += bar_width;
+            }
+
+            // Value column (right-aligned so digits line up)
+            if value_width > 0 {
+                x += 1;
+                let value = row.value_text();
+                let len = value.chars().count() as u16;
+                let start = x + value_width.saturating_sub(len);
+                for (i, ch) in value.chars().take(value_width as usize).enumerate() {
+                    let cx = start + i as u16;
+                    if cx < inner.right() {
+                        buf[(cx, y)].set_char(ch).set_fg(text_fg);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(label: &str, current: u32, max: u32) -> VitalsRow {
+        VitalsRow {
+            label: label.to_string(),
+            current,
+            max,
+            text: String::new(),
+            fill: None,
+        }
+    }
+
+    #[test]
+    fn test_columns_align_across_rows() {
+        let mut cluster = VitalsCluster::new("Vitals");
+        cluster.set_border_config(false, None, None);
+        cluster.set_rows(vec![
+            row("HP", 95, 100),
+            row("Stamina", 8, 150),
+            VitalsRow {
+                text: "clear as a bell".to_string(),
+                ..row("Mind", 0, 100)
+            },
+        ]);
+
+        let area = Rect::new(0, 0, 40, 3);
+        let mut buf = Buffer::empty(area);
+        cluster.render(area, &mut buf);
+
+        let line = |y: u16| -> String { (0..40).map(|x| buf[(x, y)].symbol()).collect() };
+        assert!(line(0).starts_with("HP      "));
+        assert!(line(1).starts_with("Stamina "));
+        // Values are right-aligned to the same column
+        assert!(line(0).ends_with(" 95/100"));
+        assert!(line(1).ends_with("  8/150"));
+        assert!(line(2).ends_with("clear as a bell"));
+
+        let (label, bar, value) = column_widths(&cluster.rows, 40);
+        assert_eq!((label, value), (7, 15));
+        assert_eq!(bar, 40 - 7 - 15 - 2);
+    }
+}
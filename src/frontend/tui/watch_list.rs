@@ -0,0 +1,65 @@
+//! Watch list rows: friends and enemies from watchlist.toml seen online,
+//! with how long they've been on.
+//!
+//! Rows are rebuilt from `AppCore::watch` on every sync and drawn through a
+//! `ListWidget` with the times pinned to the right edge.
+
+use super::list_widget::ListRows;
+use crate::config::WatchListConfig;
+use crate::data::{format_elapsed, WatchKind, WatchTracker};
+use chrono::Local;
+
+/// Fill the rows from the tracker
+pub fn fill(
+    rows: &mut ListRows,
+    watch: &WatchTracker,
+    watchlist: &WatchListConfig,
+    show_offline: bool,
+) {
+    for (kind, heading, names, color) in [
+        (
+            WatchKind::Friend,
+            "Friends",
+            &watchlist.friends,
+            &watchlist.friend_color,
+        ),
+        (
+            WatchKind::Enemy,
+            "Enemies",
+            &watchlist.enemies,
+            &watchlist.enemy_color,
+        ),
+    ] {
+        if names.is_empty() {
+            continue;
+        }
+        let online: Vec<_> = watch.online_of(kind).collect();
+        rows.row(heading, heading, Some(online.len().to_string()));
+        for presence in &online {
+            let label = if presence.died.is_some() {
+                format!("  {} (dead)", presence.name)
+            } else {
+                format!("  {}", presence.name)
+            };
+            let on_for = format_elapsed(Local::now() - presence.since);
+            rows.row_full(
+                &presence.name.to_lowercase(),
+                &label,
+                Some(on_for),
+                0,
+                1,
+                Some(color.clone()),
+            );
+        }
+        if show_offline {
+            let mut offline: Vec<&String> = names
+                .iter()
+                .filter(|n| !online.iter().any(|p| p.name.eq_ignore_ascii_case(n)))
+                .collect();
+            offline.sort_by_key(|n| n.to_lowercase());
+            for name in offline {
+                rows.row(&name.to_lowercase(), &format!("  {}", name), None);
+            }
+        }
+    }
+}
@@ -4,19 +4,23 @@
 //! borders, and stream assignments for a given window definition.
 //!
 //! Uses a section-based navigation system inspired by the theme editor:
-//! - Section 1: Identity (name, title, show title, locked)
+//! - Section 1: Identity (name, title, show title, locked, parent container)
 //! - Section 2: Position/Size (row, col, rows, cols)
 //! - Section 3: Constraints (min/max rows/cols)
 //! - Section 4: Border (show, color, style, sides)
 //! - Section 5: Special (widget-specific: streams, cursor colors)
+//!
+//! While the dialog is open, a ghost outline shows where the window would
+//! land with the position, size and border typed so far.
 
 use crate::config::WindowDef;
 use crate::theme::EditorTheme;
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
-    style::{Color, Style},
-    widgets::{Block, Borders, Clear, Paragraph, Widget},
+    style::{Color, Modifier, Style},
+    symbols::border,
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Widget},
 };
 use tui_textarea::TextArea;
 
@@ -41,6 +45,7 @@ enum FieldRef {
     TextColor,
     CursorColor,
     CursorBg,
+    Container,
 
     // Checkboxes
     ShowTitle,
@@ -82,6 +87,7 @@ impl FieldRef {
             FieldRef::TextColor => 23,
             FieldRef::CursorColor => 24,
             FieldRef::CursorBg => 25,
+            FieldRef::Container => 26,
         }
     }
 }
@@ -140,6 +146,7 @@ pub struct WindowEditor {
     text_color_input: TextArea<'static>,
     cursor_color_input: TextArea<'static>,
     cursor_bg_input: TextArea<'static>,
+    container_input: TextArea<'static>,
 
     window_def: WindowDef,
     original_window_def: WindowDef,
@@ -172,6 +179,7 @@ impl WindowEditor {
                     FieldRef::Locked,
                     FieldRef::TransparentBg,
                     FieldRef::BgColor,
+                    FieldRef::Container,
                 ],
             },
             // Section 2: Position & Size
@@ -399,6 +407,7 @@ impl WindowEditor {
             text_color_input,
             cursor_color_input,
             cursor_bg_input,
+            container_input: Self::create_textarea(),
             window_def: window_def.clone(),
             original_window_def: window_def,
             is_new: false,
@@ -417,8 +426,8 @@ impl WindowEditor {
 
     pub fn new_window(widget_type: String) -> Self {
         use crate::config::{
-            BorderSides, CommandInputWidgetData, RoomWidgetData, SpacerWidgetData, TextWidgetData, WindowBase,
-            WindowDef,
+            BorderSides, CommandInputWidgetData, ContainerWidgetData, RoomWidgetData,
+            SpacerWidgetData, TextWidgetData, WindowBase, WindowDef,
         };
 
         // Create base configuration with defaults
@@ -443,6 +452,9 @@ impl WindowEditor {
             min_cols: None,
             max_cols: None,
             visible: true,
+            priority: None,
+            width: None,
+            height: None,
         };
 
         // Create window_def based on widget type
@@ -452,6 +464,11 @@ impl WindowEditor {
                 data: TextWidgetData {
                     streams: vec![],
                     buffer_size: 10000,
+                    include: Vec::new(),
+                    exclude: Vec::new(),
+                    timestamps: None,
+                    collapse_repeats: None,
+                    gap_separator_minutes: None,
                 },
             },
             "room" => WindowDef::Room {
@@ -473,11 +490,23 @@ impl WindowEditor {
                 base,
                 data: SpacerWidgetData {},
             },
+            "container" => WindowDef::Container {
+                base,
+                data: ContainerWidgetData {
+                    direction: "rows".to_string(),
+                    children: Vec::new(),
+                },
+            },
             _ => WindowDef::Text {
                 base,
                 data: TextWidgetData {
                     streams: vec![],
                     buffer_size: 10000,
+                    include: Vec::new(),
+                    exclude: Vec::new(),
+                    timestamps: None,
+                    collapse_repeats: None,
+                    gap_separator_minutes: None,
                 },
             },
         };
@@ -544,6 +573,7 @@ impl WindowEditor {
             text_color_input,
             cursor_color_input,
             cursor_bg_input,
+            container_input: Self::create_textarea(),
             window_def: window_def.clone(),
             original_window_def: window_def,
             is_new: true,
@@ -566,6 +596,19 @@ impl WindowEditor {
         editor
     }
 
+    /// Pre-fill the parent container (the editor only sees the WindowDef, not the layout)
+    pub fn set_container(&mut self, container: Option<&str>) {
+        self.container_input = Self::create_textarea();
+        if let Some(container) = container {
+            self.container_input.insert_str(container);
+        }
+    }
+
+    /// Parent container entered by the user (None = not in a container)
+    pub fn container(&self) -> Option<String> {
+        Some(self.container_input.lines()[0].trim().to_string()).filter(|s| !s.is_empty())
+    }
+
     fn is_command_input(&self) -> bool {
         matches!(self.window_def, WindowDef::CommandInput { .. })
     }
@@ -736,6 +779,9 @@ impl WindowEditor {
             25 => {
                 self.cursor_bg_input.input(input.clone());
             }
+            26 => {
+                self.container_input.input(input);
+            }
             _ => {} // Checkboxes/dropdowns don't handle text input
         }
     }
@@ -806,22 +852,11 @@ impl WindowEditor {
         self.window_def.base_mut().name = self.name_input.lines()[0].to_string();
         self.window_def.base_mut().title =
             Some(self.title_input.lines()[0].to_string()).filter(|s| !s.is_empty());
-        self.window_def.base_mut().row = self.row_input.lines()[0].parse().unwrap_or(0);
-        self.window_def.base_mut().col = self.col_input.lines()[0].parse().unwrap_or(0);
-        let rows_lines = self.rows_input.lines();
-        let content_rows = rows_lines
-            .get(0)
-            .and_then(|s| s.parse::<u16>().ok())
-            .unwrap_or(1);
-        let cols_lines = self.cols_input.lines();
-        let content_cols = cols_lines
-            .get(0)
-            .and_then(|s| s.parse::<u16>().ok())
-            .unwrap_or(40);
-        let border_rows = self.window_def.base().horizontal_border_units();
-        let border_cols = self.window_def.base().vertical_border_units();
-        self.window_def.base_mut().rows = content_rows.saturating_add(border_rows).max(1);
-        self.window_def.base_mut().cols = content_cols.saturating_add(border_cols).max(1);
+        let (row, col, rows, cols) = self.prospective_geometry();
+        self.window_def.base_mut().row = row;
+        self.window_def.base_mut().col = col;
+        self.window_def.base_mut().rows = rows;
+        self.window_def.base_mut().cols = cols;
         self.window_def.base_mut().min_rows = self.min_rows_input.lines()[0].parse().ok();
         self.window_def.base_mut().min_cols = self.min_cols_input.lines()[0].parse().ok();
         self.window_def.base_mut().max_rows = self.max_rows_input.lines()[0].parse().ok();
@@ -851,6 +886,96 @@ impl WindowEditor {
         }
     }
 
+    /// Row, col, rows and cols (borders included) from the values typed so far
+    fn prospective_geometry(&self) -> (u16, u16, u16, u16) {
+        let parse = |input: &TextArea, default: u16| {
+            input
+                .lines()
+                .first()
+                .and_then(|s| s.parse::<u16>().ok())
+                .unwrap_or(default)
+        };
+        let base = self.window_def.base();
+        let rows = parse(&self.rows_input, 1)
+            .saturating_add(base.horizontal_border_units())
+            .max(1);
+        let cols = parse(&self.cols_input, 40)
+            .saturating_add(base.vertical_border_units())
+            .max(1);
+        (
+            parse(&self.row_input, 0),
+            parse(&self.col_input, 0),
+            rows,
+            cols,
+        )
+    }
+
+    /// Ghost outline of the window as edited, drawn behind the dialog
+    ///
+    /// Uses the window's own border style and sides; a borderless window
+    /// gets a dotted outline so its extent is still visible.
+    fn render_preview(&self, area: Rect, buf: &mut Buffer, theme: &EditorTheme) {
+        let (row, col, rows, cols) = self.prospective_geometry();
+        let preview = Rect::new(col, row, cols, rows).intersection(area);
+        if preview.is_empty() {
+            return;
+        }
+        let base = self.window_def.base();
+        let style = Style::default()
+            .fg(theme.focused_label_color)
+            .add_modifier(Modifier::DIM);
+
+        let mut block = Block::default().border_style(style);
+        if base.show_border && base.border_style != "none" {
+            let sides = &base.border_sides;
+            let mut borders = Borders::NONE;
+            if sides.top {
+                borders |= Borders::TOP;
+            }
+            if sides.bottom {
+                borders |= Borders::BOTTOM;
+            }
+            if sides.left {
+                borders |= Borders::LEFT;
+            }
+            if sides.right {
+                borders |= Borders::RIGHT;
+            }
+            let border_type = match base.border_style.as_str() {
+                "double" => BorderType::Double,
+                "rounded" => BorderType::Rounded,
+                "thick" => BorderType::Thick,
+                _ => BorderType::Plain,
+            };
+            block = block.borders(borders).border_type(border_type);
+        } else {
+            block = block.borders(Borders::ALL).border_set(border::Set {
+                top_left: "·",
+                top_right: "·",
+                bottom_left: "·",
+                bottom_right: "·",
+                vertical_left: "┊",
+                vertical_right: "┊",
+                horizontal_top: "┄",
+                horizontal_bottom: "┄",
+            });
+        }
+
+        let name = self.name_input.lines().first().cloned().unwrap_or_default();
+        let title = self
+            .title_input
+            .lines()
+            .first()
+            .filter(|t| !t.is_empty())
+            .cloned()
+            .unwrap_or(name);
+        if base.show_title && !title.is_empty() {
+            block = block.title(title);
+        }
+        block = block.title_bottom(format!(" {}x{} at {},{} ", rows, cols, row, col));
+        block.render(preview, buf);
+    }
+
     pub fn get_window_def(&mut self) -> &WindowDef {
         self.sync_to_window_def();
         &self.window_def
@@ -953,6 +1078,7 @@ impl WindowEditor {
             height: self.popup_height,
         };
 
+        self.render_preview(area, buf, theme);
         Clear.render(popup_area, buf);
 
         for y in popup_area.y..popup_area.y + popup_area.height {
@@ -1133,6 +1259,19 @@ impl WindowEditor {
                     FieldRef::CursorBg => {
                         self.render_color_field(field_id, "Cursor BG:", &self.cursor_bg_input, x, y, buf, theme, is_current);
                     }
+                    FieldRef::Container => {
+                        self.render_textarea_compact(
+                            field_id,
+                            "Container:",
+                            &self.container_input,
+                            x,
+                            y,
+                            20,
+                            buf,
+                            theme,
+                            is_current,
+                        );
+                    }
                     FieldRef::ShowTitle => {
                         self.render_checkbox_compact(field_id, "Show Title", self.window_def.base().show_title, x, y, buf, theme, is_current);
                     }
@@ -1463,6 +1602,9 @@ mod tests {
                 min_cols: None,
                 max_cols: None,
                 visible: true,
+                priority: None,
+                width: None,
+                height: None,
             },
             data: SpacerWidgetData {},
         };
@@ -1481,6 +1623,40 @@ mod tests {
         assert_eq!(name, "spacer_2");
     }
 
+    #[test]
+    fn test_preview_follows_typed_geometry() {
+        let mut editor = WindowEditor::new_window("text".to_string());
+        editor.row_input = WindowEditor::textarea_with_value(2);
+        editor.col_input = WindowEditor::textarea_with_value(3);
+        editor.rows_input = WindowEditor::textarea_with_value(4);
+        editor.cols_input = WindowEditor::textarea_with_value(30);
+        let (row, col, rows, cols) = editor.prospective_geometry();
+        let border_rows = editor.window_def.base().horizontal_border_units();
+        assert_eq!((row, col, rows), (2, 3, 4 + border_rows));
+
+        // Park the dialog in the corner so the outline shows
+        editor.popup_x = 40;
+        editor.popup_y = 20;
+        editor.popup_width = 40;
+        editor.popup_height = 20;
+        let area = Rect::new(0, 0, 80, 40);
+        let mut buf = Buffer::empty(area);
+        editor.render(
+            area,
+            &mut buf,
+            &crate::theme::AppTheme::default().to_editor_theme(),
+        );
+        let bottom: String = (3..3 + cols)
+            .map(|x| buf[(x, 2 + rows - 1)].symbol().to_string())
+            .collect();
+        assert!(
+            bottom.contains(&format!("{}x{} at 2,3", rows, cols)),
+            "{}",
+            bottom
+        );
+        assert_ne!(buf[(3, 2)].symbol(), " ");
+    }
+
     #[test]
     fn test_build_sections_excludes_border_for_spacer() {
         // RED: Test that build_sections_for_widget returns sections without Border for spacer
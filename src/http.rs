@@ -0,0 +1,172 @@
+//! Minimal HTTP client for outgoing integrations
+//!
+//! The webhook relay and push notifications only ever POST a small JSON body
+//! and look at the status code, so this is a blocking request over
+//! `std::net` (openssl for https, verifying certificates against the system
+//! store) rather than a full client. Callers run it on their own threads.
+
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+/// Connect, write and read timeout for one request
+const HTTP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Where a POST goes, parsed from an http(s) URL
+#[derive(Debug, Clone, PartialEq)]
+pub struct Endpoint {
+    pub tls: bool,
+    pub host: String,
+    pub port: u16,
+    pub path: String,
+}
+
+impl Endpoint {
+    pub fn parse(url: &str) -> Result<Self, String> {
+        let (tls, rest) = if let Some(rest) = url.strip_prefix("https://") {
+            (true, rest)
+        } else if let Some(rest) = url.strip_prefix("http://") {
+            (false, rest)
+        } else {
+            return Err("url must start with http:// or https://".to_string());
+        };
+        let (authority, path) = match rest.find('/') {
+            Some(i) => (&rest[..i], &rest[i..]),
+            None => (rest, "/"),
+        };
+        let default_port = if tls { 443 } else { 80 };
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => (
+                host,
+                port.parse()
+                    .map_err(|_| format!("invalid port in url: {}", port))?,
+            ),
+            None => (authority, default_port),
+        };
+        if host.is_empty() {
+            return Err("url has no host".to_string());
+        }
+        Ok(Self {
+            tls,
+            host: host.to_string(),
+            port,
+            path: path.to_string(),
+        })
+    }
+}
+
+/// POST `body` as JSON with any extra headers; returns the HTTP status
+pub fn post_json(endpoint: &Endpoint, body: &str, headers: &[(&str, &str)]) -> Result<u16, String> {
+    let addr = (endpoint.host.as_str(), endpoint.port)
+        .to_socket_addrs()
+        .map_err(|e| format!("can't resolve {}: {}", endpoint.host, e))?
+        .next()
+        .ok_or_else(|| format!("can't resolve {}", endpoint.host))?;
+    let tcp = TcpStream::connect_timeout(&addr, HTTP_TIMEOUT).map_err(|e| e.to_string())?;
+    tcp.set_read_timeout(Some(HTTP_TIMEOUT)).ok();
+    tcp.set_write_timeout(Some(HTTP_TIMEOUT)).ok();
+
+    let extra: String = headers
+        .iter()
+        .map(|(name, value)| format!("{}: {}\r\n", name, value))
+        .collect();
+    let request = format!(
+        "POST {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: two-face\r\nContent-Type: application/json\r\nContent-Length: {}\r\n{}Connection: close\r\n\r\n{}",
+        endpoint.path,
+        endpoint.host,
+        body.len(),
+        extra,
+        body
+    );
+
+    let mut response = Vec::new();
+    if endpoint.tls {
+        use openssl::ssl::{SslConnector, SslMethod};
+        let connector = SslConnector::builder(SslMethod::tls_client())
+            .map_err(|e| e.to_string())?
+            .build();
+        let mut stream = connector
+            .connect(&endpoint.host, tcp)
+            .map_err(|e| format!("TLS to {} failed: {}", endpoint.host, e))?;
+        stream
+            .write_all(request.as_bytes())
+            .map_err(|e| e.to_string())?;
+        read_status_line(&mut stream, &mut response)?;
+    } else {
+        let mut stream = tcp;
+        stream
+            .write_all(request.as_bytes())
+            .map_err(|e| e.to_string())?;
+        read_status_line(&mut stream, &mut response)?;
+    }
+
+    // "HTTP/1.1 204 No Content"
+    String::from_utf8_lossy(&response)
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse().ok())
+        .ok_or_else(|| "malformed HTTP response".to_string())
+}
+
+/// Read until the end of the status line (the rest of the response is ignored)
+fn read_status_line(stream: &mut impl Read, response: &mut Vec<u8>) -> Result<(), String> {
+    let mut buf = [0u8; 512];
+    while !response.contains(&b'\n') {
+        match stream.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => response.extend_from_slice(&buf[..n]),
+            Err(e) => return Err(e.to_string()),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::BufRead;
+
+    #[test]
+    fn test_endpoint_parse() {
+        assert_eq!(
+            Endpoint::parse("https://discord.com/api/webhooks/1/abc").unwrap(),
+            Endpoint {
+                tls: true,
+                host: "discord.com".to_string(),
+                port: 443,
+                path: "/api/webhooks/1/abc".to_string()
+            }
+        );
+        assert_eq!(Endpoint::parse("http://127.0.0.1:9000").unwrap().path, "/");
+        assert!(Endpoint::parse("ftp://example.com").is_err());
+    }
+
+    #[test]
+    fn test_post_json() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = std::io::BufReader::new(stream.try_clone().unwrap());
+            let mut head = Vec::new();
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).unwrap();
+                if header.trim().is_empty() {
+                    break;
+                }
+                head.push(header.trim().to_string());
+            }
+            (&stream)
+                .write_all(b"HTTP/1.1 429 Too Many Requests\r\n\r\n")
+                .unwrap();
+            head
+        });
+        let endpoint = Endpoint::parse(&format!("http://127.0.0.1:{}/hook", port)).unwrap();
+        assert_eq!(post_json(&endpoint, "{}", &[("Title", "Hi")]), Ok(429));
+        let head = server.join().unwrap();
+        assert_eq!(head[0], "POST /hook HTTP/1.1");
+        assert!(head.contains(&"Title: Hi".to_string()));
+        assert!(head.contains(&"Content-Length: 2".to_string()));
+    }
+}
@@ -0,0 +1,230 @@
+//! One running instance per character
+//!
+//! Two clients attached to the same character fight over the Lich port and
+//! overwrite each other's history, layout and widget state on exit. At
+//! startup a lock file holding our PID is created in the character's profile
+//! directory. If another live process already holds it, the user is asked
+//! whether to quit or carry on. Locks left behind by a crash (dead PID) are
+//! taken over silently.
+
+use anyhow::{Context, Result};
+use std::fs::OpenOptions;
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
+
+/// Another instance that holds the lock
+#[derive(Debug, Clone, PartialEq)]
+pub struct LockOwner {
+    pub pid: u32,
+    pub port: Option<u16>,
+    pub started: String,
+}
+
+impl LockOwner {
+    fn parse(contents: &str) -> Option<Self> {
+        let mut fields = contents.lines().map(str::trim);
+        let pid = fields.next()?.parse().ok()?;
+        let port = fields.next().and_then(|p| p.parse().ok());
+        let started = fields.next().unwrap_or("").to_string();
+        Some(Self { pid, port, started })
+    }
+}
+
+/// Held for the life of the process; removes the lock file on drop
+#[derive(Debug)]
+pub struct InstanceLock {
+    path: PathBuf,
+}
+
+/// What `InstanceLock::acquire` found
+#[derive(Debug)]
+pub enum LockStatus {
+    Acquired(InstanceLock),
+    /// A live process has the lock; `force` takes it over anyway
+    Held(LockOwner),
+}
+
+impl InstanceLock {
+    /// Try to take the lock at `path`, replacing a stale one
+    pub fn acquire(path: &Path, port: u16) -> Result<LockStatus> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+
+        for _ in 0..2 {
+            match OpenOptions::new().write(true).create_new(true).open(path) {
+                Ok(mut file) => {
+                    file.write_all(Self::contents(port).as_bytes())?;
+                    return Ok(LockStatus::Acquired(Self {
+                        path: path.to_path_buf(),
+                    }));
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    let contents = std::fs::read_to_string(path).unwrap_or_default();
+                    match LockOwner::parse(&contents) {
+                        Some(owner)
+                            if owner.pid != std::process::id() && process_alive(owner.pid) =>
+                        {
+                            return Ok(LockStatus::Held(owner));
+                        }
+                        _ => {
+                            tracing::info!("Removing stale instance lock {}", path.display());
+                            let _ = std::fs::remove_file(path);
+                        }
+                    }
+                }
+                Err(e) => {
+                    return Err(e).with_context(|| format!("Failed to create {}", path.display()));
+                }
+            }
+        }
+        // Still couldn't create it after clearing a stale lock; just claim it
+        Ok(LockStatus::Acquired(Self::force(path, port)?))
+    }
+
+    /// Take the lock even though another instance holds it
+    pub fn force(path: &Path, port: u16) -> Result<Self> {
+        std::fs::write(path, Self::contents(port))
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(Self {
+            path: path.to_path_buf(),
+        })
+    }
+
+    fn contents(port: u16) -> String {
+        format!(
+            "{}\n{}\n{}\n",
+            std::process::id(),
+            port,
+            chrono::Local::now().format("%Y-%m-%d %H:%M:%S")
+        )
+    }
+}
+
+impl Drop for InstanceLock {
+    fn drop(&mut self) {
+        // Leave it alone if a forced second instance has taken it over
+        let ours = std::fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|c| LockOwner::parse(&c))
+            .is_some_and(|owner| owner.pid == std::process::id());
+        if ours {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// Is a process with this PID still running?
+///
+/// Errs on the side of "alive" when the platform can't tell, so a live
+/// instance is never mistaken for a stale lock.
+pub fn process_alive(pid: u32) -> bool {
+    use std::process::{Command, Stdio};
+
+    if cfg!(target_os = "linux") {
+        return Path::new(&format!("/proc/{}", pid)).exists();
+    }
+    if cfg!(windows) {
+        return Command::new("tasklist")
+            .args(["/FI", &format!("PID eq {}", pid), "/NH"])
+            .output()
+            .map(|o| String::from_utf8_lossy(&o.stdout).contains(&pid.to_string()))
+            .unwrap_or(true);
+    }
+    Command::new("kill")
+        .args(["-0", &pid.to_string()])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map(|s| s.success())
+        .unwrap_or(true)
+}
+
+/// Lock the profile for this run, asking the user if it's already in use
+///
+/// Returns None when the user chose to quit.
+pub fn acquire_or_prompt(
+    path: &Path,
+    character: Option<&str>,
+    port: u16,
+    force: bool,
+) -> Result<Option<InstanceLock>> {
+    let owner = match InstanceLock::acquire(path, port)? {
+        LockStatus::Acquired(lock) => return Ok(Some(lock)),
+        LockStatus::Held(owner) => owner,
+    };
+
+    let who = character.unwrap_or("the default profile");
+    let port_note = owner
+        .port
+        .map(|p| format!(" on port {}", p))
+        .unwrap_or_default();
+    eprintln!(
+        "Warning: two-face is already running for {} (PID {}{}, started {}).",
+        who, owner.pid, port_note, owner.started
+    );
+    eprintln!("Two clients on one character share its Lich connection and overwrite each other's saved state.");
+
+    if !force {
+        if !std::io::stdin().is_terminal() {
+            eprintln!("Refusing to start. Pass --force-instance to run anyway.");
+            return Ok(None);
+        }
+        eprint!("[q]uit or [c]ontinue anyway? [q] ");
+        let _ = std::io::stderr().flush();
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer)?;
+        if !matches!(answer.trim().to_lowercase().as_str(), "c" | "continue") {
+            return Ok(None);
+        }
+    }
+
+    tracing::warn!("Starting alongside instance PID {} for {}", owner.pid, who);
+    Ok(Some(InstanceLock::force(path, port)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A lock path in a fresh directory for one test
+    fn lock_path(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("two-face-lock-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir.join("instance.lock")
+    }
+
+    #[test]
+    fn test_lock_records_owner_and_is_removed_on_drop() {
+        let path = lock_path("owner");
+        let lock = match InstanceLock::acquire(&path, 8000).unwrap() {
+            LockStatus::Acquired(lock) => lock,
+            LockStatus::Held(owner) => panic!("unexpectedly held by {:?}", owner),
+        };
+        let owner = LockOwner::parse(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(owner.pid, std::process::id());
+        assert_eq!(owner.port, Some(8000));
+        drop(lock);
+        assert!(!path.exists());
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_lock_of_dead_process_is_taken_over() {
+        let path = lock_path("stale");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, "4294967294\n8001\n").unwrap();
+        assert!(matches!(
+            InstanceLock::acquire(&path, 8000).unwrap(),
+            LockStatus::Acquired(_)
+        ));
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_lock_owner_parse_rejects_garbage() {
+        assert!(LockOwner::parse("not a pid").is_none());
+    }
+}
@@ -0,0 +1,394 @@
+//! Control socket for `two-face send`
+//!
+//! A running instance listens on a Unix socket in its profile directory
+//! (next to the instance lock), or on a named pipe derived from that path on
+//! Windows. A second invocation - from a window-manager keybind or a script -
+//! connects and writes one command per line; each is run as if it had been
+//! typed, and answered with `ok`. Lines starting with `dot ` are client
+//! commands and get a leading `.` if they lack one.
+//!
+//! Up to `MAX_CLIENTS` connections are served at once, each on its own
+//! thread, so a script holding a connection open doesn't lock out a keybind.
+//! The socket is bound inside an owner-only directory, so nobody else can
+//! reach it even before its own mode is tightened, and is removed when the
+//! instance exits.
+
+use anyhow::Result;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{Receiver, Sender};
+use std::sync::Arc;
+
+/// Connections served at once; more are turned away with an error
+const MAX_CLIENTS: usize = 8;
+
+/// One command from a control connection
+fn parse_request(line: &str) -> Option<String> {
+    let line = line.trim_end_matches(['\r', '\n']);
+    if let Some(dot) = line.strip_prefix("dot ") {
+        let dot = dot.trim();
+        if dot.is_empty() {
+            return None;
+        }
+        return Some(if dot.starts_with('.') {
+            dot.to_string()
+        } else {
+            format!(".{}", dot)
+        });
+    }
+    let command = line.strip_prefix("cmd ").unwrap_or(line);
+    (!command.trim().is_empty()).then(|| command.to_string())
+}
+
+/// Answer one connection's commands until it closes
+fn serve(reader: impl Read, mut writer: impl Write, tx: &Sender<String>) {
+    for line in BufReader::new(reader).lines() {
+        let Ok(line) = line else { break };
+        let answer = match parse_request(&line) {
+            Some(command) => {
+                tracing::info!("Control socket command: {}", command);
+                if tx.send(command).is_err() {
+                    return;
+                }
+                "ok\n"
+            }
+            None => "error: empty command\n",
+        };
+        if writer.write_all(answer.as_bytes()).is_err() {
+            break;
+        }
+    }
+}
+
+/// A counted connection; frees its place when the client thread ends
+struct ClientSlot(Arc<AtomicUsize>);
+
+impl Drop for ClientSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Serve a connection on its own thread, or turn it away when `MAX_CLIENTS` are connected
+fn accept<R, W>(reader: R, mut writer: W, tx: &Sender<String>, active: &Arc<AtomicUsize>)
+where
+    R: Read + Send + 'static,
+    W: Write + Send + 'static,
+{
+    let slot = ClientSlot(Arc::clone(active));
+    if active.fetch_add(1, Ordering::SeqCst) >= MAX_CLIENTS {
+        let _ = writer.write_all(b"error: too many connections\n");
+        return;
+    }
+    let tx = tx.clone();
+    let spawned = std::thread::Builder::new()
+        .name("control-client".to_string())
+        .spawn(move || {
+            let _slot = slot;
+            serve(reader, writer, &tx);
+        });
+    if let Err(e) = spawned {
+        tracing::warn!("Control connection dropped: {}", e);
+    }
+}
+
+/// Listening control socket owned by the main loop; removes the socket file on drop
+pub struct ControlSocket {
+    path: PathBuf,
+    rx: Receiver<String>,
+}
+
+impl ControlSocket {
+    /// Commands received since the last poll
+    pub fn poll_commands(&self) -> Vec<String> {
+        self.rx.try_iter().collect()
+    }
+}
+
+impl Drop for ControlSocket {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(unix)]
+impl ControlSocket {
+    /// Listen at `path`, replacing a socket left behind by a crash
+    ///
+    /// `path`'s directory is made owner-only, so it should hold nothing else.
+    pub fn start(path: &Path) -> Result<Self> {
+        use anyhow::Context;
+        use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+        use std::os::unix::net::{UnixListener, UnixStream};
+
+        // Other users can't traverse the directory, so the socket is private
+        // from the moment it's bound
+        if let Some(parent) = path.parent() {
+            std::fs::DirBuilder::new()
+                .recursive(true)
+                .mode(0o700)
+                .create(parent)?;
+            std::fs::set_permissions(parent, std::fs::Permissions::from_mode(0o700))?;
+        }
+
+        if path.exists() {
+            if UnixStream::connect(path).is_ok() {
+                anyhow::bail!("{} is in use by another instance", path.display());
+            }
+            let _ = std::fs::remove_file(path);
+        }
+        let listener = UnixListener::bind(path)
+            .with_context(|| format!("Failed to listen on {}", path.display()))?;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::Builder::new()
+            .name("control-socket".to_string())
+            .spawn(move || {
+                let active = Arc::new(AtomicUsize::new(0));
+                for stream in listener.incoming() {
+                    let Ok(stream) = stream else { continue };
+                    let Ok(reply) = stream.try_clone() else {
+                        continue;
+                    };
+                    accept(stream, reply, &tx, &active);
+                }
+            })?;
+
+        tracing::info!("Control socket listening on {}", path.display());
+        Ok(Self {
+            path: path.to_path_buf(),
+            rx,
+        })
+    }
+}
+
+/// Named pipe standing in for the socket file on Windows, one per profile
+#[cfg(windows)]
+fn pipe_name(path: &Path) -> String {
+    let id: String = path
+        .to_string_lossy()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    format!(r"\\.\pipe\two-face-{}", id)
+}
+
+#[cfg(windows)]
+impl ControlSocket {
+    /// Listen on the named pipe for `path`
+    pub fn start(path: &Path) -> Result<Self> {
+        use anyhow::Context;
+        use std::os::windows::ffi::OsStrExt;
+        use std::os::windows::io::{AsRawHandle, FromRawHandle, RawHandle};
+        use windows_sys::Win32::Foundation::{
+            GetLastError, ERROR_PIPE_CONNECTED, INVALID_HANDLE_VALUE,
+        };
+        use windows_sys::Win32::Storage::FileSystem::{
+            FILE_FLAG_FIRST_PIPE_INSTANCE, PIPE_ACCESS_DUPLEX,
+        };
+        use windows_sys::Win32::System::Pipes::{
+            ConnectNamedPipe, CreateNamedPipeW, PIPE_READMODE_BYTE, PIPE_REJECT_REMOTE_CLIENTS,
+            PIPE_TYPE_BYTE, PIPE_UNLIMITED_INSTANCES, PIPE_WAIT,
+        };
+
+        let name = pipe_name(path);
+        let wide: Vec<u16> = std::ffi::OsStr::new(&name)
+            .encode_wide()
+            .chain(Some(0))
+            .collect();
+        // The default security descriptor only lets the owner (and admins) write to the pipe
+        let create = move |first: bool| -> std::io::Result<std::fs::File> {
+            let open_mode = PIPE_ACCESS_DUPLEX
+                | if first {
+                    FILE_FLAG_FIRST_PIPE_INSTANCE
+                } else {
+                    0
+                };
+            let pipe_mode =
+                PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT | PIPE_REJECT_REMOTE_CLIENTS;
+            // SAFETY: `wide` is a NUL-terminated UTF-16 name that outlives the
+            // call, and a null security attributes pointer means the default
+            let handle = unsafe {
+                CreateNamedPipeW(
+                    wide.as_ptr(),
+                    open_mode,
+                    pipe_mode,
+                    PIPE_UNLIMITED_INSTANCES,
+                    4096,
+                    4096,
+                    0,
+                    std::ptr::null(),
+                )
+            };
+            if handle == INVALID_HANDLE_VALUE {
+                return Err(std::io::Error::last_os_error());
+            }
+            // SAFETY: the handle is valid and nothing else owns it
+            Ok(unsafe { std::fs::File::from_raw_handle(handle as RawHandle) })
+        };
+        let first =
+            create(true).with_context(|| format!("{} is in use by another instance", name))?;
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::Builder::new()
+            .name("control-socket".to_string())
+            .spawn(move || {
+                let active = Arc::new(AtomicUsize::new(0));
+                let mut pending = first;
+                loop {
+                    // SAFETY: `pending` owns a live pipe handle; a null OVERLAPPED
+                    // makes the call synchronous
+                    let connected = unsafe {
+                        ConnectNamedPipe(pending.as_raw_handle() as _, std::ptr::null_mut())
+                    } != 0
+                        || unsafe { GetLastError() } == ERROR_PIPE_CONNECTED;
+                    // The next client needs a fresh instance of the pipe
+                    let next = match create(false) {
+                        Ok(next) => next,
+                        Err(e) => {
+                            tracing::warn!("Control pipe stopped listening: {}", e);
+                            return;
+                        }
+                    };
+                    let client = std::mem::replace(&mut pending, next);
+                    if connected {
+                        if let Ok(reply) = client.try_clone() {
+                            accept(client, reply, &tx, &active);
+                        }
+                    }
+                }
+            })?;
+
+        tracing::info!("Control pipe listening on {}", name);
+        Ok(Self {
+            path: path.to_path_buf(),
+            rx,
+        })
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+impl ControlSocket {
+    pub fn start(_path: &Path) -> Result<Self> {
+        anyhow::bail!("The control socket isn't available on this platform")
+    }
+}
+
+/// Open a connection to the instance at `path`, as (reader, writer)
+#[cfg(unix)]
+fn connect(path: &Path) -> Result<(impl Read, impl Write)> {
+    use anyhow::Context;
+    use std::os::unix::net::UnixStream;
+
+    let stream = UnixStream::connect(path)
+        .with_context(|| format!("No running instance is listening on {}", path.display()))?;
+    Ok((stream.try_clone()?, stream))
+}
+
+#[cfg(windows)]
+fn connect(path: &Path) -> Result<(impl Read, impl Write)> {
+    use anyhow::Context;
+
+    let name = pipe_name(path);
+    let pipe = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(&name)
+        .with_context(|| format!("No running instance is listening on {}", name))?;
+    Ok((pipe.try_clone()?, pipe))
+}
+
+#[cfg(not(any(unix, windows)))]
+fn connect(_path: &Path) -> Result<(std::io::Empty, std::io::Sink)> {
+    anyhow::bail!("The control socket isn't available on this platform")
+}
+
+/// Send commands to the instance listening at `path` (`two-face send`)
+pub fn send(path: &Path, commands: &[String], dot: bool) -> Result<()> {
+    let (reader, mut writer) = connect(path)?;
+    let mut reader = BufReader::new(reader);
+    for command in commands {
+        if command.contains('\n') {
+            anyhow::bail!("Commands can't contain newlines: {:?}", command);
+        }
+        let kind = if dot { "dot" } else { "cmd" };
+        writeln!(writer, "{} {}", kind, command)?;
+        let mut answer = String::new();
+        reader.read_line(&mut answer)?;
+        if answer.trim() != "ok" {
+            anyhow::bail!("{}: {}", command, answer.trim());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_control_socket() {
+        assert_eq!(parse_request("cmd look"), Some("look".to_string()));
+        assert_eq!(
+            parse_request("dot loadlayout hunt\n"),
+            Some(".loadlayout hunt".to_string())
+        );
+        assert_eq!(
+            parse_request("dot .savelayout"),
+            Some(".savelayout".to_string())
+        );
+        assert_eq!(parse_request("cmd  "), None);
+
+        let dir =
+            std::env::temp_dir().join(format!("two-face-control-test-{}", std::process::id()));
+        let path = dir.join("control.sock");
+        let socket = ControlSocket::start(&path).unwrap();
+        assert!(ControlSocket::start(&path).is_err());
+
+        send(&path, &["look".to_string(), "north".to_string()], false).unwrap();
+        send(&path, &["loadlayout hunt".to_string()], true).unwrap();
+        assert_eq!(
+            socket.poll_commands(),
+            vec!["look", "north", ".loadlayout hunt"]
+        );
+
+        drop(socket);
+        assert!(!path.exists());
+        assert!(send(&path, &["look".to_string()], false).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_control_socket_serves_clients_side_by_side() {
+        use std::os::unix::net::UnixStream;
+
+        let dir =
+            std::env::temp_dir().join(format!("two-face-control-multi-{}", std::process::id()));
+        let path = dir.join("control.sock");
+        let socket = ControlSocket::start(&path).unwrap();
+        let mode = |path: &Path| {
+            let permissions = std::fs::metadata(path).unwrap().permissions();
+            std::os::unix::fs::PermissionsExt::mode(&permissions) & 0o777
+        };
+        assert_eq!(mode(&dir), 0o700);
+        assert_eq!(mode(&path), 0o600);
+
+        // A client that stays connected doesn't hold up the next one
+        let idle = UnixStream::connect(&path).unwrap();
+        send(&path, &["look".to_string()], false).unwrap();
+        assert_eq!(socket.poll_commands(), vec!["look"]);
+        drop(idle);
+
+        // Past the limit, connections are turned away
+        let held: Vec<UnixStream> = (0..MAX_CLIENTS)
+            .map(|_| UnixStream::connect(&path).unwrap())
+            .collect();
+        let err = send(&path, &["look".to_string()], false).unwrap_err();
+        assert!(err.to_string().contains("too many connections"));
+        drop(held);
+        drop(socket);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
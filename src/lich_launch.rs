@@ -0,0 +1,286 @@
+//! Starting Lich when nothing is listening on its port
+//!
+//! Without `--direct`, two-face connects to Lich's detachable-client port,
+//! which means starting Lich first. If nothing answers on the configured
+//! port at startup, the user is offered to have Lich started for them: the
+//! `[lich] command` from config, or `ruby lich.rbw --login <character>
+//! --without-frontend --detachable-client=<port>` when a lich.rbw is found in
+//! a usual install location. Lich's output goes to `lich.log` in the
+//! two-face directory, and the client waits for the port to open before
+//! connecting.
+
+use anyhow::{Context, Result};
+use std::io::{IsTerminal, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Where Lich 5 usually lives, relative to the home directory
+const HOME_CANDIDATES: [&str; 4] = [
+    "lich5/lich.rbw",
+    "Lich5/lich.rbw",
+    "lich/lich.rbw",
+    ".lich/lich.rbw",
+];
+
+/// The Windows installer's default location
+const WINDOWS_CANDIDATES: [&str; 2] = [
+    "C:\\Ruby4Lich5\\Lich5\\lich.rbw",
+    "C:\\Ruby4Lich5\\lich.rbw",
+];
+
+/// Is something accepting connections on host:port?
+pub fn port_open(host: &str, port: u16) -> bool {
+    let Ok(addrs) = (host, port).to_socket_addrs() else {
+        return false;
+    };
+    addrs
+        .into_iter()
+        .any(|addr| TcpStream::connect_timeout(&addr, Duration::from_millis(500)).is_ok())
+}
+
+/// Only a Lich on this machine can be started for you
+fn is_local(host: &str) -> bool {
+    matches!(host, "localhost" | "127.0.0.1" | "::1" | "0.0.0.0")
+}
+
+/// Split a command line on spaces, honouring "double" and 'single' quotes
+fn split_command(command: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut quote = None;
+    let mut in_arg = false;
+    for c in command.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => current.push(c),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                in_arg = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            (None, c) => {
+                current.push(c);
+                in_arg = true;
+            }
+        }
+    }
+    if in_arg {
+        args.push(current);
+    }
+    args
+}
+
+/// Fill `{character}` and `{port}` and expand a leading `~/` in each argument
+fn expand_command(command: &str, character: &str, port: u16, home: Option<&Path>) -> Vec<String> {
+    split_command(command)
+        .into_iter()
+        .map(|arg| {
+            let arg = arg
+                .replace("{character}", character)
+                .replace("{port}", &port.to_string());
+            match (arg.strip_prefix("~/"), home) {
+                (Some(rest), Some(home)) => home.join(rest).to_string_lossy().into_owned(),
+                _ => arg,
+            }
+        })
+        .collect()
+}
+
+/// A lich.rbw in one of the usual places
+fn find_lich(home: Option<&Path>) -> Option<PathBuf> {
+    let from_home = home
+        .into_iter()
+        .flat_map(|home| HOME_CANDIDATES.iter().map(move |c| home.join(c)));
+    let windows = cfg!(windows)
+        .then_some(WINDOWS_CANDIDATES.iter().map(PathBuf::from))
+        .into_iter()
+        .flatten();
+    from_home.chain(windows).find(|path| path.is_file())
+}
+
+/// The command line that would start Lich, or None if there's no way to
+fn lich_command(
+    config: &crate::config::LichLaunchConfig,
+    character: &str,
+    port: u16,
+) -> Option<Vec<String>> {
+    let home = dirs::home_dir();
+    if !config.command.trim().is_empty() {
+        return Some(expand_command(
+            &config.command,
+            character,
+            port,
+            home.as_deref(),
+        ));
+    }
+    let script = find_lich(home.as_deref())?;
+    Some(vec![
+        "ruby".to_string(),
+        script.to_string_lossy().into_owned(),
+        "--login".to_string(),
+        character.to_string(),
+        "--without-frontend".to_string(),
+        format!("--detachable-client={}", port),
+    ])
+}
+
+/// Make sure something is listening on the Lich port, starting Lich if the
+/// user agrees (or `auto_launch = "always"`)
+///
+/// Never fails startup: if Lich can't be started, the connection attempt
+/// that follows reports the problem as it always has.
+pub fn ensure_lich(
+    config: &crate::config::Config,
+    character: Option<&str>,
+    log_path: &Path,
+) -> Result<()> {
+    let launch = &config.lich;
+    let host = config.connection.host.as_str();
+    let port = config.connection.port;
+    if launch.auto_launch == "never" || !is_local(host) || port_open(host, port) {
+        return Ok(());
+    }
+
+    let Some(character) = character.or(config.connection.character.as_deref()) else {
+        eprintln!("Nothing is listening on {}:{}. Start Lich, or pass --character so it can be started for you.", host, port);
+        return Ok(());
+    };
+    let Some(command) = lich_command(launch, character, port) else {
+        eprintln!(
+            "Nothing is listening on {}:{}, and no lich.rbw was found.",
+            host, port
+        );
+        eprintln!(
+            "Start Lich with --detachable-client={}, or set `command` under [lich] in config.toml.",
+            port
+        );
+        return Ok(());
+    };
+
+    let shown = command.join(" ");
+    if launch.auto_launch != "always" {
+        if !std::io::stdin().is_terminal() {
+            return Ok(());
+        }
+        eprintln!("Nothing is listening on {}:{}.", host, port);
+        eprint!("Start Lich with `{}`? [Y/n] ", shown);
+        let _ = std::io::stderr().flush();
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer)?;
+        if matches!(answer.trim().to_lowercase().as_str(), "n" | "no") {
+            return Ok(());
+        }
+    }
+
+    let log = std::fs::File::create(log_path)
+        .with_context(|| format!("Failed to create {}", log_path.display()))?;
+    let spawned = std::process::Command::new(&command[0])
+        .args(&command[1..])
+        .stdin(std::process::Stdio::null())
+        .stdout(log.try_clone()?)
+        .stderr(log)
+        .spawn();
+    let mut child = match spawned {
+        Ok(child) => child,
+        Err(e) => {
+            eprintln!("Failed to start `{}`: {}", shown, e);
+            return Ok(());
+        }
+    };
+    tracing::info!("Started Lich (PID {}): {}", child.id(), shown);
+
+    eprint!("Waiting for Lich on port {}", port);
+    let deadline = Instant::now() + Duration::from_secs(launch.wait_secs);
+    while Instant::now() < deadline {
+        if port_open(host, port) {
+            eprintln!(" ready.");
+            return Ok(());
+        }
+        if let Ok(Some(status)) = child.try_wait() {
+            eprintln!();
+            eprintln!("Lich exited ({}); see {}", status, log_path.display());
+            return Ok(());
+        }
+        eprint!(".");
+        let _ = std::io::stderr().flush();
+        std::thread::sleep(Duration::from_millis(500));
+    }
+    eprintln!();
+    eprintln!(
+        "Lich didn't open port {} within {}s; see {}",
+        port,
+        launch.wait_secs,
+        log_path.display()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_command_quoting() {
+        assert_eq!(
+            split_command(r#"ruby "C:\Lich 5\lich.rbw" --login {character}  '--x=a b'"#),
+            vec![
+                "ruby",
+                r"C:\Lich 5\lich.rbw",
+                "--login",
+                "{character}",
+                "--x=a b"
+            ]
+        );
+        assert_eq!(split_command(r#"a "" b"#), vec!["a", "", "b"]);
+    }
+
+    #[test]
+    fn test_expand_command_placeholders_and_home() {
+        assert_eq!(
+            expand_command(
+                "ruby ~/lich5/lich.rbw --login {character} --detachable-client={port}",
+                "Ann",
+                8001,
+                Some(Path::new("/home/ann"))
+            ),
+            vec![
+                "ruby",
+                "/home/ann/lich5/lich.rbw",
+                "--login",
+                "Ann",
+                "--detachable-client=8001"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_lich() {
+        let home = std::env::temp_dir().join(format!("two-face-lich-test-{}", std::process::id()));
+        std::fs::create_dir_all(home.join("Lich5")).unwrap();
+        assert_eq!(find_lich(Some(&home)), None);
+        std::fs::write(home.join("Lich5/lich.rbw"), "").unwrap();
+        assert_eq!(find_lich(Some(&home)), Some(home.join("Lich5/lich.rbw")));
+        std::fs::remove_dir_all(&home).ok();
+    }
+
+    #[test]
+    fn test_port_open() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        assert!(port_open("127.0.0.1", port));
+        drop(listener);
+        assert!(!port_open("127.0.0.1", port));
+    }
+
+    #[test]
+    fn test_is_local() {
+        assert!(is_local("localhost"));
+        assert!(!is_local("gs4.play.net"));
+    }
+}
@@ -2,23 +2,37 @@
 //!
 //! Supports both TUI (ratatui) and GUI (egui) frontends with shared core logic.
 
+mod bench;
 mod clipboard;
 mod cmdlist;
 mod config;
 mod core;
 mod data;
 mod frontend;
+mod http;
+mod instance_lock;
+mod ipc;
+mod lich_launch;
 mod network;
 mod parser;
 mod performance;
+mod plugins;
 mod selection;
 mod sound;
+mod passthrough;
+mod push;
+mod relay;
+mod spectator;
+mod stt;
 mod theme;
+mod token;
 mod tts;
+mod websocket;
 
 use anyhow::{bail, Context, Result};
 use clap::{Parser as ClapParser, Subcommand};
 use frontend::Frontend;
+use std::io::IsTerminal;
 use std::path::PathBuf;
 
 #[derive(ClapParser)]
@@ -74,6 +88,10 @@ struct Cli {
     #[arg(long)]
     nomusic: bool,
 
+    /// Start even if another instance is running for this character
+    #[arg(long)]
+    force_instance: bool,
+
     #[command(subcommand)]
     command: Option<Commands>,
 }
@@ -150,68 +168,6 @@ fn build_direct_config(
     }))
 }
 
-/// Convert KeyCode + KeyModifiers to a string format matching the keybind HashMap
-///
-/// NOTE: Crossterm doesn't distinguish numpad from regular number keys.
-/// So we format keys as-is. Numpad-specific keybinds (num_0, etc.) won't match.
-fn format_key_for_keybind(
-    code: crossterm::event::KeyCode,
-    modifiers: crossterm::event::KeyModifiers,
-) -> String {
-    use crossterm::event::KeyCode;
-
-    let base_key = match code {
-        KeyCode::Char(c) => {
-            // Return character as-is (don't map to numpad format)
-            if modifiers.is_empty() {
-                return c.to_string();
-            } else {
-                return format!("{}+{}", format_modifiers(modifiers), c);
-            }
-        }
-        KeyCode::Enter => "enter",
-        KeyCode::Esc => "esc",
-        KeyCode::Tab => "tab",
-        KeyCode::Backspace => "backspace",
-        KeyCode::Delete => "delete",
-        KeyCode::Up => "up",
-        KeyCode::Down => "down",
-        KeyCode::Left => "left",
-        KeyCode::Right => "right",
-        KeyCode::Home => "home",
-        KeyCode::End => "end",
-        KeyCode::PageUp => "pageup",
-        KeyCode::PageDown => "pagedown",
-        KeyCode::F(n) => return format!("f{}", n),
-        _ => return String::new(), // Unhandled key
-    };
-
-    // Add modifiers if present
-    if modifiers.is_empty() {
-        base_key.to_string()
-    } else {
-        format!("{}+{}", format_modifiers(modifiers), base_key)
-    }
-}
-
-/// Format modifiers as a string (helper for format_key_for_keybind)
-fn format_modifiers(modifiers: crossterm::event::KeyModifiers) -> String {
-    use crossterm::event::KeyModifiers;
-    let mut parts = Vec::new();
-
-    if modifiers.contains(KeyModifiers::CONTROL) {
-        parts.push("ctrl");
-    }
-    if modifiers.contains(KeyModifiers::ALT) {
-        parts.push("alt");
-    }
-    if modifiers.contains(KeyModifiers::SHIFT) {
-        parts.push("shift");
-    }
-
-    parts.join("+")
-}
-
 /// Build windows submenu
 fn build_windows_submenu(_app_core: &core::AppCore) -> Vec<data::ui_state::PopupMenuItem> {
     let items = vec![
@@ -289,213 +245,100 @@ fn build_config_submenu() -> Vec<data::ui_state::PopupMenuItem> {
     ]
 }
 
-/// Build settings items from config
+/// Build settings items from the typed settings registry plus every other config field
+///
+/// Registry entries come first within each category; fields found by
+/// reflection follow, and categories the registry doesn't know go last.
 fn build_settings_items(
     config: &config::Config,
 ) -> Vec<frontend::tui::settings_editor::SettingItem> {
-    use frontend::tui::settings_editor::{SettingItem, SettingValue};
-
-    let mut items = Vec::new();
-
-    // Connection settings
-    items.push(SettingItem {
-        category: "Connection".to_string(),
-        key: "connection.host".to_string(),
-        display_name: "Host".to_string(),
-        value: SettingValue::String(config.connection.host.clone()),
-        description: Some("Game server hostname or IP address".to_string()),
-        editable: true,
-        name_width: None,
-    });
-
-    items.push(SettingItem {
-        category: "Connection".to_string(),
-        key: "connection.port".to_string(),
-        display_name: "Port".to_string(),
-        value: SettingValue::Number(config.connection.port as i64),
-        description: Some("Game server port number".to_string()),
-        editable: true,
-        name_width: None,
-    });
-
-    if let Some(ref character) = config.connection.character {
-        items.push(SettingItem {
-            category: "Connection".to_string(),
-            key: "connection.character".to_string(),
-            display_name: "Character".to_string(),
-            value: SettingValue::String(character.clone()),
-            description: Some("Default character name".to_string()),
+    use config::settings::{ApplyMode, SOUNDS_PREFIX};
+    use frontend::tui::settings_editor::SettingItem;
+
+    let mut items: Vec<SettingItem> = config::settings::all()
+        .iter()
+        .map(|def| SettingItem {
+            category: def.category.to_string(),
+            key: def.key.to_string(),
+            display_name: def.display_name.to_string(),
+            value: def.value(config),
+            description: Some(def.description.to_string()),
             editable: true,
             name_width: None,
-        });
+            restart_required: def.apply == ApplyMode::Restart,
+        })
+        .collect();
+    items.extend(
+        config::settings::reflected(config)
+            .into_iter()
+            .map(|setting| {
+                let sounds = setting.key.starts_with(SOUNDS_PREFIX);
+                let file = if sounds { "sounds.toml" } else { "config.toml" };
+                SettingItem {
+                    description: Some(format!("{} in {}", setting.key, file)),
+                    category: setting.category,
+                    key: setting.key,
+                    display_name: setting.display_name,
+                    value: setting.value,
+                    editable: true,
+                    name_width: None,
+                    restart_required: !sounds,
+                }
+            }),
+    );
+
+    // The editor draws a header whenever the category changes, so group them
+    let mut order: Vec<String> = Vec::new();
+    for item in &items {
+        if !order.contains(&item.category) {
+            order.push(item.category.clone());
+        }
     }
+    items.sort_by_key(|item| order.iter().position(|c| *c == item.category));
+    items
+}
 
-    // UI settings
-    items.push(SettingItem {
-        category: "UI".to_string(),
-        key: "ui.buffer_size".to_string(),
-        display_name: "Buffer Size".to_string(),
-        value: SettingValue::Number(config.ui.buffer_size as i64),
-        description: Some("Number of lines to keep in text window buffers".to_string()),
-        editable: true,
-        name_width: None,
-    });
-
-    items.push(SettingItem {
-        category: "UI".to_string(),
-        key: "ui.show_timestamps".to_string(),
-        display_name: "Show Timestamps".to_string(),
-        value: SettingValue::Boolean(config.ui.show_timestamps),
-        description: Some("Display timestamps in text windows".to_string()),
-        editable: true,
-        name_width: None,
-    });
-
-    items.push(SettingItem {
-        category: "UI".to_string(),
-        key: "ui.border_style".to_string(),
-        display_name: "Border Style".to_string(),
-        value: SettingValue::Enum(
-            config.ui.border_style.clone(),
-            vec![
-                "single".to_string(),
-                "double".to_string(),
-                "rounded".to_string(),
-                "thick".to_string(),
-                "none".to_string(),
-            ],
-        ),
-        description: Some("Widget border style".to_string()),
-        editable: true,
-        name_width: None,
-    });
-
-    items.push(SettingItem {
-        category: "UI".to_string(),
-        key: "ui.countdown_icon".to_string(),
-        display_name: "Countdown Icon".to_string(),
-        value: SettingValue::String(config.ui.countdown_icon.clone()),
-        description: Some("Unicode character for countdown blocks".to_string()),
-        editable: true,
-        name_width: None,
-    });
-
-    items.push(SettingItem {
-        category: "UI".to_string(),
-        key: "ui.poll_timeout_ms".to_string(),
-        display_name: "Poll Timeout (ms)".to_string(),
-        value: SettingValue::Number(config.ui.poll_timeout_ms as i64),
-        description: Some("Event poll timeout - lower = higher FPS, higher CPU".to_string()),
-        editable: true,
-        name_width: None,
-    });
-
-    items.push(SettingItem {
-        category: "UI".to_string(),
-        key: "ui.startup_music".to_string(),
-        display_name: "Startup Music".to_string(),
-        value: SettingValue::Boolean(config.ui.startup_music),
-        description: Some("Play music when connecting".to_string()),
-        editable: true,
-        name_width: None,
-    });
-
-    items.push(SettingItem {
-        category: "UI".to_string(),
-        key: "ui.startup_music_file".to_string(),
-        display_name: "Startup Music File".to_string(),
-        value: SettingValue::String(config.ui.startup_music_file.clone()),
-        description: Some("Sound file to play on startup (without extension)".to_string()),
-        editable: true,
-        name_width: None,
-    });
-
-    items.push(SettingItem {
-        category: "UI".to_string(),
-        key: "ui.selection_enabled".to_string(),
-        display_name: "Selection Enabled".to_string(),
-        value: SettingValue::Boolean(config.ui.selection_enabled),
-        description: Some("Enable text selection with mouse".to_string()),
-        editable: true,
-        name_width: None,
-    });
-
-    items.push(SettingItem {
-        category: "UI".to_string(),
-        key: "ui.selection_respect_window_boundaries".to_string(),
-        display_name: "Selection Respects Windows".to_string(),
-        value: SettingValue::Boolean(config.ui.selection_respect_window_boundaries),
-        description: Some("Prevent selection from crossing window boundaries".to_string()),
-        editable: true,
-        name_width: None,
-    });
-
-    items.push(SettingItem {
-        category: "UI".to_string(),
-        key: "ui.drag_modifier_key".to_string(),
-        display_name: "Drag Modifier Key".to_string(),
-        value: SettingValue::Enum(
-            config.ui.drag_modifier_key.clone(),
-            vec!["ctrl".to_string(), "alt".to_string(), "shift".to_string()],
-        ),
-        description: Some("Modifier key required for drag and drop".to_string()),
-        editable: true,
-        name_width: None,
-    });
-
-    items.push(SettingItem {
-        category: "UI".to_string(),
-        key: "ui.min_command_length".to_string(),
-        display_name: "Min Command Length".to_string(),
-        value: SettingValue::Number(config.ui.min_command_length as i64),
-        description: Some("Minimum command length to save to history".to_string()),
-        editable: true,
-        name_width: None,
-    });
-
-    // Sound settings
-    items.push(SettingItem {
-        category: "Sound".to_string(),
-        key: "sound.enabled".to_string(),
-        display_name: "Sound Enabled".to_string(),
-        value: SettingValue::Boolean(config.sound.enabled),
-        description: Some("Enable sound effects".to_string()),
-        editable: true,
-        name_width: None,
-    });
-
-    items.push(SettingItem {
-        category: "Sound".to_string(),
-        key: "sound.volume".to_string(),
-        display_name: "Master Volume".to_string(),
-        value: SettingValue::Float(config.sound.volume as f64),
-        description: Some("Master volume (0.0 to 1.0)".to_string()),
-        editable: true,
-        name_width: None,
-    });
-
-    items.push(SettingItem {
-        category: "Sound".to_string(),
-        key: "sound.cooldown_ms".to_string(),
-        display_name: "Sound Cooldown (ms)".to_string(),
-        value: SettingValue::Number(config.sound.cooldown_ms as i64),
-        description: Some("Cooldown between same sound plays".to_string()),
-        editable: true,
-        name_width: None,
-    });
-
-    // Theme settings
-    items.push(SettingItem {
-        category: "Theme".to_string(),
-        key: "active_theme".to_string(),
-        display_name: "Active Theme".to_string(),
-        value: SettingValue::String(config.active_theme.clone()),
-        description: Some("Currently active color theme".to_string()),
-        editable: true,
-        name_width: None,
-    });
+/// Validate and apply edits made in the settings editor
+///
+/// Invalid values are reverted in the editor with an explanation; valid ones
+/// are saved, applied live where possible, and flagged when a restart is needed.
+fn apply_settings_changes(
+    app_core: &mut core::AppCore,
+    frontend: &mut frontend::tui::TuiFrontend,
+) -> Result<()> {
+    let changes = match frontend.settings_editor {
+        Some(ref mut editor) => editor.take_changes(),
+        None => return Ok(()),
+    };
 
-    items
+    for change in changes {
+        let name = config::settings::find(&change.key)
+            .map(|def| def.display_name)
+            .unwrap_or(change.key.as_str())
+            .to_string();
+        match app_core.apply_setting(&change.key, &change.value) {
+            Ok(applied) => {
+                frontend.apply_settings(&app_core.config);
+                if let Some(action) = applied.follow_up {
+                    handle_menu_action(app_core, frontend, &action)?;
+                }
+                let status = if applied.restart_required {
+                    format!("Saved {} - restart required", name)
+                } else {
+                    format!("Applied {}", name)
+                };
+                if let Some(ref mut editor) = frontend.settings_editor {
+                    editor.set_status(Some(status));
+                }
+            }
+            Err(e) => {
+                if let Some(ref mut editor) = frontend.settings_editor {
+                    editor.reject_change(&change, e.to_string());
+                }
+            }
+        }
+    }
+    Ok(())
 }
 
 /// Build layouts submenu
@@ -581,9 +424,47 @@ fn build_widget_category_picker(app_core: &core::AppCore, category: &str) -> Vec
     let category_windows: Vec<&str> = match category {
         "countdown" => vec!["roundtime", "casttime", "stuntime"],
         "hand" => vec!["left_hand", "right_hand", "spell_hand"],
-        "other" => vec!["compass", "inventory", "room", "spells", "injuries", "spacer", "quickbar"],
-        "progressbar" => vec!["health", "mana", "stamina", "spirit", "encumlevel", "pbarStance", "mindState", "lblBPs"],
-        "text" => vec!["thoughts", "speech", "announcements", "loot", "death", "logons", "familiar", "ambients", "bounty", "society"],
+        "other" => vec![
+            "compass",
+            "inventory",
+            "room",
+            "spells",
+            "injuries",
+            "spacer",
+            "quickbar",
+            "container",
+            "loot_tracker",
+            "session_stats",
+            "bounty_tracker",
+            "containers",
+            "watch_list",
+            "art",
+            "status_line",
+            "plugin",
+        ],
+        "progressbar" => vec![
+            "health",
+            "mana",
+            "stamina",
+            "spirit",
+            "encumlevel",
+            "pbarStance",
+            "mindState",
+            "lblBPs",
+            "vitals",
+        ],
+        "text" => vec![
+            "thoughts",
+            "speech",
+            "announcements",
+            "loot",
+            "death",
+            "logons",
+            "familiar",
+            "ambients",
+            "bounty",
+            "society",
+        ],
         _ => vec![],
     };
 
@@ -664,257 +545,520 @@ fn build_hidewindow_picker(app_core: &core::AppCore) -> Vec<data::ui_state::Popu
 }
 
 /// Handle menu action commands
+///
+/// Menu items, dot commands, keybinds, and custom actions all name an action
+/// ("action:settings", "nexttab", "my_custom_action"); resolve it through the
+/// action registry and dispatch it.
 fn handle_menu_action(
     app_core: &mut core::AppCore,
     frontend: &mut frontend::tui::TuiFrontend,
     command: &str,
 ) -> Result<()> {
-    if command.starts_with("action:loadlayout:") {
-        // Load a layout with proper terminal size
-        let layout_name = &command[18..];
-        tracing::info!("[MAIN.RS] Menu action loadlayout: '{}'", layout_name);
-        let (width, height) = frontend.size();
-        tracing::info!(
-            "[MAIN.RS] Terminal size from frontend: {}x{}",
-            width,
-            height
-        );
-        if let Some((theme_id, theme)) = app_core.load_layout(layout_name, width, height) {
-            frontend.update_theme_cache(theme_id, theme);
+    match core::actions::AppAction::resolve(command, &app_core.config.actions) {
+        Some(action) => dispatch_action(app_core, frontend, action),
+        None => {
+            tracing::warn!("Unknown menu action: {}", command);
+            Ok(())
         }
-    } else if command.starts_with("action:createwindow:") {
-        // Create a new window with the specified widget type
-        let widget_type = &command[20..];
-
-        // Get template for this widget type (use widget type name as template name)
-        if let Some(template) = config::Config::get_window_template(widget_type) {
-            // Open window editor with template (proper defaults + marked as new)
-            // Use new_window_with_layout for spacers to enable auto-naming
-            frontend.window_editor = Some(
-                frontend::tui::window_editor::WindowEditor::new_window_with_layout(
-                    widget_type.to_string(),
-                    &app_core.layout,
-                ),
-            );
-            app_core.ui_state.input_mode = data::ui_state::InputMode::WindowEditor;
-        } else {
-            tracing::warn!("No template found for widget type: {}", widget_type);
-        }
-    } else if command.starts_with("action:editwindow:") {
-        // Edit an existing window
-        let window_name = &command[18..];
-
-        // Find the window definition
-        if let Some(window_def) = app_core
-            .layout
-            .windows
-            .iter()
-            .find(|w| w.name() == window_name)
-            .cloned()
-        {
-            // Open window editor
-            frontend.window_editor =
-                Some(frontend::tui::window_editor::WindowEditor::new(window_def));
-            app_core.ui_state.input_mode = data::ui_state::InputMode::WindowEditor;
-        } else {
-            tracing::warn!("Window not found for editing: {}", window_name);
-        }
-    } else if command.starts_with("action:showwindow:") {
-        // Add/show the window (from template)
-        let window_name = &command[18..];
-
-        // Get terminal size for window positioning
-        let (width, height) = frontend.size();
-
-        // Show window from layout template
-        app_core.show_window(window_name, width, height);
-
-        // Close menus
-        app_core.ui_state.popup_menu = None;
-        app_core.ui_state.submenu = None;
-        app_core.ui_state.input_mode = data::ui_state::InputMode::Normal;
-        app_core.needs_render = true;
-    } else if command.starts_with("action:addwindow:") {
-        // Show submenu with windows for this category
-        let category = &command[17..];
-
-        // Close main menu, show submenu with windows in this category
-        app_core.ui_state.popup_menu = None;
-        app_core.ui_state.submenu = Some(data::ui_state::PopupMenu::new(
-            build_widget_category_picker(app_core, category),
-            (40, 12),
-        ));
-        app_core.ui_state.input_mode = data::ui_state::InputMode::Menu;
-    } else if command.starts_with("action:hidewindow:") {
-        // Hide a visible window
-        let window_name = &command[18..];
-        app_core.hide_window(window_name);
-    } else {
-        match command {
-            "action:addwindow" => {
-                // Close submenu if it exists
-                app_core.ui_state.submenu = None;
-                // Show widget type picker
-                app_core.ui_state.popup_menu = Some(data::ui_state::PopupMenu::new(
-                    build_widget_picker(app_core),
-                    (40, 12),
-                ));
-                // Stay in Menu mode
-                app_core.ui_state.input_mode = data::ui_state::InputMode::Menu;
-            }
-            "action:hidewindow" => {
-                // Close submenu if it exists
-                app_core.ui_state.submenu = None;
-                // Show window picker for hiding
-                app_core.ui_state.popup_menu = Some(data::ui_state::PopupMenu::new(
-                    build_hidewindow_picker(app_core),
-                    (40, 12),
-                ));
-                // Stay in Menu mode
-                app_core.ui_state.input_mode = data::ui_state::InputMode::Menu;
-            }
-            "action:listwindows" => {
-                // List all windows
-                app_core.send_command(".windows".to_string())?;
-            }
-            "action:highlights" => {
-                // Open highlight browser
-                frontend.highlight_browser =
-                    Some(frontend::tui::highlight_browser::HighlightBrowser::new(
-                        &app_core.config.highlights,
-                    ));
-                app_core.ui_state.input_mode = data::ui_state::InputMode::HighlightBrowser;
-            }
-            "action:addhighlight" => {
-                // Open highlight form for creating new highlight
-                frontend.highlight_form =
-                    Some(frontend::tui::highlight_form::HighlightFormWidget::new());
-                app_core.ui_state.input_mode = data::ui_state::InputMode::HighlightForm;
+    }
+}
+
+/// After a form edits highlights/keybinds/colors, show what would be written
+///
+/// Only when `ui.confirm_config_writes` is on and something actually differs
+/// from disk; otherwise the edit is written right away.
+fn offer_config_review(app_core: &mut core::AppCore, frontend: &mut frontend::tui::TuiFrontend) {
+    if !app_core.config.ui.confirm_config_writes {
+        if let Err(e) = app_core.save_config() {
+            app_core.add_system_message(&format!("Failed to save config: {}", e));
+        }
+        return;
+    }
+    match config::diff::pending_changes(&app_core.config) {
+        Ok(changes) if !changes.is_empty() => {
+            frontend.config_diff = Some(frontend::tui::config_diff::ConfigDiffView::new(changes));
+            app_core.ui_state.input_mode = data::ui_state::InputMode::ConfigDiff;
+        }
+        Ok(_) => {}
+        Err(e) => tracing::warn!("Failed to compare config files: {}", e),
+    }
+}
+
+/// A key while in copy mode: move, yank or leave
+fn handle_copy_mode_key(
+    app_core: &mut core::AppCore,
+    frontend: &mut frontend::tui::TuiFrontend,
+    code: crossterm::event::KeyCode,
+    modifiers: crossterm::event::KeyModifiers,
+) {
+    use frontend::tui::copy_mode::CopyModeOutcome;
+    match frontend.copy_mode_key(code, modifiers) {
+        Some(CopyModeOutcome::Moved) => {
+            app_core.ui_state.selection_state = frontend.copy_mode_selection();
+        }
+        Some(CopyModeOutcome::Ignored) => return,
+        Some(CopyModeOutcome::Yank) => {
+            if let Some(copy) = frontend.copy_mode_yank() {
+                match clipboard::copy(&copy.plain) {
+                    Ok(()) => app_core.add_system_message(&format!(
+                        "Copied {} characters",
+                        copy.plain.chars().count()
+                    )),
+                    Err(e) => app_core.add_system_message(&format!("Failed to copy: {}", e)),
+                }
+                app_core.ui_state.last_copy = Some(copy);
             }
-            "action:keybinds" => {
-                // Open keybind browser
-                frontend.keybind_browser = Some(
-                    frontend::tui::keybind_browser::KeybindBrowser::new(&app_core.config.keybinds),
-                );
-                app_core.ui_state.input_mode = data::ui_state::InputMode::KeybindBrowser;
+            exit_copy_mode(app_core, frontend);
+        }
+        Some(CopyModeOutcome::Exit) | None => exit_copy_mode(app_core, frontend),
+    }
+    app_core.needs_render = true;
+}
+
+fn exit_copy_mode(app_core: &mut core::AppCore, frontend: &mut frontend::tui::TuiFrontend) {
+    frontend.end_copy_mode();
+    app_core.ui_state.selection_state = None;
+    app_core.ui_state.input_mode = data::ui_state::InputMode::Normal;
+}
+
+/// Run a single resolved action
+fn dispatch_action(
+    app_core: &mut core::AppCore,
+    frontend: &mut frontend::tui::TuiFrontend,
+    action: core::actions::AppAction,
+) -> Result<()> {
+    use core::actions::AppAction;
+
+    match action {
+        AppAction::Key(key_action) => {
+            app_core.execute_key_action(key_action)?;
+            app_core.needs_render = true;
+        }
+        AppAction::SetMode(mode) => {
+            app_core.set_keybind_mode(mode.as_deref());
+        }
+        AppAction::Custom(name) => {
+            // Expand fully before running so a bad step doesn't leave things half-done
+            match core::actions::expand_custom_action(&name, &app_core.config.actions) {
+                Ok(steps) => {
+                    for step in steps {
+                        dispatch_action(app_core, frontend, step)?;
+                    }
+                }
+                Err(e) => {
+                    app_core.add_system_message(&e.to_string());
+                }
             }
-            "action:addkeybind" => {
-                // Open keybind form for creating new keybind
-                frontend.keybind_form = Some(frontend::tui::keybind_form::KeybindFormWidget::new());
-                app_core.ui_state.input_mode = data::ui_state::InputMode::KeybindForm;
+        }
+        AppAction::LoadLayout(layout_name) => {
+            // Load a layout with proper terminal size
+            tracing::info!("[MAIN.RS] Menu action loadlayout: '{}'", layout_name);
+            let (width, height) = frontend.size();
+            tracing::info!(
+                "[MAIN.RS] Terminal size from frontend: {}x{}",
+                width,
+                height
+            );
+            if let Some((theme_id, theme)) = app_core.load_layout(&layout_name, width, height) {
+                frontend.update_theme_cache(theme_id, theme);
             }
-            "action:colors" => {
-                // Open color palette browser
-                frontend.color_palette_browser = Some(
-                    frontend::tui::color_palette_browser::ColorPaletteBrowser::new(
-                        app_core.config.colors.color_palette.clone(),
+        }
+        AppAction::CreateWindow(widget_type) => {
+            // Create a new window with the specified widget type
+
+            // Get template for this widget type (use widget type name as template name)
+            if config::Config::get_window_template(&widget_type).is_some() {
+                // Open window editor with template (proper defaults + marked as new)
+                // Use new_window_with_layout for spacers to enable auto-naming
+                frontend.window_editor = Some(
+                    frontend::tui::window_editor::WindowEditor::new_window_with_layout(
+                        widget_type,
+                        &app_core.layout,
                     ),
                 );
-                app_core.ui_state.input_mode = data::ui_state::InputMode::ColorPaletteBrowser;
-            }
-            "action:addcolor" => {
-                // Open color form for creating new palette color
-                frontend.color_form = Some(frontend::tui::color_form::ColorForm::new_create());
-                app_core.ui_state.input_mode = data::ui_state::InputMode::ColorForm;
+                app_core.ui_state.input_mode = data::ui_state::InputMode::WindowEditor;
+            } else {
+                tracing::warn!("No template found for widget type: {}", widget_type);
             }
-            "action:uicolors" => {
-                // Open UI colors browser
-                frontend.uicolors_browser = Some(
-                    frontend::tui::uicolors_browser::UIColorsBrowser::new(&app_core.config.colors),
-                );
-                app_core.ui_state.input_mode = data::ui_state::InputMode::UIColorsBrowser;
+        }
+        AppAction::EditWindow(Some(window_name)) => {
+            // Edit an existing window
+
+            // Find the window definition
+            if let Some(window_def) = app_core
+                .layout
+                .windows
+                .iter()
+                .find(|w| w.name() == window_name)
+                .cloned()
+            {
+                // Open window editor
+                let mut editor = frontend::tui::window_editor::WindowEditor::new(window_def);
+                editor.set_container(app_core.layout.container_of(&window_name));
+                frontend.window_editor = Some(editor);
+                app_core.ui_state.input_mode = data::ui_state::InputMode::WindowEditor;
+            } else {
+                tracing::warn!("Window not found for editing: {}", window_name);
             }
-            "action:spellcolors" => {
-                // Open spell colors browser
-                frontend.spell_color_browser =
-                    Some(frontend::tui::spell_color_browser::SpellColorBrowser::new(
-                        &app_core.config.colors.spell_colors,
-                    ));
-                app_core.ui_state.input_mode = data::ui_state::InputMode::SpellColorsBrowser;
+        }
+        AppAction::ShowWindow(window_name) => {
+            // Add/show the window (from template)
+
+            // Get terminal size for window positioning
+            let (width, height) = frontend.size();
+
+            // Show window from layout template
+            app_core.show_window(&window_name, width, height);
+
+            // Close menus
+            app_core.ui_state.popup_menu = None;
+            app_core.ui_state.submenu = None;
+            app_core.ui_state.input_mode = data::ui_state::InputMode::Normal;
+            app_core.needs_render = true;
+        }
+        AppAction::AddWindow(Some(category)) => {
+            // Show submenu with windows for this category
+
+            // Close main menu, show submenu with windows in this category
+            app_core.ui_state.popup_menu = None;
+            app_core.ui_state.submenu = Some(data::ui_state::PopupMenu::new(
+                build_widget_category_picker(app_core, &category),
+                (40, 12),
+            ));
+            app_core.ui_state.input_mode = data::ui_state::InputMode::Menu;
+        }
+        AppAction::HideWindow(Some(window_name)) => {
+            // Hide a visible window
+            app_core.hide_window(&window_name);
+        }
+        AppAction::AddWindow(None) => {
+            // Close submenu if it exists
+            app_core.ui_state.submenu = None;
+            // Show widget type picker
+            app_core.ui_state.popup_menu = Some(data::ui_state::PopupMenu::new(
+                build_widget_picker(app_core),
+                (40, 12),
+            ));
+            // Stay in Menu mode
+            app_core.ui_state.input_mode = data::ui_state::InputMode::Menu;
+        }
+        AppAction::HideWindow(None) => {
+            // Close submenu if it exists
+            app_core.ui_state.submenu = None;
+            // Show window picker for hiding
+            app_core.ui_state.popup_menu = Some(data::ui_state::PopupMenu::new(
+                build_hidewindow_picker(app_core),
+                (40, 12),
+            ));
+            // Stay in Menu mode
+            app_core.ui_state.input_mode = data::ui_state::InputMode::Menu;
+        }
+        AppAction::ListWindows => {
+            // List all windows
+            app_core.send_command(".windows".to_string())?;
+        }
+        AppAction::Highlights => {
+            // Open highlight browser
+            frontend.highlight_browser =
+                Some(frontend::tui::highlight_browser::HighlightBrowser::new(
+                    &app_core.config.highlights,
+                ));
+            app_core.ui_state.input_mode = data::ui_state::InputMode::HighlightBrowser;
+        }
+        AppAction::AddHighlight => {
+            // Open highlight form for creating new highlight
+            frontend.highlight_form =
+                Some(frontend::tui::highlight_form::HighlightFormWidget::new());
+            app_core.ui_state.input_mode = data::ui_state::InputMode::HighlightForm;
+        }
+        AppAction::Keybinds => {
+            // Open keybind browser
+            frontend.keybind_browser = Some(frontend::tui::keybind_browser::KeybindBrowser::new(
+                &app_core.config.keybinds,
+            ));
+            app_core.ui_state.input_mode = data::ui_state::InputMode::KeybindBrowser;
+        }
+        AppAction::AddKeybind => {
+            // Open keybind form for creating new keybind
+            frontend.keybind_form = Some(frontend::tui::keybind_form::KeybindFormWidget::new());
+            app_core.ui_state.input_mode = data::ui_state::InputMode::KeybindForm;
+        }
+        AppAction::Colors => {
+            // Open color palette browser
+            frontend.color_palette_browser = Some(
+                frontend::tui::color_palette_browser::ColorPaletteBrowser::new(
+                    app_core.config.colors.color_palette.clone(),
+                ),
+            );
+            app_core.ui_state.input_mode = data::ui_state::InputMode::ColorPaletteBrowser;
+        }
+        AppAction::AddColor => {
+            // Open color form for creating new palette color
+            frontend.color_form = Some(frontend::tui::color_form::ColorForm::new_create());
+            app_core.ui_state.input_mode = data::ui_state::InputMode::ColorForm;
+        }
+        AppAction::UiColors => {
+            // Open UI colors browser
+            frontend.uicolors_browser = Some(
+                frontend::tui::uicolors_browser::UIColorsBrowser::new(&app_core.config.colors),
+            );
+            app_core.ui_state.input_mode = data::ui_state::InputMode::UIColorsBrowser;
+        }
+        AppAction::SpellColors => {
+            // Open spell colors browser
+            frontend.spell_color_browser =
+                Some(frontend::tui::spell_color_browser::SpellColorBrowser::new(
+                    &app_core.config.colors.spell_colors,
+                ));
+            app_core.ui_state.input_mode = data::ui_state::InputMode::SpellColorsBrowser;
+        }
+        AppAction::AddSpellColor => {
+            // Open spell color form for creating new spell color
+            frontend.spell_color_form =
+                Some(frontend::tui::spell_color_form::SpellColorFormWidget::new());
+            app_core.ui_state.input_mode = data::ui_state::InputMode::SpellColorForm;
+        }
+        AppAction::ConfigDiff => match config::diff::pending_changes(&app_core.config) {
+            Ok(changes) => {
+                frontend.config_diff =
+                    Some(frontend::tui::config_diff::ConfigDiffView::new(changes));
+                app_core.ui_state.input_mode = data::ui_state::InputMode::ConfigDiff;
             }
-            "action:addspellcolor" => {
-                // Open spell color form for creating new spell color
-                frontend.spell_color_form =
-                    Some(frontend::tui::spell_color_form::SpellColorFormWidget::new());
-                app_core.ui_state.input_mode = data::ui_state::InputMode::SpellColorForm;
+            Err(e) => app_core.add_system_message(&format!("Can't compare config files: {}", e)),
+        },
+        AppAction::Settings => {
+            // Open settings editor
+            let settings_items = build_settings_items(&app_core.config);
+            frontend.settings_editor = Some(frontend::tui::settings_editor::SettingsEditor::new(
+                settings_items,
+            ));
+            app_core.ui_state.input_mode = data::ui_state::InputMode::SettingsEditor;
+        }
+        AppAction::TtsSettings => {
+            let mut editor = frontend::tui::settings_editor::SettingsEditor::new(
+                build_settings_items(&app_core.config),
+            );
+            editor.set_category_filter(Some("TTS".to_string()));
+            frontend.settings_editor = Some(editor);
+            app_core.ui_state.input_mode = data::ui_state::InputMode::SettingsEditor;
+        }
+        AppAction::Themes => {
+            // Open theme browser (includes built-in and custom themes)
+            frontend.theme_browser = Some(frontend::tui::theme_browser::ThemeBrowser::new(
+                app_core.config.active_theme.clone(),
+                app_core.config.character.as_deref(),
+            ));
+            app_core.ui_state.input_mode = data::ui_state::InputMode::ThemeBrowser;
+        }
+        AppAction::More => {
+            frontend.acknowledge_more();
+            app_core.needs_render = true;
+        }
+        AppAction::Dump {
+            window,
+            file,
+            force,
+        } => match frontend.window_buffer_text(&window) {
+            Some(lines) => app_core.dump_window(&window, file.as_deref(), force, &lines),
+            None => {
+                app_core.add_system_message(&format!("Window '{}' has no text to dump", window))
             }
-            "action:settings" => {
-                // Open settings editor
-                let settings_items = build_settings_items(&app_core.config);
-                frontend.settings_editor = Some(
-                    frontend::tui::settings_editor::SettingsEditor::new(settings_items),
-                );
-                app_core.ui_state.input_mode = data::ui_state::InputMode::SettingsEditor;
+        },
+        AppAction::Search { window, pattern } => {
+            // Scripted search from .search <window> <pattern>
+            match frontend.execute_search(&window, &pattern) {
+                Ok(count) => {
+                    app_core.record_search(&window, &pattern);
+                    app_core.add_system_message(&format!(
+                        "Found {} matches for '{}' in {}",
+                        count, pattern, window
+                    ));
+                }
+                Err(e) => {
+                    app_core
+                        .add_system_message(&format!("Invalid search regex '{}': {}", pattern, e));
+                }
             }
-            "action:themes" => {
-                // Open theme browser (includes built-in and custom themes)
-                frontend.theme_browser = Some(frontend::tui::theme_browser::ThemeBrowser::new(
-                    app_core.config.active_theme.clone(),
-                    app_core.config.character.as_deref(),
-                ));
-                app_core.ui_state.input_mode = data::ui_state::InputMode::ThemeBrowser;
+            app_core.needs_render = true;
+        }
+        AppAction::SetTheme(theme_id) => {
+            // .settheme has already switched; keybinds and menus arrive here first
+            if theme_id == theme::terminal::TERMINAL_THEME {
+                return dispatch_action(app_core, frontend, AppAction::TerminalTheme);
             }
-            action if action.starts_with("action:settheme:") => {
-                // Update frontend theme cache when theme changes via .settheme command
-                let theme_id = action.strip_prefix("action:settheme:").unwrap().to_string();
-                let theme = app_core.config.get_theme();
-                frontend.update_theme_cache(theme_id, theme);
+            if app_core.config.active_theme != theme_id && !app_core.switch_theme(&theme_id) {
                 app_core.needs_render = true;
+                return Ok(());
             }
-            "action:edittheme" => {
-                // Open theme editor with current theme
-                let current_theme = app_core.config.get_theme();
-                frontend.theme_editor = Some(frontend::tui::theme_editor::ThemeEditor::new_edit(
-                    &current_theme,
-                ));
-                app_core.ui_state.input_mode = data::ui_state::InputMode::ThemeEditor;
+            // Update frontend theme cache with the now-active theme
+            let theme = app_core.config.get_theme();
+            frontend.update_theme_cache(theme_id, theme);
+            app_core.needs_render = true;
+        }
+        AppAction::TerminalTheme => {
+            // Query happens here: the main loop isn't reading input right now
+            match theme::terminal::refresh() {
+                Ok(theme) => {
+                    let theme_id = theme::terminal::TERMINAL_THEME.to_string();
+                    app_core.config.active_theme = theme_id.clone();
+                    if let Err(e) = app_core.save_config() {
+                        tracing::error!("Failed to save config after theme change: {}", e);
+                    }
+                    frontend.update_theme_cache(theme_id, theme);
+                    app_core.add_system_message("Theme switched to: terminal (palette refreshed)");
+                }
+                Err(e) => {
+                    app_core.add_system_message(&format!("Terminal theme unavailable: {}", e))
+                }
             }
-            "action:editwindow" => {
-                // Open window picker for editing
-                let window_names: Vec<String> = app_core
-                    .layout
-                    .windows
-                    .iter()
-                    .map(|w| w.name().to_string())
-                    .collect();
-
-                let items: Vec<data::ui_state::PopupMenuItem> = window_names
-                    .iter()
-                    .map(|name| data::ui_state::PopupMenuItem {
-                        text: name.clone(),
-                        command: format!("action:editwindow:{}", name),
-                        disabled: false,
-                    })
-                    .collect();
-
-                // Close submenu if it exists
-                app_core.ui_state.submenu = None;
-                // Create new popup menu for window selection
-                app_core.ui_state.popup_menu =
-                    Some(data::ui_state::PopupMenu::new(items, (40, 12)));
-                // Stay in Menu mode
-                app_core.ui_state.input_mode = data::ui_state::InputMode::Menu;
+            app_core.needs_render = true;
+        }
+        AppAction::MouseCapture(enabled) => {
+            let enabled = enabled.unwrap_or(!frontend.mouse_captured());
+            match frontend.set_mouse_capture(enabled) {
+                Ok(()) if enabled => app_core.add_system_message("Mouse capture on"),
+                Ok(()) => app_core.add_system_message(
+                    "Mouse capture off - terminal selection and paste work natively (.mouse on to restore)",
+                ),
+                Err(e) => {
+                    app_core.add_system_message(&format!("Failed to change mouse capture: {}", e))
+                }
             }
-            "action:nexttab" => {
-                // Navigate to next tab in all tabbed windows
-                frontend.next_tab_all();
-                app_core.needs_render = true;
+            app_core.needs_render = true;
+        }
+        AppAction::CopySelection(format) => match &app_core.ui_state.last_copy {
+            None => app_core.add_system_message("Nothing to copy - select some text first"),
+            Some(copy) => {
+                use crate::selection::CopyFormat;
+                let (result, label) = match format {
+                    CopyFormat::Plain => (clipboard::copy(&copy.plain), "text"),
+                    CopyFormat::Ansi => (clipboard::copy(&copy.ansi), "ANSI"),
+                    CopyFormat::Html => (clipboard::copy_html(&copy.html, &copy.plain), "HTML"),
+                };
+                let message = match result {
+                    Ok(()) => format!("Copied selection as {}", label),
+                    Err(e) => format!("Failed to copy selection: {}", e),
+                };
+                app_core.add_system_message(&message);
             }
-            "action:prevtab" => {
-                // Navigate to previous tab in all tabbed windows
-                frontend.prev_tab_all();
-                app_core.needs_render = true;
+        },
+        AppAction::CopyMode => {
+            // The focused text window, else main (indexed like the mouse selection)
+            let focused = app_core.get_focused_window_name();
+            let window_name = match app_core.ui_state.windows.get(&focused) {
+                Some(window) if matches!(window.content, data::WindowContent::Text(_)) => focused,
+                _ => "main".to_string(),
+            };
+            let mut window_names: Vec<&String> = app_core.ui_state.windows.keys().collect();
+            window_names.sort();
+            let window_index = window_names.iter().position(|name| **name == window_name);
+            match window_index {
+                Some(index) if frontend.start_copy_mode(&window_name, index) => {
+                    app_core.ui_state.input_mode = data::ui_state::InputMode::CopyMode;
+                    app_core.add_system_message(
+                        "Copy mode: arrows/hjkl move, v select, Ctrl+V block, y copy, Esc leave",
+                    );
+                }
+                _ => app_core.add_system_message(&format!("Nothing to copy in '{}'", window_name)),
             }
-            "action:gonew" => {
-                // Navigate to next tab with unread messages
-                if !frontend.go_to_next_unread_tab() {
-                    app_core.add_system_message("No tabs with new messages");
+            app_core.needs_render = true;
+        }
+        AppAction::Paste(choice) => {
+            use core::paste::PasteChoice;
+            if let Some(lines) = app_core.ui_state.pending_paste.take() {
+                match choice {
+                    PasteChoice::All => app_core.send_pasted_lines(&lines),
+                    PasteChoice::First => app_core.send_pasted_lines(&lines[..1]),
+                    PasteChoice::Edit => {
+                        frontend.open_composer(lines);
+                        app_core.ui_state.input_mode = data::ui_state::InputMode::Composer;
+                    }
                 }
-                app_core.needs_render = true;
             }
-            _ => {
-                tracing::warn!("Unknown menu action: {}", command);
+            app_core.needs_render = true;
+        }
+        AppAction::Compose => {
+            frontend.open_composer(Vec::new());
+            app_core.ui_state.input_mode = data::ui_state::InputMode::Composer;
+            app_core.needs_render = true;
+        }
+        AppAction::Snippets => {
+            frontend.snippet_browser = Some(frontend::tui::snippet_browser::SnippetBrowser::new(
+                &app_core.config.snippets,
+            ));
+            app_core.ui_state.input_mode = data::ui_state::InputMode::SnippetBrowser;
+            app_core.needs_render = true;
+        }
+        AppAction::RunSnippet(name) => {
+            app_core.run_snippet(&name, &[]);
+        }
+        AppAction::EditSnippet(name) => {
+            let text = app_core
+                .config
+                .snippets
+                .get(&name)
+                .map(|snippet| snippet.text.clone())
+                .unwrap_or_default();
+            frontend.composer = Some(frontend::tui::composer::Composer::for_snippet(&name, &text));
+            app_core.ui_state.input_mode = data::ui_state::InputMode::Composer;
+            app_core.needs_render = true;
+        }
+        AppAction::SetDefaultLayout(name) => {
+            app_core.set_startup_layout(Some(&name));
+            dispatch_action(app_core, frontend, AppAction::LoadLayout(name))?;
+        }
+        AppAction::StopLayoutPrompt => {
+            app_core.set_ask_startup_layout(false);
+        }
+        AppAction::EditTheme => {
+            // Open theme editor with current theme
+            let current_theme = app_core.config.get_theme();
+            frontend.theme_editor = Some(frontend::tui::theme_editor::ThemeEditor::new_edit(
+                &current_theme,
+            ));
+            app_core.ui_state.input_mode = data::ui_state::InputMode::ThemeEditor;
+        }
+        AppAction::EditWindow(None) => {
+            // Open window picker for editing
+            let window_names: Vec<String> = app_core
+                .layout
+                .windows
+                .iter()
+                .map(|w| w.name().to_string())
+                .collect();
+
+            let items: Vec<data::ui_state::PopupMenuItem> = window_names
+                .iter()
+                .map(|name| data::ui_state::PopupMenuItem {
+                    text: name.clone(),
+                    command: format!("action:editwindow:{}", name),
+                    disabled: false,
+                })
+                .collect();
+
+            // Close submenu if it exists
+            app_core.ui_state.submenu = None;
+            // Create new popup menu for window selection
+            app_core.ui_state.popup_menu = Some(data::ui_state::PopupMenu::new(items, (40, 12)));
+            // Stay in Menu mode
+            app_core.ui_state.input_mode = data::ui_state::InputMode::Menu;
+        }
+        AppAction::NextTab => {
+            // Navigate to next tab in all tabbed windows
+            frontend.next_tab_all();
+            app_core.needs_render = true;
+        }
+        AppAction::PrevTab => {
+            // Navigate to previous tab in all tabbed windows
+            frontend.prev_tab_all();
+            app_core.needs_render = true;
+        }
+        AppAction::GoNew => {
+            // Navigate to next tab with unread messages
+            if !frontend.go_to_next_unread_tab() {
+                app_core.add_system_message("No tabs with new messages");
             }
+            app_core.needs_render = true;
         }
     }
     Ok(())
@@ -928,6 +1072,140 @@ enum Commands {
         #[arg(value_name = "FILE")]
         layout: Option<PathBuf>,
     },
+    /// Import keybinds/macros from a Wizard (.key) or StormFront macro file
+    ImportKeybinds {
+        /// Macro file to import
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+        /// Source format: wizard or stormfront (detected if omitted)
+        #[arg(long)]
+        format: Option<String>,
+        /// Replace existing bindings on the same key
+        #[arg(long)]
+        overwrite: bool,
+        /// Show what would be imported without writing keybinds.toml
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Compare layouts or merge windows between them
+    Layout {
+        #[command(subcommand)]
+        command: LayoutCommand,
+    },
+    /// Check config files or export the config.toml schema
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+    /// Import a StormFront (.sal) or VellumFE layout file as a two-face layout
+    ImportLayout {
+        /// Layout file to import
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+        /// Source format: stormfront or vellumfe (detected if omitted)
+        #[arg(long)]
+        format: Option<String>,
+        /// Name to save the layout as (defaults to the file name)
+        #[arg(long)]
+        name: Option<String>,
+        /// Replace an existing layout with the same name
+        #[arg(long)]
+        overwrite: bool,
+        /// Show the converted windows without saving the layout
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Package config, layouts, themes, highlights and keybinds into a
+    /// .tar.gz (credentials are left out); --character limits it to one profile
+    Export {
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+    },
+    /// Unpack a bundle made by `export` into the two-face directory
+    Import {
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+        /// Replace files that differ from the bundle's copies
+        #[arg(long)]
+        overwrite: bool,
+        /// List what would be written without writing it
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Send commands to the running instance for --character (or --port)
+    Send {
+        /// Commands to run, in order, as if typed
+        #[arg(value_name = "COMMAND", required = true)]
+        commands: Vec<String>,
+        /// Run them as client dot commands (the leading '.' is optional)
+        #[arg(long)]
+        dot: bool,
+    },
+    /// Measure parser and highlight throughput over a captured session
+    Bench {
+        /// Raw game stream, one server line per file line
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+        /// Times to replay the file
+        #[arg(long, default_value_t = 1)]
+        iterations: usize,
+        /// Parse only, without the profile's highlights
+        #[arg(long)]
+        no_highlights: bool,
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum LayoutCommand {
+    /// List windows added, removed or changed from layout A to layout B
+    Diff {
+        /// Layout file or saved layout name
+        #[arg(value_name = "A")]
+        a: String,
+        /// Layout file or saved layout name
+        #[arg(value_name = "B")]
+        b: String,
+    },
+    /// Copy windows that FROM has and INTO lacks into INTO
+    Merge {
+        /// Layout file or saved layout name to take windows from
+        #[arg(value_name = "FROM")]
+        from: String,
+        /// Layout file or saved layout name to add them to
+        #[arg(value_name = "INTO")]
+        into: String,
+        /// Only copy this window (repeatable)
+        #[arg(long = "window", value_name = "NAME")]
+        windows: Vec<String>,
+        /// Show what would be added without writing INTO
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigCommand {
+    /// Check config.toml, highlights, keybinds, colors and themes for
+    /// unknown keys, bad regexes and invalid colors (profile from --character)
+    Validate,
+    /// Print a JSON schema for config.toml, for editor autocompletion
+    Schema,
+}
+
+/// A layout argument: an existing file, else a saved layout name
+fn layout_file_arg(arg: &str) -> Result<PathBuf> {
+    let path = PathBuf::from(arg);
+    if path.exists() {
+        return Ok(path);
+    }
+    let saved = config::Config::layout_path(arg)?;
+    if !saved.exists() {
+        bail!("No layout file or saved layout named '{}'", arg);
+    }
+    Ok(saved)
 }
 
 fn main() -> Result<()> {
@@ -948,7 +1226,7 @@ fn main() -> Result<()> {
         .init();
 
     // Parse CLI arguments
-    let cli = Cli::parse();
+    let mut cli = Cli::parse();
 
     if cli.direct && matches!(cli.frontend, FrontendType::Gui) {
         bail!("Direct mode is currently only supported with the TUI frontend");
@@ -1030,6 +1308,395 @@ fn main() -> Result<()> {
 
                 return Ok(());
             }
+            Commands::ImportKeybinds {
+                file,
+                format,
+                overwrite,
+                dry_run,
+            } => {
+                use config::keybind_import::{import_file, ImportFormat};
+
+                if let Some(data_dir) = &cli.data_dir {
+                    std::env::set_var("TWO_FACE_DIR", data_dir);
+                }
+                let format = match format.as_deref() {
+                    Some(name) => match ImportFormat::from_str(name) {
+                        Some(format) => Some(format),
+                        None => bail!("Unknown macro format '{}' (use wizard or stormfront)", name),
+                    },
+                    None => None,
+                };
+
+                let report = import_file(&file, format)?;
+                for (source, reason) in &report.skipped {
+                    eprintln!("⚠ Skipped {}: {}", source, reason);
+                }
+                let mut entries: Vec<_> = report.keybinds.iter().collect();
+                entries.sort_by(|a, b| a.0.cmp(b.0));
+                for (key, action) in entries {
+                    println!("  {} = {}", key, action.describe());
+                }
+
+                let character = cli.character.as_deref();
+                let mut keybinds = config::Config::load_keybinds(character)?;
+                let (applied, kept) = report.merge_into(&mut keybinds, overwrite);
+                for key in &kept {
+                    println!(
+                        "⚠ Kept existing binding for {} (use --overwrite to replace)",
+                        key
+                    );
+                }
+
+                if dry_run {
+                    println!("Dry run: {} keybind(s) would be imported", applied);
+                } else {
+                    config::Config::write_keybinds(&keybinds, character)?;
+                    println!(
+                        "✓ Imported {} keybind(s) into {}",
+                        applied,
+                        config::Config::keybinds_path(character)?.display()
+                    );
+                }
+                return Ok(());
+            }
+            Commands::Export { file } => {
+                if let Some(data_dir) = &cli.data_dir {
+                    std::env::set_var("TWO_FACE_DIR", data_dir);
+                }
+                let base = config::Config::base_dir()?;
+                let entries = config::bundle::collect(&base, cli.character.as_deref())?;
+                config::bundle::write_bundle(&file, &entries, cli.character.as_deref())?;
+                for entry in &entries {
+                    println!("  {}", entry.path);
+                }
+                println!(
+                    "✓ Exported {} file(s) from {} to {}",
+                    entries.len(),
+                    base.display(),
+                    file.display()
+                );
+                return Ok(());
+            }
+            Commands::Import {
+                file,
+                overwrite,
+                dry_run,
+            } => {
+                use config::bundle::{apply_import, plan_import, read_bundle, ImportAction};
+
+                if let Some(data_dir) = &cli.data_dir {
+                    std::env::set_var("TWO_FACE_DIR", data_dir);
+                }
+                let base = config::Config::base_dir()?;
+                let entries = read_bundle(&file)?;
+                for entry in &entries {
+                    let tables = config::bundle::local_tables(entry);
+                    if !tables.is_empty() {
+                        eprintln!(
+                            "⚠ {}: keeping your own [{}] (connection and service settings aren't imported)",
+                            entry.path,
+                            tables.join("], [")
+                        );
+                    }
+                }
+                let plan = plan_import(&base, entries, overwrite);
+                for (entry, action) in &plan {
+                    match action {
+                        ImportAction::Create => println!("+ {}", entry.path),
+                        ImportAction::Replace => println!("~ {}", entry.path),
+                        ImportAction::Unchanged => {}
+                        ImportAction::Conflict => {
+                            eprintln!(
+                                "⚠ Skipped {}: differs from yours (use --overwrite)",
+                                entry.path
+                            )
+                        }
+                    }
+                }
+                if dry_run {
+                    let count = plan
+                        .iter()
+                        .filter(|(_, a)| matches!(a, ImportAction::Create | ImportAction::Replace))
+                        .count();
+                    println!(
+                        "Dry run: {} file(s) would be written to {}",
+                        count,
+                        base.display()
+                    );
+                } else {
+                    let written = apply_import(&base, &plan)?;
+                    println!("✓ Imported {} file(s) into {}", written, base.display());
+                }
+                return Ok(());
+            }
+            Commands::Config { command } => {
+                use config::validate::{config_schema, validate_files, Severity};
+
+                if let Some(data_dir) = &cli.data_dir {
+                    std::env::set_var("TWO_FACE_DIR", data_dir);
+                }
+                match command {
+                    ConfigCommand::Validate => {
+                        let character = cli.character.as_deref();
+                        let profile = config::Config::config_path(character)?;
+                        let issues = validate_files(
+                            &profile,
+                            &config::Config::highlights_path(character)?,
+                            &config::Config::keybinds_path(character)?,
+                            &config::Config::colors_path(character)?,
+                            &config::Config::base_dir()?.join("themes"),
+                        );
+                        let errors = issues
+                            .iter()
+                            .filter(|i| i.severity == Severity::Error)
+                            .count();
+                        for issue in &issues {
+                            match issue.severity {
+                                Severity::Error => eprintln!("{}", issue.describe()),
+                                Severity::Warning => println!("{}", issue.describe()),
+                            }
+                        }
+                        let dir = profile
+                            .parent()
+                            .map(|p| p.display().to_string())
+                            .unwrap_or_default();
+                        if issues.is_empty() {
+                            println!("✓ Config in {} is valid", dir);
+                        } else {
+                            println!(
+                                "{} error(s), {} warning(s) in {}",
+                                errors,
+                                issues.len() - errors,
+                                dir
+                            );
+                        }
+                        if errors > 0 {
+                            std::process::exit(1);
+                        }
+                    }
+                    ConfigCommand::Schema => {
+                        println!("{}", serde_json::to_string_pretty(&config_schema())?);
+                    }
+                }
+                return Ok(());
+            }
+            Commands::Layout { command } => {
+                use config::diff::ChangeKind;
+                use config::layout_diff::{diff_layouts, merge_additions};
+
+                if let Some(data_dir) = &cli.data_dir {
+                    std::env::set_var("TWO_FACE_DIR", data_dir);
+                }
+                match command {
+                    LayoutCommand::Diff { a, b } => {
+                        let a_layout = config::Layout::load_from_file(&layout_file_arg(&a)?)?;
+                        let b_layout = config::Layout::load_from_file(&layout_file_arg(&b)?)?;
+                        let size = |layout: &config::Layout| match (
+                            layout.terminal_width,
+                            layout.terminal_height,
+                        ) {
+                            (Some(w), Some(h)) => format!("{}x{}", w, h),
+                            _ => "unknown".to_string(),
+                        };
+                        if size(&a_layout) != size(&b_layout) {
+                            println!(
+                                "Terminal size {} -> {} (positions are not scaled)",
+                                size(&a_layout),
+                                size(&b_layout)
+                            );
+                        }
+
+                        let changes = diff_layouts(&a_layout, &b_layout);
+                        for change in &changes {
+                            let label = match change.kind {
+                                ChangeKind::Added => "added",
+                                ChangeKind::Modified => "changed",
+                                ChangeKind::Removed => "removed",
+                            };
+                            println!("{} {} ({})", change.kind.symbol(), change.name, label);
+                            for detail in &change.details {
+                                println!("    {}", detail);
+                            }
+                        }
+                        if changes.is_empty() {
+                            println!("✓ Layouts have the same windows");
+                        } else {
+                            println!("{} window(s) differ", changes.len());
+                        }
+                    }
+                    LayoutCommand::Merge {
+                        from,
+                        into,
+                        windows,
+                        dry_run,
+                    } => {
+                        let from_layout = config::Layout::load_from_file(&layout_file_arg(&from)?)?;
+                        let into_path = layout_file_arg(&into)?;
+                        let mut into_layout = config::Layout::load_from_file(&into_path)?;
+                        for name in &windows {
+                            if from_layout.get_window(name).is_none() {
+                                eprintln!("⚠ {} has no window '{}'", from, name);
+                            } else if into_layout.get_window(name).is_some() {
+                                eprintln!("⚠ {} already has window '{}'", into, name);
+                            }
+                        }
+
+                        let added = merge_additions(&from_layout, &mut into_layout, &windows);
+                        for name in &added {
+                            println!("+ {}", name);
+                        }
+                        if added.is_empty() {
+                            println!("Nothing to merge");
+                        } else if dry_run {
+                            println!(
+                                "Dry run: {} window(s) would be added to {}",
+                                added.len(),
+                                into_path.display()
+                            );
+                        } else {
+                            into_layout.save_to_file(&into_path)?;
+                            println!(
+                                "✓ Added {} window(s) to {}",
+                                added.len(),
+                                into_path.display()
+                            );
+                        }
+                    }
+                }
+                return Ok(());
+            }
+            Commands::Send { commands, dot } => {
+                if let Some(data_dir) = &cli.data_dir {
+                    std::env::set_var("TWO_FACE_DIR", data_dir);
+                }
+                // Same profile and port resolution as the instance uses for its socket
+                let character = cli.character.as_deref();
+                let port = cli.port.unwrap_or(8000);
+                let config = match &cli.config {
+                    Some(config_path) => {
+                        config::Config::load_from_path(config_path, character, port)?
+                    }
+                    None => config::Config::load_with_options(character, port)?,
+                };
+                let path = config::Config::control_socket_path(character, config.connection.port)?;
+                ipc::send(&path, &commands, dot)?;
+                return Ok(());
+            }
+            Commands::Bench {
+                file,
+                iterations,
+                no_highlights,
+                json,
+            } => {
+                if let Some(data_dir) = &cli.data_dir {
+                    std::env::set_var("TWO_FACE_DIR", data_dir);
+                }
+                let bytes = std::fs::read(&file)
+                    .with_context(|| format!("Failed to read {}", file.display()))?;
+                let lines: Vec<String> = String::from_utf8_lossy(&bytes)
+                    .lines()
+                    .map(|line| line.to_string())
+                    .collect();
+                let config = config::Config::load_with_options(
+                    cli.character.as_deref(),
+                    cli.port.unwrap_or(8000),
+                )?;
+                let highlights = if no_highlights {
+                    std::collections::HashMap::new()
+                } else {
+                    config.highlights.clone()
+                };
+                let presets = config
+                    .colors
+                    .presets
+                    .iter()
+                    .map(|(id, preset)| (id.clone(), preset.fg.clone(), preset.bg.clone()))
+                    .collect();
+                let parser =
+                    parser::XmlParser::with_presets(presets, config.event_patterns.clone());
+
+                let report = bench::run(&lines, parser, &highlights, iterations);
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&report)?);
+                } else {
+                    print!("{}", report.summary());
+                }
+                return Ok(());
+            }
+            Commands::ImportLayout {
+                file,
+                format,
+                name,
+                overwrite,
+                dry_run,
+            } => {
+                use config::layout_import::{import_file, LayoutFormat};
+
+                if let Some(data_dir) = &cli.data_dir {
+                    std::env::set_var("TWO_FACE_DIR", data_dir);
+                }
+                let format = match format.as_deref() {
+                    Some(name) => match LayoutFormat::from_str(name) {
+                        Some(format) => Some(format),
+                        None => bail!(
+                            "Unknown layout format '{}' (use stormfront or vellumfe)",
+                            name
+                        ),
+                    },
+                    None => None,
+                };
+                let name = match name {
+                    Some(name) => name,
+                    None => file
+                        .file_stem()
+                        .and_then(|s| s.to_str())
+                        .context("Can't name the layout after the file; use --name")?
+                        .to_string(),
+                };
+
+                // StormFront positions are in pixels: fit them to this terminal
+                let screen = crossterm::terminal::size().unwrap_or((120, 40));
+                let mut report = import_file(&file, format, screen)?;
+                for (source, reason) in &report.skipped {
+                    eprintln!("⚠ Skipped {}: {}", source, reason);
+                }
+                for window in &report.layout.windows {
+                    let base = window.base();
+                    println!(
+                        "  {:<16} {:<14} row {:>3}, col {:>3}, {}x{}{}",
+                        base.name,
+                        window.widget_type(),
+                        base.row,
+                        base.col,
+                        base.cols,
+                        base.rows,
+                        if base.visible { "" } else { " (hidden)" }
+                    );
+                }
+
+                let path = config::Config::layout_path(&name)?;
+                if dry_run {
+                    println!(
+                        "Dry run: {} window(s) would be saved as layout '{}'",
+                        report.layout.windows.len(),
+                        name
+                    );
+                } else if path.exists() && !overwrite {
+                    bail!(
+                        "Layout '{}' already exists (use --overwrite or --name)",
+                        name
+                    );
+                } else {
+                    report.layout.save(&name, None, false)?;
+                    println!(
+                        "✓ Imported {} window(s) into {} (load it with .loadlayout {})",
+                        report.layout.windows.len(),
+                        path.display(),
+                        name
+                    );
+                }
+                return Ok(());
+            }
         }
     }
 
@@ -1041,6 +1708,39 @@ fn main() -> Result<()> {
         tracing::info!("Using data directory from TWO_FACE_DIR: {}", env_dir);
     }
 
+    // No --character: offer the profiles played before
+    let interactive = std::io::stdin().is_terminal() && std::io::stdout().is_terminal();
+    if cli.character.is_none()
+        && cli.config.is_none()
+        && !cli.direct
+        && matches!(cli.frontend, FrontendType::Tui)
+        && interactive
+    {
+        let base = config::Config::base_dir()?;
+        let records = config::launch_history::load(&base);
+        let profiles = config::launch_history::list_profiles(&base, &records);
+        if profiles.iter().any(|p| p.name != "default") {
+            use frontend::tui::profile_picker::{self, PickerResult};
+            match profile_picker::run(profiles)? {
+                PickerResult::Quit => return Ok(()),
+                PickerResult::Profile(name) => {
+                    let record = records.get(&name).cloned().unwrap_or_default();
+                    cli.port = cli.port.or(record.port);
+                    if let Some(account) = record.direct_account {
+                        cli.direct = true;
+                        cli.direct_account = Some(account);
+                        cli.direct_game = record.direct_game.and_then(|game| {
+                            <DirectGameArg as clap::ValueEnum>::from_str(&game, true).ok()
+                        });
+                    }
+                    if name != "default" {
+                        cli.character = Some(name);
+                    }
+                }
+            }
+        }
+    }
+
     // Load configuration
     let port = cli.port.unwrap_or(8000);
     let character = cli.character.as_deref();
@@ -1060,25 +1760,88 @@ fn main() -> Result<()> {
 
     let direct_config = build_direct_config(&cli, &config)?;
 
+    // One instance per character (held until this function returns)
+    let lock_character = direct_config
+        .as_ref()
+        .map(|d| d.character.clone())
+        .or_else(|| cli.character.clone());
+    let lock_path =
+        config::Config::instance_lock_path(lock_character.as_deref(), config.connection.port)?;
+    let Some(_instance_lock) = instance_lock::acquire_or_prompt(
+        &lock_path,
+        lock_character.as_deref(),
+        config.connection.port,
+        cli.force_instance,
+    )?
+    else {
+        return Ok(());
+    };
+
+    // Remembered for the profile picker next time
+    let launch = config::launch_history::LaunchRecord {
+        port: Some(config.connection.port),
+        frontend: Some(match cli.frontend {
+            FrontendType::Tui => "tui".to_string(),
+            FrontendType::Gui => "gui".to_string(),
+        }),
+        direct_account: cli.direct_account.clone(),
+        direct_game: cli.direct.then(|| {
+            format!("{:?}", cli.direct_game.unwrap_or(DirectGameArg::Prime)).to_lowercase()
+        }),
+        ..Default::default()
+    };
+    let profile = cli.character.as_deref().unwrap_or("default");
+    if let Err(e) = config::launch_history::record(&config::Config::base_dir()?, profile, launch) {
+        tracing::warn!("Launch not recorded: {:#}", e);
+    }
+
+    // `two-face send` reaches this instance through the control socket
+    let control =
+        config::Config::control_socket_path(lock_character.as_deref(), config.connection.port)
+            .and_then(|path| ipc::ControlSocket::start(&path));
+    let control = match control {
+        Ok(control) => Some(control),
+        Err(e) => {
+            tracing::warn!("Control socket not started: {:#}", e);
+            None
+        }
+    };
+
+    // Offer to start Lich if its port isn't open yet
+    if direct_config.is_none() {
+        let log_path = config::Config::base_dir()?.join("lich.log");
+        if let Err(e) = lich_launch::ensure_lich(&config, cli.character.as_deref(), &log_path) {
+            tracing::warn!("Lich auto-launch failed: {:#}", e);
+        }
+    }
+
     // Run appropriate frontend
     let character = cli.character.clone();
     match cli.frontend {
-        FrontendType::Tui => run_tui(config, character, direct_config)?,
+        FrontendType::Tui => run_tui(config, character, direct_config, control)?,
         FrontendType::Gui => run_gui(config)?,
     }
 
     Ok(())
 }
 
+/// Longest the main loop spends applying parsed server lines per pass
+const PARSE_APPLY_BUDGET: std::time::Duration = std::time::Duration::from_millis(8);
+
+/// Clock tick while idle (nothing is counting down, so only age fading cares;
+/// relative timestamps also tick on the minute)
+const IDLE_TICK: std::time::Duration = std::time::Duration::from_secs(10);
+
 /// Run TUI frontend
 fn run_tui(
     config: config::Config,
     character: Option<String>,
     direct: Option<network::DirectConnectConfig>,
+    control: Option<ipc::ControlSocket>,
 ) -> Result<()> {
     // Use tokio runtime for async network I/O
     let runtime = tokio::runtime::Runtime::new()?;
-    runtime.block_on(async_run_tui(config, character, direct))
+    runtime.block_on(async_run_tui(config, character, direct, control))
 }
 
 /// Async TUI main loop with network support
@@ -1086,6 +1849,7 @@ async fn async_run_tui(
     config: config::Config,
     character: Option<String>,
     direct: Option<network::DirectConnectConfig>,
+    control: Option<ipc::ControlSocket>,
 ) -> Result<()> {
     use core::AppCore;
     use frontend::{Frontend, TuiFrontend};
@@ -1104,7 +1868,14 @@ async fn async_run_tui(
     let mut app_core = AppCore::new(config)?;
 
     // Create TUI frontend
-    let mut frontend = TuiFrontend::new()?;
+    let mut frontend = TuiFrontend::new(app_core.config.ui.keyboard_enhancement)?;
+    // The terminal theme only exists once the palette has been read
+    if app_core.config.active_theme == theme::terminal::TERMINAL_THEME {
+        if let Err(e) = theme::terminal::refresh() {
+            tracing::warn!("Could not read terminal palette: {}", e);
+        }
+    }
+
     // Ensure frontend theme cache matches whatever layout/theme AppCore activated
     let initial_theme_id = app_core.config.active_theme.clone();
     let initial_theme = app_core.config.get_theme();
@@ -1113,6 +1884,7 @@ async fn async_run_tui(
     // Initialize command input widget BEFORE any rendering
     // This ensures it exists when we start routing keys to it
     frontend.ensure_command_input_exists("command_input");
+    frontend.apply_settings(&app_core.config);
 
     // Load command history
     if let Err(e) = frontend.command_input_load_history("command_input", character.as_deref()) {
@@ -1123,6 +1895,18 @@ async fn async_run_tui(
     let (width, height) = frontend.size();
     app_core.init_windows(width, height);
 
+    // A default saved layout replaces last session's; several without one get a dialog
+    if let Some(name) = app_core.startup_layout() {
+        dispatch_action(
+            &mut app_core,
+            &mut frontend,
+            core::actions::AppAction::LoadLayout(name),
+        )?;
+    } else if let Some(items) = app_core.build_startup_layout_menu() {
+        app_core.ui_state.popup_menu = Some(data::ui_state::PopupMenu::new(items, (40, 12)));
+        app_core.ui_state.input_mode = data::ui_state::InputMode::Menu;
+    }
+
     // Spawn network connection task
     let network_handle = match direct {
         Some(cfg) => tokio::spawn(async move {
@@ -1142,8 +1926,12 @@ async fn async_run_tui(
         }
     };
 
+    // Parse server text off the UI thread
+    app_core.start_parse_worker();
+
     // Track time for periodic countdown updates
     let mut last_countdown_update = std::time::Instant::now();
+    let mut last_tick_minute = chrono::Utc::now().timestamp() / 60;
 
     // Main event loop
     while app_core.running {
@@ -1153,8 +1941,22 @@ async fn async_run_tui(
         // Poll TTS callback events for auto-play
         app_core.poll_tts_events();
 
+        // Drop a half-typed key chord once its timeout passes
+        app_core.check_chord_timeout();
+
+        let had_input = !events.is_empty();
+
         // Process frontend events
         for event in events {
+            if matches!(
+                event,
+                frontend::FrontendEvent::Key { .. }
+                    | frontend::FrontendEvent::Mouse { .. }
+                    | frontend::FrontendEvent::Paste { .. }
+            ) {
+                app_core.record_user_input();
+            }
+
             // Handle events that need frontend access directly
             match &event {
                 frontend::FrontendEvent::Mouse {
@@ -1170,10 +1972,10 @@ async fn async_run_tui(
                     // Create stable window index mapping (sorted by window name for consistency)
                     let mut window_names: Vec<&String> = app_core.ui_state.windows.keys().collect();
                     window_names.sort();
-                    let window_index_map: std::collections::HashMap<&String, usize> = window_names
+                    let window_index_map: std::collections::HashMap<String, usize> = window_names
                         .iter()
                         .enumerate()
-                        .map(|(idx, name)| (*name, idx))
+                        .map(|(idx, name)| ((*name).clone(), idx))
                         .collect();
 
                     // Handle window editor mouse events first (if open)
@@ -1211,36 +2013,22 @@ async fn async_run_tui(
                     match kind {
                         MouseEventKind::ScrollUp => {
                             // Find which window the mouse is over
-                            let mut target_window = "main".to_string();
-                            for (name, window) in &app_core.ui_state.windows {
-                                let pos = &window.position;
-                                if *x >= pos.x
-                                    && *x < pos.x + pos.width
-                                    && *y >= pos.y
-                                    && *y < pos.y + pos.height
-                                {
-                                    target_window = name.clone();
-                                    break;
-                                }
-                            }
+                            let target_window = app_core
+                                .ui_state
+                                .window_at(*x, *y)
+                                .cloned()
+                                .unwrap_or_else(|| "main".to_string());
                             frontend.scroll_window(&target_window, 10);
                             app_core.needs_render = true;
                             continue;
                         }
                         MouseEventKind::ScrollDown => {
                             // Find which window the mouse is over
-                            let mut target_window = "main".to_string();
-                            for (name, window) in &app_core.ui_state.windows {
-                                let pos = &window.position;
-                                if *x >= pos.x
-                                    && *x < pos.x + pos.width
-                                    && *y >= pos.y
-                                    && *y < pos.y + pos.height
-                                {
-                                    target_window = name.clone();
-                                    break;
-                                }
-                            }
+                            let target_window = app_core
+                                .ui_state
+                                .window_at(*x, *y)
+                                .cloned()
+                                .unwrap_or_else(|| "main".to_string());
                             frontend.scroll_window(&target_window, -10);
                             app_core.needs_render = true;
                             continue;
@@ -1258,6 +2046,7 @@ async fn async_run_tui(
                                         .get_items()
                                         .iter()
                                         .map(|item| item.text.len())
+                                        .chain(menu.title.iter().map(|title| title.len()))
                                         .max()
                                         .unwrap_or(10)
                                         as u16
@@ -1340,6 +2129,8 @@ async fn async_run_tui(
                                             ) {
                                                 tracing::error!("Menu action error: {}", e);
                                             }
+                                        } else if let Some(text) = command.strip_prefix("macro:") {
+                                            app_core.start_menu_macro(text);
                                         } else {
                                             // Game command - send to server
                                             let _ = command_tx.send(format!("{}\n", command));
@@ -1363,6 +2154,31 @@ async fn async_run_tui(
                                 continue;
                             }
 
+                            // Compact mode: taps on or next to the command input move its cursor
+                            // (touch targets are coarse) and never start a drag
+                            if app_core.compact_active() {
+                                if let Some(input) = app_core.ui_state.windows.get("command_input")
+                                {
+                                    if core::compact::hits_input(&input.position, *x, *y) {
+                                        let pos = &input.position;
+                                        let input_rect = ratatui::layout::Rect {
+                                            x: pos.x,
+                                            y: pos.y,
+                                            width: pos.width,
+                                            height: pos.height,
+                                        };
+                                        frontend.command_input_click(
+                                            "command_input",
+                                            *x,
+                                            input_rect,
+                                        );
+                                        app_core.ui_state.selection_state = None;
+                                        app_core.needs_render = true;
+                                        continue;
+                                    }
+                                }
+                            }
+
                             // Mouse down handling (find links, start drags)
                             app_core.ui_state.selection_state = None;
 
@@ -1370,13 +2186,31 @@ async fn async_run_tui(
                             let mut drag_op = None;
                             let mut clicked_window_name: Option<String> = None;
 
-                            for (name, window) in &app_core.ui_state.windows {
+                            // Topmost window first: it covers the ones below
+                            for (name, window) in
+                                app_core.ui_state.windows_in_z_order().into_iter().rev()
+                            {
                                 let pos = &window.position;
-                                if *x >= pos.x
+                                if window.visible
+                                    && *x >= pos.x
                                     && *x < pos.x + pos.width
                                     && *y >= pos.y
                                     && *y < pos.y + pos.height
                                 {
+                                    // A container's interior belongs to its children
+                                    if window.widget_type == data::WidgetType::Container {
+                                        let framed = app_core
+                                            .layout
+                                            .get_window(name)
+                                            .is_some_and(|def| def.base().show_border);
+                                        let on_frame = *x == pos.x
+                                            || *x + 1 == pos.x + pos.width
+                                            || *y == pos.y
+                                            || *y + 1 == pos.y + pos.height;
+                                        if !(framed && on_frame) {
+                                            continue;
+                                        }
+                                    }
                                     clicked_window_name = Some(name.clone());
 
                                     let right_col = pos.x + pos.width - 1;
@@ -1405,10 +2239,13 @@ async fn async_run_tui(
                                         found_window = Some(name.clone());
                                         break;
                                     }
+                                    break;
                                 }
                             }
 
                             if let (Some(window_name), Some(operation)) = (found_window, drag_op) {
+                                // Contained windows drag their whole container group
+                                let window_name = app_core.container_root(&window_name);
                                 if let Some(window) = app_core.ui_state.get_window(&window_name) {
                                     let pos = &window.position;
                                     app_core.ui_state.mouse_drag = Some(MouseDragState {
@@ -1419,6 +2256,13 @@ async fn async_run_tui(
                                     });
                                 }
                             } else if let Some(window_name) = clicked_window_name {
+                                // Clicking a text window focuses it (scroll and search keys follow focus)
+                                if app_core.ui_state.get_window(&window_name).is_some_and(|w| {
+                                    matches!(w.content, data::WindowContent::Text(_))
+                                }) {
+                                    app_core.ui_state.set_focus(Some(window_name.clone()));
+                                    app_core.needs_render = true;
+                                }
                                 if let Some(window) = app_core.ui_state.get_window(&window_name) {
                                     let pos = &window.position;
                                     let window_rect = ratatui::layout::Rect {
@@ -1428,9 +2272,58 @@ async fn async_run_tui(
                                         height: pos.height,
                                     };
 
-                                    if let Some(link_data) =
-                                        frontend.link_at_position(&window_name, *x, *y, window_rect)
-                                    {
+                                    if let Some(key) = frontend.inventory_node_at(
+                                        &window_name,
+                                        *x,
+                                        *y,
+                                        window_rect,
+                                    ) {
+                                        app_core.toggle_inventory_node(&window_name, &key);
+                                        continue;
+                                    }
+                                    if let Some(name) =
+                                        frontend.player_at(&window_name, *x, *y, window_rect)
+                                    {
+                                        let items = app_core.player_menu(&name);
+                                        app_core.ui_state.popup_menu =
+                                            Some(data::ui_state::PopupMenu::new(items, (*x, *y)));
+                                        app_core.ui_state.input_mode = InputMode::Menu;
+                                        app_core.needs_render = true;
+                                        continue;
+                                    }
+                                    if let Some(part) =
+                                        frontend.injury_part_at(&window_name, *x, *y, window_rect)
+                                    {
+                                        let items =
+                                            app_core.injury_details_menu(&window_name, part);
+                                        app_core.ui_state.popup_menu =
+                                            Some(data::ui_state::PopupMenu::new(items, (*x, *y)));
+                                        app_core.ui_state.input_mode = InputMode::Menu;
+                                        app_core.needs_render = true;
+                                        continue;
+                                    }
+
+                                    let clicked_link = frontend.link_at_position(
+                                        &window_name,
+                                        *x,
+                                        *y,
+                                        window_rect,
+                                    );
+                                    // Web links open on Ctrl/Alt+click; a plain click selects their text
+                                    let (url_link, clicked_link) = match clicked_link {
+                                        Some(link)
+                                            if link.exist_id == crate::core::urls::URL_LINK_ID =>
+                                        {
+                                            (Some(link), None)
+                                        }
+                                        other => (None, other),
+                                    };
+                                    if let Some(url_link) = url_link.filter(|_| {
+                                        modifiers
+                                            .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+                                    }) {
+                                        app_core.open_url(&url_link.noun);
+                                    } else if let Some(link_data) = clicked_link {
                                         let has_ctrl = modifiers.contains(KeyModifiers::CONTROL);
 
                                         if has_ctrl {
@@ -1463,12 +2356,35 @@ async fn async_run_tui(
                                                 .get(&window_name)
                                                 .copied()
                                                 .unwrap_or(0);
+
+                                            // Double click takes the word, triple click the line
+                                            // (copied on release like a dragged selection)
+                                            let clicks = app_core.ui_state.click_counter.click(
+                                                *x,
+                                                *y,
+                                                std::time::Instant::now(),
+                                            );
+                                            if let Some(selection) = frontend.click_selection(
+                                                &window_name,
+                                                window_index,
+                                                line,
+                                                col,
+                                                clicks,
+                                            ) {
+                                                app_core.ui_state.selection_drag_start = None;
+                                                app_core.ui_state.selection_state = Some(selection);
+                                                app_core.needs_render = true;
+                                                continue;
+                                            }
+
+                                            // Alt-drag selects a block of columns
+                                            let start = if modifiers.contains(KeyModifiers::ALT) {
+                                                crate::selection::SelectionState::new_block
+                                            } else {
+                                                crate::selection::SelectionState::new
+                                            };
                                             app_core.ui_state.selection_state =
-                                                Some(crate::selection::SelectionState::new(
-                                                    window_index,
-                                                    line,
-                                                    col,
-                                                ));
+                                                Some(start(window_index, line, col));
                                         }
                                     }
                                 }
@@ -1488,6 +2404,11 @@ async fn async_run_tui(
 
                                 let (min_width_constraint, min_height_constraint) =
                                     app_core.window_min_size(&drag_state.window_name);
+                                let snapper = app_core.window_snapper(
+                                    &drag_state.window_name,
+                                    (term_width, term_height),
+                                );
+                                let mut guides = Vec::new();
 
                                 if let Some(window) =
                                     app_core.ui_state.get_window_mut(&drag_state.window_name)
@@ -1513,8 +2434,15 @@ async fn async_run_tui(
                                             let max_y =
                                                 term_height.saturating_sub(window.position.height);
 
+                                            let (new_x, new_y, snapped) = snapper.snap_move(
+                                                new_x.min(max_x),
+                                                new_y.min(max_y),
+                                                window.position.width,
+                                                window.position.height,
+                                            );
                                             window.position.x = new_x.min(max_x);
                                             window.position.y = new_y.min(max_y);
+                                            guides = snapped;
                                         }
                                         DragOperation::ResizeRight => {
                                             // Calculate new width
@@ -1526,7 +2454,14 @@ async fn async_run_tui(
                                             // Clamp to prevent overflow beyond terminal edge
                                             let max_width =
                                                 term_width.saturating_sub(window.position.x);
-                                            window.position.width = new_width.min(max_width);
+                                            let (right, guide) = snapper.snap_right(
+                                                window.position.x + new_width.min(max_width),
+                                            );
+                                            window.position.width = right
+                                                .saturating_sub(window.position.x)
+                                                .max(min_width_constraint)
+                                                .min(max_width);
+                                            guides.extend(guide);
                                         }
                                         DragOperation::ResizeBottom => {
                                             // Calculate new height
@@ -1538,7 +2473,14 @@ async fn async_run_tui(
                                             // Clamp to prevent overflow beyond terminal edge
                                             let max_height =
                                                 term_height.saturating_sub(window.position.y);
-                                            window.position.height = new_height.min(max_height);
+                                            let (bottom, guide) = snapper.snap_bottom(
+                                                window.position.y + new_height.min(max_height),
+                                            );
+                                            window.position.height = bottom
+                                                .saturating_sub(window.position.y)
+                                                .max(min_height_constraint)
+                                                .min(max_height);
+                                            guides.extend(guide);
                                         }
                                         DragOperation::ResizeBottomRight => {
                                             // Calculate new dimensions
@@ -1557,12 +2499,28 @@ async fn async_run_tui(
                                             let max_height =
                                                 term_height.saturating_sub(window.position.y);
 
-                                            window.position.width = new_width.min(max_width);
-                                            window.position.height = new_height.min(max_height);
+                                            let (right, column) = snapper.snap_right(
+                                                window.position.x + new_width.min(max_width),
+                                            );
+                                            let (bottom, row) = snapper.snap_bottom(
+                                                window.position.y + new_height.min(max_height),
+                                            );
+                                            window.position.width = right
+                                                .saturating_sub(window.position.x)
+                                                .max(min_width_constraint)
+                                                .min(max_width);
+                                            window.position.height = bottom
+                                                .saturating_sub(window.position.y)
+                                                .max(min_height_constraint)
+                                                .min(max_height);
+                                            guides.extend(column);
+                                            guides.extend(row);
                                         }
                                     }
                                     app_core.needs_render = true;
                                 }
+                                app_core.snap_guides = guides;
+                                app_core.apply_container_layout();
                             } else if app_core.ui_state.pending_link_click.is_some() {
                                 app_core.ui_state.pending_link_click = None;
                             } else if let Some(_drag_start) = app_core.ui_state.selection_drag_start
@@ -1606,10 +2564,12 @@ async fn async_run_tui(
                                 let dy = (*y as i16 - link_drag.start_pos.1 as i16).abs();
 
                                 if dx > 2 || dy > 2 {
-                                    let mut drop_target_hand: Option<String> = None;
-                                    let mut drop_target_id: Option<String> = None;
+                                    use crate::core::item_flags::{drag_command, DropTarget};
+                                    let mut drop_target = DropTarget::Ground;
 
-                                    for (name, window) in &app_core.ui_state.windows {
+                                    for (name, window) in
+                                        app_core.ui_state.windows_in_z_order().into_iter().rev()
+                                    {
                                         let pos = &window.position;
                                         if *x >= pos.x
                                             && *x < pos.x + pos.width
@@ -1618,43 +2578,51 @@ async fn async_run_tui(
                                         {
                                             // First check if this is a hand widget (left or right only)
                                             if name == "left_hand" {
-                                                drop_target_hand = Some("left".to_string());
+                                                drop_target = DropTarget::Hand("left".to_string());
                                                 break;
                                             } else if name == "right_hand" {
-                                                drop_target_hand = Some("right".to_string());
+                                                drop_target = DropTarget::Hand("right".to_string());
                                                 break;
                                             }
 
-                                            // Otherwise check if we dropped on a link
+                                            // Otherwise check if we dropped on a link (in the
+                                            // inventory tree, anywhere on an item's row)
                                             let window_rect = ratatui::layout::Rect {
                                                 x: pos.x,
                                                 y: pos.y,
                                                 width: pos.width,
                                                 height: pos.height,
                                             };
-                                            if let Some(target_link) =
-                                                frontend.link_at_position(name, *x, *y, window_rect)
+                                            if let Some(target_link) = frontend
+                                                .inventory_item_at(name, *x, *y, window_rect)
+                                                .or_else(|| {
+                                                    frontend.link_at_position(
+                                                        name,
+                                                        *x,
+                                                        *y,
+                                                        window_rect,
+                                                    )
+                                                })
+                                                .filter(|l| {
+                                                    l.exist_id != crate::core::urls::URL_LINK_ID
+                                                })
                                             {
-                                                drop_target_id = Some(target_link.exist_id);
+                                                drop_target = DropTarget::Item(target_link);
                                                 break;
                                             }
                                         }
                                     }
 
-                                    let command = if let Some(hand_type) = drop_target_hand {
-                                        format!(
-                                            "_drag #{} {}\n",
-                                            link_drag.link_data.exist_id, hand_type
-                                        )
-                                    } else if let Some(target_id) = drop_target_id {
-                                        format!(
-                                            "_drag #{} #{}\n",
-                                            link_drag.link_data.exist_id, target_id
-                                        )
-                                    } else {
-                                        format!("_drag #{} drop\n", link_drag.link_data.exist_id)
-                                    };
-                                    let _ = command_tx.send(command);
+                                    // Item flags steer the command (worn gear gets removed, not stowed)
+                                    match drag_command(&link_drag.link_data, &drop_target) {
+                                        Ok(command) => {
+                                            let _ = command_tx.send(format!("{}\n", command));
+                                        }
+                                        Err(reason) => {
+                                            app_core.add_system_message(&reason);
+                                            app_core.needs_render = true;
+                                        }
+                                    }
                                 }
                             } else if let Some(pending_click) =
                                 app_core.ui_state.pending_link_click.take()
@@ -1713,6 +2681,13 @@ async fn async_run_tui(
                                         .find(|w| w.name() == drag_state.window_name)
                                     {
                                         let base = window_def.base_mut();
+                                        // A size set by hand is fixed from now on
+                                        if base.cols != window.position.width {
+                                            base.width = None;
+                                        }
+                                        if base.rows != window.position.height {
+                                            base.height = None;
+                                        }
                                         base.col = window.position.x;
                                         base.row = window.position.y;
                                         base.cols = window.position.width;
@@ -1725,72 +2700,45 @@ async fn async_run_tui(
                             }
 
                             app_core.ui_state.mouse_drag = None;
+                            app_core.snap_guides.clear();
                             app_core.ui_state.selection_drag_start = None;
 
                             // Handle text selection copy to clipboard
                             if let Some(ref selection) = app_core.ui_state.selection_state {
                                 if !selection.is_empty() {
-                                    // Extract text from selection
-                                    let (start, end) = selection.normalized_range();
-
-                                    // Find the window (for now assume main window)
-                                    if let Some((_line, _col)) = frontend.mouse_to_text_coords(
-                                        "main",
-                                        *x,
-                                        *y,
-                                        ratatui::layout::Rect {
-                                            x: app_core
-                                                .ui_state
-                                                .windows
-                                                .get("main")
-                                                .map(|w| w.position.x)
-                                                .unwrap_or(0),
-                                            y: app_core
-                                                .ui_state
-                                                .windows
-                                                .get("main")
-                                                .map(|w| w.position.y)
-                                                .unwrap_or(0),
-                                            width: app_core
-                                                .ui_state
-                                                .windows
-                                                .get("main")
-                                                .map(|w| w.position.width)
-                                                .unwrap_or(80),
-                                            height: app_core
-                                                .ui_state
-                                                .windows
-                                                .get("main")
-                                                .map(|w| w.position.height)
-                                                .unwrap_or(24),
-                                        },
-                                    ) {
-                                        if let Some(text) = frontend.extract_selection_text(
-                                            "main", start.line, start.col, end.line, end.col,
-                                        ) {
-                                            // Copy to clipboard
-                                            match arboard::Clipboard::new() {
-                                                Ok(mut clipboard) => {
-                                                    if let Err(e) = clipboard.set_text(&text) {
-                                                        tracing::warn!(
-                                                            "Failed to copy to clipboard: {}",
-                                                            e
-                                                        );
-                                                    } else {
-                                                        tracing::info!(
-                                                            "Copied {} chars to clipboard",
-                                                            text.len()
-                                                        );
-                                                    }
-                                                }
-                                                Err(e) => {
+                                    // The window the selection started in (same order as window_index_map)
+                                    let mut window_names: Vec<&String> =
+                                        app_core.ui_state.windows.keys().collect();
+                                    window_names.sort();
+                                    let window_name = window_names
+                                        .get(selection.start.window_index)
+                                        .map(|name| name.to_string())
+                                        .unwrap_or_else(|| "main".to_string());
+
+                                    // Extract text from selection (colored copies are kept for copy_ansi/copy_html)
+                                    if let Some(copy) =
+                                        frontend.selection_copy(&window_name, selection)
+                                    {
+                                        // Copy to clipboard
+                                        match arboard::Clipboard::new() {
+                                            Ok(mut clipboard) => {
+                                                if let Err(e) = clipboard.set_text(&copy.plain) {
                                                     tracing::warn!(
-                                                        "Failed to access clipboard: {}",
+                                                        "Failed to copy to clipboard: {}",
                                                         e
                                                     );
+                                                } else {
+                                                    tracing::info!(
+                                                        "Copied {} chars to clipboard",
+                                                        copy.plain.len()
+                                                    );
                                                 }
                                             }
+                                            Err(e) => {
+                                                tracing::warn!("Failed to access clipboard: {}", e);
+                                            }
                                         }
+                                        app_core.ui_state.last_copy = Some(copy);
                                     }
                                 }
                                 // Clear selection
@@ -1810,45 +2758,166 @@ async fn async_run_tui(
                 _ => {}
             }
 
-            if let Some(command) = handle_frontend_event(&mut app_core, &mut frontend, event)? {
+            let started = std::time::Instant::now();
+            let command = handle_frontend_event(&mut app_core, &mut frontend, event)?;
+            app_core
+                .perf_stats
+                .record_event_process_time(started.elapsed());
+            if let Some(command) = command {
+                app_core.perf_stats.record_bytes_sent(command.len() as u64);
                 let _ = command_tx.send(command);
             }
         }
 
+        // Keybind macros: pauses resume here, typed text lands in the command input
+        for effect in app_core.poll_macro()? {
+            match effect {
+                core::macros::MacroEffect::Send(to_send) => {
+                    if to_send.starts_with("action:") {
+                        handle_menu_action(&mut app_core, &mut frontend, &to_send)?;
+                    } else if !to_send.is_empty() {
+                        let _ = command_tx.send(to_send);
+                    }
+                }
+                core::macros::MacroEffect::TypeInput { text, cursor } => {
+                    frontend.command_input_type("command_input", &text, cursor);
+                }
+                core::macros::MacroEffect::ClearInput => {
+                    frontend.command_input_clear("command_input");
+                }
+            }
+        }
+
+        // Theme browser previews the highlighted theme once navigation pauses
+        if frontend.poll_theme_preview() {
+            app_core.needs_render = true;
+        }
+
+        // Day/night theme schedule (waits while the theme browser is open)
+        if frontend.theme_browser.is_none() {
+            if let Some((theme_id, theme)) = app_core.poll_theme_schedule() {
+                frontend.update_theme_cache(theme_id, theme);
+            }
+        }
+
+        app_core.spectator.publish(&app_core.ui_state);
+        app_core.perf_stats.record_passthrough(
+            app_core.passthrough.clients(),
+            app_core.passthrough.bytes_mirrored(),
+        );
+
+        // Compact profile below ui.compact_width columns
+        app_core.update_compact_mode(frontend.size().0);
+
+        // Roundtime-end and spell-expiring sounds
+        app_core.poll_do_not_disturb();
+        app_core.poll_sound_events();
+        app_core.poll_afk();
+        if app_core.ui_state.expire_flashes(std::time::Instant::now()) {
+            app_core.needs_render = true;
+        }
+
+        // Commands from plugins, AFK auto-replies, WebSocket clients and `two-face send` (dot commands included)
+        let mut remote_commands = app_core.poll_plugin_actions();
+        remote_commands.extend(app_core.take_afk_replies());
+        remote_commands.extend(app_core.websocket.poll_commands());
+        if let Some(control) = &control {
+            remote_commands.extend(control.poll_commands());
+        }
+        for command in remote_commands {
+            let to_send = app_core.send_scripted_command(command)?;
+            if to_send.starts_with("action:") {
+                handle_menu_action(&mut app_core, &mut frontend, &to_send)?;
+            } else if !to_send.is_empty() {
+                let _ = command_tx.send(to_send);
+            }
+        }
+
+        // Dictated text waits in the command input for Enter unless auto_send is on
+        for transcript in app_core.poll_stt_events() {
+            if app_core.config.stt.auto_send {
+                let to_send = app_core.send_command(transcript)?;
+                if to_send.starts_with("action:") {
+                    handle_menu_action(&mut app_core, &mut frontend, &to_send)?;
+                } else {
+                    let _ = command_tx.send(to_send);
+                }
+            } else {
+                frontend.command_input_insert_text("command_input", &transcript);
+            }
+        }
+
         // Poll for server messages (non-blocking)
+        let network_backlog = server_rx.len();
         while let Ok(msg) = server_rx.try_recv() {
             match msg {
                 ServerMessage::Text(line) => {
-                    // Process incoming server data through parser
-                    if let Err(e) = app_core.process_server_data(&line) {
+                    app_core
+                        .perf_stats
+                        .record_bytes_received(line.len() as u64 + 1);
+                    // Companion tools get the line exactly as the server sent it
+                    app_core.passthrough.mirror(&line);
+                    // Hand the line to the parse worker
+                    if let Err(e) = app_core.submit_server_line(line) {
                         tracing::error!("Error processing server data: {}", e);
                     }
-                    // Check for highlight sound triggers
-                    app_core.check_sound_triggers(&line);
                 }
                 ServerMessage::Connected => {
                     tracing::info!("Connected to game server");
                     app_core.game_state.connected = true;
+                    app_core.notify_connected();
                     app_core.needs_render = true;
                 }
                 ServerMessage::Disconnected => {
                     tracing::info!("Disconnected from game server");
                     app_core.game_state.connected = false;
+                    app_core.notify_disconnected();
                     app_core.needs_render = true;
                 }
             }
         }
 
-        // Force render every second for countdown widgets
-        if last_countdown_update.elapsed().as_secs() >= 1 {
-            app_core.needs_render = true;
+        // Show what the parse worker has finished, a frame's worth at a time
+        if let Err(e) = app_core.apply_parsed_lines(PARSE_APPLY_BUDGET) {
+            tracing::error!("Error processing server data: {}", e);
+        }
+        let parse_backlog = app_core.parse_backlog();
+        app_core
+            .perf_stats
+            .record_backlogs(network_backlog, parse_backlog);
+
+        // Back off polling (and ticking) once nothing is happening
+        frontend.set_idle(
+            !had_input
+                && network_backlog == 0
+                && parse_backlog == 0
+                && !app_core.needs_render
+                && !app_core.has_running_timers(),
+        );
+
+        // Tick countdown widgets every second (only they get redrawn)
+        let tick = if frontend.is_idle() {
+            IDLE_TICK
+        } else {
+            std::time::Duration::from_secs(1)
+        };
+        // Relative timestamp labels change when the clock minute turns, so don't
+        // wait out the idle tick
+        let minute = chrono::Utc::now().timestamp() / 60;
+        let new_minute =
+            minute != last_tick_minute && !app_core.relative_timestamp_windows().is_empty();
+        if last_countdown_update.elapsed() >= tick || new_minute {
+            app_core.content_changed = true;
+            app_core.update_perf_memory_stats();
             last_countdown_update = std::time::Instant::now();
+            last_tick_minute = minute;
         }
 
         // Render if needed
-        if app_core.needs_render {
+        if app_core.needs_render || app_core.content_changed {
             frontend.render(&mut app_core)?;
             app_core.needs_render = false;
+            app_core.content_changed = false;
         }
 
         // No sleep needed - event::poll() timeout already limits frame rate to ~60 FPS
@@ -1999,6 +3068,11 @@ fn handle_frontend_event(
                     app_core.needs_render = true;
                     return Ok(None);
                 }
+                if app_core.ui_state.input_mode == InputMode::CopyMode {
+                    exit_copy_mode(app_core, frontend);
+                    app_core.needs_render = true;
+                    return Ok(None);
+                }
                 // If in search mode, clear search and exit search mode
                 if app_core.ui_state.input_mode == InputMode::Search {
                     frontend.clear_all_searches();
@@ -2016,20 +3090,47 @@ fn handle_frontend_event(
                     frontend.color_form = None;
                     frontend.spell_color_browser = None;
                     frontend.spell_color_form = None;
+                    if frontend
+                        .config_diff
+                        .take()
+                        .is_some_and(|view| !view.is_empty())
+                    {
+                        app_core.add_system_message(
+                            "Config changes kept in memory only (.configdiff to review and write them)",
+                        );
+                    }
                     frontend.uicolors_browser = None;
-                    frontend.theme_browser = None;
+                    frontend.close_theme_browser(
+                        app_core.config.active_theme.clone(),
+                        app_core.config.get_theme(),
+                    );
                     frontend.theme_editor = None;
                     frontend.settings_editor = None;
+                    frontend.composer = None;
+                    frontend.snippet_browser = None;
                     app_core.ui_state.input_mode = InputMode::Normal;
                     app_core.needs_render = true;
                     return Ok(None);
                 }
+                // Leave a Ctrl+R history search, restoring what was typed
+                if frontend.command_input_cancel_search("command_input") {
+                    app_core.needs_render = true;
+                    return Ok(None);
+                }
+                // Stop a snippet that is asking for placeholder values
+                app_core.cancel_snippet();
                 // Otherwise do nothing - Escape does not quit (use .quit or Ctrl+C instead)
                 return Ok(None);
             }
 
             // LAYER 2: Priority windows (editors, browsers, forms)
             use crate::core::input_router;
+            // Numpad keys are only distinct for keybinds; fields type the digit
+            let code = if input_router::has_priority_window(&app_core.ui_state.input_mode) {
+                core::event_bridge::keypad_as_char(code)
+            } else {
+                code
+            };
             if input_router::has_priority_window(&app_core.ui_state.input_mode) {
                 let key_event = crossterm::event::KeyEvent::new(code, modifiers);
 
@@ -2062,7 +3163,14 @@ fn handle_frontend_event(
                                 crate::core::menu_actions::MenuAction::Delete => {
                                     if let Some(name) = browser.delete_selected() {
                                         app_core.config.highlights.retain(|k, _v| k != &name);
+                                        frontend.refresh_highlights(app_core);
+                                        app_core.refresh_sound_triggers();
                                         tracing::info!("Deleted highlight: {}", name);
+                                        // Deletes are written (or reviewed) like edits
+                                        offer_config_review(app_core, frontend);
+                                        if app_core.ui_state.input_mode == InputMode::ConfigDiff {
+                                            frontend.highlight_browser = None;
+                                        }
                                     }
                                 }
                                 crate::core::menu_actions::MenuAction::Edit => {
@@ -2120,6 +3228,10 @@ fn handle_frontend_event(
                                         app_core.config.keybinds.remove(&combo);
                                         app_core.rebuild_keybind_map();
                                         tracing::info!("Deleted keybind: {}", combo);
+                                        offer_config_review(app_core, frontend);
+                                        if app_core.ui_state.input_mode == InputMode::ConfigDiff {
+                                            frontend.keybind_browser = None;
+                                        }
                                     }
                                 }
                                 _ => {}
@@ -2160,8 +3272,100 @@ fn handle_frontend_event(
                                             .color_palette
                                             .retain(|c| c.name != name);
                                         tracing::info!("Deleted color: {}", name);
+                                        offer_config_review(app_core, frontend);
+                                        if app_core.ui_state.input_mode == InputMode::ConfigDiff {
+                                            frontend.color_palette_browser = None;
+                                        }
+                                    }
+                                }
+                                _ => {}
+                            }
+                            app_core.needs_render = true;
+                        }
+                        return Ok(None);
+                    }
+                    InputMode::ConfigDiff => {
+                        if let Some(ref mut view) = frontend.config_diff {
+                            use crate::frontend::tui::widget_traits::Navigable;
+                            let action = input_router::route_input(
+                                key_event,
+                                &app_core.ui_state.input_mode,
+                                &app_core.config,
+                            );
+
+                            match action {
+                                crate::core::menu_actions::MenuAction::NavigateUp => {
+                                    view.navigate_up()
+                                }
+                                crate::core::menu_actions::MenuAction::NavigateDown => {
+                                    view.navigate_down()
+                                }
+                                crate::core::menu_actions::MenuAction::PageUp => view.page_up(),
+                                crate::core::menu_actions::MenuAction::PageDown => view.page_down(),
+                                crate::core::menu_actions::MenuAction::Delete => {
+                                    if let Some(change) = view.selected().cloned() {
+                                        let reverted =
+                                            config::diff::discard(&mut app_core.config, &change)
+                                                .and_then(|_| {
+                                                    config::diff::pending_changes(&app_core.config)
+                                                });
+                                        match reverted {
+                                            Ok(changes) => {
+                                                view.set_changes(changes);
+                                                match change.file {
+                                                    config::diff::ConfigFile::Highlights => {
+                                                        frontend.refresh_highlights(app_core);
+                                                        app_core.refresh_sound_triggers();
+                                                    }
+                                                    config::diff::ConfigFile::Keybinds => {
+                                                        app_core.rebuild_keybind_map()
+                                                    }
+                                                    config::diff::ConfigFile::Colors => {}
+                                                }
+                                                tracing::info!(
+                                                    "Discarded config change: {} {}",
+                                                    change.file.file_name(),
+                                                    change.key()
+                                                );
+                                            }
+                                            Err(e) => app_core.add_system_message(&format!(
+                                                "Failed to discard {}: {}",
+                                                change.key(),
+                                                e
+                                            )),
+                                        }
                                     }
                                 }
+                                crate::core::menu_actions::MenuAction::Select
+                                | crate::core::menu_actions::MenuAction::Save => {
+                                    match config::diff::save_reviewed(&app_core.config) {
+                                        Ok(files) if files.is_empty() => app_core
+                                            .add_system_message("Config files already up to date"),
+                                        Ok(files) => {
+                                            let names: Vec<&str> =
+                                                files.iter().map(|f| f.file_name()).collect();
+                                            app_core.add_system_message(&format!(
+                                                "Wrote {}",
+                                                names.join(", ")
+                                            ));
+                                        }
+                                        Err(e) => app_core.add_system_message(&format!(
+                                            "Failed to write config: {}",
+                                            e
+                                        )),
+                                    }
+                                    frontend.config_diff = None;
+                                    app_core.ui_state.input_mode = InputMode::Normal;
+                                }
+                                crate::core::menu_actions::MenuAction::Cancel => {
+                                    if !view.is_empty() {
+                                        app_core.add_system_message(
+                                            "Config changes kept in memory only (.configdiff to review and write them)",
+                                        );
+                                    }
+                                    frontend.config_diff = None;
+                                    app_core.ui_state.input_mode = InputMode::Normal;
+                                }
                                 _ => {}
                             }
                             app_core.needs_render = true;
@@ -2248,7 +3452,10 @@ fn handle_frontend_event(
                                     browser.page_down()
                                 }
                                 crate::core::menu_actions::MenuAction::Cancel => {
-                                    frontend.theme_browser = None;
+                                    frontend.close_theme_browser(
+                                        app_core.config.active_theme.clone(),
+                                        app_core.config.get_theme(),
+                                    );
                                     app_core.ui_state.input_mode = InputMode::Normal;
                                 }
                                 crate::core::menu_actions::MenuAction::Select => {
@@ -2331,11 +3538,16 @@ fn handle_frontend_event(
                             use crate::frontend::tui::widget_traits::{
                                 Cyclable, Navigable, Toggleable,
                             };
-                            let action = input_router::route_input(
-                                key_event,
-                                &app_core.ui_state.input_mode,
-                                &app_core.config,
-                            );
+                            // Search text (including Esc to clear it) belongs to the editor
+                            let action = if editor.is_searching() {
+                                crate::core::menu_actions::MenuAction::None
+                            } else {
+                                input_router::route_input(
+                                    key_event,
+                                    &app_core.ui_state.input_mode,
+                                    &app_core.config,
+                                )
+                            };
 
                             match action {
                                 crate::core::menu_actions::MenuAction::NavigateUp => {
@@ -2363,6 +3575,7 @@ fn handle_frontend_event(
                             }
                             app_core.needs_render = true;
                         }
+                        apply_settings_changes(app_core, frontend)?;
                         return Ok(None);
                     }
                     InputMode::HighlightForm => {
@@ -2408,16 +3621,22 @@ fn handle_frontend_event(
                                     if let Some(result) = form.handle_key(key) {
                                         match result {
                                             crate::frontend::tui::highlight_form::FormResult::Save { name, pattern } => {
-                                                app_core.config.highlights.insert(name.clone(), pattern);
+                                                app_core.config.highlights.insert(name.clone(), *pattern);
+                                                frontend.refresh_highlights(app_core);
+                                                app_core.refresh_sound_triggers();
                                                 frontend.highlight_form = None;
                                                 app_core.ui_state.input_mode = InputMode::Normal;
                                                 tracing::info!("Saved highlight: {}", name);
+                                                offer_config_review(app_core, frontend);
                                             }
                                             crate::frontend::tui::highlight_form::FormResult::Delete { name } => {
                                                 app_core.config.highlights.remove(&name);
+                                                frontend.refresh_highlights(app_core);
+                                                app_core.refresh_sound_triggers();
                                                 frontend.highlight_form = None;
                                                 app_core.ui_state.input_mode = InputMode::Normal;
                                                 tracing::info!("Deleted highlight: {}", name);
+                                                offer_config_review(app_core, frontend);
                                             }
                                             crate::frontend::tui::highlight_form::FormResult::Cancel => {
                                                 frontend.highlight_form = None;
@@ -2436,11 +3655,16 @@ fn handle_frontend_event(
                             use crate::frontend::tui::widget_traits::{
                                 Cyclable, FieldNavigable, TextEditable, Toggleable,
                             };
-                            let action = input_router::route_input(
-                                key_event,
-                                &app_core.ui_state.input_mode,
-                                &app_core.config,
-                            );
+                            // The conflict dialog takes every key until it is resolved
+                            let action = if form.has_conflicts() {
+                                crate::core::menu_actions::MenuAction::None
+                            } else {
+                                input_router::route_input(
+                                    key_event,
+                                    &app_core.ui_state.input_mode,
+                                    &app_core.config,
+                                )
+                            };
 
                             match action {
                                 crate::core::menu_actions::MenuAction::NextField => {
@@ -2473,8 +3697,25 @@ fn handle_frontend_event(
                                     let key = crossterm::event::KeyEvent::new(code, modifiers);
                                     if let Some(result) = form.handle_key(key) {
                                         match result {
-                                            crate::frontend::tui::keybind_form::KeybindFormResult::Save { key_combo, action_type, value } => {
+                                            crate::frontend::tui::keybind_form::KeybindFormResult::Save { key_combo, action_type, value, replace } => {
                                                 use crate::frontend::tui::keybind_form::KeybindActionType;
+                                                let replace = match replace {
+                                                    Some(replace) => replace,
+                                                    None => {
+                                                        let conflicts = app_core.config.keybind_conflicts(&key_combo, form.original_key());
+                                                        if !conflicts.is_empty() {
+                                                            // Ask how to resolve instead of silently overwriting/shadowing
+                                                            form.show_conflicts(conflicts);
+                                                            app_core.needs_render = true;
+                                                            return Ok(None);
+                                                        }
+                                                        Vec::new()
+                                                    }
+                                                };
+                                                for combo in &replace {
+                                                    app_core.config.keybinds.remove(combo);
+                                                    tracing::info!("Removed conflicting keybind: {}", combo);
+                                                }
                                                 let action = match action_type {
                                                     KeybindActionType::Action => crate::config::KeyBindAction::Action(value),
                                                     KeybindActionType::Macro => crate::config::KeyBindAction::Macro(
@@ -2486,6 +3727,7 @@ fn handle_frontend_event(
                                                 frontend.keybind_form = None;
                                                 app_core.ui_state.input_mode = InputMode::Normal;
                                                 tracing::info!("Saved keybind: {}", key_combo);
+                                                offer_config_review(app_core, frontend);
                                             }
                                             crate::frontend::tui::keybind_form::KeybindFormResult::Delete { key_combo } => {
                                                 app_core.config.keybinds.remove(&key_combo);
@@ -2493,6 +3735,7 @@ fn handle_frontend_event(
                                                 frontend.keybind_form = None;
                                                 app_core.ui_state.input_mode = InputMode::Normal;
                                                 tracing::info!("Deleted keybind: {}", key_combo);
+                                                offer_config_review(app_core, frontend);
                                             }
                                             crate::frontend::tui::keybind_form::KeybindFormResult::Cancel => {
                                                 frontend.keybind_form = None;
@@ -2564,6 +3807,7 @@ fn handle_frontend_event(
                                                 frontend.color_form = None;
                                                 app_core.ui_state.input_mode = InputMode::Normal;
                                                 tracing::info!("Saved color: {}", color.name);
+                                                offer_config_review(app_core, frontend);
                                             }
                                             crate::frontend::tui::color_form::FormAction::Delete => {
                                                 // Delete handled by original_name
@@ -2630,6 +3874,7 @@ fn handle_frontend_event(
                                                 frontend.spell_color_form = None;
                                                 app_core.ui_state.input_mode = InputMode::Normal;
                                                 tracing::info!("Saved spell color range");
+                                                offer_config_review(app_core, frontend);
                                             }
                                             crate::frontend::tui::spell_color_form::SpellColorFormResult::Delete(index) => {
                                                 if index < app_core.config.colors.spell_colors.len() {
@@ -2638,6 +3883,7 @@ fn handle_frontend_event(
                                                 }
                                                 frontend.spell_color_form = None;
                                                 app_core.ui_state.input_mode = InputMode::Normal;
+                                                offer_config_review(app_core, frontend);
                                             }
                                             crate::frontend::tui::spell_color_form::SpellColorFormResult::Cancel => {
                                                 frontend.spell_color_form = None;
@@ -2691,6 +3937,79 @@ fn handle_frontend_event(
                         }
                         return Ok(None);
                     }
+                    InputMode::CopyMode => {
+                        handle_copy_mode_key(app_core, frontend, code, modifiers);
+                        return Ok(None);
+                    }
+                    InputMode::SnippetBrowser => {
+                        if let Some(ref mut browser) = frontend.snippet_browser {
+                            use crate::frontend::tui::widget_traits::{Navigable, Selectable};
+                            let action = input_router::route_input(
+                                key_event,
+                                &app_core.ui_state.input_mode,
+                                &app_core.config,
+                            );
+
+                            match action {
+                                crate::core::menu_actions::MenuAction::NavigateUp => {
+                                    browser.navigate_up()
+                                }
+                                crate::core::menu_actions::MenuAction::NavigateDown => {
+                                    browser.navigate_down()
+                                }
+                                crate::core::menu_actions::MenuAction::PageUp => browser.page_up(),
+                                crate::core::menu_actions::MenuAction::PageDown => {
+                                    browser.page_down()
+                                }
+                                crate::core::menu_actions::MenuAction::Home => browser.home(),
+                                crate::core::menu_actions::MenuAction::End => browser.end(),
+                                crate::core::menu_actions::MenuAction::Cancel => {
+                                    frontend.snippet_browser = None;
+                                    app_core.ui_state.input_mode = InputMode::Normal;
+                                }
+                                crate::core::menu_actions::MenuAction::Delete => {
+                                    if let Some(name) = browser.delete_selected() {
+                                        app_core.delete_snippet(&name);
+                                    }
+                                }
+                                crate::core::menu_actions::MenuAction::Select => {
+                                    if let Some(name) = browser.get_selected() {
+                                        frontend.snippet_browser = None;
+                                        app_core.ui_state.input_mode = InputMode::Normal;
+                                        app_core.run_snippet(&name, &[]);
+                                    }
+                                }
+                                crate::core::menu_actions::MenuAction::Edit => {
+                                    if let Some(name) = browser.get_selected() {
+                                        frontend.snippet_browser = None;
+                                        dispatch_action(
+                                            app_core,
+                                            frontend,
+                                            core::actions::AppAction::EditSnippet(name),
+                                        )?;
+                                    }
+                                }
+                                _ => {}
+                            }
+                            app_core.needs_render = true;
+                        }
+                        return Ok(None);
+                    }
+                    InputMode::Composer => {
+                        if code == KeyCode::Char('s') && modifiers.contains(KeyModifiers::CONTROL) {
+                            if let Some(composer) = frontend.composer.take() {
+                                match composer.snippet {
+                                    Some(ref name) => app_core.save_snippet(name, &composer.text()),
+                                    None => app_core.send_composed_lines(&composer.lines()),
+                                }
+                            }
+                            app_core.ui_state.input_mode = InputMode::Normal;
+                        } else if let Some(ref mut composer) = frontend.composer {
+                            composer.input(crossterm::event::KeyEvent::new(code, modifiers));
+                        }
+                        app_core.needs_render = true;
+                        return Ok(None);
+                    }
                     _ => {
                         // Other modes fall through
                     }
@@ -2975,10 +4294,14 @@ fn handle_frontend_event(
                                         app_core.layout.get_window(window_name)
                                     {
                                         // Open window editor
-                                        frontend.window_editor =
-                                            Some(frontend::tui::window_editor::WindowEditor::new(
+                                        let mut editor =
+                                            frontend::tui::window_editor::WindowEditor::new(
                                                 window_def.clone(),
-                                            ));
+                                            );
+                                        editor.set_container(
+                                            app_core.layout.container_of(window_name),
+                                        );
+                                        frontend.window_editor = Some(editor);
                                         app_core.ui_state.input_mode =
                                             data::ui_state::InputMode::WindowEditor;
                                         tracing::info!(
@@ -3010,6 +4333,8 @@ fn handle_frontend_event(
                                     if command.starts_with("action:") {
                                         // Internal action - handle it
                                         handle_menu_action(app_core, frontend, &command)?;
+                                    } else if let Some(text) = command.strip_prefix("macro:") {
+                                        app_core.start_menu_macro(text);
                                     } else if command.starts_with(".") {
                                         // Dot command - handle locally via handle_menu_action
                                         let action_command = format!("action:{}", &command[1..]); // Convert .addcolor to action:addcolor
@@ -3149,6 +4474,10 @@ fn handle_frontend_event(
                                     }
                                 }
 
+                                // Move into (or out of) a container group
+                                let container = editor.container();
+                                app_core.assign_container(window_def.name(), container.as_deref());
+
                                 // Mark layout as modified
                                 app_core.mark_layout_modified();
 
@@ -3195,7 +4524,20 @@ fn handle_frontend_event(
                 }
                 return Ok(None);
             } else if app_core.ui_state.input_mode == InputMode::Search {
-                // Handle search input
+                // Keys bound in the [search] table of keybind_modes.toml win over editing
+                let key_event = crossterm::event::KeyEvent::new(code, modifiers);
+                if let Some(action) = app_core.lookup_keybind(&key_event) {
+                    app_core.needs_render = true;
+                    if let config::KeyBindAction::Action(name) = &action {
+                        handle_menu_action(app_core, frontend, name)?;
+                    } else {
+                        app_core.execute_keybind_action(&action)?;
+                    }
+                    return Ok(None);
+                }
+
+                // Handle search input (numpad keys type their characters here)
+                let code = config::keypad_char(code).map_or(code, KeyCode::Char);
                 match code {
                     KeyCode::Enter => {
                         // Execute search
@@ -3209,6 +4551,7 @@ fn handle_frontend_event(
                                     } else {
                                         tracing::info!("No matches found for '{}'", pattern);
                                     }
+                                    app_core.record_search(&window_name, &pattern);
                                     app_core.needs_render = true;
                                 }
                                 Err(e) => {
@@ -3257,6 +4600,14 @@ fn handle_frontend_event(
                         app_core.ui_state.search_cursor = app_core.ui_state.search_input.len();
                         app_core.needs_render = true;
                     }
+                    KeyCode::Up => {
+                        // Recall older search for this window
+                        app_core.search_history_previous();
+                    }
+                    KeyCode::Down => {
+                        // Step forward through search history
+                        app_core.search_history_next();
+                    }
                     _ => {}
                 }
             } else {
@@ -3267,8 +4618,24 @@ fn handle_frontend_event(
                 // Handle Enter key specially - always submit command, never keybind
                 match code {
                     KeyCode::Enter => {
-                        // Submit command from CommandInput widget
-                        if let Some(command) = frontend.command_input_submit("command_input") {
+                        // Enter on an empty line pages on from "-- more --"
+                        if frontend.is_more_paused()
+                            && !frontend.command_input_has_text("command_input")
+                        {
+                            frontend.acknowledge_more();
+                            app_core.needs_render = true;
+                            return Ok(None);
+                        }
+                        // Typing a command means the screen has been read; output stays held if paused
+                        if !frontend.is_more_paused() {
+                            frontend.acknowledge_more();
+                        }
+                        // Submit command from CommandInput widget (also resumes a macro waiting on \?)
+                        let submitted = frontend.command_input_submit("command_input");
+                        app_core.macro_input_submitted();
+                        // A snippet asking for a placeholder takes the line as its value
+                        let submitted = submitted.filter(|text| !app_core.answer_snippet(text));
+                        if let Some(command) = submitted {
                             // Special handling for .savelayout - needs terminal size
                             if command.starts_with(".savelayout ") || command == ".savelayout" {
                                 let name = command
@@ -3340,55 +4707,56 @@ fn handle_frontend_event(
                     _ => {
                         // Check for non-command-input keybinds first (Tab, F12, Ctrl+R, Ctrl+T, etc.)
                         let key_event = crossterm::event::KeyEvent::new(code, modifiers);
-                        if let Some(action) = app_core.keybind_map.get(&key_event).cloned() {
+
+                        // Multi-key chords ("ctrl+x h") are matched before single-key binds
+                        let action = match app_core.process_chord_key(key_event) {
+                            core::app_core::ChordResult::Complete(action) => Some(action),
+                            core::app_core::ChordResult::Pending
+                            | core::app_core::ChordResult::Cancelled => {
+                                app_core.needs_render = true;
+                                return Ok(None);
+                            }
+                            core::app_core::ChordResult::NotChord => {
+                                app_core.lookup_keybind(&key_event)
+                            }
+                        };
+
+                        if let Some(action) = action {
                             // Check if this is a command-input action that should be handled by the widget
                             let is_command_input_action = matches!(&action,
                                 config::KeyBindAction::Action(s) if matches!(s.as_str(),
                                     "cursor_left" | "cursor_right" | "cursor_word_left" | "cursor_word_right" |
                                     "cursor_home" | "cursor_end" | "cursor_backspace" | "cursor_delete" |
-                                    "previous_command" | "next_command" | "send_last_command" | "send_second_last_command"
-                                )
+                                    "previous_command" | "next_command" | "send_last_command" | "send_second_last_command" |
+                                    "history_search"
+                                ) || (s == "switch_current_window" && frontend.command_input_has_text("command_input"))
                             );
 
                             if is_command_input_action {
                                 // Route to CommandInput widget instead of app_core
-                                let available_commands = app_core.get_available_commands();
-                                let available_window_names = app_core.get_window_names();
                                 frontend.command_input_key(
                                     "command_input",
                                     code,
                                     modifiers,
-                                    &available_commands,
-                                    &available_window_names,
+                                    app_core,
                                 );
                                 app_core.needs_render = true;
+                            } else if let config::KeyBindAction::Action(name) = &action {
+                                // Named actions (built-in, menu, or custom) share the menu dispatch path
+                                if let Err(e) = handle_menu_action(app_core, frontend, name) {
+                                    tracing::warn!("Keybind action failed: {}", e);
+                                }
+                                app_core.needs_render = true;
                             } else {
-                                // Execute non-command-input keybind actions
-                                match app_core.execute_keybind_action(&action) {
-                                    Ok(commands) => {
-                                        // Return first command from macro (if any) to be sent to server
-                                        if let Some(cmd) = commands.into_iter().next() {
-                                            app_core.needs_render = true;
-                                            return Ok(Some(cmd));
-                                        }
-                                    }
-                                    Err(e) => {
-                                        tracing::warn!("Keybind action failed: {}", e);
-                                    }
+                                // Start macro keybinds; the main loop's poll_macro() sends their output
+                                if let Err(e) = app_core.execute_keybind_action(&action) {
+                                    tracing::warn!("Keybind action failed: {}", e);
                                 }
                                 app_core.needs_render = true;
                             }
                         } else {
                             // No keybind - route to CommandInput widget for typing
-                            let available_commands = app_core.get_available_commands();
-                            let available_window_names = app_core.get_window_names();
-                            frontend.command_input_key(
-                                "command_input",
-                                code,
-                                modifiers,
-                                &available_commands,
-                                &available_window_names,
-                            );
+                            frontend.command_input_key("command_input", code, modifiers, app_core);
                             app_core.needs_render = true;
                         }
                     }
@@ -3403,8 +4771,68 @@ fn handle_frontend_event(
             // Just log the terminal size change for debugging
             tracing::info!("Terminal resized to {}x{} (auto-resize disabled, use .resize command)", width, height);
         }
+        FrontendEvent::Focus { gained } => {
+            app_core.set_terminal_focused(gained);
+        }
+        FrontendEvent::Paste { text } => return handle_paste(app_core, frontend, &text),
         _ => {}
     }
 
     Ok(None)
 }
+
+/// Bracketed paste: one line goes in the command input, several wait for the paste dialog
+fn handle_paste(
+    app_core: &mut core::AppCore,
+    frontend: &mut frontend::tui::TuiFrontend,
+    text: &str,
+) -> Result<Option<String>> {
+    use data::ui_state::InputMode;
+
+    app_core.needs_render = true;
+    match app_core.ui_state.input_mode {
+        InputMode::Normal | InputMode::Navigation | InputMode::History => {
+            let mut lines = core::paste::split_lines(text);
+            if lines.len() <= 1 {
+                if let Some(line) = lines.pop() {
+                    frontend.command_input_insert_text("command_input", &line);
+                }
+            } else if let Some(menu) = app_core.hold_paste(lines) {
+                app_core.ui_state.popup_menu = Some(menu);
+                app_core.ui_state.input_mode = InputMode::Menu;
+            }
+            Ok(None)
+        }
+        InputMode::Search => {
+            let line = core::paste::split_lines(text)
+                .into_iter()
+                .next()
+                .unwrap_or_default();
+            for c in line.chars() {
+                let pos = app_core.ui_state.search_cursor;
+                app_core.ui_state.search_input.insert(pos, c);
+                app_core.ui_state.search_cursor += 1;
+            }
+            Ok(None)
+        }
+        // Keys mean commands here, not text
+        InputMode::Menu | InputMode::CopyMode | InputMode::SnippetBrowser => Ok(None),
+        InputMode::Composer => {
+            if let Some(ref mut composer) = frontend.composer {
+                composer.insert_text(text);
+            }
+            Ok(None)
+        }
+        // Forms and editors take it as typing, as they did before bracketed paste
+        _ => {
+            for c in text.chars().filter(|c| !c.is_control()) {
+                let key = frontend::FrontendEvent::Key {
+                    code: crossterm::event::KeyCode::Char(c),
+                    modifiers: crossterm::event::KeyModifiers::NONE,
+                };
+                handle_frontend_event(app_core, frontend, key)?;
+            }
+            Ok(None)
+        }
+    }
+}
@@ -5,7 +5,7 @@
 //! operate on higher-level `ParsedElement` values instead of raw XML.
 
 use crate::config::EventAction;
-use crate::data::LinkData;
+use crate::data::{ItemFlags, LinkData};
 use regex::Regex;
 use std::collections::HashMap;
 
@@ -17,6 +17,7 @@ pub enum SpanType {
     Monsterbold, // <preset id="monsterbold"> from parser
     Spell,       // <spell> tag from parser
     Speech,      // <preset id="speech"> from parser
+    Whisper,     // <preset id="whisper"> from parser
 }
 
 /// Convenience struct used while normalizing spans before they are wrapped in a
@@ -645,6 +646,7 @@ impl XmlParser {
                         noun,
                         text: item.clone(),
                         coord: Self::extract_attribute(whole_tag, "coord"),
+                        flags: ItemFlags::held(),
                     });
                 elements.push(ParsedElement::LeftHand { item, link });
             }
@@ -668,6 +670,7 @@ impl XmlParser {
                         noun,
                         text: item.clone(),
                         coord: Self::extract_attribute(whole_tag, "coord"),
+                        flags: ItemFlags::held(),
                     });
                 elements.push(ParsedElement::RightHand { item, link });
             }
@@ -1070,6 +1073,7 @@ impl XmlParser {
             noun: cmd.clone().unwrap_or_default(), // Store cmd in noun field temporarily
             text: String::new(),                // Will be populated as text is rendered
             coord: None,                        // <d> tags don't use coords
+            flags: ItemFlags::default(),
         });
 
         // Don't apply color if we're inside monsterbold (bold has priority)
@@ -1143,6 +1147,7 @@ impl XmlParser {
                 noun: n,
                 text: String::new(), // Will be populated as text is rendered
                 coord,               // Optional coord for direct commands
+                flags: ItemFlags::default(), // Filled in per line by core::item_flags
             });
         }
 
@@ -1311,6 +1316,8 @@ impl XmlParser {
             SpanType::Link
         } else if self.current_preset_id.as_deref() == Some("speech") {
             SpanType::Speech
+        } else if self.current_preset_id.as_deref() == Some("whisper") {
+            SpanType::Whisper
         } else {
             SpanType::Normal
         };
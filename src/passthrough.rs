@@ -0,0 +1,246 @@
+//! Read-only mirror of the raw game stream for companion tools
+//!
+//! Loggers and analyzers can connect to a local TCP port and receive every
+//! line the game server sends, exactly as it arrived (XML and all), without
+//! opening a second game connection. Features:
+//! - Plain `std::net`: one thread accepts clients, another writes to them, so
+//!   a slow client never holds up the UI thread
+//! - Only addresses on the allowlist may connect (loopback by default)
+//! - Write-only: the read half of each client socket is shut down, so nothing
+//!   a client sends can reach the game connection
+
+use crate::config::PassthroughConfig;
+use std::io::Write;
+use std::net::{IpAddr, Shutdown, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// A client that can't take a line within this long is dropped
+const WRITE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Counters shared with the accept and writer threads
+#[derive(Debug, Default)]
+struct Counters {
+    clients: AtomicUsize,
+    bytes: AtomicU64,
+}
+
+/// Passthrough manager owned by AppCore
+///
+/// When disabled in config this is a no-op and `mirror` returns immediately.
+pub struct Passthrough {
+    config: PassthroughConfig,
+    tx: Option<Sender<Arc<[u8]>>>,
+    counters: Arc<Counters>,
+    /// Address being served, or why the port is not open
+    status: Result<String, String>,
+}
+
+impl Passthrough {
+    pub fn new(config: &PassthroughConfig) -> Self {
+        let mut passthrough = Self {
+            config: config.clone(),
+            tx: None,
+            counters: Arc::default(),
+            status: Err("disabled (set enabled = true under [passthrough])".to_string()),
+        };
+        if config.enabled {
+            passthrough.status = passthrough.start();
+            match &passthrough.status {
+                Ok(addr) => tracing::info!("Raw stream passthrough listening on {}", addr),
+                Err(e) => tracing::warn!("Raw stream passthrough not started: {}", e),
+            }
+        }
+        passthrough
+    }
+
+    /// Tools currently connected
+    pub fn clients(&self) -> usize {
+        self.counters.clients.load(Ordering::Relaxed)
+    }
+
+    /// Bytes written to tools this session (summed over clients)
+    pub fn bytes_mirrored(&self) -> u64 {
+        self.counters.bytes.load(Ordering::Relaxed)
+    }
+
+    /// One-line description for `.passthrough`
+    pub fn status_line(&self) -> String {
+        match &self.status {
+            Ok(addr) => format!(
+                "Raw passthrough: {} ({} client(s), {} bytes mirrored; allowed: {})",
+                addr,
+                self.clients(),
+                self.bytes_mirrored(),
+                self.config.allow.join(", ")
+            ),
+            Err(reason) => format!("Raw passthrough: {}", reason),
+        }
+    }
+
+    /// Hand one raw server line to the connected tools
+    pub fn mirror(&self, line: &str) {
+        let Some(tx) = &self.tx else {
+            return;
+        };
+        if self.clients() == 0 {
+            return;
+        }
+        let mut bytes = Vec::with_capacity(line.len() + 1);
+        bytes.extend_from_slice(line.as_bytes());
+        bytes.push(b'\n');
+        let _ = tx.send(bytes.into());
+    }
+
+    fn start(&mut self) -> Result<String, String> {
+        let allow = parse_allowlist(&self.config.allow)?;
+        let addr = format!("{}:{}", self.config.bind, self.config.port);
+        let listener =
+            TcpListener::bind(&addr).map_err(|e| format!("cannot listen on {}: {}", addr, e))?;
+        let addr = listener
+            .local_addr()
+            .map_err(|e| e.to_string())?
+            .to_string();
+
+        let clients: Arc<Mutex<Vec<TcpStream>>> = Arc::default();
+        let (tx, rx) = mpsc::channel();
+
+        let accept_clients = Arc::clone(&clients);
+        let accept_counters = Arc::clone(&self.counters);
+        std::thread::Builder::new()
+            .name("passthrough-accept".to_string())
+            .spawn(move || accept(listener, &allow, &accept_clients, &accept_counters))
+            .map_err(|e| e.to_string())?;
+
+        let counters = Arc::clone(&self.counters);
+        std::thread::Builder::new()
+            .name("passthrough-write".to_string())
+            .spawn(move || write_loop(rx, &clients, &counters))
+            .map_err(|e| e.to_string())?;
+
+        self.tx = Some(tx);
+        Ok(addr)
+    }
+}
+
+/// Parse the allowlist ("127.0.0.1", "::1", ...); an empty list is refused
+fn parse_allowlist(entries: &[String]) -> Result<Vec<IpAddr>, String> {
+    if entries.is_empty() {
+        return Err("empty allow list under [passthrough]; refusing to listen".to_string());
+    }
+    entries
+        .iter()
+        .map(|entry| {
+            entry
+                .trim()
+                .parse()
+                .map_err(|_| format!("'{}' in [passthrough] allow is not an IP address", entry))
+        })
+        .collect()
+}
+
+/// Is `ip` on the allowlist? (IPv4 clients may also arrive as IPv4-mapped IPv6)
+fn is_allowed(allow: &[IpAddr], ip: IpAddr) -> bool {
+    let ip = match ip {
+        IpAddr::V6(v6) => v6.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(ip),
+        v4 => v4,
+    };
+    allow.contains(&ip)
+}
+
+fn accept(
+    listener: TcpListener,
+    allow: &[IpAddr],
+    clients: &Mutex<Vec<TcpStream>>,
+    counters: &Counters,
+) {
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else { continue };
+        let Ok(peer) = stream.peer_addr() else {
+            continue;
+        };
+        if !is_allowed(allow, peer.ip()) {
+            tracing::warn!("Raw passthrough refused {} (not in allow list)", peer);
+            continue;
+        }
+        let _ = stream.shutdown(Shutdown::Read);
+        let _ = stream.set_nodelay(true);
+        let _ = stream.set_write_timeout(Some(WRITE_TIMEOUT));
+        tracing::info!("Raw passthrough client connected: {}", peer);
+        if let Ok(mut clients) = clients.lock() {
+            clients.push(stream);
+            counters.clients.store(clients.len(), Ordering::Relaxed);
+        }
+    }
+}
+
+fn write_loop(rx: Receiver<Arc<[u8]>>, clients: &Mutex<Vec<TcpStream>>, counters: &Counters) {
+    for chunk in rx {
+        let Ok(mut clients) = clients.lock() else {
+            return;
+        };
+        clients.retain_mut(|client| match client.write_all(&chunk) {
+            Ok(()) => {
+                counters
+                    .bytes
+                    .fetch_add(chunk.len() as u64, Ordering::Relaxed);
+                true
+            }
+            Err(e) => {
+                tracing::info!("Raw passthrough client dropped: {}", e);
+                false
+            }
+        });
+        counters.clients.store(clients.len(), Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn test_allowlist() {
+        let allow = parse_allowlist(&["127.0.0.1".to_string(), " ::1 ".to_string()]).unwrap();
+        assert!(is_allowed(&allow, "127.0.0.1".parse().unwrap()));
+        assert!(is_allowed(&allow, "::ffff:127.0.0.1".parse().unwrap()));
+        assert!(is_allowed(&allow, "::1".parse().unwrap()));
+        assert!(!is_allowed(&allow, "192.168.1.20".parse().unwrap()));
+
+        assert!(parse_allowlist(&[]).is_err());
+        assert!(parse_allowlist(&["localhost".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_mirror_to_client() {
+        let config = PassthroughConfig {
+            enabled: true,
+            port: 0, // Any free port
+            ..PassthroughConfig::default()
+        };
+        let passthrough = Passthrough::new(&config);
+        let addr = passthrough.status.clone().unwrap();
+        let mut tool = TcpStream::connect(&addr).unwrap();
+        tool.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+
+        let connected = std::time::Instant::now();
+        while passthrough.clients() == 0 && connected.elapsed() < Duration::from_secs(5) {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(passthrough.clients(), 1);
+
+        passthrough.mirror("<pushStream id=\"thoughts\"/>");
+        let mut received = vec![0; 28];
+        tool.read_exact(&mut received).unwrap();
+        assert_eq!(received, b"<pushStream id=\"thoughts\"/>\n");
+
+        let written = std::time::Instant::now();
+        while passthrough.bytes_mirrored() < 28 && written.elapsed() < Duration::from_secs(5) {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(passthrough.bytes_mirrored(), 28);
+    }
+}
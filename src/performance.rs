@@ -4,9 +4,36 @@
 //! network IO, and general memory indicators so the UI can surface them in the
 //! performance overlay as well as log spikes for diagnostics.
 
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::Write;
 use std::time::{Duration, Instant};
 
+/// Times consecutive steps of one pass (e.g. each widget sync in a frame)
+pub struct LapTimer {
+    last: Instant,
+    laps: Vec<(&'static str, Duration)>,
+}
+
+impl LapTimer {
+    pub fn start() -> Self {
+        Self {
+            last: Instant::now(),
+            laps: Vec::new(),
+        }
+    }
+
+    /// Close the step `name` (it ran since the previous lap)
+    pub fn lap(&mut self, name: &'static str) {
+        let now = Instant::now();
+        self.laps.push((name, now.duration_since(self.last)));
+        self.last = now;
+    }
+
+    pub fn finish(self) -> Vec<(&'static str, Duration)> {
+        self.laps
+    }
+}
+
 /// Performance statistics tracker
 #[derive(Debug, Clone)]
 pub struct PerformanceStats {
@@ -21,12 +48,14 @@ pub struct PerformanceStats {
     network_sample_start: Instant,
     bytes_received_last_second: u64,
     bytes_sent_last_second: u64,
+    passthrough_clients: usize, // Tools on the raw passthrough port
+    passthrough_bytes: u64,     // Bytes mirrored to them this session
 
     // Parser stats
     parse_times: VecDeque<Duration>,
-    chunks_parsed: u64,
+    lines_parsed: u64,
     parse_sample_start: Instant,
-    chunks_parsed_last_second: u64,
+    lines_parsed_last_second: u64,
     max_parse_samples: usize,
 
     // General
@@ -51,6 +80,15 @@ pub struct PerformanceStats {
     elements_parsed: u64, // Total XML elements parsed
     elements_sample_start: Instant,
     elements_parsed_last_second: u64,
+
+    // Per-widget sync time (widget kind -> recent samples)
+    sync_times: BTreeMap<&'static str, VecDeque<Duration>>,
+
+    // Channel backlogs: lines waiting at last check, and the peak since reset
+    network_backlog: usize,
+    parse_backlog: usize,
+    peak_network_backlog: usize,
+    peak_parse_backlog: usize,
 }
 
 impl Default for PerformanceStats {
@@ -73,19 +111,21 @@ impl PerformanceStats {
             network_sample_start: now,
             bytes_received_last_second: 0,
             bytes_sent_last_second: 0,
+            passthrough_clients: 0,
+            passthrough_bytes: 0,
 
             parse_times: VecDeque::with_capacity(60),
-            chunks_parsed: 0,
+            lines_parsed: 0,
             parse_sample_start: now,
-            chunks_parsed_last_second: 0,
+            lines_parsed_last_second: 0,
             max_parse_samples: 60,
 
             app_start_time: now,
 
-            render_times: VecDeque::with_capacity(60),
-            ui_render_times: VecDeque::with_capacity(60),
-            text_wrap_times: VecDeque::with_capacity(60),
-            max_render_samples: 60,
+            render_times: VecDeque::with_capacity(300),
+            ui_render_times: VecDeque::with_capacity(300),
+            text_wrap_times: VecDeque::with_capacity(300),
+            max_render_samples: 300, // Enough frames for meaningful p99
 
             event_process_times: VecDeque::with_capacity(100),
             events_processed: 0,
@@ -97,9 +137,23 @@ impl PerformanceStats {
             elements_parsed: 0,
             elements_sample_start: now,
             elements_parsed_last_second: 0,
+
+            sync_times: BTreeMap::new(),
+
+            network_backlog: 0,
+            parse_backlog: 0,
+            peak_network_backlog: 0,
+            peak_parse_backlog: 0,
         }
     }
 
+    /// Clear every sample and counter (uptime keeps counting)
+    pub fn reset(&mut self) {
+        let app_start_time = self.app_start_time;
+        *self = Self::new();
+        self.app_start_time = app_start_time;
+    }
+
     /// Record a frame render
     pub fn record_frame(&mut self) {
         let now = Instant::now();
@@ -128,6 +182,12 @@ impl PerformanceStats {
         }
     }
 
+    /// Record the raw passthrough port's client count and bytes mirrored so far
+    pub fn record_passthrough(&mut self, clients: usize, bytes: u64) {
+        self.passthrough_clients = clients;
+        self.passthrough_bytes = bytes;
+    }
+
     /// Record bytes sent to network
     pub fn record_bytes_sent(&mut self, bytes: u64) {
         self.bytes_sent += bytes;
@@ -143,7 +203,7 @@ impl PerformanceStats {
         }
     }
 
-    /// Record a parse operation
+    /// Record the parse of one server line
     pub fn record_parse(&mut self, duration: Duration) {
         let now = Instant::now();
 
@@ -152,12 +212,12 @@ impl PerformanceStats {
             self.parse_times.pop_front();
         }
 
-        self.chunks_parsed += 1;
+        self.lines_parsed += 1;
 
         // Update per-second stats
         if now.duration_since(self.parse_sample_start) >= Duration::from_secs(1) {
-            self.chunks_parsed_last_second = self.chunks_parsed;
-            self.chunks_parsed = 0;
+            self.lines_parsed_last_second = self.lines_parsed;
+            self.lines_parsed = 0;
             self.parse_sample_start = now;
         }
     }
@@ -216,6 +276,16 @@ impl PerformanceStats {
         self.bytes_sent_last_second
     }
 
+    /// Get tools connected to the raw passthrough port
+    pub fn passthrough_clients(&self) -> usize {
+        self.passthrough_clients
+    }
+
+    /// Get bytes mirrored to passthrough tools this session
+    pub fn passthrough_bytes(&self) -> u64 {
+        self.passthrough_bytes
+    }
+
     /// Get average parse time in microseconds
     pub fn avg_parse_time_us(&self) -> f64 {
         if self.parse_times.is_empty() {
@@ -226,9 +296,9 @@ impl PerformanceStats {
         total.as_secs_f64() * 1_000_000.0 / self.parse_times.len() as f64
     }
 
-    /// Get chunks parsed per second
-    pub fn chunks_per_sec(&self) -> u64 {
-        self.chunks_parsed_last_second
+    /// Get server lines parsed per second
+    pub fn lines_per_sec(&self) -> u64 {
+        self.lines_parsed_last_second
     }
 
     /// Get app uptime
@@ -299,6 +369,25 @@ impl PerformanceStats {
         }
     }
 
+    /// Record how long each widget kind took to sync this frame
+    pub fn record_sync_times(&mut self, laps: Vec<(&'static str, Duration)>) {
+        for (name, duration) in laps {
+            let samples = self.sync_times.entry(name).or_default();
+            samples.push_back(duration);
+            if samples.len() > self.max_render_samples {
+                samples.pop_front();
+            }
+        }
+    }
+
+    /// Record lines waiting in the network channel and the parse worker
+    pub fn record_backlogs(&mut self, network: usize, parse: usize) {
+        self.network_backlog = network;
+        self.parse_backlog = parse;
+        self.peak_network_backlog = self.peak_network_backlog.max(network);
+        self.peak_parse_backlog = self.peak_parse_backlog.max(parse);
+    }
+
     // === Getters for new metrics ===
 
     /// Get average render time in milliseconds
@@ -319,6 +408,18 @@ impl PerformanceStats {
             .unwrap_or(0.0)
     }
 
+    /// Get the render time in milliseconds that `pct` percent of frames stay under
+    pub fn render_time_percentile_ms(&self, pct: f64) -> f64 {
+        if self.render_times.is_empty() {
+            return 0.0;
+        }
+        let mut sorted: Vec<Duration> = self.render_times.iter().copied().collect();
+        sorted.sort_unstable();
+        // Nearest-rank percentile
+        let rank = (pct / 100.0 * sorted.len() as f64).ceil() as usize;
+        sorted[rank.clamp(1, sorted.len()) - 1].as_secs_f64() * 1000.0
+    }
+
     /// Get average UI render time in milliseconds
     pub fn avg_ui_render_time_ms(&self) -> f64 {
         if self.ui_render_times.is_empty() {
@@ -375,10 +476,133 @@ impl PerformanceStats {
         self.elements_parsed_last_second
     }
 
+    /// Get average sync time per widget kind in microseconds, slowest first
+    pub fn sync_times_us(&self) -> Vec<(&'static str, f64)> {
+        let mut times: Vec<(&'static str, f64)> = self
+            .sync_times
+            .iter()
+            .filter(|(_, samples)| !samples.is_empty())
+            .map(|(name, samples)| {
+                let total: Duration = samples.iter().sum();
+                (
+                    *name,
+                    total.as_secs_f64() * 1_000_000.0 / samples.len() as f64,
+                )
+            })
+            .collect();
+        times.sort_by(|a, b| b.1.total_cmp(&a.1));
+        times
+    }
+
+    /// Get lines waiting in the network channel (current, peak)
+    pub fn network_backlog(&self) -> (usize, usize) {
+        (self.network_backlog, self.peak_network_backlog)
+    }
+
+    /// Get lines waiting on the parse worker (current, peak)
+    pub fn parse_backlog(&self) -> (usize, usize) {
+        (self.parse_backlog, self.peak_parse_backlog)
+    }
+
     /// Estimate memory usage in MB (very rough approximation)
     pub fn estimated_memory_mb(&self) -> f64 {
         // Rough estimate: ~200 bytes per line on average (including overhead)
         let line_bytes = self.total_lines_buffered * 200;
         line_bytes as f64 / (1024.0 * 1024.0)
     }
+
+    /// Snapshot of every metric plus the raw render samples, as
+    /// `metric,value,unit` CSV for attaching to bug reports
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("metric,value,unit\n");
+        let mut row = |metric: &str, value: f64, unit: &str| {
+            let _ = writeln!(csv, "{},{:.3},{}", metric, value, unit);
+        };
+        row("uptime", self.uptime().as_secs_f64(), "s");
+        row("fps", self.fps(), "");
+        row("frame_avg", self.avg_frame_time_ms(), "ms");
+        row("frame_max", self.max_frame_time_ms(), "ms");
+        row("render_avg", self.avg_render_time_ms(), "ms");
+        for pct in [50.0, 95.0, 99.0] {
+            row(
+                &format!("render_p{}", pct),
+                self.render_time_percentile_ms(pct),
+                "ms",
+            );
+        }
+        row("render_max", self.max_render_time_ms(), "ms");
+        row("ui_render_avg", self.avg_ui_render_time_ms(), "ms");
+        row("text_wrap_avg", self.avg_text_wrap_time_us(), "us");
+        for (name, us) in self.sync_times_us() {
+            row(&format!("sync_{}", name), us, "us");
+        }
+        row("net_in", self.bytes_received_per_sec() as f64, "B/s");
+        row("net_out", self.bytes_sent_per_sec() as f64, "B/s");
+        row("passthrough_clients", self.passthrough_clients as f64, "");
+        row("passthrough_bytes", self.passthrough_bytes as f64, "B");
+        row("parse_avg", self.avg_parse_time_us(), "us");
+        row("lines_per_sec", self.lines_per_sec() as f64, "");
+        row("elements_per_sec", self.elements_per_sec() as f64, "");
+        row("network_backlog", self.network_backlog as f64, "lines");
+        row(
+            "network_backlog_peak",
+            self.peak_network_backlog as f64,
+            "lines",
+        );
+        row("parse_backlog", self.parse_backlog as f64, "lines");
+        row(
+            "parse_backlog_peak",
+            self.peak_parse_backlog as f64,
+            "lines",
+        );
+        row("event_avg", self.avg_event_process_time_us(), "us");
+        row("event_max", self.max_event_process_time_us(), "us");
+        row("events_processed", self.events_processed as f64, "");
+        row("lines_buffered", self.total_lines_buffered as f64, "");
+        row("windows", self.active_window_count as f64, "");
+        row("memory_estimate", self.estimated_memory_mb(), "MB");
+        for sample in &self.render_times {
+            row("render_sample", sample.as_secs_f64() * 1000.0, "ms");
+        }
+        csv
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentiles_reset_and_csv() {
+        let mut stats = PerformanceStats::new();
+        for ms in 1..=100 {
+            stats.record_render_time(Duration::from_millis(ms));
+        }
+        assert_eq!(stats.render_time_percentile_ms(50.0), 50.0);
+        assert_eq!(stats.render_time_percentile_ms(95.0), 95.0);
+        assert_eq!(stats.render_time_percentile_ms(99.0), 99.0);
+        assert_eq!(stats.render_time_percentile_ms(0.0), 1.0);
+
+        stats.record_sync_times(vec![
+            ("text", Duration::from_micros(300)),
+            ("room", Duration::from_micros(40)),
+        ]);
+        stats.record_backlogs(12, 3);
+        stats.record_backlogs(0, 1);
+        assert_eq!(stats.sync_times_us(), vec![("text", 300.0), ("room", 40.0)]);
+        assert_eq!(stats.network_backlog(), (0, 12));
+        assert_eq!(stats.parse_backlog(), (1, 3));
+
+        let csv = stats.to_csv();
+        assert!(csv.starts_with("metric,value,unit\n"));
+        assert!(csv.contains("render_p95,95.000,ms\n"));
+        assert!(csv.contains("sync_text,300.000,us\n"));
+        assert!(csv.contains("network_backlog_peak,12.000,lines\n"));
+        assert_eq!(csv.matches("render_sample,").count(), 100);
+
+        stats.reset();
+        assert_eq!(stats.render_time_percentile_ms(99.0), 0.0);
+        assert!(stats.sync_times_us().is_empty());
+        assert_eq!(stats.network_backlog(), (0, 0));
+    }
 }
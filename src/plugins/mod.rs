@@ -0,0 +1,602 @@
+//! Plugin System
+//!
+//! Lets third parties extend the client without forking it. Plugins are
+//! shared libraries (`.so`, `.dylib`, `.dll`) dropped into
+//! `~/.two-face/plugins/` and loaded at startup.
+//! Features:
+//! - Line hooks: every line the server sends, with its stream
+//! - Command hooks: dot commands a plugin registers (`.mycommand args`)
+//! - Widget providers: named widgets a plugin fills with lines, shown in a
+//!   `plugin` window the plugin can open itself. Lines may color runs of
+//!   text with `{#rrggbb}` ... `{/}`; scripts outside a plugin can fill the
+//!   same widgets with `.widget` (e.g. through `two-face send`)
+//! - Config pages: settings a plugin declares, stored in
+//!   `plugins/settings.toml` (one table per library file name) and changed
+//!   with `.plugin set`
+//! - Compiled out entirely unless built with the `plugins` cargo feature
+//!
+//! # ABI
+//!
+//! The API is JSON over two C functions, so a plugin can be written in any
+//! language that builds a C-compatible shared library and never has to match
+//! the client's Rust version:
+//!
+//! ```text
+//! char *two_face_plugin_call(const char *request);   // JSON in, JSON out
+//! void  two_face_plugin_free(char *response);        // frees the response
+//! ```
+//!
+//! Each request is a `PluginEvent` tagged by `"event"`. The response to
+//! `init` carries the plugin's `PluginManifest`; every response may carry
+//! `actions` for the client to perform. `PLUGIN_API_VERSION` changes only
+//! when an existing event or action changes shape - new ones are added
+//! alongside, and plugins ignore events they don't know.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+/// Version of the event/action format, sent in `init`
+pub const PLUGIN_API_VERSION: u32 = 1;
+
+/// Settings values per plugin, as stored in plugins/settings.toml
+pub type PluginSettings = BTreeMap<String, BTreeMap<String, String>>;
+
+/// Events sent to plugins
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum PluginEvent<'a> {
+    /// First call after loading; the response must include a manifest
+    Init {
+        api_version: u32,
+        character: Option<&'a str>,
+        settings: &'a BTreeMap<String, String>,
+    },
+    /// A line from the server, for plugins with `line_hook` set
+    Line { stream: &'a str, text: &'a str },
+    /// One of the plugin's registered commands (`.name args...`)
+    Command { name: &'a str, args: &'a [String] },
+    /// A setting changed with `.plugin set`
+    Setting { key: &'a str, value: &'a str },
+    /// The client is quitting
+    Shutdown,
+}
+
+/// What a plugin says about itself in response to `init`
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct PluginManifest {
+    pub name: String,
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub description: String,
+    /// API version the plugin was written against
+    #[serde(default = "default_api_version")]
+    pub api_version: u32,
+    /// Send every server line as a `line` event
+    #[serde(default)]
+    pub line_hook: bool,
+    /// Dot commands the plugin handles, without the dot
+    #[serde(default)]
+    pub commands: Vec<String>,
+    /// Widget names the plugin provides lines for
+    #[serde(default)]
+    pub widgets: Vec<String>,
+    /// Settings shown by `.plugin settings <name>`
+    #[serde(default)]
+    pub settings: Vec<PluginSetting>,
+}
+
+fn default_api_version() -> u32 {
+    PLUGIN_API_VERSION
+}
+
+/// A setting declared by a plugin
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct PluginSetting {
+    pub key: String,
+    #[serde(default)]
+    pub label: String,
+    #[serde(default)]
+    pub default: String,
+}
+
+/// Something a plugin asks the client to do
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum PluginAction {
+    /// Send a command to the game (dot commands work too)
+    Send { command: String },
+    /// Show a message in main
+    Message { text: String },
+    /// Replace the lines of one of the plugin's widgets
+    Widget { name: String, lines: Vec<String> },
+    /// Add a `plugin` window showing a widget, unless the layout has one
+    OpenWidget {
+        name: String,
+        #[serde(default)]
+        title: Option<String>,
+    },
+}
+
+/// A plugin's answer to one event
+#[derive(Debug, Default, Deserialize)]
+pub struct PluginResponse {
+    #[serde(default)]
+    pub manifest: Option<PluginManifest>,
+    #[serde(default)]
+    pub actions: Vec<PluginAction>,
+}
+
+/// Something that answers plugin events - a loaded library, or a stand-in
+/// in tests
+pub trait PluginBackend {
+    /// Send one JSON request and return the JSON response
+    fn call(&mut self, request: &str) -> Result<String>;
+}
+
+/// A plugin that initialized successfully
+pub struct LoadedPlugin {
+    pub manifest: PluginManifest,
+    pub path: PathBuf,
+    backend: Box<dyn PluginBackend>,
+}
+
+impl LoadedPlugin {
+    /// Table name in settings.toml - the library's file name without extension
+    pub fn settings_key(&self) -> String {
+        self.path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+
+    fn send(&mut self, event: &PluginEvent) -> Result<PluginResponse> {
+        let request = serde_json::to_string(event)?;
+        let response = self.backend.call(&request)?;
+        if response.trim().is_empty() {
+            return Ok(PluginResponse::default());
+        }
+        serde_json::from_str(&response)
+            .with_context(|| format!("Bad response from plugin {}", self.manifest.name))
+    }
+}
+
+/// All loaded plugins, plus the lines their widgets last sent
+#[derive(Default)]
+pub struct PluginHost {
+    plugins: Vec<LoadedPlugin>,
+    settings: PluginSettings,
+    /// Widget name -> lines (from plugins or `.widget`)
+    widgets: HashMap<String, Vec<String>>,
+    /// Why plugins in the folder didn't load
+    pub errors: Vec<String>,
+}
+
+impl PluginHost {
+    /// Load every plugin library in `dir` (missing folder means no plugins)
+    pub fn load(
+        dir: &Path,
+        settings_path: &Path,
+        character: Option<&str>,
+    ) -> (Self, Vec<PluginAction>) {
+        let mut host = Self::default();
+        match load_settings(settings_path) {
+            Ok(settings) => host.settings = settings,
+            Err(e) => host.errors.push(format!("{:#}", e)),
+        }
+
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return (host, Vec::new());
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| {
+                matches!(
+                    p.extension().and_then(|e| e.to_str()),
+                    Some("so" | "dylib" | "dll")
+                )
+            })
+            .collect();
+        paths.sort();
+
+        let mut actions = Vec::new();
+        for path in paths {
+            match open_library(&path) {
+                Ok(backend) => match host.add(path.clone(), backend, character) {
+                    Ok(init_actions) => actions.extend(init_actions),
+                    Err(e) => host.errors.push(format!("{}: {:#}", path.display(), e)),
+                },
+                Err(e) => host.errors.push(format!("{}: {:#}", path.display(), e)),
+            }
+        }
+        for error in &host.errors {
+            tracing::warn!("Plugin not loaded: {}", error);
+        }
+        (host, actions)
+    }
+
+    /// Initialize a plugin and keep it if it answers with a manifest
+    pub fn add(
+        &mut self,
+        path: PathBuf,
+        backend: Box<dyn PluginBackend>,
+        character: Option<&str>,
+    ) -> Result<Vec<PluginAction>> {
+        let mut plugin = LoadedPlugin {
+            manifest: PluginManifest::default(),
+            path,
+            backend,
+        };
+        let empty = BTreeMap::new();
+        let response = plugin.send(&PluginEvent::Init {
+            api_version: PLUGIN_API_VERSION,
+            character,
+            settings: self.settings.get(&plugin.settings_key()).unwrap_or(&empty),
+        })?;
+
+        let manifest = response.manifest.context("Plugin didn't send a manifest")?;
+        if manifest.api_version > PLUGIN_API_VERSION {
+            anyhow::bail!(
+                "{} needs plugin API {} (this client has {})",
+                manifest.name,
+                manifest.api_version,
+                PLUGIN_API_VERSION
+            );
+        }
+        if self.find(&manifest.name).is_some() {
+            anyhow::bail!("A plugin named {} is already loaded", manifest.name);
+        }
+        tracing::info!(
+            "Loaded plugin {} {} from {}",
+            manifest.name,
+            manifest.version,
+            plugin.path.display()
+        );
+        plugin.manifest = manifest;
+        self.plugins.push(plugin);
+        Ok(response.actions)
+    }
+
+    pub fn plugins(&self) -> &[LoadedPlugin] {
+        &self.plugins
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.plugins.is_empty()
+    }
+
+    fn find(&self, name: &str) -> Option<usize> {
+        self.plugins
+            .iter()
+            .position(|p| p.manifest.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Pass a server line to plugins with a line hook
+    pub fn on_line(&mut self, stream: &str, text: &str) -> Vec<PluginAction> {
+        let event = PluginEvent::Line { stream, text };
+        let mut actions = Vec::new();
+        for plugin in self.plugins.iter_mut().filter(|p| p.manifest.line_hook) {
+            match plugin.send(&event) {
+                Ok(response) => actions.extend(response.actions),
+                Err(e) => {
+                    tracing::warn!("Plugin {} line hook failed: {:#}", plugin.manifest.name, e)
+                }
+            }
+        }
+        actions
+    }
+
+    /// Run a plugin command; None if no plugin registered `name`
+    pub fn on_command(&mut self, name: &str, args: &[String]) -> Option<Result<Vec<PluginAction>>> {
+        let plugin = self.plugins.iter_mut().find(|p| {
+            p.manifest
+                .commands
+                .iter()
+                .any(|c| c.eq_ignore_ascii_case(name))
+        })?;
+        Some(
+            plugin
+                .send(&PluginEvent::Command { name, args })
+                .map(|response| response.actions),
+        )
+    }
+
+    /// Current value of each declared setting for a plugin
+    pub fn settings_of(&self, name: &str) -> Option<Vec<(PluginSetting, String)>> {
+        let plugin = &self.plugins[self.find(name)?];
+        let stored = self.settings.get(&plugin.settings_key());
+        Some(
+            plugin
+                .manifest
+                .settings
+                .iter()
+                .map(|s| {
+                    let value = stored
+                        .and_then(|values| values.get(&s.key))
+                        .cloned()
+                        .unwrap_or_else(|| s.default.clone());
+                    (s.clone(), value)
+                })
+                .collect(),
+        )
+    }
+
+    /// Change a declared setting, tell the plugin, and save settings.toml
+    pub fn set_setting(
+        &mut self,
+        name: &str,
+        key: &str,
+        value: &str,
+        settings_path: &Path,
+    ) -> Result<Vec<PluginAction>> {
+        let index = self
+            .find(name)
+            .with_context(|| format!("No plugin named {}", name))?;
+        let plugin = &mut self.plugins[index];
+        if !plugin.manifest.settings.iter().any(|s| s.key == key) {
+            anyhow::bail!("{} has no setting '{}'", plugin.manifest.name, key);
+        }
+        self.settings
+            .entry(plugin.settings_key())
+            .or_default()
+            .insert(key.to_string(), value.to_string());
+        save_settings(settings_path, &self.settings)?;
+
+        Ok(plugin.send(&PluginEvent::Setting { key, value })?.actions)
+    }
+
+    /// Tell every plugin the client is quitting
+    pub fn shutdown(&mut self) {
+        for plugin in &mut self.plugins {
+            if let Err(e) = plugin.send(&PluginEvent::Shutdown) {
+                tracing::warn!("Plugin {} shutdown failed: {:#}", plugin.manifest.name, e);
+            }
+        }
+    }
+
+    /// Keep the lines from a `widget` action for `plugin` windows to show
+    pub fn set_widget_lines(&mut self, widget: String, lines: Vec<String>) {
+        self.widgets.insert(widget, lines);
+    }
+
+    /// Lines a plugin last sent for `widget`
+    pub fn widget_lines(&self, widget: &str) -> Option<&[String]> {
+        self.widgets.get(widget).map(|lines| lines.as_slice())
+    }
+
+    /// Lines of `widget` to edit in place, created empty if needed
+    pub fn widget_lines_mut(&mut self, widget: &str) -> &mut Vec<String> {
+        self.widgets.entry(widget.to_string()).or_default()
+    }
+}
+
+/// Read plugins/settings.toml (empty if it doesn't exist)
+pub fn load_settings(path: &Path) -> Result<PluginSettings> {
+    if !path.exists() {
+        return Ok(PluginSettings::new());
+    }
+    let contents = std::fs::read_to_string(path).context("Failed to read plugin settings")?;
+    toml::from_str(&contents).context("Failed to parse plugin settings")
+}
+
+fn save_settings(path: &Path, settings: &PluginSettings) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let contents =
+        toml::to_string_pretty(settings).context("Failed to serialize plugin settings")?;
+    std::fs::write(path, contents).context("Failed to write plugin settings")?;
+    Ok(())
+}
+
+#[cfg(feature = "plugins")]
+fn open_library(path: &Path) -> Result<Box<dyn PluginBackend>> {
+    Ok(Box::new(dylib::DylibPlugin::open(path)?))
+}
+
+/// Loader stub for when the plugins feature is disabled
+#[cfg(not(feature = "plugins"))]
+fn open_library(_path: &Path) -> Result<Box<dyn PluginBackend>> {
+    anyhow::bail!("Plugins not available (built without the `plugins` feature)")
+}
+
+#[cfg(feature = "plugins")]
+mod dylib {
+    //! Shared-library plugins through libloading. The library stays loaded
+    //! for the life of the client; symbols are looked up once at load.
+
+    use super::PluginBackend;
+    use anyhow::{Context, Result};
+    use libloading::{Library, Symbol};
+    use std::ffi::{c_char, CStr, CString};
+    use std::path::Path;
+
+    type CallFn = unsafe extern "C" fn(*const c_char) -> *mut c_char;
+    type FreeFn = unsafe extern "C" fn(*mut c_char);
+
+    pub(super) struct DylibPlugin {
+        call: CallFn,
+        free: FreeFn,
+        // Keeps `call` and `free` valid; dropped last
+        _library: Library,
+    }
+
+    impl DylibPlugin {
+        pub(super) fn open(path: &Path) -> Result<Self> {
+            // SAFETY: loading runs the library's initializers; plugins are
+            // code the player chose to install, like any other program.
+            unsafe {
+                let library = Library::new(path).context("Failed to load library")?;
+                let call: Symbol<CallFn> = library
+                    .get(b"two_face_plugin_call\0")
+                    .context("Missing two_face_plugin_call")?;
+                let free: Symbol<FreeFn> = library
+                    .get(b"two_face_plugin_free\0")
+                    .context("Missing two_face_plugin_free")?;
+                Ok(Self {
+                    call: *call,
+                    free: *free,
+                    _library: library,
+                })
+            }
+        }
+    }
+
+    impl PluginBackend for DylibPlugin {
+        fn call(&mut self, request: &str) -> Result<String> {
+            let request = CString::new(request).context("Request contains a NUL byte")?;
+            // SAFETY: the plugin contract is a NUL-terminated response (or
+            // null) that stays valid until handed back to `free`.
+            unsafe {
+                let response = (self.call)(request.as_ptr());
+                if response.is_null() {
+                    return Ok(String::new());
+                }
+                let text = CStr::from_ptr(response).to_string_lossy().into_owned();
+                (self.free)(response);
+                Ok(text)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Answers like a small plugin would
+    struct EchoPlugin;
+
+    impl PluginBackend for EchoPlugin {
+        fn call(&mut self, request: &str) -> Result<String> {
+            let request: serde_json::Value = serde_json::from_str(request)?;
+            let response = match request["event"].as_str() {
+                Some("init") => serde_json::json!({
+                    "manifest": {
+                        "name": "echo",
+                        "line_hook": true,
+                        "commands": ["echo", "tracker"],
+                        "widgets": ["echo_last"],
+                        "settings": [{"key": "prefix", "default": ">"}]
+                    },
+                    "actions": [{"action": "message", "text": "echo loaded"}]
+                }),
+                Some("line") if request["text"] == "ping" => serde_json::json!({
+                    "actions": [
+                        {"action": "send", "command": "say pong"},
+                        {"action": "widget", "name": "echo_last", "lines": ["ping"]}
+                    ]
+                }),
+                Some("command") if request["name"] == "tracker" => serde_json::json!({
+                    "actions": [{"action": "open_widget", "name": "echo_last", "title": "Echo"}]
+                }),
+                Some("command") => serde_json::json!({
+                    "actions": [{"action": "message", "text": request["args"][0]}]
+                }),
+                _ => serde_json::json!({}),
+            };
+            Ok(response.to_string())
+        }
+    }
+
+    /// A host with the echo plugin loaded
+    fn host() -> PluginHost {
+        let mut host = PluginHost::default();
+        host.add(
+            PathBuf::from("echo.so"),
+            Box::new(EchoPlugin),
+            Some("Tester"),
+        )
+        .unwrap();
+        host
+    }
+
+    #[test]
+    fn test_add_runs_init_actions() {
+        let mut host = PluginHost::default();
+        let actions = host
+            .add(
+                PathBuf::from("echo.so"),
+                Box::new(EchoPlugin),
+                Some("Tester"),
+            )
+            .unwrap();
+        assert_eq!(
+            actions,
+            vec![PluginAction::Message {
+                text: "echo loaded".to_string()
+            }]
+        );
+        assert_eq!(host.plugins()[0].manifest.api_version, PLUGIN_API_VERSION);
+    }
+
+    #[test]
+    fn test_same_name_twice_is_refused() {
+        let mut host = host();
+        assert!(host
+            .add(PathBuf::from("echo2.so"), Box::new(EchoPlugin), None)
+            .is_err());
+    }
+
+    #[test]
+    fn test_line_hook_actions() {
+        let mut host = host();
+        assert!(host.on_line("main", "nothing").is_empty());
+        let mut actions = host.on_line("main", "ping");
+        let Some(PluginAction::Widget { name, lines }) = actions.pop() else {
+            panic!("expected a widget update");
+        };
+        host.set_widget_lines(name, lines);
+        assert_eq!(
+            host.widget_lines("echo_last"),
+            Some(&["ping".to_string()][..])
+        );
+        assert_eq!(
+            actions,
+            vec![PluginAction::Send {
+                command: "say pong".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_commands_route_to_owner() {
+        let mut host = host();
+        let actions = host
+            .on_command("ECHO", &["hi".to_string()])
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            actions,
+            vec![PluginAction::Message {
+                text: "hi".to_string()
+            }]
+        );
+        assert!(host.on_command("other", &[]).is_none());
+        let actions = host.on_command("tracker", &[]).unwrap().unwrap();
+        assert_eq!(
+            actions,
+            vec![PluginAction::OpenWidget {
+                name: "echo_last".to_string(),
+                title: Some("Echo".to_string())
+            }]
+        );
+    }
+
+    #[test]
+    fn test_settings_saved_per_plugin() {
+        let mut host = host();
+        let path = std::env::temp_dir()
+            .join(format!("two-face-plugins-test-{}", std::process::id()))
+            .join("settings.toml");
+        assert!(host.set_setting("echo", "missing", "x", &path).is_err());
+        host.set_setting("echo", "prefix", "#", &path).unwrap();
+        assert_eq!(load_settings(&path).unwrap()["echo"]["prefix"], "#");
+        assert_eq!(host.plugins()[0].settings_key(), "echo");
+        assert_eq!(host.settings_of("echo").unwrap()[0].1, "#");
+        std::fs::remove_dir_all(path.parent().unwrap()).ok();
+    }
+}
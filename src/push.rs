@@ -0,0 +1,430 @@
+//! Push notifications for critical events (ntfy or Pushover)
+//!
+//! A few things are worth reaching for the phone over: the character dying,
+//! a whisper arriving while nobody has typed for a while, and the game
+//! connection dropping. Each event can be turned off, given its own priority
+//! and quiet hours, and is held back for `cooldown_secs` after it last went
+//! out so a chatty friend doesn't ring the phone for every line. Nothing is
+//! sent during do-not-disturb.
+//!
+//! Requests go out from a worker thread through `crate::http`.
+
+use crate::config::{PushConfig, PushEventConfig};
+use crate::http::Endpoint;
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const PUSHOVER_URL: &str = "https://api.pushover.net/1/messages.json";
+
+/// Events that can send a notification
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PushEvent {
+    Death,
+    Whisper,
+    Disconnect,
+    /// `.push test`
+    Test,
+}
+
+impl PushEvent {
+    pub const ALL: [PushEvent; 3] = [Self::Death, Self::Whisper, Self::Disconnect];
+
+    /// Table name under [push.events]
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Death => "death",
+            Self::Whisper => "whisper",
+            Self::Disconnect => "disconnect",
+            Self::Test => "test",
+        }
+    }
+
+    /// Priority when the event's config doesn't set one
+    fn default_priority(&self) -> &'static str {
+        match self {
+            Self::Death | Self::Disconnect => "high",
+            Self::Whisper | Self::Test => "default",
+        }
+    }
+}
+
+/// A notification on its way out
+#[derive(Debug, Clone, PartialEq)]
+struct Notification {
+    title: String,
+    message: String,
+    priority: String,
+}
+
+/// Where notifications go
+#[derive(Debug, Clone, PartialEq)]
+enum Service {
+    /// ntfy server and topic, with an optional access token
+    Ntfy {
+        endpoint: Endpoint,
+        topic: String,
+        token: String,
+    },
+    /// Pushover application token and user key
+    Pushover {
+        endpoint: Endpoint,
+        token: String,
+        user: String,
+    },
+}
+
+impl Service {
+    fn from_config(config: &PushConfig) -> Result<Self, String> {
+        match config.service.to_lowercase().as_str() {
+            "ntfy" => {
+                if config.topic.trim().is_empty() {
+                    return Err("set topic under [push] for ntfy".to_string());
+                }
+                let server = config.server.trim().trim_end_matches('/');
+                Ok(Self::Ntfy {
+                    endpoint: Endpoint::parse(&format!("{}/", server))?,
+                    topic: config.topic.trim().to_string(),
+                    token: config.token.clone(),
+                })
+            }
+            "pushover" => {
+                if config.token.is_empty() || config.user.is_empty() {
+                    return Err("set token and user under [push] for Pushover".to_string());
+                }
+                Ok(Self::Pushover {
+                    endpoint: Endpoint::parse(PUSHOVER_URL)?,
+                    token: config.token.clone(),
+                    user: config.user.clone(),
+                })
+            }
+            other => Err(format!("unknown service {:?} (ntfy or pushover)", other)),
+        }
+    }
+
+    /// Endpoint, JSON body and extra headers for one notification
+    fn request(
+        &self,
+        notification: &Notification,
+    ) -> (&Endpoint, String, Vec<(&'static str, String)>) {
+        match self {
+            Self::Ntfy {
+                endpoint,
+                topic,
+                token,
+            } => {
+                // ntfy priorities: min, low, default, high, urgent (or 1-5)
+                let priority = match notification.priority.as_str() {
+                    "min" | "lowest" => 1,
+                    "low" => 2,
+                    "high" => 4,
+                    "urgent" | "emergency" => 5,
+                    _ => 3,
+                };
+                let body = serde_json::json!({
+                    "topic": topic,
+                    "title": notification.title,
+                    "message": notification.message,
+                    "priority": priority,
+                });
+                let headers = if token.is_empty() {
+                    Vec::new()
+                } else {
+                    vec![("Authorization", format!("Bearer {}", token))]
+                };
+                (endpoint, body.to_string(), headers)
+            }
+            Self::Pushover {
+                endpoint,
+                token,
+                user,
+            } => {
+                // Pushover priorities run -2..2; 2 needs retry/expire, so "urgent" stops at 1
+                let priority = match notification.priority.as_str() {
+                    "min" | "lowest" => -2,
+                    "low" => -1,
+                    "high" | "urgent" | "emergency" => 1,
+                    _ => 0,
+                };
+                let body = serde_json::json!({
+                    "token": token,
+                    "user": user,
+                    "title": notification.title,
+                    "message": notification.message,
+                    "priority": priority,
+                });
+                (endpoint, body.to_string(), Vec::new())
+            }
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Ntfy { .. } => "ntfy",
+            Self::Pushover { .. } => "Pushover",
+        }
+    }
+}
+
+/// Push notifier owned by AppCore
+///
+/// When disabled in config this is a no-op.
+pub struct PushNotifier {
+    tx: Option<Sender<Notification>>,
+    events: HashMap<String, PushEventConfig>,
+    quiet_hours: Option<String>,
+    cooldown: Duration,
+    character: String,
+    last_sent: HashMap<PushEvent, Instant>,
+    last_error: Arc<Mutex<Option<String>>>,
+    /// Service in use, or why notifications are off
+    status: Result<String, String>,
+}
+
+impl PushNotifier {
+    pub fn new(config: &PushConfig, character: Option<&str>) -> Self {
+        let mut notifier = Self {
+            tx: None,
+            events: config.events.clone(),
+            quiet_hours: config.quiet_hours.clone(),
+            cooldown: Duration::from_secs(config.cooldown_secs),
+            character: character.unwrap_or("two-face").to_string(),
+            last_sent: HashMap::new(),
+            last_error: Arc::default(),
+            status: Err("disabled (set enabled = true under [push])".to_string()),
+        };
+        if config.enabled {
+            notifier.status = notifier.start(config);
+            match &notifier.status {
+                Ok(service) => tracing::info!("Push notifications via {}", service),
+                Err(e) => tracing::warn!("Push notifications not started: {}", e),
+            }
+        }
+        notifier
+    }
+
+    fn start(&mut self, config: &PushConfig) -> Result<String, String> {
+        let service = Service::from_config(config)?;
+        let name = service.name().to_string();
+        let last_error = self.last_error.clone();
+        let (tx, rx) = mpsc::channel::<Notification>();
+        std::thread::Builder::new()
+            .name("push".to_string())
+            .spawn(move || {
+                for notification in rx {
+                    let (endpoint, body, headers) = service.request(&notification);
+                    let headers: Vec<(&str, &str)> =
+                        headers.iter().map(|(k, v)| (*k, v.as_str())).collect();
+                    let error = match crate::http::post_json(endpoint, &body, &headers) {
+                        Ok(status) if (200..300).contains(&status) => None,
+                        Ok(status) => Some(format!("HTTP {}", status)),
+                        Err(e) => Some(e),
+                    };
+                    if let Some(error) = &error {
+                        tracing::warn!("Push notification failed: {}", error);
+                    }
+                    *last_error.lock().unwrap() = error;
+                }
+            })
+            .map_err(|e| e.to_string())?;
+        self.tx = Some(tx);
+        Ok(name)
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.tx.is_some()
+    }
+
+    /// Would `event` be sent at `now` (local time `time`)? Records the send if so
+    fn should_send(&mut self, event: PushEvent, now: Instant, time: chrono::NaiveTime) -> bool {
+        let config = self.events.get(event.name());
+        if event != PushEvent::Test {
+            if config.is_some_and(|c| !c.enabled) {
+                return false;
+            }
+            let quiet = config
+                .and_then(|c| c.quiet_hours.as_deref())
+                .or(self.quiet_hours.as_deref());
+            if quiet.is_some_and(|q| crate::core::trigger_gate::in_quiet_hours(q, time)) {
+                return false;
+            }
+            if self
+                .last_sent
+                .get(&event)
+                .is_some_and(|last| now.duration_since(*last) < self.cooldown)
+            {
+                return false;
+            }
+        }
+        self.last_sent.insert(event, now);
+        true
+    }
+
+    /// Send `message` for `event` unless it's off, in quiet hours or cooling down
+    pub fn notify(&mut self, event: PushEvent, message: &str) {
+        if self.tx.is_none()
+            || !self.should_send(event, Instant::now(), chrono::Local::now().time())
+        {
+            return;
+        }
+        let priority = self
+            .events
+            .get(event.name())
+            .and_then(|c| c.priority.clone())
+            .unwrap_or_else(|| event.default_priority().to_string());
+        let notification = Notification {
+            title: format!("{}: {}", self.character, event.name()),
+            message: message.to_string(),
+            priority,
+        };
+        if let Some(tx) = &self.tx {
+            let _ = tx.send(notification);
+        }
+    }
+
+    /// One-line description for `.push`
+    pub fn status_line(&self) -> String {
+        match &self.status {
+            Ok(service) => {
+                let events: Vec<&str> = PushEvent::ALL
+                    .iter()
+                    .filter(|e| self.events.get(e.name()).is_none_or(|c| c.enabled))
+                    .map(|e| e.name())
+                    .collect();
+                let mut line = format!(
+                    "Push notifications: via {} for {}",
+                    service,
+                    events.join(", ")
+                );
+                if let Some(error) = self.last_error.lock().unwrap().as_ref() {
+                    line.push_str(&format!("; last error: {}", error));
+                }
+                line
+            }
+            Err(reason) => format!("Push notifications: {}", reason),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveTime;
+
+    /// ntfy with global quiet hours, whispers off and urgent disconnects
+    /// that ignore the quiet hours
+    fn config() -> PushConfig {
+        let mut config = PushConfig {
+            enabled: true,
+            topic: "my-hunts".to_string(),
+            quiet_hours: Some("23:00-07:00".to_string()),
+            ..PushConfig::default()
+        };
+        config.events.insert(
+            "whisper".to_string(),
+            PushEventConfig {
+                enabled: false,
+                priority: None,
+                quiet_hours: None,
+            },
+        );
+        config.events.insert(
+            "disconnect".to_string(),
+            PushEventConfig {
+                enabled: true,
+                priority: Some("urgent".to_string()),
+                quiet_hours: Some(String::new()),
+            },
+        );
+        config
+    }
+
+    fn notification() -> Notification {
+        Notification {
+            title: "Ann: death".to_string(),
+            message: "You died".to_string(),
+            priority: "urgent".to_string(),
+        }
+    }
+
+    fn noon() -> NaiveTime {
+        NaiveTime::from_hms_opt(12, 0, 0).unwrap()
+    }
+
+    fn night() -> NaiveTime {
+        NaiveTime::from_hms_opt(2, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_disabled_events_never_send() {
+        let mut notifier = PushNotifier::new(&config(), Some("Ann"));
+        assert!(!notifier.should_send(PushEvent::Whisper, Instant::now(), noon()));
+    }
+
+    #[test]
+    fn test_quiet_hours() {
+        let mut notifier = PushNotifier::new(&config(), Some("Ann"));
+        let now = Instant::now();
+        assert!(!notifier.should_send(PushEvent::Death, now, night()));
+        // The event's own (empty) quiet hours replace the global ones
+        assert!(notifier.should_send(PushEvent::Disconnect, now, night()));
+    }
+
+    #[test]
+    fn test_same_event_rate_limited() {
+        let mut notifier = PushNotifier::new(&config(), Some("Ann"));
+        let now = Instant::now();
+        assert!(notifier.should_send(PushEvent::Death, now, noon()));
+        assert!(!notifier.should_send(PushEvent::Death, now + Duration::from_secs(10), noon()));
+        assert!(notifier.should_send(PushEvent::Death, now + Duration::from_secs(61), noon()));
+    }
+
+    #[test]
+    fn test_status_line_lists_events() {
+        let notifier = PushNotifier::new(&config(), Some("Ann"));
+        assert!(notifier.is_running());
+        assert!(notifier
+            .status_line()
+            .contains("via ntfy for death, disconnect"));
+    }
+
+    #[test]
+    fn test_disabled_config_not_running() {
+        assert!(!PushNotifier::new(&PushConfig::default(), None).is_running());
+    }
+
+    #[test]
+    fn test_ntfy_request() {
+        let service = Service::from_config(&config()).unwrap();
+        let (endpoint, body, headers) = service.request(&notification());
+        assert_eq!(
+            (endpoint.host.as_str(), endpoint.path.as_str()),
+            ("ntfy.sh", "/")
+        );
+        let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(
+            (body["topic"].as_str(), body["priority"].as_i64()),
+            (Some("my-hunts"), Some(5))
+        );
+        assert!(headers.is_empty());
+    }
+
+    #[test]
+    fn test_pushover_needs_token_and_user() {
+        let mut config = PushConfig {
+            service: "pushover".to_string(),
+            ..config()
+        };
+        assert!(Service::from_config(&config).is_err());
+        config.token = "app".to_string();
+        config.user = "me".to_string();
+        let service = Service::from_config(&config).unwrap();
+        let body: serde_json::Value =
+            serde_json::from_str(&service.request(&notification()).1).unwrap();
+        assert_eq!(
+            (body["user"].as_str(), body["priority"].as_i64()),
+            (Some("me"), Some(1))
+        );
+    }
+}
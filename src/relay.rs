@@ -0,0 +1,462 @@
+//! Webhook relay - forwards selected lines to Discord or another HTTP endpoint
+//!
+//! Lines from configured streams (death, thoughts...), lines matching relay
+//! patterns, and lines matching named highlights are posted to a webhook so
+//! a guild channel or a phone can follow what matters without the client in
+//! view. Features:
+//! - `format = "discord"` posts `{"content": ...}` with mentions disabled;
+//!   `format = "json"` posts `{"character": ..., "lines": [...]}`
+//! - Lines arriving close together are batched into one post
+//! - At most `max_per_minute` posts; lines over the limit are dropped and
+//!   counted, and the next post says how many were lost
+//! - Redaction rules (regex -> replacement) run before anything leaves
+//! - Posts go out from a worker thread; the game loop never waits on the
+//!   network
+
+use crate::config::{HighlightPattern, RelayConfig};
+use crate::http::Endpoint;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Discord rejects message content longer than this
+const DISCORD_MAX_CONTENT: usize = 2000;
+
+/// Lines queued for the worker beyond this are dropped
+const MAX_QUEUED: usize = 500;
+
+/// One line on its way out
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct RelayLine {
+    stream: String,
+    text: String,
+}
+
+/// Sliding one-minute window of posts
+#[derive(Debug)]
+struct RateLimiter {
+    max_per_minute: usize,
+    sent: VecDeque<Instant>,
+}
+
+impl RateLimiter {
+    fn new(max_per_minute: u32) -> Self {
+        Self {
+            max_per_minute: max_per_minute as usize,
+            sent: VecDeque::new(),
+        }
+    }
+
+    /// Take a slot for a post at `now`; false if the last minute is full
+    fn allow(&mut self, now: Instant) -> bool {
+        while self
+            .sent
+            .front()
+            .is_some_and(|t| now.duration_since(*t) >= Duration::from_secs(60))
+        {
+            self.sent.pop_front();
+        }
+        if self.max_per_minute > 0 && self.sent.len() >= self.max_per_minute {
+            return false;
+        }
+        self.sent.push_back(now);
+        true
+    }
+}
+
+/// Which lines go out, and what is hidden in them
+struct Filter {
+    streams: Vec<String>,
+    patterns: Vec<Regex>,
+    redact: Vec<(Regex, String)>,
+}
+
+impl Filter {
+    fn new(
+        config: &RelayConfig,
+        highlights: &HashMap<String, HighlightPattern>,
+    ) -> Result<Self, String> {
+        let compile = |pattern: &str| {
+            Regex::new(pattern).map_err(|e| format!("bad pattern {:?}: {}", pattern, e))
+        };
+        let mut patterns = Vec::new();
+        for pattern in &config.patterns {
+            patterns.push(compile(pattern)?);
+        }
+        for name in &config.highlights {
+            let highlight = highlights
+                .get(name)
+                .ok_or_else(|| format!("no highlight named {:?}", name))?;
+            patterns.push(compile(&highlight.pattern)?);
+        }
+        let mut redact = Vec::new();
+        for rule in &config.redact {
+            redact.push((compile(&rule.pattern)?, rule.replacement.clone()));
+        }
+        Ok(Self {
+            streams: config.streams.iter().map(|s| s.to_lowercase()).collect(),
+            patterns,
+            redact,
+        })
+    }
+
+    /// The line as it should be relayed, or None if it stays local
+    fn apply(&self, stream: &str, text: &str) -> Option<String> {
+        let text = text.trim_end();
+        if text.trim().is_empty() {
+            return None;
+        }
+        let wanted = self.streams.iter().any(|s| s.eq_ignore_ascii_case(stream))
+            || self.patterns.iter().any(|p| p.is_match(text));
+        if !wanted {
+            return None;
+        }
+        let mut text = text.to_string();
+        for (pattern, replacement) in &self.redact {
+            text = pattern
+                .replace_all(&text, replacement.as_str())
+                .into_owned();
+        }
+        Some(text)
+    }
+}
+
+/// Request body for one batch
+fn build_payload(format: &str, character: &str, lines: &[RelayLine], dropped: usize) -> String {
+    if format.eq_ignore_ascii_case("json") {
+        return serde_json::json!({
+            "character": character,
+            "lines": lines,
+            "dropped": dropped,
+        })
+        .to_string();
+    }
+
+    let mut content = String::new();
+    for line in lines {
+        let entry = format!("`[{}]` {}\n", line.stream, line.text);
+        if content.chars().count() + entry.chars().count() > DISCORD_MAX_CONTENT - 40 {
+            break;
+        }
+        content.push_str(&entry);
+    }
+    if dropped > 0 {
+        content.push_str(&format!(
+            "_({} line(s) dropped by the rate limit)_",
+            dropped
+        ));
+    }
+    serde_json::json!({
+        "username": character,
+        "content": content.trim_end(),
+        "allowed_mentions": { "parse": [] },
+    })
+    .to_string()
+}
+
+/// Counters shared with the worker
+#[derive(Default)]
+struct Shared {
+    sent: AtomicUsize,
+    dropped: AtomicUsize,
+    last_error: Mutex<Option<String>>,
+}
+
+/// Webhook relay owned by AppCore
+///
+/// When disabled in config this is a no-op and lines are never matched.
+pub struct Relay {
+    tx: Option<Sender<RelayLine>>,
+    filter: Option<Filter>,
+    queued: Arc<AtomicUsize>,
+    shared: Arc<Shared>,
+    /// Host being posted to, or why the relay is off
+    status: Result<String, String>,
+}
+
+impl Relay {
+    pub fn new(
+        config: &RelayConfig,
+        highlights: &HashMap<String, HighlightPattern>,
+        character: Option<&str>,
+    ) -> Self {
+        let mut relay = Self {
+            tx: None,
+            filter: None,
+            queued: Arc::default(),
+            shared: Arc::default(),
+            status: Err("disabled (set enabled = true under [relay])".to_string()),
+        };
+        if config.enabled {
+            relay.status = relay.start(config, highlights, character.unwrap_or("two-face"));
+            match &relay.status {
+                Ok(host) => tracing::info!("Relay posting to {}", host),
+                Err(e) => tracing::warn!("Relay not started: {}", e),
+            }
+        }
+        relay
+    }
+
+    fn start(
+        &mut self,
+        config: &RelayConfig,
+        highlights: &HashMap<String, HighlightPattern>,
+        character: &str,
+    ) -> Result<String, String> {
+        let endpoint = Endpoint::parse(config.url.trim())?;
+        let filter = Filter::new(config, highlights)?;
+        let (tx, rx) = mpsc::channel();
+        let worker = Worker {
+            endpoint: endpoint.clone(),
+            format: config.format.clone(),
+            character: character.to_string(),
+            batch: Duration::from_millis(config.batch_ms),
+            limiter: RateLimiter::new(config.max_per_minute),
+            queued: self.queued.clone(),
+            shared: self.shared.clone(),
+        };
+        std::thread::Builder::new()
+            .name("relay".to_string())
+            .spawn(move || worker.run(rx))
+            .map_err(|e| e.to_string())?;
+        self.tx = Some(tx);
+        self.filter = Some(filter);
+        // Only the host: webhook URLs carry their secret in the path
+        Ok(endpoint.host)
+    }
+
+    /// Queue a server line if it's one the relay forwards
+    pub fn record_line(&self, stream: &str, text: &str) {
+        let (Some(tx), Some(filter)) = (&self.tx, &self.filter) else {
+            return;
+        };
+        let Some(text) = filter.apply(stream, text) else {
+            return;
+        };
+        if self.queued.load(Ordering::Relaxed) >= MAX_QUEUED {
+            self.shared.dropped.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        self.queued.fetch_add(1, Ordering::Relaxed);
+        let _ = tx.send(RelayLine {
+            stream: stream.to_string(),
+            text,
+        });
+    }
+
+    /// One-line description for `.relay`
+    pub fn status_line(&self) -> String {
+        match &self.status {
+            Ok(host) => {
+                let mut line = format!(
+                    "Relay: posting to {} ({} sent, {} dropped)",
+                    host,
+                    self.shared.sent.load(Ordering::Relaxed),
+                    self.shared.dropped.load(Ordering::Relaxed)
+                );
+                if let Some(error) = self.shared.last_error.lock().unwrap().as_ref() {
+                    line.push_str(&format!("; last error: {}", error));
+                }
+                line
+            }
+            Err(reason) => format!("Relay: {}", reason),
+        }
+    }
+}
+
+/// Background thread that batches and posts lines
+struct Worker {
+    endpoint: Endpoint,
+    format: String,
+    character: String,
+    batch: Duration,
+    limiter: RateLimiter,
+    queued: Arc<AtomicUsize>,
+    shared: Arc<Shared>,
+}
+
+impl Worker {
+    fn run(mut self, rx: Receiver<RelayLine>) {
+        // Lines lost to the rate limit since the last post that went out
+        let mut unreported = 0;
+        while let Ok(first) = rx.recv() {
+            let mut lines = vec![first];
+            let deadline = Instant::now() + self.batch;
+            loop {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                match rx.recv_timeout(remaining) {
+                    Ok(line) => lines.push(line),
+                    Err(RecvTimeoutError::Timeout) => break,
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+            }
+            self.queued.fetch_sub(lines.len(), Ordering::Relaxed);
+
+            if !self.limiter.allow(Instant::now()) {
+                unreported += lines.len();
+                self.shared
+                    .dropped
+                    .fetch_add(lines.len(), Ordering::Relaxed);
+                continue;
+            }
+
+            let body = build_payload(&self.format, &self.character, &lines, unreported);
+            match crate::http::post_json(&self.endpoint, &body, &[]) {
+                Ok(status) if (200..300).contains(&status) => {
+                    unreported = 0;
+                    self.shared.sent.fetch_add(lines.len(), Ordering::Relaxed);
+                    *self.shared.last_error.lock().unwrap() = None;
+                }
+                Ok(status) => self.fail(format!("HTTP {}", status), lines.len()),
+                Err(e) => self.fail(e, lines.len()),
+            }
+        }
+    }
+
+    fn fail(&self, error: String, lines: usize) {
+        tracing::warn!("Relay post to {} failed: {}", self.endpoint.host, error);
+        self.shared.dropped.fetch_add(lines, Ordering::Relaxed);
+        *self.shared.last_error.lock().unwrap() = Some(error);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RedactRule;
+    use std::io::{BufRead, Read, Write};
+
+    fn highlights() -> HashMap<String, HighlightPattern> {
+        let mut highlights = HashMap::new();
+        highlights.insert(
+            "whispers".to_string(),
+            toml::from_str::<HighlightPattern>(r#"pattern = " whispers""#).unwrap(),
+        );
+        highlights
+    }
+
+    /// Death stream plus whispers, with digit pairs redacted
+    fn config() -> RelayConfig {
+        RelayConfig {
+            enabled: true,
+            streams: vec!["Death".to_string()],
+            highlights: vec!["whispers".to_string()],
+            redact: vec![RedactRule {
+                pattern: r"\d{4}-\d{4}".to_string(),
+                replacement: "####".to_string(),
+            }],
+            ..RelayConfig::default()
+        }
+    }
+
+    fn lines() -> Vec<RelayLine> {
+        vec![RelayLine {
+            stream: "death".to_string(),
+            text: "@everyone Bob died".to_string(),
+        }]
+    }
+
+    #[test]
+    fn test_rate_limiter() {
+        let mut limiter = RateLimiter::new(2);
+        let start = Instant::now();
+        assert!(limiter.allow(start));
+        assert!(limiter.allow(start));
+        assert!(!limiter.allow(start + Duration::from_secs(30)));
+        assert!(limiter.allow(start + Duration::from_secs(61)));
+    }
+
+    #[test]
+    fn test_filter_streams_highlights_and_redaction() {
+        let filter = Filter::new(&config(), &highlights()).unwrap();
+        assert_eq!(
+            filter.apply("death", " * Bob was slain."),
+            Some(" * Bob was slain.".to_string())
+        );
+        assert_eq!(
+            filter.apply("main", "Ann whispers, \"code 1234-5678\""),
+            Some("Ann whispers, \"code ####\"".to_string())
+        );
+        assert_eq!(filter.apply("main", "You swing a dagger."), None);
+    }
+
+    #[test]
+    fn test_filter_rejects_unknown_highlight() {
+        let missing = RelayConfig {
+            highlights: vec!["nope".to_string()],
+            ..config()
+        };
+        assert!(Filter::new(&missing, &highlights()).is_err());
+    }
+
+    #[test]
+    fn test_discord_payload_blocks_mentions() {
+        let discord: serde_json::Value =
+            serde_json::from_str(&build_payload("discord", "Ann", &lines(), 3)).unwrap();
+        assert_eq!(discord["username"], "Ann");
+        assert!(discord["content"]
+            .as_str()
+            .unwrap()
+            .starts_with("`[death]` @everyone Bob died"));
+        assert!(discord["content"]
+            .as_str()
+            .unwrap()
+            .contains("3 line(s) dropped"));
+        assert_eq!(discord["allowed_mentions"]["parse"], serde_json::json!([]));
+    }
+
+    #[test]
+    fn test_json_payload() {
+        let json: serde_json::Value =
+            serde_json::from_str(&build_payload("json", "Ann", &lines(), 0)).unwrap();
+        assert_eq!(json["lines"][0]["text"], "@everyone Bob died");
+    }
+
+    #[test]
+    fn test_relay_posts_to_endpoint() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = std::io::BufReader::new(stream.try_clone().unwrap());
+            let mut length = 0;
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).unwrap();
+                if let Some(value) = header.to_lowercase().strip_prefix("content-length:") {
+                    length = value.trim().parse().unwrap();
+                }
+                if header.trim().is_empty() {
+                    break;
+                }
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            (&stream)
+                .write_all(b"HTTP/1.1 204 No Content\r\n\r\n")
+                .unwrap();
+            String::from_utf8(body).unwrap()
+        });
+        let config = RelayConfig {
+            url: format!("http://127.0.0.1:{}/hook", port),
+            format: "json".to_string(),
+            batch_ms: 50,
+            ..config()
+        };
+        let relay = Relay::new(&config, &highlights(), Some("Ann"));
+        relay.record_line("death", " * Bob was slain.");
+        relay.record_line("main", "You swing a dagger.");
+        let body: serde_json::Value = serde_json::from_str(&server.join().unwrap()).unwrap();
+        assert_eq!(body["character"], "Ann");
+        assert_eq!(body["lines"].as_array().unwrap().len(), 1);
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while relay.shared.sent.load(Ordering::Relaxed) == 0 && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert!(relay.status_line().contains("1 sent"));
+    }
+}
@@ -2,9 +2,15 @@
 //!
 //! The `selection` module tracks the active selection range in window space and
 //! offers utilities for translating between screen coordinates and window
-//! rectangles.
+//! rectangles. A normal drag selects a run of text; an alt-drag selects a
+//! rectangle of display columns (block mode) for copying tables. A double
+//! click selects the word under the pointer and a triple click the whole line.
 
 use ratatui::layout::Rect;
+use std::time::{Duration, Instant};
+
+/// Longest gap between clicks that still counts as a double/triple click
+const MULTI_CLICK_INTERVAL: Duration = Duration::from_millis(400);
 
 /// Represents a position in the text (window, line, column)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -23,6 +29,8 @@ pub struct SelectionState {
     pub end: TextPosition,
     /// Whether the selection is currently active
     pub active: bool,
+    /// Block mode: the same columns on every line between start and end
+    pub block: bool,
 }
 
 impl SelectionState {
@@ -37,6 +45,15 @@ impl SelectionState {
             start: pos,
             end: pos,
             active: true,
+            block: false,
+        }
+    }
+
+    /// Create a block (rectangular) selection anchored at window/line/column.
+    pub fn new_block(window_index: usize, line: usize, col: usize) -> Self {
+        Self {
+            block: true,
+            ..Self::new(window_index, line, col)
         }
     }
 
@@ -58,6 +75,12 @@ impl SelectionState {
             return false;
         }
 
+        if self.block {
+            let (left, right) = self.block_columns();
+            let (top, bottom) = self.block_lines();
+            return line >= top && line <= bottom && col >= left && col < right;
+        }
+
         let (start, end) = self.normalized_range();
 
         // Same line selection
@@ -90,15 +113,118 @@ impl SelectionState {
         }
     }
 
+    /// First and last line of a block selection
+    pub fn block_lines(&self) -> (usize, usize) {
+        (
+            self.start.line.min(self.end.line),
+            self.start.line.max(self.end.line),
+        )
+    }
+
+    /// Column range (left inclusive, right exclusive) of a block selection
+    pub fn block_columns(&self) -> (usize, usize) {
+        (
+            self.start.col.min(self.end.col),
+            self.start.col.max(self.end.col),
+        )
+    }
+
     /// Clear the selection
     pub fn clear(&mut self) {
         self.active = false;
     }
 
-    /// Check if the selection is empty (start == end)
+    /// Check if the selection is empty (start == end, or a block with no width)
     pub fn is_empty(&self) -> bool {
-        self.start == self.end
+        self.start == self.end || (self.block && self.start.col == self.end.col)
+    }
+}
+
+/// Clipboard formats a selection can be copied in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopyFormat {
+    Plain,
+    /// Text with ANSI color escapes
+    Ansi,
+    /// `<pre>` block with colored spans
+    Html,
+}
+
+/// A finished selection rendered in every copy format
+///
+/// Built when the mouse is released, so copying it again later as ANSI or
+/// HTML doesn't depend on the window's scrollback still holding those lines.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelectionCopy {
+    pub plain: String,
+    pub ansi: String,
+    pub html: String,
+}
+
+/// Cut the columns `left..right` out of each display line, one row per line
+///
+/// Trailing spaces are dropped so short rows don't carry padding.
+pub fn extract_block<'a>(
+    lines: impl IntoIterator<Item = &'a str>,
+    left: usize,
+    right: usize,
+) -> String {
+    lines
+        .into_iter()
+        .map(|line| {
+            let cut: String = line
+                .chars()
+                .skip(left)
+                .take(right.saturating_sub(left))
+                .collect();
+            cut.trim_end().to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Counts quick clicks on the same cell (1 = single, 2 = double, 3 = triple)
+#[derive(Debug, Clone, Default)]
+pub struct ClickCounter {
+    last: Option<(Instant, u16, u16)>,
+    count: u8,
+}
+
+impl ClickCounter {
+    /// Record a click and return how many in a row it makes (a fourth starts over)
+    pub fn click(&mut self, x: u16, y: u16, now: Instant) -> u8 {
+        let repeat = self.last.is_some_and(|(at, last_x, last_y)| {
+            last_x == x && last_y == y && now.duration_since(at) <= MULTI_CLICK_INTERVAL
+        });
+        self.count = if repeat && self.count < 3 {
+            self.count + 1
+        } else {
+            1
+        };
+        self.last = Some((now, x, y));
+        self.count
+    }
+}
+
+/// Character range (end exclusive) of the word at `col`, if there is one
+///
+/// Words are letters, digits and `_`, `-` and `'`, so "Bob's" and
+/// "blue-green" come out whole.
+pub fn word_bounds(text: &str, col: usize) -> Option<(usize, usize)> {
+    let is_word = |c: &char| c.is_alphanumeric() || matches!(c, '_' | '-' | '\'');
+    let chars: Vec<char> = text.chars().collect();
+    if !chars.get(col).is_some_and(is_word) {
+        return None;
     }
+    let start = chars[..col]
+        .iter()
+        .rposition(|c| !is_word(c))
+        .map_or(0, |i| i + 1);
+    let end = chars[col..]
+        .iter()
+        .position(|c| !is_word(c))
+        .map_or(chars.len(), |i| col + i);
+    Some((start, end))
 }
 
 /// Convert screen coordinates (x, y) to window-relative coordinates
@@ -123,3 +249,60 @@ pub fn screen_to_window_coords(
 
     Some((rel_x, rel_y))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_selection() {
+        // Dragged from bottom-right up to top-left
+        let mut selection = SelectionState::new_block(2, 5, 14);
+        selection.update_end(2, 3, 4);
+        assert_eq!(selection.block_lines(), (3, 5));
+        assert_eq!(selection.block_columns(), (4, 14));
+        assert!(selection.contains(2, 4, 4));
+        assert!(selection.contains(2, 3, 13));
+        assert!(!selection.contains(2, 4, 14));
+        assert!(!selection.contains(2, 4, 2)); // A stream selection would include this
+        assert!(!selection.contains(1, 4, 5));
+        assert!(!selection.is_empty());
+
+        let rows = [
+            "  Edged Weapons........  42 12",
+            "  Armor Use............  30",
+            "  Shield",
+        ];
+        assert_eq!(
+            extract_block(rows, 2, 28),
+            "Edged Weapons........  42\nArmor Use............  30\nShield"
+        );
+
+        let mut zero_width = SelectionState::new_block(0, 1, 3);
+        zero_width.update_end(0, 4, 3);
+        assert!(zero_width.is_empty());
+    }
+
+    #[test]
+    fn test_multi_click() {
+        let mut clicks = ClickCounter::default();
+        let start = Instant::now();
+        let ms = Duration::from_millis;
+        assert_eq!(clicks.click(5, 2, start), 1);
+        assert_eq!(clicks.click(5, 2, start + ms(200)), 2);
+        assert_eq!(clicks.click(5, 2, start + ms(400)), 3);
+        assert_eq!(clicks.click(5, 2, start + ms(500)), 1);
+        // Too slow, or somewhere else
+        assert_eq!(clicks.click(5, 2, start + ms(1000)), 1);
+        assert_eq!(clicks.click(6, 2, start + ms(1100)), 1);
+
+        let line = "Bob's blue-green cloak (worn), 12 silvers.";
+        assert_eq!(word_bounds(line, 0), Some((0, 5)));
+        assert_eq!(word_bounds(line, 8), Some((6, 16)));
+        assert_eq!(word_bounds(line, 24), Some((24, 28)));
+        assert_eq!(word_bounds(line, 31), Some((31, 33)));
+        assert_eq!(word_bounds(line, 5), None); // the space
+        assert_eq!(word_bounds(line, 23), None); // "("
+        assert_eq!(word_bounds(line, 99), None);
+    }
+}
@@ -5,9 +5,18 @@
 //! and abstract over whether the `sound` cargo feature is enabled.  It also
 //! provides helpers for seeding a default `~/.two-face/sounds` directory so the
 //! application can ship bundled effects.
+//!
+//! Every sound belongs to a category (alerts, ambience, UI) whose level scales
+//! the sound's own volume, so e.g. ambient loops can be turned down without
+//! touching alerts.
+//!
+//! A sound pack is a subdirectory of the sounds directory
+//! (`~/.two-face/sounds/<pack>/`). With a pack active, sound names are looked
+//! up there first and fall back to the sounds directory itself, so a pack only
+//! needs to contain the effects it replaces.
 
 use anyhow::Result;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::time::Instant;
 use tracing::{debug, warn};
@@ -19,6 +28,34 @@ use std::fs::File;
 #[cfg(feature = "sound")]
 use std::io::BufReader;
 
+/// Volume category a sound plays in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SoundCategory {
+    /// Things that need attention: deaths, whispers, highlight alarms
+    Alerts,
+    /// Background atmosphere
+    Ambience,
+    /// Client feedback: roundtime over, spell running out
+    Ui,
+}
+
+impl SoundCategory {
+    pub const ALL: [SoundCategory; 3] = [Self::Alerts, Self::Ambience, Self::Ui];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Alerts => "alerts",
+            Self::Ambience => "ambience",
+            Self::Ui => "ui",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_lowercase();
+        Self::ALL.into_iter().find(|c| c.name() == name)
+    }
+}
+
 /// Sound player for playing audio files
 pub struct SoundPlayer {
     #[cfg(feature = "sound")]
@@ -27,6 +64,10 @@ pub struct SoundPlayer {
     stream_handle: OutputStreamHandle,
     enabled: bool,
     volume: f32,
+    /// Level per category, indexed by `SoundCategory as usize`
+    category_volumes: [f32; 3],
+    /// Active sound pack (subdirectory of the sounds directory)
+    pack: Option<String>,
     cooldown_map: Arc<Mutex<std::collections::HashMap<String, Instant>>>,
     cooldown_duration: std::time::Duration,
 }
@@ -43,6 +84,8 @@ impl SoundPlayer {
                 stream_handle,
                 enabled,
                 volume: volume.clamp(0.0, 1.0),
+                category_volumes: [1.0; 3],
+                pack: None,
                 cooldown_map: Arc::new(Mutex::new(std::collections::HashMap::new())),
                 cooldown_duration: std::time::Duration::from_millis(cooldown_ms),
             })
@@ -53,6 +96,8 @@ impl SoundPlayer {
             Ok(Self {
                 enabled,
                 volume: volume.clamp(0.0, 1.0),
+                category_volumes: [1.0; 3],
+                pack: None,
                 cooldown_map: Arc::new(Mutex::new(std::collections::HashMap::new())),
                 cooldown_duration: std::time::Duration::from_millis(cooldown_ms),
             })
@@ -71,6 +116,29 @@ impl SoundPlayer {
         debug!("Sound player volume set to: {}", self.volume);
     }
 
+    /// Set the level of one category (0.0 to 1.0)
+    pub fn set_category_volume(&mut self, category: SoundCategory, volume: f32) {
+        self.category_volumes[category as usize] = volume.clamp(0.0, 1.0);
+    }
+
+    /// Switch sound packs (None or "" = just the sounds directory)
+    pub fn set_pack(&mut self, pack: Option<&str>) {
+        self.pack = pack.filter(|p| !p.is_empty()).map(str::to_string);
+        debug!("Sound pack: {:?}", self.pack);
+    }
+
+    /// Volume a sound actually plays at: its own volume (or the master
+    /// volume) scaled by its category's level
+    fn effective_volume(&self, volume_override: Option<f32>, category: SoundCategory) -> f32 {
+        let volume = volume_override.unwrap_or(self.volume).clamp(0.0, 1.0);
+        volume * self.category_volumes[category as usize]
+    }
+
+    /// Set the minimum time between plays of the same sound
+    pub fn set_cooldown_ms(&mut self, cooldown_ms: u64) {
+        self.cooldown_duration = std::time::Duration::from_millis(cooldown_ms);
+    }
+
     /// Check if a sound is on cooldown
     fn is_on_cooldown(&self, sound_id: &str) -> bool {
         let map = self.cooldown_map.lock().unwrap();
@@ -92,12 +160,23 @@ impl SoundPlayer {
     /// # Arguments
     /// * `path` - Path to the sound file (supports WAV, MP3, OGG, FLAC)
     /// * `volume_override` - Optional volume override for this sound (0.0 to 1.0)
+    /// * `category` - Volume category the sound plays in
     /// * `sound_id` - Identifier for cooldown tracking (usually the file path)
     #[cfg(feature = "sound")]
-    pub fn play(&self, path: &PathBuf, volume_override: Option<f32>, sound_id: &str) -> Result<()> {
+    pub fn play(
+        &self,
+        path: &PathBuf,
+        volume_override: Option<f32>,
+        category: SoundCategory,
+        sound_id: &str,
+    ) -> Result<()> {
         if !self.enabled {
             return Ok(());
         }
+        let volume = self.effective_volume(volume_override, category);
+        if volume <= 0.0 {
+            return Ok(());
+        }
 
         // Check cooldown
         if self.is_on_cooldown(sound_id) {
@@ -123,10 +202,6 @@ impl SoundPlayer {
             }
         };
 
-        // Calculate final volume
-        let volume = volume_override.unwrap_or(self.volume);
-        let volume = volume.clamp(0.0, 1.0);
-
         // Create a sink and play
         let sink = Sink::try_new(&self.stream_handle)?;
         sink.set_volume(volume);
@@ -146,46 +221,80 @@ impl SoundPlayer {
         &self,
         _path: &PathBuf,
         _volume_override: Option<f32>,
+        _category: SoundCategory,
         _sound_id: &str,
     ) -> Result<()> {
         debug!("Sound playback disabled (sound feature not enabled)");
         Ok(())
     }
 
-    /// Play a sound from the shared sounds directory
+    /// Play a sound from the shared sounds directory (active pack first)
     ///
     /// # Arguments
-    /// * `filename` - Filename in ~/.two-face/sounds/
+    /// * `filename` - Filename in ~/.two-face/sounds/<pack>/ or ~/.two-face/sounds/
     /// * `volume_override` - Optional volume override
-    pub fn play_from_sounds_dir(&self, filename: &str, volume_override: Option<f32>) -> Result<()> {
+    /// * `category` - Volume category the sound plays in
+    pub fn play_from_sounds_dir(
+        &self,
+        filename: &str,
+        volume_override: Option<f32>,
+        category: SoundCategory,
+    ) -> Result<()> {
         let sounds_dir = crate::config::Config::sounds_dir()
             .map_err(|e| anyhow::anyhow!("Failed to get sounds directory: {}", e))?;
 
-        let mut path = sounds_dir.join(filename);
-
-        // If file doesn't exist as-is, try common audio extensions
-        if !path.exists() {
-            let extensions = ["mp3", "wav", "ogg", "flac"];
-            let mut found = false;
-            for ext in &extensions {
-                let path_with_ext = sounds_dir.join(format!("{}.{}", filename, ext));
-                if path_with_ext.exists() {
-                    path = path_with_ext;
-                    found = true;
-                    break;
-                }
-            }
-            if !found {
+        match resolve_sound(&sounds_dir, self.pack.as_deref(), filename) {
+            Some(path) => self.play(&path, volume_override, category, filename),
+            None => {
                 warn!(
-                    "Sound file not found: {:?} (tried extensions: mp3, wav, ogg, flac)",
-                    sounds_dir.join(filename)
+                    "Sound file not found: {:?} (pack: {:?}, tried extensions: {})",
+                    sounds_dir.join(filename),
+                    self.pack,
+                    SOUND_EXTENSIONS.join(", ")
                 );
-                return Ok(()); // Don't error, just skip
+                Ok(()) // Don't error, just skip
             }
         }
+    }
+}
+
+/// Extensions tried when a sound name is given without one
+const SOUND_EXTENSIONS: [&str; 4] = ["mp3", "wav", "ogg", "flac"];
 
-        self.play(&path, volume_override, filename)
+/// Find a sound by name: the pack's directory first, then the sounds
+/// directory itself, each as-is and then with the common audio extensions
+pub fn resolve_sound(sounds_dir: &Path, pack: Option<&str>, filename: &str) -> Option<PathBuf> {
+    let find_in = |dir: PathBuf| -> Option<PathBuf> {
+        let path = dir.join(filename);
+        if path.is_file() {
+            return Some(path);
+        }
+        SOUND_EXTENSIONS
+            .iter()
+            .map(|ext| dir.join(format!("{}.{}", filename, ext)))
+            .find(|path| path.is_file())
+    };
+
+    pack.filter(|p| !p.is_empty())
+        .and_then(|pack| find_in(sounds_dir.join(pack)))
+        .or_else(|| find_in(sounds_dir.to_path_buf()))
+}
+
+/// Sound packs installed under the sounds directory (its subdirectories)
+pub fn list_sound_packs() -> Result<Vec<String>> {
+    let sounds_dir = crate::config::Config::sounds_dir()
+        .map_err(|e| anyhow::anyhow!("Failed to get sounds directory: {}", e))?;
+    if !sounds_dir.exists() {
+        return Ok(Vec::new());
     }
+
+    let mut packs: Vec<String> = std::fs::read_dir(&sounds_dir)?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect();
+    packs.sort();
+    Ok(packs)
 }
 
 /// Embedded default sound files (included at compile time)
@@ -222,3 +331,44 @@ pub fn ensure_sounds_directory() -> Result<PathBuf> {
 
     Ok(sounds_dir)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_sound_pack_fallback() {
+        let dir =
+            std::env::temp_dir().join(format!("two-face-sound-pack-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("retro")).unwrap();
+        std::fs::write(dir.join("death.wav"), b"").unwrap();
+        std::fs::write(dir.join("whisper.ogg"), b"").unwrap();
+        std::fs::write(dir.join("retro").join("death.mp3"), b"").unwrap();
+
+        // The pack's own file wins, missing ones fall back to the shared directory
+        assert_eq!(
+            resolve_sound(&dir, Some("retro"), "death"),
+            Some(dir.join("retro").join("death.mp3"))
+        );
+        assert_eq!(
+            resolve_sound(&dir, Some("retro"), "whisper"),
+            Some(dir.join("whisper.ogg"))
+        );
+        assert_eq!(
+            resolve_sound(&dir, None, "death"),
+            Some(dir.join("death.wav"))
+        );
+        assert_eq!(
+            resolve_sound(&dir, Some(""), "death.wav"),
+            Some(dir.join("death.wav"))
+        );
+        assert_eq!(
+            resolve_sound(&dir, Some("missing"), "death"),
+            Some(dir.join("death.wav"))
+        );
+        assert_eq!(resolve_sound(&dir, Some("retro"), "bell"), None);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,466 @@
+//! Read-only spectator view over HTTP
+//!
+//! Serves a live HTML copy of selected text windows (main, thoughts by
+//! default) so a tablet or second screen can follow along. Features:
+//! - Plain `std::net` server on its own thread; the UI thread only publishes
+//!   pre-rendered HTML into a shared snapshot
+//! - Every request needs the configured token (`?token=` or a Bearer header)
+//! - GET only: there is no path that reaches the game connection
+//! - The HTTP server is only compiled in with the `spectator` cargo feature
+
+use crate::config::SpectatorConfig;
+use crate::data::{StyledLine, TextContent, UiState, WindowContent};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How often the UI thread refreshes the shared snapshot
+const PUBLISH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// One window as served to spectators
+#[cfg_attr(not(feature = "spectator"), allow(dead_code))]
+#[derive(Debug, Clone, Default)]
+struct WindowSnapshot {
+    name: String,
+    title: String,
+    lines: Vec<String>, // Already-escaped HTML, oldest first
+}
+
+/// Everything the server thread needs to answer a request
+#[cfg_attr(not(feature = "spectator"), allow(dead_code))]
+#[derive(Debug, Default)]
+struct Snapshot {
+    windows: Vec<WindowSnapshot>,
+}
+
+/// Spectator manager owned by AppCore
+///
+/// When disabled in config (or built without the `spectator` feature) this is
+/// a no-op and `publish` returns immediately.
+pub struct Spectator {
+    config: SpectatorConfig,
+    shared: Arc<Mutex<Snapshot>>,
+    /// Last published generation per window, to skip unchanged windows
+    generations: HashMap<String, u64>,
+    last_publish: Option<Instant>,
+    /// Address being served, or why the server is not running
+    status: Result<String, String>,
+}
+
+impl Spectator {
+    pub fn new(config: &SpectatorConfig) -> Self {
+        let mut spectator = Self {
+            config: config.clone(),
+            shared: Arc::new(Mutex::new(Snapshot::default())),
+            generations: HashMap::new(),
+            last_publish: None,
+            status: Err("disabled (set enabled = true under [spectator])".to_string()),
+        };
+        if config.enabled {
+            spectator.status = spectator.start();
+            match &spectator.status {
+                Ok(addr) => tracing::info!("Spectator view serving on http://{}/", addr),
+                Err(e) => tracing::warn!("Spectator view not started: {}", e),
+            }
+        }
+        spectator
+    }
+
+    /// Whether the server is up and snapshots are being published
+    pub fn is_running(&self) -> bool {
+        self.status.is_ok()
+    }
+
+    /// One-line description for `.spectator`
+    pub fn status_line(&self) -> String {
+        match &self.status {
+            Ok(addr) => format!(
+                "Spectator view: http://{}/?token=<token> (windows: {})",
+                addr,
+                self.config.windows.join(", ")
+            ),
+            Err(reason) => format!("Spectator view: {}", reason),
+        }
+    }
+
+    /// Copy the configured windows into the shared snapshot (throttled)
+    pub fn publish(&mut self, ui_state: &UiState) {
+        if !self.is_running() {
+            return;
+        }
+        if self
+            .last_publish
+            .is_some_and(|t| t.elapsed() < PUBLISH_INTERVAL)
+        {
+            return;
+        }
+        self.last_publish = Some(Instant::now());
+
+        let contents: Vec<(&String, &TextContent)> = self
+            .config
+            .windows
+            .iter()
+            .filter_map(|name| match &ui_state.get_window(name)?.content {
+                WindowContent::Text(content) => Some((name, content)),
+                _ => None,
+            })
+            .collect();
+
+        // Rendering lines to HTML is the costly part, so skip it when nothing moved
+        let mut changed = false;
+        for (name, content) in &contents {
+            if self.generations.get(*name) != Some(&content.generation) {
+                self.generations.insert((*name).clone(), content.generation);
+                changed = true;
+            }
+        }
+        if !changed {
+            return;
+        }
+
+        let windows = contents
+            .into_iter()
+            .map(|(name, content)| {
+                let skip = content.lines.len().saturating_sub(self.config.lines);
+                WindowSnapshot {
+                    name: name.clone(),
+                    title: if content.title.is_empty() {
+                        name.clone()
+                    } else {
+                        content.title.clone()
+                    },
+                    lines: content.lines.iter().skip(skip).map(line_to_html).collect(),
+                }
+            })
+            .collect();
+        if let Ok(mut shared) = self.shared.lock() {
+            shared.windows = windows;
+        }
+    }
+
+    #[cfg(feature = "spectator")]
+    fn start(&self) -> Result<String, String> {
+        if self.config.token.trim().is_empty() {
+            return Err("no token set under [spectator]; refusing to serve".to_string());
+        }
+        let addr = format!("{}:{}", self.config.bind, self.config.port);
+        let listener = std::net::TcpListener::bind(&addr)
+            .map_err(|e| format!("cannot listen on {}: {}", addr, e))?;
+        let shared = Arc::clone(&self.shared);
+        let token = self.config.token.clone();
+        let refresh_secs = self.config.refresh_secs.max(1);
+        std::thread::Builder::new()
+            .name("spectator-http".to_string())
+            .spawn(move || server::run(listener, shared, token, refresh_secs))
+            .map_err(|e| e.to_string())?;
+        Ok(addr)
+    }
+
+    #[cfg(not(feature = "spectator"))]
+    fn start(&self) -> Result<String, String> {
+        Err("not available (built without the `spectator` feature)".to_string())
+    }
+}
+
+/// Escape text for inclusion in HTML
+fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Only well-formed "#RRGGBB" colors make it into style attributes
+fn css_color(color: Option<&String>) -> Option<&str> {
+    let color = color?.as_str();
+    let valid = color.len() == 7
+        && color.starts_with('#')
+        && color[1..].chars().all(|c| c.is_ascii_hexdigit());
+    valid.then_some(color)
+}
+
+/// Render one styled line as HTML spans
+fn line_to_html(line: &StyledLine) -> String {
+    let mut html = String::new();
+    for segment in &line.segments {
+        let mut style = String::new();
+        if let Some(fg) = css_color(segment.fg.as_ref()) {
+            let _ = write!(style, "color:{};", fg);
+        }
+        if let Some(bg) = css_color(segment.bg.as_ref()) {
+            let _ = write!(style, "background:{};", bg);
+        }
+        if segment.bold {
+            style.push_str("font-weight:bold;");
+        }
+        let text = escape_html(&segment.text);
+        if style.is_empty() {
+            html.push_str(&text);
+        } else {
+            let _ = write!(html, "<span style=\"{}\">{}</span>", style, text);
+        }
+    }
+    html
+}
+
+#[cfg(feature = "spectator")]
+mod server {
+    //! Minimal HTTP/1.0-style responder: one request per connection, GET only.
+
+    use super::{escape_html, Snapshot};
+    use crate::token::token_matches;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    /// Request heads are cut off here (we only accept bare GETs)
+    const MAX_HEAD_BYTES: usize = 8 * 1024;
+
+    pub(super) fn run(
+        listener: TcpListener,
+        shared: Arc<Mutex<Snapshot>>,
+        token: String,
+        refresh_secs: u64,
+    ) {
+        for stream in listener.incoming() {
+            let Ok(stream) = stream else { continue };
+            // A stalled client must not hold up the next one for long
+            let _ = stream.set_read_timeout(Some(Duration::from_secs(2)));
+            let _ = stream.set_write_timeout(Some(Duration::from_secs(2)));
+            if let Err(e) = handle(stream, &shared, &token, refresh_secs) {
+                tracing::debug!("Spectator request failed: {}", e);
+            }
+        }
+    }
+
+    fn handle(
+        stream: TcpStream,
+        shared: &Arc<Mutex<Snapshot>>,
+        token: &str,
+        refresh_secs: u64,
+    ) -> std::io::Result<()> {
+        let mut reader = BufReader::new(stream.try_clone()?.take(MAX_HEAD_BYTES as u64));
+        let mut head = String::new();
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line)? == 0 || line == "\r\n" || line == "\n" {
+                break;
+            }
+            head.push_str(&line);
+        }
+
+        let Some(request) = Request::parse(&head) else {
+            return respond(stream, "400 Bad Request", "text/plain", "Bad request");
+        };
+        if request.method != "GET" {
+            return respond(stream, "405 Method Not Allowed", "text/plain", "Read-only");
+        }
+        if !request
+            .token
+            .as_deref()
+            .is_some_and(|t| token_matches(t, token))
+        {
+            return respond(stream, "401 Unauthorized", "text/plain", "Token required");
+        }
+        if request.path != "/" {
+            return respond(stream, "404 Not Found", "text/plain", "Not found");
+        }
+
+        let page = match shared.lock() {
+            Ok(snapshot) => render_page(&snapshot, request.window.as_deref(), token, refresh_secs),
+            Err(_) => String::new(),
+        };
+        respond(stream, "200 OK", "text/html; charset=utf-8", &page)
+    }
+
+    fn respond(
+        mut stream: TcpStream,
+        status: &str,
+        content_type: &str,
+        body: &str,
+    ) -> std::io::Result<()> {
+        write!(
+            stream,
+            "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n{}",
+            status,
+            content_type,
+            body.len(),
+            body
+        )?;
+        stream.flush()
+    }
+
+    /// The parts of a request line and headers we care about
+    #[derive(Debug, PartialEq)]
+    pub(super) struct Request {
+        pub method: String,
+        pub path: String,
+        pub token: Option<String>,
+        pub window: Option<String>,
+    }
+
+    impl Request {
+        pub fn parse(head: &str) -> Option<Self> {
+            let mut lines = head.lines();
+            let mut parts = lines.next()?.split_whitespace();
+            let method = parts.next()?.to_string();
+            let target = parts.next()?;
+            let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+            let mut token = None;
+            let mut window = None;
+            for pair in query.split('&') {
+                match pair.split_once('=') {
+                    Some(("token", value)) => token = Some(percent_decode(value)),
+                    Some(("window", value)) => window = Some(percent_decode(value)),
+                    _ => {}
+                }
+            }
+            for header in lines {
+                if let Some((name, value)) = header.split_once(':') {
+                    if name.trim().eq_ignore_ascii_case("authorization") {
+                        if let Some(bearer) = value.trim().strip_prefix("Bearer ") {
+                            token = Some(bearer.trim().to_string());
+                        }
+                    }
+                }
+            }
+
+            Some(Self {
+                method,
+                path: path.to_string(),
+                token,
+                window,
+            })
+        }
+    }
+
+    fn percent_decode(value: &str) -> String {
+        let bytes = value.as_bytes();
+        let mut out = Vec::with_capacity(bytes.len());
+        let mut i = 0;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'%' if i + 2 < bytes.len() => {
+                    let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+                    match hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                        Some(b) => {
+                            out.push(b);
+                            i += 3;
+                            continue;
+                        }
+                        None => out.push(b'%'),
+                    }
+                }
+                b'+' => out.push(b' '),
+                b => out.push(b),
+            }
+            i += 1;
+        }
+        String::from_utf8_lossy(&out).into_owned()
+    }
+
+    fn render_page(
+        snapshot: &Snapshot,
+        only: Option<&str>,
+        token: &str,
+        refresh_secs: u64,
+    ) -> String {
+        let token = escape_html(token);
+        let mut html = format!(
+            "<!DOCTYPE html><html><head><meta charset=\"utf-8\">\
+             <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\
+             <meta http-equiv=\"refresh\" content=\"{}\">\
+             <title>two-face</title><style>\
+             body{{background:#000;color:#ccc;font-family:monospace;margin:0.5em}}\
+             nav a{{color:#8af;margin-right:1em}}\
+             section{{border:1px solid #444;margin-bottom:0.5em;padding:0.25em}}\
+             h2{{font-size:1em;margin:0 0 0.25em 0;color:#fff}}\
+             pre{{white-space:pre-wrap;margin:0}}\
+             </style></head><body><nav><a href=\"/?token={}\">all</a>",
+            refresh_secs, token
+        );
+        for window in &snapshot.windows {
+            let name = escape_html(&window.name);
+            html.push_str(&format!(
+                "<a href=\"/?token={}&amp;window={}\">{}</a>",
+                token, name, name
+            ));
+        }
+        html.push_str("</nav>");
+
+        for window in &snapshot.windows {
+            if only.is_some_and(|o| o != window.name) {
+                continue;
+            }
+            html.push_str(&format!(
+                "<section><h2>{}</h2><pre>{}</pre></section>",
+                escape_html(&window.title),
+                window.lines.join("\n")
+            ));
+        }
+        // Newest text at the bottom, so start there after each refresh
+        html.push_str(
+            "<script>window.scrollTo(0,document.body.scrollHeight)</script></body></html>",
+        );
+        html
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_parse_request_token_sources() {
+            let req =
+                Request::parse("GET /?token=s%3Dcret&window=main HTTP/1.1\r\nHost: x\r\n").unwrap();
+            assert_eq!(req.method, "GET");
+            assert_eq!(req.path, "/");
+            assert_eq!(req.token.as_deref(), Some("s=cret"));
+            assert_eq!(req.window.as_deref(), Some("main"));
+
+            let req = Request::parse("POST / HTTP/1.1\r\nAuthorization: Bearer abc\r\n").unwrap();
+            assert_eq!(req.method, "POST");
+            assert_eq!(req.token.as_deref(), Some("abc"));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{SpanType, TextSegment};
+
+    #[test]
+    fn test_line_to_html_escapes_and_filters_colors() {
+        let segment = |text: &str, fg: Option<&str>| TextSegment {
+            text: text.to_string(),
+            fg: fg.map(str::to_string),
+            bg: None,
+            bold: false,
+            span_type: SpanType::Normal,
+            link_data: None,
+        };
+        let line = StyledLine {
+            segments: vec![
+                segment("<b>hi</b> & ", None),
+                segment("red", Some("#ff0000")),
+                segment("bad", Some("red;background:url(x)")),
+            ],
+        };
+        assert_eq!(
+            line_to_html(&line),
+            "&lt;b&gt;hi&lt;/b&gt; &amp; <span style=\"color:#ff0000;\">red</span>bad"
+        );
+    }
+}
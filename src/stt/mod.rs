@@ -0,0 +1,384 @@
+//! Speech-to-Text System
+//!
+//! Provides push-to-talk dictation into the command input for players who
+//! can't (or would rather not) type every command.
+//! Features:
+//! - Local transcription via whisper.cpp (no audio leaves the machine)
+//! - Push-to-talk toggle: first press starts listening, second press transcribes
+//! - Capture and transcription run on a worker thread so the UI never blocks
+//! - Compiled out entirely unless built with the `stt` cargo feature
+
+use anyhow::Result;
+use std::sync::mpsc::{channel, Receiver, Sender};
+
+/// Events sent from the STT worker thread to the main event loop
+#[cfg_attr(not(feature = "stt"), allow(dead_code))]
+#[derive(Debug, Clone)]
+pub enum SttEvent {
+    /// Microphone is open and recording
+    Listening,
+    /// Recording stopped, transcription in progress
+    Transcribing,
+    /// Finished transcript (already trimmed, never empty)
+    Transcribed(String),
+    /// Recording produced no recognizable speech
+    NoSpeech,
+    /// Capture or transcription failed
+    Error(String),
+}
+
+/// Commands sent from the main thread to the STT worker
+enum SttCommand {
+    Start,
+    Stop,
+}
+
+/// Speech-to-Text manager
+///
+/// Owns the worker thread that records from the default input device and
+/// runs whisper over the captured audio. When STT is disabled in config (or
+/// the `stt` feature is not compiled in) this is a no-op.
+pub struct SttManager {
+    /// Is STT enabled in config?
+    enabled: bool,
+
+    /// Are we currently recording?
+    listening: bool,
+
+    /// Is the worker transcribing the last recording?
+    transcribing: bool,
+
+    /// Command channel to the worker (None until first use)
+    command_tx: Option<Sender<SttCommand>>,
+
+    /// Event channel from the worker
+    #[cfg_attr(not(feature = "stt"), allow(dead_code))]
+    event_tx: Sender<SttEvent>,
+    event_rx: Receiver<SttEvent>,
+
+    /// Resolved model path and language from config
+    #[cfg_attr(not(feature = "stt"), allow(dead_code))]
+    model_path: Option<std::path::PathBuf>,
+    #[cfg_attr(not(feature = "stt"), allow(dead_code))]
+    language: String,
+}
+
+impl SttManager {
+    /// Create a new STT manager from config
+    pub fn new(config: &crate::config::SttConfig) -> Self {
+        let (event_tx, event_rx) = channel();
+
+        // Relative model paths are looked up in ~/.two-face/models/
+        let model_path = config.model_path.as_ref().map(|p| {
+            let path = std::path::PathBuf::from(p);
+            if path.is_absolute() {
+                path
+            } else {
+                crate::config::Config::models_dir()
+                    .map(|dir| dir.join(&path))
+                    .unwrap_or(path)
+            }
+        });
+
+        Self {
+            enabled: config.enabled,
+            listening: false,
+            transcribing: false,
+            command_tx: None,
+            event_tx,
+            event_rx,
+            model_path,
+            language: config.language.clone(),
+        }
+    }
+
+    /// Short status for the command input title while dictation is active
+    pub fn status_label(&self) -> Option<&'static str> {
+        if self.listening {
+            Some("Listening...")
+        } else if self.transcribing {
+            Some("Transcribing...")
+        } else {
+            None
+        }
+    }
+
+    /// Push-to-talk toggle: start listening, or stop and transcribe
+    pub fn toggle(&mut self) -> Result<()> {
+        if self.listening {
+            self.stop_listening()
+        } else {
+            self.start_listening()
+        }
+    }
+
+    /// Open the microphone and start recording
+    pub fn start_listening(&mut self) -> Result<()> {
+        if !self.enabled {
+            anyhow::bail!("Speech input is disabled (set enabled = true in [stt])");
+        }
+        if self.listening {
+            return Ok(());
+        }
+
+        self.ensure_worker()?;
+        if let Some(ref tx) = self.command_tx {
+            if tx.send(SttCommand::Start).is_err() {
+                // Worker died (e.g. model failed to load) - respawn on next attempt
+                self.command_tx = None;
+                anyhow::bail!("STT worker is not running");
+            }
+        }
+        self.listening = true;
+        Ok(())
+    }
+
+    /// Stop recording and hand the audio to whisper
+    pub fn stop_listening(&mut self) -> Result<()> {
+        if !self.listening {
+            return Ok(());
+        }
+        self.listening = false;
+
+        if let Some(ref tx) = self.command_tx {
+            if tx.send(SttCommand::Stop).is_err() {
+                self.command_tx = None;
+                anyhow::bail!("STT worker is not running");
+            }
+            self.transcribing = true;
+        }
+        Ok(())
+    }
+
+    /// Try to receive an STT event from the worker (non-blocking)
+    pub fn try_recv_event(&mut self) -> Result<SttEvent, std::sync::mpsc::TryRecvError> {
+        let event = self.event_rx.try_recv()?;
+        match event {
+            SttEvent::Transcribed(_) | SttEvent::NoSpeech => self.transcribing = false,
+            SttEvent::Error(_) => {
+                // Worker gave up on this recording; don't leave the UI stuck
+                self.listening = false;
+                self.transcribing = false;
+            }
+            SttEvent::Listening | SttEvent::Transcribing => {}
+        }
+        Ok(event)
+    }
+
+    /// Spawn the worker thread (lazy initialization)
+    #[cfg(feature = "stt")]
+    fn ensure_worker(&mut self) -> Result<()> {
+        if self.command_tx.is_some() {
+            return Ok(());
+        }
+
+        let model_path = self.model_path.clone().ok_or_else(|| {
+            anyhow::anyhow!("No whisper model configured (set model_path in [stt])")
+        })?;
+        if !model_path.exists() {
+            anyhow::bail!("Whisper model not found: {}", model_path.display());
+        }
+
+        let (command_tx, command_rx) = channel();
+        let event_tx = self.event_tx.clone();
+        let language = self.language.clone();
+
+        std::thread::Builder::new()
+            .name("stt-worker".to_string())
+            .spawn(move || worker::run(model_path, language, command_rx, event_tx))?;
+
+        self.command_tx = Some(command_tx);
+        tracing::info!("STT worker started");
+        Ok(())
+    }
+
+    /// Worker stub for when the stt feature is disabled
+    #[cfg(not(feature = "stt"))]
+    fn ensure_worker(&mut self) -> Result<()> {
+        anyhow::bail!("Speech input not available (built without the `stt` feature)")
+    }
+}
+
+#[cfg(feature = "stt")]
+mod worker {
+    //! Audio capture (cpal) and transcription (whisper-rs) on a dedicated thread.
+    //! cpal streams aren't Send on every platform, so the stream never leaves here.
+
+    use super::{SttCommand, SttEvent};
+    use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+    use std::path::PathBuf;
+    use std::sync::mpsc::{Receiver, Sender};
+    use std::sync::{Arc, Mutex};
+    use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
+
+    /// Whisper expects 16 kHz mono f32 samples
+    const WHISPER_SAMPLE_RATE: u32 = 16_000;
+
+    /// Ignore recordings shorter than this (accidental double taps)
+    const MIN_SAMPLES: usize = WHISPER_SAMPLE_RATE as usize / 4;
+
+    pub(super) fn run(
+        model_path: PathBuf,
+        language: String,
+        command_rx: Receiver<SttCommand>,
+        event_tx: Sender<SttEvent>,
+    ) {
+        let ctx = match WhisperContext::new_with_params(
+            &model_path.to_string_lossy(),
+            WhisperContextParameters::default(),
+        ) {
+            Ok(ctx) => ctx,
+            Err(e) => {
+                let _ = event_tx.send(SttEvent::Error(format!("Failed to load model: {}", e)));
+                return;
+            }
+        };
+
+        let mut recording: Option<(cpal::Stream, Arc<Mutex<Vec<f32>>>, u32)> = None;
+
+        while let Ok(command) = command_rx.recv() {
+            match command {
+                SttCommand::Start => {
+                    if recording.is_some() {
+                        continue;
+                    }
+                    match open_input_stream() {
+                        Ok(rec) => {
+                            recording = Some(rec);
+                            let _ = event_tx.send(SttEvent::Listening);
+                        }
+                        Err(e) => {
+                            let _ = event_tx.send(SttEvent::Error(e.to_string()));
+                        }
+                    }
+                }
+                SttCommand::Stop => {
+                    let Some((stream, buffer, sample_rate)) = recording.take() else {
+                        continue;
+                    };
+                    drop(stream); // Closes the microphone
+
+                    let samples = std::mem::take(&mut *buffer.lock().unwrap());
+                    let samples = resample(&samples, sample_rate, WHISPER_SAMPLE_RATE);
+                    if samples.len() < MIN_SAMPLES {
+                        let _ = event_tx.send(SttEvent::NoSpeech);
+                        continue;
+                    }
+
+                    let _ = event_tx.send(SttEvent::Transcribing);
+                    let event = match transcribe(&ctx, &language, &samples) {
+                        Ok(text) if text.is_empty() => SttEvent::NoSpeech,
+                        Ok(text) => SttEvent::Transcribed(text),
+                        Err(e) => SttEvent::Error(format!("Transcription failed: {}", e)),
+                    };
+                    let _ = event_tx.send(event);
+                }
+            }
+        }
+
+        tracing::debug!("STT worker exiting");
+    }
+
+    /// Open the default input device, downmixing to mono into a shared buffer
+    fn open_input_stream() -> anyhow::Result<(cpal::Stream, Arc<Mutex<Vec<f32>>>, u32)> {
+        let host = cpal::default_host();
+        let device = host
+            .default_input_device()
+            .ok_or_else(|| anyhow::anyhow!("No microphone found"))?;
+        let supported = device.default_input_config()?;
+        let sample_rate = supported.sample_rate().0;
+        let channels = supported.channels() as usize;
+        let buffer = Arc::new(Mutex::new(Vec::new()));
+
+        let err_fn = |e| tracing::warn!("STT input stream error: {}", e);
+        let stream = match supported.sample_format() {
+            cpal::SampleFormat::F32 => {
+                let buffer = buffer.clone();
+                device.build_input_stream(
+                    &supported.into(),
+                    move |data: &[f32], _: &_| push_mono(&buffer, data, channels, |s| s),
+                    err_fn,
+                    None,
+                )?
+            }
+            cpal::SampleFormat::I16 => {
+                let buffer = buffer.clone();
+                device.build_input_stream(
+                    &supported.into(),
+                    move |data: &[i16], _: &_| {
+                        push_mono(&buffer, data, channels, |s| s as f32 / i16::MAX as f32)
+                    },
+                    err_fn,
+                    None,
+                )?
+            }
+            cpal::SampleFormat::U16 => {
+                let buffer = buffer.clone();
+                device.build_input_stream(
+                    &supported.into(),
+                    move |data: &[u16], _: &_| {
+                        push_mono(&buffer, data, channels, |s| (s as f32 - 32768.0) / 32768.0)
+                    },
+                    err_fn,
+                    None,
+                )?
+            }
+            other => anyhow::bail!("Unsupported microphone sample format: {:?}", other),
+        };
+        stream.play()?;
+
+        Ok((stream, buffer, sample_rate))
+    }
+
+    fn push_mono<T: Copy>(
+        buffer: &Arc<Mutex<Vec<f32>>>,
+        data: &[T],
+        channels: usize,
+        to_f32: impl Fn(T) -> f32,
+    ) {
+        let channels = channels.max(1);
+        let mut buffer = buffer.lock().unwrap();
+        for frame in data.chunks(channels) {
+            let sum: f32 = frame.iter().map(|&s| to_f32(s)).sum();
+            buffer.push(sum / frame.len() as f32);
+        }
+    }
+
+    /// Linear resample - good enough for speech recognition
+    fn resample(samples: &[f32], from: u32, to: u32) -> Vec<f32> {
+        if from == to || samples.is_empty() {
+            return samples.to_vec();
+        }
+        let ratio = from as f64 / to as f64;
+        let out_len = (samples.len() as f64 / ratio) as usize;
+        (0..out_len)
+            .map(|i| {
+                let pos = i as f64 * ratio;
+                let idx = pos as usize;
+                let frac = (pos - idx as f64) as f32;
+                let a = samples[idx.min(samples.len() - 1)];
+                let b = samples[(idx + 1).min(samples.len() - 1)];
+                a + (b - a) * frac
+            })
+            .collect()
+    }
+
+    fn transcribe(ctx: &WhisperContext, language: &str, samples: &[f32]) -> anyhow::Result<String> {
+        let mut state = ctx.create_state()?;
+        let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+        params.set_language(Some(language));
+        params.set_print_special(false);
+        params.set_print_progress(false);
+        params.set_print_realtime(false);
+        params.set_print_timestamps(false);
+        params.set_single_segment(true);
+
+        state.full(params, samples)?;
+
+        let mut text = String::new();
+        for i in 0..state.full_n_segments()? {
+            text.push_str(&state.full_get_segment_text(i)?);
+        }
+        Ok(text.trim().to_string())
+    }
+}
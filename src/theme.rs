@@ -7,6 +7,9 @@ use ratatui::style::Color;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+pub mod import;
+pub mod terminal;
+
 /// Complete application theme defining all UI colors
 #[derive(Debug, Clone)]
 pub struct AppTheme {
@@ -3773,11 +3776,23 @@ impl ThemePresets {
         custom_themes
     }
 
+    /// Save a theme where `load_custom_themes` will find it
+    pub fn save_custom_theme(
+        theme: &AppTheme,
+        config_base: Option<&str>,
+    ) -> anyhow::Result<std::path::PathBuf> {
+        crate::frontend::tui::theme_editor::ThemeData::from_theme(theme).save_to_file(config_base)
+    }
+
     /// Get all available themes (built-in + custom)
     pub fn all_with_custom(config_base: Option<&str>) -> HashMap<String, AppTheme> {
         let mut themes = Self::all();
         let custom = Self::load_custom_themes(config_base);
         themes.extend(custom);
+        // The terminal pseudo-theme exists once its palette has been read
+        if let Some(theme) = terminal::cached_theme() {
+            themes.insert(terminal::TERMINAL_THEME.to_string(), theme);
+        }
         themes
     }
 }
@@ -0,0 +1,252 @@
+//! Import base16 and iTerm2 color schemes as custom themes
+//!
+//! Both formats describe a terminal palette, so they're converted to a
+//! `TerminalPalette` and mapped onto theme slots the same way as the
+//! terminal pseudo-theme. The caller saves the result under `themes/`.
+
+use super::terminal::TerminalPalette;
+use super::AppTheme;
+use anyhow::{bail, Context, Result};
+use regex::Regex;
+use std::path::Path;
+
+/// Supported scheme file formats
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SchemeFormat {
+    /// base16 / tinted-theming YAML (`base00` .. `base0F`)
+    Base16,
+    /// iTerm2 `.itermcolors` property list
+    ITerm,
+}
+
+impl SchemeFormat {
+    /// Guess the format from the file extension, then the contents
+    pub fn detect(path: &Path, contents: &str) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("itermcolors") => Self::ITerm,
+            Some(ext) if ext.eq_ignore_ascii_case("yaml") || ext.eq_ignore_ascii_case("yml") => {
+                Self::Base16
+            }
+            _ if contents.contains("<plist") => Self::ITerm,
+            _ => Self::Base16,
+        }
+    }
+}
+
+/// Read a scheme file and build a theme from it
+///
+/// The theme id defaults to a slug of the scheme's name (or the file name).
+pub fn import_scheme(path: &Path, name: Option<&str>) -> Result<AppTheme> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let format = SchemeFormat::detect(path, &contents);
+
+    let (scheme_name, author, palette) = match format {
+        SchemeFormat::Base16 => parse_base16(&contents)?,
+        SchemeFormat::ITerm => (None, None, parse_itermcolors(&contents)?),
+    };
+
+    let file_stem = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("imported");
+    let display_name = scheme_name.as_deref().unwrap_or(file_stem);
+
+    let mut theme = palette.to_theme();
+    theme.name = slugify(name.unwrap_or(display_name));
+    theme.description = match (format, author) {
+        (SchemeFormat::Base16, Some(author)) => {
+            format!("{} (base16, by {})", display_name, author)
+        }
+        (SchemeFormat::Base16, None) => format!("{} (base16)", display_name),
+        (SchemeFormat::ITerm, _) => format!("{} (iTerm2)", display_name),
+    };
+    if theme.name.is_empty() {
+        bail!("Theme name is empty");
+    }
+    Ok(theme)
+}
+
+/// Lowercase, dash-separated id usable with `.settheme`
+fn slugify(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.trim().chars() {
+        if c.is_alphanumeric() {
+            slug.extend(c.to_lowercase());
+        } else if !slug.ends_with('-') && !slug.is_empty() {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+/// Parse base16 YAML: returns (scheme name, author, palette)
+///
+/// Only flat `key: value` lines are needed, so this reads them directly
+/// instead of pulling in a YAML parser. Both the classic layout and the
+/// tinted-theming one (colors nested under `palette:`) work.
+fn parse_base16(contents: &str) -> Result<(Option<String>, Option<String>, TerminalPalette)> {
+    let mut name = None;
+    let mut author = None;
+    let mut base: [Option<(u8, u8, u8)>; 16] = [None; 16];
+
+    for line in contents.lines() {
+        let Some((key, value)) = line.trim().split_once(':') else {
+            continue;
+        };
+        let value = yaml_scalar(value);
+        match key.trim() {
+            "scheme" | "name" if name.is_none() && !value.is_empty() => name = Some(value),
+            "author" if !value.is_empty() => author = Some(value),
+            key => {
+                let Some(index) = key
+                    .strip_prefix("base0")
+                    .and_then(|digit| u8::from_str_radix(digit, 16).ok())
+                else {
+                    continue;
+                };
+                base[index as usize] = parse_hex(&value);
+            }
+        }
+    }
+
+    let missing: Vec<String> = (0..16)
+        .filter(|&i| base[i].is_none())
+        .map(|i| format!("base0{:X}", i))
+        .collect();
+    if !missing.is_empty() {
+        bail!("Not a base16 scheme (missing {})", missing.join(", "));
+    }
+
+    // Standard base16-shell mapping of the 16 ANSI slots
+    const ANSI_FROM_BASE: [usize; 16] = [
+        0x0, 0x8, 0xB, 0xA, 0xD, 0xE, 0xC, 0x5, 0x3, 0x8, 0xB, 0xA, 0xD, 0xE, 0xC, 0x7,
+    ];
+    let palette = TerminalPalette {
+        ansi: ANSI_FROM_BASE.map(|i| base[i]),
+        foreground: base[0x5],
+        background: base[0x0],
+    };
+    Ok((name, author, palette))
+}
+
+/// Strip quotes and trailing comments from a YAML scalar
+fn yaml_scalar(value: &str) -> String {
+    let value = value.trim();
+    for quote in ['"', '\''] {
+        if let Some(rest) = value.strip_prefix(quote) {
+            return rest.split(quote).next().unwrap_or("").to_string();
+        }
+    }
+    value.split(" #").next().unwrap_or("").trim().to_string()
+}
+
+fn parse_hex(value: &str) -> Option<(u8, u8, u8)> {
+    let hex = value.trim().trim_start_matches('#');
+    if hex.len() != 6 {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some((channel(0)?, channel(2)?, channel(4)?))
+}
+
+/// Parse an iTerm2 `.itermcolors` plist
+fn parse_itermcolors(contents: &str) -> Result<TerminalPalette> {
+    let entry_re = Regex::new(r"(?s)<key>\s*([^<]+?)\s*</key>\s*<dict>(.*?)</dict>").unwrap();
+    let component_re = Regex::new(
+        r"(?s)<key>\s*(Red|Green|Blue) Component\s*</key>\s*<(?:real|integer)>\s*([^<]+?)\s*</",
+    )
+    .unwrap();
+
+    let mut palette = TerminalPalette::default();
+    for entry in entry_re.captures_iter(contents) {
+        let mut rgb = [None; 3];
+        for component in component_re.captures_iter(&entry[2]) {
+            let slot = match &component[1] {
+                "Red" => 0,
+                "Green" => 1,
+                _ => 2,
+            };
+            rgb[slot] = component[2]
+                .parse::<f64>()
+                .ok()
+                .map(|v| (v.clamp(0.0, 1.0) * 255.0).round() as u8);
+        }
+        let [Some(r), Some(g), Some(b)] = rgb else {
+            continue;
+        };
+
+        match &entry[1] {
+            "Foreground Color" => palette.foreground = Some((r, g, b)),
+            "Background Color" => palette.background = Some((r, g, b)),
+            key => {
+                let index = key
+                    .strip_prefix("Ansi ")
+                    .and_then(|rest| rest.strip_suffix(" Color"))
+                    .and_then(|n| n.parse::<usize>().ok());
+                if let Some(index) = index.filter(|&i| i < 16) {
+                    palette.ansi[index] = Some((r, g, b));
+                }
+            }
+        }
+    }
+
+    if !palette.is_usable() {
+        bail!("Not an iTerm2 color scheme (no foreground/background colors)");
+    }
+    Ok(palette)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::Color;
+
+    #[test]
+    fn test_parse_base16() {
+        let yaml = "scheme: \"Test Scheme\"\nauthor: \"Someone\" # comment\n\
+            base00: \"1d2021\"\nbase01: \"3c3836\"\nbase02: \"504945\"\nbase03: \"665c54\"\n\
+            base04: \"bdae93\"\nbase05: \"d5c4a1\"\nbase06: \"ebdbb2\"\nbase07: \"fbf1c7\"\n\
+            base08: \"fb4934\"\nbase09: \"fe8019\"\nbase0A: \"fabd2f\"\nbase0B: \"b8bb26\"\n\
+            base0C: \"8ec07c\"\nbase0D: \"83a598\"\nbase0E: \"d3869b\"\nbase0F: \"d65d0e\"\n";
+        let (name, author, palette) = parse_base16(yaml).unwrap();
+        assert_eq!(name.as_deref(), Some("Test Scheme"));
+        assert_eq!(author.as_deref(), Some("Someone"));
+        assert_eq!(palette.background, Some((0x1d, 0x20, 0x21)));
+        assert_eq!(palette.ansi[1], Some((0xfb, 0x49, 0x34)));
+        assert_eq!(palette.ansi[15], Some((0xfb, 0xf1, 0xc7)));
+        assert_eq!(slugify(&name.unwrap()), "test-scheme");
+    }
+
+    #[test]
+    fn test_parse_base16_needs_every_color() {
+        assert!(parse_base16("base00: \"000000\"").is_err());
+    }
+
+    #[test]
+    fn test_parse_itermcolors() {
+        let plist = r#"<?xml version="1.0"?><plist version="1.0"><dict>
+            <key>Ansi 1 Color</key><dict>
+                <key>Blue Component</key><real>0.0</real>
+                <key>Green Component</key><real>0.0</real>
+                <key>Red Component</key><real>0.8</real>
+            </dict>
+            <key>Background Color</key><dict>
+                <key>Blue Component</key><real>0.1</real>
+                <key>Green Component</key><real>0.1</real>
+                <key>Red Component</key><real>0.1</real>
+            </dict>
+            <key>Foreground Color</key><dict>
+                <key>Blue Component</key><integer>1</integer>
+                <key>Green Component</key><integer>1</integer>
+                <key>Red Component</key><integer>1</integer>
+            </dict>
+        </dict></plist>"#;
+        let palette = parse_itermcolors(plist).unwrap();
+        assert_eq!(palette.ansi[1], Some((204, 0, 0)));
+        assert_eq!(palette.background, Some((26, 26, 26)));
+        let theme = palette.to_theme();
+        assert_eq!(theme.text_primary, Color::Rgb(255, 255, 255));
+        assert_eq!(theme.status_error, Color::Rgb(204, 0, 0));
+    }
+}
@@ -0,0 +1,316 @@
+//! Theme generated from the terminal's own color palette
+//!
+//! Terminals that answer OSC 4/10/11 queries report their 16 ANSI colors and
+//! default foreground/background. Those become the "terminal" pseudo-theme,
+//! so two-face blends in with whatever scheme the terminal is using. The
+//! palette is only queried on demand (`.termtheme`, or at startup when the
+//! terminal theme is active) and cached for the rest of the session.
+
+use super::{blend_colors, derive_injury_default_color, indexed_color_to_rgb, AppTheme};
+use anyhow::{anyhow, bail, Result};
+use ratatui::style::Color;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Theme id of the pseudo-theme
+pub const TERMINAL_THEME: &str = "terminal";
+
+/// How long to wait for the terminal to answer
+const QUERY_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Theme built by the last successful refresh
+static CACHED_THEME: Mutex<Option<AppTheme>> = Mutex::new(None);
+
+type Rgb = (u8, u8, u8);
+
+/// Colors reported by the terminal; anything it didn't answer stays None
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TerminalPalette {
+    pub ansi: [Option<Rgb>; 16],
+    pub foreground: Option<Rgb>,
+    pub background: Option<Rgb>,
+}
+
+impl TerminalPalette {
+    /// Pull every OSC 4/10/11 reply out of raw terminal output
+    pub fn parse_responses(bytes: &[u8]) -> Self {
+        let mut palette = Self::default();
+        let text = String::from_utf8_lossy(bytes);
+
+        for reply in text.split("\x1b]").skip(1) {
+            // Replies end with ST (ESC \) or BEL
+            let end = reply.find(['\x1b', '\x07']).unwrap_or(reply.len());
+            let mut fields = reply[..end].split(';');
+            match fields.next() {
+                Some("4") => {
+                    let index = fields.next().and_then(|i| i.parse::<usize>().ok());
+                    let color = fields.next().and_then(parse_color_spec);
+                    if let (Some(index), Some(color)) = (index, color) {
+                        if index < 16 {
+                            palette.ansi[index] = Some(color);
+                        }
+                    }
+                }
+                Some("10") => palette.foreground = fields.next().and_then(parse_color_spec),
+                Some("11") => palette.background = fields.next().and_then(parse_color_spec),
+                _ => {}
+            }
+        }
+        palette
+    }
+
+    /// Without a foreground and background there's nothing to blend in with
+    pub fn is_usable(&self) -> bool {
+        self.foreground.is_some() && self.background.is_some()
+    }
+
+    fn ansi_color(&self, index: usize) -> Color {
+        let (r, g, b) = self.ansi[index].unwrap_or_else(|| indexed_color_to_rgb(index as u8));
+        Color::Rgb(r, g, b)
+    }
+
+    /// Map the palette onto every theme slot
+    pub fn to_theme(&self) -> AppTheme {
+        let rgb = |(r, g, b): Rgb| Color::Rgb(r, g, b);
+        let background = rgb(self.background.unwrap_or((0, 0, 0)));
+        let foreground = rgb(self.foreground.unwrap_or((229, 229, 229)));
+
+        let red = self.ansi_color(1);
+        let green = self.ansi_color(2);
+        let yellow = self.ansi_color(3);
+        let blue = self.ansi_color(4);
+        let magenta = self.ansi_color(5);
+        let cyan = self.ansi_color(6);
+        let bright_black = self.ansi_color(8);
+        let bright_yellow = self.ansi_color(11);
+        let bright_blue = self.ansi_color(12);
+
+        let secondary = blend_colors(foreground, background, 0.35);
+        let raised = blend_colors(background, foreground, 0.08);
+        let hover = blend_colors(background, foreground, 0.15);
+        let selected = blend_colors(background, foreground, 0.25);
+
+        let mut theme = AppTheme {
+            name: "Terminal".to_string(),
+            description: "Colors read from the terminal's palette".to_string(),
+
+            window_border: cyan,
+            window_border_focused: bright_yellow,
+            window_background: background,
+            window_title: foreground,
+
+            text_primary: foreground,
+            text_secondary: secondary,
+            text_disabled: bright_black,
+            text_selected: bright_yellow,
+
+            background_primary: background,
+            background_secondary: raised,
+            background_selected: selected,
+            background_hover: hover,
+
+            editor_border: cyan,
+            editor_label: cyan,
+            editor_label_focused: bright_yellow,
+            editor_text: foreground,
+            editor_cursor: bright_yellow,
+            editor_status: yellow,
+            editor_background: background,
+
+            browser_border: cyan,
+            browser_title: foreground,
+            browser_item_normal: foreground,
+            browser_item_selected: background,
+            browser_item_focused: bright_yellow,
+            browser_background: background,
+            browser_scrollbar: cyan,
+
+            form_border: cyan,
+            form_label: bright_blue,
+            form_label_focused: bright_yellow,
+            form_field_background: raised,
+            form_field_text: cyan,
+            form_checkbox_checked: green,
+            form_checkbox_unchecked: secondary,
+            form_error: red,
+
+            menu_border: cyan,
+            menu_background: background,
+            menu_item_normal: foreground,
+            menu_item_selected: background,
+            menu_item_focused: bright_yellow,
+            menu_separator: bright_black,
+
+            status_info: cyan,
+            status_success: green,
+            status_warning: yellow,
+            status_error: red,
+            status_background: background,
+
+            button_normal: cyan,
+            button_hover: bright_yellow,
+            button_active: green,
+            button_disabled: bright_black,
+
+            command_echo: foreground,
+            selection_background: selected,
+            link_color: blue,
+            speech_color: green,
+            whisper_color: cyan,
+            thought_color: magenta,
+            injury_default_color: Color::Reset,
+        };
+
+        theme.injury_default_color =
+            derive_injury_default_color(theme.window_background, theme.text_secondary);
+        theme
+    }
+}
+
+/// Parse an X11 color spec ("rgb:RRRR/GGGG/BBBB" with 1-4 hex digits, or "#RRGGBB")
+fn parse_color_spec(spec: &str) -> Option<Rgb> {
+    let spec = spec.trim();
+    if let Some(hex) = spec.strip_prefix('#') {
+        if hex.len() != 6 {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        return Some((channel(0)?, channel(2)?, channel(4)?));
+    }
+
+    let body = spec
+        .strip_prefix("rgb:")
+        .or_else(|| spec.strip_prefix("rgba:"))?;
+    let mut channels = body.split('/').map(|part| {
+        if part.is_empty() || part.len() > 4 {
+            return None;
+        }
+        let value = u32::from_str_radix(part, 16).ok()?;
+        let max = (1u32 << (4 * part.len())) - 1;
+        Some(((value * 255 + max / 2) / max) as u8)
+    });
+    Some((channels.next()??, channels.next()??, channels.next()??))
+}
+
+/// The terminal theme from the last refresh, if any
+pub fn cached_theme() -> Option<AppTheme> {
+    CACHED_THEME.lock().ok()?.clone()
+}
+
+/// Query the terminal and rebuild the cached theme
+///
+/// Must run while the TUI owns the terminal in raw mode and nothing else is
+/// reading input, otherwise the replies are echoed or eaten as keystrokes.
+pub fn refresh() -> Result<AppTheme> {
+    let palette = TerminalPalette::parse_responses(&query_terminal()?);
+    if !palette.is_usable() {
+        bail!("Terminal did not report its colors (OSC 10/11 not supported)");
+    }
+    let answered = palette.ansi.iter().filter(|c| c.is_some()).count();
+    tracing::info!(
+        "Read terminal palette ({} of 16 ANSI colors answered)",
+        answered
+    );
+
+    let theme = palette.to_theme();
+    if let Ok(mut cached) = CACHED_THEME.lock() {
+        *cached = Some(theme.clone());
+    }
+    Ok(theme)
+}
+
+/// Send the palette queries and collect the raw replies
+///
+/// A trailing DA1 request acts as a sentinel: every VT100-style terminal
+/// answers it, and answers in order, so its reply means all the OSC replies
+/// that are coming have arrived.
+fn query_terminal() -> Result<Vec<u8>> {
+    use std::io::{Read, Write};
+    use std::sync::mpsc;
+    use std::time::Instant;
+
+    let mut tty = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .map_err(|e| anyhow!("Cannot open terminal for palette query: {}", e))?;
+    let mut reader = tty.try_clone()?;
+
+    let mut query = String::new();
+    for index in 0..16 {
+        query.push_str(&format!("\x1b]4;{};?\x1b\\", index));
+    }
+    query.push_str("\x1b]10;?\x1b\\\x1b]11;?\x1b\\\x1b[c");
+
+    let (tx, rx) = mpsc::channel();
+    let deadline = Instant::now() + QUERY_TIMEOUT;
+    std::thread::Builder::new()
+        .name("palette-query".to_string())
+        .spawn(move || {
+            let mut received = Vec::new();
+            let mut chunk = [0u8; 1024];
+            // A blocking read can't be cancelled; give up after the first
+            // read that completes past the deadline so at most one late
+            // keystroke is swallowed by a terminal that never answers.
+            while let Ok(n) = reader.read(&mut chunk) {
+                if n == 0 {
+                    break;
+                }
+                received.extend_from_slice(&chunk[..n]);
+                if has_device_attributes_reply(&received) || Instant::now() > deadline {
+                    break;
+                }
+            }
+            let _ = tx.send(received);
+        })?;
+
+    tty.write_all(query.as_bytes())?;
+    tty.flush()?;
+
+    rx.recv_timeout(QUERY_TIMEOUT)
+        .map_err(|_| anyhow!("Terminal did not answer the palette query"))
+}
+
+/// True once the DA1 reply (ESC [ ? ... c) is in the buffer
+fn has_device_attributes_reply(bytes: &[u8]) -> bool {
+    bytes.windows(3).enumerate().any(|(start, w)| {
+        w == b"\x1b[?"
+            && bytes[start + 3..]
+                .iter()
+                .find(|b| !(b.is_ascii_digit() || **b == b';'))
+                == Some(&b'c')
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REPLIES: &[u8] = b"\x1b]4;1;rgb:cccc/0000/0000\x1b\\\x1b]4;12;rgb:5/8/f\x07\
+        \x1b]10;rgb:ffff/ffff/ffff\x1b\\\x1b]11;#1e1e2e\x1b\\\x1b[?62;22c";
+
+    #[test]
+    fn test_has_device_attributes_reply() {
+        assert!(has_device_attributes_reply(REPLIES));
+        assert!(!has_device_attributes_reply(b"\x1b]11;#1e1e2e\x1b\\"));
+    }
+
+    #[test]
+    fn test_parse_palette_replies() {
+        let palette = TerminalPalette::parse_responses(REPLIES);
+        assert_eq!(palette.ansi[1], Some((204, 0, 0)));
+        assert_eq!(palette.ansi[12], Some((85, 136, 255)));
+        assert_eq!(palette.ansi[0], None);
+        assert_eq!(palette.foreground, Some((255, 255, 255)));
+        assert_eq!(palette.background, Some((30, 30, 46)));
+        assert!(palette.is_usable());
+    }
+
+    #[test]
+    fn test_terminal_palette_to_theme() {
+        let theme = TerminalPalette::parse_responses(REPLIES).to_theme();
+        assert_eq!(theme.window_background, Color::Rgb(30, 30, 46));
+        assert_eq!(theme.status_error, Color::Rgb(204, 0, 0));
+        assert_eq!(theme.form_label, Color::Rgb(85, 136, 255));
+    }
+}
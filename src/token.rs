@@ -0,0 +1,23 @@
+//! Access token checks for the local servers
+//!
+//! The spectator view and the WebSocket API both gate clients on a shared
+//! token, compared here in constant time so response timing doesn't leak how
+//! much of a guess was right.
+
+/// Compare a client's token without leaking how much of it matched
+pub fn token_matches(given: &str, token: &str) -> bool {
+    given.len() == token.len() && openssl::memcmp::eq(given.as_bytes(), token.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_matches() {
+        assert!(token_matches("secret", "secret"));
+        assert!(!token_matches("secreT", "secret"));
+        assert!(!token_matches("secret2", "secret"));
+        assert!(!token_matches("", "secret"));
+    }
+}
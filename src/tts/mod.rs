@@ -3,12 +3,15 @@
 //! Provides accessibility support through text-to-speech output.
 //! Features:
 //! - Cross-platform TTS (Windows SAPI, macOS AVSpeechSynthesizer, Linux Speech Dispatcher)
-//! - Priority-based queue (Critical > High > Normal)
+//! - Priority-based queue (Critical > High > Normal); urgent lines interrupt
+//! - Regex include/exclude and priority rules, optional "only when unfocused"
 //! - Answering machine controls (Next/Previous/Pause/Mute)
-//! - Per-window speech configuration
+//! - Per-window speech configuration, with its own voice, rate and pitch
 //! - Zero performance cost when disabled
 
+use crate::config::TtsConfig;
 use anyhow::Result;
+use regex::Regex;
 use std::collections::VecDeque;
 use std::sync::mpsc::{channel, Sender, Receiver};
 use tts::{Tts, UtteranceId};
@@ -29,6 +32,85 @@ pub enum Priority {
     Critical = 2, // Damage warnings, death, critical alerts
 }
 
+impl Priority {
+    /// Parse a config name ("normal", "high", "critical")
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "normal" | "low" => Some(Self::Normal),
+            "high" => Some(Self::High),
+            "critical" => Some(Self::Critical),
+            _ => None,
+        }
+    }
+}
+
+/// Compiled include/exclude lists and priority rules from `[tts]`
+#[derive(Debug, Default)]
+pub struct TtsFilter {
+    include: Vec<Regex>,
+    exclude: Vec<Regex>,
+    rules: Vec<(Regex, Priority)>,
+    /// Lowest priority that interrupts less urgent speech (None = never)
+    interrupt_at: Option<Priority>,
+}
+
+impl TtsFilter {
+    /// Compile the config's patterns; bad ones are logged and skipped
+    pub fn new(config: &TtsConfig) -> Self {
+        let compile = |pattern: &str| match Regex::new(pattern) {
+            Ok(re) => Some(re),
+            Err(e) => {
+                tracing::warn!("Ignoring invalid TTS pattern '{}': {}", pattern, e);
+                None
+            }
+        };
+
+        let rules = config
+            .priority_rules
+            .iter()
+            .filter_map(|rule| {
+                let Some(priority) = Priority::from_name(&rule.priority) else {
+                    tracing::warn!(
+                        "Ignoring TTS rule with unknown priority '{}'",
+                        rule.priority
+                    );
+                    return None;
+                };
+                Some((compile(&rule.pattern)?, priority))
+            })
+            .collect();
+
+        Self {
+            include: config.include.iter().filter_map(|p| compile(p)).collect(),
+            exclude: config.exclude.iter().filter_map(|p| compile(p)).collect(),
+            rules,
+            interrupt_at: Priority::from_name(&config.interrupt_priority),
+        }
+    }
+
+    /// Priority to speak `text` at, or None if the lists filter it out
+    pub fn check(&self, text: &str, window_priority: Priority) -> Option<Priority> {
+        if !self.include.is_empty() && !self.include.iter().any(|re| re.is_match(text)) {
+            return None;
+        }
+        if self.exclude.iter().any(|re| re.is_match(text)) {
+            return None;
+        }
+        Some(
+            self.rules
+                .iter()
+                .find(|(re, _)| re.is_match(text))
+                .map(|(_, priority)| *priority)
+                .unwrap_or(window_priority),
+        )
+    }
+
+    /// Should a new entry cut off speech at `speaking` priority?
+    pub fn interrupts(&self, new: Priority, speaking: Priority) -> bool {
+        self.interrupt_at.is_some_and(|at| new >= at) && new > speaking
+    }
+}
+
 /// A single speech entry in the queue
 #[derive(Debug, Clone)]
 pub struct SpeechEntry {
@@ -66,6 +148,27 @@ pub struct TtsManager {
     /// Speech volume from config
     volume: f32,
 
+    /// Default voice, pitch and per-window overrides from config
+    voices: TtsConfig,
+
+    /// Voice, rate and pitch last sent to the engine
+    applied_voice: Option<(Option<String>, f32, f32)>,
+
+    /// Voices the engine offers (filled on initialization)
+    available_voices: Vec<tts::Voice>,
+
+    /// The engine's own voice at startup, restored for windows without one
+    system_voice: Option<tts::Voice>,
+
+    /// What gets spoken and what interrupts
+    filter: TtsFilter,
+
+    /// Terminal has focus (for `only_when_unfocused`)
+    app_focused: bool,
+
+    /// Paused: new lines queue up but nothing plays until resumed
+    paused: bool,
+
     /// Maximum queue size (prevent memory bloat)
     max_queue_size: usize,
 
@@ -78,11 +181,14 @@ pub struct TtsManager {
     backend_max_rate: f32,
     backend_min_volume: f32,
     backend_max_volume: f32,
+    backend_min_pitch: f32,
+    backend_max_pitch: f32,
+    backend_normal_pitch: f32,
 }
 
 impl TtsManager {
     /// Create a new TTS manager
-    pub fn new(enabled: bool, rate: f32, volume: f32) -> Self {
+    pub fn new(config: &TtsConfig) -> Self {
         let (event_tx, event_rx) = channel();
 
         Self {
@@ -91,9 +197,16 @@ impl TtsManager {
             current_index: None,
             current_utterance_id: None,
             muted: false,
-            enabled,
-            rate,
-            volume,
+            enabled: config.enabled,
+            rate: config.rate,
+            volume: config.volume,
+            voices: config.clone(),
+            applied_voice: None,
+            available_voices: Vec::new(),
+            system_voice: None,
+            filter: TtsFilter::new(config),
+            app_focused: true,
+            paused: false,
             max_queue_size: 100, // Reasonable limit
             event_tx,
             event_rx,
@@ -102,9 +215,36 @@ impl TtsManager {
             backend_max_rate: 10.0,
             backend_min_volume: 0.0,
             backend_max_volume: 1.0,
+            backend_min_pitch: 0.0,
+            backend_max_pitch: 2.0,
+            backend_normal_pitch: 1.0,
+        }
+    }
+
+    /// Apply changed TTS settings (voices, rate, volume, enabled)
+    pub fn configure(&mut self, config: &TtsConfig) {
+        self.set_enabled(config.enabled);
+        self.rate = config.rate;
+        self.volume = config.volume;
+        self.voices = config.clone();
+        self.applied_voice = None; // Re-sent before the next utterance
+        self.filter = TtsFilter::new(config);
+
+        let normalized_volume = self.normalize_volume(self.volume);
+        if let Some(ref mut engine) = self.engine {
+            let _ = engine.set_volume(normalized_volume);
         }
     }
 
+    /// Names of the voices the TTS engine offers
+    pub fn voice_names(&mut self) -> Result<Vec<String>> {
+        if !self.enabled {
+            anyhow::bail!("TTS is disabled ([tts] enabled = false)");
+        }
+        self.ensure_initialized()?;
+        Ok(self.available_voices.iter().map(|v| v.name()).collect())
+    }
+
     /// Initialize the TTS engine (lazy initialization)
     fn ensure_initialized(&mut self) -> Result<()> {
         if self.enabled && self.engine.is_none() {
@@ -116,6 +256,11 @@ impl TtsManager {
             self.backend_max_rate = tts.max_rate();
             self.backend_min_volume = tts.min_volume();
             self.backend_max_volume = tts.max_volume();
+            self.backend_min_pitch = tts.min_pitch();
+            self.backend_max_pitch = tts.max_pitch();
+            self.backend_normal_pitch = tts.normal_pitch();
+            self.available_voices = tts.voices().unwrap_or_default();
+            self.system_voice = tts.voice().ok().flatten();
 
             tracing::info!(
                 "TTS backend ranges: rate={} to {}, volume={} to {}",
@@ -159,6 +304,51 @@ impl TtsManager {
         config_rate.clamp(self.backend_min_rate, self.backend_max_rate)
     }
 
+    /// Map config pitch (1.0 = normal) onto the backend's range
+    fn normalize_pitch(&self, config_pitch: f32) -> f32 {
+        (self.backend_normal_pitch * config_pitch.clamp(0.5, 2.0))
+            .clamp(self.backend_min_pitch, self.backend_max_pitch)
+    }
+
+    /// Switch the engine to a window's voice, rate and pitch if they differ
+    fn apply_voice_for(&mut self, window: &str) {
+        let wanted = self.voices.voice_for(window);
+        if self.applied_voice.as_ref() == Some(&wanted) {
+            return;
+        }
+
+        let rate = self.normalize_rate(wanted.1);
+        let pitch = self.normalize_pitch(wanted.2);
+        let voice = match wanted.0 {
+            Some(ref name) => {
+                let found = self
+                    .available_voices
+                    .iter()
+                    .find(|v| v.name().eq_ignore_ascii_case(name) || v.id() == *name)
+                    .cloned();
+                if found.is_none() {
+                    tracing::warn!("TTS voice '{}' not found, using the system voice", name);
+                }
+                found
+            }
+            None => None,
+        };
+        // Without its own voice a window gets the system voice back, not
+        // whichever window spoke last
+        let voice = voice.or_else(|| self.system_voice.clone());
+        let volume = self.normalize_volume(self.volume);
+
+        if let Some(ref mut engine) = self.engine {
+            if let Some(ref voice) = voice {
+                let _ = engine.set_voice(voice);
+            }
+            let _ = engine.set_rate(rate);
+            let _ = engine.set_pitch(pitch);
+            let _ = engine.set_volume(volume);
+        }
+        self.applied_voice = Some(wanted);
+    }
+
     /// Normalize config volume value to backend's actual range
     fn normalize_volume(&self, config_volume: f32) -> f32 {
         // Config volume is expected to be 0.0 to 1.0
@@ -167,10 +357,71 @@ impl TtsManager {
         self.backend_min_volume + clamped * (self.backend_max_volume - self.backend_min_volume)
     }
 
-    /// Enqueue a speech event
-    pub fn enqueue(&mut self, entry: SpeechEntry) {
+    /// Decide whether a line should be spoken and at what priority
+    ///
+    /// Applies the include/exclude lists, priority rules, and the
+    /// `only_when_unfocused` setting.
+    pub fn filter_line(&self, text: &str, window_priority: Priority) -> Option<Priority> {
+        if self.voices.only_when_unfocused && self.app_focused {
+            return None;
+        }
+        self.filter.check(text, window_priority)
+    }
+
+    /// Track terminal focus (from focus-change events)
+    pub fn set_app_focused(&mut self, focused: bool) {
+        self.app_focused = focused;
+    }
+
+    /// Queue a new line and speak it now if nothing more urgent is playing
+    ///
+    /// Lines at or above `interrupt_priority` cut off less urgent speech.
+    /// Others wait and play when the current utterance ends.
+    pub fn announce(&mut self, entry: SpeechEntry) -> Result<()> {
         if !self.enabled || self.muted {
-            return;
+            return Ok(());
+        }
+        let priority = entry.priority;
+        let Some(index) = self.enqueue(entry) else {
+            return Ok(());
+        };
+        if self.paused {
+            return Ok(());
+        }
+        self.ensure_initialized()?;
+
+        let speaking = self.speaking_priority();
+        match speaking {
+            None => self.speak_at_index(index, false),
+            Some(current) if self.filter.interrupts(priority, current) => {
+                self.speak_at_index(index, true)
+            }
+            Some(_) => Ok(()), // Played by auto_play_next when the current line ends
+        }
+    }
+
+    /// Priority of the entry being spoken right now, if the engine is talking
+    fn speaking_priority(&self) -> Option<Priority> {
+        // Engines that can't report this fall back to speaking every line at once
+        let speaking = self
+            .engine
+            .as_ref()
+            .is_some_and(|engine| engine.is_speaking().unwrap_or(false));
+        if !speaking {
+            return None;
+        }
+        Some(
+            self.current_index
+                .and_then(|i| self.queue.get(i))
+                .map(|e| e.priority)
+                .unwrap_or(Priority::Normal),
+        )
+    }
+
+    /// Enqueue a speech event, returning where it landed in the queue
+    pub fn enqueue(&mut self, entry: SpeechEntry) -> Option<usize> {
+        if !self.enabled || self.muted {
+            return None;
         }
 
         // Prevent queue from growing unbounded
@@ -206,9 +457,8 @@ impl TtsManager {
             }
         }
 
-        // Queue silently - user has full manual control with prev/next/next_unread
-        // Messages will auto-play via callbacks when user manually navigates
         tracing::debug!("Queued message at index {} (total: {})", insert_pos, self.queue.len());
+        Some(insert_pos)
     }
 
     /// Speak the next item in the queue (sequential, includes read messages)
@@ -308,20 +558,11 @@ impl TtsManager {
     /// Auto-play the next unread item (called from utterance_end callback)
     /// Does NOT interrupt - only plays if there's an unspoken item waiting
     pub fn auto_play_next(&mut self) -> Result<()> {
-        if !self.enabled || self.muted {
+        if !self.enabled || self.muted || self.paused {
             return Ok(());
         }
 
-        // Find next unspoken entry
-        let next_index = if let Some(current) = self.current_index {
-            (current + 1..self.queue.len())
-                .find(|&i| !self.queue[i].spoken)
-        } else {
-            (0..self.queue.len())
-                .find(|&i| !self.queue[i].spoken)
-        };
-
-        if let Some(index) = next_index {
+        if let Some(index) = self.next_unspoken() {
             // Auto-play - don't interrupt (nothing should be playing)
             self.speak_at_index(index, false)?;
             tracing::debug!("Auto-playing next TTS entry at index {}", index);
@@ -330,9 +571,25 @@ impl TtsManager {
         Ok(())
     }
 
+    /// Most urgent unspoken entry, oldest first within a priority
+    ///
+    /// Urgent lines are inserted ahead of older spoken ones, so this searches
+    /// the whole queue rather than only after `current_index`.
+    fn next_unspoken(&self) -> Option<usize> {
+        self.queue
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| !e.spoken)
+            .min_by_key(|(i, e)| (std::cmp::Reverse(e.priority), *i))
+            .map(|(i, _)| i)
+    }
+
     /// Speak the entry at a specific index
     /// interrupt: if true, stops current speech before speaking (for manual navigation)
     fn speak_at_index(&mut self, index: usize, interrupt: bool) -> Result<()> {
+        if let Some(window) = self.queue.get(index).map(|e| e.source_window.clone()) {
+            self.apply_voice_for(&window);
+        }
         if let Some(entry) = self.queue.get_mut(index) {
             if let Some(ref mut engine) = self.engine {
                 tracing::debug!("Speaking [{}]: {}", entry.source_window, entry.text);
@@ -373,6 +630,55 @@ impl TtsManager {
         Ok(())
     }
 
+    /// Pause or resume playback, returning true when now paused
+    ///
+    /// Unlike mute, lines keep queuing while paused. Resuming starts the
+    /// interrupted line over, then carries on through the queue.
+    pub fn toggle_pause(&mut self) -> Result<bool> {
+        self.paused = !self.paused;
+        if self.paused {
+            if self.speaking_priority().is_some() {
+                if let Some(entry) = self.current_index.and_then(|i| self.queue.get_mut(i)) {
+                    entry.spoken = false;
+                }
+            }
+            self.stop()?;
+        } else {
+            self.ensure_initialized()?;
+            self.auto_play_next()?;
+        }
+        Ok(self.paused)
+    }
+
+    /// Cut off the current line and move on to the next queued one
+    pub fn skip(&mut self) -> Result<()> {
+        self.stop()?;
+        self.auto_play_next()
+    }
+
+    /// Stop speaking and discard everything not yet spoken
+    ///
+    /// Flushed lines stay in the history for previous/next navigation.
+    /// Returns how many were dropped.
+    pub fn flush(&mut self) -> usize {
+        let _ = self.stop();
+        let mut dropped = 0;
+        for entry in self.queue.iter_mut().filter(|e| !e.spoken) {
+            entry.spoken = true;
+            dropped += 1;
+        }
+        dropped
+    }
+
+    /// Say the most recently spoken line again
+    pub fn repeat_last(&mut self) -> Result<()> {
+        let Some(index) = self.current_index.filter(|&i| i < self.queue.len()) else {
+            return Ok(());
+        };
+        self.ensure_initialized()?;
+        self.speak_at_index(index, true)
+    }
+
     /// Toggle mute
     pub fn toggle_mute(&mut self) {
         self.muted = !self.muted;
@@ -421,6 +727,8 @@ impl TtsManager {
     /// Increase speech rate by 0.1
     pub fn increase_rate(&mut self) -> Result<()> {
         self.rate = (self.rate + 0.1).min(2.0); // Cap at 2.0
+        self.voices.rate = self.rate;
+        self.applied_voice = None;
         let normalized = self.normalize_rate(self.rate);
 
         if let Some(ref mut engine) = self.engine {
@@ -433,6 +741,8 @@ impl TtsManager {
     /// Decrease speech rate by 0.1
     pub fn decrease_rate(&mut self) -> Result<()> {
         self.rate = (self.rate - 0.1).max(0.5); // Don't go below 0.5
+        self.voices.rate = self.rate;
+        self.applied_voice = None;
         let normalized = self.normalize_rate(self.rate);
 
         if let Some(ref mut engine) = self.engine {
@@ -476,3 +786,83 @@ impl TtsManager {
         self.current_utterance_id.as_ref() == Some(&id)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::TtsPriorityRule;
+
+    fn entry(text: &str, priority: Priority) -> SpeechEntry {
+        SpeechEntry {
+            text: text.to_string(),
+            source_window: "main".to_string(),
+            priority,
+            spoken: false,
+        }
+    }
+
+    #[test]
+    fn test_filter_and_priority_order() {
+        let config = TtsConfig {
+            enabled: true,
+            exclude: vec!["^\\[LNet\\]".to_string(), "(".to_string()],
+            priority_rules: vec![TtsPriorityRule {
+                pattern: "whispers".to_string(),
+                priority: "critical".to_string(),
+            }],
+            ..Default::default()
+        };
+        let filter = TtsFilter::new(&config);
+        assert_eq!(filter.check("[LNet] hi", Priority::High), None);
+        assert_eq!(
+            filter.check("Bob whispers, \"hi\"", Priority::High),
+            Some(Priority::Critical)
+        );
+        assert_eq!(
+            filter.check("A goblin arrives.", Priority::Normal),
+            Some(Priority::Normal)
+        );
+        assert!(filter.interrupts(Priority::High, Priority::Normal));
+        assert!(!filter.interrupts(Priority::High, Priority::High));
+        assert!(!filter.interrupts(Priority::Normal, Priority::Normal));
+
+        let include_only = TtsFilter::new(&TtsConfig {
+            include: vec!["goblin".to_string()],
+            interrupt_priority: "never".to_string(),
+            ..Default::default()
+        });
+        assert_eq!(
+            include_only.check("A troll arrives.", Priority::Normal),
+            None
+        );
+        assert!(!include_only.interrupts(Priority::Critical, Priority::Normal));
+
+        // Urgent lines play before older queued text
+        let mut manager = TtsManager::new(&config);
+        manager.enqueue(entry("first", Priority::Normal));
+        manager.queue[0].spoken = true;
+        manager.current_index = Some(0);
+        manager.enqueue(entry("room text", Priority::Normal));
+        let urgent = manager.enqueue(entry("whisper", Priority::High));
+        assert_eq!(urgent, Some(0));
+        assert_eq!(manager.next_unspoken(), Some(0));
+        manager.queue[0].spoken = true;
+        assert_eq!(
+            manager.queue[manager.next_unspoken().unwrap()].text,
+            "room text"
+        );
+
+        assert_eq!(manager.flush(), 1);
+        assert_eq!(manager.next_unspoken(), None);
+
+        manager.set_app_focused(true);
+        let quiet = TtsManager::new(&TtsConfig {
+            only_when_unfocused: true,
+            ..config.clone()
+        });
+        assert_eq!(
+            quiet.filter_line("A goblin arrives.", Priority::Normal),
+            None
+        );
+    }
+}
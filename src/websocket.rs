@@ -0,0 +1,672 @@
+//! Local WebSocket API for companion apps
+//!
+//! Overlay maps, stream widgets and phone remotes can follow a running
+//! session and send commands into it without parsing the raw game stream.
+//! Features:
+//! - Parsed events as JSON text messages: room changes, vitals, and every
+//!   line with the stream it landed on
+//! - Commands from clients (`{"type": "command", "command": "look"}`) go
+//!   through the same path as typed input; `accept_commands = false` makes
+//!   the API read-only
+//! - Plain `std::net` threads like the passthrough, with just enough of RFC
+//!   6455 for text messages, ping/pong and close
+//! - A token is required on the upgrade request (`?token=...` or
+//!   `Authorization: Bearer ...`); the server won't start without one
+//! - Up to `MAX_CLIENTS` connections are served at once; more are turned
+//!   away with a 503
+
+use crate::config::WebSocketConfig;
+use crate::token::token_matches;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// A client that can't take a message within this long is dropped
+const WRITE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Largest message accepted from a client
+const MAX_MESSAGE: usize = 64 * 1024;
+
+/// Connections served at once, counting ones still in the handshake
+const MAX_CLIENTS: usize = 8;
+
+/// Upgrade requests are cut off here, before the token is checked
+const MAX_HEAD_BYTES: u64 = 8 * 1024;
+
+/// Headers accepted on an upgrade request
+const MAX_HEADERS: usize = 64;
+
+/// Appended to the client's key for Sec-WebSocket-Accept (RFC 6455)
+const ACCEPT_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// Events sent to clients
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ApiEvent<'a> {
+    /// The room changed (sent again to each client as it connects)
+    Room {
+        id: Option<&'a str>,
+        lich_id: Option<&'a str>,
+        name: Option<&'a str>,
+        exits: &'a [String],
+    },
+    /// Vitals as percentages (sent again to each client as it connects)
+    Vitals {
+        health: u8,
+        mana: u8,
+        stamina: u8,
+        spirit: u8,
+    },
+    /// A line of game text and the stream it came in on
+    Line { stream: &'a str, text: &'a str },
+}
+
+/// Messages accepted from clients
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ClientMessage {
+    Command { command: String },
+}
+
+/// A connected client; writes are locked so frames never interleave
+type Client = Arc<Mutex<TcpStream>>;
+
+/// State shared with the server threads
+#[derive(Default)]
+struct Shared {
+    clients: Mutex<Vec<Client>>,
+    client_count: AtomicUsize,
+    /// Connection threads running, handshake included
+    connections: Arc<AtomicUsize>,
+    /// Last room and vitals message, replayed to new clients
+    latest: Mutex<HashMap<&'static str, Arc<str>>>,
+}
+
+/// WebSocket API manager owned by AppCore
+///
+/// When disabled in config this is a no-op: nothing is serialized and
+/// `poll_commands` returns nothing.
+pub struct WebSocketApi {
+    tx: Option<Sender<Arc<str>>>,
+    commands: Option<Receiver<String>>,
+    shared: Arc<Shared>,
+    /// Address being served, or why the port is not open
+    status: Result<String, String>,
+}
+
+impl WebSocketApi {
+    pub fn new(config: &WebSocketConfig) -> Self {
+        let mut api = Self {
+            tx: None,
+            commands: None,
+            shared: Arc::default(),
+            status: Err("disabled (set enabled = true under [websocket])".to_string()),
+        };
+        if config.enabled {
+            api.status = api.start(config);
+            match &api.status {
+                Ok(addr) => tracing::info!("WebSocket API listening on {}", addr),
+                Err(e) => tracing::warn!("WebSocket API not started: {}", e),
+            }
+        }
+        api
+    }
+
+    /// Clients currently connected
+    pub fn clients(&self) -> usize {
+        self.shared.client_count.load(Ordering::Relaxed)
+    }
+
+    /// Is the server up? Callers skip building events when it isn't
+    pub fn is_running(&self) -> bool {
+        self.tx.is_some()
+    }
+
+    /// One-line description for `.websocket`
+    pub fn status_line(&self) -> String {
+        match &self.status {
+            Ok(addr) => format!(
+                "WebSocket API: ws://{}/ ({} client(s){})",
+                addr,
+                self.clients(),
+                if self.commands.is_some() {
+                    ""
+                } else {
+                    ", read-only"
+                }
+            ),
+            Err(reason) => format!("WebSocket API: {}", reason),
+        }
+    }
+
+    /// Send an event to every client; room and vitals are only sent when
+    /// they differ from the last ones
+    pub fn publish(&self, event: &ApiEvent) {
+        let Some(tx) = &self.tx else {
+            return;
+        };
+        let Ok(json) = serde_json::to_string(event) else {
+            return;
+        };
+        let json: Arc<str> = json.into();
+        let state_key = match event {
+            ApiEvent::Room { .. } => Some("room"),
+            ApiEvent::Vitals { .. } => Some("vitals"),
+            ApiEvent::Line { .. } => None,
+        };
+        if let Some(key) = state_key {
+            let Ok(mut latest) = self.shared.latest.lock() else {
+                return;
+            };
+            if latest.get(key) == Some(&json) {
+                return;
+            }
+            latest.insert(key, Arc::clone(&json));
+        }
+        if self.clients() > 0 {
+            let _ = tx.send(json);
+        }
+    }
+
+    /// Commands clients sent since the last poll
+    pub fn poll_commands(&self) -> Vec<String> {
+        match &self.commands {
+            Some(rx) => rx.try_iter().collect(),
+            None => Vec::new(),
+        }
+    }
+
+    fn start(&mut self, config: &WebSocketConfig) -> Result<String, String> {
+        if config.token.trim().is_empty() {
+            return Err("no token set under [websocket]; refusing to listen".to_string());
+        }
+        let addr = format!("{}:{}", config.bind, config.port);
+        let listener =
+            TcpListener::bind(&addr).map_err(|e| format!("cannot listen on {}: {}", addr, e))?;
+        let addr = listener
+            .local_addr()
+            .map_err(|e| e.to_string())?
+            .to_string();
+
+        let (tx, rx) = mpsc::channel();
+        let (command_tx, command_rx) = mpsc::channel();
+        let command_tx = config.accept_commands.then_some(command_tx);
+
+        let token = config.token.clone();
+        let accept_shared = Arc::clone(&self.shared);
+        std::thread::Builder::new()
+            .name("websocket-accept".to_string())
+            .spawn(move || accept(listener, &token, &accept_shared, command_tx))
+            .map_err(|e| e.to_string())?;
+
+        let shared = Arc::clone(&self.shared);
+        std::thread::Builder::new()
+            .name("websocket-write".to_string())
+            .spawn(move || write_loop(rx, &shared))
+            .map_err(|e| e.to_string())?;
+
+        self.tx = Some(tx);
+        if config.accept_commands {
+            self.commands = Some(command_rx);
+        }
+        Ok(addr)
+    }
+}
+
+/// Frees a connection slot when the client's thread ends
+struct ClientSlot(Arc<AtomicUsize>);
+
+impl Drop for ClientSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+fn accept(
+    listener: TcpListener,
+    token: &str,
+    shared: &Arc<Shared>,
+    commands: Option<Sender<String>>,
+) {
+    for stream in listener.incoming() {
+        let Ok(mut stream) = stream else { continue };
+        let slot = ClientSlot(Arc::clone(&shared.connections));
+        if shared.connections.fetch_add(1, Ordering::SeqCst) >= MAX_CLIENTS {
+            let _ = stream.set_write_timeout(Some(WRITE_TIMEOUT));
+            let _ =
+                stream.write_all(b"HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\n\r\n");
+            continue;
+        }
+        let shared = Arc::clone(shared);
+        let token = token.to_string();
+        let commands = commands.clone();
+        // Each client reads on its own thread; the handshake happens there
+        // too so a silent connection can't hold up the next one
+        let spawned = std::thread::Builder::new()
+            .name("websocket-client".to_string())
+            .spawn(move || {
+                let _slot = slot;
+                serve_client(stream, &token, &shared, commands);
+            });
+        if let Err(e) = spawned {
+            tracing::warn!("WebSocket API connection dropped: {}", e);
+        }
+    }
+}
+
+fn serve_client(stream: TcpStream, token: &str, shared: &Shared, commands: Option<Sender<String>>) {
+    let peer = stream
+        .peer_addr()
+        .map(|a| a.to_string())
+        .unwrap_or_default();
+    let _ = stream.set_nodelay(true);
+    let _ = stream.set_write_timeout(Some(WRITE_TIMEOUT));
+    let _ = stream.set_read_timeout(Some(Duration::from_secs(10)));
+    let Ok(write_half) = stream.try_clone() else {
+        return;
+    };
+    let mut reader = BufReader::new(stream);
+    let client: Client = Arc::new(Mutex::new(write_half));
+
+    match handshake(&mut reader, token) {
+        Ok(response) => {
+            let Ok(mut socket) = client.lock() else {
+                return;
+            };
+            if socket.write_all(response.as_bytes()).is_err() {
+                return;
+            }
+            // Join the broadcast list before catching up on the room and
+            // vitals; the writer waits on this lock, so nothing is missed
+            if let Ok(mut clients) = shared.clients.lock() {
+                clients.push(Arc::clone(&client));
+                shared.client_count.store(clients.len(), Ordering::Relaxed);
+            }
+            if let Ok(latest) = shared.latest.lock() {
+                for json in latest.values() {
+                    let _ = socket.write_all(&encode_frame(0x1, json.as_bytes()));
+                }
+            }
+        }
+        Err(response) => {
+            tracing::warn!("WebSocket API refused {}", peer);
+            if let Ok(mut socket) = client.lock() {
+                let _ = socket.write_all(response.as_bytes());
+            }
+            return;
+        }
+    }
+
+    tracing::info!("WebSocket API client connected: {}", peer);
+    let _ = reader.get_ref().set_read_timeout(None);
+
+    while let Ok((opcode, payload)) = read_message(&mut reader) {
+        match opcode {
+            0x1 => {
+                let Some(tx) = &commands else { continue };
+                match serde_json::from_slice::<ClientMessage>(&payload) {
+                    Ok(ClientMessage::Command { command }) => {
+                        let _ = tx.send(command);
+                    }
+                    Err(e) => tracing::debug!("WebSocket API ignored message from {}: {}", peer, e),
+                }
+            }
+            0x8 => {
+                if let Ok(mut socket) = client.lock() {
+                    let _ = socket.write_all(&encode_frame(0x8, &[]));
+                }
+                break;
+            }
+            0x9 => {
+                if let Ok(mut socket) = client.lock() {
+                    let _ = socket.write_all(&encode_frame(0xA, &payload));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    tracing::info!("WebSocket API client disconnected: {}", peer);
+    if let Ok(mut clients) = shared.clients.lock() {
+        clients.retain(|c| !Arc::ptr_eq(c, &client));
+        shared.client_count.store(clients.len(), Ordering::Relaxed);
+    }
+}
+
+/// Read the upgrade request; Ok is the 101 response, Err an HTTP refusal
+fn handshake(reader: &mut impl BufRead, token: &str) -> Result<String, String> {
+    const BAD_REQUEST: &str = "HTTP/1.1 400 Bad Request\r\nContent-Length: 0\r\n\r\n";
+    const UNAUTHORIZED: &str = "HTTP/1.1 401 Unauthorized\r\nContent-Length: 0\r\n\r\n";
+
+    // A line cut short by the limit (or the connection closing) has no newline
+    let mut reader = reader.take(MAX_HEAD_BYTES);
+    let mut request_line = String::new();
+    reader
+        .read_line(&mut request_line)
+        .map_err(|_| BAD_REQUEST.to_string())?;
+    if !request_line.ends_with('\n') {
+        return Err(BAD_REQUEST.to_string());
+    }
+    let path = request_line.split_whitespace().nth(1).unwrap_or_default();
+    let mut authorized = path
+        .split_once('?')
+        .map(|(_, query)| {
+            query
+                .split('&')
+                .filter_map(|pair| pair.strip_prefix("token="))
+                .any(|given| token_matches(given, token))
+        })
+        .unwrap_or(false);
+
+    let mut key = None;
+    let mut headers = 0;
+    loop {
+        let mut line = String::new();
+        reader
+            .read_line(&mut line)
+            .map_err(|_| BAD_REQUEST.to_string())?;
+        if !line.ends_with('\n') {
+            return Err(BAD_REQUEST.to_string());
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        headers += 1;
+        if headers > MAX_HEADERS {
+            return Err(BAD_REQUEST.to_string());
+        }
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match name.trim().to_ascii_lowercase().as_str() {
+            "sec-websocket-key" => key = Some(value.to_string()),
+            "authorization" => {
+                authorized |= value
+                    .strip_prefix("Bearer ")
+                    .is_some_and(|given| token_matches(given, token))
+            }
+            _ => {}
+        }
+    }
+
+    let key = key.ok_or_else(|| BAD_REQUEST.to_string())?;
+    if !authorized {
+        return Err(UNAUTHORIZED.to_string());
+    }
+    Ok(format!(
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+        accept_key(&key)
+    ))
+}
+
+/// Sec-WebSocket-Accept for a client's Sec-WebSocket-Key
+fn accept_key(key: &str) -> String {
+    let digest = openssl::sha::sha1(format!("{}{}", key, ACCEPT_GUID).as_bytes());
+    STANDARD.encode(digest)
+}
+
+/// An unmasked, unfragmented frame (servers never mask)
+fn encode_frame(opcode: u8, payload: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(payload.len() + 10);
+    frame.push(0x80 | opcode);
+    match payload.len() {
+        len if len < 126 => frame.push(len as u8),
+        len if len <= u16::MAX as usize => {
+            frame.push(126);
+            frame.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        len => {
+            frame.push(127);
+            frame.extend_from_slice(&(len as u64).to_be_bytes());
+        }
+    }
+    frame.extend_from_slice(payload);
+    frame
+}
+
+/// Read one whole message, joining continuation frames; control frames
+/// come back on their own
+fn read_message(reader: &mut impl Read) -> std::io::Result<(u8, Vec<u8>)> {
+    let mut message = Vec::new();
+    let mut message_opcode = None;
+    loop {
+        let mut header = [0u8; 2];
+        reader.read_exact(&mut header)?;
+        let fin = header[0] & 0x80 != 0;
+        let opcode = header[0] & 0x0F;
+        let masked = header[1] & 0x80 != 0;
+        let len = match header[1] & 0x7F {
+            126 => {
+                let mut ext = [0u8; 2];
+                reader.read_exact(&mut ext)?;
+                u16::from_be_bytes(ext) as u64
+            }
+            127 => {
+                let mut ext = [0u8; 8];
+                reader.read_exact(&mut ext)?;
+                u64::from_be_bytes(ext)
+            }
+            len => len as u64,
+        };
+        // Checked before allocating: a 64-bit length must not overflow the total
+        let len = usize::try_from(len)
+            .ok()
+            .filter(|&len| len <= MAX_MESSAGE)
+            .filter(|&len| {
+                message
+                    .len()
+                    .checked_add(len)
+                    .is_some_and(|total| total <= MAX_MESSAGE)
+            })
+            .ok_or_else(|| {
+                std::io::Error::new(std::io::ErrorKind::InvalidData, "message too large")
+            })?;
+        let mut mask = [0u8; 4];
+        if masked {
+            reader.read_exact(&mut mask)?;
+        }
+        let mut payload = vec![0u8; len];
+        reader.read_exact(&mut payload)?;
+        if masked {
+            for (i, byte) in payload.iter_mut().enumerate() {
+                *byte ^= mask[i % 4];
+            }
+        }
+
+        if opcode >= 0x8 {
+            return Ok((opcode, payload));
+        }
+        if opcode != 0x0 {
+            message_opcode = Some(opcode);
+        }
+        message.extend_from_slice(&payload);
+        if fin {
+            return Ok((message_opcode.unwrap_or(0x1), message));
+        }
+    }
+}
+
+fn write_loop(rx: Receiver<Arc<str>>, shared: &Shared) {
+    for json in rx {
+        let frame = encode_frame(0x1, json.as_bytes());
+        let Ok(mut clients) = shared.clients.lock() else {
+            return;
+        };
+        clients.retain(|client| {
+            let Ok(mut socket) = client.lock() else {
+                return false;
+            };
+            match socket.write_all(&frame) {
+                Ok(()) => true,
+                Err(e) => {
+                    tracing::info!("WebSocket API client dropped: {}", e);
+                    let _ = socket.shutdown(std::net::Shutdown::Both);
+                    false
+                }
+            }
+        });
+        shared.client_count.store(clients.len(), Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_websocket_api() {
+        // RFC 6455 section 1.3 example
+        assert_eq!(
+            accept_key("dGhlIHNhbXBsZSBub25jZQ=="),
+            "s3pPLMBiTxaQ9kYGzzhZRbK+xOo="
+        );
+
+        let config = WebSocketConfig {
+            enabled: true,
+            port: 0, // Any free port
+            token: "secret".to_string(),
+            ..WebSocketConfig::default()
+        };
+        let api = WebSocketApi::new(&config);
+        let addr = api.status.clone().unwrap();
+        api.publish(&ApiEvent::Vitals {
+            health: 90,
+            mana: 50,
+            stamina: 100,
+            spirit: 100,
+        });
+
+        // Wrong token is refused
+        let mut refused = TcpStream::connect(&addr).unwrap();
+        refused
+            .write_all(
+                b"GET /?token=nope HTTP/1.1\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\r\n",
+            )
+            .unwrap();
+        let mut response = String::new();
+        BufReader::new(refused).read_line(&mut response).unwrap();
+        assert!(response.contains("401"));
+
+        let mut client = TcpStream::connect(&addr).unwrap();
+        client
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        client
+            .write_all(b"GET /?token=secret HTTP/1.1\r\nUpgrade: websocket\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\r\n")
+            .unwrap();
+        let mut reader = BufReader::new(client.try_clone().unwrap());
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        assert!(line.contains("101"));
+        while line.trim() != "" {
+            line.clear();
+            reader.read_line(&mut line).unwrap();
+        }
+
+        // Vitals published before connecting are replayed
+        let (opcode, payload) = read_message(&mut reader).unwrap();
+        assert_eq!(opcode, 0x1);
+        assert!(String::from_utf8(payload)
+            .unwrap()
+            .contains("\"health\":90"));
+
+        let connected = std::time::Instant::now();
+        while api.clients() == 0 && connected.elapsed() < Duration::from_secs(5) {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        api.publish(&ApiEvent::Line {
+            stream: "main",
+            text: "Hello.",
+        });
+        let (_, payload) = read_message(&mut reader).unwrap();
+        assert_eq!(
+            payload,
+            br#"{"type":"line","stream":"main","text":"Hello."}"#
+        );
+
+        // A masked command from the client
+        let body = br#"{"type":"command","command":"look"}"#;
+        let mask = [1u8, 2, 3, 4];
+        let mut frame = vec![0x81, 0x80 | body.len() as u8];
+        frame.extend_from_slice(&mask);
+        frame.extend(body.iter().enumerate().map(|(i, b)| b ^ mask[i % 4]));
+        client.write_all(&frame).unwrap();
+
+        let sent = std::time::Instant::now();
+        let mut commands = Vec::new();
+        while commands.is_empty() && sent.elapsed() < Duration::from_secs(5) {
+            commands = api.poll_commands();
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(commands, vec!["look".to_string()]);
+    }
+    #[test]
+    fn test_read_message_rejects_oversized_frames() {
+        // A 64-bit length that would overflow the running total
+        let mut huge = vec![0x81, 0x7F];
+        huge.extend_from_slice(&u64::MAX.to_be_bytes());
+        let err = read_message(&mut &huge[..]).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+        // Fragments that only go over the limit together
+        let half = MAX_MESSAGE / 2 + 1;
+        let mut frames = Vec::new();
+        for opcode in [0x01u8, 0x80] {
+            frames.extend_from_slice(&[opcode, 127]);
+            frames.extend_from_slice(&(half as u64).to_be_bytes());
+            frames.extend(std::iter::repeat_n(b'a', half));
+        }
+        assert!(read_message(&mut &frames[..]).is_err());
+    }
+
+    #[test]
+    fn test_handshake_limits_the_request_head() {
+        let upgrade = "GET /?token=secret HTTP/1.1\r\nSec-WebSocket-Key: abc\r\n";
+        let ok = format!("{}\r\n", upgrade);
+        assert!(handshake(&mut ok.as_bytes(), "secret").is_ok());
+
+        let long_line = format!(
+            "GET /{} HTTP/1.1\r\n\r\n",
+            "a".repeat(MAX_HEAD_BYTES as usize)
+        );
+        let err = handshake(&mut long_line.as_bytes(), "secret").unwrap_err();
+        assert!(err.starts_with("HTTP/1.1 400"), "{}", err);
+
+        let many_headers = format!("{}{}\r\n", upgrade, "X-Pad: 1\r\n".repeat(MAX_HEADERS));
+        let err = handshake(&mut many_headers.as_bytes(), "secret").unwrap_err();
+        assert!(err.starts_with("HTTP/1.1 400"), "{}", err);
+    }
+
+    #[test]
+    fn test_websocket_refuses_connections_over_the_limit() {
+        let config = WebSocketConfig {
+            enabled: true,
+            port: 0,
+            token: "secret".to_string(),
+            ..WebSocketConfig::default()
+        };
+        let api = WebSocketApi::new(&config);
+        let addr = api.status.clone().unwrap();
+
+        // Silent connections hold their slots while the handshake waits
+        let held: Vec<TcpStream> = (0..MAX_CLIENTS)
+            .map(|_| TcpStream::connect(&addr).unwrap())
+            .collect();
+        let extra = TcpStream::connect(&addr).unwrap();
+        extra
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        let mut response = String::new();
+        BufReader::new(extra).read_line(&mut response).unwrap();
+        assert!(response.contains("503"));
+        drop(held);
+    }
+}